@@ -0,0 +1,354 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+use crate::process::{ProcessConfig, ProcessOutput, spawn_and_stream};
+use crate::review_schema::{ReviewFinding, Severity};
+use crate::worktree::WorktreeManager;
+
+/// JSON output formats understood by command review phases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalyzerFormat {
+    /// `cargo clippy --message-format=json` (one compiler message per line).
+    Clippy,
+    /// `eslint --format json`.
+    Eslint,
+    /// `ruff check --output-format json`.
+    Ruff,
+}
+
+impl FromStr for AnalyzerFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "clippy" => Ok(AnalyzerFormat::Clippy),
+            "eslint" => Ok(AnalyzerFormat::Eslint),
+            "ruff" => Ok(AnalyzerFormat::Ruff),
+            _ => Err(Error::ConfigValidation(format!(
+                "unknown analyzer format: {s} (expected clippy, eslint, or ruff)"
+            ))),
+        }
+    }
+}
+
+impl AnalyzerFormat {
+    /// Guess the format from the analyzer command line.
+    pub fn detect(command: &str) -> Option<Self> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let has = |name: &str| words.iter().any(|w| w.rsplit('/').next() == Some(name));
+        if has("clippy") || has("cargo-clippy") {
+            Some(AnalyzerFormat::Clippy)
+        } else if has("eslint") {
+            Some(AnalyzerFormat::Eslint)
+        } else if has("ruff") {
+            Some(AnalyzerFormat::Ruff)
+        } else {
+            None
+        }
+    }
+}
+
+/// Run an analyzer command with `sh -c` in `working_dir` and convert its JSON output
+/// into findings. Analyzers exit non-zero when they report problems, so the exit code
+/// only matters when the output cannot be parsed.
+pub async fn run_analyzer(
+    command: &str,
+    format: AnalyzerFormat,
+    working_dir: &Path,
+    timeout: Option<Duration>,
+    log_prefix: &str,
+) -> Result<Vec<ReviewFinding>> {
+    let output = run_shell(command, working_dir, timeout, log_prefix).await?;
+    let stdout = output.stdout_lines.join("\n");
+    parse_analyzer_output(format, &stdout, working_dir).map_err(|e| {
+        if output.success() {
+            e
+        } else {
+            Error::Process(format!(
+                "analyzer `{command}` exited with code {}: {e}\n{}",
+                output.exit_code,
+                output.stderr_lines.join("\n")
+            ))
+        }
+    })
+}
+
+/// Run `command` with `sh -c` in `working_dir`, capturing its output quietly.
+pub async fn run_shell(
+    command: &str,
+    working_dir: &Path,
+    timeout: Option<Duration>,
+    log_prefix: &str,
+) -> Result<ProcessOutput> {
+    spawn_and_stream(ProcessConfig {
+        command: "sh".to_string(),
+        args: vec!["-c".to_string(), command.to_string()],
+        working_dir: working_dir.to_path_buf(),
+        timeout,
+        log_prefix: log_prefix.to_string(),
+        stream_output: false,
+        env: vec![],
+        stdin_data: None,
+        quiet: true,
+        stdout_tx: None,
+    })
+    .await
+}
+
+/// Convert an analyzer's JSON output into review findings.
+pub fn parse_analyzer_output(
+    format: AnalyzerFormat,
+    stdout: &str,
+    working_dir: &Path,
+) -> Result<Vec<ReviewFinding>> {
+    let findings = match format {
+        AnalyzerFormat::Clippy => parse_clippy(stdout)?,
+        AnalyzerFormat::Eslint => parse_eslint(stdout, working_dir)?,
+        AnalyzerFormat::Ruff => parse_ruff(stdout, working_dir)?,
+    };
+    // Macro expansions and multiple targets repeat the same diagnostic.
+    let mut unique: Vec<ReviewFinding> = Vec::new();
+    for f in findings {
+        if !unique.iter().any(|u| u.id == f.id) {
+            unique.push(f);
+        }
+    }
+    Ok(unique)
+}
+
+fn finding(code: &str, file: &str, line: u32, severity: Severity, message: &str) -> ReviewFinding {
+    let stem = Path::new(file)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    ReviewFinding {
+        id: WorktreeManager::slugify(&format!("{code}-{stem}-{line}")),
+        file: file.to_string(),
+        line,
+        severity,
+        description: format!("{message} ({code})"),
+        category: None,
+        depends_on: vec![],
+        extra: Default::default(),
+    }
+}
+
+pub(crate) fn relative_path(path: &str, working_dir: &Path) -> String {
+    Path::new(path)
+        .strip_prefix(working_dir)
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<RustcDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct RustcDiagnostic {
+    message: String,
+    level: String,
+    code: Option<RustcCode>,
+    spans: Vec<RustcSpan>,
+}
+
+#[derive(Deserialize)]
+struct RustcCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RustcSpan {
+    file_name: String,
+    line_start: u32,
+    is_primary: bool,
+}
+
+fn parse_clippy(stdout: &str) -> Result<Vec<ReviewFinding>> {
+    let mut findings = Vec::new();
+    let mut saw_message = false;
+    for line in stdout.lines().filter(|l| l.trim_start().starts_with('{')) {
+        let msg: CargoMessage = serde_json::from_str(line)
+            .map_err(|e| Error::Process(format!("failed to parse clippy JSON: {e}")))?;
+        saw_message = true;
+        if msg.reason != "compiler-message" {
+            continue;
+        }
+        let Some(diag) = msg.message else { continue };
+        let Some(span) = diag.spans.iter().find(|s| s.is_primary) else {
+            continue;
+        };
+        let severity = match diag.level.as_str() {
+            "error" => Severity::Critical,
+            "warning" => Severity::Warning,
+            _ => Severity::Info,
+        };
+        let code = diag.code.map_or_else(|| diag.level.clone(), |c| c.code);
+        findings.push(finding(
+            &code,
+            &span.file_name,
+            span.line_start,
+            severity,
+            &diag.message,
+        ));
+    }
+    if !saw_message {
+        return Err(Error::Process(
+            "clippy produced no JSON messages (is --message-format=json set?)".to_string(),
+        ));
+    }
+    Ok(findings)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EslintFile {
+    file_path: String,
+    messages: Vec<EslintMessage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EslintMessage {
+    rule_id: Option<String>,
+    severity: u8,
+    message: String,
+    #[serde(default)]
+    line: u32,
+}
+
+fn parse_eslint(stdout: &str, working_dir: &Path) -> Result<Vec<ReviewFinding>> {
+    let files: Vec<EslintFile> = serde_json::from_str(stdout.trim())
+        .map_err(|e| Error::Process(format!("failed to parse eslint JSON: {e}")))?;
+    Ok(files
+        .iter()
+        .flat_map(|file| {
+            let path = relative_path(&file.file_path, working_dir);
+            file.messages.iter().map(move |m| {
+                let severity = if m.severity >= 2 {
+                    Severity::Critical
+                } else {
+                    Severity::Warning
+                };
+                let code = m.rule_id.as_deref().unwrap_or("eslint");
+                finding(code, &path, m.line.max(1), severity, &m.message)
+            })
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct RuffDiagnostic {
+    code: Option<String>,
+    message: String,
+    filename: String,
+    location: RuffLocation,
+}
+
+#[derive(Deserialize)]
+struct RuffLocation {
+    row: u32,
+}
+
+fn parse_ruff(stdout: &str, working_dir: &Path) -> Result<Vec<ReviewFinding>> {
+    let diagnostics: Vec<RuffDiagnostic> = serde_json::from_str(stdout.trim())
+        .map_err(|e| Error::Process(format!("failed to parse ruff JSON: {e}")))?;
+    Ok(diagnostics
+        .iter()
+        .map(|d| {
+            // Ruff reports syntax errors without a rule code.
+            let (code, severity) = match &d.code {
+                Some(code) => (code.as_str(), Severity::Warning),
+                None => ("syntax-error", Severity::Critical),
+            };
+            let path = relative_path(&d.filename, working_dir);
+            finding(code, &path, d.location.row, severity, &d.message)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(
+            AnalyzerFormat::detect("cargo clippy --message-format=json"),
+            Some(AnalyzerFormat::Clippy)
+        );
+        assert_eq!(
+            AnalyzerFormat::detect("npx eslint --format json src"),
+            Some(AnalyzerFormat::Eslint)
+        );
+        assert_eq!(
+            AnalyzerFormat::detect("/usr/bin/ruff check --output-format json ."),
+            Some(AnalyzerFormat::Ruff)
+        );
+        assert_eq!(AnalyzerFormat::detect("make lint"), None);
+        assert!("pylint".parse::<AnalyzerFormat>().is_err());
+    }
+
+    #[test]
+    fn test_parse_clippy() {
+        let stdout = r#"{"reason":"compiler-artifact","package_id":"x"}
+{"reason":"compiler-message","message":{"message":"unused variable: `x`","level":"warning","code":{"code":"unused_variables"},"spans":[{"file_name":"src/lib.rs","line_start":7,"is_primary":true}]}}
+{"reason":"compiler-message","message":{"message":"unused variable: `x`","level":"warning","code":{"code":"unused_variables"},"spans":[{"file_name":"src/lib.rs","line_start":7,"is_primary":true}]}}
+{"reason":"compiler-message","message":{"message":"aborting due to 1 previous error","level":"error","code":null,"spans":[]}}
+{"reason":"build-finished","success":false}"#;
+        let findings =
+            parse_analyzer_output(AnalyzerFormat::Clippy, stdout, Path::new("/")).unwrap();
+        assert_eq!(findings.len(), 1);
+        let f = &findings[0];
+        assert_eq!(f.id, "unused-variables-lib-rs-7");
+        assert_eq!((f.file.as_str(), f.line), ("src/lib.rs", 7));
+        assert_eq!(f.severity, Severity::Warning);
+        assert_eq!(f.description, "unused variable: `x` (unused_variables)");
+
+        assert!(
+            parse_analyzer_output(
+                AnalyzerFormat::Clippy,
+                "error: no such command",
+                Path::new("/")
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_eslint() {
+        let stdout = r#"[{"filePath":"/repo/src/app.js","messages":[
+            {"ruleId":"no-unused-vars","severity":2,"message":"'a' is defined but never used.","line":3},
+            {"ruleId":null,"severity":1,"message":"Unused eslint-disable directive.","line":9}
+        ]},{"filePath":"/repo/src/ok.js","messages":[]}]"#;
+        let findings =
+            parse_analyzer_output(AnalyzerFormat::Eslint, stdout, Path::new("/repo")).unwrap();
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].file, "src/app.js");
+        assert_eq!(findings[0].severity, Severity::Critical);
+        assert_eq!(findings[0].id, "no-unused-vars-app-js-3");
+        assert_eq!(findings[1].severity, Severity::Warning);
+        assert_eq!(findings[1].id, "eslint-app-js-9");
+    }
+
+    #[test]
+    fn test_parse_ruff() {
+        let stdout = r#"[
+            {"code":"F401","message":"`os` imported but unused","filename":"/repo/pkg/mod.py","location":{"row":1,"column":8}},
+            {"code":null,"message":"SyntaxError: unexpected indent","filename":"/repo/pkg/bad.py","location":{"row":4,"column":1}}
+        ]"#;
+        let findings =
+            parse_analyzer_output(AnalyzerFormat::Ruff, stdout, Path::new("/repo")).unwrap();
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].file, "pkg/mod.py");
+        assert_eq!(findings[0].id, "f401-mod-py-1");
+        assert_eq!(findings[1].severity, Severity::Critical);
+        assert!(parse_analyzer_output(AnalyzerFormat::Ruff, "", Path::new("/repo")).is_err());
+    }
+}
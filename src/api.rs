@@ -0,0 +1,819 @@
+//! `rlph api`: a small HTTP server that lets an external scheduler decide what
+//! runs when, while rlph runs it.
+//!
+//! Jobs are queued by `POST /tasks/{id}/run` and `POST /prs/{n}/review` and run
+//! one at a time, in order. `GET /status` lists the running, queued, and recently
+//! finished jobs, and `GET /events` streams progress as server-sent events.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde_json::{Value, json};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{Notify, broadcast};
+use tracing::{debug, info, warn};
+
+use crate::dry_run::DryRunAction;
+use crate::error::{Error, Result};
+use crate::orchestrator::{IterationOutcome, ProgressReporter};
+use crate::poll::QueueDelta;
+use crate::runs::RunDigest;
+
+/// Finished jobs kept for `GET /status`.
+const MAX_FINISHED_JOBS: usize = 50;
+
+/// Largest request (head plus body) accepted, in bytes.
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+
+/// Events buffered per `/events` subscriber; a slower subscriber skips ahead.
+const EVENT_BUFFER: usize = 256;
+
+/// Work requested through the API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Job {
+    /// Run one task through the full pipeline, bypassing selection.
+    Task { task_id: String },
+    /// Review an existing PR, as `rlph review` does.
+    Review { pr_number: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    /// The task was skipped (e.g. estimated above `max_complexity`).
+    Skipped,
+    Failed,
+}
+
+/// A job and where it is in the queue.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JobRecord {
+    pub id: u64,
+    #[serde(flatten)]
+    pub job: Job,
+    pub status: JobStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Runs queued jobs. The `rlph` binary builds an orchestrator per job, with an
+/// [`EventReporter`] around its usual reporter.
+pub trait JobRunner {
+    fn run(&self, job: &Job, events: EventSink) -> impl Future<Output = Result<IterationOutcome>>;
+}
+
+/// A progress event as streamed from `GET /events`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiEvent {
+    pub name: String,
+    /// JSON object with the event name, the job ID, and the event's fields.
+    pub data: String,
+}
+
+/// Publishes events to `/events` subscribers, tagged with the job they belong to.
+#[derive(Debug, Clone)]
+pub struct EventSink {
+    tx: broadcast::Sender<ApiEvent>,
+    job_id: Option<u64>,
+}
+
+impl EventSink {
+    pub fn publish(&self, name: &str, fields: Value) {
+        let mut data = json!({ "event": name });
+        if let Some(id) = self.job_id {
+            data["job_id"] = json!(id);
+        }
+        if let (Value::Object(data), Value::Object(fields)) = (&mut data, fields) {
+            data.extend(fields);
+        }
+        // Nobody listening is not an error.
+        let _ = self.tx.send(ApiEvent {
+            name: name.to_string(),
+            data: data.to_string(),
+        });
+    }
+}
+
+/// Reporter that publishes every progress event to an [`EventSink`], then
+/// forwards it to `inner`.
+pub struct EventReporter<P> {
+    inner: P,
+    events: EventSink,
+}
+
+impl<P> EventReporter<P> {
+    pub fn new(inner: P, events: EventSink) -> Self {
+        Self { inner, events }
+    }
+}
+
+impl<P: ProgressReporter> ProgressReporter for EventReporter<P> {
+    fn fetching_tasks(&self) {
+        self.events.publish("fetching_tasks", json!({}));
+        self.inner.fetching_tasks();
+    }
+
+    fn tasks_found(&self, count: usize) {
+        self.events
+            .publish("tasks_found", json!({ "count": count }));
+        self.inner.tasks_found(count);
+    }
+
+    fn queue_changed(&self, delta: &QueueDelta) {
+        self.events.publish("queue_changed", json!(delta));
+        self.inner.queue_changed(delta);
+    }
+
+    fn task_selected(&self, issue_number: u64, title: &str) {
+        self.events.publish(
+            "task_selected",
+            json!({ "issue_number": issue_number, "title": title }),
+        );
+        self.inner.task_selected(issue_number, title);
+    }
+
+    fn implement_started(&self) {
+        self.events.publish("implement_started", json!({}));
+        self.inner.implement_started();
+    }
+
+    fn pr_created(&self, url: &str) {
+        self.events.publish("pr_created", json!({ "pr_url": url }));
+        self.inner.pr_created(url);
+    }
+
+    fn iteration_complete(&self, issue_number: u64, title: &str) {
+        self.events.publish(
+            "iteration_complete",
+            json!({ "issue_number": issue_number, "title": title }),
+        );
+        self.inner.iteration_complete(issue_number, title);
+    }
+
+    fn phases_started(&self, names: &[String]) {
+        self.events
+            .publish("phases_started", json!({ "phases": names }));
+        self.inner.phases_started(names);
+    }
+
+    fn phase_complete(&self, name: &str) {
+        self.events
+            .publish("phase_complete", json!({ "phase": name }));
+        self.inner.phase_complete(name);
+    }
+
+    fn review_summary(&self, body: &str) {
+        self.events
+            .publish("review_summary", json!({ "body": body }));
+        self.inner.review_summary(body);
+    }
+
+    fn review_failed(&self, pr_url: Option<&str>, reason: &str) {
+        self.events.publish(
+            "review_failed",
+            json!({ "pr_url": pr_url, "reason": reason }),
+        );
+        self.inner.review_failed(pr_url, reason);
+    }
+
+    fn pr_url(&self, url: &str) {
+        self.events.publish("pr_url", json!({ "pr_url": url }));
+        self.inner.pr_url(url);
+    }
+
+    fn dry_run_action(&self, action: &DryRunAction) {
+        self.events.publish("dry_run_action", json!(action));
+        self.inner.dry_run_action(action);
+    }
+
+    fn run_digest(&self, digest: &RunDigest) {
+        self.events.publish("run_digest", json!(digest));
+        self.inner.run_digest(digest);
+    }
+}
+
+#[derive(Default)]
+struct Jobs {
+    next_id: u64,
+    queued: VecDeque<JobRecord>,
+    running: Option<JobRecord>,
+    /// Most recent first.
+    finished: VecDeque<JobRecord>,
+}
+
+/// The job queue and event stream behind `rlph api`.
+#[derive(Clone)]
+pub struct ApiServer {
+    jobs: Arc<Mutex<Jobs>>,
+    job_added: Arc<Notify>,
+    events: broadcast::Sender<ApiEvent>,
+    token: Option<String>,
+}
+
+impl Default for ApiServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApiServer {
+    pub fn new() -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(Jobs::default())),
+            job_added: Arc::new(Notify::new()),
+            events: broadcast::channel(EVENT_BUFFER).0,
+            token: None,
+        }
+    }
+
+    /// Require `Authorization: Bearer <token>` on every request. Without a
+    /// token, requests must name a loopback `Host` and carry no `Origin`, so
+    /// web pages and rebinding DNS names cannot queue jobs.
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token.filter(|t| !t.is_empty());
+        self
+    }
+
+    /// Queue `job` behind any others and return its record.
+    pub fn enqueue(&self, job: Job) -> JobRecord {
+        let record = {
+            let mut jobs = self.jobs.lock().unwrap();
+            jobs.next_id += 1;
+            let record = JobRecord {
+                id: jobs.next_id,
+                job,
+                status: JobStatus::Queued,
+                error: None,
+            };
+            jobs.queued.push_back(record.clone());
+            record
+        };
+        self.sink(Some(record.id))
+            .publish("job_queued", serde_json::to_value(&record.job).unwrap());
+        self.job_added.notify_one();
+        record
+    }
+
+    /// Running, queued, and recently finished jobs as returned by `GET /status`.
+    pub fn status(&self) -> Value {
+        let jobs = self.jobs.lock().unwrap();
+        json!({
+            "running": jobs.running,
+            "queued": jobs.queued,
+            "finished": jobs.finished,
+        })
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ApiEvent> {
+        self.events.subscribe()
+    }
+
+    fn sink(&self, job_id: Option<u64>) -> EventSink {
+        EventSink {
+            tx: self.events.clone(),
+            job_id,
+        }
+    }
+
+    /// Accept connections on `listener` and run queued jobs with `runner` until
+    /// accepting fails. Connections are handled on their own tasks; jobs run on
+    /// this one, so `runner`'s futures need not be `Send`.
+    pub async fn serve<R: JobRunner>(&self, listener: TcpListener, runner: R) -> Result<()> {
+        tokio::select! {
+            result = self.accept_loop(listener) => result,
+            () = self.run_jobs(&runner) => Ok(()),
+        }
+    }
+
+    async fn accept_loop(&self, listener: TcpListener) -> Result<()> {
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            debug!(%peer, "api connection");
+            let server = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server.handle_connection(stream).await {
+                    debug!(%peer, error = %e, "api connection failed");
+                }
+            });
+        }
+    }
+
+    async fn run_jobs<R: JobRunner>(&self, runner: &R) {
+        loop {
+            let next = {
+                let mut jobs = self.jobs.lock().unwrap();
+                let next = jobs.queued.pop_front().map(|mut record| {
+                    record.status = JobStatus::Running;
+                    record
+                });
+                jobs.running = next.clone();
+                next
+            };
+            let Some(mut record) = next else {
+                self.job_added.notified().await;
+                continue;
+            };
+
+            info!(job_id = record.id, job = ?record.job, "api job started");
+            let events = self.sink(Some(record.id));
+            events.publish("job_started", serde_json::to_value(&record.job).unwrap());
+            match runner.run(&record.job, events.clone()).await {
+                Ok(IterationOutcome::SkippedTask | IterationOutcome::NoEligibleTasks) => {
+                    record.status = JobStatus::Skipped;
+                }
+                Ok(IterationOutcome::ProcessedTask) => record.status = JobStatus::Succeeded,
+                Err(e) => {
+                    warn!(job_id = record.id, error = %e, "api job failed");
+                    record.status = JobStatus::Failed;
+                    record.error = Some(e.to_string());
+                }
+            }
+            events.publish(
+                "job_finished",
+                json!({ "status": record.status, "error": record.error }),
+            );
+
+            let mut jobs = self.jobs.lock().unwrap();
+            jobs.running = None;
+            jobs.finished.push_front(record);
+            jobs.finished.truncate(MAX_FINISHED_JOBS);
+        }
+    }
+
+    async fn handle_connection(&self, mut stream: TcpStream) -> Result<()> {
+        let request = match read_request(&mut stream).await? {
+            Ok(request) => request,
+            Err((status, message)) => {
+                return respond(&mut stream, status, &json!({ "error": message })).await;
+            }
+        };
+        if let Some(token) = &self.token
+            && request.bearer.as_deref() != Some(token.as_str())
+        {
+            return respond(
+                &mut stream,
+                401,
+                &json!({ "error": "missing or wrong token" }),
+            )
+            .await;
+        }
+        if self.token.is_none()
+            && let Some(message) = untrusted_without_token(&request)
+        {
+            return respond(&mut stream, 403, &json!({ "error": message })).await;
+        }
+
+        match route(&request.method, &request.path) {
+            Ok(Route::Enqueue(job)) => {
+                let record = self.enqueue(job);
+                respond(&mut stream, 202, &serde_json::to_value(record).unwrap()).await
+            }
+            Ok(Route::Status) => respond(&mut stream, 200, &self.status()).await,
+            Ok(Route::Events) => self.stream_events(stream).await,
+            Err((status, message)) => {
+                respond(&mut stream, status, &json!({ "error": message })).await
+            }
+        }
+    }
+
+    async fn stream_events(&self, mut stream: TcpStream) -> Result<()> {
+        let mut events = self.subscribe();
+        stream
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+                  Cache-Control: no-cache\r\nConnection: close\r\n\r\n: connected\n\n",
+            )
+            .await?;
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    let frame = format!("event: {}\ndata: {}\n\n", event.name, event.data);
+                    stream.write_all(frame.as_bytes()).await?;
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    debug!(skipped, "api event subscriber lagging");
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Request {
+    method: String,
+    /// Path without the query string.
+    path: String,
+    bearer: Option<String>,
+    host: Option<String>,
+    origin: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Route {
+    Enqueue(Job),
+    Status,
+    Events,
+}
+
+type HttpError = (u16, String);
+
+/// Read one request, discarding its body. The inner error is an HTTP status and
+/// message for requests that cannot be served.
+async fn read_request(stream: &mut TcpStream) -> Result<std::result::Result<Request, HttpError>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_REQUEST_BYTES {
+            return Ok(Err((413, "request too large".to_string())));
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(Error::Orchestrator(
+                "connection closed before the request head".to_string(),
+            ));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+    let head = String::from_utf8_lossy(&buf[..head_end]).into_owned();
+    let request = match parse_request_head(&head) {
+        Ok(request) => request,
+        Err(e) => return Ok(Err(e)),
+    };
+
+    // Drain the body so the client sees the response rather than a reset.
+    let content_length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    if content_length > MAX_REQUEST_BYTES {
+        return Ok(Err((413, "request too large".to_string())));
+    }
+    let mut remaining = content_length.saturating_sub(buf.len() - head_end - 4);
+    while remaining > 0 {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        remaining = remaining.saturating_sub(n);
+    }
+    Ok(Ok(request))
+}
+
+fn parse_request_head(head: &str) -> std::result::Result<Request, HttpError> {
+    let mut lines = head.lines();
+    let mut parts = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err((400, "malformed request line".to_string()));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err((400, format!("unsupported protocol {version}")));
+    }
+    let headers: Vec<(&str, &str)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect();
+    let header = |wanted: &str| {
+        headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(wanted))
+            .map(|(_, value)| value.to_string())
+    };
+    let bearer = header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer ").map(|t| t.trim().to_string()));
+    let path = target.split_once('?').map_or(target, |(path, _)| path);
+    Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        bearer,
+        host: header("host"),
+        origin: header("origin"),
+    })
+}
+
+/// Why a request must be refused when no token is configured, if it must.
+fn untrusted_without_token(request: &Request) -> Option<String> {
+    if let Some(origin) = &request.origin {
+        return Some(format!(
+            "requests from a browser origin ({origin}) need RLPH_API_TOKEN"
+        ));
+    }
+    let host = request.host.as_deref().unwrap_or_default();
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    if !matches!(name, "localhost" | "127.0.0.1" | "::1") {
+        return Some(format!(
+            "Host '{host}' is not a loopback address; set RLPH_API_TOKEN to serve other hosts"
+        ));
+    }
+    None
+}
+
+fn route(method: &str, path: &str) -> std::result::Result<Route, HttpError> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let (route, allowed) = match segments.as_slice() {
+        ["tasks", id, "run"] => {
+            let valid = !id.is_empty()
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid {
+                return Err((400, format!("invalid task id '{id}'")));
+            }
+            let job = Job::Task {
+                task_id: id.to_string(),
+            };
+            (Route::Enqueue(job), "POST")
+        }
+        ["prs", number, "review"] => {
+            let pr_number = number
+                .parse()
+                .map_err(|_| (400, format!("invalid PR number '{number}'")))?;
+            (Route::Enqueue(Job::Review { pr_number }), "POST")
+        }
+        ["status"] => (Route::Status, "GET"),
+        ["events"] => (Route::Events, "GET"),
+        _ => return Err((404, format!("no route for {path}"))),
+    };
+    if method != allowed {
+        return Err((405, format!("{path} accepts {allowed} only")));
+    }
+    Ok(route)
+}
+
+async fn respond(stream: &mut TcpStream, status: u16, body: &Value) -> Result<()> {
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Error",
+    };
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::StderrReporter;
+    use std::time::Duration;
+
+    #[test]
+    fn test_route() {
+        assert_eq!(
+            route("POST", "/tasks/gh-42/run"),
+            Ok(Route::Enqueue(Job::Task {
+                task_id: "gh-42".to_string()
+            }))
+        );
+        assert_eq!(
+            route("POST", "/prs/7/review/"),
+            Ok(Route::Enqueue(Job::Review { pr_number: 7 }))
+        );
+        assert_eq!(route("GET", "/status"), Ok(Route::Status));
+        assert_eq!(route("GET", "/events"), Ok(Route::Events));
+
+        assert_eq!(route("GET", "/tasks/42/run").unwrap_err().0, 405);
+        assert_eq!(route("POST", "/prs/abc/review").unwrap_err().0, 400);
+        assert_eq!(route("POST", "/tasks/a%20b/run").unwrap_err().0, 400);
+        assert_eq!(route("GET", "/nope").unwrap_err().0, 404);
+    }
+
+    #[test]
+    fn test_parse_request_head() {
+        let request = parse_request_head(
+            "GET /status?verbose=1 HTTP/1.1\r\nHost: localhost\r\nauthorization: Bearer s3cret",
+        )
+        .unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/status");
+        assert_eq!(request.bearer.as_deref(), Some("s3cret"));
+        assert_eq!(request.host.as_deref(), Some("localhost"));
+        assert_eq!(untrusted_without_token(&request), None);
+
+        assert_eq!(parse_request_head("GET /status").unwrap_err().0, 400);
+    }
+
+    #[test]
+    fn test_untrusted_without_token() {
+        let head = |extra: &str| parse_request_head(&format!("POST /tasks/1/run HTTP/1.1{extra}"));
+        for host in ["127.0.0.1:8080", "localhost", "[::1]:8080"] {
+            let request = head(&format!("\r\nHost: {host}")).unwrap();
+            assert_eq!(untrusted_without_token(&request), None, "{host}");
+        }
+        let request = head("\r\nHost: evil.example:8080").unwrap();
+        assert!(
+            untrusted_without_token(&request)
+                .unwrap()
+                .contains("evil.example")
+        );
+        assert!(untrusted_without_token(&head("").unwrap()).is_some());
+        let request = head("\r\nHost: 127.0.0.1\r\nOrigin: https://evil.example").unwrap();
+        assert!(
+            untrusted_without_token(&request)
+                .unwrap()
+                .contains("browser origin")
+        );
+    }
+
+    /// Records the jobs it runs; task `fail` fails and task `skip` is skipped.
+    #[derive(Default)]
+    struct RecordingRunner {
+        jobs: Arc<Mutex<Vec<Job>>>,
+    }
+
+    impl JobRunner for RecordingRunner {
+        async fn run(&self, job: &Job, events: EventSink) -> Result<IterationOutcome> {
+            self.jobs.lock().unwrap().push(job.clone());
+            EventReporter::new(StderrReporter, events).task_selected(42, "Fix bug");
+            match job {
+                Job::Task { task_id } if task_id == "fail" => {
+                    Err(Error::Orchestrator("agent crashed".to_string()))
+                }
+                Job::Task { task_id } if task_id == "skip" => Ok(IterationOutcome::SkippedTask),
+                _ => Ok(IterationOutcome::ProcessedTask),
+            }
+        }
+    }
+
+    async fn request(addr: std::net::SocketAddr, raw: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(raw.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    async fn wait_for_finished(server: &ApiServer, count: usize) -> Value {
+        for _ in 0..200 {
+            let status = server.status();
+            if status["finished"].as_array().unwrap().len() == count {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("jobs did not finish: {}", server.status());
+    }
+
+    #[tokio::test]
+    async fn test_serve_runs_jobs_in_order_and_reports_status() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = ApiServer::new();
+        let mut events = server.subscribe();
+        let runner = RecordingRunner::default();
+        let jobs = Arc::clone(&runner.jobs);
+        let serving = {
+            let server = server.clone();
+            tokio::spawn(async move { server.serve(listener, runner).await })
+        };
+
+        let response = request(
+            addr,
+            "POST /tasks/1/run HTTP/1.1\r\nHost: 127.0.0.1\r\nOrigin: https://evil.example\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden"), "{response}");
+        let response = request(
+            addr,
+            "POST /tasks/42/run HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 202 Accepted"), "{response}");
+        assert!(response.contains(r#""kind":"task""#));
+        assert!(response.contains(r#""task_id":"42""#));
+        request(
+            addr,
+            "POST /prs/7/review HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2\r\n\r\n{}",
+        )
+        .await;
+        request(
+            addr,
+            "POST /tasks/fail/run HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        )
+        .await;
+        request(
+            addr,
+            "POST /tasks/skip/run HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        )
+        .await;
+
+        let status = wait_for_finished(&server, 4).await;
+        assert!(status["running"].is_null());
+        let finished: Vec<&str> = status["finished"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|j| j["status"].as_str().unwrap())
+            .collect();
+        assert_eq!(finished, ["skipped", "failed", "succeeded", "succeeded"]);
+        assert_eq!(
+            status["finished"][1]["error"],
+            "orchestrator error: agent crashed"
+        );
+        assert_eq!(
+            *jobs.lock().unwrap(),
+            vec![
+                Job::Task {
+                    task_id: "42".to_string()
+                },
+                Job::Review { pr_number: 7 },
+                Job::Task {
+                    task_id: "fail".to_string()
+                },
+                Job::Task {
+                    task_id: "skip".to_string()
+                },
+            ]
+        );
+
+        let response = request(addr, "GET /status HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.contains(r#""status":"failed""#));
+
+        let first = events.recv().await.unwrap();
+        assert_eq!(first.name, "job_queued");
+        let data: Value = serde_json::from_str(&first.data).unwrap();
+        assert_eq!(data["job_id"], 1);
+        let names: Vec<String> = std::iter::from_fn(|| events.try_recv().ok())
+            .map(|e| e.name)
+            .take_while(|name| name != "job_finished")
+            .collect();
+        assert!(names.contains(&"job_started".to_string()));
+        assert!(names.contains(&"task_selected".to_string()));
+
+        serving.abort();
+    }
+
+    #[tokio::test]
+    async fn test_serve_checks_token_and_streams_events() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = ApiServer::new().with_token(Some("s3cret".to_string()));
+        let serving = {
+            let server = server.clone();
+            tokio::spawn(async move { server.serve(listener, RecordingRunner::default()).await })
+        };
+
+        let response = request(addr, "POST /tasks/42/run HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 401"), "{response}");
+        assert!(server.status()["queued"].as_array().unwrap().is_empty());
+
+        let mut sse = TcpStream::connect(addr).await.unwrap();
+        sse.write_all(b"GET /events HTTP/1.1\r\nAuthorization: Bearer s3cret\r\n\r\n")
+            .await
+            .unwrap();
+        let mut received = String::new();
+        let mut chunk = [0u8; 1024];
+        while !received.contains(": connected") {
+            let n = sse.read(&mut chunk).await.unwrap();
+            received.push_str(&String::from_utf8_lossy(&chunk[..n]));
+        }
+        assert!(received.contains("Content-Type: text/event-stream"));
+
+        request(
+            addr,
+            "POST /prs/9/review HTTP/1.1\r\nAuthorization: Bearer s3cret\r\n\r\n",
+        )
+        .await;
+        while !received.contains("event: job_finished") {
+            let n = tokio::time::timeout(Duration::from_secs(5), sse.read(&mut chunk))
+                .await
+                .unwrap()
+                .unwrap();
+            received.push_str(&String::from_utf8_lossy(&chunk[..n]));
+        }
+        assert!(received.contains("event: job_queued\ndata: {"));
+        assert!(received.contains(r#""pr_number":9"#));
+        assert!(received.contains("event: task_selected"));
+
+        serving.abort();
+    }
+}
@@ -0,0 +1,166 @@
+//! `automated_checks`: results of command review phases and the PR's CI,
+//! summarized for the aggregate prompt so the verdict weighs them alongside
+//! the agents' findings.
+
+use std::fmt::Write;
+
+use crate::review_schema::{ReviewFinding, Severity};
+use crate::submission::CiStatus;
+
+/// Findings listed per failing check; the rest are only counted.
+const MAX_LISTED_FINDINGS: usize = 10;
+
+/// Outcome of one automated check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckOutcome {
+    Passed,
+    Failed,
+    /// Still running, e.g. CI that has not finished.
+    Pending,
+}
+
+impl CheckOutcome {
+    fn label(&self) -> &'static str {
+        match self {
+            CheckOutcome::Passed => "PASS",
+            CheckOutcome::Failed => "FAIL",
+            CheckOutcome::Pending => "PENDING",
+        }
+    }
+}
+
+/// One line of `automated_checks`, with optional detail lines under it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutomatedCheck {
+    pub name: String,
+    pub outcome: CheckOutcome,
+    pub summary: String,
+    pub details: Vec<String>,
+}
+
+impl AutomatedCheck {
+    /// A command review phase (analyzer or coverage gate). It fails when it
+    /// reported any critical or warning finding.
+    pub fn from_gate(phase: &str, findings: &[ReviewFinding]) -> Self {
+        let count = |severity: Severity| findings.iter().filter(|f| f.severity == severity).count();
+        let (critical, warning, info) = (
+            count(Severity::Critical),
+            count(Severity::Warning),
+            count(Severity::Info),
+        );
+        let outcome = if critical + warning > 0 {
+            CheckOutcome::Failed
+        } else {
+            CheckOutcome::Passed
+        };
+        let summary = if findings.is_empty() {
+            "no findings".to_string()
+        } else {
+            format!("{critical} critical, {warning} warning, {info} info")
+        };
+        let mut details: Vec<String> = findings
+            .iter()
+            .filter(|f| f.severity != Severity::Info)
+            .take(MAX_LISTED_FINDINGS)
+            .map(|f| format!("`{}` L{}: {}", f.file, f.line, f.description))
+            .collect();
+        let more = critical + warning - details.len();
+        if more > 0 {
+            details.push(format!("… and {more} more"));
+        }
+        AutomatedCheck {
+            name: format!("review command `{phase}`"),
+            outcome,
+            summary,
+            details,
+        }
+    }
+
+    /// The combined CI status of the PR's head commit.
+    pub fn from_ci(status: &CiStatus) -> Self {
+        let (outcome, summary, details) = match status {
+            CiStatus::Passed => (CheckOutcome::Passed, "all checks passed", Vec::new()),
+            CiStatus::Pending => (
+                CheckOutcome::Pending,
+                "some checks have not finished",
+                Vec::new(),
+            ),
+            CiStatus::Failed(checks) => (
+                CheckOutcome::Failed,
+                "failing checks",
+                checks.iter().map(|c| format!("`{c}`")).collect(),
+            ),
+        };
+        AutomatedCheck {
+            name: "CI".to_string(),
+            outcome,
+            summary: summary.to_string(),
+            details,
+        }
+    }
+}
+
+/// Render checks as a Markdown list for the `automated_checks` prompt
+/// variable; empty when there are none.
+pub fn render_automated_checks(checks: &[AutomatedCheck]) -> String {
+    let mut out = String::new();
+    for check in checks {
+        let _ = writeln!(
+            out,
+            "- {}: **{}** — {}",
+            check.name,
+            check.outcome.label(),
+            check.summary
+        );
+        for detail in &check.details {
+            let _ = writeln!(out, "  - {detail}");
+        }
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(severity: Severity, line: u32) -> ReviewFinding {
+        ReviewFinding {
+            id: format!("f{line}"),
+            file: "src/lib.rs".to_string(),
+            line,
+            severity,
+            description: "unused variable".to_string(),
+            category: None,
+            depends_on: Vec::new(),
+            extra: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_render_gate_and_ci_checks() {
+        let checks = [
+            AutomatedCheck::from_gate(
+                "clippy",
+                &[finding(Severity::Warning, 3), finding(Severity::Info, 9)],
+            ),
+            AutomatedCheck::from_gate("coverage", &[]),
+            AutomatedCheck::from_ci(&CiStatus::Failed(vec!["test (ubuntu)".to_string()])),
+            AutomatedCheck::from_ci(&CiStatus::Pending),
+        ];
+        assert_eq!(
+            render_automated_checks(&checks),
+            "- review command `clippy`: **FAIL** — 0 critical, 1 warning, 1 info\n  \
+             - `src/lib.rs` L3: unused variable\n\
+             - review command `coverage`: **PASS** — no findings\n\
+             - CI: **FAIL** — failing checks\n  \
+             - `test (ubuntu)`\n\
+             - CI: **PENDING** — some checks have not finished"
+        );
+        assert_eq!(render_automated_checks(&[]), "");
+
+        let many: Vec<_> = (0..12).map(|i| finding(Severity::Critical, i)).collect();
+        let check = AutomatedCheck::from_gate("clippy", &many);
+        assert_eq!(check.details.len(), MAX_LISTED_FINDINGS + 1);
+        assert_eq!(check.details.last().unwrap(), "… and 2 more");
+    }
+}
@@ -0,0 +1,138 @@
+use std::path::Path;
+use std::process::Command;
+
+use tracing::{info, warn};
+
+use crate::config::{Config, GitHubTransport};
+use crate::gh_quota::gh_output;
+use crate::github_api::{GitHubApi, use_api};
+use crate::state::StateManager;
+use crate::worktree::{git_in_dir, validate_branch_name};
+
+/// Used when `base_branch` is not configured and detection finds nothing.
+pub const FALLBACK_BASE_BRANCH: &str = "main";
+
+/// Fill in `config.base_branch` when it was not configured: the branch
+/// detected on an earlier run, else `origin`'s default branch, else `main`.
+/// A newly detected branch is recorded in state so every later iteration
+/// uses the same one.
+pub fn resolve_base_branch(config: &mut Config, repo_root: &Path, state: &StateManager) {
+    if !config.detect_base_branch {
+        return;
+    }
+    if let Some(recorded) = state.detected_base_branch() {
+        config.base_branch = recorded;
+        return;
+    }
+    match detect_default_branch(repo_root, config.github_transport) {
+        Some(branch) => {
+            info!(branch, "detected base branch");
+            if let Err(e) = state.set_detected_base_branch(&branch) {
+                warn!(error = %e, "failed to record detected base branch");
+            }
+            config.base_branch = branch;
+        }
+        None => {
+            warn!(
+                fallback = FALLBACK_BASE_BRANCH,
+                "could not detect the default branch of origin; set base_branch"
+            );
+            config.base_branch = FALLBACK_BASE_BRANCH.to_string();
+        }
+    }
+}
+
+/// Default branch of `origin`: the local `refs/remotes/origin/HEAD`, then
+/// `git remote show origin`, then GitHub's default branch, asked through `gh`
+/// or the REST API per `transport`.
+pub fn detect_default_branch(repo_root: &Path, transport: GitHubTransport) -> Option<String> {
+    let from_ref = || {
+        git_in_dir(
+            repo_root,
+            &["symbolic-ref", "--short", "refs/remotes/origin/HEAD"],
+        )
+        .ok()
+        .and_then(|out| out.trim().strip_prefix("origin/").map(str::to_string))
+    };
+    let from_remote = || {
+        git_in_dir(repo_root, &["remote", "show", "origin"])
+            .ok()
+            .and_then(|out| parse_remote_show_head(&out))
+    };
+    let from_github = || {
+        if use_api(transport) {
+            return GitHubApi::from_env()
+                .ok()?
+                .default_branch()
+                .inspect_err(|e| warn!(error = %e, "failed to read the default branch from GitHub"))
+                .ok();
+        }
+        gh_output(Command::new("gh").current_dir(repo_root).args([
+            "repo",
+            "view",
+            "--json",
+            "defaultBranchRef",
+            "--jq",
+            ".defaultBranchRef.name",
+        ]))
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+    };
+    from_ref()
+        .or_else(from_remote)
+        .or_else(from_github)
+        .filter(|branch| validate_branch_name(branch).is_ok())
+}
+
+/// The `HEAD branch:` line of `git remote show` output.
+fn parse_remote_show_head(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("HEAD branch:"))
+        .map(str::trim)
+        .filter(|branch| !branch.is_empty() && *branch != "(unknown)")
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_remote_show_head() {
+        let output = "* remote origin\n  Fetch URL: git@github.com:o/r.git\n  HEAD branch: develop\n  Remote branches:\n";
+        assert_eq!(parse_remote_show_head(output).as_deref(), Some("develop"));
+        assert_eq!(parse_remote_show_head("  HEAD branch: (unknown)\n"), None);
+        assert_eq!(parse_remote_show_head("* remote origin\n"), None);
+    }
+
+    #[test]
+    fn test_detect_default_branch_reads_origin_head() {
+        let repo = TempDir::new().unwrap();
+        let git = |args: &[&str]| git_in_dir(repo.path(), args).unwrap();
+        git(&["init", "-q"]);
+        git(&[
+            "-c",
+            "user.name=t",
+            "-c",
+            "user.email=t@t",
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "init",
+        ]);
+        git(&["update-ref", "refs/remotes/origin/trunk", "HEAD"]);
+        git(&[
+            "symbolic-ref",
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/trunk",
+        ]);
+        assert_eq!(
+            detect_default_branch(repo.path(), GitHubTransport::Cli).as_deref(),
+            Some("trunk")
+        );
+    }
+}
@@ -0,0 +1,689 @@
+//! Bitbucket Cloud support: the REST 2.0 client shared by
+//! [`BitbucketSource`](crate::sources::bitbucket::BitbucketSource) and
+//! [`BitbucketSubmission`].
+
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use tracing::{info, warn};
+
+use crate::check_run::{CheckConclusion, ReviewCheck};
+use crate::config::{BitbucketConfig, Config, MergeMethod};
+use crate::error::{Error, Result};
+use crate::submission::{
+    CiStatus, PrComment, PrContext, PrState, REVIEW_MARKER, SubmissionBackend, SubmitResult,
+    extract_issue_number_reference, pr_body_references_issue,
+};
+use crate::suggestion::SuggestionComment;
+
+const MAX_RETRIES: u32 = 3;
+const INITIAL_BACKOFF_MS: u64 = 500;
+/// Items requested per page of a paginated listing.
+const PAGE_LEN: u32 = 50;
+/// Most pages read from one listing.
+const MAX_PAGES: usize = 20;
+
+// ---------------------------------------------------------------------------
+// Client abstraction (for testability)
+// ---------------------------------------------------------------------------
+
+pub trait BitbucketClient: Send + Sync {
+    /// Send `method` to `path`, which is relative to the repository
+    /// (`/repositories/<workspace>/<repo_slug>`) unless it is an absolute URL
+    /// such as a page's `next` link. Returns the response JSON, or `Null` for
+    /// an empty body.
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&Value>,
+    ) -> std::result::Result<Value, String>;
+}
+
+struct HttpBitbucketClient {
+    repo_url: String,
+    authorization: String,
+}
+
+impl HttpBitbucketClient {
+    fn url(&self, path: &str) -> String {
+        if path.starts_with("https://") || path.starts_with("http://") {
+            path.to_string()
+        } else {
+            format!("{}{path}", self.repo_url)
+        }
+    }
+}
+
+impl BitbucketClient for HttpBitbucketClient {
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&Value>,
+    ) -> std::result::Result<Value, String> {
+        let url = self.url(path);
+        let mut backoff_ms = INITIAL_BACKOFF_MS;
+        for attempt in 1..=MAX_RETRIES {
+            let request = ureq::request(method, &url)
+                .set("Authorization", &self.authorization)
+                .set("Accept", "application/json");
+            let response = match body {
+                Some(body) => request.send_json(body),
+                None => request.call(),
+            };
+            match response {
+                Ok(response) => {
+                    let text = response
+                        .into_string()
+                        .map_err(|e| format!("failed to read Bitbucket response: {e}"))?;
+                    if text.trim().is_empty() {
+                        return Ok(Value::Null);
+                    }
+                    return serde_json::from_str(&text)
+                        .map_err(|e| format!("failed to parse Bitbucket response: {e}"));
+                }
+                Err(ureq::Error::Status(code, response))
+                    if (code == 429 || code >= 500) && attempt < MAX_RETRIES =>
+                {
+                    let wait = response
+                        .header("Retry-After")
+                        .and_then(|s| s.trim().parse::<u64>().ok())
+                        .map(Duration::from_secs)
+                        .unwrap_or(Duration::from_millis(backoff_ms));
+                    warn!(
+                        attempt,
+                        code,
+                        wait_ms = wait.as_millis() as u64,
+                        "retrying Bitbucket API request"
+                    );
+                    thread::sleep(wait);
+                    backoff_ms *= 2;
+                }
+                Err(ureq::Error::Status(code, response)) => {
+                    let body = response.into_string().unwrap_or_default();
+                    return Err(format!(
+                        "Bitbucket API {method} {path} failed: status {code}: {body}"
+                    ));
+                }
+                Err(e) if attempt < MAX_RETRIES => {
+                    warn!(attempt, error = %e, backoff_ms, "retrying Bitbucket API after transient error");
+                    thread::sleep(Duration::from_millis(backoff_ms));
+                    backoff_ms *= 2;
+                }
+                Err(e) => return Err(format!("Bitbucket API {method} {path} failed: {e}")),
+            }
+        }
+        Err(format!(
+            "Bitbucket API {method} {path} failed; retries exhausted"
+        ))
+    }
+}
+
+/// Build the HTTP client for `[bitbucket]`. With a username in
+/// `username_env` the token is sent as HTTP basic auth (app passwords and API
+/// tokens); without one it is sent as a bearer token (access tokens).
+pub fn client_from_config(config: &Config) -> Result<Box<dyn BitbucketClient>> {
+    let bb = bitbucket_config(config)?;
+    let token = std::env::var(&bb.token_env).map_err(|_| {
+        Error::ConfigValidation(format!("Bitbucket token not found in ${}", bb.token_env))
+    })?;
+    let authorization = match std::env::var(&bb.username_env) {
+        Ok(user) if !user.is_empty() => {
+            format!(
+                "Basic {}",
+                base64_encode(format!("{user}:{token}").as_bytes())
+            )
+        }
+        _ => format!("Bearer {token}"),
+    };
+    Ok(Box::new(HttpBitbucketClient {
+        repo_url: format!(
+            "{}/repositories/{}/{}",
+            bb.api_url.trim_end_matches('/'),
+            bb.workspace,
+            bb.repo_slug
+        ),
+        authorization,
+    }))
+}
+
+fn bitbucket_config(config: &Config) -> Result<&BitbucketConfig> {
+    config
+        .bitbucket
+        .as_ref()
+        .ok_or_else(|| Error::ConfigValidation("[bitbucket] config section required".to_string()))
+}
+
+fn base64_encode(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for (i, shift) in [18, 12, 6, 0].into_iter().enumerate() {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((n >> shift) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Every item of a paginated listing starting at `path`.
+pub fn fetch_all(
+    client: &dyn BitbucketClient,
+    path: &str,
+) -> std::result::Result<Vec<Value>, String> {
+    let separator = if path.contains('?') { '&' } else { '?' };
+    let mut next = Some(format!("{path}{separator}pagelen={PAGE_LEN}"));
+    let mut items = Vec::new();
+    for _ in 0..MAX_PAGES {
+        let Some(page_path) = next.take() else {
+            break;
+        };
+        let page = client.request("GET", &page_path, None)?;
+        if let Some(values) = page.get("values").and_then(Value::as_array) {
+            items.extend(values.iter().cloned());
+        }
+        next = page.get("next").and_then(Value::as_str).map(str::to_string);
+    }
+    if next.is_some() {
+        warn!(path, pages = MAX_PAGES, "Bitbucket listing truncated");
+    }
+    Ok(items)
+}
+
+/// Percent-encode a BBQL query for the `q` parameter.
+pub fn encode_query(query: &str) -> String {
+    query
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Quote `value` as a BBQL string literal.
+pub fn bbql_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// ---------------------------------------------------------------------------
+// Submission
+// ---------------------------------------------------------------------------
+
+/// Bitbucket Cloud pull requests.
+///
+/// Bitbucket numbers issues and pull requests separately, so comments that
+/// rlph would put on an issue thread on GitHub (heartbeats) are skipped, and
+/// `review_target = "issue-comment"` is rejected at config validation.
+pub struct BitbucketSubmission {
+    client: Box<dyn BitbucketClient>,
+}
+
+impl BitbucketSubmission {
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self::with_client(client_from_config(config)?))
+    }
+
+    pub fn with_client(client: Box<dyn BitbucketClient>) -> Self {
+        Self { client }
+    }
+
+    fn call(&self, method: &str, path: &str, body: Option<&Value>) -> Result<Value> {
+        self.client
+            .request(method, path, body)
+            .map_err(Error::Submission)
+    }
+
+    fn open_prs(&self, query: Option<&str>) -> Result<Vec<Value>> {
+        let mut bbql = "state=\"OPEN\"".to_string();
+        if let Some(query) = query {
+            bbql.push_str(&format!(" AND {query}"));
+        }
+        fetch_all(
+            self.client.as_ref(),
+            &format!("/pullrequests?q={}", encode_query(&bbql)),
+        )
+        .map_err(Error::Submission)
+    }
+
+    fn pr(&self, pr_number: u64) -> Result<Value> {
+        self.call("GET", &format!("/pullrequests/{pr_number}"), None)
+    }
+
+    fn comments(&self, pr_number: u64) -> Result<Vec<Value>> {
+        fetch_all(
+            self.client.as_ref(),
+            &format!("/pullrequests/{pr_number}/comments"),
+        )
+        .map_err(Error::Submission)
+    }
+
+    fn find_marked_comment(&self, pr_number: u64, marker: &str) -> Result<Option<u64>> {
+        Ok(self.comments(pr_number)?.iter().find_map(|c| {
+            let body = c.pointer("/content/raw").and_then(Value::as_str)?;
+            if body.contains(marker) {
+                c.get("id").and_then(Value::as_u64)
+            } else {
+                None
+            }
+        }))
+    }
+}
+
+/// A pull request from the Bitbucket API as a [`PrContext`].
+fn pr_context(pr: &Value) -> Option<PrContext> {
+    let str_at = |pointer: &str| pr.pointer(pointer).and_then(Value::as_str);
+    let body = str_at("/description").unwrap_or_default().to_string();
+    Some(PrContext {
+        number: pr.get("id").and_then(Value::as_u64)?,
+        title: str_at("/title").unwrap_or_default().to_string(),
+        url: str_at("/links/html/href").unwrap_or_default().to_string(),
+        head_branch: str_at("/source/branch/name")
+            .filter(|b| !b.is_empty())?
+            .to_string(),
+        head_sha: str_at("/source/commit/hash").map(str::to_string),
+        base_branch: str_at("/destination/branch/name").map(str::to_string),
+        linked_issue_number: extract_issue_number_reference(&body),
+        body,
+    })
+}
+
+fn pr_comment(comment: &Value) -> Option<PrComment> {
+    Some(PrComment::new(
+        comment.get("id").and_then(Value::as_u64)?,
+        comment
+            .pointer("/user/nickname")
+            .or_else(|| comment.pointer("/user/display_name"))
+            .and_then(Value::as_str)
+            .unwrap_or("unknown"),
+        comment
+            .pointer("/content/raw")
+            .and_then(Value::as_str)
+            .unwrap_or_default(),
+        comment
+            .get("created_on")
+            .and_then(Value::as_str)
+            .unwrap_or_default(),
+    ))
+}
+
+fn comment_body(body: &str) -> Value {
+    json!({ "content": { "raw": body } })
+}
+
+impl SubmissionBackend for BitbucketSubmission {
+    fn submit(&self, branch: &str, base: &str, title: &str, body: &str) -> Result<SubmitResult> {
+        let existing =
+            self.open_prs(Some(&format!("source.branch.name={}", bbql_string(branch))))?;
+        if let Some(pr) = existing.first().and_then(pr_context) {
+            info!(url = %pr.url, "found existing PR for branch");
+            return Ok(SubmitResult {
+                url: pr.url,
+                number: Some(pr.number),
+            });
+        }
+        let created = self.call(
+            "POST",
+            "/pullrequests",
+            Some(&json!({
+                "title": title,
+                "description": body,
+                "source": { "branch": { "name": branch } },
+                "destination": { "branch": { "name": base } },
+                "close_source_branch": true,
+            })),
+        )?;
+        let pr = pr_context(&created).ok_or_else(|| {
+            Error::Submission("Bitbucket did not return the created pull request".to_string())
+        })?;
+        Ok(SubmitResult {
+            url: pr.url,
+            number: Some(pr.number),
+        })
+    }
+
+    fn find_existing_pr_for_issue(&self, issue_number: u64) -> Result<Option<u64>> {
+        Ok(self
+            .open_prs(None)?
+            .iter()
+            .filter_map(pr_context)
+            .find(|pr| pr_body_references_issue(&pr.body, issue_number))
+            .map(|pr| pr.number))
+    }
+
+    fn upsert_review_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        match self.find_marked_comment(pr_number, REVIEW_MARKER)? {
+            Some(id) => self.call(
+                "PUT",
+                &format!("/pullrequests/{pr_number}/comments/{id}"),
+                Some(&comment_body(body)),
+            )?,
+            None => self.call(
+                "POST",
+                &format!("/pullrequests/{pr_number}/comments"),
+                Some(&comment_body(body)),
+            )?,
+        };
+        Ok(())
+    }
+
+    fn fetch_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        Ok(self
+            .comments(pr_number)?
+            .iter()
+            .filter(|c| c.get("deleted").and_then(Value::as_bool) != Some(true))
+            .filter_map(pr_comment)
+            .collect())
+    }
+
+    fn list_open_prs(&self) -> Result<Vec<PrContext>> {
+        Ok(self.open_prs(None)?.iter().filter_map(pr_context).collect())
+    }
+
+    fn post_pr_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.call(
+            "POST",
+            &format!("/pullrequests/{pr_number}/comments"),
+            Some(&comment_body(body)),
+        )?;
+        Ok(())
+    }
+
+    fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()> {
+        let title = self
+            .pr(pr_number)?
+            .get("title")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        self.call(
+            "PUT",
+            &format!("/pullrequests/{pr_number}"),
+            Some(&json!({ "title": title, "description": body })),
+        )?;
+        Ok(())
+    }
+
+    /// Bitbucket has no suggested-change comments; findings stay in the review
+    /// comment.
+    fn post_suggestions(
+        &self,
+        _pr_number: u64,
+        _suggestions: &[SuggestionComment],
+    ) -> Result<usize> {
+        Ok(0)
+    }
+
+    fn pr_state(&self, pr_number: u64) -> Result<PrState> {
+        match self.pr(pr_number)?.get("state").and_then(Value::as_str) {
+            Some("OPEN") => Ok(PrState::Open),
+            Some("MERGED") => Ok(PrState::Merged),
+            Some("DECLINED" | "SUPERSEDED") => Ok(PrState::Closed),
+            other => Err(Error::Submission(format!(
+                "unknown Bitbucket PR state: {other:?}"
+            ))),
+        }
+    }
+
+    fn close_pr(&self, pr_number: u64, comment: &str) -> Result<()> {
+        self.post_pr_comment(pr_number, comment)?;
+        self.call("POST", &format!("/pullrequests/{pr_number}/decline"), None)?;
+        Ok(())
+    }
+
+    /// Published as a build status on the PR's head commit. Bitbucket build
+    /// statuses carry no annotations, so the summary holds the findings.
+    fn publish_review_check(&self, pr_number: u64, check: &ReviewCheck) -> Result<()> {
+        let pr = self.pr(pr_number)?;
+        let context = pr_context(&pr).ok_or_else(|| {
+            Error::Submission(format!("Bitbucket PR {pr_number} has no source branch"))
+        })?;
+        let sha = context.head_sha.ok_or_else(|| {
+            Error::Submission(format!("Bitbucket PR {pr_number} has no head commit"))
+        })?;
+        let state = match check.conclusion {
+            CheckConclusion::Failure => "FAILED",
+            CheckConclusion::Success | CheckConclusion::Neutral => "SUCCESSFUL",
+        };
+        let description: String = check.summary.chars().take(255).collect();
+        self.call(
+            "POST",
+            &format!("/commit/{sha}/statuses/build"),
+            Some(&json!({
+                "key": "rlph-review",
+                "state": state,
+                "name": check.title,
+                "description": description,
+                "url": context.url,
+            })),
+        )?;
+        Ok(())
+    }
+
+    /// Skipped: the thread may be an issue, whose number Bitbucket does not
+    /// share with pull requests.
+    fn upsert_heartbeat_comment(&self, _number: u64, _body: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn delete_heartbeat_comment(&self, _number: u64) -> Result<()> {
+        Ok(())
+    }
+
+    fn ci_status(&self, pr_number: u64) -> Result<CiStatus> {
+        let statuses = fetch_all(
+            self.client.as_ref(),
+            &format!("/pullrequests/{pr_number}/statuses"),
+        )
+        .map_err(Error::Submission)?;
+        let mut pending = false;
+        let mut failed = Vec::new();
+        for status in &statuses {
+            let name = status
+                .get("name")
+                .or_else(|| status.get("key"))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            match status.get("state").and_then(Value::as_str) {
+                Some("SUCCESSFUL") => {}
+                Some("FAILED" | "STOPPED") => failed.push(name.to_string()),
+                _ => pending = true,
+            }
+        }
+        Ok(if !failed.is_empty() {
+            CiStatus::Failed(failed)
+        } else if pending {
+            CiStatus::Pending
+        } else {
+            CiStatus::Passed
+        })
+    }
+
+    /// Bitbucket Cloud has no auto-merge, so the PR is merged right away. rlph
+    /// only calls this once CI has passed.
+    fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        let strategy = match method {
+            MergeMethod::Rebase => "fast_forward",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Merge => "merge_commit",
+        };
+        self.call(
+            "POST",
+            &format!("/pullrequests/{pr_number}/merge"),
+            Some(&json!({ "merge_strategy": strategy })),
+        )?;
+        info!(pr_number, "merged Bitbucket PR");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::MockHttpClient;
+
+    impl BitbucketClient for MockHttpClient {
+        fn request(
+            &self,
+            method: &str,
+            path: &str,
+            body: Option<&Value>,
+        ) -> std::result::Result<Value, String> {
+            self.reply(method, path, &[], body)
+        }
+    }
+
+    impl BitbucketClient for std::sync::Arc<MockHttpClient> {
+        fn request(
+            &self,
+            method: &str,
+            path: &str,
+            body: Option<&Value>,
+        ) -> std::result::Result<Value, String> {
+            (**self).request(method, path, body)
+        }
+    }
+
+    fn pr_json(id: u64, branch: &str, description: &str) -> Value {
+        json!({
+            "id": id,
+            "title": "Fix login",
+            "description": description,
+            "state": "OPEN",
+            "links": { "html": { "href": format!("https://bitbucket.org/w/r/pull-requests/{id}") } },
+            "source": { "branch": { "name": branch }, "commit": { "hash": "abc123" } },
+            "destination": { "branch": { "name": "main" } },
+        })
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b"user:pass"), "dXNlcjpwYXNz");
+        assert_eq!(base64_encode(b"ab"), "YWI=");
+        assert_eq!(base64_encode(b"a"), "YQ==");
+    }
+
+    #[test]
+    fn test_fetch_all_follows_next_links() {
+        let client = MockHttpClient::default()
+            .respond(
+                "GET /issues?pagelen=50",
+                json!({ "values": [{ "id": 1 }], "next": "https://api.bitbucket.org/2.0/page2" }),
+            )
+            .respond(
+                "GET https://api.bitbucket.org/2.0/page2",
+                json!({ "values": [{ "id": 2 }] }),
+            );
+        let items = fetch_all(&client, "/issues").unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1]["id"], 2);
+    }
+
+    #[test]
+    fn test_submit_creates_pr_or_reuses_open_one() {
+        let client = std::sync::Arc::new(
+            MockHttpClient::default()
+                .respond("GET /pullrequests?q=", json!({ "values": [] }))
+                .respond(
+                    "POST /pullrequests",
+                    pr_json(7, "rlph-3-fix", "Resolves #3"),
+                ),
+        );
+        let submission = BitbucketSubmission::with_client(Box::new(client.clone()));
+        let result = submission
+            .submit("rlph-3-fix", "main", "Fix login", "Resolves #3")
+            .unwrap();
+        assert_eq!(result.number, Some(7));
+        assert_eq!(result.url, "https://bitbucket.org/w/r/pull-requests/7");
+        let requests = client.requests.lock().unwrap();
+        let (_, _, body) = &requests[1];
+        let body = body.as_ref().unwrap();
+        assert_eq!(body["source"]["branch"]["name"], "rlph-3-fix");
+        assert_eq!(body["destination"]["branch"]["name"], "main");
+        drop(requests);
+
+        let client = MockHttpClient::default().respond(
+            "GET /pullrequests?q=",
+            json!({ "values": [pr_json(5, "rlph-3-fix", "")] }),
+        );
+        let submission = BitbucketSubmission::with_client(Box::new(client));
+        let result = submission
+            .submit("rlph-3-fix", "main", "Fix login", "")
+            .unwrap();
+        assert_eq!(result.number, Some(5));
+    }
+
+    #[test]
+    fn test_upsert_review_comment_updates_marked_comment() {
+        let client = std::sync::Arc::new(MockHttpClient::default().respond(
+            "GET /pullrequests/7/comments",
+            json!({ "values": [
+                { "id": 11, "content": { "raw": "looks good" } },
+                { "id": 12, "content": { "raw": format!("{REVIEW_MARKER}\nold") } },
+            ] }),
+        ));
+        let submission = BitbucketSubmission::with_client(Box::new(client.clone()));
+        submission
+            .upsert_review_comment(7, &format!("{REVIEW_MARKER}\nnew"))
+            .unwrap();
+        let requests = client.requests.lock().unwrap();
+        assert_eq!(
+            requests.last().unwrap().0,
+            "PUT /pullrequests/7/comments/12"
+        );
+    }
+
+    #[test]
+    fn test_pr_state_and_context_mapping() {
+        let mut declined = pr_json(9, "rlph-4-x", "Resolves #4");
+        declined["state"] = json!("DECLINED");
+        let client = MockHttpClient::default().respond("GET /pullrequests/9", declined);
+        let submission = BitbucketSubmission::with_client(Box::new(client));
+        assert_eq!(submission.pr_state(9).unwrap(), PrState::Closed);
+
+        let context = pr_context(&pr_json(9, "rlph-4-x", "Resolves #4")).unwrap();
+        assert_eq!(context.head_sha.as_deref(), Some("abc123"));
+        assert_eq!(context.base_branch.as_deref(), Some("main"));
+        assert_eq!(context.linked_issue_number, Some(4));
+        assert!(pr_context(&json!({ "id": 1 })).is_none());
+    }
+
+    #[test]
+    fn test_ci_status_from_build_statuses() {
+        let client = MockHttpClient::default()
+            .respond(
+                "GET /pullrequests/9/statuses",
+                json!({ "values": [
+                    { "key": "build", "name": "Build", "state": "SUCCESSFUL" },
+                    { "key": "lint", "name": "Lint", "state": "INPROGRESS" },
+                ] }),
+            )
+            .respond(
+                "GET /pullrequests/9/statuses",
+                json!({ "values": [
+                    { "key": "build", "name": "Build", "state": "SUCCESSFUL" },
+                    { "key": "lint", "name": "Lint", "state": "FAILED" },
+                ] }),
+            );
+        let submission = BitbucketSubmission::with_client(Box::new(client));
+        assert_eq!(submission.ci_status(9).unwrap(), CiStatus::Pending);
+        assert_eq!(
+            submission.ci_status(9).unwrap(),
+            CiStatus::Failed(vec!["Lint".to_string()])
+        );
+        assert_eq!(submission.ci_status(9).unwrap(), CiStatus::Passed);
+    }
+}
@@ -0,0 +1,316 @@
+//! Assemble a ready-to-run [`Orchestrator`] from a [`Config`], for embedding
+//! rlph as a library.
+//!
+//! ```no_run
+//! use clap::Parser;
+//! use rlph::{Config, OrchestratorBuilder};
+//!
+//! # async fn run() -> rlph::Result<()> {
+//! let cli = rlph::cli::Cli::parse_from(["rlph", "--once"]);
+//! let repo_root = std::env::current_dir()?;
+//! let config = Config::load_from(&cli, &repo_root)?;
+//! let orchestrator = OrchestratorBuilder::new(config, repo_root).build()?;
+//! orchestrator.run_once().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::base_branch::resolve_base_branch;
+use crate::bitbucket::BitbucketSubmission;
+use crate::config::Config;
+use crate::error::Result;
+use crate::gitea::GiteaSubmission;
+use crate::github_api::{GitHubApiSubmission, use_api};
+use crate::hooks::HookReporter;
+use crate::orchestrator::{DefaultReviewRunnerFactory, Orchestrator, StderrReporter};
+use crate::prompts::PromptEngine;
+use crate::runner::{AgentRunner, AnyRunner, build_runner};
+use crate::sources::bitbucket::BitbucketSource;
+use crate::sources::gitea::GiteaSource;
+use crate::sources::github::GitHubSource;
+use crate::sources::github_api::GitHubApiSource;
+use crate::sources::linear::LinearSource;
+use crate::sources::trello::TrelloSource;
+use crate::sources::{AnySource, TaskSource};
+use crate::state::StateManager;
+use crate::submission::{AnySubmission, GitHubSubmission, SubmissionBackend};
+use crate::worktree::WorktreeManager;
+
+/// Placeholder for a component the builder creates from the [`Config`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FromConfig;
+
+/// A task source, or [`FromConfig`] for the one named by `config.source`.
+pub trait IntoSource {
+    type Source: TaskSource;
+    fn into_source(self, config: &Config) -> Result<Self::Source>;
+}
+
+impl IntoSource for FromConfig {
+    type Source = AnySource;
+
+    fn into_source(self, config: &Config) -> Result<AnySource> {
+        match config.source.as_str() {
+            "linear" => Ok(AnySource::Linear(LinearSource::new(config)?)),
+            "bitbucket" => Ok(AnySource::Bitbucket(BitbucketSource::new(config)?)),
+            "trello" => Ok(AnySource::Trello(TrelloSource::new(config)?)),
+            "gitea" => Ok(AnySource::Gitea(GiteaSource::new(config)?)),
+            _ if use_api(config.github_transport) => {
+                Ok(AnySource::GitHubApi(GitHubApiSource::new(config)?))
+            }
+            _ => Ok(AnySource::GitHub(GitHubSource::new(config))),
+        }
+    }
+}
+
+impl<T: TaskSource> IntoSource for T {
+    type Source = T;
+
+    fn into_source(self, _config: &Config) -> Result<T> {
+        Ok(self)
+    }
+}
+
+/// An agent runner, or [`FromConfig`] for the implement runner described by
+/// `config.runner` and the `agent_*` settings.
+pub trait IntoRunner {
+    type Runner: AgentRunner;
+    fn into_runner(self, config: &Config) -> Result<Self::Runner>;
+}
+
+impl IntoRunner for FromConfig {
+    type Runner = AnyRunner;
+
+    fn into_runner(self, config: &Config) -> Result<AnyRunner> {
+        let runner = build_runner(
+            config.runner,
+            &config.agent_binary,
+            config.agent_model.as_deref(),
+            config.agent_effort.as_deref(),
+            config.agent_variant.as_deref(),
+            config.implement_timeout.map(Duration::from_secs),
+            config.agent_timeout_retries,
+        )
+        .with_permission_mode(config.permission_mode)
+        .with_identity(config.agent_identity.clone())
+        .with_sampling(config.agent_temperature, config.agent_extra_args.clone());
+        if config.quiet {
+            return Ok(runner);
+        }
+        Ok(runner.with_stream_prefix("implement".to_string()))
+    }
+}
+
+impl<T: AgentRunner> IntoRunner for T {
+    type Runner = T;
+
+    fn into_runner(self, _config: &Config) -> Result<T> {
+        Ok(self)
+    }
+}
+
+/// A submission backend, or [`FromConfig`] for the one named by
+/// `config.submission`: GitHub PRs honoring `config.pr_head_owner` (through
+/// `gh` or the REST API, per `config.github_transport`), or Bitbucket or Gitea
+/// pull requests.
+pub trait IntoSubmission {
+    type Submission: SubmissionBackend;
+    fn into_submission(self, config: &Config) -> Result<Self::Submission>;
+}
+
+impl IntoSubmission for FromConfig {
+    type Submission = AnySubmission;
+
+    fn into_submission(self, config: &Config) -> Result<AnySubmission> {
+        match config.submission.as_str() {
+            "bitbucket" => Ok(AnySubmission::Bitbucket(BitbucketSubmission::new(config)?)),
+            "gitea" => Ok(AnySubmission::Gitea(GiteaSubmission::new(config)?)),
+            _ if use_api(config.github_transport) => {
+                Ok(AnySubmission::GitHubApi(GitHubApiSubmission::new(config)?))
+            }
+            _ => Ok(AnySubmission::GitHub(
+                GitHubSubmission::new().with_head_owner(config.pr_head_owner.clone()),
+            )),
+        }
+    }
+}
+
+impl<T: SubmissionBackend> IntoSubmission for T {
+    type Submission = T;
+
+    fn into_submission(self, _config: &Config) -> Result<T> {
+        Ok(self)
+    }
+}
+
+/// The orchestrator returned by [`OrchestratorBuilder::build`].
+pub type BuiltOrchestrator<S, R, B> =
+    Orchestrator<S, R, B, DefaultReviewRunnerFactory, HookReporter<StderrReporter>>;
+
+/// Builds an [`Orchestrator`] the way the `rlph` binary does.
+///
+/// The source, implement runner, and submission backend come from the config
+/// unless replaced with `with_source`, `with_runner`, or `with_submission`.
+/// Relative `worktree_dir` and `cache_dir` paths resolve against `repo_root`,
+/// state lives in `.rlph/state/`, and progress goes to stderr with `[hooks]`
+/// applied. Review runners, the reporter, and the correction runner can still
+/// be swapped on the returned orchestrator.
+pub struct OrchestratorBuilder<S = FromConfig, R = FromConfig, B = FromConfig> {
+    config: Config,
+    repo_root: PathBuf,
+    source: S,
+    runner: R,
+    submission: B,
+    state_dir: Option<PathBuf>,
+    prompt_dir: Option<String>,
+}
+
+impl OrchestratorBuilder {
+    pub fn new(config: Config, repo_root: impl Into<PathBuf>) -> Self {
+        Self {
+            config,
+            repo_root: repo_root.into(),
+            source: FromConfig,
+            runner: FromConfig,
+            submission: FromConfig,
+            state_dir: None,
+            prompt_dir: None,
+        }
+    }
+}
+
+impl<S, R, B> OrchestratorBuilder<S, R, B> {
+    pub fn with_source<S2: TaskSource>(self, source: S2) -> OrchestratorBuilder<S2, R, B> {
+        OrchestratorBuilder {
+            config: self.config,
+            repo_root: self.repo_root,
+            source,
+            runner: self.runner,
+            submission: self.submission,
+            state_dir: self.state_dir,
+            prompt_dir: self.prompt_dir,
+        }
+    }
+
+    pub fn with_runner<R2: AgentRunner>(self, runner: R2) -> OrchestratorBuilder<S, R2, B> {
+        OrchestratorBuilder {
+            config: self.config,
+            repo_root: self.repo_root,
+            source: self.source,
+            runner,
+            submission: self.submission,
+            state_dir: self.state_dir,
+            prompt_dir: self.prompt_dir,
+        }
+    }
+
+    pub fn with_submission<B2: SubmissionBackend>(
+        self,
+        submission: B2,
+    ) -> OrchestratorBuilder<S, R, B2> {
+        OrchestratorBuilder {
+            config: self.config,
+            repo_root: self.repo_root,
+            source: self.source,
+            runner: self.runner,
+            submission,
+            state_dir: self.state_dir,
+            prompt_dir: self.prompt_dir,
+        }
+    }
+
+    /// Keep orchestrator state somewhere other than `.rlph/state/`.
+    pub fn with_state_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.state_dir = Some(dir.into());
+        self
+    }
+
+    /// Directory of prompt template overrides, as for `PromptEngine::new`.
+    pub fn with_prompt_dir(mut self, dir: impl Into<String>) -> Self {
+        self.prompt_dir = Some(dir.into());
+        self
+    }
+}
+
+impl<S: IntoSource, R: IntoRunner, B: IntoSubmission> OrchestratorBuilder<S, R, B> {
+    pub fn build(self) -> Result<BuiltOrchestrator<S::Source, R::Runner, B::Submission>> {
+        let mut config = self.config;
+        let repo_root = self.repo_root;
+        let state_dir = self
+            .state_dir
+            .unwrap_or_else(|| StateManager::default_dir(&repo_root));
+        let state_mgr = StateManager::new(state_dir);
+        resolve_base_branch(&mut config, &repo_root, &state_mgr);
+        let source = self.source.into_source(&config)?;
+        let runner = self.runner.into_runner(&config)?;
+        let submission = self.submission.into_submission(&config)?;
+        let worktree_mgr = WorktreeManager::new(
+            repo_root.clone(),
+            repo_root.join(&config.worktree_dir),
+            config.base_branch.clone(),
+        )
+        .with_branch_template(config.branch_template.clone())
+        .with_push_remote(config.push_remote.clone())
+        .with_shared_caches(
+            repo_root.join(&config.cache_dir),
+            config.shared_cache_dirs.clone(),
+        )
+        .with_git_identity(config.git_identity.clone());
+        let reporter = HookReporter::new(StderrReporter, config.hooks.clone(), repo_root.clone());
+
+        Ok(Orchestrator::new(
+            source,
+            runner,
+            submission,
+            worktree_mgr,
+            state_mgr,
+            PromptEngine::new(self.prompt_dir).with_version(&config.prompt_version),
+            config,
+            repo_root,
+        )
+        .with_reporter(reporter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use clap::Parser;
+
+    fn load_config(dir: &std::path::Path, toml: &str) -> Config {
+        std::fs::create_dir_all(dir.join(".rlph")).unwrap();
+        std::fs::write(dir.join(".rlph/config.toml"), toml).unwrap();
+        Config::load_from(&Cli::parse_from(["rlph", "--once"]), dir).unwrap()
+    }
+
+    #[test]
+    fn test_build_from_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = load_config(tmp.path(), "");
+        let orchestrator = OrchestratorBuilder::new(config, tmp.path())
+            .with_state_dir(tmp.path().join("state"))
+            .build();
+        assert!(orchestrator.is_ok());
+    }
+
+    #[test]
+    fn test_build_surfaces_source_errors() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = load_config(
+            tmp.path(),
+            "source = \"linear\"\n[linear]\nteam = \"ENG\"\napi_key_env = \"RLPH_BUILDER_TEST_UNSET_KEY\"\n",
+        );
+        let err = OrchestratorBuilder::new(config, tmp.path())
+            .build()
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string().contains("RLPH_BUILDER_TEST_UNSET_KEY"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use serde::Serialize;
+
+use crate::review_schema::{ReviewFinding, Severity};
+
+/// Name of the check run rlph publishes reviews under.
+pub const CHECK_RUN_NAME: &str = "rlph review";
+
+/// Most annotations GitHub accepts in one check run request.
+pub const MAX_ANNOTATIONS_PER_REQUEST: usize = 50;
+
+/// Longest check run summary GitHub accepts, in characters.
+const MAX_SUMMARY_CHARS: usize = 65_535;
+
+/// Severity of a check run annotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnotationLevel {
+    Notice,
+    Warning,
+    Failure,
+}
+
+/// A finding anchored to a file line of the PR head.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckAnnotation {
+    pub path: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub annotation_level: AnnotationLevel,
+    pub title: String,
+    pub message: String,
+}
+
+/// Overall outcome of a review published as a check run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckConclusion {
+    Success,
+    Neutral,
+    Failure,
+}
+
+/// A review rendered as a completed check run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReviewCheck {
+    pub title: String,
+    /// Markdown shown on the check run page.
+    pub summary: String,
+    pub conclusion: CheckConclusion,
+    pub annotations: Vec<CheckAnnotation>,
+}
+
+/// Build the check run for a review round. Critical findings fail the check, other
+/// findings leave it neutral, and a clean review succeeds. Findings without a file
+/// stay in the summary only.
+pub fn review_check(findings: &[ReviewFinding], summary: &str) -> ReviewCheck {
+    let conclusion = if findings.iter().any(|f| f.severity == Severity::Critical) {
+        CheckConclusion::Failure
+    } else if findings.is_empty() {
+        CheckConclusion::Success
+    } else {
+        CheckConclusion::Neutral
+    };
+    let title = match findings.len() {
+        0 => "No findings".to_string(),
+        1 => "1 finding".to_string(),
+        n => format!("{n} findings"),
+    };
+    let annotations = findings
+        .iter()
+        .filter(|f| !f.file.trim().is_empty())
+        .map(|f| {
+            let line = f.line.max(1);
+            CheckAnnotation {
+                path: f.file.clone(),
+                start_line: line,
+                end_line: line,
+                annotation_level: match f.severity {
+                    Severity::Critical => AnnotationLevel::Failure,
+                    Severity::Warning => AnnotationLevel::Warning,
+                    Severity::Info => AnnotationLevel::Notice,
+                },
+                title: format!("{} {}", f.severity.label(), f.id),
+                message: f.description.trim().to_string(),
+            }
+        })
+        .collect();
+    ReviewCheck {
+        title,
+        summary: truncate_summary(summary),
+        conclusion,
+        annotations,
+    }
+}
+
+/// Split annotations into request-sized batches. A check without annotations
+/// still gets one (empty) batch so it is created.
+pub fn annotation_batches(annotations: &[CheckAnnotation]) -> Vec<&[CheckAnnotation]> {
+    if annotations.is_empty() {
+        return vec![&[]];
+    }
+    annotations.chunks(MAX_ANNOTATIONS_PER_REQUEST).collect()
+}
+
+fn truncate_summary(summary: &str) -> String {
+    match summary.char_indices().nth(MAX_SUMMARY_CHARS) {
+        Some((end, _)) => summary[..end].to_string(),
+        None => summary.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn finding(id: &str, file: &str, line: u32, severity: Severity) -> ReviewFinding {
+        ReviewFinding {
+            id: id.to_string(),
+            file: file.to_string(),
+            line,
+            severity,
+            description: format!("problem {id}"),
+            category: None,
+            depends_on: vec![],
+            extra: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_review_check_conclusion_follows_severity() {
+        assert_eq!(
+            review_check(&[], "clean").conclusion,
+            CheckConclusion::Success
+        );
+        let warnings = [finding("a", "src/a.rs", 3, Severity::Warning)];
+        assert_eq!(
+            review_check(&warnings, "").conclusion,
+            CheckConclusion::Neutral
+        );
+        let critical = [
+            finding("a", "src/a.rs", 3, Severity::Warning),
+            finding("b", "src/b.rs", 9, Severity::Critical),
+        ];
+        let check = review_check(&critical, "");
+        assert_eq!(check.conclusion, CheckConclusion::Failure);
+        assert_eq!(check.title, "2 findings");
+    }
+
+    #[test]
+    fn test_review_check_annotates_findings_with_a_file() {
+        let findings = [
+            finding("a", "src/a.rs", 0, Severity::Info),
+            finding("b", "", 4, Severity::Warning),
+        ];
+        let check = review_check(&findings, "summary");
+        assert_eq!(
+            check.annotations,
+            vec![CheckAnnotation {
+                path: "src/a.rs".to_string(),
+                start_line: 1,
+                end_line: 1,
+                annotation_level: AnnotationLevel::Notice,
+                title: format!("{} a", Severity::Info.label()),
+                message: "problem a".to_string(),
+            }]
+        );
+        assert_eq!(check.summary, "summary");
+    }
+
+    #[test]
+    fn test_annotation_batches() {
+        assert_eq!(annotation_batches(&[]), vec![&[] as &[CheckAnnotation]]);
+
+        let findings: Vec<ReviewFinding> = (0..120)
+            .map(|i| finding(&i.to_string(), "src/a.rs", i + 1, Severity::Warning))
+            .collect();
+        let check = review_check(&findings, "");
+        let sizes: Vec<usize> = annotation_batches(&check.annotations)
+            .iter()
+            .map(|b| b.len())
+            .collect();
+        assert_eq!(sizes, vec![50, 50, 20]);
+    }
+}
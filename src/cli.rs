@@ -1,5 +1,8 @@
 use clap::{Parser, Subcommand};
 
+use crate::prompt_vars::PromptPhase;
+use crate::self_update::Channel;
+
 /// rlph — autonomous AI development loop
 #[derive(Parser, Debug, Clone)]
 #[command(name = "rlph", version, about)]
@@ -27,11 +30,11 @@ pub struct Cli {
     #[arg(long)]
     pub runner: Option<String>,
 
-    /// Task source to use (github, linear)
+    /// Task source to use (github, linear, bitbucket, trello, gitea)
     #[arg(long, global = true)]
     pub source: Option<String>,
 
-    /// Submission backend to use (github, graphite)
+    /// Submission backend to use (github, graphite, bitbucket, gitea)
     #[arg(long)]
     pub submission: Option<String>,
 
@@ -79,6 +82,10 @@ pub struct Cli {
     #[arg(long)]
     pub agent_variant: Option<String>,
 
+    /// Agent permissions: full, workspace-write, read-only
+    #[arg(long)]
+    pub permission_mode: Option<String>,
+
     /// Maximum review rounds per task
     #[arg(long)]
     pub max_review_rounds: Option<u32>,
@@ -86,6 +93,44 @@ pub struct Cli {
     /// Maximum retries when agent times out (session resume)
     #[arg(long)]
     pub agent_timeout_retries: Option<u32>,
+
+    /// Address new collaborator comments on rlph's open PRs before each iteration
+    #[arg(long)]
+    pub pr_feedback: bool,
+
+    /// Refetch every closed task for dependency checks instead of updating the cache
+    #[arg(long)]
+    pub refresh_deps: bool,
+
+    /// Work on tasks even if a PR that delivered them is still open or merged
+    #[arg(long, global = true)]
+    pub redo: bool,
+
+    /// Submit the PR without running the review loop
+    #[arg(long, global = true, conflicts_with = "review_phases")]
+    pub skip_review: bool,
+
+    /// Run only these review phases (comma-separated names, e.g. correctness,security)
+    #[arg(long, global = true, value_delimiter = ',')]
+    pub review_phases: Option<Vec<String>>,
+
+    /// Run the full pipeline once for this task (e.g. 42 or gh-42), skipping
+    /// task selection
+    #[arg(long, conflicts_with_all = ["continuous", "max_iterations"])]
+    pub task: Option<String>,
+
+    /// With --task, run the task even if it lacks the label or is not eligible
+    /// in the source
+    #[arg(long, requires = "task")]
+    pub force: bool,
+
+    /// Print no progress messages and stream no agent output
+    #[arg(long, short, global = true)]
+    pub quiet: bool,
+
+    /// Print a single JSON result line on stdout when done
+    #[arg(long, global = true)]
+    pub porcelain: bool,
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -93,10 +138,52 @@ pub enum CliCommand {
     /// Initialize the project for the configured task source (e.g., create labels)
     Init,
 
+    /// Interactive first-run setup: pick a source, runner and label, check
+    /// credentials, write .rlph/config.toml and create labels
+    Setup,
+
     /// Run review phases directly for an existing GitHub PR
     Review {
         /// GitHub pull request number or URL
         pr_ref: String,
+
+        /// Allow review agents to modify the worktree (for autofix review setups)
+        #[arg(long)]
+        allow_writes: bool,
+
+        /// Print the review phases that would run, with their runner, model,
+        /// effort, timeout, and prompt and the config level each came from,
+        /// without running anything
+        #[arg(long)]
+        explain: bool,
+    },
+
+    /// Bring a human-authored GitHub PR into the review loop, fixes included,
+    /// and keep reviewing it as new commits are pushed
+    AdoptPr {
+        /// GitHub pull request number or URL
+        pr_ref: String,
+    },
+
+    /// Run a single iteration, optionally for one task and with an interactive
+    /// implement session
+    Run {
+        /// Task to run (e.g. 42 or gh-42), skipping selection
+        #[arg(value_name = "TASK", conflicts_with = "task")]
+        task_arg: Option<String>,
+
+        /// Same as the positional TASK
+        #[arg(long)]
+        task: Option<String>,
+
+        /// Run the task even if it lacks the label or is not eligible in the source
+        #[arg(long)]
+        force: bool,
+
+        /// Open the implement phase as an agent session in this terminal, then
+        /// continue with push, PR, and review once it exits
+        #[arg(long)]
+        interactive: bool,
     },
 
     /// Fix review findings for an existing GitHub PR
@@ -118,7 +205,7 @@ pub enum CliCommand {
         #[arg(long)]
         runner: Option<String>,
 
-        /// Task source to use (github, linear)
+        /// Task source to use (github, linear, bitbucket, trello, gitea)
         #[arg(long)]
         source: Option<String>,
 
@@ -134,6 +221,104 @@ pub enum CliCommand {
         #[arg(long)]
         agent_model: Option<String>,
     },
+
+    /// Resume an interrupted review from its last saved round
+    Resume,
+
+    /// Serve an HTTP API so an external scheduler can queue tasks and reviews
+    Api {
+        /// Port to listen on
+        #[arg(long)]
+        port: u16,
+
+        /// Address to bind; keep it local unless RLPH_API_TOKEN is set
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+    },
+
+    /// Stop the loop from picking up new tasks by creating .rlph/PAUSE
+    Pause {
+        /// Note recorded in the pause file and logged by the loop
+        #[arg(long)]
+        reason: Option<String>,
+    },
+
+    /// Remove .rlph/PAUSE so the loop picks up tasks again
+    Unpause,
+
+    /// Browse the per-iteration run summaries in .rlph/runs/
+    Runs {
+        #[command(subcommand)]
+        action: RunsAction,
+    },
+
+    /// Export the context rlph would give the implement agent for a task
+    /// (prompt, task, dependencies, conventions, diff) into a directory
+    Context {
+        /// Task to export (e.g. 42 or gh-42)
+        task: String,
+
+        /// Output directory (default: .rlph/context/<task>)
+        #[arg(long)]
+        out: Option<String>,
+    },
+
+    /// Inspect the variables available to prompt templates
+    Prompts {
+        #[command(subcommand)]
+        action: PromptsAction,
+    },
+
+    /// Replace this binary with the newest GitHub release on a channel
+    SelfUpdate {
+        /// Release channel (stable, nightly)
+        #[arg(long, default_value = "stable")]
+        channel: Channel,
+
+        /// Only report whether an update is available
+        #[arg(long)]
+        check: bool,
+
+        /// Reinstall even if the release is not newer than this binary
+        #[arg(long)]
+        force: bool,
+
+        /// Verify the checksum only, skipping `gh attestation verify`
+        #[arg(long)]
+        skip_attestation: bool,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum RunsAction {
+    /// List recorded runs, oldest first
+    List {
+        /// Show only the most recent N runs
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+
+    /// Print one run summary as JSON
+    Show {
+        /// Run id (file name without .json) or task id for its latest run
+        id: String,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum PromptsAction {
+    /// List the variables a phase's prompt receives, with descriptions
+    Vars {
+        /// Phase (choose, implement, review, review-aggregate, review-fix, fix, estimate, translate, prd)
+        phase: PromptPhase,
+    },
+    /// Show how the built-in prompts changed between two prompt versions
+    Diff {
+        /// Older prompt version (e.g. v1)
+        from: String,
+        /// Newer prompt version
+        to: String,
+    },
 }
 
 #[cfg(test)]
@@ -203,11 +388,81 @@ mod tests {
         assert_eq!(cli.label.as_deref(), Some("auto"));
     }
 
+    #[test]
+    fn test_parse_run_interactive_task() {
+        let cli = Cli::parse_from(["rlph", "run", "--task", "42", "--interactive"]);
+        match cli.command {
+            Some(CliCommand::Run {
+                task,
+                interactive,
+                force,
+                ..
+            }) => {
+                assert_eq!(task.as_deref(), Some("42"));
+                assert!(interactive);
+                assert!(!force);
+            }
+            other => panic!("expected Run command, got {other:?}"),
+        }
+
+        let cli = Cli::parse_from(["rlph", "run", "gh-7", "--force"]);
+        match cli.command {
+            Some(CliCommand::Run {
+                task_arg, force, ..
+            }) => {
+                assert_eq!(task_arg.as_deref(), Some("gh-7"));
+                assert!(force);
+            }
+            other => panic!("expected Run command, got {other:?}"),
+        }
+
+        let cli = Cli::parse_from(["rlph", "run"]);
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Run {
+                task_arg: None,
+                task: None,
+                interactive: false,
+                force: false,
+            })
+        ));
+        assert!(Cli::try_parse_from(["rlph", "run", "1", "--task", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_review_phase_flags() {
+        let cli = Cli::parse_from(["rlph", "--once", "--review-phases", "correctness,security"]);
+        assert_eq!(
+            cli.review_phases,
+            Some(vec!["correctness".to_string(), "security".to_string()])
+        );
+        assert!(!cli.skip_review);
+
+        let cli = Cli::parse_from(["rlph", "run", "42", "--skip-review", "--redo"]);
+        assert!(cli.skip_review);
+        assert!(cli.redo);
+
+        assert!(
+            Cli::try_parse_from(["rlph", "--skip-review", "--review-phases", "security"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_top_level_task() {
+        let cli = Cli::parse_from(["rlph", "--task", "123", "--force"]);
+        assert!(cli.command.is_none());
+        assert_eq!(cli.task.as_deref(), Some("123"));
+        assert!(cli.force);
+
+        assert!(Cli::try_parse_from(["rlph", "--task", "1", "--continuous"]).is_err());
+        assert!(Cli::try_parse_from(["rlph", "--force", "--once"]).is_err());
+    }
+
     #[test]
     fn test_parse_review() {
         let cli = Cli::parse_from(["rlph", "review", "123"]);
         match cli.command {
-            Some(CliCommand::Review { pr_ref }) => assert_eq!(pr_ref, "123"),
+            Some(CliCommand::Review { pr_ref, .. }) => assert_eq!(pr_ref, "123"),
             _ => panic!("expected Review subcommand"),
         }
     }
@@ -216,26 +471,66 @@ mod tests {
     fn test_parse_review_url() {
         let cli = Cli::parse_from(["rlph", "review", "https://github.com/owner/repo/pull/456"]);
         match cli.command {
-            Some(CliCommand::Review { pr_ref }) => {
+            Some(CliCommand::Review { pr_ref, .. }) => {
                 assert_eq!(pr_ref, "https://github.com/owner/repo/pull/456");
             }
             _ => panic!("expected Review subcommand"),
         }
     }
 
+    #[test]
+    fn test_parse_review_allow_writes() {
+        let cli = Cli::parse_from(["rlph", "review", "12"]);
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Review {
+                allow_writes: false,
+                ..
+            })
+        ));
+        let cli = Cli::parse_from(["rlph", "review", "12", "--allow-writes"]);
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Review {
+                allow_writes: true,
+                ..
+            })
+        ));
+        let cli = Cli::parse_from(["rlph", "review", "12", "--explain"]);
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Review {
+                explain: true,
+                allow_writes: false,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn test_parse_review_with_global_args_after_subcommand() {
         let cli = Cli::parse_from([
             "rlph", "review", "77", "--source", "github", "--label", "rlph",
         ]);
         match cli.command {
-            Some(CliCommand::Review { pr_ref }) => assert_eq!(pr_ref, "77"),
+            Some(CliCommand::Review { pr_ref, .. }) => assert_eq!(pr_ref, "77"),
             _ => panic!("expected Review subcommand"),
         }
         assert_eq!(cli.source.as_deref(), Some("github"));
         assert_eq!(cli.label.as_deref(), Some("rlph"));
     }
 
+    #[test]
+    fn test_parse_adopt_pr() {
+        let cli = Cli::parse_from(["rlph", "adopt-pr", "https://github.com/o/r/pull/31"]);
+        match cli.command {
+            Some(CliCommand::AdoptPr { pr_ref }) => {
+                assert_eq!(pr_ref, "https://github.com/o/r/pull/31");
+            }
+            _ => panic!("expected AdoptPr subcommand"),
+        }
+    }
+
     #[test]
     fn test_parse_prd_no_description() {
         let cli = Cli::parse_from(["rlph", "prd"]);
@@ -317,6 +612,121 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_runs_list_and_show() {
+        let cli = Cli::parse_from(["rlph", "runs", "list", "--limit", "5"]);
+        match cli.command {
+            Some(CliCommand::Runs {
+                action: RunsAction::List { limit },
+            }) => assert_eq!(limit, Some(5)),
+            _ => panic!("expected Runs List subcommand"),
+        }
+
+        let cli = Cli::parse_from(["rlph", "runs", "show", "gh-42"]);
+        match cli.command {
+            Some(CliCommand::Runs {
+                action: RunsAction::Show { id },
+            }) => assert_eq!(id, "gh-42"),
+            _ => panic!("expected Runs Show subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_parse_pause_unpause() {
+        let cli = Cli::parse_from(["rlph", "pause", "--reason", "incident"]);
+        match cli.command {
+            Some(CliCommand::Pause { reason }) => assert_eq!(reason.as_deref(), Some("incident")),
+            other => panic!("expected Pause command, got {other:?}"),
+        }
+        let cli = Cli::parse_from(["rlph", "unpause"]);
+        assert!(matches!(cli.command, Some(CliCommand::Unpause)));
+    }
+
+    #[test]
+    fn test_parse_context() {
+        let cli = Cli::parse_from(["rlph", "context", "42", "--out", "/tmp/ctx"]);
+        match cli.command {
+            Some(CliCommand::Context { task, out }) => {
+                assert_eq!(task, "42");
+                assert_eq!(out.as_deref(), Some("/tmp/ctx"));
+            }
+            other => panic!("expected Context command, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["rlph", "context"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_prompts_vars() {
+        let cli = Cli::parse_from(["rlph", "prompts", "vars", "review-fix"]);
+        match cli.command {
+            Some(CliCommand::Prompts {
+                action: PromptsAction::Vars { phase },
+            }) => assert_eq!(phase, PromptPhase::ReviewFix),
+            _ => panic!("expected Prompts Vars subcommand"),
+        }
+        assert!(Cli::try_parse_from(["rlph", "prompts", "vars", "deploy"]).is_err());
+
+        let cli = Cli::parse_from(["rlph", "prompts", "diff", "v3", "v4"]);
+        match cli.command {
+            Some(CliCommand::Prompts {
+                action: PromptsAction::Diff { from, to },
+            }) => assert_eq!((from.as_str(), to.as_str()), ("v3", "v4")),
+            _ => panic!("expected Prompts Diff subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_parse_api() {
+        let cli = Cli::parse_from(["rlph", "api", "--port", "8080"]);
+        match cli.command {
+            Some(CliCommand::Api { port, host }) => {
+                assert_eq!(port, 8080);
+                assert_eq!(host, "127.0.0.1");
+            }
+            _ => panic!("expected Api subcommand"),
+        }
+        assert!(Cli::try_parse_from(["rlph", "api"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_quiet_and_porcelain() {
+        let cli = Cli::parse_from(["rlph", "--once", "-q", "--porcelain"]);
+        assert!(cli.quiet);
+        assert!(cli.porcelain);
+
+        let cli = Cli::parse_from(["rlph", "review", "42", "--quiet"]);
+        assert!(cli.quiet);
+        assert!(!cli.porcelain);
+    }
+
+    #[test]
+    fn test_parse_self_update() {
+        let cli = Cli::parse_from(["rlph", "self-update"]);
+        match cli.command {
+            Some(CliCommand::SelfUpdate {
+                channel,
+                check,
+                force,
+                skip_attestation,
+            }) => {
+                assert_eq!(channel, Channel::Stable);
+                assert!(!check && !force && !skip_attestation);
+            }
+            _ => panic!("expected SelfUpdate subcommand"),
+        }
+
+        let cli = Cli::parse_from(["rlph", "self-update", "--channel", "nightly", "--check"]);
+        match cli.command {
+            Some(CliCommand::SelfUpdate { channel, check, .. }) => {
+                assert_eq!(channel, Channel::Nightly);
+                assert!(check);
+            }
+            _ => panic!("expected SelfUpdate subcommand"),
+        }
+
+        assert!(Cli::try_parse_from(["rlph", "self-update", "--channel", "beta"]).is_err());
+    }
+
     #[test]
     fn test_bare_rlph_once_still_works() {
         let cli = Cli::parse_from(["rlph", "--once"]);
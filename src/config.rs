@@ -1,10 +1,30 @@
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 
+use crate::analyzer::AnalyzerFormat;
+use crate::base_branch::FALLBACK_BASE_BRANCH;
 use crate::cli::Cli;
+use crate::coverage::DEFAULT_COVERAGE_THRESHOLD;
 use crate::error::{Error, Result};
-use crate::runner::RunnerKind;
+use crate::gh_quota::{self, DEFAULT_GITHUB_RPS};
+use crate::preprocess::PreprocessConfig;
+use crate::prompt_vars::{check_pr_template, pr_vars};
+use crate::prompts::{
+    PROMPT_VERSION, PROMPT_VERSIONS, is_prompt_version, render_template, validate_template,
+};
+use crate::review_presets::{DEFAULT_REVIEW_PRESET, review_preset};
+use crate::review_schema::{CORE_FINDING_FIELDS, Complexity, ExportFormat};
+use crate::runner::{AgentIdentity, PermissionMode, RunnerKind};
+use crate::sources::Task;
+use crate::stream::StreamMode;
+use crate::submission::{
+    DEFAULT_PR_BODY_TEMPLATE, DEFAULT_PR_TITLE_TEMPLATE, pr_body_references_issue,
+};
+use crate::worktree::{
+    DEFAULT_BRANCH_TEMPLATE, GitIdentity, render_branch_name, validate_branch_name,
+};
 
 #[derive(Debug, Clone, Deserialize, Default, PartialEq)]
 #[serde(deny_unknown_fields)]
@@ -12,6 +32,7 @@ pub struct LinearConfigFile {
     pub team: Option<String>,
     pub project: Option<String>,
     pub api_key_env: Option<String>,
+    pub todo_state: Option<String>,
     pub in_progress_state: Option<String>,
     pub in_review_state: Option<String>,
     pub done_state: Option<String>,
@@ -22,34 +43,676 @@ pub struct LinearConfig {
     pub team: String,
     pub project: Option<String>,
     pub api_key_env: String,
+    /// State a task returns to when a failed iteration releases it.
+    pub todo_state: String,
     pub in_progress_state: String,
     pub in_review_state: String,
     pub done_state: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BitbucketConfigFile {
+    pub workspace: Option<String>,
+    pub repo_slug: Option<String>,
+    pub username_env: Option<String>,
+    pub token_env: Option<String>,
+    pub api_url: Option<String>,
+}
+
+/// Bitbucket Cloud repository used by `source = "bitbucket"` and
+/// `submission = "bitbucket"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitbucketConfig {
+    pub workspace: String,
+    pub repo_slug: String,
+    /// Env var holding the account username. When unset or empty the token is
+    /// sent as a bearer token instead of with basic auth.
+    pub username_env: String,
+    pub token_env: String,
+    pub api_url: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GiteaConfigFile {
+    pub base_url: Option<String>,
+    pub owner: Option<String>,
+    pub repo: Option<String>,
+    pub token_env: Option<String>,
+}
+
+/// Gitea or Forgejo repository used by `source = "gitea"` and
+/// `submission = "gitea"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GiteaConfig {
+    /// Root URL of the instance, e.g. `https://codeberg.org`.
+    pub base_url: String,
+    pub owner: String,
+    pub repo: String,
+    pub token_env: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TrelloConfigFile {
+    pub board_id: Option<String>,
+    pub api_key_env: Option<String>,
+    pub token_env: Option<String>,
+    pub todo_list: Option<String>,
+    pub in_progress_list: Option<String>,
+    pub in_review_list: Option<String>,
+    pub done_list: Option<String>,
+}
+
+/// Trello board used by `source = "trello"`. Each workflow state is a list,
+/// named as it appears on the board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrelloConfig {
+    pub board_id: String,
+    pub api_key_env: String,
+    pub token_env: String,
+    /// List a task returns to when a failed iteration releases it.
+    pub todo_list: String,
+    pub in_progress_list: String,
+    pub in_review_list: String,
+    pub done_list: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GitHubConfigFile {
+    pub project: Option<GitHubProjectConfigFile>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GitHubProjectConfigFile {
+    pub owner: Option<String>,
+    pub number: Option<u64>,
+    pub status_field: Option<String>,
+    pub ready_column: Option<String>,
+    pub in_progress_column: Option<String>,
+    pub in_review_column: Option<String>,
+    pub done_column: Option<String>,
+}
+
+/// GitHub Projects (v2) board that replaces the label as the GitHub source's
+/// view of eligible tasks. Each workflow state is an option of the board's
+/// single-select status field, named as it appears on the board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitHubProjectConfig {
+    /// User or organization login owning the project.
+    pub owner: String,
+    /// Project number, as in `github.com/orgs/<owner>/projects/<number>`.
+    pub number: u64,
+    pub status_field: String,
+    /// Column eligible tasks are picked from, and that a failed iteration
+    /// releases a task back to.
+    pub ready_column: String,
+    pub in_progress_column: String,
+    pub in_review_column: String,
+    pub done_column: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PriorityPolicyFile {
+    pub stale_after_days: Option<u64>,
+    pub stale_boost: Option<u8>,
+    pub urgent_labels: Option<Vec<String>>,
+}
+
+/// Adjusts task priorities for age and SLA labels before a task is chosen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriorityPolicy {
+    /// Tasks created at least this many days ago are boosted; `None` disables it.
+    pub stale_after_days: Option<u64>,
+    /// Priority levels a stale task moves up (unprioritized tasks start at p9).
+    pub stale_boost: u8,
+    /// Labels (case-insensitive) that raise a task to p1.
+    pub urgent_labels: Vec<String>,
+}
+
+impl Default for PriorityPolicy {
+    fn default() -> Self {
+        Self {
+            stale_after_days: None,
+            stale_boost: 1,
+            urgent_labels: vec!["sla-urgent".to_string()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct HooksConfigFile {
+    pub on_task_selected: Option<String>,
+    pub on_pr_created: Option<String>,
+    pub on_review_failed: Option<String>,
+    pub on_iteration_complete: Option<String>,
+    pub on_queue_changed: Option<String>,
+    pub on_run_digest: Option<String>,
+    pub timeout_seconds: Option<u64>,
+}
+
+/// Shell commands run on orchestrator lifecycle events, each receiving a JSON
+/// payload on stdin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HooksConfig {
+    pub on_task_selected: Option<String>,
+    pub on_pr_created: Option<String>,
+    pub on_review_failed: Option<String>,
+    pub on_iteration_complete: Option<String>,
+    /// Run in continuous mode when the eligible tasks change between polls.
+    pub on_queue_changed: Option<String>,
+    /// Run with the run digest when a multi-iteration loop exits.
+    pub on_run_digest: Option<String>,
+    /// A hook still running after this long is killed.
+    pub timeout_seconds: u64,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            on_task_selected: None,
+            on_pr_created: None,
+            on_review_failed: None,
+            on_iteration_complete: None,
+            on_queue_changed: None,
+            on_run_digest: None,
+            timeout_seconds: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CleanupConfigFile {
+    pub enabled: Option<bool>,
+    pub delete_remote_branches: Option<bool>,
+    pub requeue_closed: Option<bool>,
+}
+
+/// What happens to rlph's PRs once they are merged or closed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CleanupConfig {
+    /// Check tracked PRs at the start of each iteration.
+    pub enabled: bool,
+    /// Delete the PR's branch from `push_remote`.
+    pub delete_remote_branches: bool,
+    /// Release the task back to the eligible queue when its PR is closed unmerged.
+    pub requeue_closed: bool,
+}
+
+impl Default for CleanupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            delete_remote_branches: true,
+            requeue_closed: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PreprocessConfigFile {
+    pub steps: Option<Vec<String>>,
+    pub max_snippet_lines: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FixTrailersConfigFile {
+    pub enabled: Option<bool>,
+    pub agent_email: Option<String>,
+    pub human_email: Option<String>,
+}
+
+/// `Co-authored-by:` trailers added to commits made in review-fix rounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixTrailersConfig {
+    pub enabled: bool,
+    /// Email credited for each review agent whose findings a round fixed.
+    pub agent_email: String,
+    /// Email credited for a person whose PR feedback a round addressed;
+    /// `{login}` is replaced with their login.
+    pub human_email: String,
+}
+
+impl Default for FixTrailersConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            agent_email: "rlph@users.noreply.github.com".to_string(),
+            human_email: "{login}@users.noreply.github.com".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SquashConfigFile {
+    pub enabled: Option<bool>,
+    pub mode: Option<String>,
+    pub auto_merge: Option<bool>,
+    pub merge_method: Option<String>,
+    pub ci_timeout_secs: Option<u64>,
+}
+
+/// What an approved branch's commits are squashed into.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SquashMode {
+    /// Fold the fix-round commits into the last commit made before review,
+    /// keeping the implementation's own commits.
+    #[default]
+    Logical,
+    /// Squash the whole branch into one commit.
+    Single,
+}
+
+impl std::str::FromStr for SquashMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "logical" => Ok(SquashMode::Logical),
+            "single" => Ok(SquashMode::Single),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown squash.mode: {other} (expected: logical, single)"
+            ))),
+        }
+    }
+}
+
+/// How an auto-merged PR lands on the base branch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeMethod {
+    /// Rebase the branch's commits onto the base, keeping them as squashed.
+    #[default]
+    Rebase,
+    Squash,
+    Merge,
+}
+
+impl std::str::FromStr for MergeMethod {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "rebase" => Ok(MergeMethod::Rebase),
+            "squash" => Ok(MergeMethod::Squash),
+            "merge" => Ok(MergeMethod::Merge),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown squash.merge_method: {other} (expected: rebase, squash, merge)"
+            ))),
+        }
+    }
+}
+
+/// Squashing of fix-round commits once a PR's review is approved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SquashConfig {
+    pub enabled: bool,
+    pub mode: SquashMode,
+    /// Enable auto-merge once CI passes on the squashed branch.
+    pub auto_merge: bool,
+    pub merge_method: MergeMethod,
+    /// How long to wait for CI on the squashed branch before giving up on
+    /// auto-merge.
+    pub ci_timeout_secs: u64,
+}
+
+impl Default for SquashConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: SquashMode::default(),
+            auto_merge: true,
+            merge_method: MergeMethod::default(),
+            ci_timeout_secs: 1800,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ImplementSlicesConfigFile {
+    pub slice_minutes: Option<u64>,
+    pub max_slices: Option<u32>,
+}
+
+/// Time-boxing of the implement phase. After each slice the agent is stopped
+/// and made to commit its work in progress; a task unfinished after the last
+/// slice becomes a partial PR plus a follow-up issue.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImplementSlicesConfig {
+    pub slice_minutes: u64,
+    pub max_slices: u32,
+}
+
+impl Default for ImplementSlicesConfig {
+    fn default() -> Self {
+        Self {
+            slice_minutes: 30,
+            max_slices: 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ToolchainConfigFile {
+    pub build: Option<String>,
+    pub test: Option<String>,
+    pub lint: Option<String>,
+    pub coverage: Option<String>,
+}
+
+/// Project commands that override the ones guessed from the worktree's marker files
+/// (`Cargo.toml`, `package.json`, ...). `None` keeps the detected command.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolchainConfig {
+    pub build: Option<String>,
+    pub test: Option<String>,
+    pub lint: Option<String>,
+    /// Default command for coverage review phases that set none.
+    pub coverage: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FindingsExportConfigFile {
+    pub path: Option<String>,
+    pub format: Option<String>,
+}
+
+/// Where each review round's aggregated findings are written for other tools,
+/// such as a GitHub code scanning upload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FindingsExportConfig {
+    /// Output file, relative to the repository root. `{task_id}` and `{round}`
+    /// are replaced per round; without `{round}` each round overwrites the last.
+    pub path: String,
+    pub format: ExportFormat,
+}
+
 #[derive(Debug, Clone, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct ReviewPhaseConfigFile {
     pub name: String,
+    #[serde(default)]
     pub prompt: String,
+    /// `agent` (default), `command`, or `coverage`.
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub command: Option<String>,
+    pub format: Option<String>,
+    /// Minimum changed-line coverage percent for `coverage` phases.
+    pub threshold: Option<f64>,
     pub runner: Option<String>,
     pub agent_binary: Option<String>,
     pub agent_model: Option<String>,
     pub agent_effort: Option<String>,
     pub agent_variant: Option<String>,
+    pub agent_temperature: Option<f64>,
+    pub agent_extra_args: Option<Vec<String>>,
     pub agent_timeout: Option<u64>,
+    pub permission_mode: Option<String>,
+    /// Skip aggregation and go straight to the fix round when this phase reports a
+    /// critical finding.
+    pub fail_fast: Option<bool>,
+    /// Artifacts precomputed into the prompt: `diff`, `full_files`, `blame`.
+    pub context: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ReviewPhaseConfig {
     pub name: String,
     pub prompt: String,
+    pub kind: ReviewPhaseKind,
     pub runner: RunnerKind,
     pub agent_binary: String,
     pub agent_model: Option<String>,
     pub agent_effort: Option<String>,
     pub agent_variant: Option<String>,
+    pub agent_temperature: Option<f64>,
+    pub agent_extra_args: Vec<String>,
+    pub agent_identity: AgentIdentity,
     pub agent_timeout: Option<u64>,
+    pub permission_mode: PermissionMode,
+    pub fail_fast: bool,
+    pub context: Vec<ReviewContext>,
+}
+
+/// An artifact precomputed for a review phase and injected as a prompt variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewContext {
+    /// The branch diff against its base, as `context_diff`.
+    Diff,
+    /// Current contents of every changed file, as `context_full_files`.
+    FullFiles,
+    /// `git blame` of the lines the branch modifies or removes, as `context_blame`.
+    Blame,
+}
+
+impl std::str::FromStr for ReviewContext {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "diff" => Ok(ReviewContext::Diff),
+            "full_files" => Ok(ReviewContext::FullFiles),
+            "blame" => Ok(ReviewContext::Blame),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown review phase context: {other} (expected: diff, full_files, blame)"
+            ))),
+        }
+    }
+}
+
+/// Default upper bound on the adaptive poll interval, in seconds.
+pub const DEFAULT_POLL_MAX_SECONDS: u64 = 600;
+
+/// Default cap on tasks listed in the choose prompt.
+pub const DEFAULT_CHOOSE_MAX_TASKS: usize = 30;
+
+/// Default cap on each task body in the choose prompt, in characters.
+pub const DEFAULT_CHOOSE_BODY_CHARS: usize = 1000;
+
+/// How a review phase produces findings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewPhaseKind {
+    /// An agent run with the phase prompt.
+    Agent,
+    /// A static analyzer command whose JSON output is converted into findings.
+    Command {
+        command: String,
+        format: AnalyzerFormat,
+    },
+    /// A test command with coverage whose changed-line coverage is gated on `threshold`.
+    /// Without a `command`, one is picked from `[toolchain]` or the detected toolchain.
+    Coverage {
+        command: Option<String>,
+        threshold: f64,
+    },
+}
+
+/// What the loop does when an iteration fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnError {
+    /// Return the error and exit, keeping the worktree for inspection.
+    #[default]
+    Stop,
+    /// Log the failure, remove the worktree, release the task, and keep going.
+    Continue,
+}
+
+impl std::str::FromStr for OnError {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "stop" => Ok(OnError::Stop),
+            "continue" => Ok(OnError::Continue),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown on_error: {other} (expected: stop, continue)"
+            ))),
+        }
+    }
+}
+
+/// How many review rounds a task gets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReviewRoundPolicy {
+    /// Run up to `max_review_rounds` rounds until the review is approved.
+    #[default]
+    Fixed,
+    /// Also stop early when a fix round changes nothing or findings stop
+    /// decreasing; `max_review_rounds` stays the upper bound.
+    Adaptive,
+}
+
+impl std::str::FromStr for ReviewRoundPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "fixed" => Ok(ReviewRoundPolicy::Fixed),
+            "adaptive" => Ok(ReviewRoundPolicy::Adaptive),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown review_round_policy: {other} (expected: fixed, adaptive)"
+            ))),
+        }
+    }
+}
+
+/// How a worker claims a task before working on it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClaimStrategy {
+    /// Label (GitHub) or workflow state (Linear) only; fine for a single worker.
+    #[default]
+    Label,
+    /// Also assign the authenticated user, checked before and after the write,
+    /// so several workers can share one backlog.
+    Assignee,
+}
+
+impl std::str::FromStr for ClaimStrategy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "label" => Ok(ClaimStrategy::Label),
+            "assignee" => Ok(ClaimStrategy::Assignee),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown claim_strategy: {other} (expected: label, assignee)"
+            ))),
+        }
+    }
+}
+
+/// How the GitHub source and submission backend talk to GitHub.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GitHubTransport {
+    /// The `gh` CLI when it is on `PATH`, else the REST API.
+    #[default]
+    Auto,
+    /// Always shell out to `gh`.
+    Cli,
+    /// Always call the REST API directly, authenticating with `GITHUB_TOKEN`.
+    Api,
+}
+
+impl std::str::FromStr for GitHubTransport {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "auto" => Ok(GitHubTransport::Auto),
+            "cli" => Ok(GitHubTransport::Cli),
+            "api" => Ok(GitHubTransport::Api),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown github_transport: {other} (expected: auto, cli, api)"
+            ))),
+        }
+    }
+}
+
+/// Where review findings are published.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReviewTarget {
+    /// One marker comment on the PR, updated every round.
+    #[default]
+    PrComment,
+    /// A check run on the PR head with a file/line annotation per finding.
+    CheckRun,
+    /// The marker comment on the source issue instead of the PR.
+    IssueComment,
+}
+
+impl std::str::FromStr for ReviewTarget {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pr-comment" => Ok(ReviewTarget::PrComment),
+            "check-run" => Ok(ReviewTarget::CheckRun),
+            "issue-comment" => Ok(ReviewTarget::IssueComment),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown review_target: {other} (expected: pr-comment, check-run, issue-comment)"
+            ))),
+        }
+    }
+}
+
+/// How the next task is picked when several are eligible.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChooseStrategy {
+    /// Run the choose agent over the eligible tasks.
+    #[default]
+    Agent,
+    /// Pick deterministically: highest priority, then the task that unblocks
+    /// the most downstream work.
+    Ranked,
+}
+
+impl std::str::FromStr for ChooseStrategy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "agent" => Ok(ChooseStrategy::Agent),
+            "ranked" => Ok(ChooseStrategy::Ranked),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown choose_strategy: {other} (expected: agent, ranked)"
+            ))),
+        }
+    }
+}
+
+/// A signal that ranks eligible tasks, in `priority_signal` order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrioritySignal {
+    /// Priority from labels (or the source's own field), after `priority_policy`.
+    Labels,
+    /// 👍 reactions on GitHub, reactions on Linear; more ranks higher.
+    Reactions,
+}
+
+impl std::str::FromStr for PrioritySignal {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "labels" => Ok(PrioritySignal::Labels),
+            "reactions" => Ok(PrioritySignal::Reactions),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown priority_signal: {other} (expected: labels, reactions)"
+            ))),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Default, PartialEq)]
@@ -61,7 +724,10 @@ pub struct ReviewStepConfigFile {
     pub agent_model: Option<String>,
     pub agent_effort: Option<String>,
     pub agent_variant: Option<String>,
+    pub agent_temperature: Option<f64>,
+    pub agent_extra_args: Option<Vec<String>>,
     pub agent_timeout: Option<u64>,
+    pub permission_mode: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -72,7 +738,11 @@ pub struct ReviewStepConfig {
     pub agent_model: Option<String>,
     pub agent_effort: Option<String>,
     pub agent_variant: Option<String>,
+    pub agent_temperature: Option<f64>,
+    pub agent_extra_args: Vec<String>,
+    pub agent_identity: AgentIdentity,
     pub agent_timeout: Option<u64>,
+    pub permission_mode: PermissionMode,
 }
 
 #[derive(Debug, Clone, Deserialize, Default, PartialEq)]
@@ -84,23 +754,107 @@ pub struct ConfigFile {
     pub label: Option<String>,
     #[serde(alias = "poll_interval")]
     pub poll_seconds: Option<u64>,
+    pub poll_max_seconds: Option<u64>,
     pub worktree_dir: Option<String>,
     pub max_iterations: Option<u32>,
     pub dry_run: Option<bool>,
     pub base_branch: Option<String>,
+    /// Label or milestone name → base branch for matching tasks.
+    pub base_branches: Option<BTreeMap<String, String>>,
     pub agent_binary: Option<String>,
     pub agent_model: Option<String>,
     pub agent_timeout: Option<u64>,
     pub implement_timeout: Option<u64>,
     pub agent_effort: Option<String>,
     pub agent_variant: Option<String>,
+    /// Sampling temperature; only codex accepts one.
+    pub agent_temperature: Option<f64>,
+    /// Extra flags appended to the agent command, e.g. `["--flag", "val"]`.
+    pub agent_extra_args: Option<Vec<String>>,
+    /// Codex profile (`codex --profile`) rlph runs codex under.
+    pub codex_profile: Option<String>,
+    /// Settings dir (`CLAUDE_CONFIG_DIR`) rlph runs claude with.
+    pub claude_config_dir: Option<String>,
+    pub permission_mode: Option<String>,
     pub max_review_rounds: Option<u32>,
+    pub review_round_policy: Option<String>,
     pub agent_timeout_retries: Option<u32>,
+    /// Built-in review phases used when `review_phases` is not set.
+    pub review_preset: Option<String>,
     pub review_phases: Option<Vec<ReviewPhaseConfigFile>>,
+    /// Run only these review phases, by name.
+    pub review_phase_filter: Option<Vec<String>>,
+    pub skip_review: Option<bool>,
     pub review_aggregate: Option<ReviewStepConfigFile>,
     pub review_fix: Option<ReviewStepConfigFile>,
     pub fix: Option<ReviewStepConfigFile>,
+    pub pr_feedback: Option<bool>,
+    pub hold_labels: Option<Vec<String>>,
+    pub estimate: Option<ReviewStepConfigFile>,
+    pub max_complexity: Option<String>,
+    pub agent_language: Option<String>,
+    pub translate: Option<ReviewStepConfigFile>,
+    pub followup_issues: Option<bool>,
+    pub followup_label: Option<String>,
+    pub branch_template: Option<String>,
+    pub pr_title_template: Option<String>,
+    pub pr_body_template: Option<String>,
+    pub shared_cache_dirs: Option<Vec<String>>,
+    pub cache_dir: Option<String>,
+    pub conventions_files: Option<Vec<String>>,
+    pub protected_paths: Option<Vec<String>>,
+    pub finding_required_fields: Option<Vec<String>>,
+    pub finding_template: Option<String>,
+    pub prompt_version: Option<String>,
+    pub suggestion_comments: Option<bool>,
+    pub review_lessons: Option<bool>,
+    pub issue_pr_comments: Option<bool>,
+    pub test_plan: Option<bool>,
+    pub differential_review: Option<bool>,
+    pub review_concurrency: Option<usize>,
+    pub untrusted_sources: Option<bool>,
+    pub allow_dangerous_permissions: Option<bool>,
+    pub max_task_attempts: Option<u32>,
+    pub retry_backoff_seconds: Option<u64>,
+    pub closed_tasks_ttl_seconds: Option<u64>,
+    pub abandon_after_days: Option<u64>,
+    pub heartbeat_minutes: Option<u64>,
+    pub max_worktree_disk_mb: Option<u64>,
+    pub digest_issue: Option<String>,
+    pub failed_label: Option<String>,
+    pub on_error: Option<String>,
+    pub claim_strategy: Option<String>,
+    pub choose_strategy: Option<String>,
+    pub choose_max_tasks: Option<usize>,
+    pub choose_body_chars: Option<usize>,
+    pub priority_policy: Option<PriorityPolicyFile>,
+    pub priority_signal: Option<Vec<String>>,
+    pub hooks: Option<HooksConfigFile>,
+    pub cleanup: Option<CleanupConfigFile>,
+    pub preprocess: Option<PreprocessConfigFile>,
+    pub permalink_context: Option<bool>,
+    pub fix_trailers: Option<FixTrailersConfigFile>,
+    pub squash: Option<SquashConfigFile>,
+    pub implement_slices: Option<ImplementSlicesConfigFile>,
+    pub toolchain: Option<ToolchainConfigFile>,
+    pub review_output: Option<String>,
+    pub review_target: Option<String>,
+    pub findings_export: Option<FindingsExportConfigFile>,
+    pub runner_probe: Option<bool>,
+    pub push_remote: Option<String>,
+    pub pr_head_owner: Option<String>,
+    pub github_rps: Option<f64>,
+    pub github_transport: Option<String>,
+    pub git_user_name: Option<String>,
+    pub git_user_email: Option<String>,
+    pub git_signing_key: Option<String>,
+    /// `openpgp` (default), `ssh`, or `x509`.
+    pub git_signing_format: Option<String>,
     pub linear: Option<LinearConfigFile>,
+    pub bitbucket: Option<BitbucketConfigFile>,
+    pub trello: Option<TrelloConfigFile>,
+    pub gitea: Option<GiteaConfigFile>,
+    pub github: Option<GitHubConfigFile>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -109,26 +863,203 @@ pub struct Config {
     pub runner: RunnerKind,
     pub submission: String,
     pub label: String,
+    /// Base (and minimum) poll interval in continuous mode.
     pub poll_seconds: u64,
+    /// Upper bound the poll interval backs off to while idle or rate limited.
+    pub poll_max_seconds: u64,
     pub worktree_dir: String,
     pub base_branch: String,
+    /// `base_branch` was not configured, so it is detected from `origin` at
+    /// startup (see `base_branch::resolve_base_branch`).
+    pub detect_base_branch: bool,
+    /// Per-task overrides of `base_branch`, keyed by label or milestone name.
+    pub base_branches: BTreeMap<String, String>,
     pub max_iterations: Option<u32>,
     pub dry_run: bool,
     pub once: bool,
     pub continuous: bool,
+    /// Print no progress messages and stream no agent output (`--quiet`).
+    pub quiet: bool,
+    /// Run implement as an agent session attached to the terminal instead of
+    /// headless (`rlph run --interactive`).
+    pub interactive: bool,
     pub agent_binary: String,
     pub agent_model: Option<String>,
     pub agent_timeout: Option<u64>,
     pub implement_timeout: Option<u64>,
     pub agent_effort: Option<String>,
     pub agent_variant: Option<String>,
+    /// Sampling temperature for implement and, unless overridden, every other
+    /// phase. Only codex accepts one.
+    pub agent_temperature: Option<f64>,
+    /// Extra flags appended to every agent command, unless a phase sets its own.
+    pub agent_extra_args: Vec<String>,
+    /// Dedicated codex profile / claude settings dir for every agent rlph runs.
+    pub agent_identity: AgentIdentity,
+    /// Agent permissions for implement and any phase without its own `permission_mode`.
+    pub permission_mode: PermissionMode,
     pub max_review_rounds: u32,
+    /// Whether review stops early when rounds stop making progress.
+    pub review_round_policy: ReviewRoundPolicy,
     pub agent_timeout_retries: u32,
     pub review_phases: Vec<ReviewPhaseConfig>,
+    /// Names of the review phases this run executes (`--review-phases`); all of
+    /// them when unset.
+    pub review_phase_filter: Option<Vec<String>>,
+    /// Submit the PR without reviewing it (`--skip-review`).
+    pub skip_review: bool,
     pub review_aggregate: ReviewStepConfig,
     pub review_fix: ReviewStepConfig,
     pub fix: ReviewStepConfig,
+    /// Watch rlph's own open PRs for new collaborator comments and address them.
+    pub pr_feedback: bool,
+    /// Labels that keep a task out of the queue without removing the main label.
+    pub hold_labels: Vec<String>,
+    /// Optional estimation step run before implement. Enabled by an `[estimate]`
+    /// section or by setting `max_complexity`.
+    pub estimate: Option<ReviewStepConfig>,
+    /// Skip tasks estimated above this complexity.
+    pub max_complexity: Option<Complexity>,
+    /// Language agents work in (e.g. `English`). When set, tasks written in
+    /// another language are translated before implement, and review comments are
+    /// written in the task's original language.
+    pub agent_language: Option<String>,
+    /// Translation step run when `agent_language` is set.
+    pub translate: Option<ReviewStepConfig>,
+    /// File non-critical findings left on an approved review as new issues.
+    pub followup_issues: bool,
+    /// Label applied to follow-up issues; also used to find existing ones.
+    pub followup_label: String,
+    /// Template for task branch names (`{{ issue }}`, `{{ slug }}`, `{{ user }}`).
+    pub branch_template: String,
+    /// Template for PR titles, rendered with task variables.
+    pub pr_title_template: String,
+    /// Template for PR bodies, rendered with task variables.
+    pub pr_body_template: String,
+    /// Worktree-relative dirs (e.g. `target`) symlinked to a shared cache.
+    pub shared_cache_dirs: Vec<String>,
+    /// Root of the shared caches; defaults to `<worktree_dir>/.rlph-cache`.
+    pub cache_dir: String,
+    /// Worktree-relative guidance files (e.g. `AGENTS.md`) injected into prompts as
+    /// `repo_conventions`.
+    pub conventions_files: Vec<String>,
+    /// Gitignore-style patterns agents must not change; a branch touching them is
+    /// not pushed until the changes are reverted.
+    pub protected_paths: Vec<String>,
+    /// Extension fields every review finding must carry (e.g. `confidence`).
+    pub finding_required_fields: Vec<String>,
+    /// Template for each finding line in review comments; `None` uses the built-in format.
+    pub finding_template: Option<String>,
+    /// Version of the built-in prompts and findings schema to use, so an rlph
+    /// upgrade does not change agent behavior mid-project. Defaults to the
+    /// newest embedded version.
+    pub prompt_version: String,
+    /// Post findings' `suggested_patch` as inline GitHub suggestions once review
+    /// settles. Off by default.
+    pub suggestion_comments: bool,
+    /// Record fixed review findings in `.rlph/lessons.md` and inject them into
+    /// implement prompts as `past_lessons`.
+    pub review_lessons: bool,
+    /// Comment on the source issue (and attach a link on Linear) when a PR is opened.
+    pub issue_pr_comments: bool,
+    /// Ask the implement agent for a test plan, list it as a checklist in the
+    /// PR body, and have the correctness review check it against the diff.
+    pub test_plan: bool,
+    /// Review rounds after a fix, and re-reviews of a PR pushed to since its last
+    /// review, only review changes since the commit the last review covered; the
+    /// aggregator carries the earlier findings forward.
+    pub differential_review: bool,
+    /// Treat task content as attacker-controlled: agents run without `--dangerously-*` flags.
+    pub untrusted_sources: bool,
+    /// Keep full agent permissions even when `untrusted_sources` is set.
+    pub allow_dangerous_permissions: bool,
+    /// Failed iterations allowed per task before it is labeled with `failed_label`.
+    pub max_task_attempts: u32,
+    /// Delay before the first retry of a failed task; doubles with each further failure.
+    pub retry_backoff_seconds: u64,
+    /// How long cached closed task IDs are updated incrementally before they
+    /// are fetched in full again.
+    pub closed_tasks_ttl_seconds: u64,
+    /// Ignore cached closed task IDs for the first dependency check (`--refresh-deps`).
+    pub refresh_deps: bool,
+    /// Work on tasks whose delivered PR is still open or merged (`--redo`).
+    pub redo: bool,
+    /// Close open rlph PRs left unmerged this many days, or whose issue was closed
+    /// or lost `label`. Disabled when unset.
+    pub abandon_after_days: Option<u64>,
+    /// Keep a "still working" comment on the issue or PR while a phase runs
+    /// longer than this many minutes, refreshed at the same interval. Disabled
+    /// when unset.
+    pub heartbeat_minutes: Option<u64>,
+    /// Stop an agent once its worktree grows past this many megabytes (e.g. a
+    /// runaway build); the iteration fails and is retried later. Disabled when
+    /// unset.
+    pub max_worktree_disk_mb: Option<u64>,
+    /// Task id the digest of a multi-iteration loop is commented on when the
+    /// loop exits. Not posted when unset.
+    pub digest_issue: Option<String>,
+    /// Label added to tasks that exhausted `max_task_attempts`; such tasks are skipped.
+    pub failed_label: String,
+    /// Whether a failed iteration stops the loop or is cleaned up and skipped.
+    pub on_error: OnError,
+    /// How `mark_in_progress` claims a task so concurrent workers do not collide.
+    pub claim_strategy: ClaimStrategy,
+    /// Whether the choose phase runs an agent or picks the top-ranked task.
+    pub choose_strategy: ChooseStrategy,
+    /// Most tasks listed to the choose agent; the highest effective priorities are kept.
+    pub choose_max_tasks: usize,
+    /// Characters of each task body shown to the choose agent; `0` omits bodies.
+    pub choose_body_chars: usize,
+    /// Age and SLA-label boosts applied to task priorities when choosing.
+    pub priority_policy: PriorityPolicy,
+    /// Signals that rank eligible tasks, most significant first.
+    pub priority_signal: Vec<PrioritySignal>,
+    /// External commands run on lifecycle events.
+    pub hooks: HooksConfig,
+    /// Post-merge/close cleanup of rlph's PRs.
+    pub cleanup: CleanupConfig,
+    /// Transformations applied to task bodies before they are put in prompts.
+    pub preprocess: PreprocessConfig,
+    /// Quote the code behind commit-pinned GitHub permalinks in the task body
+    /// as `referenced_code` for the implement and review prompts.
+    pub permalink_context: bool,
+    pub fix_trailers: FixTrailersConfig,
+    pub squash: SquashConfig,
+    /// Split implement into time slices; disabled when unset.
+    pub implement_slices: Option<ImplementSlicesConfig>,
+    /// Configured build/test/lint/coverage commands, taking precedence over detection.
+    pub toolchain: ToolchainConfig,
+    /// Most review phases running at once; `None` runs them all together and `1`
+    /// runs them one at a time in declared order.
+    pub review_concurrency: Option<usize>,
+    /// Whether parallel review phases stream interleaved lines or whole transcripts.
+    pub review_output: StreamMode,
+    /// Where review findings are published: a PR comment, a check run, or the
+    /// source issue.
+    pub review_target: ReviewTarget,
+    /// Write each review round's findings to a file (SARIF or JSON).
+    pub findings_export: Option<FindingsExportConfig>,
+    /// Send the implement runner a one-word prompt before claiming a task, so
+    /// expired auth or a missing model fails the iteration early.
+    pub runner_probe: bool,
+    /// Git remote task branches are pushed to and PR branches fetched from.
+    pub push_remote: String,
+    /// Owner of the fork holding `push_remote`; PRs are opened as `owner:branch`.
+    pub pr_head_owner: Option<String>,
+    /// Sustained `gh` requests per second per host, shared by every GitHub call
+    /// in the process.
+    pub github_rps: f64,
+    /// `gh` CLI or REST API for the GitHub source and submission backend.
+    pub github_transport: GitHubTransport,
+    /// Commit identity and signing key set in task worktrees.
+    pub git_identity: GitIdentity,
     pub linear: Option<LinearConfig>,
+    pub bitbucket: Option<BitbucketConfig>,
+    pub trello: Option<TrelloConfig>,
+    pub gitea: Option<GiteaConfig>,
+    /// `[github.project]`: pick GitHub tasks from a project column instead of
+    /// by label.
+    pub github_project: Option<GitHubProjectConfig>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -137,6 +1068,79 @@ pub struct InitConfig {
     pub label: String,
 }
 
+/// Resolve a review phase's `type`, `command`, and `format` settings.
+fn resolve_phase_kind(p: &ReviewPhaseConfigFile) -> Result<ReviewPhaseKind> {
+    match p.kind.as_deref().unwrap_or("agent") {
+        "agent" => {
+            if p.command.is_some() || p.format.is_some() || p.threshold.is_some() {
+                return Err(Error::ConfigValidation(format!(
+                    "review phase '{}': command, format, and threshold require type = \"command\" or \"coverage\"",
+                    p.name
+                )));
+            }
+            Ok(ReviewPhaseKind::Agent)
+        }
+        "coverage" => {
+            if p.format.is_some() {
+                return Err(Error::ConfigValidation(format!(
+                    "review phase '{}': format is not used by coverage phases",
+                    p.name
+                )));
+            }
+            let threshold = p.threshold.unwrap_or(DEFAULT_COVERAGE_THRESHOLD);
+            if !(0.0..=100.0).contains(&threshold) {
+                return Err(Error::ConfigValidation(format!(
+                    "review phase '{}': threshold must be between 0 and 100",
+                    p.name
+                )));
+            }
+            let command = p
+                .command
+                .as_deref()
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(str::to_string);
+            Ok(ReviewPhaseKind::Coverage { command, threshold })
+        }
+        "command" => {
+            if p.threshold.is_some() {
+                return Err(Error::ConfigValidation(format!(
+                    "review phase '{}': threshold requires type = \"coverage\"",
+                    p.name
+                )));
+            }
+            let command = p
+                .command
+                .as_deref()
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .ok_or_else(|| {
+                    Error::ConfigValidation(format!(
+                        "review phase '{}' has type = \"command\" but no command",
+                        p.name
+                    ))
+                })?;
+            let format = match &p.format {
+                Some(f) => f.parse()?,
+                None => AnalyzerFormat::detect(command).ok_or_else(|| {
+                    Error::ConfigValidation(format!(
+                        "review phase '{}': cannot detect analyzer output format; set format = \"clippy\", \"eslint\", or \"ruff\"",
+                        p.name
+                    ))
+                })?,
+            };
+            Ok(ReviewPhaseKind::Command {
+                command: command.to_string(),
+                format,
+            })
+        }
+        other => Err(Error::ConfigValidation(format!(
+            "review phase '{}': unknown type '{other}' (expected agent, command, or coverage)",
+            p.name
+        ))),
+    }
+}
+
 const DEFAULT_CONFIG_FILE: &str = ".rlph/config.toml";
 
 /// Default review phases for use in tests and when no config is provided.
@@ -145,36 +1149,62 @@ pub fn default_review_phases() -> Vec<ReviewPhaseConfig> {
         ReviewPhaseConfig {
             name: "correctness".to_string(),
             prompt: "correctness-review".to_string(),
+            kind: ReviewPhaseKind::Agent,
             runner: RunnerKind::Claude,
             agent_binary: "claude".to_string(),
             agent_model: None,
             agent_effort: None,
             agent_variant: None,
+            agent_temperature: None,
+            agent_extra_args: Vec::new(),
+            agent_identity: AgentIdentity::default(),
             agent_timeout: None,
+            permission_mode: PermissionMode::Full,
+            fail_fast: false,
+            context: Vec::new(),
         },
         ReviewPhaseConfig {
             name: "security".to_string(),
             prompt: "security-review".to_string(),
+            kind: ReviewPhaseKind::Agent,
             runner: RunnerKind::Claude,
             agent_binary: "claude".to_string(),
             agent_model: None,
             agent_effort: None,
             agent_variant: None,
+            agent_temperature: None,
+            agent_extra_args: Vec::new(),
+            agent_identity: AgentIdentity::default(),
             agent_timeout: None,
+            permission_mode: PermissionMode::Full,
+            fail_fast: false,
+            context: Vec::new(),
         },
         ReviewPhaseConfig {
             name: "hygiene".to_string(),
             prompt: "hygiene-review".to_string(),
+            kind: ReviewPhaseKind::Agent,
             runner: RunnerKind::Claude,
             agent_binary: "claude".to_string(),
             agent_model: None,
             agent_effort: None,
             agent_variant: None,
+            agent_temperature: None,
+            agent_extra_args: Vec::new(),
+            agent_identity: AgentIdentity::default(),
             agent_timeout: None,
+            permission_mode: PermissionMode::Full,
+            fail_fast: false,
+            context: Vec::new(),
         },
     ]
 }
 
+/// Default control labels that put a task on hold.
+pub fn default_hold_labels() -> Vec<String> {
+    vec!["rlph-hold".to_string(), "rlph-skip".to_string()]
+}
+
 /// Default review step config for use in tests.
 pub fn default_review_step(prompt: &str) -> ReviewStepConfig {
     ReviewStepConfig {
@@ -184,7 +1214,11 @@ pub fn default_review_step(prompt: &str) -> ReviewStepConfig {
         agent_model: None,
         agent_effort: None,
         agent_variant: None,
+        agent_temperature: None,
+        agent_extra_args: Vec::new(),
+        agent_identity: AgentIdentity::default(),
         agent_timeout: None,
+        permission_mode: PermissionMode::Full,
     }
 }
 
@@ -193,9 +1227,44 @@ impl Config {
         Self::load_from(cli, Path::new("."))
     }
 
+    /// Load the config and apply `github_rps` to the process-wide `gh` quota.
     pub fn load_from(cli: &Cli, project_dir: &Path) -> Result<Self> {
         let file_config = load_file_config(cli, project_dir)?;
-        merge(file_config, cli)
+        let config = merge(file_config, cli)?;
+        gh_quota::configure(config.github_rps);
+        Ok(config)
+    }
+
+    /// Base branch for `task`: the `base_branches` entry for its first mapped
+    /// label, else for its milestone, else `base_branch`.
+    pub fn base_branch_for(&self, task: &Task) -> &str {
+        task.labels
+            .iter()
+            .chain(task.milestone.as_ref())
+            .find_map(|key| self.base_branches.get(key))
+            .unwrap_or(&self.base_branch)
+    }
+
+    /// Review phases this run executes, in declared order.
+    pub fn active_review_phases(&self) -> impl Iterator<Item = &ReviewPhaseConfig> {
+        self.review_phases.iter().filter(|p| {
+            self.review_phase_filter
+                .as_ref()
+                .is_none_or(|names| names.contains(&p.name))
+        })
+    }
+
+    /// Configured review phases left out by `review_phase_filter`.
+    pub fn skipped_review_phases(&self) -> Vec<&str> {
+        self.review_phases
+            .iter()
+            .filter(|p| {
+                self.review_phase_filter
+                    .as_ref()
+                    .is_some_and(|names| !names.contains(&p.name))
+            })
+            .map(|p| p.name.as_str())
+            .collect()
     }
 }
 
@@ -212,10 +1281,10 @@ pub fn resolve_init_config_from(cli: &Cli, project_dir: &Path) -> Result<InitCon
         .unwrap_or_else(|| "github".to_string());
 
     match source.as_str() {
-        "github" | "linear" => {}
+        "github" | "linear" | "bitbucket" | "trello" | "gitea" => {}
         other => {
             return Err(Error::ConfigValidation(format!(
-                "unknown source: {other} (expected: github, linear)"
+                "unknown source: {other} (expected: github, linear, bitbucket, trello, gitea)"
             )));
         }
     }
@@ -230,7 +1299,9 @@ pub fn resolve_init_config_from(cli: &Cli, project_dir: &Path) -> Result<InitCon
     })
 }
 
-fn load_file_config(cli: &Cli, project_dir: &Path) -> Result<ConfigFile> {
+/// Read the config file `--config` names, or `.rlph/config.toml` under
+/// `project_dir` if it exists.
+pub fn load_file_config(cli: &Cli, project_dir: &Path) -> Result<ConfigFile> {
     match &cli.config {
         Some(explicit_path) => {
             let path = Path::new(explicit_path);
@@ -299,6 +1370,7 @@ pub fn merge(file: ConfigFile, cli: &Cli) -> Result<Config> {
         api_key_env: lc
             .api_key_env
             .unwrap_or_else(|| "LINEAR_API_KEY".to_string()),
+        todo_state: lc.todo_state.unwrap_or_else(|| "Todo".to_string()),
         in_progress_state: lc
             .in_progress_state
             .unwrap_or_else(|| "In Progress".to_string()),
@@ -308,11 +1380,67 @@ pub fn merge(file: ConfigFile, cli: &Cli) -> Result<Config> {
         done_state: lc.done_state.unwrap_or_else(|| "Done".to_string()),
     });
 
+    let bitbucket = file.bitbucket.map(|bc| BitbucketConfig {
+        workspace: bc.workspace.unwrap_or_default(),
+        repo_slug: bc.repo_slug.unwrap_or_default(),
+        username_env: bc
+            .username_env
+            .unwrap_or_else(|| "BITBUCKET_USERNAME".to_string()),
+        token_env: bc
+            .token_env
+            .unwrap_or_else(|| "BITBUCKET_TOKEN".to_string()),
+        api_url: bc
+            .api_url
+            .unwrap_or_else(|| "https://api.bitbucket.org/2.0".to_string()),
+    });
+
+    let trello = file.trello.map(|tc| TrelloConfig {
+        board_id: tc.board_id.unwrap_or_default(),
+        api_key_env: tc
+            .api_key_env
+            .unwrap_or_else(|| "TRELLO_API_KEY".to_string()),
+        token_env: tc.token_env.unwrap_or_else(|| "TRELLO_TOKEN".to_string()),
+        todo_list: tc.todo_list.unwrap_or_else(|| "To Do".to_string()),
+        in_progress_list: tc.in_progress_list.unwrap_or_else(|| "Doing".to_string()),
+        in_review_list: tc.in_review_list.unwrap_or_else(|| "In Review".to_string()),
+        done_list: tc.done_list.unwrap_or_else(|| "Done".to_string()),
+    });
+
+    let gitea = file.gitea.map(|gc| GiteaConfig {
+        base_url: gc.base_url.unwrap_or_default(),
+        owner: gc.owner.unwrap_or_default(),
+        repo: gc.repo.unwrap_or_default(),
+        token_env: gc.token_env.unwrap_or_else(|| "GITEA_TOKEN".to_string()),
+    });
+
+    let github_project = file
+        .github
+        .and_then(|gc| gc.project)
+        .map(|pc| GitHubProjectConfig {
+            owner: pc.owner.unwrap_or_default(),
+            number: pc.number.unwrap_or_default(),
+            status_field: pc.status_field.unwrap_or_else(|| "Status".to_string()),
+            ready_column: pc.ready_column.unwrap_or_else(|| "Todo".to_string()),
+            in_progress_column: pc
+                .in_progress_column
+                .unwrap_or_else(|| "In Progress".to_string()),
+            in_review_column: pc
+                .in_review_column
+                .unwrap_or_else(|| "In Review".to_string()),
+            done_column: pc.done_column.unwrap_or_else(|| "Done".to_string()),
+        });
+
     let global_runner = runner;
     let global_binary_override = cli.agent_binary.clone().or(file.agent_binary.clone());
     let global_model_override = cli.agent_model.clone().or(file.agent_model.clone());
     let global_effort_override = cli.agent_effort.clone().or(file.agent_effort.clone());
     let global_variant_override = cli.agent_variant.clone().or(file.agent_variant.clone());
+    let global_temperature = file.agent_temperature;
+    let global_extra_args = file.agent_extra_args.clone().unwrap_or_default();
+    let global_identity = AgentIdentity {
+        codex_profile: file.codex_profile.clone(),
+        claude_config_dir: file.claude_config_dir.as_deref().map(PathBuf::from),
+    };
 
     let global_binary = global_binary_override
         .clone()
@@ -325,30 +1453,36 @@ pub fn merge(file: ConfigFile, cli: &Cli) -> Result<Config> {
         .or_else(|| default_effort.map(str::to_string));
     let global_variant = global_variant_override.clone();
     let global_timeout = cli.agent_timeout.or(file.agent_timeout).or(Some(600));
+    let untrusted_sources = file.untrusted_sources.unwrap_or(false);
+    let allow_dangerous_permissions = file.allow_dangerous_permissions.unwrap_or(false);
+    // Untrusted sources default to sandboxed agents; validation rejects an explicit
+    // "full" there unless dangerous permissions are allowed.
+    let global_permission_mode = match cli
+        .permission_mode
+        .as_deref()
+        .or(file.permission_mode.as_deref())
+    {
+        Some(mode) => mode.parse()?,
+        None if untrusted_sources && !allow_dangerous_permissions => PermissionMode::WorkspaceWrite,
+        None => PermissionMode::Full,
+    };
     let implement_timeout = cli
         .implement_timeout
         .or(file.implement_timeout)
         .or(Some(1800));
 
+    // Explicit `[[review_phases]]` replace the preset; the preset name is still checked.
+    let preset_phases = review_preset(
+        file.review_preset
+            .as_deref()
+            .unwrap_or(DEFAULT_REVIEW_PRESET),
+    )?;
     let review_phases: Vec<ReviewPhaseConfig> = file
         .review_phases
-        .unwrap_or_else(|| {
-            default_review_phases()
-                .into_iter()
-                .map(|p| ReviewPhaseConfigFile {
-                    name: p.name,
-                    prompt: p.prompt,
-                    runner: None,
-                    agent_binary: None,
-                    agent_model: None,
-                    agent_effort: None,
-                    agent_variant: None,
-                    agent_timeout: None,
-                })
-                .collect()
-        })
+        .unwrap_or(preset_phases)
         .into_iter()
         .map(|p| {
+            let kind = resolve_phase_kind(&p)?;
             let effective_runner: RunnerKind = match p.runner {
                 Some(s) => s.parse()?,
                 None => global_runner,
@@ -359,6 +1493,7 @@ pub fn merge(file: ConfigFile, cli: &Cli) -> Result<Config> {
             Ok(ReviewPhaseConfig {
                 name: p.name,
                 prompt: p.prompt,
+                kind,
                 agent_binary: p
                     .agent_binary
                     .or_else(|| global_binary_override.clone())
@@ -372,7 +1507,25 @@ pub fn merge(file: ConfigFile, cli: &Cli) -> Result<Config> {
                     .or_else(|| global_effort_override.clone())
                     .or_else(|| runner_effort.map(str::to_string)),
                 agent_variant: p.agent_variant.or_else(|| global_variant_override.clone()),
+                agent_temperature: p.agent_temperature.or(global_temperature),
+                agent_extra_args: p
+                    .agent_extra_args
+                    .unwrap_or_else(|| global_extra_args.clone()),
+                agent_identity: global_identity.clone(),
                 agent_timeout: p.agent_timeout.or(global_timeout),
+                permission_mode: p
+                    .permission_mode
+                    .as_deref()
+                    .map(str::parse)
+                    .transpose()?
+                    .unwrap_or(global_permission_mode),
+                fail_fast: p.fail_fast.unwrap_or(false),
+                context: p
+                    .context
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|c| c.parse())
+                    .collect::<Result<_>>()?,
                 runner: effective_runner,
             })
         })
@@ -403,7 +1556,18 @@ pub fn merge(file: ConfigFile, cli: &Cli) -> Result<Config> {
                     .or_else(|| global_effort_override.clone())
                     .or_else(|| runner_effort.map(str::to_string)),
                 agent_variant: s.agent_variant.or_else(|| global_variant_override.clone()),
+                agent_temperature: s.agent_temperature.or(global_temperature),
+                agent_extra_args: s
+                    .agent_extra_args
+                    .unwrap_or_else(|| global_extra_args.clone()),
+                agent_identity: global_identity.clone(),
                 agent_timeout: s.agent_timeout.or(global_timeout),
+                permission_mode: s
+                    .permission_mode
+                    .as_deref()
+                    .map(str::parse)
+                    .transpose()?
+                    .unwrap_or(global_permission_mode),
                 runner: effective_runner,
             })
         };
@@ -411,6 +1575,35 @@ pub fn merge(file: ConfigFile, cli: &Cli) -> Result<Config> {
     let review_aggregate = resolve_step(file.review_aggregate, "review-aggregate")?;
     let review_fix = resolve_step(file.review_fix, "review-fix")?;
     let fix = resolve_step(file.fix, "fix")?;
+    let max_complexity = file
+        .max_complexity
+        .as_deref()
+        .map(str::parse::<Complexity>)
+        .transpose()?;
+    let estimate = if file.estimate.is_some() || max_complexity.is_some() {
+        Some(resolve_step(file.estimate, "estimate")?)
+    } else {
+        None
+    };
+    let translate = if file.agent_language.is_some() {
+        Some(resolve_step(file.translate, "translate")?)
+    } else {
+        None
+    };
+
+    let worktree_dir = cli
+        .worktree_dir
+        .clone()
+        .or(file.worktree_dir)
+        .unwrap_or_else(|| "../rlph-worktrees".to_string());
+    let cache_dir = file
+        .cache_dir
+        .unwrap_or_else(|| format!("{}/.rlph-cache", worktree_dir.trim_end_matches('/')));
+
+    let poll_seconds = cli.poll_seconds.or(file.poll_seconds).unwrap_or(30);
+    let poll_max_seconds = file
+        .poll_max_seconds
+        .unwrap_or(DEFAULT_POLL_MAX_SECONDS.max(poll_seconds));
 
     let config = Config {
         source: cli
@@ -429,20 +1622,21 @@ pub fn merge(file: ConfigFile, cli: &Cli) -> Result<Config> {
             .clone()
             .or(file.label)
             .unwrap_or_else(|| "rlph".to_string()),
-        poll_seconds: cli.poll_seconds.or(file.poll_seconds).unwrap_or(30),
-        worktree_dir: cli
-            .worktree_dir
-            .clone()
-            .or(file.worktree_dir)
-            .unwrap_or_else(|| "../rlph-worktrees".to_string()),
+        poll_seconds,
+        poll_max_seconds,
+        worktree_dir,
+        detect_base_branch: cli.base_branch.is_none() && file.base_branch.is_none(),
         base_branch: cli
             .base_branch
             .clone()
             .or(file.base_branch)
-            .unwrap_or_else(|| "main".to_string()),
+            .unwrap_or_else(|| FALLBACK_BASE_BRANCH.to_string()),
+        base_branches: file.base_branches.unwrap_or_default(),
         max_iterations: cli.max_iterations.or(file.max_iterations),
         dry_run: cli.dry_run || file.dry_run.unwrap_or(false),
         once: cli.once,
+        quiet: cli.quiet,
+        interactive: false,
         continuous: cli.continuous,
         agent_binary: global_binary,
         agent_model: global_model,
@@ -450,30 +1644,274 @@ pub fn merge(file: ConfigFile, cli: &Cli) -> Result<Config> {
         implement_timeout,
         agent_effort: global_effort,
         agent_variant: global_variant,
+        agent_temperature: global_temperature,
+        agent_extra_args: global_extra_args,
+        agent_identity: global_identity,
+        permission_mode: global_permission_mode,
         max_review_rounds: cli
             .max_review_rounds
             .or(file.max_review_rounds)
             .unwrap_or(1),
+        review_round_policy: file
+            .review_round_policy
+            .as_deref()
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or_default(),
         agent_timeout_retries: cli
             .agent_timeout_retries
             .or(file.agent_timeout_retries)
             .unwrap_or(2),
         review_phases,
+        review_phase_filter: cli.review_phases.clone().or(file.review_phase_filter),
+        skip_review: cli.skip_review || file.skip_review.unwrap_or(false),
         review_aggregate,
         review_fix,
         fix,
-        linear,
-    };
-    validate(&config)?;
-    Ok(config)
-}
-
-fn validate_runner_flags(
-    scope: &str,
-    runner: RunnerKind,
-    effort: &Option<String>,
-    variant: &Option<String>,
-) -> Result<()> {
+        pr_feedback: cli.pr_feedback || file.pr_feedback.unwrap_or(false),
+        hold_labels: file.hold_labels.unwrap_or_else(default_hold_labels),
+        estimate,
+        max_complexity,
+        agent_language: file.agent_language,
+        translate,
+        followup_issues: file.followup_issues.unwrap_or(false),
+        followup_label: file
+            .followup_label
+            .unwrap_or_else(|| "rlph-followup".to_string()),
+        branch_template: file
+            .branch_template
+            .unwrap_or_else(|| DEFAULT_BRANCH_TEMPLATE.to_string()),
+        pr_title_template: file
+            .pr_title_template
+            .unwrap_or_else(|| DEFAULT_PR_TITLE_TEMPLATE.to_string()),
+        pr_body_template: file
+            .pr_body_template
+            .unwrap_or_else(|| DEFAULT_PR_BODY_TEMPLATE.to_string()),
+        shared_cache_dirs: file.shared_cache_dirs.unwrap_or_default(),
+        conventions_files: file.conventions_files.unwrap_or_default(),
+        protected_paths: file.protected_paths.unwrap_or_default(),
+        cache_dir,
+        finding_required_fields: file.finding_required_fields.unwrap_or_default(),
+        prompt_version: file
+            .prompt_version
+            .unwrap_or_else(|| PROMPT_VERSION.to_string()),
+        finding_template: file.finding_template,
+        suggestion_comments: file.suggestion_comments.unwrap_or(false),
+        review_lessons: file.review_lessons.unwrap_or(true),
+        issue_pr_comments: file.issue_pr_comments.unwrap_or(false),
+        test_plan: file.test_plan.unwrap_or(false),
+        differential_review: file.differential_review.unwrap_or(false),
+        review_concurrency: file.review_concurrency,
+        untrusted_sources,
+        allow_dangerous_permissions,
+        max_task_attempts: file.max_task_attempts.unwrap_or(3),
+        retry_backoff_seconds: file.retry_backoff_seconds.unwrap_or(300),
+        closed_tasks_ttl_seconds: file.closed_tasks_ttl_seconds.unwrap_or(86_400),
+        refresh_deps: cli.refresh_deps,
+        redo: cli.redo,
+        abandon_after_days: file.abandon_after_days,
+        heartbeat_minutes: file.heartbeat_minutes,
+        max_worktree_disk_mb: file.max_worktree_disk_mb,
+        digest_issue: file.digest_issue.filter(|id| !id.trim().is_empty()),
+        failed_label: file
+            .failed_label
+            .unwrap_or_else(|| "rlph-failed".to_string()),
+        on_error: file
+            .on_error
+            .as_deref()
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or_default(),
+        claim_strategy: file
+            .claim_strategy
+            .as_deref()
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or_default(),
+        choose_strategy: file
+            .choose_strategy
+            .as_deref()
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or_default(),
+        choose_max_tasks: file.choose_max_tasks.unwrap_or(DEFAULT_CHOOSE_MAX_TASKS),
+        choose_body_chars: file.choose_body_chars.unwrap_or(DEFAULT_CHOOSE_BODY_CHARS),
+        priority_policy: file
+            .priority_policy
+            .map(|pp| {
+                let default = PriorityPolicy::default();
+                PriorityPolicy {
+                    stale_after_days: pp.stale_after_days,
+                    stale_boost: pp.stale_boost.unwrap_or(default.stale_boost),
+                    urgent_labels: pp.urgent_labels.unwrap_or(default.urgent_labels),
+                }
+            })
+            .unwrap_or_default(),
+        priority_signal: match file.priority_signal {
+            Some(signals) => signals.iter().map(|s| s.parse()).collect::<Result<_>>()?,
+            None => vec![PrioritySignal::Labels],
+        },
+        hooks: file
+            .hooks
+            .map(|h| HooksConfig {
+                on_task_selected: h.on_task_selected,
+                on_pr_created: h.on_pr_created,
+                on_review_failed: h.on_review_failed,
+                on_iteration_complete: h.on_iteration_complete,
+                on_queue_changed: h.on_queue_changed,
+                on_run_digest: h.on_run_digest,
+                timeout_seconds: h
+                    .timeout_seconds
+                    .unwrap_or(HooksConfig::default().timeout_seconds),
+            })
+            .unwrap_or_default(),
+        cleanup: file
+            .cleanup
+            .map(|c| {
+                let default = CleanupConfig::default();
+                CleanupConfig {
+                    enabled: c.enabled.unwrap_or(default.enabled),
+                    delete_remote_branches: c
+                        .delete_remote_branches
+                        .unwrap_or(default.delete_remote_branches),
+                    requeue_closed: c.requeue_closed.unwrap_or(default.requeue_closed),
+                }
+            })
+            .unwrap_or_default(),
+        preprocess: match file.preprocess {
+            Some(p) => PreprocessConfig {
+                steps: p
+                    .steps
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|s| s.parse())
+                    .collect::<Result<_>>()?,
+                max_snippet_lines: p
+                    .max_snippet_lines
+                    .unwrap_or(PreprocessConfig::default().max_snippet_lines),
+            },
+            None => PreprocessConfig::default(),
+        },
+        permalink_context: file.permalink_context.unwrap_or(true),
+        fix_trailers: file
+            .fix_trailers
+            .map(|t| {
+                let default = FixTrailersConfig::default();
+                FixTrailersConfig {
+                    enabled: t.enabled.unwrap_or(true),
+                    agent_email: t.agent_email.unwrap_or(default.agent_email),
+                    human_email: t.human_email.unwrap_or(default.human_email),
+                }
+            })
+            .unwrap_or_default(),
+        squash: match file.squash {
+            Some(s) => {
+                let default = SquashConfig::default();
+                SquashConfig {
+                    enabled: s.enabled.unwrap_or(true),
+                    mode: s
+                        .mode
+                        .as_deref()
+                        .map(str::parse)
+                        .transpose()?
+                        .unwrap_or_default(),
+                    auto_merge: s.auto_merge.unwrap_or(default.auto_merge),
+                    merge_method: s
+                        .merge_method
+                        .as_deref()
+                        .map(str::parse)
+                        .transpose()?
+                        .unwrap_or_default(),
+                    ci_timeout_secs: s.ci_timeout_secs.unwrap_or(default.ci_timeout_secs),
+                }
+            }
+            None => SquashConfig::default(),
+        },
+        implement_slices: file.implement_slices.map(|s| {
+            let default = ImplementSlicesConfig::default();
+            ImplementSlicesConfig {
+                slice_minutes: s.slice_minutes.unwrap_or(default.slice_minutes),
+                max_slices: s.max_slices.unwrap_or(default.max_slices),
+            }
+        }),
+        toolchain: file
+            .toolchain
+            .map(|t| {
+                let command =
+                    |c: Option<String>| c.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+                ToolchainConfig {
+                    build: command(t.build),
+                    test: command(t.test),
+                    lint: command(t.lint),
+                    coverage: command(t.coverage),
+                }
+            })
+            .unwrap_or_default(),
+        review_output: file
+            .review_output
+            .as_deref()
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or_default(),
+        review_target: file
+            .review_target
+            .as_deref()
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or_default(),
+        findings_export: file
+            .findings_export
+            .map(|e| -> Result<_> {
+                let format: ExportFormat = e
+                    .format
+                    .as_deref()
+                    .map(str::parse)
+                    .transpose()?
+                    .unwrap_or_default();
+                Ok(FindingsExportConfig {
+                    path: e.path.unwrap_or_else(|| {
+                        format!(
+                            ".rlph/findings/{{task_id}}-round-{{round}}.{}",
+                            format.extension()
+                        )
+                    }),
+                    format,
+                })
+            })
+            .transpose()?,
+        runner_probe: file.runner_probe.unwrap_or(true),
+        push_remote: file.push_remote.unwrap_or_else(|| "origin".to_string()),
+        pr_head_owner: file.pr_head_owner,
+        github_rps: file.github_rps.unwrap_or(DEFAULT_GITHUB_RPS),
+        github_transport: file
+            .github_transport
+            .as_deref()
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or_default(),
+        git_identity: GitIdentity {
+            user_name: file.git_user_name,
+            user_email: file.git_user_email,
+            signing_key: file.git_signing_key,
+            signing_format: file.git_signing_format,
+        },
+        linear,
+        bitbucket,
+        trello,
+        gitea,
+        github_project,
+    };
+    validate(&config)?;
+    Ok(config)
+}
+
+fn validate_runner_flags(
+    scope: &str,
+    runner: RunnerKind,
+    effort: &Option<String>,
+    variant: &Option<String>,
+    temperature: Option<f64>,
+) -> Result<()> {
     if runner == RunnerKind::OpenCode && effort.is_some() {
         return Err(Error::ConfigValidation(format!(
             "{scope}: opencode uses agent_variant, not agent_effort"
@@ -484,23 +1922,35 @@ fn validate_runner_flags(
             "{scope}: agent_variant is only supported by opencode"
         )));
     }
+    if let Some(temperature) = temperature {
+        if runner != RunnerKind::Codex {
+            return Err(Error::ConfigValidation(format!(
+                "{scope}: agent_temperature is only supported by codex; pass sampling flags through agent_extra_args instead"
+            )));
+        }
+        if !(0.0..=2.0).contains(&temperature) {
+            return Err(Error::ConfigValidation(format!(
+                "{scope}: agent_temperature must be between 0.0 and 2.0, got {temperature}"
+            )));
+        }
+    }
     Ok(())
 }
 
 fn validate(config: &Config) -> Result<()> {
     match config.source.as_str() {
-        "github" | "linear" => {}
+        "github" | "linear" | "bitbucket" | "trello" | "gitea" => {}
         other => {
             return Err(Error::ConfigValidation(format!(
-                "unknown source: {other} (expected: github, linear)"
+                "unknown source: {other} (expected: github, linear, bitbucket, trello, gitea)"
             )));
         }
     }
     match config.submission.as_str() {
-        "github" | "graphite" => {}
+        "github" | "graphite" | "bitbucket" | "gitea" => {}
         other => {
             return Err(Error::ConfigValidation(format!(
-                "unknown submission: {other} (expected: github, graphite)"
+                "unknown submission: {other} (expected: github, graphite, bitbucket, gitea)"
             )));
         }
     }
@@ -509,6 +1959,7 @@ fn validate(config: &Config) -> Result<()> {
         config.runner,
         &config.agent_effort,
         &config.agent_variant,
+        config.agent_temperature,
     )?;
     for phase in &config.review_phases {
         validate_runner_flags(
@@ -516,6 +1967,7 @@ fn validate(config: &Config) -> Result<()> {
             phase.runner,
             &phase.agent_effort,
             &phase.agent_variant,
+            phase.agent_temperature,
         )?;
     }
     validate_runner_flags(
@@ -523,29 +1975,392 @@ fn validate(config: &Config) -> Result<()> {
         config.review_aggregate.runner,
         &config.review_aggregate.agent_effort,
         &config.review_aggregate.agent_variant,
+        config.review_aggregate.agent_temperature,
     )?;
     validate_runner_flags(
         "review_fix",
         config.review_fix.runner,
         &config.review_fix.agent_effort,
         &config.review_fix.agent_variant,
+        config.review_fix.agent_temperature,
     )?;
     validate_runner_flags(
         "fix",
         config.fix.runner,
         &config.fix.agent_effort,
         &config.fix.agent_variant,
+        config.fix.agent_temperature,
     )?;
+    if let Some(estimate) = &config.estimate {
+        validate_runner_flags(
+            "estimate",
+            estimate.runner,
+            &estimate.agent_effort,
+            &estimate.agent_variant,
+            estimate.agent_temperature,
+        )?;
+    }
+    if let Some(translate) = &config.translate {
+        validate_runner_flags(
+            "translate",
+            translate.runner,
+            &translate.agent_effort,
+            &translate.agent_variant,
+            translate.agent_temperature,
+        )?;
+    }
+    if config.untrusted_sources && !config.allow_dangerous_permissions {
+        let phase_modes = config
+            .review_phases
+            .iter()
+            .map(|p| (format!("review phase '{}'", p.name), p.permission_mode));
+        let step_modes = [
+            ("review_aggregate", Some(&config.review_aggregate)),
+            ("review_fix", Some(&config.review_fix)),
+            ("fix", Some(&config.fix)),
+            ("estimate", config.estimate.as_ref()),
+            ("translate", config.translate.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(scope, step)| step.map(|s| (scope.to_string(), s.permission_mode)));
+        let full = std::iter::once(("global".to_string(), config.permission_mode))
+            .chain(phase_modes)
+            .chain(step_modes)
+            .find(|(_, mode)| *mode == PermissionMode::Full);
+        if let Some((scope, _)) = full {
+            return Err(Error::ConfigValidation(format!(
+                "{scope}: permission_mode = \"full\" with untrusted_sources requires allow_dangerous_permissions = true"
+            )));
+        }
+    }
+    let sample = |issue| render_branch_name(&config.branch_template, issue, "example-task", "user");
+    match (sample(1), sample(2)) {
+        (Ok(a), Ok(b)) if a != b => {}
+        (Ok(_), Ok(_)) => {
+            return Err(Error::ConfigValidation(
+                "branch_template must include {{ issue }}".to_string(),
+            ));
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            return Err(Error::ConfigValidation(format!(
+                "invalid branch_template: {e}"
+            )));
+        }
+    }
+    for (name, template) in [
+        ("pr_title_template", &config.pr_title_template),
+        ("pr_body_template", &config.pr_body_template),
+    ] {
+        validate_template(template)
+            .and_then(|()| check_pr_template(template))
+            .map_err(|e| Error::ConfigValidation(format!("invalid {name}: {e}")))?;
+    }
+    // Existing PRs are found (and issues auto-closed) by the `#N` in the body.
+    let sample_vars: HashMap<String, String> = pr_vars()
+        .into_iter()
+        .map(|v| match v.name {
+            "issue_number" => (v.name.to_string(), "42".to_string()),
+            name => (name.to_string(), format!("<{name}>")),
+        })
+        .collect();
+    let sample_body = render_template(&config.pr_body_template, &sample_vars)
+        .map_err(|e| Error::ConfigValidation(format!("invalid pr_body_template: {e}")))?;
+    if !pr_body_references_issue(&sample_body, 42) {
+        return Err(Error::ConfigValidation(
+            "pr_body_template must reference the issue as #{{ issue_number }}".to_string(),
+        ));
+    }
+    if let Some(template) = &config.finding_template {
+        validate_template(template)
+            .map_err(|e| Error::ConfigValidation(format!("invalid finding_template: {e}")))?;
+    }
+    if !is_prompt_version(&config.prompt_version) {
+        return Err(Error::ConfigValidation(format!(
+            "prompt_version {:?} is not embedded in this rlph (available: {})",
+            config.prompt_version,
+            PROMPT_VERSIONS.join(", ")
+        )));
+    }
+    for field in &config.finding_required_fields {
+        if field.trim().is_empty() || CORE_FINDING_FIELDS.contains(&field.as_str()) {
+            return Err(Error::ConfigValidation(format!(
+                "finding_required_fields entry must name an extension field: {field:?}"
+            )));
+        }
+    }
+    for dir in &config.shared_cache_dirs {
+        let path = Path::new(dir);
+        if dir.trim().is_empty()
+            || path.is_absolute()
+            || path
+                .components()
+                .any(|c| !matches!(c, std::path::Component::Normal(_)))
+            || path.starts_with(".git")
+        {
+            return Err(Error::ConfigValidation(format!(
+                "shared_cache_dirs entry must be a plain relative path inside the worktree: {dir}"
+            )));
+        }
+    }
+    for file in &config.conventions_files {
+        let path = Path::new(file);
+        if file.trim().is_empty()
+            || path.is_absolute()
+            || path
+                .components()
+                .any(|c| !matches!(c, std::path::Component::Normal(_)))
+        {
+            return Err(Error::ConfigValidation(format!(
+                "conventions_files entry must be a plain relative path inside the worktree: {file}"
+            )));
+        }
+    }
+    for (key, branch) in &config.base_branches {
+        if key.trim().is_empty() {
+            return Err(Error::ConfigValidation(
+                "base_branches keys must not be empty".to_string(),
+            ));
+        }
+        validate_branch_name(branch).map_err(|e| {
+            Error::ConfigValidation(format!("invalid base_branches entry {key:?}: {e}"))
+        })?;
+    }
+    for pattern in &config.protected_paths {
+        if pattern.trim().is_empty() || pattern.starts_with('#') || pattern.contains(['\n', '\r']) {
+            return Err(Error::ConfigValidation(format!(
+                "invalid protected_paths entry: {pattern:?}"
+            )));
+        }
+    }
     if config.poll_seconds == 0 {
         return Err(Error::ConfigValidation(
             "poll_seconds must be > 0".to_string(),
         ));
     }
+    if config.poll_max_seconds < config.poll_seconds {
+        return Err(Error::ConfigValidation(format!(
+            "poll_max_seconds ({}) must be >= poll_seconds ({})",
+            config.poll_max_seconds, config.poll_seconds
+        )));
+    }
+    if config.max_task_attempts == 0 {
+        return Err(Error::ConfigValidation(
+            "max_task_attempts must be > 0".to_string(),
+        ));
+    }
+    if let Some(profile) = &config.agent_identity.codex_profile
+        && (profile.trim().is_empty() || profile.starts_with('-'))
+    {
+        return Err(Error::ConfigValidation(format!(
+            "invalid codex_profile: {profile:?}"
+        )));
+    }
+    if config
+        .agent_identity
+        .claude_config_dir
+        .as_ref()
+        .is_some_and(|dir| !dir.is_absolute())
+    {
+        // Agents run inside worktrees, so a relative dir would move with them.
+        return Err(Error::ConfigValidation(
+            "claude_config_dir must be an absolute path".to_string(),
+        ));
+    }
+    if config.push_remote.trim().is_empty()
+        || config.push_remote.starts_with('-')
+        || config.push_remote.contains(char::is_whitespace)
+    {
+        return Err(Error::ConfigValidation(format!(
+            "invalid push_remote: {:?}",
+            config.push_remote
+        )));
+    }
+    if let Some(owner) = &config.pr_head_owner
+        && (owner.is_empty() || !owner.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+    {
+        return Err(Error::ConfigValidation(format!(
+            "invalid pr_head_owner: {owner:?}"
+        )));
+    }
+    if config.submission == "github"
+        && config.push_remote != "origin"
+        && config.pr_head_owner.is_none()
+    {
+        return Err(Error::ConfigValidation(
+            "pr_head_owner is required when push_remote is not origin".to_string(),
+        ));
+    }
+    let identity = &config.git_identity;
+    for (key, value) in [
+        ("git_user_name", &identity.user_name),
+        ("git_user_email", &identity.user_email),
+        ("git_signing_key", &identity.signing_key),
+    ] {
+        if let Some(value) = value
+            && (value.trim().is_empty() || value.contains(['\n', '\r']))
+        {
+            return Err(Error::ConfigValidation(format!("invalid {key}: {value:?}")));
+        }
+    }
+    if let Some(email) = &identity.user_email
+        && !email.contains('@')
+    {
+        return Err(Error::ConfigValidation(format!(
+            "invalid git_user_email: {email:?}"
+        )));
+    }
+    if let Some(format) = &identity.signing_format {
+        if !matches!(format.as_str(), "openpgp" | "ssh" | "x509") {
+            return Err(Error::ConfigValidation(format!(
+                "unknown git_signing_format: {format} (expected: openpgp, ssh, x509)"
+            )));
+        }
+        if identity.signing_key.is_none() {
+            return Err(Error::ConfigValidation(
+                "git_signing_format requires git_signing_key".to_string(),
+            ));
+        }
+    }
+    let hooks = [
+        ("on_task_selected", &config.hooks.on_task_selected),
+        ("on_pr_created", &config.hooks.on_pr_created),
+        ("on_review_failed", &config.hooks.on_review_failed),
+        ("on_iteration_complete", &config.hooks.on_iteration_complete),
+        ("on_queue_changed", &config.hooks.on_queue_changed),
+        ("on_run_digest", &config.hooks.on_run_digest),
+    ];
+    for (name, command) in hooks {
+        if command.as_deref().is_some_and(|c| c.trim().is_empty()) {
+            return Err(Error::ConfigValidation(format!(
+                "hooks.{name} must not be empty"
+            )));
+        }
+    }
+    if config.choose_max_tasks == 0 {
+        return Err(Error::ConfigValidation(
+            "choose_max_tasks must be at least 1".to_string(),
+        ));
+    }
+    if !(config.github_rps.is_finite() && config.github_rps > 0.0) {
+        return Err(Error::ConfigValidation(format!(
+            "github_rps must be a positive number, got {}",
+            config.github_rps
+        )));
+    }
+    if config.review_target == ReviewTarget::IssueComment && config.source != "github" {
+        return Err(Error::ConfigValidation(format!(
+            "review_target = \"issue-comment\" requires source = \"github\", got {}",
+            config.source
+        )));
+    }
+    if config.review_target == ReviewTarget::IssueComment && config.submission == "bitbucket" {
+        return Err(Error::ConfigValidation(
+            "review_target = \"issue-comment\" is not supported with submission = \"bitbucket\""
+                .to_string(),
+        ));
+    }
+    if config.review_concurrency == Some(0) {
+        return Err(Error::ConfigValidation(
+            "review_concurrency must be at least 1".to_string(),
+        ));
+    }
+    if config.hooks.timeout_seconds == 0 {
+        return Err(Error::ConfigValidation(
+            "hooks.timeout_seconds must be at least 1".to_string(),
+        ));
+    }
+    if let Some(slices) = config.implement_slices
+        && (slices.slice_minutes == 0 || slices.max_slices == 0)
+    {
+        return Err(Error::ConfigValidation(
+            "implement_slices.slice_minutes and max_slices must be at least 1".to_string(),
+        ));
+    }
+    if config.priority_policy.stale_after_days == Some(0) {
+        return Err(Error::ConfigValidation(
+            "priority_policy.stale_after_days must be at least 1".to_string(),
+        ));
+    }
+    if !(1..=8).contains(&config.priority_policy.stale_boost) {
+        return Err(Error::ConfigValidation(format!(
+            "priority_policy.stale_boost must be between 1 and 8, got {}",
+            config.priority_policy.stale_boost
+        )));
+    }
+    if config.preprocess.max_snippet_lines == 0 {
+        return Err(Error::ConfigValidation(
+            "preprocess.max_snippet_lines must be at least 1".to_string(),
+        ));
+    }
+    if config.priority_signal.is_empty() {
+        return Err(Error::ConfigValidation(
+            "priority_signal must list at least one signal".to_string(),
+        ));
+    }
+    if let Some(dup) = config
+        .priority_signal
+        .iter()
+        .enumerate()
+        .find_map(|(i, s)| config.priority_signal[..i].contains(s).then_some(s))
+    {
+        return Err(Error::ConfigValidation(format!(
+            "priority_signal lists {dup:?} twice"
+        )));
+    }
+    if config.abandon_after_days == Some(0) {
+        return Err(Error::ConfigValidation(
+            "abandon_after_days must be at least 1".to_string(),
+        ));
+    }
+    if config
+        .agent_language
+        .as_deref()
+        .is_some_and(|l| l.trim().is_empty())
+    {
+        return Err(Error::ConfigValidation(
+            "agent_language must not be empty".to_string(),
+        ));
+    }
+    if config.heartbeat_minutes == Some(0) {
+        return Err(Error::ConfigValidation(
+            "heartbeat_minutes must be at least 1".to_string(),
+        ));
+    }
+    if config.max_worktree_disk_mb == Some(0) {
+        return Err(Error::ConfigValidation(
+            "max_worktree_disk_mb must be at least 1".to_string(),
+        ));
+    }
+    if config.failed_label.trim().is_empty() {
+        return Err(Error::ConfigValidation(
+            "failed_label must not be empty".to_string(),
+        ));
+    }
     if config.review_phases.is_empty() {
         return Err(Error::ConfigValidation(
             "at least one review phase is required".to_string(),
         ));
     }
+    if let Some(ref filter) = config.review_phase_filter {
+        if filter.is_empty() {
+            return Err(Error::ConfigValidation(
+                "review_phase_filter must name at least one review phase".to_string(),
+            ));
+        }
+        for name in filter {
+            if !config.review_phases.iter().any(|p| &p.name == name) {
+                let known: Vec<&str> = config
+                    .review_phases
+                    .iter()
+                    .map(|p| p.name.as_str())
+                    .collect();
+                return Err(Error::ConfigValidation(format!(
+                    "unknown review phase '{name}' in review_phase_filter (configured: {})",
+                    known.join(", ")
+                )));
+            }
+        }
+    }
     {
         let mut seen_names = std::collections::HashSet::new();
         for phase in &config.review_phases {
@@ -554,7 +2369,7 @@ fn validate(config: &Config) -> Result<()> {
                     "review phase name must not be empty".to_string(),
                 ));
             }
-            if phase.prompt.is_empty() {
+            if phase.kind == ReviewPhaseKind::Agent && phase.prompt.is_empty() {
                 return Err(Error::ConfigValidation(format!(
                     "review phase '{}' prompt must not be empty",
                     phase.name
@@ -583,6 +2398,78 @@ fn validate(config: &Config) -> Result<()> {
             _ => {}
         }
     }
+    if config.source == "trello" {
+        match &config.trello {
+            Some(tc) if tc.board_id.is_empty() => {
+                return Err(Error::ConfigValidation(
+                    "trello.board_id is required when source = \"trello\"".to_string(),
+                ));
+            }
+            None => {
+                return Err(Error::ConfigValidation(
+                    "[trello] config section required when source = \"trello\"".to_string(),
+                ));
+            }
+            _ => {}
+        }
+    }
+    if let Some(pc) = &config.github_project {
+        if config.source != "github" {
+            return Err(Error::ConfigValidation(
+                "[github.project] requires source = \"github\"".to_string(),
+            ));
+        }
+        if pc.owner.is_empty() || pc.number == 0 {
+            return Err(Error::ConfigValidation(
+                "github.project.owner and github.project.number are required".to_string(),
+            ));
+        }
+        // Boards are driven through `gh api graphql`, which the REST transport
+        // cannot stand in for.
+        if config.github_transport == GitHubTransport::Api {
+            return Err(Error::ConfigValidation(
+                "[github.project] needs the gh CLI; it cannot be used with github_transport = \"api\""
+                    .to_string(),
+            ));
+        }
+        if crate::github_api::use_api(config.github_transport) {
+            return Err(Error::ConfigValidation(
+                "[github.project] needs the gh CLI, which is not on PATH".to_string(),
+            ));
+        }
+    }
+    if config.source == "gitea" || config.submission == "gitea" {
+        match &config.gitea {
+            Some(gc) if gc.base_url.is_empty() || gc.owner.is_empty() || gc.repo.is_empty() => {
+                return Err(Error::ConfigValidation(
+                    "gitea.base_url, gitea.owner, and gitea.repo are required".to_string(),
+                ));
+            }
+            None => {
+                return Err(Error::ConfigValidation(
+                    "[gitea] config section required when source or submission is \"gitea\""
+                        .to_string(),
+                ));
+            }
+            _ => {}
+        }
+    }
+    if config.source == "bitbucket" || config.submission == "bitbucket" {
+        match &config.bitbucket {
+            Some(bc) if bc.workspace.is_empty() || bc.repo_slug.is_empty() => {
+                return Err(Error::ConfigValidation(
+                    "bitbucket.workspace and bitbucket.repo_slug are required".to_string(),
+                ));
+            }
+            None => {
+                return Err(Error::ConfigValidation(
+                    "[bitbucket] config section required when source or submission is \"bitbucket\""
+                        .to_string(),
+                ));
+            }
+            _ => {}
+        }
+    }
     Ok(())
 }
 
@@ -590,6 +2477,7 @@ fn validate(config: &Config) -> Result<()> {
 mod tests {
     use super::*;
     use crate::cli::Cli;
+    use crate::preprocess::PreprocessStep;
     use crate::runner::RunnerKind;
     use clap::Parser;
 
@@ -621,101 +2509,1308 @@ worktree_dir = "/tmp/wt"
     }
 
     #[test]
-    fn test_file_invalid_source_rejected() {
-        let tmp = tempfile::tempdir().unwrap();
-        let cfg_dir = tmp.path().join(".rlph");
-        std::fs::create_dir_all(&cfg_dir).unwrap();
-        std::fs::write(cfg_dir.join("config.toml"), r#"source = "jira""#).unwrap();
+    fn test_file_invalid_source_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        std::fs::write(cfg_dir.join("config.toml"), r#"source = "jira""#).unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("unknown source: jira"));
+    }
+
+    #[test]
+    fn test_file_invalid_runner_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        std::fs::write(cfg_dir.join("config.toml"), r#"runner = "podman""#).unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("unknown runner: podman"));
+    }
+
+    #[test]
+    fn test_file_invalid_submission_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        std::fs::write(cfg_dir.join("config.toml"), r#"submission = "gitlab""#).unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("unknown submission: gitlab"));
+    }
+
+    #[test]
+    fn test_poll_max_seconds_default_and_bounds() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = Config::load_from(&cli, tmp.path()).unwrap();
+        assert_eq!(config.poll_max_seconds, DEFAULT_POLL_MAX_SECONDS);
+
+        let cli = Cli::parse_from(["rlph", "--once", "--poll-seconds", "900"]);
+        let config = Config::load_from(&cli, tmp.path()).unwrap();
+        assert_eq!(config.poll_max_seconds, 900);
+
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        std::fs::write(
+            cfg_dir.join("config.toml"),
+            "poll_seconds = 60\npoll_max_seconds = 30\n",
+        )
+        .unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("poll_max_seconds (30) must be >= poll_seconds (60)")
+        );
+    }
+
+    #[test]
+    fn test_file_zero_poll_seconds_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        std::fs::write(cfg_dir.join("config.toml"), r#"poll_seconds = 0"#).unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("poll_seconds must be > 0"));
+    }
+
+    #[test]
+    fn test_retry_policy_defaults_and_overrides() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = Config::load_from(&cli, tmp.path()).unwrap();
+        assert_eq!(config.max_task_attempts, 3);
+        assert_eq!(config.retry_backoff_seconds, 300);
+        assert_eq!(config.failed_label, "rlph-failed");
+
+        std::fs::write(
+            cfg_dir.join("config.toml"),
+            "max_task_attempts = 5\nretry_backoff_seconds = 60\nfailed_label = \"stuck\"",
+        )
+        .unwrap();
+        let config = Config::load_from(&cli, tmp.path()).unwrap();
+        assert_eq!(config.max_task_attempts, 5);
+        assert_eq!(config.retry_backoff_seconds, 60);
+        assert_eq!(config.failed_label, "stuck");
+
+        std::fs::write(cfg_dir.join("config.toml"), "max_task_attempts = 0").unwrap();
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("max_task_attempts must be > 0"));
+    }
+
+    #[test]
+    fn test_heartbeat_minutes() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(parse_config("heartbeat_minutes = 10").unwrap(), &cli).unwrap();
+        assert_eq!(config.heartbeat_minutes, Some(10));
+
+        let err = merge(parse_config("heartbeat_minutes = 0").unwrap(), &cli).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("heartbeat_minutes must be at least 1")
+        );
+    }
+
+    #[test]
+    fn test_max_worktree_disk_mb() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(parse_config("").unwrap(), &cli).unwrap();
+        assert_eq!(config.max_worktree_disk_mb, None);
+        let config = merge(parse_config("max_worktree_disk_mb = 2048").unwrap(), &cli).unwrap();
+        assert_eq!(config.max_worktree_disk_mb, Some(2048));
+
+        let err = merge(parse_config("max_worktree_disk_mb = 0").unwrap(), &cli).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("max_worktree_disk_mb must be at least 1")
+        );
+    }
+
+    #[test]
+    fn test_digest_issue() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(parse_config("").unwrap(), &cli).unwrap();
+        assert_eq!(config.digest_issue, None);
+        let config = merge(parse_config("digest_issue = \"42\"").unwrap(), &cli).unwrap();
+        assert_eq!(config.digest_issue.as_deref(), Some("42"));
+        let config = merge(parse_config("digest_issue = \" \"").unwrap(), &cli).unwrap();
+        assert_eq!(config.digest_issue, None);
+    }
+
+    #[test]
+    fn test_agent_language_enables_translate() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(parse_config("").unwrap(), &cli).unwrap();
+        assert!(config.agent_language.is_none());
+        assert!(config.translate.is_none());
+
+        let file = parse_config(
+            r#"
+agent_language = "English"
+
+[translate]
+agent_model = "claude-haiku-4-5"
+"#,
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.agent_language.as_deref(), Some("English"));
+        let translate = config.translate.unwrap();
+        assert_eq!(translate.prompt, "translate");
+        assert_eq!(translate.agent_model.as_deref(), Some("claude-haiku-4-5"));
+
+        let err = merge(parse_config(r#"agent_language = " ""#).unwrap(), &cli).unwrap_err();
+        assert!(err.to_string().contains("agent_language must not be empty"));
+    }
+
+    #[test]
+    fn test_abandon_after_days() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        assert_eq!(
+            Config::load_from(&cli, tmp.path())
+                .unwrap()
+                .abandon_after_days,
+            None
+        );
+
+        std::fs::write(cfg_dir.join("config.toml"), "abandon_after_days = 14").unwrap();
+        assert_eq!(
+            Config::load_from(&cli, tmp.path())
+                .unwrap()
+                .abandon_after_days,
+            Some(14)
+        );
+
+        std::fs::write(cfg_dir.join("config.toml"), "abandon_after_days = 0").unwrap();
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("abandon_after_days"), "{err}");
+    }
+
+    #[test]
+    fn test_on_error_parsing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        let cli = Cli::parse_from(["rlph", "--continuous"]);
+        assert_eq!(
+            Config::load_from(&cli, tmp.path()).unwrap().on_error,
+            OnError::Stop
+        );
+
+        std::fs::write(cfg_dir.join("config.toml"), r#"on_error = "continue""#).unwrap();
+        assert_eq!(
+            Config::load_from(&cli, tmp.path()).unwrap().on_error,
+            OnError::Continue
+        );
+
+        std::fs::write(cfg_dir.join("config.toml"), r#"on_error = "retry""#).unwrap();
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("unknown on_error: retry"));
+    }
+
+    #[test]
+    fn test_review_round_policy_parsing() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.review_round_policy, ReviewRoundPolicy::Fixed);
+
+        let file = parse_config(r#"review_round_policy = "adaptive""#).unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.review_round_policy, ReviewRoundPolicy::Adaptive);
+
+        let file = parse_config(r#"review_round_policy = "smart""#).unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("unknown review_round_policy: smart")
+        );
+    }
+
+    #[test]
+    fn test_claim_strategy_parsing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        assert_eq!(
+            Config::load_from(&cli, tmp.path()).unwrap().claim_strategy,
+            ClaimStrategy::Label
+        );
+
+        std::fs::write(
+            cfg_dir.join("config.toml"),
+            r#"claim_strategy = "assignee""#,
+        )
+        .unwrap();
+        assert_eq!(
+            Config::load_from(&cli, tmp.path()).unwrap().claim_strategy,
+            ClaimStrategy::Assignee
+        );
+
+        std::fs::write(cfg_dir.join("config.toml"), r#"claim_strategy = "lock""#).unwrap();
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("unknown claim_strategy: lock"));
+    }
+
+    #[test]
+    fn test_priority_policy() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.priority_policy, PriorityPolicy::default());
+        assert_eq!(config.priority_policy.urgent_labels, vec!["sla-urgent"]);
+
+        let file = parse_config(
+            r#"
+[priority_policy]
+stale_after_days = 14
+stale_boost = 2
+urgent_labels = ["sla-urgent", "incident"]
+"#,
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.priority_policy.stale_after_days, Some(14));
+        assert_eq!(config.priority_policy.stale_boost, 2);
+        assert_eq!(
+            config.priority_policy.urgent_labels,
+            vec!["sla-urgent", "incident"]
+        );
+
+        let file = parse_config("[priority_policy]\nstale_after_days = 0\n").unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("stale_after_days must be at least 1")
+        );
+
+        let file = parse_config("[priority_policy]\nstale_boost = 9\n").unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("stale_boost must be between 1 and 8")
+        );
+    }
+
+    #[test]
+    fn test_hooks() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.hooks, HooksConfig::default());
+
+        let file = parse_config(
+            r#"
+[hooks]
+on_task_selected = "./scripts/claim.sh"
+on_review_failed = "notify-send rlph"
+timeout_seconds = 5
+"#,
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(
+            config.hooks.on_task_selected.as_deref(),
+            Some("./scripts/claim.sh")
+        );
+        assert_eq!(config.hooks.on_pr_created, None);
+        assert_eq!(
+            config.hooks.on_review_failed.as_deref(),
+            Some("notify-send rlph")
+        );
+        assert_eq!(config.hooks.timeout_seconds, 5);
+
+        let file = parse_config("[hooks]\non_pr_created = \" \"\n").unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("hooks.on_pr_created must not be empty")
+        );
+
+        let file = parse_config("[hooks]\ntimeout_seconds = 0\n").unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("hooks.timeout_seconds must be at least 1")
+        );
+
+        assert!(parse_config("[hooks]\non_merge = \"x\"\n").is_err());
+    }
+
+    #[test]
+    fn test_cleanup() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.cleanup, CleanupConfig::default());
+        assert!(config.cleanup.enabled);
+
+        let file = parse_config("[cleanup]\ndelete_remote_branches = false\n").unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert!(config.cleanup.enabled);
+        assert!(!config.cleanup.delete_remote_branches);
+        assert!(config.cleanup.requeue_closed);
+
+        assert!(parse_config("[cleanup]\ndelete_local = true\n").is_err());
+    }
+
+    #[test]
+    fn test_findings_export() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert!(config.findings_export.is_none());
+
+        let file = parse_config("[findings_export]\n").unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(
+            config.findings_export,
+            Some(FindingsExportConfig {
+                path: ".rlph/findings/{task_id}-round-{round}.sarif".to_string(),
+                format: ExportFormat::Sarif,
+            })
+        );
+
+        let file =
+            parse_config("[findings_export]\npath = \"out/review.json\"\nformat = \"json\"\n")
+                .unwrap();
+        let export = merge(file, &cli).unwrap().findings_export.unwrap();
+        assert_eq!(export.path, "out/review.json");
+        assert_eq!(export.format, ExportFormat::Json);
+
+        let file = parse_config("[findings_export]\nformat = \"csv\"\n").unwrap();
+        assert!(merge(file, &cli).is_err());
+    }
+
+    #[test]
+    fn test_toolchain() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.toolchain, ToolchainConfig::default());
+
+        let file = parse_config("[toolchain]\ntest = \" make test \"\nlint = \"  \"\n").unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.toolchain.test.as_deref(), Some("make test"));
+        assert_eq!(config.toolchain.lint, None);
+        assert_eq!(config.toolchain.build, None);
+
+        assert!(parse_config("[toolchain]\nformat = \"cargo fmt\"\n").is_err());
+    }
+
+    #[test]
+    fn test_git_identity() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.git_identity, GitIdentity::default());
+
+        let file = parse_config(
+            r#"
+git_user_name = "rlph-bot"
+git_user_email = "bot@example.com"
+git_signing_key = "~/.ssh/rlph.pub"
+git_signing_format = "ssh"
+"#,
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.git_identity.user_name.as_deref(), Some("rlph-bot"));
+        assert_eq!(config.git_identity.signing_format.as_deref(), Some("ssh"));
+
+        for (toml, message) in [
+            (r#"git_user_email = "bot""#, "invalid git_user_email"),
+            (r#"git_user_name = " ""#, "invalid git_user_name"),
+            (
+                r#"git_signing_format = "ssh""#,
+                "git_signing_format requires git_signing_key",
+            ),
+            (
+                "git_signing_key = \"ABC\"\ngit_signing_format = \"pgp\"",
+                "unknown git_signing_format: pgp",
+            ),
+        ] {
+            let err = merge(parse_config(toml).unwrap(), &cli).unwrap_err();
+            assert!(err.to_string().contains(message), "{toml}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_choose_strategy_parsing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        assert_eq!(
+            Config::load_from(&cli, tmp.path()).unwrap().choose_strategy,
+            ChooseStrategy::Agent
+        );
+
+        std::fs::write(cfg_dir.join("config.toml"), r#"choose_strategy = "ranked""#).unwrap();
+        assert_eq!(
+            Config::load_from(&cli, tmp.path()).unwrap().choose_strategy,
+            ChooseStrategy::Ranked
+        );
+
+        std::fs::write(cfg_dir.join("config.toml"), r#"choose_strategy = "random""#).unwrap();
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("unknown choose_strategy: random"));
+    }
+
+    #[test]
+    fn test_preprocess_config() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.preprocess, PreprocessConfig::default());
+
+        let file = parse_config(
+            r#"
+[preprocess]
+steps = ["strip_html_comments", "expand_references"]
+max_snippet_lines = 10
+"#,
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(
+            config.preprocess.steps,
+            vec![
+                PreprocessStep::StripHtmlComments,
+                PreprocessStep::ExpandReferences
+            ]
+        );
+        assert_eq!(config.preprocess.max_snippet_lines, 10);
+
+        let file = parse_config("[preprocess]\nsteps = [\"shout\"]\n").unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(err.to_string().contains("unknown preprocess step: shout"));
+    }
+
+    #[test]
+    fn test_priority_signal() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.priority_signal, vec![PrioritySignal::Labels]);
+
+        let file = parse_config(r#"priority_signal = ["reactions", "labels"]"#).unwrap();
+        assert_eq!(
+            merge(file, &cli).unwrap().priority_signal,
+            vec![PrioritySignal::Reactions, PrioritySignal::Labels]
+        );
+
+        for (toml, message) in [
+            (
+                r#"priority_signal = ["votes"]"#,
+                "unknown priority_signal: votes",
+            ),
+            ("priority_signal = []", "at least one signal"),
+            (r#"priority_signal = ["labels", "labels"]"#, "twice"),
+        ] {
+            let err = merge(parse_config(toml).unwrap(), &cli).unwrap_err();
+            assert!(err.to_string().contains(message), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_push_remote_and_pr_head_owner() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.push_remote, "origin");
+        assert_eq!(config.pr_head_owner, None);
+
+        let file = parse_config(
+            r#"
+push_remote = "fork"
+pr_head_owner = "rlph-bot"
+"#,
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.push_remote, "fork");
+        assert_eq!(config.pr_head_owner.as_deref(), Some("rlph-bot"));
+
+        let file = parse_config(r#"push_remote = "fork""#).unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(err.to_string().contains("pr_head_owner is required"));
+
+        let file = parse_config(r#"push_remote = "--mirror""#).unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(err.to_string().contains("invalid push_remote"));
+
+        let file = parse_config(
+            r#"
+push_remote = "fork"
+pr_head_owner = "rlph bot"
+"#,
+        )
+        .unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(err.to_string().contains("invalid pr_head_owner"));
+    }
+
+    #[test]
+    fn test_review_output_parsing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        assert_eq!(
+            Config::load_from(&cli, tmp.path()).unwrap().review_output,
+            StreamMode::Interleaved
+        );
+
+        std::fs::write(
+            cfg_dir.join("config.toml"),
+            r#"review_output = "serialized""#,
+        )
+        .unwrap();
+        assert_eq!(
+            Config::load_from(&cli, tmp.path()).unwrap().review_output,
+            StreamMode::Serialized
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_field() {
+        let toml = r#"bogus = "value""#;
+        let err = parse_config(toml).unwrap_err();
+        assert!(err.to_string().contains("unknown field"));
+    }
+
+    #[test]
+    fn test_cli_overrides_config() {
+        let file = ConfigFile {
+            source: Some("github".to_string()),
+            runner: Some("claude".to_string()),
+            label: Some("file-label".to_string()),
+            poll_seconds: Some(120),
+            linear: Some(LinearConfigFile {
+                team: Some("ENG".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let cli = Cli::parse_from([
+            "rlph",
+            "--once",
+            "--source",
+            "linear",
+            "--label",
+            "cli-label",
+        ]);
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.source, "linear"); // CLI wins
+        assert_eq!(config.label, "cli-label"); // CLI wins
+        assert_eq!(config.runner, RunnerKind::Claude); // file value kept
+        assert_eq!(config.poll_seconds, 120); // file value kept
+        assert!(config.once);
+    }
+
+    #[test]
+    fn test_defaults_applied() {
+        let file = ConfigFile::default();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.source, "github");
+        assert_eq!(config.runner, RunnerKind::Claude);
+        assert_eq!(config.submission, "github");
+        assert_eq!(config.label, "rlph");
+        assert_eq!(config.poll_seconds, 30);
+        assert_eq!(config.agent_binary, "claude");
+        assert_eq!(config.agent_model.as_deref(), Some("claude-opus-4-6"));
+        assert_eq!(config.agent_effort.as_deref(), Some("high"));
+        assert_eq!(config.agent_timeout, Some(600));
+        assert_eq!(config.implement_timeout, Some(1800));
+        assert_eq!(config.agent_timeout_retries, 2);
+    }
+
+    #[test]
+    fn test_issue_pr_comments_default_and_override() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert!(!config.issue_pr_comments);
+
+        let file = parse_config("issue_pr_comments = true").unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert!(config.issue_pr_comments);
+    }
+
+    #[test]
+    fn test_choose_prompt_limits() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.choose_max_tasks, DEFAULT_CHOOSE_MAX_TASKS);
+        assert_eq!(config.choose_body_chars, DEFAULT_CHOOSE_BODY_CHARS);
+
+        let file = parse_config("choose_max_tasks = 5\nchoose_body_chars = 0").unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.choose_max_tasks, 5);
+        assert_eq!(config.choose_body_chars, 0);
+
+        let file = parse_config("choose_max_tasks = 0").unwrap();
+        assert!(
+            merge(file, &cli)
+                .unwrap_err()
+                .to_string()
+                .contains("choose_max_tasks must be at least 1")
+        );
+    }
+
+    #[test]
+    fn test_github_transport() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.github_transport, GitHubTransport::Auto);
+
+        let file = parse_config(r#"github_transport = "api""#).unwrap();
+        assert_eq!(
+            merge(file, &cli).unwrap().github_transport,
+            GitHubTransport::Api
+        );
+
+        let file = parse_config(r#"github_transport = "graphql""#).unwrap();
+        assert!(
+            merge(file, &cli)
+                .unwrap_err()
+                .to_string()
+                .contains("unknown github_transport: graphql")
+        );
+    }
+
+    #[test]
+    fn test_github_rps() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.github_rps, DEFAULT_GITHUB_RPS);
+
+        let file = parse_config("github_rps = 0.5").unwrap();
+        assert_eq!(merge(file, &cli).unwrap().github_rps, 0.5);
+
+        let file = parse_config("github_rps = 0.0").unwrap();
+        assert!(
+            merge(file, &cli)
+                .unwrap_err()
+                .to_string()
+                .contains("github_rps must be a positive number")
+        );
+    }
+
+    #[test]
+    fn test_review_target() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.review_target, ReviewTarget::PrComment);
+
+        let file = parse_config(r#"review_target = "check-run""#).unwrap();
+        assert_eq!(
+            merge(file, &cli).unwrap().review_target,
+            ReviewTarget::CheckRun
+        );
+
+        let file = parse_config(r#"review_target = "status""#).unwrap();
+        assert!(
+            merge(file, &cli)
+                .unwrap_err()
+                .to_string()
+                .contains("unknown review_target: status")
+        );
+
+        let file = parse_config(
+            "source = \"linear\"\nreview_target = \"issue-comment\"\n[linear]\nteam = \"ENG\"\nproject = \"rlph\"",
+        )
+        .unwrap();
+        assert!(
+            merge(file, &cli)
+                .unwrap_err()
+                .to_string()
+                .contains("requires source = \"github\"")
+        );
+    }
+
+    #[test]
+    fn test_bitbucket_config() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let file = parse_config(
+            "source = \"bitbucket\"\nsubmission = \"bitbucket\"\n[bitbucket]\nworkspace = \"acme\"\nrepo_slug = \"app\"",
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        let bb = config.bitbucket.unwrap();
+        assert_eq!(bb.workspace, "acme");
+        assert_eq!(bb.token_env, "BITBUCKET_TOKEN");
+        assert_eq!(bb.api_url, "https://api.bitbucket.org/2.0");
+
+        let file = parse_config("submission = \"bitbucket\"").unwrap();
+        let err = merge(file, &cli).unwrap_err().to_string();
+        assert!(err.contains("[bitbucket] config section required"), "{err}");
+
+        let file =
+            parse_config("source = \"bitbucket\"\n[bitbucket]\nworkspace = \"acme\"").unwrap();
+        let err = merge(file, &cli).unwrap_err().to_string();
+        assert!(err.contains("repo_slug are required"), "{err}");
+    }
+
+    #[test]
+    fn test_trello_config() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let file = parse_config(
+            "source = \"trello\"\n[trello]\nboard_id = \"abc123\"\nin_progress_list = \"Building\"",
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        let tc = config.trello.unwrap();
+        assert_eq!(tc.board_id, "abc123");
+        assert_eq!(tc.todo_list, "To Do");
+        assert_eq!(tc.in_progress_list, "Building");
+
+        let file = parse_config("source = \"trello\"").unwrap();
+        let err = merge(file, &cli).unwrap_err().to_string();
+        assert!(err.contains("[trello] config section required"), "{err}");
+    }
+
+    #[test]
+    fn test_github_project_config() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let file = parse_config(
+            "github_transport = \"cli\"\n[github.project]\nowner = \"acme\"\nnumber = 7\nready_column = \"Ready\"",
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        let pc = config.github_project.unwrap();
+        assert_eq!(pc.owner, "acme");
+        assert_eq!(pc.number, 7);
+        assert_eq!(pc.status_field, "Status");
+        assert_eq!(pc.ready_column, "Ready");
+        assert_eq!(pc.in_progress_column, "In Progress");
+
+        let file = parse_config("[github.project]\nowner = \"acme\"").unwrap();
+        let err = merge(file, &cli).unwrap_err().to_string();
+        assert!(err.contains("number are required"), "{err}");
+
+        let file = parse_config(
+            "source = \"linear\"\n[linear]\nteam = \"ENG\"\n[github.project]\nowner = \"acme\"\nnumber = 7",
+        )
+        .unwrap();
+        let err = merge(file, &cli).unwrap_err().to_string();
+        assert!(err.contains("requires source = \"github\""), "{err}");
+
+        let file = parse_config(
+            "github_transport = \"api\"\n[github.project]\nowner = \"acme\"\nnumber = 7",
+        )
+        .unwrap();
+        let err = merge(file, &cli).unwrap_err().to_string();
+        assert!(err.contains("needs the gh CLI"), "{err}");
+    }
+
+    #[test]
+    fn test_gitea_config() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let file = parse_config(
+            "source = \"gitea\"\nsubmission = \"gitea\"\n[gitea]\nbase_url = \"https://codeberg.org\"\nowner = \"me\"\nrepo = \"app\"",
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        let gc = config.gitea.unwrap();
+        assert_eq!(gc.base_url, "https://codeberg.org");
+        assert_eq!(gc.token_env, "GITEA_TOKEN");
+
+        let file = parse_config("submission = \"gitea\"\n[gitea]\nowner = \"me\"\nrepo = \"app\"")
+            .unwrap();
+        let err = merge(file, &cli).unwrap_err().to_string();
+        assert!(err.contains("gitea.base_url"), "{err}");
+    }
+
+    #[test]
+    fn test_fix_trailers() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert!(!config.fix_trailers.enabled);
+
+        let file = parse_config("[fix_trailers]\nagent_email = \"bot@example.com\"\n").unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert!(config.fix_trailers.enabled);
+        assert_eq!(config.fix_trailers.agent_email, "bot@example.com");
+        assert_eq!(
+            config.fix_trailers.human_email,
+            FixTrailersConfig::default().human_email
+        );
+    }
+
+    #[test]
+    fn test_squash() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert!(!config.squash.enabled);
+
+        let file =
+            parse_config("[squash]\nmode = \"single\"\nmerge_method = \"squash\"\n").unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert!(config.squash.enabled);
+        assert!(config.squash.auto_merge);
+        assert_eq!(config.squash.mode, SquashMode::Single);
+        assert_eq!(config.squash.merge_method, MergeMethod::Squash);
+
+        let file = parse_config("[squash]\nmode = \"fixups\"\n").unwrap();
+        let err = merge(file, &cli).unwrap_err().to_string();
+        assert!(err.contains("squash.mode"), "{err}");
+    }
+
+    #[test]
+    fn test_implement_slices() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert!(config.implement_slices.is_none());
+
+        let file = parse_config("[implement_slices]\nslice_minutes = 20\n").unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(
+            config.implement_slices,
+            Some(ImplementSlicesConfig {
+                slice_minutes: 20,
+                max_slices: 4,
+            })
+        );
+
+        let file = parse_config("[implement_slices]\nmax_slices = 0\n").unwrap();
+        let err = merge(file, &cli).unwrap_err().to_string();
+        assert!(err.contains("implement_slices"), "{err}");
+    }
+
+    #[test]
+    fn test_review_concurrency() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.review_concurrency, None);
+
+        let file = parse_config("review_concurrency = 2").unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.review_concurrency, Some(2));
+
+        let file = parse_config("review_concurrency = 0").unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("review_concurrency must be at least 1")
+        );
+    }
+
+    #[test]
+    fn test_hold_labels_default_and_override() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.hold_labels, default_hold_labels());
+
+        let file = parse_config(r#"hold_labels = ["paused"]"#).unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.hold_labels, vec!["paused".to_string()]);
+    }
+
+    #[test]
+    fn test_branch_template_default_and_validation() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.branch_template, DEFAULT_BRANCH_TEMPLATE);
+
+        let file =
+            parse_config(r#"branch_template = "{{ user }}/rlph/{{ issue }}-{{ slug }}""#).unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(
+            config.branch_template,
+            "{{ user }}/rlph/{{ issue }}-{{ slug }}"
+        );
+
+        let file = parse_config(r#"branch_template = "rlph/{{ slug }}""#).unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(err.to_string().contains("must include {{ issue }}"));
+
+        let file = parse_config(r#"branch_template = "rlph {{ issue }}""#).unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(err.to_string().contains("invalid branch_template"));
+    }
+
+    #[test]
+    fn test_finding_fields_and_template() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert!(config.finding_required_fields.is_empty());
+        assert!(config.finding_template.is_none());
+
+        let file = parse_config(
+            r#"
+finding_required_fields = ["confidence"]
+finding_template = "{{ severity }} ({{ confidence }}): {{ description }}"
+"#,
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.finding_required_fields, vec!["confidence"]);
+        assert!(config.finding_template.is_some());
+
+        let file = parse_config(r#"finding_required_fields = ["severity"]"#).unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(err.to_string().contains("must name an extension field"));
+
+        let file = parse_config(r#"finding_template = "{% if x %}""#).unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(err.to_string().contains("invalid finding_template"));
+    }
+
+    #[test]
+    fn test_prompt_version() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.prompt_version, PROMPT_VERSION);
+        let config = merge(parse_config(r#"prompt_version = "v1""#).unwrap(), &cli).unwrap();
+        assert_eq!(config.prompt_version, "v1");
+
+        let err = merge(parse_config(r#"prompt_version = "v99""#).unwrap(), &cli).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains(r#"prompt_version "v99" is not embedded in this rlph"#),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_untrusted_sources_tightens_permissions() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.permission_mode, PermissionMode::Full);
+
+        let file = parse_config("untrusted_sources = true").unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.permission_mode, PermissionMode::WorkspaceWrite);
+        assert_eq!(
+            config.review_phases[0].permission_mode,
+            PermissionMode::WorkspaceWrite
+        );
+
+        let file =
+            parse_config("untrusted_sources = true\nallow_dangerous_permissions = true").unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.permission_mode, PermissionMode::Full);
+
+        let file = parse_config(
+            r#"
+untrusted_sources = true
+
+[review_fix]
+permission_mode = "full"
+"#,
+        )
+        .unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(err.to_string().contains("review_fix: permission_mode"));
+    }
+
+    #[test]
+    fn test_permission_mode_inherited_and_overridden() {
+        let file = parse_config(
+            r#"
+permission_mode = "workspace-write"
+
+[[review_phases]]
+name = "correctness"
+prompt = "correctness-review"
+permission_mode = "read-only"
+
+[[review_phases]]
+name = "security"
+prompt = "security-review"
+"#,
+        )
+        .unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.permission_mode, PermissionMode::WorkspaceWrite);
+        assert_eq!(
+            config.review_phases[0].permission_mode,
+            PermissionMode::ReadOnly
+        );
+        assert_eq!(
+            config.review_phases[1].permission_mode,
+            PermissionMode::WorkspaceWrite
+        );
+        assert_eq!(config.fix.permission_mode, PermissionMode::WorkspaceWrite);
+
+        let file = parse_config(r#"permission_mode = "workspace-write""#).unwrap();
+        let cli = Cli::parse_from(["rlph", "--once", "--permission-mode", "read-only"]);
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.permission_mode, PermissionMode::ReadOnly);
+
+        let file = parse_config(r#"permission_mode = "yolo""#).unwrap();
+        let err = merge(file, &Cli::parse_from(["rlph", "--once"])).unwrap_err();
+        assert!(err.to_string().contains("unknown permission_mode"));
+    }
+
+    #[test]
+    fn test_review_phase_fail_fast() {
+        let file = parse_config(
+            r#"
+[[review_phases]]
+name = "secrets-scan"
+prompt = "security-review"
+fail_fast = true
+
+[[review_phases]]
+name = "correctness"
+prompt = "correctness-review"
+"#,
+        )
+        .unwrap();
+        let config = merge(file, &Cli::parse_from(["rlph", "--once"])).unwrap();
+        assert!(config.review_phases[0].fail_fast);
+        assert!(!config.review_phases[1].fail_fast);
+    }
+
+    #[test]
+    fn test_review_phase_context() {
+        let file = parse_config(
+            r#"
+[[review_phases]]
+name = "regression-risk"
+prompt = "regression-review"
+context = ["diff", "blame"]
+"#,
+        )
+        .unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(
+            config.review_phases[0].context,
+            [ReviewContext::Diff, ReviewContext::Blame]
+        );
+
+        let file = parse_config(
+            "[[review_phases]]\nname = \"r\"\nprompt = \"r\"\ncontext = [\"history\"]\n",
+        )
+        .unwrap();
+        let err = merge(file, &cli).unwrap_err().to_string();
+        assert!(
+            err.contains("unknown review phase context: history"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_pr_templates_default_and_validation() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert_eq!(config.pr_title_template, DEFAULT_PR_TITLE_TEMPLATE);
+        assert_eq!(config.pr_body_template, DEFAULT_PR_BODY_TEMPLATE);
+
+        let file = parse_config(r#"pr_title_template = "[{{ issue_number }}] {{ issue_title }}""#)
+            .unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(
+            config.pr_title_template,
+            "[{{ issue_number }}] {{ issue_title }}"
+        );
+
+        let file = parse_config(r#"pr_body_template = "{% if x %}unclosed""#).unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(err.to_string().contains("invalid pr_body_template"));
+
+        let file = parse_config(r#"pr_title_template = "{{ issue_titel }}""#).unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("invalid pr_title_template: prompt error: template uses `issue_titel`"),
+            "{err}"
+        );
+
+        let file = parse_config(r#"pr_body_template = "Fixes {{ issue_title }}""#).unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("pr_body_template must reference the issue as #{{ issue_number }}"),
+            "{err}"
+        );
+        let file = parse_config(r#"pr_body_template = "Closes (#{{ issue_number }}).""#).unwrap();
+        assert!(merge(file, &cli).is_ok());
+    }
+
+    #[test]
+    fn test_shared_cache_dirs_defaults_and_validation() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert!(config.shared_cache_dirs.is_empty());
+        assert_eq!(config.cache_dir, "../rlph-worktrees/.rlph-cache");
+
+        let file = parse_config(
+            r#"
+shared_cache_dirs = ["target", "node_modules/.cache"]
+cache_dir = "/var/cache/rlph"
+"#,
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(
+            config.shared_cache_dirs,
+            vec!["target", "node_modules/.cache"]
+        );
+        assert_eq!(config.cache_dir, "/var/cache/rlph");
+
+        for bad in ["/abs", "../up", ".git/objects", "a/../b", ""] {
+            let file = ConfigFile {
+                shared_cache_dirs: Some(vec![bad.to_string()]),
+                ..Default::default()
+            };
+            assert!(merge(file, &cli).is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_conventions_files_defaults_and_validation() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert!(config.conventions_files.is_empty());
+
+        let file =
+            parse_config(r#"conventions_files = ["AGENTS.md", "docs/CONTRIBUTING.md"]"#).unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(
+            config.conventions_files,
+            vec!["AGENTS.md", "docs/CONTRIBUTING.md"]
+        );
+
+        for bad in ["/etc/passwd", "../AGENTS.md", "docs/../../x", ""] {
+            let file = ConfigFile {
+                conventions_files: Some(vec![bad.to_string()]),
+                ..Default::default()
+            };
+            assert!(merge(file, &cli).is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_base_branches_resolve_per_task() {
         let cli = Cli::parse_from(["rlph", "--once"]);
-        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
-        assert!(err.to_string().contains("unknown source: jira"));
+        let file = parse_config(
+            r#"
+base_branch = "main"
+
+[base_branches]
+"backport-1.x" = "release/1.x"
+"v2.0" = "next"
+"#,
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        let task = |labels: &[&str], milestone: Option<&str>| Task {
+            id: "1".to_string(),
+            title: "t".to_string(),
+            body: String::new(),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            url: String::new(),
+            priority: None,
+            created_at: None,
+            updated_at: None,
+            milestone: milestone.map(str::to_string),
+            reactions: None,
+            assignee: None,
+            estimate: None,
+            project: None,
+        };
+        assert_eq!(config.base_branch_for(&task(&["rlph"], None)), "main");
+        assert_eq!(
+            config.base_branch_for(&task(&["rlph", "backport-1.x"], None)),
+            "release/1.x"
+        );
+        assert_eq!(config.base_branch_for(&task(&[], Some("v2.0"))), "next");
+        // A mapped label wins over the milestone.
+        assert_eq!(
+            config.base_branch_for(&task(&["backport-1.x"], Some("v2.0"))),
+            "release/1.x"
+        );
+
+        let file = parse_config("[base_branches]\nbackport = \"bad branch\"\n").unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(err.to_string().contains("invalid base_branches entry"));
     }
 
     #[test]
-    fn test_file_invalid_runner_rejected() {
-        let tmp = tempfile::tempdir().unwrap();
-        let cfg_dir = tmp.path().join(".rlph");
-        std::fs::create_dir_all(&cfg_dir).unwrap();
-        std::fs::write(cfg_dir.join("config.toml"), r#"runner = "podman""#).unwrap();
+    fn test_protected_paths_defaults_and_validation() {
         let cli = Cli::parse_from(["rlph", "--once"]);
-        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
-        assert!(err.to_string().contains("unknown runner: podman"));
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert!(config.protected_paths.is_empty());
+
+        let file =
+            parse_config(r#"protected_paths = [".github/workflows/**", "Cargo.lock"]"#).unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(
+            config.protected_paths,
+            vec![".github/workflows/**", "Cargo.lock"]
+        );
+
+        for bad in ["", "  ", "# comment", "a\nb"] {
+            let file = ConfigFile {
+                protected_paths: Some(vec![bad.to_string()]),
+                ..Default::default()
+            };
+            assert!(merge(file, &cli).is_err(), "{bad:?} should be rejected");
+        }
     }
 
     #[test]
-    fn test_file_invalid_submission_rejected() {
-        let tmp = tempfile::tempdir().unwrap();
-        let cfg_dir = tmp.path().join(".rlph");
-        std::fs::create_dir_all(&cfg_dir).unwrap();
-        std::fs::write(cfg_dir.join("config.toml"), r#"submission = "gitlab""#).unwrap();
+    fn test_followup_defaults_and_override() {
         let cli = Cli::parse_from(["rlph", "--once"]);
-        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
-        assert!(err.to_string().contains("unknown submission: gitlab"));
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert!(!config.followup_issues);
+        assert_eq!(config.followup_label, "rlph-followup");
+
+        let file = parse_config(
+            r#"
+followup_issues = true
+followup_label = "tech-debt"
+"#,
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert!(config.followup_issues);
+        assert_eq!(config.followup_label, "tech-debt");
     }
 
     #[test]
-    fn test_file_zero_poll_seconds_rejected() {
-        let tmp = tempfile::tempdir().unwrap();
-        let cfg_dir = tmp.path().join(".rlph");
-        std::fs::create_dir_all(&cfg_dir).unwrap();
-        std::fs::write(cfg_dir.join("config.toml"), r#"poll_seconds = 0"#).unwrap();
+    fn test_estimate_disabled_by_default() {
         let cli = Cli::parse_from(["rlph", "--once"]);
-        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
-        assert!(err.to_string().contains("poll_seconds must be > 0"));
+        let config = merge(ConfigFile::default(), &cli).unwrap();
+        assert!(config.estimate.is_none());
+        assert!(config.max_complexity.is_none());
     }
 
     #[test]
-    fn test_parse_unknown_field() {
-        let toml = r#"bogus = "value""#;
-        let err = parse_config(toml).unwrap_err();
-        assert!(err.to_string().contains("unknown field"));
-    }
+    fn test_max_complexity_enables_estimate() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let file = parse_config(r#"max_complexity = "M""#).unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(config.max_complexity, Some(Complexity::M));
+        assert_eq!(config.estimate.unwrap().prompt, "estimate");
 
-    #[test]
-    fn test_cli_overrides_config() {
-        let file = ConfigFile {
-            source: Some("github".to_string()),
-            runner: Some("claude".to_string()),
-            label: Some("file-label".to_string()),
-            poll_seconds: Some(120),
-            linear: Some(LinearConfigFile {
-                team: Some("ENG".to_string()),
-                ..Default::default()
-            }),
-            ..Default::default()
-        };
-        let cli = Cli::parse_from([
-            "rlph",
-            "--once",
-            "--source",
-            "linear",
-            "--label",
-            "cli-label",
-        ]);
+        let file = parse_config(
+            r#"
+[estimate]
+agent_model = "claude-haiku-4-5"
+"#,
+        )
+        .unwrap();
         let config = merge(file, &cli).unwrap();
-        assert_eq!(config.source, "linear"); // CLI wins
-        assert_eq!(config.label, "cli-label"); // CLI wins
-        assert_eq!(config.runner, RunnerKind::Claude); // file value kept
-        assert_eq!(config.poll_seconds, 120); // file value kept
-        assert!(config.once);
+        assert!(config.max_complexity.is_none());
+        assert_eq!(
+            config.estimate.unwrap().agent_model.as_deref(),
+            Some("claude-haiku-4-5")
+        );
     }
 
     #[test]
-    fn test_defaults_applied() {
-        let file = ConfigFile::default();
+    fn test_invalid_max_complexity_rejected() {
         let cli = Cli::parse_from(["rlph", "--once"]);
-        let config = merge(file, &cli).unwrap();
-        assert_eq!(config.source, "github");
-        assert_eq!(config.runner, RunnerKind::Claude);
-        assert_eq!(config.submission, "github");
-        assert_eq!(config.label, "rlph");
-        assert_eq!(config.poll_seconds, 30);
-        assert_eq!(config.agent_binary, "claude");
-        assert_eq!(config.agent_model.as_deref(), Some("claude-opus-4-6"));
-        assert_eq!(config.agent_effort.as_deref(), Some("high"));
-        assert_eq!(config.agent_timeout, Some(600));
-        assert_eq!(config.implement_timeout, Some(1800));
-        assert_eq!(config.agent_timeout_retries, 2);
+        let file = parse_config(r#"max_complexity = "XL""#).unwrap();
+        let err = merge(file, &cli).unwrap_err();
+        assert!(err.to_string().contains("unknown complexity"));
     }
 
     #[test]
@@ -926,6 +4021,56 @@ agent_effort = "high"
         );
     }
 
+    #[test]
+    fn test_review_preset_expands_and_is_overridden() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        let cli = Cli::parse_from(["rlph", "--once", "--runner", "claude"]);
+
+        std::fs::write(
+            cfg_dir.join("config.toml"),
+            r#"review_preset = "rust-strict""#,
+        )
+        .unwrap();
+        let config = Config::load_from(&cli, tmp.path()).unwrap();
+        let names: Vec<&str> = config
+            .review_phases
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            ["clippy", "correctness", "unsafe", "perf", "hygiene"]
+        );
+        assert_eq!(config.review_phases[1].runner, RunnerKind::Claude);
+        assert!(matches!(
+            config.review_phases[0].kind,
+            ReviewPhaseKind::Command {
+                format: AnalyzerFormat::Clippy,
+                ..
+            }
+        ));
+
+        std::fs::write(
+            cfg_dir.join("config.toml"),
+            r#"
+review_preset = "rust-strict"
+
+[[review_phases]]
+name = "correctness"
+prompt = "correctness-review"
+"#,
+        )
+        .unwrap();
+        let config = Config::load_from(&cli, tmp.path()).unwrap();
+        assert_eq!(config.review_phases.len(), 1);
+
+        std::fs::write(cfg_dir.join("config.toml"), r#"review_preset = "paranoid""#).unwrap();
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("unknown review_preset: paranoid"));
+    }
+
     #[test]
     fn test_review_phases_duplicate_name_rejected() {
         let tmp = tempfile::tempdir().unwrap();
@@ -949,6 +4094,52 @@ prompt = "other-review"
         assert!(err.to_string().contains("duplicate review phase name"));
     }
 
+    #[test]
+    fn test_review_phase_filter_and_skip_review() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        std::fs::write(
+            cfg_dir.join("config.toml"),
+            r#"
+review_phase_filter = ["correctness"]
+
+[[review_phases]]
+name = "correctness"
+prompt = "correctness-review"
+
+[[review_phases]]
+name = "security"
+prompt = "security-review"
+"#,
+        )
+        .unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = Config::load_from(&cli, tmp.path()).unwrap();
+        let active: Vec<&str> = config
+            .active_review_phases()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(active, vec!["correctness"]);
+        assert_eq!(config.skipped_review_phases(), vec!["security"]);
+        assert!(!config.skip_review);
+
+        // The CLI overrides the file.
+        let cli = Cli::parse_from(["rlph", "--once", "--review-phases", "security"]);
+        let config = Config::load_from(&cli, tmp.path()).unwrap();
+        assert_eq!(config.skipped_review_phases(), vec!["correctness"]);
+
+        let cli = Cli::parse_from(["rlph", "--once", "--skip-review"]);
+        assert!(Config::load_from(&cli, tmp.path()).unwrap().skip_review);
+
+        let cli = Cli::parse_from(["rlph", "--once", "--review-phases", "style"]);
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(
+            err.to_string().contains("unknown review phase 'style'"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn test_review_aggregate_and_fix_parsed() {
         let tmp = tempfile::tempdir().unwrap();
@@ -1040,6 +4231,105 @@ prompt = ""
         assert!(err.to_string().contains("prompt must not be empty"));
     }
 
+    #[test]
+    fn test_review_phase_command_type() {
+        let file = parse_config(
+            r#"
+[[review_phases]]
+name = "clippy"
+type = "command"
+command = "cargo clippy --message-format=json"
+
+[[review_phases]]
+name = "lint"
+type = "command"
+command = "make lint-json"
+format = "eslint"
+"#,
+        )
+        .unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(
+            config.review_phases[0].kind,
+            ReviewPhaseKind::Command {
+                command: "cargo clippy --message-format=json".to_string(),
+                format: AnalyzerFormat::Clippy,
+            }
+        );
+        assert!(matches!(
+            config.review_phases[1].kind,
+            ReviewPhaseKind::Command {
+                format: AnalyzerFormat::Eslint,
+                ..
+            }
+        ));
+
+        for (toml, expected) in [
+            (
+                "[[review_phases]]\nname = \"x\"\ntype = \"command\"",
+                "no command",
+            ),
+            (
+                "[[review_phases]]\nname = \"x\"\ntype = \"command\"\ncommand = \"make lint\"",
+                "cannot detect analyzer output format",
+            ),
+            (
+                "[[review_phases]]\nname = \"x\"\nprompt = \"p\"\ncommand = \"ruff check\"",
+                "require type = \"command\" or \"coverage\"",
+            ),
+            (
+                "[[review_phases]]\nname = \"x\"\ntype = \"script\"",
+                "unknown type 'script'",
+            ),
+        ] {
+            let err = merge(parse_config(toml).unwrap(), &cli).unwrap_err();
+            assert!(err.to_string().contains(expected), "got: {err}");
+        }
+    }
+
+    #[test]
+    fn test_review_phase_coverage_type() {
+        let cli = Cli::parse_from(["rlph", "--once"]);
+        let file =
+            parse_config("[[review_phases]]\nname = \"coverage\"\ntype = \"coverage\"").unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(
+            config.review_phases[0].kind,
+            ReviewPhaseKind::Coverage {
+                command: None,
+                threshold: DEFAULT_COVERAGE_THRESHOLD,
+            }
+        );
+
+        let file = parse_config(
+            "[[review_phases]]\nname = \"cov\"\ntype = \"coverage\"\ncommand = \"cargo llvm-cov --lcov\"\nthreshold = 90",
+        )
+        .unwrap();
+        let config = merge(file, &cli).unwrap();
+        assert_eq!(
+            config.review_phases[0].kind,
+            ReviewPhaseKind::Coverage {
+                command: Some("cargo llvm-cov --lcov".to_string()),
+                threshold: 90.0,
+            }
+        );
+
+        for (toml, expected) in [
+            (
+                "[[review_phases]]\nname = \"x\"\ntype = \"coverage\"\nthreshold = 101",
+                "between 0 and 100",
+            ),
+            (
+                "[[review_phases]]\nname = \"x\"\ntype = \"command\"\ncommand = \"ruff check\"\nthreshold = 50",
+                "threshold requires type = \"coverage\"",
+            ),
+        ] {
+            let err = merge(parse_config(toml).unwrap(), &cli).unwrap_err();
+            assert!(err.to_string().contains(expected), "got: {err}");
+        }
+    }
+
     #[test]
     fn test_review_phase_invalid_runner_rejected() {
         let tmp = tempfile::tempdir().unwrap();
@@ -1301,6 +4591,100 @@ prompt = "review-fix"
         );
     }
 
+    #[test]
+    fn test_agent_temperature_and_extra_args_per_phase() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        let write = |toml: &str| std::fs::write(cfg_dir.join("config.toml"), toml).unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+
+        write(
+            r#"
+runner = "codex"
+agent_temperature = 0.8
+agent_extra_args = ["--flag", "val"]
+
+[[review_phases]]
+name = "check"
+prompt = "check-review"
+agent_temperature = 0.1
+
+[[review_phases]]
+name = "style"
+prompt = "style-review"
+runner = "claude"
+agent_extra_args = []
+"#,
+        );
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("review phase 'style': agent_temperature is only supported by codex"),
+            "{err}"
+        );
+
+        write(
+            r#"
+runner = "codex"
+agent_temperature = 0.8
+agent_extra_args = ["--flag", "val"]
+
+[[review_phases]]
+name = "check"
+prompt = "check-review"
+agent_temperature = 0.1
+
+[fix]
+agent_extra_args = []
+"#,
+        );
+        let config = Config::load_from(&cli, tmp.path()).unwrap();
+        assert_eq!(config.agent_temperature, Some(0.8));
+        assert_eq!(config.agent_extra_args, vec!["--flag", "val"]);
+        assert_eq!(config.review_phases[0].agent_temperature, Some(0.1));
+        assert_eq!(
+            config.review_phases[0].agent_extra_args,
+            vec!["--flag", "val"]
+        );
+        assert_eq!(config.review_aggregate.agent_temperature, Some(0.8));
+        assert!(config.fix.agent_extra_args.is_empty());
+
+        write("runner = \"codex\"\nagent_temperature = 2.5\n");
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("between 0.0 and 2.0"), "{err}");
+    }
+
+    #[test]
+    fn test_agent_identity() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg_dir = tmp.path().join(".rlph");
+        std::fs::create_dir_all(&cfg_dir).unwrap();
+        let write = |toml: &str| std::fs::write(cfg_dir.join("config.toml"), toml).unwrap();
+        let cli = Cli::parse_from(["rlph", "--once"]);
+
+        let config = Config::load_from(&cli, tmp.path()).unwrap();
+        assert_eq!(config.agent_identity, AgentIdentity::default());
+
+        write("codex_profile = \"rlph-bot\"\nclaude_config_dir = \"/srv/rlph/claude\"\n");
+        let config = Config::load_from(&cli, tmp.path()).unwrap();
+        let expected = AgentIdentity {
+            codex_profile: Some("rlph-bot".to_string()),
+            claude_config_dir: Some(PathBuf::from("/srv/rlph/claude")),
+        };
+        assert_eq!(config.agent_identity, expected);
+        assert_eq!(config.review_phases[0].agent_identity, expected);
+        assert_eq!(config.fix.agent_identity, expected);
+
+        write("codex_profile = \"--yolo\"\n");
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("invalid codex_profile"), "{err}");
+
+        write("claude_config_dir = \".claude-rlph\"\n");
+        let err = Config::load_from(&cli, tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("absolute path"), "{err}");
+    }
+
     #[test]
     fn test_opencode_variant_plumbed_to_review_phases() {
         let tmp = tempfile::tempdir().unwrap();
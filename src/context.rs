@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// Everything rlph would hand the implement agent for one task, assembled by
+/// `rlph context` for inspection or for pasting into a chat manually.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextBundle {
+    pub task_id: String,
+    /// The implement prompt exactly as the agent would receive it.
+    pub prompt: String,
+    /// Every prompt variable, keyed by name.
+    pub vars: BTreeMap<String, String>,
+    /// The task as written in the source, before prompt sanitization.
+    pub task_markdown: String,
+    /// Tasks this one depends on, parsed from its description.
+    pub dependencies: Vec<u64>,
+    /// Diff of an existing task worktree against its base branch, if any.
+    pub diff: Option<String>,
+}
+
+impl ContextBundle {
+    /// `.rlph/context/<task_id>` under the repository root.
+    pub fn default_dir(repo_root: &Path, task_id: &str) -> PathBuf {
+        repo_root.join(".rlph").join("context").join(task_id)
+    }
+
+    /// Write the bundle into `dir`, one file per part, and return the paths
+    /// written. Files left from an earlier export are replaced.
+    pub fn write_to(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let vars_dir = dir.join("vars");
+        if vars_dir.exists() {
+            std::fs::remove_dir_all(&vars_dir).map_err(|e| {
+                Error::State(format!("failed to clear {}: {e}", vars_dir.display()))
+            })?;
+        }
+        std::fs::create_dir_all(&vars_dir)
+            .map_err(|e| Error::State(format!("failed to create {}: {e}", vars_dir.display())))?;
+
+        let mut files = vec![
+            ("prompt.md".to_string(), self.prompt.clone()),
+            ("task.md".to_string(), self.task_markdown.clone()),
+            (
+                "dependencies.md".to_string(),
+                format_dependencies(&self.dependencies),
+            ),
+        ];
+        if let Some(diff) = &self.diff {
+            files.push(("diff.patch".to_string(), diff.clone()));
+        } else {
+            let _ = std::fs::remove_file(dir.join("diff.patch"));
+        }
+        files.extend(
+            self.vars
+                .iter()
+                .filter(|(_, value)| !value.trim().is_empty())
+                .map(|(name, value)| (format!("vars/{name}.txt"), value.clone())),
+        );
+
+        let mut written = Vec::with_capacity(files.len());
+        for (name, content) in files {
+            let path = dir.join(name);
+            std::fs::write(&path, content)
+                .map_err(|e| Error::State(format!("failed to write {}: {e}", path.display())))?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+fn format_dependencies(deps: &[u64]) -> String {
+    if deps.is_empty() {
+        return "No dependencies.\n".to_string();
+    }
+    deps.iter().map(|n| format!("- #{n}\n")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_to_lays_out_bundle_and_replaces_stale_files() {
+        let dir = TempDir::new().unwrap();
+        let mut bundle = ContextBundle {
+            task_id: "gh-7".to_string(),
+            prompt: "Implement #7".to_string(),
+            vars: BTreeMap::from([
+                ("issue_title".to_string(), "Fix login".to_string()),
+                ("previous_attempts".to_string(), String::new()),
+            ]),
+            task_markdown: "# Fix login\n".to_string(),
+            dependencies: vec![3, 5],
+            diff: Some("diff --git a/x b/x\n".to_string()),
+        };
+
+        let written = bundle.write_to(dir.path()).unwrap();
+        assert_eq!(written.len(), 5);
+        let read = |name: &str| std::fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read("prompt.md"), "Implement #7");
+        assert_eq!(read("dependencies.md"), "- #3\n- #5\n");
+        assert_eq!(read("vars/issue_title.txt"), "Fix login");
+        assert!(!dir.path().join("vars/previous_attempts.txt").exists());
+
+        bundle.diff = None;
+        bundle.dependencies.clear();
+        bundle.vars.remove("issue_title");
+        bundle.write_to(dir.path()).unwrap();
+        assert!(!dir.path().join("diff.patch").exists());
+        assert!(!dir.path().join("vars/issue_title.txt").exists());
+        assert_eq!(read("dependencies.md"), "No dependencies.\n");
+    }
+}
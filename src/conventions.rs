@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use tracing::{debug, warn};
+
+/// Cap on the combined conventions text injected into prompts.
+pub const MAX_CONVENTIONS_CHARS: usize = 16_000;
+
+/// Read the configured conventions files (e.g. `AGENTS.md`, `CONTRIBUTING.md`)
+/// from `dir` into the `repo_conventions` prompt variable.
+///
+/// Each file becomes a `### <path>` section. Missing files are skipped, and the
+/// result is truncated at [`MAX_CONVENTIONS_CHARS`]. Returns an empty string when
+/// no file could be read.
+pub fn load_conventions(dir: &Path, files: &[String]) -> String {
+    let mut sections = Vec::new();
+    for file in files {
+        let path = dir.join(file);
+        match std::fs::read_to_string(&path) {
+            Ok(content) if !content.trim().is_empty() => {
+                sections.push(format!("### {file}\n\n{}", content.trim()));
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                debug!(path = %path.display(), "conventions file not found");
+            }
+            Err(e) => warn!(path = %path.display(), error = %e, "failed to read conventions file"),
+        }
+    }
+    truncate_conventions(sections.join("\n\n"))
+}
+
+fn truncate_conventions(text: String) -> String {
+    if text.chars().count() <= MAX_CONVENTIONS_CHARS {
+        return text;
+    }
+    let mut truncated: String = text.chars().take(MAX_CONVENTIONS_CHARS).collect();
+    truncated.push_str("\n\n[conventions truncated]");
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_conventions_sections_and_missing_files() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("AGENTS.md"), "Run cargo fmt.\n").unwrap();
+        std::fs::create_dir(dir.path().join("docs")).unwrap();
+        std::fs::write(dir.path().join("docs/STYLE.md"), "No unwrap.").unwrap();
+        std::fs::write(dir.path().join("EMPTY.md"), "  \n").unwrap();
+
+        let files = ["AGENTS.md", "MISSING.md", "EMPTY.md", "docs/STYLE.md"].map(String::from);
+        assert_eq!(
+            load_conventions(dir.path(), &files),
+            "### AGENTS.md\n\nRun cargo fmt.\n\n### docs/STYLE.md\n\nNo unwrap."
+        );
+        assert_eq!(load_conventions(dir.path(), &[]), "");
+    }
+
+    #[test]
+    fn test_load_conventions_is_capped() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("AGENTS.md"),
+            "é".repeat(MAX_CONVENTIONS_CHARS),
+        )
+        .unwrap();
+
+        let text = load_conventions(dir.path(), &["AGENTS.md".to_string()]);
+        assert!(text.ends_with("[conventions truncated]"));
+        assert!(text.chars().count() < MAX_CONVENTIONS_CHARS + 30);
+    }
+}
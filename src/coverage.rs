@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::analyzer::{relative_path, run_shell};
+use crate::error::{Error, Result};
+use crate::review_schema::{ReviewFinding, Severity};
+use crate::worktree::WorktreeManager;
+
+/// Default command for coverage review phases.
+pub const DEFAULT_COVERAGE_COMMAND: &str = "cargo llvm-cov --json";
+
+/// Default minimum changed-line coverage, in percent.
+pub const DEFAULT_COVERAGE_THRESHOLD: f64 = 80.0;
+
+/// Executed-line counts per file, keyed by worktree-relative path.
+pub type LineCoverage = BTreeMap<String, BTreeMap<u32, u64>>;
+
+/// Run the coverage command, compare coverage of lines added since `base_ref`
+/// against `threshold`, and return findings for uncovered new code.
+pub async fn run_coverage_gate(
+    command: &str,
+    threshold: f64,
+    base_ref: &str,
+    working_dir: &Path,
+    timeout: Option<Duration>,
+    log_prefix: &str,
+) -> Result<Vec<ReviewFinding>> {
+    let output = run_shell(command, working_dir, timeout, log_prefix).await?;
+    let stdout = output.stdout_lines.join("\n");
+    let coverage = parse_coverage(&stdout, working_dir).map_err(|e| {
+        if output.success() {
+            e
+        } else {
+            Error::Process(format!(
+                "coverage command `{command}` exited with code {}: {e}\n{}",
+                output.exit_code,
+                output.stderr_lines.join("\n")
+            ))
+        }
+    })?;
+
+    let diff = tokio::process::Command::new("git")
+        .args([
+            "diff",
+            "--unified=0",
+            "--no-color",
+            &format!("{base_ref}...HEAD"),
+        ])
+        .current_dir(working_dir)
+        .output()
+        .await
+        .map_err(|e| Error::Process(format!("failed to run git diff: {e}")))?;
+    if !diff.status.success() {
+        return Err(Error::Process(format!(
+            "git diff {base_ref}...HEAD failed: {}",
+            String::from_utf8_lossy(&diff.stderr)
+        )));
+    }
+    let added = added_lines(&String::from_utf8_lossy(&diff.stdout));
+    Ok(coverage_findings(&added, &coverage, threshold))
+}
+
+/// Lines added per file in a `--unified=0` diff.
+pub fn added_lines(diff: &str) -> BTreeMap<String, BTreeSet<u32>> {
+    let mut added: BTreeMap<String, BTreeSet<u32>> = BTreeMap::new();
+    let mut current: Option<String> = None;
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            current = None;
+        } else if let Some(target) = line.strip_prefix("+++ ") {
+            current = target.trim().strip_prefix("b/").map(str::to_string);
+        } else if line.starts_with("@@")
+            && let Some(path) = &current
+            && let Some(new) = line.split_whitespace().nth(2)
+            && let Some(new) = new.strip_prefix('+')
+        {
+            let (start, count) = match new.split_once(',') {
+                Some((s, c)) => (s.parse().unwrap_or(0), c.parse().unwrap_or(0)),
+                None => (new.parse().unwrap_or(0), 1),
+            };
+            added
+                .entry(path.clone())
+                .or_default()
+                .extend(start..start + count);
+        }
+    }
+    added.retain(|_, lines| !lines.is_empty());
+    added
+}
+
+/// Parse coverage output: an llvm-cov JSON export (`cargo llvm-cov --json`) or LCOV.
+pub fn parse_coverage(stdout: &str, working_dir: &Path) -> Result<LineCoverage> {
+    // llvm-cov prints the export as a single JSON line, possibly after test output.
+    if let Some(export) = stdout
+        .lines()
+        .rev()
+        .filter(|l| l.trim_start().starts_with('{'))
+        .find_map(|l| serde_json::from_str::<LlvmExport>(l.trim()).ok())
+    {
+        return Ok(llvm_line_coverage(&export, working_dir));
+    }
+    let lcov = parse_lcov(stdout, working_dir);
+    if lcov.is_empty() {
+        return Err(Error::Process(
+            "coverage output is neither an llvm-cov JSON export nor LCOV".to_string(),
+        ));
+    }
+    Ok(lcov)
+}
+
+#[derive(Deserialize)]
+struct LlvmExport {
+    data: Vec<LlvmData>,
+}
+
+#[derive(Deserialize)]
+struct LlvmData {
+    files: Vec<LlvmFile>,
+}
+
+#[derive(Deserialize)]
+struct LlvmFile {
+    filename: String,
+    /// `[line, col, count, has_count, is_region_entry, (is_gap_region)]`
+    segments: Vec<Vec<serde_json::Value>>,
+}
+
+struct Segment {
+    line: u32,
+    count: u64,
+    has_count: bool,
+    is_region_entry: bool,
+}
+
+fn parse_segment(raw: &[serde_json::Value]) -> Option<Segment> {
+    Some(Segment {
+        line: u32::try_from(raw.first()?.as_u64()?).ok()?,
+        count: raw.get(2)?.as_u64()?,
+        has_count: raw.get(3)?.as_bool()?,
+        is_region_entry: raw.get(4)?.as_bool()?,
+    })
+}
+
+/// Derive per-line counts from llvm-cov segments: a line's count is the larger of the
+/// region active when the line starts and any region entered on it.
+fn llvm_line_coverage(export: &LlvmExport, working_dir: &Path) -> LineCoverage {
+    let mut coverage = LineCoverage::new();
+    for file in export.data.iter().flat_map(|d| &d.files) {
+        let segments: Vec<Segment> = file
+            .segments
+            .iter()
+            .filter_map(|s| parse_segment(s))
+            .collect();
+        let Some(last) = segments.last() else {
+            continue;
+        };
+        let lines = coverage
+            .entry(relative_path(&file.filename, working_dir))
+            .or_default();
+        let mut active: Option<u64> = None;
+        let mut idx = 0;
+        for line in segments[0].line..=last.line {
+            let mut count = active;
+            while idx < segments.len() && segments[idx].line == line {
+                let seg = &segments[idx];
+                if seg.has_count && seg.is_region_entry {
+                    count = Some(count.map_or(seg.count, |c| c.max(seg.count)));
+                }
+                active = seg.has_count.then_some(seg.count);
+                idx += 1;
+            }
+            if let Some(count) = count {
+                lines.insert(line, count);
+            }
+        }
+    }
+    coverage
+}
+
+fn parse_lcov(stdout: &str, working_dir: &Path) -> LineCoverage {
+    let mut coverage = LineCoverage::new();
+    let mut current: Option<String> = None;
+    for line in stdout.lines().map(str::trim) {
+        if let Some(path) = line.strip_prefix("SF:") {
+            current = Some(relative_path(path, working_dir));
+        } else if line == "end_of_record" {
+            current = None;
+        } else if let Some(data) = line.strip_prefix("DA:")
+            && let Some(path) = &current
+        {
+            let mut parts = data.split(',');
+            if let (Some(Ok(line)), Some(Ok(count))) = (
+                parts.next().map(str::parse::<u32>),
+                parts.next().map(str::parse::<u64>),
+            ) {
+                coverage
+                    .entry(path.clone())
+                    .or_default()
+                    .insert(line, count);
+            }
+        }
+    }
+    coverage
+}
+
+/// Findings for uncovered added lines when changed-line coverage is below `threshold`.
+/// Added lines without coverage data (comments, blank lines, untested languages) are
+/// ignored. Contiguous uncovered lines are reported as one finding.
+pub fn coverage_findings(
+    added: &BTreeMap<String, BTreeSet<u32>>,
+    coverage: &LineCoverage,
+    threshold: f64,
+) -> Vec<ReviewFinding> {
+    let mut instrumented = 0usize;
+    let mut uncovered: BTreeMap<&str, Vec<u32>> = BTreeMap::new();
+    for (file, lines) in added {
+        let Some(counts) = coverage.get(file) else {
+            continue;
+        };
+        for line in lines {
+            if let Some(&count) = counts.get(line) {
+                instrumented += 1;
+                if count == 0 {
+                    uncovered.entry(file).or_default().push(*line);
+                }
+            }
+        }
+    }
+    if instrumented == 0 {
+        return Vec::new();
+    }
+    let missed: usize = uncovered.values().map(Vec::len).sum();
+    let percent = 100.0 * (instrumented - missed) as f64 / instrumented as f64;
+    if percent >= threshold {
+        return Vec::new();
+    }
+
+    let mut findings = Vec::new();
+    for (file, lines) in uncovered {
+        let mut start = lines[0];
+        let mut end = start;
+        for &line in lines[1..].iter().chain(std::iter::once(&u32::MAX)) {
+            if line == end + 1 {
+                end = line;
+                continue;
+            }
+            let range = if start == end {
+                format!("Line {start} is")
+            } else {
+                format!("Lines {start}-{end} are")
+            };
+            let stem = Path::new(file)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            findings.push(ReviewFinding {
+                id: WorktreeManager::slugify(&format!("uncovered-{stem}-{start}")),
+                file: file.to_string(),
+                line: start,
+                severity: Severity::Warning,
+                description: format!(
+                    "{range} new code not covered by tests (changed-line coverage {percent:.1}% < {threshold}% threshold)"
+                ),
+                category: Some("coverage".to_string()),
+                depends_on: vec![],
+                extra: Default::default(),
+            });
+            start = line;
+            end = line;
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_added_lines() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n@@ -3,0 +4,2 @@\n+x\n+y\n@@ -10 +12 @@\n-a\n+b\n@@ -20,2 +22,0 @@\n-c\n-d\ndiff --git a/old.rs b/old.rs\n--- a/old.rs\n+++ /dev/null\n@@ -1 +0,0 @@\n-z\n";
+        let added = added_lines(diff);
+        assert_eq!(added.len(), 1);
+        assert_eq!(
+            added["src/a.rs"].iter().copied().collect::<Vec<_>>(),
+            vec![4, 5, 12]
+        );
+    }
+
+    #[test]
+    fn test_parse_lcov() {
+        let lcov = "TN:\nSF:/repo/src/a.rs\nDA:1,3\nDA:2,0\nend_of_record\nSF:src/b.rs\nDA:7,1\nend_of_record\n";
+        let coverage = parse_coverage(lcov, Path::new("/repo")).unwrap();
+        assert_eq!(coverage["src/a.rs"][&1], 3);
+        assert_eq!(coverage["src/a.rs"][&2], 0);
+        assert_eq!(coverage["src/b.rs"][&7], 1);
+        assert!(parse_coverage("running 3 tests", Path::new("/repo")).is_err());
+    }
+
+    #[test]
+    fn test_parse_llvm_json_segments() {
+        // Lines 1-3 in a covered region; an uncovered region spans 4-5; line 6 is a gap
+        // closing the function with a covered count again.
+        let json = r#"{"data":[{"files":[{"filename":"/repo/src/lib.rs","segments":[
+            [1,1,5,true,true,false],
+            [4,5,0,true,true,false],
+            [5,6,5,true,false,false],
+            [6,2,0,false,false,false]
+        ]}]}],"type":"llvm.coverage.json.export","version":"2.0.1"}"#;
+        let stdout = format!(
+            "running 2 tests\ntest result: ok\n{}",
+            json.replace('\n', "")
+        );
+        let coverage = parse_coverage(&stdout, Path::new("/repo")).unwrap();
+        let lines = &coverage["src/lib.rs"];
+        assert_eq!(lines[&1], 5);
+        assert_eq!(lines[&3], 5);
+        assert_eq!(lines[&4], 5);
+        assert_eq!(lines[&5], 0);
+        assert_eq!(lines[&6], 5);
+    }
+
+    #[test]
+    fn test_coverage_findings_threshold_and_ranges() {
+        let added = BTreeMap::from([("src/a.rs".to_string(), BTreeSet::from([1, 2, 3, 4, 5, 9]))]);
+        let coverage = LineCoverage::from([(
+            "src/a.rs".to_string(),
+            BTreeMap::from([(1, 1), (2, 0), (3, 0), (4, 2), (9, 0)]),
+        )]);
+
+        // 2 of 5 instrumented lines covered = 40%
+        let findings = coverage_findings(&added, &coverage, 80.0);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].id, "uncovered-a-rs-2");
+        assert_eq!(findings[0].line, 2);
+        assert!(
+            findings[0]
+                .description
+                .starts_with("Lines 2-3 are new code not covered by tests (changed-line coverage 40.0% < 80% threshold)")
+        );
+        assert!(findings[1].description.starts_with("Line 9 is"));
+        assert_eq!(findings[1].category.as_deref(), Some("coverage"));
+
+        assert!(coverage_findings(&added, &coverage, 40.0).is_empty());
+        assert!(coverage_findings(&added, &LineCoverage::new(), 100.0).is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
 use regex::Regex;
+use serde::Serialize;
 use tracing::warn;
 
 use crate::sources::Task;
@@ -43,6 +44,15 @@ pub struct DependencyGraph {
     edges: HashMap<u64, HashSet<u64>>,
 }
 
+/// How much downstream work finishing a task would unblock.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct UnblockingPower {
+    /// Tasks that depend on this one, directly or transitively.
+    pub dependents: usize,
+    /// Length of the longest chain of dependents (critical-path depth).
+    pub depth: usize,
+}
+
 #[derive(Default)]
 struct TarjanState {
     index: usize,
@@ -160,6 +170,91 @@ impl DependencyGraph {
         }
     }
 
+    /// task_id -> set of task_ids that depend on it
+    fn reverse_edges(&self) -> HashMap<u64, HashSet<u64>> {
+        let mut reverse: HashMap<u64, HashSet<u64>> = HashMap::new();
+        for (&task, deps) in &self.edges {
+            for &dep in deps {
+                if dep != task {
+                    reverse.entry(dep).or_default().insert(task);
+                }
+            }
+        }
+        reverse
+    }
+
+    /// Count the tasks downstream of `id` and the depth of its longest
+    /// dependent chain. Cycles are walked once and do not inflate either value.
+    pub fn unblocking_power(&self, id: u64) -> UnblockingPower {
+        let reverse = self.reverse_edges();
+        let mut depths = HashMap::new();
+        Self::power_with(&reverse, id, &mut depths)
+    }
+
+    fn power_with(
+        reverse: &HashMap<u64, HashSet<u64>>,
+        id: u64,
+        depths: &mut HashMap<u64, usize>,
+    ) -> UnblockingPower {
+        let mut seen = HashSet::from([id]);
+        let mut queue = vec![id];
+        while let Some(node) = queue.pop() {
+            for &dependent in reverse.get(&node).into_iter().flatten() {
+                if seen.insert(dependent) {
+                    queue.push(dependent);
+                }
+            }
+        }
+        let mut on_path = HashSet::new();
+        UnblockingPower {
+            dependents: seen.len() - 1,
+            depth: Self::depth(reverse, id, depths, &mut on_path),
+        }
+    }
+
+    fn depth(
+        reverse: &HashMap<u64, HashSet<u64>>,
+        node: u64,
+        memo: &mut HashMap<u64, usize>,
+        on_path: &mut HashSet<u64>,
+    ) -> usize {
+        if let Some(&d) = memo.get(&node) {
+            return d;
+        }
+        on_path.insert(node);
+        let mut best = 0;
+        for &dependent in reverse.get(&node).into_iter().flatten() {
+            // Back edges close a cycle; following them would never terminate.
+            if !on_path.contains(&dependent) {
+                best = best.max(1 + Self::depth(reverse, dependent, memo, on_path));
+            }
+        }
+        on_path.remove(&node);
+        memo.insert(node, best);
+        best
+    }
+
+    /// Order tasks so those that unblock the most downstream work come first:
+    /// by transitive dependents, then critical-path depth. The sort is stable,
+    /// so ties keep their incoming order. Tasks with non-numeric IDs rank last.
+    pub fn rank_by_unblocking_power(&self, tasks: Vec<Task>) -> Vec<(Task, UnblockingPower)> {
+        let reverse = self.reverse_edges();
+        let mut depths = HashMap::new();
+        let mut ranked: Vec<(Task, UnblockingPower)> = tasks
+            .into_iter()
+            .map(|task| {
+                let power = task
+                    .id
+                    .parse()
+                    .map(|id| Self::power_with(&reverse, id, &mut depths))
+                    .unwrap_or_default();
+                (task, power)
+            })
+            .collect();
+        ranked.sort_by_key(|(_, power)| std::cmp::Reverse(*power));
+        ranked
+    }
+
     /// Filter tasks, returning only those whose dependencies are all in `done_ids`.
     /// Cycle-internal blockers are ignored (with a warning logged), but external blockers
     /// on cycle tasks are still enforced.
@@ -210,6 +305,13 @@ mod tests {
             labels: vec![],
             url: String::new(),
             priority: None,
+            created_at: None,
+            updated_at: None,
+            milestone: None,
+            reactions: None,
+            assignee: None,
+            estimate: None,
+            project: None,
         }
     }
 
@@ -491,4 +593,89 @@ mod tests {
         assert!(ids.contains(&"2"));
         assert!(ids.contains(&"3"));
     }
+
+    // --- Unblocking power tests ---
+
+    #[test]
+    fn test_unblocking_power_counts_transitive_dependents() {
+        // 1 <- 2 <- 3, 1 <- 4, 5 standalone
+        let tasks = vec![
+            make_task(1, "No deps"),
+            make_task(2, "Blocked by #1"),
+            make_task(3, "Depends on #2"),
+            make_task(4, "Blocked by #1"),
+            make_task(5, "No deps"),
+        ];
+        let graph = DependencyGraph::build(&tasks);
+        assert_eq!(
+            graph.unblocking_power(1),
+            UnblockingPower {
+                dependents: 3,
+                depth: 2
+            }
+        );
+        assert_eq!(
+            graph.unblocking_power(2),
+            UnblockingPower {
+                dependents: 1,
+                depth: 1
+            }
+        );
+        assert_eq!(graph.unblocking_power(5), UnblockingPower::default());
+    }
+
+    #[test]
+    fn test_unblocking_power_terminates_on_cycles() {
+        let tasks = vec![
+            make_task(1, "Blocked by #3"),
+            make_task(2, "Blocked by #1"),
+            make_task(3, "Blocked by #2"),
+            make_task(4, "Blocked by #4"),
+        ];
+        let graph = DependencyGraph::build(&tasks);
+        let power = graph.unblocking_power(1);
+        assert_eq!(power.dependents, 2);
+        assert_eq!(power.depth, 2);
+        assert_eq!(graph.unblocking_power(4), UnblockingPower::default());
+    }
+
+    #[test]
+    fn test_rank_by_unblocking_power() {
+        // 10 unblocks one task; 20 unblocks a chain of two; 30 and 40 unblock nothing.
+        let tasks = vec![
+            make_task(30, "No deps"),
+            make_task(10, "No deps"),
+            make_task(40, "No deps"),
+            make_task(20, "No deps"),
+            make_task(11, "Blocked by #10"),
+            make_task(21, "Blocked by #20"),
+            make_task(22, "Blocked by #21"),
+        ];
+        let graph = DependencyGraph::build(&tasks);
+        let eligible = graph.filter_eligible(tasks, &HashSet::new());
+        let ranked = graph.rank_by_unblocking_power(eligible);
+        let ids: Vec<&str> = ranked.iter().map(|(t, _)| t.id.as_str()).collect();
+        // Ties (30, 40) keep their incoming order.
+        assert_eq!(ids, vec!["20", "10", "30", "40"]);
+        assert_eq!(ranked[0].1.dependents, 2);
+        assert_eq!(ranked[0].1.depth, 2);
+    }
+
+    #[test]
+    fn test_rank_breaks_dependent_ties_by_depth() {
+        // 1 unblocks 2 and 3 side by side; 4 unblocks 5 which unblocks 6.
+        let tasks = vec![
+            make_task(1, "No deps"),
+            make_task(2, "Blocked by #1"),
+            make_task(3, "Blocked by #1"),
+            make_task(4, "No deps"),
+            make_task(5, "Blocked by #4"),
+            make_task(6, "Blocked by #5"),
+        ];
+        let graph = DependencyGraph::build(&tasks);
+        let eligible = graph.filter_eligible(tasks, &HashSet::new());
+        let ranked = graph.rank_by_unblocking_power(eligible);
+        let ids: Vec<&str> = ranked.iter().map(|(t, _)| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["4", "1"]);
+    }
 }
@@ -0,0 +1,100 @@
+//! Disk usage of task worktrees and the `max_worktree_disk_mb` quota.
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tracing::debug;
+
+/// How often a running agent's worktree is measured against the quota.
+pub const QUOTA_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Total size of the files under `path`, in bytes. Symlinks are not followed,
+/// so shared caches linked into a worktree are not counted; unreadable entries
+/// are skipped.
+pub fn dir_size(path: &Path) -> u64 {
+    let mut total = 0;
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                pending.push(entry.path());
+            } else if meta.is_file() {
+                total += meta.len();
+            }
+        }
+    }
+    total
+}
+
+/// Whole megabytes in `bytes`, rounded up so a non-empty tree is never 0 MB.
+pub fn to_mb(bytes: u64) -> u64 {
+    bytes.div_ceil(BYTES_PER_MB)
+}
+
+/// [`dir_size`] in megabytes, measured off the async runtime.
+pub async fn dir_size_mb(path: &Path) -> u64 {
+    let path: PathBuf = path.to_path_buf();
+    tokio::task::spawn_blocking(move || to_mb(dir_size(&path)))
+        .await
+        .unwrap_or_default()
+}
+
+/// Await `fut`, measuring `dir` every `every`. Once it is larger than
+/// `limit_mb`, `fut` is dropped and the measured size is returned as the error.
+/// Dropping a [`spawn_and_stream`](crate::process::spawn_and_stream) run
+/// kills the agent's whole process group, as its timeout does.
+pub async fn with_disk_quota<F: Future>(
+    fut: F,
+    dir: &Path,
+    limit_mb: u64,
+    every: Duration,
+) -> Result<F::Output, u64> {
+    tokio::pin!(fut);
+    let mut interval = tokio::time::interval(every);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            output = &mut fut => return Ok(output),
+            _ = interval.tick() => {
+                let used = dir_size_mb(dir).await;
+                debug!(path = %dir.display(), used_mb = used, limit_mb, "worktree disk usage");
+                if used > limit_mb {
+                    return Err(used);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_quota_stops_future_once_exceeded() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("sub/big"), vec![0u8; 3 * 1024 * 1024]).unwrap();
+        std::fs::write(dir.path().join("small"), b"x").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.path().join("sub"), dir.path().join("link")).unwrap();
+        assert_eq!(dir_size(dir.path()), 3 * 1024 * 1024 + 1);
+        assert_eq!(to_mb(dir_size(dir.path())), 4);
+
+        let slow = tokio::time::sleep(Duration::from_secs(60));
+        let result = with_disk_quota(slow, dir.path(), 2, Duration::from_millis(10)).await;
+        assert_eq!(result, Err(4));
+
+        let result = with_disk_quota(async { 7 }, dir.path(), 2, Duration::from_secs(60)).await;
+        assert_eq!(result, Ok(7));
+    }
+}
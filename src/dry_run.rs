@@ -0,0 +1,426 @@
+//! Dry-run decorators for a task source and submission backend.
+//!
+//! In a dry run, reads reach the wrapped source and backend as usual, while
+//! every mutation is recorded in a [`DryRunLog`] with its exact payload instead
+//! of being sent. The orchestrator reports the recorded actions and writes them
+//! to the run summary, so a dry run shows everything a real run would change.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tracing::info;
+
+use crate::check_run::ReviewCheck;
+use crate::config::MergeMethod;
+use crate::error::Result;
+use crate::sources::{Task, TaskSource};
+use crate::submission::{CiStatus, PrComment, PrContext, PrState, SubmissionBackend, SubmitResult};
+use crate::suggestion::SuggestionComment;
+
+/// A mutation a dry run skipped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DryRunAction {
+    /// `source`, `submission`, or `git`.
+    pub target: String,
+    /// The method that would have run, e.g. `mark_in_progress`.
+    pub action: String,
+    /// Its arguments.
+    pub payload: Value,
+}
+
+impl fmt::Display for DryRunAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{} {}", self.target, self.action, self.payload)
+    }
+}
+
+/// Mutations skipped by a dry run, in the order they would have happened.
+#[derive(Debug, Clone, Default)]
+pub struct DryRunLog {
+    actions: Arc<Mutex<Vec<DryRunAction>>>,
+}
+
+impl DryRunLog {
+    pub fn record(&self, target: &str, action: &str, payload: Value) {
+        info!(target, action, payload = %payload, "dry run — skipped");
+        self.actions.lock().unwrap().push(DryRunAction {
+            target: target.to_string(),
+            action: action.to_string(),
+            payload,
+        });
+    }
+
+    /// Take the actions recorded since the last call.
+    pub fn drain(&self) -> Vec<DryRunAction> {
+        std::mem::take(&mut *self.actions.lock().unwrap())
+    }
+}
+
+/// A task source that, given a [`DryRunLog`], records mutations there instead
+/// of performing them. Without one it passes every call through.
+pub struct DryRunSource<S> {
+    inner: S,
+    log: Option<DryRunLog>,
+}
+
+impl<S> DryRunSource<S> {
+    pub fn new(inner: S, log: Option<DryRunLog>) -> Self {
+        Self { inner, log }
+    }
+
+    /// Record `action` in a dry run; otherwise perform it with `send`.
+    fn mutate(
+        &self,
+        action: &str,
+        payload: impl FnOnce() -> Value,
+        send: impl FnOnce(&S) -> Result<()>,
+    ) -> Result<()> {
+        match &self.log {
+            Some(log) => {
+                log.record("source", action, payload());
+                Ok(())
+            }
+            None => send(&self.inner),
+        }
+    }
+}
+
+impl<S: TaskSource> TaskSource for DryRunSource<S> {
+    fn fetch_eligible_tasks(&self) -> Result<Vec<Task>> {
+        self.inner.fetch_eligible_tasks()
+    }
+
+    fn mark_in_progress(&self, task_id: &str) -> Result<()> {
+        self.mutate(
+            "mark_in_progress",
+            || json!({ "task_id": task_id }),
+            |inner| inner.mark_in_progress(task_id),
+        )
+    }
+
+    fn mark_in_review(&self, task_id: &str) -> Result<()> {
+        self.mutate(
+            "mark_in_review",
+            || json!({ "task_id": task_id }),
+            |inner| inner.mark_in_review(task_id),
+        )
+    }
+
+    fn get_task_details(&self, task_id: &str) -> Result<Task> {
+        self.inner.get_task_details(task_id)
+    }
+
+    fn get_tasks_details(&self, task_ids: &[&str]) -> Result<Vec<Task>> {
+        self.inner.get_tasks_details(task_ids)
+    }
+
+    fn is_task_eligible(&self, task_id: &str) -> Result<bool> {
+        self.inner.is_task_eligible(task_id)
+    }
+
+    fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>> {
+        self.inner.fetch_closed_task_ids()
+    }
+
+    fn fetch_closed_task_ids_since(&self, since: u64) -> Result<HashSet<u64>> {
+        self.inner.fetch_closed_task_ids_since(since)
+    }
+
+    fn fetch_open_tasks_with_label(&self, label: &str) -> Result<Vec<Task>> {
+        self.inner.fetch_open_tasks_with_label(label)
+    }
+
+    /// In a dry run, returns a placeholder task with an empty id.
+    fn create_task(&self, title: &str, body: &str, labels: &[String]) -> Result<Task> {
+        let Some(log) = &self.log else {
+            return self.inner.create_task(title, body, labels);
+        };
+        log.record(
+            "source",
+            "create_task",
+            json!({ "title": title, "body": body, "labels": labels }),
+        );
+        Ok(Task {
+            id: String::new(),
+            title: title.to_string(),
+            body: body.to_string(),
+            labels: labels.to_vec(),
+            url: String::new(),
+            priority: None,
+            created_at: None,
+            updated_at: None,
+            milestone: None,
+            reactions: None,
+            assignee: None,
+            estimate: None,
+            project: None,
+        })
+    }
+
+    fn add_label(&self, task_id: &str, label: &str) -> Result<()> {
+        self.mutate(
+            "add_label",
+            || json!({ "task_id": task_id, "label": label }),
+            |inner| inner.add_label(task_id, label),
+        )
+    }
+
+    fn release_task(&self, task_id: &str) -> Result<()> {
+        self.mutate(
+            "release_task",
+            || json!({ "task_id": task_id }),
+            |inner| inner.release_task(task_id),
+        )
+    }
+
+    fn mark_done(&self, task_id: &str) -> Result<()> {
+        self.mutate(
+            "mark_done",
+            || json!({ "task_id": task_id }),
+            |inner| inner.mark_done(task_id),
+        )
+    }
+
+    fn add_comment(&self, task_id: &str, body: &str) -> Result<()> {
+        self.mutate(
+            "add_comment",
+            || json!({ "task_id": task_id, "body": body }),
+            |inner| inner.add_comment(task_id, body),
+        )
+    }
+
+    fn attach_link(&self, task_id: &str, url: &str, title: &str) -> Result<()> {
+        self.mutate(
+            "attach_link",
+            || json!({ "task_id": task_id, "url": url, "title": title }),
+            |inner| inner.attach_link(task_id, url, title),
+        )
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A submission backend that, given a [`DryRunLog`], records mutations there
+/// instead of performing them. Without one it passes every call through.
+pub struct DryRunSubmission<B> {
+    inner: B,
+    log: Option<DryRunLog>,
+}
+
+impl<B> DryRunSubmission<B> {
+    pub fn new(inner: B, log: Option<DryRunLog>) -> Self {
+        Self { inner, log }
+    }
+
+    /// Record `action` in a dry run; otherwise perform it with `send`.
+    fn mutate(
+        &self,
+        action: &str,
+        payload: impl FnOnce() -> Value,
+        send: impl FnOnce(&B) -> Result<()>,
+    ) -> Result<()> {
+        match &self.log {
+            Some(log) => {
+                log.record("submission", action, payload());
+                Ok(())
+            }
+            None => send(&self.inner),
+        }
+    }
+}
+
+impl<B: SubmissionBackend> SubmissionBackend for DryRunSubmission<B> {
+    /// In a dry run, returns a result with an empty URL and no PR number.
+    fn submit(&self, branch: &str, base: &str, title: &str, body: &str) -> Result<SubmitResult> {
+        let Some(log) = &self.log else {
+            return self.inner.submit(branch, base, title, body);
+        };
+        log.record(
+            "submission",
+            "submit",
+            json!({ "branch": branch, "base": base, "title": title, "body": body }),
+        );
+        Ok(SubmitResult {
+            url: String::new(),
+            number: None,
+        })
+    }
+
+    fn find_existing_pr_for_issue(&self, issue_number: u64) -> Result<Option<u64>> {
+        self.inner.find_existing_pr_for_issue(issue_number)
+    }
+
+    fn upsert_review_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.mutate(
+            "upsert_review_comment",
+            || json!({ "pr_number": pr_number, "body": body }),
+            |inner| inner.upsert_review_comment(pr_number, body),
+        )
+    }
+
+    fn fetch_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        self.inner.fetch_pr_comments(pr_number)
+    }
+
+    fn fetch_pr_review_feedback(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        self.inner.fetch_pr_review_feedback(pr_number)
+    }
+
+    fn list_open_prs(&self) -> Result<Vec<PrContext>> {
+        self.inner.list_open_prs()
+    }
+
+    fn post_pr_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.mutate(
+            "post_pr_comment",
+            || json!({ "pr_number": pr_number, "body": body }),
+            |inner| inner.post_pr_comment(pr_number, body),
+        )
+    }
+
+    fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.mutate(
+            "update_pr_body",
+            || json!({ "pr_number": pr_number, "body": body }),
+            |inner| inner.update_pr_body(pr_number, body),
+        )
+    }
+
+    /// In a dry run, every suggestion counts as posted.
+    fn post_suggestions(&self, pr_number: u64, suggestions: &[SuggestionComment]) -> Result<usize> {
+        match &self.log {
+            Some(log) => {
+                log.record(
+                    "submission",
+                    "post_suggestions",
+                    json!({ "pr_number": pr_number, "suggestions": suggestions }),
+                );
+                Ok(suggestions.len())
+            }
+            None => self.inner.post_suggestions(pr_number, suggestions),
+        }
+    }
+
+    fn pr_state(&self, pr_number: u64) -> Result<PrState> {
+        self.inner.pr_state(pr_number)
+    }
+
+    fn close_pr(&self, pr_number: u64, comment: &str) -> Result<()> {
+        self.mutate(
+            "close_pr",
+            || json!({ "pr_number": pr_number, "comment": comment }),
+            |inner| inner.close_pr(pr_number, comment),
+        )
+    }
+
+    fn publish_review_check(&self, pr_number: u64, check: &ReviewCheck) -> Result<()> {
+        self.mutate(
+            "publish_review_check",
+            || json!({ "pr_number": pr_number, "check": check }),
+            |inner| inner.publish_review_check(pr_number, check),
+        )
+    }
+
+    fn upsert_heartbeat_comment(&self, number: u64, body: &str) -> Result<()> {
+        self.mutate(
+            "upsert_heartbeat_comment",
+            || json!({ "number": number, "body": body }),
+            |inner| inner.upsert_heartbeat_comment(number, body),
+        )
+    }
+
+    fn delete_heartbeat_comment(&self, number: u64) -> Result<()> {
+        self.mutate(
+            "delete_heartbeat_comment",
+            || json!({ "number": number }),
+            |inner| inner.delete_heartbeat_comment(number),
+        )
+    }
+
+    fn ci_status(&self, pr_number: u64) -> Result<CiStatus> {
+        self.inner.ci_status(pr_number)
+    }
+
+    fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        self.mutate(
+            "enable_auto_merge",
+            || json!({ "pr_number": pr_number, "method": format!("{method:?}").to_lowercase() }),
+            |inner| inner.enable_auto_merge(pr_number, method),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    /// Source whose mutations all fail, so a recorded call proves it was not sent.
+    struct FailingSource;
+
+    impl TaskSource for FailingSource {
+        fn fetch_eligible_tasks(&self) -> Result<Vec<Task>> {
+            Ok(Vec::new())
+        }
+        fn mark_in_progress(&self, _: &str) -> Result<()> {
+            Err(Error::TaskSource("sent".to_string()))
+        }
+        fn mark_in_review(&self, _: &str) -> Result<()> {
+            Err(Error::TaskSource("sent".to_string()))
+        }
+        fn get_task_details(&self, _: &str) -> Result<Task> {
+            Err(Error::TaskSource("not found".to_string()))
+        }
+        fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>> {
+            Ok(HashSet::new())
+        }
+        fn fetch_open_tasks_with_label(&self, _: &str) -> Result<Vec<Task>> {
+            Ok(Vec::new())
+        }
+        fn create_task(&self, _: &str, _: &str, _: &[String]) -> Result<Task> {
+            Err(Error::TaskSource("sent".to_string()))
+        }
+        fn add_label(&self, _: &str, _: &str) -> Result<()> {
+            Err(Error::TaskSource("sent".to_string()))
+        }
+        fn release_task(&self, _: &str) -> Result<()> {
+            Err(Error::TaskSource("sent".to_string()))
+        }
+        fn add_comment(&self, _: &str, _: &str) -> Result<()> {
+            Err(Error::TaskSource("sent".to_string()))
+        }
+        fn attach_link(&self, _: &str, _: &str, _: &str) -> Result<()> {
+            Err(Error::TaskSource("sent".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_source_records_mutations_with_payloads() {
+        let log = DryRunLog::default();
+        let source = DryRunSource::new(FailingSource, Some(log.clone()));
+        source.mark_in_progress("7").unwrap();
+        source.add_comment("7", "PR opened").unwrap();
+        assert!(source.get_task_details("7").is_err());
+
+        let actions = log.drain();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(
+            actions[0].to_string(),
+            r#"source.mark_in_progress {"task_id":"7"}"#
+        );
+        assert_eq!(actions[1].action, "add_comment");
+        assert_eq!(actions[1].payload["body"], "PR opened");
+        assert!(log.drain().is_empty());
+    }
+
+    #[test]
+    fn test_source_passes_through_without_log() {
+        let source = DryRunSource::new(FailingSource, None);
+        assert!(source.mark_in_progress("7").is_err());
+    }
+}
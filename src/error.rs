@@ -1,6 +1,8 @@
 use std::path::PathBuf;
 use std::time::Duration;
 
+use crate::push::PushFailure;
+
 #[derive(Debug, thiserror::Error)]
 #[allow(dead_code)]
 pub enum Error {
@@ -19,6 +21,10 @@ pub enum Error {
     #[error("task source error: {0}")]
     TaskSource(String),
 
+    /// The task was claimed by someone else between selection and `mark_in_progress`.
+    #[error("task {task_id} already claimed: {reason}")]
+    TaskClaimConflict { task_id: String, reason: String },
+
     #[error("agent runner error: {0}")]
     AgentRunner(String),
 
@@ -44,9 +50,38 @@ pub enum Error {
     #[error("prompt error: {0}")]
     Prompt(String),
 
+    /// A named prompt template failed to compile or render.
+    #[error(
+        "prompt error: {message} in template '{template}'{}; provided vars: [{}]",
+        .missing.as_ref().map(|m| format!(" (missing `{m}`)")).unwrap_or_default(),
+        .provided.join(", ")
+    )]
+    PromptRender {
+        template: String,
+        /// A variable the template references but was not given, if any.
+        missing: Option<String>,
+        /// Names of the variables the template was rendered with, sorted.
+        provided: Vec<String>,
+        message: String,
+    },
+
     #[error("orchestrator error: {0}")]
     Orchestrator(String),
 
+    /// `git push` failed; `kind` tells auth problems from transient ones.
+    #[error("git push failed ({kind}): {message}")]
+    GitPush { kind: PushFailure, message: String },
+
+    /// Review rounds ran out without an approval.
+    #[error("review exhausted: {0}")]
+    ReviewExhausted(String),
+
+    #[error("self-update error: {0}")]
+    SelfUpdate(String),
+
+    #[error("setup error: {0}")]
+    Setup(String),
+
     #[error("interrupted by signal")]
     Interrupted,
 }
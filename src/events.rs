@@ -0,0 +1,228 @@
+//! Typed progress events for embedders.
+//!
+//! Every [`ProgressReporter`] call the orchestrator makes is also broadcast
+//! as a [`PipelineEvent`] to receivers from [`Orchestrator::subscribe`], so a
+//! consumer can follow a run without implementing the trait or parsing the
+//! reporter's text.
+//!
+//! [`Orchestrator::subscribe`]: crate::orchestrator::Orchestrator::subscribe
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::dry_run::DryRunAction;
+use crate::orchestrator::ProgressReporter;
+use crate::poll::QueueDelta;
+use crate::runs::RunDigest;
+
+/// Events buffered per receiver; a receiver that falls further behind gets
+/// `RecvError::Lagged` and skips ahead.
+pub const EVENT_CAPACITY: usize = 256;
+
+/// One [`ProgressReporter`] call, as data. Serializes as an object tagged by
+/// `event`, e.g. `{"event":"tasks_found","count":3}`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum PipelineEvent {
+    FetchingTasks,
+    TasksFound {
+        count: usize,
+    },
+    QueueChanged {
+        delta: QueueDelta,
+    },
+    TaskSelected {
+        issue_number: u64,
+        title: String,
+    },
+    ImplementStarted,
+    PrCreated {
+        url: String,
+    },
+    IterationComplete {
+        issue_number: u64,
+        title: String,
+    },
+    PhasesStarted {
+        names: Vec<String>,
+    },
+    PhaseComplete {
+        name: String,
+    },
+    ReviewSummary {
+        body: String,
+    },
+    ReviewFailed {
+        pr_url: Option<String>,
+        reason: String,
+    },
+    PrUrl {
+        url: String,
+    },
+    DryRunAction {
+        action: DryRunAction,
+    },
+    RunDigest {
+        digest: RunDigest,
+    },
+}
+
+/// Reporter that broadcasts each call as a [`PipelineEvent`], then forwards it
+/// to `inner`. The orchestrator keeps one around whatever reporter it is given.
+pub struct EventTap<P> {
+    inner: P,
+    events: broadcast::Sender<PipelineEvent>,
+}
+
+impl<P> EventTap<P> {
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
+
+    /// Swap the wrapped reporter, keeping existing subscribers.
+    pub fn with_inner<P2>(self, inner: P2) -> EventTap<P2> {
+        EventTap {
+            inner,
+            events: self.events,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<PipelineEvent> {
+        self.events.subscribe()
+    }
+
+    fn emit(&self, event: PipelineEvent) {
+        // Errs only when nobody is subscribed.
+        let _ = self.events.send(event);
+    }
+}
+
+impl<P: ProgressReporter> ProgressReporter for EventTap<P> {
+    fn fetching_tasks(&self) {
+        self.emit(PipelineEvent::FetchingTasks);
+        self.inner.fetching_tasks();
+    }
+
+    fn tasks_found(&self, count: usize) {
+        self.emit(PipelineEvent::TasksFound { count });
+        self.inner.tasks_found(count);
+    }
+
+    fn queue_changed(&self, delta: &QueueDelta) {
+        self.emit(PipelineEvent::QueueChanged {
+            delta: delta.clone(),
+        });
+        self.inner.queue_changed(delta);
+    }
+
+    fn task_selected(&self, issue_number: u64, title: &str) {
+        self.emit(PipelineEvent::TaskSelected {
+            issue_number,
+            title: title.to_string(),
+        });
+        self.inner.task_selected(issue_number, title);
+    }
+
+    fn implement_started(&self) {
+        self.emit(PipelineEvent::ImplementStarted);
+        self.inner.implement_started();
+    }
+
+    fn pr_created(&self, url: &str) {
+        self.emit(PipelineEvent::PrCreated {
+            url: url.to_string(),
+        });
+        self.inner.pr_created(url);
+    }
+
+    fn iteration_complete(&self, issue_number: u64, title: &str) {
+        self.emit(PipelineEvent::IterationComplete {
+            issue_number,
+            title: title.to_string(),
+        });
+        self.inner.iteration_complete(issue_number, title);
+    }
+
+    fn phases_started(&self, names: &[String]) {
+        self.emit(PipelineEvent::PhasesStarted {
+            names: names.to_vec(),
+        });
+        self.inner.phases_started(names);
+    }
+
+    fn phase_complete(&self, name: &str) {
+        self.emit(PipelineEvent::PhaseComplete {
+            name: name.to_string(),
+        });
+        self.inner.phase_complete(name);
+    }
+
+    fn review_summary(&self, body: &str) {
+        self.emit(PipelineEvent::ReviewSummary {
+            body: body.to_string(),
+        });
+        self.inner.review_summary(body);
+    }
+
+    fn review_failed(&self, pr_url: Option<&str>, reason: &str) {
+        self.emit(PipelineEvent::ReviewFailed {
+            pr_url: pr_url.map(str::to_string),
+            reason: reason.to_string(),
+        });
+        self.inner.review_failed(pr_url, reason);
+    }
+
+    fn pr_url(&self, url: &str) {
+        self.emit(PipelineEvent::PrUrl {
+            url: url.to_string(),
+        });
+        self.inner.pr_url(url);
+    }
+
+    fn dry_run_action(&self, action: &DryRunAction) {
+        self.emit(PipelineEvent::DryRunAction {
+            action: action.clone(),
+        });
+        self.inner.dry_run_action(action);
+    }
+
+    fn run_digest(&self, digest: &RunDigest) {
+        self.emit(PipelineEvent::RunDigest {
+            digest: digest.clone(),
+        });
+        self.inner.run_digest(digest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::QuietReporter;
+
+    #[test]
+    fn test_tap_broadcasts_typed_events() {
+        let tap = EventTap::new(QuietReporter);
+        tap.fetching_tasks();
+        let mut rx = tap.subscribe();
+        let tap = tap.with_inner(QuietReporter);
+        tap.task_selected(42, "Fix bug");
+        tap.review_failed(None, "no approval");
+
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            PipelineEvent::TaskSelected {
+                issue_number: 42,
+                title: "Fix bug".to_string(),
+            }
+        );
+        let failed = rx.try_recv().unwrap();
+        assert_eq!(
+            serde_json::to_value(&failed).unwrap(),
+            serde_json::json!({ "event": "review_failed", "pr_url": null, "reason": "no approval" })
+        );
+        assert!(rx.try_recv().is_err());
+    }
+}
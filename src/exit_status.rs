@@ -0,0 +1,130 @@
+use serde_json::json;
+
+use crate::error::{Error, Result};
+use crate::orchestrator::IterationOutcome;
+
+/// How an `rlph` run ended, as reported by its exit code and `--porcelain` line.
+///
+/// The codes are a stable contract for scripts. `2` is left to clap's usage
+/// errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// A task was processed, or the review passed.
+    Processed,
+    /// Any failure without a more specific status.
+    Failed,
+    /// Nothing to do: no eligible, unblocked task.
+    NoEligibleTasks,
+    /// Review rounds ran out without an approval.
+    ReviewExhausted,
+    /// The selected task was skipped (estimated above `max_complexity`).
+    Skipped,
+    /// The configuration could not be loaded or is invalid.
+    ConfigError,
+    /// Stopped by a signal.
+    Interrupted,
+}
+
+impl ExitStatus {
+    pub fn code(self) -> i32 {
+        match self {
+            ExitStatus::Processed => 0,
+            ExitStatus::Failed => 1,
+            ExitStatus::NoEligibleTasks => 3,
+            ExitStatus::ReviewExhausted => 4,
+            ExitStatus::Skipped => 5,
+            ExitStatus::ConfigError => 6,
+            ExitStatus::Interrupted => 130,
+        }
+    }
+
+    /// Name used in the `--porcelain` line.
+    pub fn name(self) -> &'static str {
+        match self {
+            ExitStatus::Processed => "processed",
+            ExitStatus::Failed => "failed",
+            ExitStatus::NoEligibleTasks => "no_eligible_tasks",
+            ExitStatus::ReviewExhausted => "review_exhausted",
+            ExitStatus::Skipped => "skipped",
+            ExitStatus::ConfigError => "config_error",
+            ExitStatus::Interrupted => "interrupted",
+        }
+    }
+
+    pub fn from_outcome(outcome: IterationOutcome) -> Self {
+        match outcome {
+            IterationOutcome::ProcessedTask => ExitStatus::Processed,
+            IterationOutcome::NoEligibleTasks => ExitStatus::NoEligibleTasks,
+            IterationOutcome::SkippedTask => ExitStatus::Skipped,
+        }
+    }
+
+    pub fn from_error(error: &Error) -> Self {
+        match error {
+            Error::ConfigNotFound(_) | Error::ConfigParse(_) | Error::ConfigValidation(_) => {
+                ExitStatus::ConfigError
+            }
+            Error::ReviewExhausted(_) => ExitStatus::ReviewExhausted,
+            Error::Interrupted => ExitStatus::Interrupted,
+            _ => ExitStatus::Failed,
+        }
+    }
+
+    pub fn from_result(result: &Result<IterationOutcome>) -> Self {
+        match result {
+            Ok(outcome) => Self::from_outcome(*outcome),
+            Err(e) => Self::from_error(e),
+        }
+    }
+}
+
+/// The single JSON line printed by `--porcelain`, e.g.
+/// `{"status":"no_eligible_tasks","exit_code":3}`. Failures add an `error` field.
+pub fn porcelain_line(result: &Result<IterationOutcome>) -> String {
+    let status = ExitStatus::from_result(result);
+    let mut line = json!({ "status": status.name(), "exit_code": status.code() });
+    if let Err(e) = result {
+        line["error"] = json!(e.to_string());
+    }
+    line.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_are_stable() {
+        let cases = [
+            (Ok(IterationOutcome::ProcessedTask), 0),
+            (Ok(IterationOutcome::NoEligibleTasks), 3),
+            (Ok(IterationOutcome::SkippedTask), 5),
+            (Err(Error::ReviewExhausted("1 round".to_string())), 4),
+            (Err(Error::ConfigValidation("bad".to_string())), 6),
+            (Err(Error::Interrupted), 130),
+            (Err(Error::Orchestrator("boom".to_string())), 1),
+        ];
+        for (result, code) in cases {
+            assert_eq!(ExitStatus::from_result(&result).code(), code, "{result:?}");
+        }
+    }
+
+    #[test]
+    fn test_porcelain_line() {
+        assert_eq!(
+            porcelain_line(&Ok(IterationOutcome::NoEligibleTasks)),
+            r#"{"exit_code":3,"status":"no_eligible_tasks"}"#
+        );
+        let line = porcelain_line(&Err(Error::ReviewExhausted(
+            "review did not complete after 3 round(s)".to_string(),
+        )));
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["status"], "review_exhausted");
+        assert_eq!(value["exit_code"], 4);
+        assert_eq!(
+            value["error"],
+            "review exhausted: review did not complete after 3 round(s)"
+        );
+        assert!(!line.contains('\n'));
+    }
+}
@@ -19,14 +19,19 @@ const MAX_FETCH_ATTEMPTS: u32 = 3;
 const MAX_CONCURRENT_FIXES: usize = 2;
 
 use crate::config::{Config, ReviewStepConfig};
+use crate::conventions::load_conventions;
 use crate::error::{Error, Result};
 use crate::fix_comment::{CheckboxState, FixItem, FixResultKind, parse_fix_items, update_comment};
 use crate::orchestrator::{CorrectionRunner, retry_with_correction};
+use crate::prompt_vars::PromptPhase;
 use crate::prompts::PromptEngine;
 use crate::review_schema::{SchemaName, StandaloneFixOutput, parse_standalone_fix_output};
+use crate::rlphignore::RlphIgnore;
 use crate::runner::{AgentRunner, Phase, RunResult, build_runner};
 use crate::submission::{REVIEW_MARKER, SubmissionBackend};
-use crate::worktree::{WorktreeManager, git_in_dir, validate_branch_name};
+use crate::toolchain::describe_toolchain;
+use crate::workspace::{affected_crates, workspace_members};
+use crate::worktree::{GitIdentity, WorktreeManager, git_in_dir, validate_branch_name};
 
 /// Run the standalone fix flow for ALL checked findings on a PR concurrently.
 ///
@@ -34,9 +39,9 @@ use crate::worktree::{WorktreeManager, git_in_dir, validate_branch_name};
 /// 1. Fetch review comment, parse checked items
 /// 2. Collect all eligible checked items
 /// 3. Spawn a fix agent for each item in parallel (JoinSet)
-///    - Each gets its own worktree off `origin/<pr-branch>`
+///    - Each gets its own worktree off `<push_remote>/<pr-branch>`
 ///    - Parse StandaloneFixOutput JSON (with retry)
-///    - If fixed: rebase onto `origin/<pr-branch>`, push with retry
+///    - If fixed: rebase onto `<push_remote>/<pr-branch>`, push with retry
 ///    - Update review comment checkbox with result
 ///    - Clean up worktree
 /// 4. Collect results, log any errors
@@ -84,10 +89,16 @@ pub async fn run_fix<C: CorrectionRunner + 'static>(
     // 3. Pre-compute per-item data and spawn into JoinSet
     let fix_config = Arc::new(config.fix.clone());
     let worktree_dir: Arc<str> = Arc::from(config.worktree_dir.as_str());
+    let push_remote: Arc<str> = Arc::from(config.push_remote.as_str());
+    let git_identity = Arc::new(config.git_identity.clone());
     let agent_timeout_retries = config.agent_timeout_retries;
     let repo_root: Arc<Path> = Arc::from(repo_root);
     let pr_branch = pr_branch.to_string();
 
+    let ignore = RlphIgnore::load(&repo_root);
+    let conventions = load_conventions(&repo_root, &config.conventions_files);
+    let members = workspace_members(&repo_root);
+
     let mut join_set = tokio::task::JoinSet::new();
     let concurrency = Arc::new(Semaphore::new(MAX_CONCURRENT_FIXES));
 
@@ -96,6 +107,8 @@ pub async fn run_fix<C: CorrectionRunner + 'static>(
         let item = (*item).clone();
         let fix_config = Arc::clone(&fix_config);
         let worktree_dir = Arc::clone(&worktree_dir);
+        let push_remote = Arc::clone(&push_remote);
+        let git_identity = Arc::clone(&git_identity);
         let repo_root = Arc::clone(&repo_root);
         let pr_branch = pr_branch.clone();
         let submission = Arc::clone(&submission);
@@ -109,8 +122,16 @@ pub async fn run_fix<C: CorrectionRunner + 'static>(
         }
 
         // Pre-render prompt
-        let vars = build_finding_vars(&item);
-        let prompt = match prompt_engine.render_phase(&fix_config.prompt, &vars) {
+        let mut vars = build_finding_vars(&item);
+        vars.insert("ignored_paths".to_string(), ignore.prompt_list());
+        vars.insert("repo_conventions".to_string(), conventions.clone());
+        let affected = affected_crates(&members, std::slice::from_ref(&item.finding.file));
+        vars.insert("affected_crates".to_string(), affected.join(", "));
+        vars.insert(
+            "project_toolchain".to_string(),
+            describe_toolchain(&repo_root, &config.toolchain, &affected),
+        );
+        let prompt = match prompt_engine.render_step(PromptPhase::Fix, &fix_config.prompt, &vars) {
             Ok(p) => p,
             Err(e) => {
                 warn!(finding_id = %item.finding.id, error = %e, "failed to render prompt, skipping");
@@ -138,6 +159,8 @@ pub async fn run_fix<C: CorrectionRunner + 'static>(
                 pr_number,
                 pr_branch: &pr_branch,
                 fix_branch: &fix_branch,
+                push_remote: &push_remote,
+                git_identity: &git_identity,
                 fix_config: &fix_config,
                 agent_timeout_retries,
                 prompt: &prompt,
@@ -206,7 +229,9 @@ async fn run_single_fix(
         repo_root.to_path_buf(),
         repo_root.join(worktree_dir),
         ctx.pr_branch.to_string(),
-    );
+    )
+    .with_push_remote(ctx.push_remote)
+    .with_git_identity(ctx.git_identity.clone());
     let worktree_path = wm.create_fresh(ctx.fix_branch, ctx.pr_branch)?.path;
     info!(
         finding_id = %ctx.item.finding.id,
@@ -237,6 +262,8 @@ struct FixContext<'a> {
     pr_number: u64,
     pr_branch: &'a str,
     fix_branch: &'a str,
+    push_remote: &'a str,
+    git_identity: &'a GitIdentity,
     fix_config: &'a ReviewStepConfig,
     agent_timeout_retries: u32,
     prompt: &'a str,
@@ -281,6 +308,12 @@ async fn run_fix_agent_and_apply(
         ctx.fix_config.agent_timeout.map(Duration::from_secs),
         ctx.agent_timeout_retries,
     )
+    .with_permission_mode(ctx.fix_config.permission_mode)
+    .with_identity(ctx.fix_config.agent_identity.clone())
+    .with_sampling(
+        ctx.fix_config.agent_temperature,
+        ctx.fix_config.agent_extra_args.clone(),
+    )
     .with_stream_prefix("fix".to_string());
 
     let run_result = runner.run(Phase::Fix, ctx.prompt, worktree_path).await?;
@@ -300,7 +333,13 @@ async fn run_fix_agent_and_apply(
     let fix_result = match fix_output {
         StandaloneFixOutput::Fixed { commit_message } => {
             info!(finding_id = %ctx.item.finding.id, commit_message, "fix applied — rebasing and pushing");
-            push_to_pr_branch_with_retry(worktree_path, ctx.fix_branch, ctx.pr_branch).await?;
+            push_to_pr_branch_with_retry(
+                worktree_path,
+                ctx.push_remote,
+                ctx.fix_branch,
+                ctx.pr_branch,
+            )
+            .await?;
             FixResultKind::Fixed {
                 commit_message: commit_message.clone(),
             }
@@ -358,6 +397,8 @@ async fn parse_fix_with_retry(
                 fix_config.agent_model.as_deref(),
                 fix_config.agent_effort.as_deref(),
                 fix_config.agent_variant.as_deref(),
+                fix_config.permission_mode,
+                &fix_config.agent_identity,
                 fix_config.agent_timeout,
                 SchemaName::StandaloneFix,
                 &err_str,
@@ -374,18 +415,19 @@ async fn parse_fix_with_retry(
     }
 }
 
-/// Fetch a ref from origin with retries to handle git lock contention under concurrency.
-async fn fetch_with_retry(cwd: &Path, refspec: &str) -> Result<()> {
+/// Fetch a ref from `remote` with retries to handle git lock contention under concurrency.
+async fn fetch_with_retry(cwd: &Path, remote: &str, refspec: &str) -> Result<()> {
     let mut last_err = String::new();
     for attempt in 1..=MAX_FETCH_ATTEMPTS {
-        match git_in_dir(cwd, &["fetch", "origin", refspec]) {
+        match git_in_dir(cwd, &["fetch", remote, refspec]) {
             Ok(_) => return Ok(()),
             Err(e) => {
                 warn!(
                     attempt,
                     max_attempts = MAX_FETCH_ATTEMPTS,
                     error = %e.trim(),
-                    "git fetch origin {} failed",
+                    "git fetch {} {} failed",
+                    remote,
                     refspec
                 );
                 last_err = e;
@@ -396,16 +438,16 @@ async fn fetch_with_retry(cwd: &Path, refspec: &str) -> Result<()> {
         }
     }
     Err(Error::Orchestrator(format!(
-        "git fetch origin {refspec} failed after {MAX_FETCH_ATTEMPTS} attempts: {}",
+        "git fetch {remote} {refspec} failed after {MAX_FETCH_ATTEMPTS} attempts: {}",
         last_err.trim()
     )))
 }
 
-/// Rebase current branch onto origin/<pr-branch>.
-async fn rebase_onto(worktree_path: &Path, pr_branch: &str) -> Result<()> {
-    fetch_with_retry(worktree_path, pr_branch).await?;
+/// Rebase current branch onto `<remote>/<pr-branch>`.
+async fn rebase_onto(worktree_path: &Path, remote: &str, pr_branch: &str) -> Result<()> {
+    fetch_with_retry(worktree_path, remote, pr_branch).await?;
 
-    let remote_ref = format!("origin/{pr_branch}");
+    let remote_ref = format!("{remote}/{pr_branch}");
 
     if let Err(stderr) = git_in_dir(worktree_path, &["rebase", &remote_ref]) {
         let _ = git_in_dir(worktree_path, &["rebase", "--abort"]);
@@ -424,18 +466,19 @@ async fn rebase_onto(worktree_path: &Path, pr_branch: &str) -> Result<()> {
 /// rebases, and retries up to [`MAX_PUSH_ATTEMPTS`] times.
 async fn push_to_pr_branch_with_retry(
     worktree_path: &Path,
+    remote: &str,
     fix_branch: &str,
     pr_branch: &str,
 ) -> Result<()> {
     let refspec = format!("{fix_branch}:{pr_branch}");
     let mut last_err = String::new();
     for attempt in 1..=MAX_PUSH_ATTEMPTS {
-        // Skip rebase on first attempt: worktree was just created from origin/<pr-branch>
+        // Skip rebase on first attempt: worktree was just created from <remote>/<pr-branch>
         if attempt > 1 {
-            rebase_onto(worktree_path, pr_branch).await?;
+            rebase_onto(worktree_path, remote, pr_branch).await?;
         }
 
-        match git_in_dir(worktree_path, &["push", "origin", &refspec]) {
+        match git_in_dir(worktree_path, &["push", remote, &refspec]) {
             Ok(_) => {
                 info!(refspec, attempt, "pushed fix to PR branch");
                 return Ok(());
@@ -457,7 +500,7 @@ async fn push_to_pr_branch_with_retry(
         }
     }
     Err(Error::Orchestrator(format!(
-        "git push origin {refspec} failed after {MAX_PUSH_ATTEMPTS} attempts: {last_err}"
+        "git push {remote} {refspec} failed after {MAX_PUSH_ATTEMPTS} attempts: {last_err}"
     )))
 }
 
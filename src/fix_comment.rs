@@ -197,6 +197,7 @@ mod tests {
             description: format!("{id} description"),
             category: Some(category.to_string()),
             depends_on: vec![],
+            extra: Default::default(),
         }
     }
 
@@ -358,6 +359,7 @@ mod tests {
             description: "Null deref".to_string(),
             category: Some("correctness".to_string()),
             depends_on: vec!["null-check".to_string()],
+            extra: Default::default(),
         };
         let comment = render_findings_for_github(&[f], "S.");
         let items = parse_fix_items(&comment);
@@ -375,6 +377,7 @@ mod tests {
             description: "Outputs --> and -- unescaped".to_string(),
             category: Some("security".to_string()),
             depends_on: vec![],
+            extra: Default::default(),
         };
         let comment = render_findings_for_github(&[f], "S.");
         let items = parse_fix_items(&comment);
@@ -0,0 +1,160 @@
+use std::path::Path;
+
+use tracing::{info, warn};
+
+use crate::config::FixTrailersConfig;
+use crate::error::{Error, Result};
+use crate::worktree::git_in_dir;
+
+/// Trailer naming the fix round a commit was made in.
+pub const ROUND_TRAILER: &str = "Rlph-Fix-Round";
+
+/// Who a fix round's commits are credited to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixAttribution {
+    /// `1`, `2`, ... for review rounds; `feedback` for PR feedback.
+    pub round: String,
+    /// Review phases whose findings the round fixed.
+    pub reviewers: Vec<String>,
+    /// Logins of people whose PR feedback the round addressed.
+    pub requesters: Vec<String>,
+}
+
+impl FixAttribution {
+    /// Trailer lines for the round, co-authors first, without duplicates.
+    pub fn trailers(&self, config: &FixTrailersConfig) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+        let reviewers = self.reviewers.iter().map(|name| {
+            format!(
+                "Co-authored-by: rlph {name} reviewer <{}>",
+                config.agent_email
+            )
+        });
+        let requesters = self.requesters.iter().map(|login| {
+            format!(
+                "Co-authored-by: {login} <{}>",
+                config.human_email.replace("{login}", login)
+            )
+        });
+        for line in reviewers.chain(requesters) {
+            if !lines.contains(&line) {
+                lines.push(line);
+            }
+        }
+        lines.push(format!("{ROUND_TRAILER}: {}", self.round));
+        lines
+    }
+}
+
+/// Add `trailers` to every commit on HEAD after `since`, rewriting them in
+/// place. Returns how many commits were rewritten. On failure the branch is
+/// left as it was.
+pub fn apply_trailers(worktree: &Path, since: &str, trailers: &[String]) -> Result<usize> {
+    let git = |args: &[&str]| {
+        git_in_dir(worktree, args).map_err(|e| {
+            Error::Worktree(format!(
+                "git {} failed in {}: {}",
+                args.join(" "),
+                worktree.display(),
+                e.trim()
+            ))
+        })
+    };
+    let count: usize = git(&["rev-list", "--count", &format!("{since}..HEAD")])?
+        .trim()
+        .parse()
+        .unwrap_or_default();
+    if count == 0 || trailers.is_empty() {
+        return Ok(0);
+    }
+
+    let mut amend = String::from("git commit --amend --no-edit --no-verify");
+    for trailer in trailers {
+        amend.push_str(" --trailer ");
+        amend.push_str(&shell_quote(trailer));
+    }
+    if let Err(e) = git(&[
+        "-c",
+        "trailer.ifexists=addIfDifferent",
+        "rebase",
+        "--quiet",
+        "--exec",
+        &amend,
+        since,
+    ]) {
+        let _ = git_in_dir(worktree, &["rebase", "--abort"]);
+        return Err(e);
+    }
+    info!(commits = count, "added fix trailers");
+    Ok(count)
+}
+
+/// Best-effort [`apply_trailers`]: failures are logged and the commits pushed
+/// without trailers.
+pub fn attribute_fix_commits(
+    config: &FixTrailersConfig,
+    worktree: &Path,
+    since: Option<&str>,
+    attribution: &FixAttribution,
+) {
+    if !config.enabled {
+        return;
+    }
+    let Some(since) = since else {
+        return;
+    };
+    if let Err(e) = apply_trailers(worktree, since, &attribution.trailers(config)) {
+        warn!(error = %e, "failed to add fix trailers");
+    }
+}
+
+/// Quote `s` as one POSIX shell word.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_trailers_dedupe_and_end_with_round() {
+        let attribution = FixAttribution {
+            round: "2".to_string(),
+            reviewers: vec!["security".to_string(), "security".to_string()],
+            requesters: vec!["alice".to_string()],
+        };
+        assert_eq!(
+            attribution.trailers(&FixTrailersConfig::default()),
+            [
+                "Co-authored-by: rlph security reviewer <rlph@users.noreply.github.com>",
+                "Co-authored-by: alice <alice@users.noreply.github.com>",
+                "Rlph-Fix-Round: 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_trailers_rewrites_only_new_commits() {
+        let repo = TempDir::new().unwrap();
+        let git = |args: &[&str]| git_in_dir(repo.path(), args).unwrap();
+        git(&["init", "-q"]);
+        git(&["config", "user.name", "t"]);
+        git(&["config", "user.email", "t@t"]);
+        let commit = |msg: &str| git(&["commit", "-q", "--allow-empty", "-m", msg]);
+        commit("base");
+        let since = git(&["rev-parse", "HEAD"]).trim().to_string();
+        commit("fix one");
+        commit("fix two");
+
+        let trailers = ["Co-authored-by: O'Brien <ob@example.com>".to_string()];
+        assert_eq!(apply_trailers(repo.path(), &since, &trailers).unwrap(), 2);
+        let log = git(&["log", "--format=%B%x00"]);
+        let messages: Vec<&str> = log.split('\0').map(str::trim).collect();
+        assert!(messages[0].ends_with("Co-authored-by: O'Brien <ob@example.com>"));
+        assert!(messages[1].ends_with("Co-authored-by: O'Brien <ob@example.com>"));
+        assert_eq!(messages[2], "base");
+        assert_eq!(apply_trailers(repo.path(), "HEAD", &trailers).unwrap(), 0);
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+use std::sync::{LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use tracing::warn;
+
+/// Default sustained rate of `gh` requests per second, per host.
+pub const DEFAULT_GITHUB_RPS: f64 = 2.0;
+/// Back-off after a rate-limit response that carries no `Retry-After`.
+pub(crate) const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(60);
+/// Times a rate-limited request is retried before its failure is returned.
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+static RETRY_AFTER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)retry-after:\s*(\d+)").expect("retry-after regex is valid"));
+
+/// The quota every `gh` call in this process draws from.
+static QUOTA: LazyLock<GhQuota> = LazyLock::new(|| GhQuota::new(DEFAULT_GITHUB_RPS));
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+    paused_until: Option<Instant>,
+}
+
+/// Token buckets keyed by GitHub host, shared by the task source, the submission
+/// backend, and parallel review phases so together they stay under GitHub's
+/// secondary rate limits.
+#[derive(Debug)]
+pub struct GhQuota {
+    rps: Mutex<f64>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl GhQuota {
+    pub fn new(rps: f64) -> Self {
+        Self {
+            rps: Mutex::new(rps),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn set_rps(&self, rps: f64) {
+        *self.rps.lock().unwrap_or_else(|e| e.into_inner()) = rps;
+    }
+
+    /// Block until a request to `host` may be made.
+    pub fn acquire(&self, host: &str) {
+        while let Some(wait) = self.try_acquire(host, Instant::now()) {
+            thread::sleep(wait);
+        }
+    }
+
+    /// Take a token for `host` at `now`, or return how long until one is free.
+    /// Bursts are capped at one second's worth of requests.
+    pub fn try_acquire(&self, host: &str, now: Instant) -> Option<Duration> {
+        let rps = *self.rps.lock().unwrap_or_else(|e| e.into_inner());
+        let capacity = rps.max(1.0);
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets.entry(host.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            refilled_at: now,
+            paused_until: None,
+        });
+        if let Some(until) = bucket.paused_until {
+            if until > now {
+                return Some(until - now);
+            }
+            bucket.paused_until = None;
+        }
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * rps).min(capacity);
+        bucket.refilled_at = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - bucket.tokens) / rps))
+        }
+    }
+
+    /// Hold all requests to `host` until `until`, e.g. for a `Retry-After`.
+    pub fn pause(&self, host: &str, until: Instant) {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(bucket) = buckets.get_mut(host) {
+            bucket.paused_until = Some(bucket.paused_until.map_or(until, |p| p.max(until)));
+        }
+    }
+}
+
+/// Set the request rate of the process-wide quota (`github_rps`).
+pub fn configure(rps: f64) {
+    QUOTA.set_rps(rps);
+}
+
+/// Block until a request to `host` may be made under the process-wide quota,
+/// for GitHub calls that do not go through `gh`.
+pub fn acquire(host: &str) {
+    QUOTA.acquire(host);
+}
+
+/// Hold every request to `host` for `delay` after a rate-limit response.
+pub fn pause(host: &str, delay: Duration) {
+    QUOTA.pause(host, Instant::now() + delay);
+}
+
+/// The host `gh` talks to: `GH_HOST` when set, else `github.com`.
+pub fn gh_host() -> String {
+    std::env::var("GH_HOST")
+        .ok()
+        .filter(|h| !h.trim().is_empty())
+        .unwrap_or_else(|| "github.com".to_string())
+}
+
+/// Run a `gh` command under the process-wide quota. Rate-limited responses are
+/// retried after their `Retry-After` (or a default back-off), pausing every other
+/// request to the same host meanwhile.
+pub fn gh_output(cmd: &mut Command) -> std::io::Result<Output> {
+    run_gh(cmd, None)
+}
+
+/// [`gh_output`] with `input` written to the command's stdin, e.g. a JSON body for
+/// `gh api --input -`.
+pub fn gh_output_with_input(cmd: &mut Command, input: &[u8]) -> std::io::Result<Output> {
+    run_gh(cmd, Some(input))
+}
+
+fn run_gh(cmd: &mut Command, input: Option<&[u8]>) -> std::io::Result<Output> {
+    let host = gh_host();
+    let mut attempt = 0;
+    loop {
+        QUOTA.acquire(&host);
+        let output = match input {
+            Some(input) => output_with_input(cmd, input)?,
+            None => cmd.output()?,
+        };
+        if output.status.success() || attempt == MAX_RATE_LIMIT_RETRIES {
+            return Ok(output);
+        }
+        let Some(delay) = rate_limit_delay(&String::from_utf8_lossy(&output.stderr)) else {
+            return Ok(output);
+        };
+        attempt += 1;
+        warn!(
+            %host,
+            attempt,
+            delay_secs = delay.as_secs(),
+            "GitHub rate limit hit; pausing requests"
+        );
+        QUOTA.pause(&host, Instant::now() + delay);
+    }
+}
+
+fn output_with_input(cmd: &mut Command, input: &[u8]) -> std::io::Result<Output> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input)?;
+    }
+    child.wait_with_output()
+}
+
+/// How long to back off after `gh` output reporting a rate limit, or `None` when
+/// the failure is something else.
+pub fn rate_limit_delay(stderr: &str) -> Option<Duration> {
+    let lower = stderr.to_lowercase();
+    if !lower.contains("rate limit") && !lower.contains("http 429") {
+        return None;
+    }
+    let delay = RETRY_AFTER_RE
+        .captures(stderr)
+        .and_then(|c| c[1].parse().ok())
+        .map_or(DEFAULT_RETRY_AFTER, Duration::from_secs);
+    Some(delay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_allows_burst_then_spaces_requests() {
+        let quota = GhQuota::new(2.0);
+        let start = Instant::now();
+        assert_eq!(quota.try_acquire("github.com", start), None);
+        assert_eq!(quota.try_acquire("github.com", start), None);
+        assert_eq!(
+            quota.try_acquire("github.com", start),
+            Some(Duration::from_millis(500))
+        );
+        // Hosts have separate buckets.
+        assert_eq!(quota.try_acquire("ghe.example.com", start), None);
+        assert_eq!(
+            quota.try_acquire("github.com", start + Duration::from_millis(500)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_pause_holds_requests_until_deadline() {
+        let quota = GhQuota::new(10.0);
+        let start = Instant::now();
+        assert_eq!(quota.try_acquire("github.com", start), None);
+        quota.pause("github.com", start + Duration::from_secs(30));
+        assert_eq!(
+            quota.try_acquire("github.com", start + Duration::from_secs(10)),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(
+            quota.try_acquire("github.com", start + Duration::from_secs(30)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_delay() {
+        assert_eq!(
+            rate_limit_delay("HTTP 403: You have exceeded a secondary rate limit\nRetry-After: 42"),
+            Some(Duration::from_secs(42))
+        );
+        assert_eq!(
+            rate_limit_delay("HTTP 429: Too Many Requests"),
+            Some(DEFAULT_RETRY_AFTER)
+        );
+        assert_eq!(rate_limit_delay("HTTP 404: Not Found"), None);
+    }
+}
@@ -0,0 +1,587 @@
+//! Gitea and Forgejo support (including Codeberg): the REST client shared by
+//! [`GiteaSource`](crate::sources::gitea::GiteaSource) and
+//! [`GiteaSubmission`].
+
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use tracing::{info, warn};
+
+use crate::check_run::{CheckConclusion, ReviewCheck};
+use crate::config::{Config, MergeMethod};
+use crate::error::{Error, Result};
+use crate::submission::{
+    CiStatus, HEARTBEAT_MARKER, PrComment, PrContext, PrState, REVIEW_MARKER, SubmissionBackend,
+    SubmitResult, extract_issue_number_reference, pr_body_references_issue,
+};
+use crate::suggestion::SuggestionComment;
+
+const MAX_RETRIES: u32 = 3;
+const INITIAL_BACKOFF_MS: u64 = 500;
+/// Items requested per page of a paginated listing.
+const PAGE_LIMIT: usize = 50;
+/// Most pages read from one listing.
+const MAX_PAGES: usize = 20;
+
+// ---------------------------------------------------------------------------
+// Client abstraction (for testability)
+// ---------------------------------------------------------------------------
+
+pub trait GiteaClient: Send + Sync {
+    /// Send `method` to `path`, relative to the repository
+    /// (`/api/v1/repos/<owner>/<repo>`), with `query` as the query string.
+    /// Returns the response JSON, or `Null` for an empty body.
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Value>,
+    ) -> std::result::Result<Value, String>;
+}
+
+struct HttpGiteaClient {
+    repo_url: String,
+    token: String,
+}
+
+impl GiteaClient for HttpGiteaClient {
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Value>,
+    ) -> std::result::Result<Value, String> {
+        let url = format!("{}{path}", self.repo_url);
+        let mut backoff_ms = INITIAL_BACKOFF_MS;
+        for attempt in 1..=MAX_RETRIES {
+            let mut request = ureq::request(method, &url)
+                .set("Authorization", &format!("token {}", self.token))
+                .set("Accept", "application/json");
+            for (name, value) in query {
+                request = request.query(name, value);
+            }
+            let response = match body {
+                Some(body) => request.send_json(body),
+                None => request.call(),
+            };
+            match response {
+                Ok(response) => {
+                    let text = response
+                        .into_string()
+                        .map_err(|e| format!("failed to read Gitea response: {e}"))?;
+                    if text.trim().is_empty() {
+                        return Ok(Value::Null);
+                    }
+                    return serde_json::from_str(&text)
+                        .map_err(|e| format!("failed to parse Gitea response: {e}"));
+                }
+                Err(ureq::Error::Status(code, _))
+                    if (code == 429 || code >= 500) && attempt < MAX_RETRIES =>
+                {
+                    warn!(attempt, code, backoff_ms, "retrying Gitea API request");
+                    thread::sleep(Duration::from_millis(backoff_ms));
+                    backoff_ms *= 2;
+                }
+                Err(ureq::Error::Status(code, response)) => {
+                    let body = response.into_string().unwrap_or_default();
+                    return Err(format!(
+                        "Gitea API {method} {path} failed: status {code}: {body}"
+                    ));
+                }
+                Err(e) if attempt < MAX_RETRIES => {
+                    warn!(attempt, error = %e, backoff_ms, "retrying Gitea API after transient error");
+                    thread::sleep(Duration::from_millis(backoff_ms));
+                    backoff_ms *= 2;
+                }
+                Err(e) => return Err(format!("Gitea API {method} {path} failed: {e}")),
+            }
+        }
+        Err(format!(
+            "Gitea API {method} {path} failed; retries exhausted"
+        ))
+    }
+}
+
+/// Build the HTTP client for `[gitea]`, authenticating with the access token
+/// in `token_env`.
+pub fn client_from_config(config: &Config) -> Result<Box<dyn GiteaClient>> {
+    let gitea = config
+        .gitea
+        .as_ref()
+        .ok_or_else(|| Error::ConfigValidation("[gitea] config section required".to_string()))?;
+    let token = std::env::var(&gitea.token_env).map_err(|_| {
+        Error::ConfigValidation(format!("Gitea token not found in ${}", gitea.token_env))
+    })?;
+    Ok(Box::new(HttpGiteaClient {
+        repo_url: format!(
+            "{}/api/v1/repos/{}/{}",
+            gitea.base_url.trim_end_matches('/'),
+            gitea.owner,
+            gitea.repo
+        ),
+        token,
+    }))
+}
+
+/// Every item of a paginated listing at `path`, read page by page until a
+/// short page.
+pub fn fetch_all(
+    client: &dyn GiteaClient,
+    path: &str,
+    query: &[(&str, &str)],
+) -> std::result::Result<Vec<Value>, String> {
+    let limit = PAGE_LIMIT.to_string();
+    let mut items = Vec::new();
+    for page in 1..=MAX_PAGES {
+        let page = page.to_string();
+        let mut paged = query.to_vec();
+        paged.extend([("limit", limit.as_str()), ("page", page.as_str())]);
+        let response = client.request("GET", path, &paged, None)?;
+        let values = response.as_array().cloned().unwrap_or_default();
+        let done = values.len() < PAGE_LIMIT;
+        items.extend(values);
+        if done {
+            return Ok(items);
+        }
+    }
+    warn!(path, pages = MAX_PAGES, "Gitea listing truncated");
+    Ok(items)
+}
+
+/// The first comment on issue or PR `number` whose body contains `marker`.
+pub(crate) fn find_marked_comment(
+    client: &dyn GiteaClient,
+    number: u64,
+    marker: &str,
+) -> std::result::Result<Option<u64>, String> {
+    let comments = fetch_all(client, &format!("/issues/{number}/comments"), &[])?;
+    Ok(comments.iter().find_map(|c| {
+        let body = c.get("body").and_then(Value::as_str)?;
+        if body.contains(marker) {
+            c.get("id").and_then(Value::as_u64)
+        } else {
+            None
+        }
+    }))
+}
+
+// ---------------------------------------------------------------------------
+// Submission
+// ---------------------------------------------------------------------------
+
+/// Gitea or Forgejo pull requests. Issues and PRs share one numbering, as on
+/// GitHub, so PR comments are issue comments.
+pub struct GiteaSubmission {
+    client: Box<dyn GiteaClient>,
+}
+
+impl GiteaSubmission {
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self::with_client(client_from_config(config)?))
+    }
+
+    pub fn with_client(client: Box<dyn GiteaClient>) -> Self {
+        Self { client }
+    }
+
+    fn call(&self, method: &str, path: &str, body: Option<&Value>) -> Result<Value> {
+        self.client
+            .request(method, path, &[], body)
+            .map_err(Error::Submission)
+    }
+
+    fn open_prs(&self) -> Result<Vec<PrContext>> {
+        Ok(
+            fetch_all(self.client.as_ref(), "/pulls", &[("state", "open")])
+                .map_err(Error::Submission)?
+                .iter()
+                .filter_map(pr_context)
+                .collect(),
+        )
+    }
+
+    fn upsert_marked_comment(&self, number: u64, marker: &str, body: &str) -> Result<()> {
+        let existing =
+            find_marked_comment(self.client.as_ref(), number, marker).map_err(Error::Submission)?;
+        match existing {
+            Some(id) => self.call(
+                "PATCH",
+                &format!("/issues/comments/{id}"),
+                Some(&json!({ "body": body })),
+            )?,
+            None => self.call(
+                "POST",
+                &format!("/issues/{number}/comments"),
+                Some(&json!({ "body": body })),
+            )?,
+        };
+        Ok(())
+    }
+}
+
+/// A pull request from the Gitea API as a [`PrContext`].
+fn pr_context(pr: &Value) -> Option<PrContext> {
+    let str_at = |pointer: &str| pr.pointer(pointer).and_then(Value::as_str);
+    let body = str_at("/body").unwrap_or_default().to_string();
+    Some(PrContext {
+        number: pr.get("number").and_then(Value::as_u64)?,
+        title: str_at("/title").unwrap_or_default().to_string(),
+        url: str_at("/html_url").unwrap_or_default().to_string(),
+        head_branch: str_at("/head/ref").filter(|b| !b.is_empty())?.to_string(),
+        head_sha: str_at("/head/sha").map(str::to_string),
+        base_branch: str_at("/base/ref").map(str::to_string),
+        linked_issue_number: extract_issue_number_reference(&body),
+        body,
+    })
+}
+
+impl SubmissionBackend for GiteaSubmission {
+    fn submit(&self, branch: &str, base: &str, title: &str, body: &str) -> Result<SubmitResult> {
+        if let Some(pr) = self
+            .open_prs()?
+            .into_iter()
+            .find(|pr| pr.head_branch == branch)
+        {
+            info!(url = %pr.url, "found existing PR for branch");
+            return Ok(SubmitResult {
+                url: pr.url,
+                number: Some(pr.number),
+            });
+        }
+        let created = self.call(
+            "POST",
+            "/pulls",
+            Some(&json!({ "head": branch, "base": base, "title": title, "body": body })),
+        )?;
+        let pr = pr_context(&created).ok_or_else(|| {
+            Error::Submission("Gitea did not return the created pull request".to_string())
+        })?;
+        Ok(SubmitResult {
+            url: pr.url,
+            number: Some(pr.number),
+        })
+    }
+
+    fn find_existing_pr_for_issue(&self, issue_number: u64) -> Result<Option<u64>> {
+        Ok(self
+            .open_prs()?
+            .iter()
+            .find(|pr| pr_body_references_issue(&pr.body, issue_number))
+            .map(|pr| pr.number))
+    }
+
+    fn upsert_review_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.upsert_marked_comment(pr_number, REVIEW_MARKER, body)
+    }
+
+    fn fetch_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        let comments = fetch_all(
+            self.client.as_ref(),
+            &format!("/issues/{pr_number}/comments"),
+            &[],
+        )
+        .map_err(Error::Submission)?;
+        Ok(comments
+            .iter()
+            .filter_map(|c| {
+                let str_at = |pointer: &str| c.pointer(pointer).and_then(Value::as_str);
+                Some(PrComment::new(
+                    c.get("id").and_then(Value::as_u64)?,
+                    str_at("/user/login").unwrap_or("unknown"),
+                    str_at("/body").unwrap_or_default(),
+                    str_at("/created_at").unwrap_or_default(),
+                ))
+            })
+            .collect())
+    }
+
+    fn list_open_prs(&self) -> Result<Vec<PrContext>> {
+        self.open_prs()
+    }
+
+    fn post_pr_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.call(
+            "POST",
+            &format!("/issues/{pr_number}/comments"),
+            Some(&json!({ "body": body })),
+        )?;
+        Ok(())
+    }
+
+    fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.call(
+            "PATCH",
+            &format!("/pulls/{pr_number}"),
+            Some(&json!({ "body": body })),
+        )?;
+        Ok(())
+    }
+
+    /// Gitea has no suggested-change comments; findings stay in the review
+    /// comment.
+    fn post_suggestions(
+        &self,
+        _pr_number: u64,
+        _suggestions: &[SuggestionComment],
+    ) -> Result<usize> {
+        Ok(0)
+    }
+
+    fn pr_state(&self, pr_number: u64) -> Result<PrState> {
+        let pr = self.call("GET", &format!("/pulls/{pr_number}"), None)?;
+        if pr.get("merged").and_then(Value::as_bool) == Some(true) {
+            return Ok(PrState::Merged);
+        }
+        match pr.get("state").and_then(Value::as_str) {
+            Some("open") => Ok(PrState::Open),
+            Some("closed") => Ok(PrState::Closed),
+            other => Err(Error::Submission(format!(
+                "unknown Gitea PR state: {other:?}"
+            ))),
+        }
+    }
+
+    fn close_pr(&self, pr_number: u64, comment: &str) -> Result<()> {
+        self.post_pr_comment(pr_number, comment)?;
+        self.call(
+            "PATCH",
+            &format!("/pulls/{pr_number}"),
+            Some(&json!({ "state": "closed" })),
+        )?;
+        Ok(())
+    }
+
+    /// Published as a commit status on the PR's head commit. Commit statuses
+    /// carry no annotations, so the summary holds the findings.
+    fn publish_review_check(&self, pr_number: u64, check: &ReviewCheck) -> Result<()> {
+        let pr = self.call("GET", &format!("/pulls/{pr_number}"), None)?;
+        let context = pr_context(&pr)
+            .ok_or_else(|| Error::Submission(format!("Gitea PR {pr_number} has no head branch")))?;
+        let sha = context
+            .head_sha
+            .ok_or_else(|| Error::Submission(format!("Gitea PR {pr_number} has no head commit")))?;
+        let state = match check.conclusion {
+            CheckConclusion::Success => "success",
+            CheckConclusion::Neutral => "warning",
+            CheckConclusion::Failure => "failure",
+        };
+        self.call(
+            "POST",
+            &format!("/statuses/{sha}"),
+            Some(&json!({
+                "context": "rlph-review",
+                "state": state,
+                "description": check.title,
+                "target_url": context.url,
+            })),
+        )?;
+        Ok(())
+    }
+
+    fn upsert_heartbeat_comment(&self, number: u64, body: &str) -> Result<()> {
+        self.upsert_marked_comment(number, HEARTBEAT_MARKER, body)
+    }
+
+    fn delete_heartbeat_comment(&self, number: u64) -> Result<()> {
+        if let Some(id) = find_marked_comment(self.client.as_ref(), number, HEARTBEAT_MARKER)
+            .map_err(Error::Submission)?
+        {
+            self.call("DELETE", &format!("/issues/comments/{id}"), None)?;
+        }
+        Ok(())
+    }
+
+    fn ci_status(&self, pr_number: u64) -> Result<CiStatus> {
+        let pr = self.call("GET", &format!("/pulls/{pr_number}"), None)?;
+        let sha = pr_context(&pr)
+            .and_then(|c| c.head_sha)
+            .ok_or_else(|| Error::Submission(format!("Gitea PR {pr_number} has no head commit")))?;
+        let combined = self.call("GET", &format!("/commits/{sha}/status"), None)?;
+        let statuses = combined
+            .get("statuses")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let mut pending = false;
+        let mut failed = Vec::new();
+        for status in statuses {
+            let context = status.get("context").and_then(Value::as_str);
+            match status.get("status").and_then(Value::as_str) {
+                Some("success" | "warning") => {}
+                Some("failure" | "error") => failed.push(context.unwrap_or_default().to_string()),
+                _ => pending = true,
+            }
+        }
+        Ok(if !failed.is_empty() {
+            CiStatus::Failed(failed)
+        } else if pending {
+            CiStatus::Pending
+        } else {
+            CiStatus::Passed
+        })
+    }
+
+    fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        let style = match method {
+            MergeMethod::Rebase => "rebase",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Merge => "merge",
+        };
+        self.call(
+            "POST",
+            &format!("/pulls/{pr_number}/merge"),
+            Some(&json!({ "Do": style, "merge_when_checks_succeed": true })),
+        )?;
+        info!(pr_number, "enabled auto-merge");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::MockHttpClient;
+    use std::sync::Arc;
+
+    impl GiteaClient for MockHttpClient {
+        fn request(
+            &self,
+            method: &str,
+            path: &str,
+            query: &[(&str, &str)],
+            body: Option<&Value>,
+        ) -> std::result::Result<Value, String> {
+            self.reply(method, path, query, body)
+        }
+    }
+
+    impl GiteaClient for Arc<MockHttpClient> {
+        fn request(
+            &self,
+            method: &str,
+            path: &str,
+            query: &[(&str, &str)],
+            body: Option<&Value>,
+        ) -> std::result::Result<Value, String> {
+            (**self).request(method, path, query, body)
+        }
+    }
+
+    fn pr_json(number: u64, branch: &str, body: &str) -> Value {
+        json!({
+            "number": number,
+            "title": "Fix login",
+            "body": body,
+            "state": "open",
+            "merged": false,
+            "html_url": format!("https://codeberg.org/o/r/pulls/{number}"),
+            "head": { "ref": branch, "sha": "abc123" },
+            "base": { "ref": "main" },
+        })
+    }
+
+    #[test]
+    fn test_fetch_all_reads_until_short_page() {
+        let full: Vec<Value> = (0..PAGE_LIMIT).map(|i| json!({ "id": i })).collect();
+        let client = Arc::new(
+            MockHttpClient::default()
+                .respond("GET /issues", Value::Array(full))
+                .respond("GET /issues", json!([{ "id": 99 }])),
+        );
+        let items = fetch_all(&client, "/issues", &[("state", "open")]).unwrap();
+        assert_eq!(items.len(), PAGE_LIMIT + 1);
+        let requests = client.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(
+            requests[1]
+                .1
+                .contains(&("page".to_string(), "2".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_submit_reuses_open_pr_for_branch_or_creates_one() {
+        let client = MockHttpClient::default().respond(
+            "GET /pulls",
+            json!([pr_json(4, "rlph-3-fix", "Resolves #3")]),
+        );
+        let submission = GiteaSubmission::with_client(Box::new(client));
+        let result = submission.submit("rlph-3-fix", "main", "t", "b").unwrap();
+        assert_eq!(result.number, Some(4));
+
+        let client = Arc::new(
+            MockHttpClient::default()
+                .respond("GET /pulls", json!([]))
+                .respond("POST /pulls", pr_json(8, "rlph-5-x", "Resolves #5")),
+        );
+        let submission = GiteaSubmission::with_client(Box::new(client.clone()));
+        let result = submission.submit("rlph-5-x", "main", "t", "b").unwrap();
+        assert_eq!(result.url, "https://codeberg.org/o/r/pulls/8");
+        let requests = client.requests.lock().unwrap();
+        let body = requests[1].2.as_ref().unwrap();
+        assert_eq!(body["head"], "rlph-5-x");
+        assert_eq!(body["base"], "main");
+    }
+
+    #[test]
+    fn test_heartbeat_comment_is_upserted_and_deleted() {
+        let marked = json!([{ "id": 31, "body": format!("{HEARTBEAT_MARKER}\nworking") }]);
+        let client = Arc::new(
+            MockHttpClient::default()
+                .respond("GET /issues/6/comments", marked.clone())
+                .respond("GET /issues/6/comments", marked),
+        );
+        let submission = GiteaSubmission::with_client(Box::new(client.clone()));
+        submission
+            .upsert_heartbeat_comment(6, &format!("{HEARTBEAT_MARKER}\nstill working"))
+            .unwrap();
+        submission.delete_heartbeat_comment(6).unwrap();
+        let requests = client.requests.lock().unwrap();
+        let keys: Vec<&str> = requests.iter().map(|r| r.0.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "GET /issues/6/comments",
+                "PATCH /issues/comments/31",
+                "GET /issues/6/comments",
+                "DELETE /issues/comments/31",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pr_state() {
+        let mut merged = pr_json(2, "b", "");
+        merged["state"] = json!("closed");
+        merged["merged"] = json!(true);
+        let mut closed = pr_json(3, "b", "");
+        closed["state"] = json!("closed");
+        let client = MockHttpClient::default()
+            .respond("GET /pulls/2", merged)
+            .respond("GET /pulls/3", closed);
+        let submission = GiteaSubmission::with_client(Box::new(client));
+        assert_eq!(submission.pr_state(2).unwrap(), PrState::Merged);
+        assert_eq!(submission.pr_state(3).unwrap(), PrState::Closed);
+    }
+
+    #[test]
+    fn test_ci_status_from_commit_statuses() {
+        let client = MockHttpClient::default()
+            .respond("GET /pulls/4", pr_json(4, "b", ""))
+            .respond(
+                "GET /commits/abc123/status",
+                json!({ "state": "failure", "statuses": [
+                    { "context": "ci/build", "status": "success" },
+                    { "context": "ci/test", "status": "failure" },
+                ] }),
+            );
+        let submission = GiteaSubmission::with_client(Box::new(client));
+        assert_eq!(
+            submission.ci_status(4).unwrap(),
+            CiStatus::Failed(vec!["ci/test".to_string()])
+        );
+    }
+}
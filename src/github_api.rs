@@ -0,0 +1,1028 @@
+//! GitHub over its REST API, for environments without the `gh` CLI
+//! (`github_transport`): the client shared by
+//! [`GitHubApiSource`](crate::sources::github_api::GitHubApiSource) and
+//! [`GitHubApiSubmission`].
+
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::{Value, json};
+use tracing::{debug, info, warn};
+
+use crate::check_run::{CHECK_RUN_NAME, ReviewCheck, annotation_batches};
+use crate::config::{Config, GitHubTransport, MergeMethod};
+use crate::error::{Error, Result};
+use crate::gh_quota::{self, DEFAULT_RETRY_AFTER, gh_host};
+use crate::setup::on_path;
+use crate::submission::{
+    CiStatus, HEARTBEAT_MARKER, PrComment, PrContext, PrState, REVIEW_MARKER, SubmissionBackend,
+    SubmitResult, extract_issue_number_reference, pr_body_references_issue, review_feedback,
+};
+use crate::suggestion::{
+    SuggestionComment, diff_line_ranges, posted_suggestion_ids, suggestion_in_diff,
+};
+use crate::worktree::git_in_dir;
+
+const MAX_RETRIES: u32 = 3;
+const INITIAL_BACKOFF_MS: u64 = 500;
+/// Items requested per page of a paginated listing (GitHub's maximum).
+const PAGE_LIMIT: usize = 100;
+const DEFAULT_API_URL: &str = "https://api.github.com";
+
+/// Whether the GitHub source and submission backend use the REST API: always
+/// with `github_transport = "api"`, and with `"auto"` when `gh` is not on
+/// `PATH`.
+pub fn use_api(transport: GitHubTransport) -> bool {
+    match transport {
+        GitHubTransport::Api => true,
+        GitHubTransport::Cli => false,
+        GitHubTransport::Auto => {
+            let missing = !on_path("gh");
+            if missing {
+                debug!("gh not found on PATH; using the GitHub REST API");
+            }
+            missing
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Client abstraction (for testability)
+// ---------------------------------------------------------------------------
+
+pub trait GitHubApiClient: Send + Sync {
+    /// Send `method` to `path`, relative to the API root (e.g.
+    /// `/repos/<owner>/<repo>/pulls`), with `query` as the query string.
+    /// Returns the response JSON, or `Null` for an empty body.
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Value>,
+    ) -> std::result::Result<Value, String>;
+}
+
+struct HttpGitHubClient {
+    api_url: String,
+    token: String,
+    /// Host whose `gh_quota` bucket requests draw from.
+    host: String,
+}
+
+impl GitHubApiClient for HttpGitHubClient {
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Value>,
+    ) -> std::result::Result<Value, String> {
+        let url = format!("{}{path}", self.api_url);
+        let mut backoff_ms = INITIAL_BACKOFF_MS;
+        for attempt in 1..=MAX_RETRIES {
+            gh_quota::acquire(&self.host);
+            let mut request = ureq::request(method, &url)
+                .set("Authorization", &format!("Bearer {}", self.token))
+                .set("Accept", "application/vnd.github+json")
+                .set("X-GitHub-Api-Version", "2022-11-28");
+            for (name, value) in query {
+                request = request.query(name, value);
+            }
+            let response = match body {
+                Some(body) => request.send_json(body),
+                None => request.call(),
+            };
+            match response {
+                Ok(response) => {
+                    let text = response
+                        .into_string()
+                        .map_err(|e| format!("failed to read GitHub response: {e}"))?;
+                    if text.trim().is_empty() {
+                        return Ok(Value::Null);
+                    }
+                    return serde_json::from_str(&text)
+                        .map_err(|e| format!("failed to parse GitHub response: {e}"));
+                }
+                Err(ureq::Error::Status(code, response)) if attempt < MAX_RETRIES => {
+                    let delay = rate_limit_delay(
+                        code,
+                        response.header("retry-after"),
+                        response.header("x-ratelimit-remaining"),
+                        response.header("x-ratelimit-reset"),
+                        SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs(),
+                    );
+                    if let Some(delay) = delay {
+                        warn!(
+                            host = %self.host,
+                            attempt,
+                            delay_secs = delay.as_secs(),
+                            "GitHub rate limit hit; pausing requests"
+                        );
+                        gh_quota::pause(&self.host, delay);
+                    } else if code >= 500 {
+                        warn!(attempt, code, backoff_ms, "retrying GitHub API request");
+                        thread::sleep(Duration::from_millis(backoff_ms));
+                        backoff_ms *= 2;
+                    } else {
+                        let body = response.into_string().unwrap_or_default();
+                        return Err(format!(
+                            "GitHub API {method} {path} failed: status {code}: {body}"
+                        ));
+                    }
+                }
+                Err(ureq::Error::Status(code, response)) => {
+                    let body = response.into_string().unwrap_or_default();
+                    return Err(format!(
+                        "GitHub API {method} {path} failed: status {code}: {body}"
+                    ));
+                }
+                Err(e) if attempt < MAX_RETRIES => {
+                    warn!(attempt, error = %e, backoff_ms, "retrying GitHub API after transient error");
+                    thread::sleep(Duration::from_millis(backoff_ms));
+                    backoff_ms *= 2;
+                }
+                Err(e) => return Err(format!("GitHub API {method} {path} failed: {e}")),
+            }
+        }
+        Err(format!(
+            "GitHub API {method} {path} failed; retries exhausted"
+        ))
+    }
+}
+
+/// How long to back off after an error response, or `None` when it is not a
+/// rate limit: a 429, or a 403 with no requests remaining. Waits for
+/// `Retry-After`, else until the `X-RateLimit-Reset` epoch second.
+fn rate_limit_delay(
+    code: u16,
+    retry_after: Option<&str>,
+    remaining: Option<&str>,
+    reset: Option<&str>,
+    now_secs: u64,
+) -> Option<Duration> {
+    let limited = code == 429 || (code == 403 && remaining.map(str::trim) == Some("0"));
+    if !limited {
+        return None;
+    }
+    if let Some(secs) = retry_after.and_then(|s| s.trim().parse::<u64>().ok()) {
+        return Some(Duration::from_secs(secs));
+    }
+    Some(
+        reset
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .filter(|&reset| reset > now_secs)
+            .map_or(DEFAULT_RETRY_AFTER, |reset| {
+                Duration::from_secs(reset - now_secs)
+            }),
+    )
+}
+
+/// `owner/repo` from a GitHub remote URL: `https://github.com/o/r.git`,
+/// `git@github.com:o/r.git`, or `ssh://git@github.com/o/r`.
+pub fn parse_remote_repo(url: &str) -> Option<String> {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let mut parts = url.rsplit(['/', ':']).filter(|p| !p.is_empty());
+    let repo = parts.next()?;
+    let owner = parts.next()?;
+    // There must be a host before the owner.
+    parts.next()?;
+    Some(format!("{owner}/{repo}"))
+}
+
+/// A client bound to one repository.
+pub struct GitHubApi {
+    client: Box<dyn GitHubApiClient>,
+    owner: String,
+    /// `/repos/<owner>/<repo>`
+    repo_path: String,
+}
+
+impl GitHubApi {
+    /// The repository in `$GITHUB_REPOSITORY` (set in GitHub Actions), else
+    /// the one the current directory's `origin` remote points at, authenticated
+    /// with `$GITHUB_TOKEN` (or `$GH_TOKEN`). Requests go to `$GITHUB_API_URL`,
+    /// else the API of `GH_HOST`.
+    pub fn from_env() -> Result<Self> {
+        let token = ["GITHUB_TOKEN", "GH_TOKEN"]
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|t| !t.trim().is_empty()))
+            .ok_or_else(|| {
+                Error::ConfigValidation(
+                    "the GitHub REST API needs a token in $GITHUB_TOKEN (or $GH_TOKEN)".to_string(),
+                )
+            })?;
+        let repo = std::env::var("GITHUB_REPOSITORY")
+            .ok()
+            .filter(|r| !r.trim().is_empty())
+            .or_else(|| {
+                git_in_dir(Path::new("."), &["remote", "get-url", "origin"])
+                    .ok()
+                    .and_then(|url| parse_remote_repo(&url))
+            })
+            .ok_or_else(|| {
+                Error::ConfigValidation(
+                    "could not tell the GitHub repository from the origin remote; set $GITHUB_REPOSITORY to owner/repo"
+                        .to_string(),
+                )
+            })?;
+        let host = gh_host();
+        let api_url = std::env::var("GITHUB_API_URL")
+            .ok()
+            .filter(|u| !u.trim().is_empty())
+            .unwrap_or_else(|| {
+                if host == "github.com" {
+                    DEFAULT_API_URL.to_string()
+                } else {
+                    format!("https://{host}/api/v3")
+                }
+            });
+        Ok(Self::with_client(
+            Box::new(HttpGitHubClient {
+                api_url: api_url.trim_end_matches('/').to_string(),
+                token,
+                host,
+            }),
+            &repo,
+        ))
+    }
+
+    /// `repo` is `owner/repo`.
+    pub fn with_client(client: Box<dyn GitHubApiClient>, repo: &str) -> Self {
+        let owner = repo.split('/').next().unwrap_or_default().to_string();
+        Self {
+            client,
+            owner,
+            repo_path: format!("/repos/{repo}"),
+        }
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// A request to `path` relative to the API root.
+    pub fn call(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&Value>,
+    ) -> std::result::Result<Value, String> {
+        self.client.request(method, path, &[], body)
+    }
+
+    /// A request to `path` relative to the repository.
+    pub fn repo_call(
+        &self,
+        method: &str,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Value>,
+    ) -> std::result::Result<Value, String> {
+        self.client
+            .request(method, &format!("{}{path}", self.repo_path), query, body)
+    }
+
+    /// Up to `limit` items of a paginated listing at `path` (relative to the
+    /// repository), read page by page until a short page.
+    pub fn fetch_all(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        limit: usize,
+    ) -> std::result::Result<Vec<Value>, String> {
+        let per_page = PAGE_LIMIT.to_string();
+        let mut items = Vec::new();
+        for page in 1.. {
+            let page = page.to_string();
+            let mut paged = query.to_vec();
+            paged.extend([("per_page", per_page.as_str()), ("page", page.as_str())]);
+            let response = self.repo_call("GET", path, &paged, None)?;
+            let values = response.as_array().cloned().unwrap_or_default();
+            let done = values.len() < PAGE_LIMIT;
+            items.extend(values);
+            if items.len() >= limit {
+                items.truncate(limit);
+                return Ok(items);
+            }
+            if done {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    /// The repository's default branch.
+    pub fn default_branch(&self) -> std::result::Result<String, String> {
+        self.repo_call("GET", "", &[], None)?
+            .get("default_branch")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| "GitHub repository has no default branch".to_string())
+    }
+
+    /// Contents of `path` at commit `sha` of `repo` (`owner/repo`), which need
+    /// not be the repository this client is bound to.
+    pub fn file_at(
+        &self,
+        repo: &str,
+        path: &str,
+        sha: &str,
+    ) -> std::result::Result<String, String> {
+        let response = self.client.request(
+            "GET",
+            &format!("/repos/{repo}/contents/{path}"),
+            &[("ref", sha)],
+            None,
+        )?;
+        let encoded: String = response
+            .get("content")
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("{repo}/{path} is not a file"))?
+            .split_whitespace()
+            .collect();
+        let bytes = STANDARD
+            .decode(encoded)
+            .map_err(|e| format!("failed to decode {repo}/{path}: {e}"))?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// The first comment on issue or PR `number` whose body contains `marker`.
+    pub fn find_marked_comment(
+        &self,
+        number: u64,
+        marker: &str,
+    ) -> std::result::Result<Option<u64>, String> {
+        let comments = self.fetch_all(&format!("/issues/{number}/comments"), &[], usize::MAX)?;
+        Ok(comments.iter().find_map(|c| {
+            let body = c.get("body").and_then(Value::as_str)?;
+            if body.contains(marker) {
+                c.get("id").and_then(Value::as_u64)
+            } else {
+                None
+            }
+        }))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Submission
+// ---------------------------------------------------------------------------
+
+/// GitHub PR submission over the REST API; the `gh`-free counterpart of
+/// [`GitHubSubmission`](crate::submission::GitHubSubmission).
+pub struct GitHubApiSubmission {
+    api: GitHubApi,
+    head_owner: Option<String>,
+}
+
+impl GitHubApiSubmission {
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self::with_api(GitHubApi::from_env()?).with_head_owner(config.pr_head_owner.clone()))
+    }
+
+    pub fn with_api(api: GitHubApi) -> Self {
+        Self {
+            api,
+            head_owner: None,
+        }
+    }
+
+    /// Open PRs from branches in `owner`'s fork rather than the base repository.
+    pub fn with_head_owner(mut self, owner: Option<String>) -> Self {
+        self.head_owner = owner;
+        self
+    }
+
+    fn call(
+        &self,
+        method: &str,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Value>,
+    ) -> Result<Value> {
+        self.api
+            .repo_call(method, path, query, body)
+            .map_err(Error::Submission)
+    }
+
+    fn pull(&self, pr_number: u64) -> Result<Value> {
+        self.call("GET", &format!("/pulls/{pr_number}"), &[], None)
+    }
+
+    fn head_sha(&self, pr_number: u64) -> Result<String> {
+        pr_context(&self.pull(pr_number)?)
+            .and_then(|c| c.head_sha)
+            .ok_or_else(|| Error::Submission(format!("GitHub PR {pr_number} has no head commit")))
+    }
+
+    fn open_prs(&self) -> Result<Vec<PrContext>> {
+        Ok(self
+            .api
+            .fetch_all("/pulls", &[("state", "open")], usize::MAX)
+            .map_err(Error::Submission)?
+            .iter()
+            .filter_map(pr_context)
+            .collect())
+    }
+
+    pub fn get_pr_context(&self, pr_number: u64) -> Result<PrContext> {
+        pr_context(&self.pull(pr_number)?)
+            .ok_or_else(|| Error::Submission(format!("GitHub PR {pr_number} has no head branch")))
+    }
+
+    fn upsert_marked_comment(&self, number: u64, marker: &str, body: &str) -> Result<()> {
+        let existing = self
+            .api
+            .find_marked_comment(number, marker)
+            .map_err(Error::Submission)?;
+        match existing {
+            Some(id) => self.call(
+                "PATCH",
+                &format!("/issues/comments/{id}"),
+                &[],
+                Some(&json!({ "body": body })),
+            )?,
+            None => self.call(
+                "POST",
+                &format!("/issues/{number}/comments"),
+                &[],
+                Some(&json!({ "body": body })),
+            )?,
+        };
+        Ok(())
+    }
+}
+
+/// A pull request from the REST API as a [`PrContext`].
+fn pr_context(pr: &Value) -> Option<PrContext> {
+    let str_at = |pointer: &str| pr.pointer(pointer).and_then(Value::as_str);
+    let body = str_at("/body").unwrap_or_default().to_string();
+    Some(PrContext {
+        number: pr.get("number").and_then(Value::as_u64)?,
+        title: str_at("/title").unwrap_or_default().to_string(),
+        url: str_at("/html_url").unwrap_or_default().to_string(),
+        head_branch: str_at("/head/ref").filter(|b| !b.is_empty())?.to_string(),
+        head_sha: str_at("/head/sha").map(str::to_string),
+        base_branch: str_at("/base/ref").map(str::to_string),
+        linked_issue_number: extract_issue_number_reference(&body),
+        body,
+    })
+}
+
+/// Combine a commit's check runs (`/commits/<sha>/check-runs`) and its
+/// combined commit status (`/commits/<sha>/status`).
+fn ci_status_from(check_runs: &Value, status: &Value) -> CiStatus {
+    let mut pending = false;
+    let mut failed = Vec::new();
+    let runs = check_runs
+        .get("check_runs")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for run in runs {
+        let name = run.get("name").and_then(Value::as_str).unwrap_or_default();
+        if run.get("status").and_then(Value::as_str) != Some("completed") {
+            pending = true;
+            continue;
+        }
+        match run.get("conclusion").and_then(Value::as_str) {
+            Some("success" | "neutral" | "skipped") => {}
+            _ => failed.push(name.to_string()),
+        }
+    }
+    let statuses = status
+        .get("statuses")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for status in statuses {
+        let context = status.get("context").and_then(Value::as_str);
+        match status.get("state").and_then(Value::as_str) {
+            Some("success") => {}
+            Some("failure" | "error") => failed.push(context.unwrap_or_default().to_string()),
+            _ => pending = true,
+        }
+    }
+    if !failed.is_empty() {
+        CiStatus::Failed(failed)
+    } else if pending {
+        CiStatus::Pending
+    } else {
+        CiStatus::Passed
+    }
+}
+
+impl SubmissionBackend for GitHubApiSubmission {
+    fn submit(&self, branch: &str, base: &str, title: &str, body: &str) -> Result<SubmitResult> {
+        let owner = self.head_owner.as_deref().unwrap_or(self.api.owner());
+        let head = format!("{owner}:{branch}");
+        let existing = self.call(
+            "GET",
+            "/pulls",
+            &[("state", "open"), ("head", head.as_str())],
+            None,
+        )?;
+        if let Some(pr) = existing
+            .as_array()
+            .and_then(|prs| prs.iter().find_map(pr_context))
+        {
+            info!(url = %pr.url, "found existing PR for branch");
+            return Ok(SubmitResult {
+                url: pr.url,
+                number: Some(pr.number),
+            });
+        }
+        let head_ref = match &self.head_owner {
+            Some(_) => head.as_str(),
+            None => branch,
+        };
+        let created = self.call(
+            "POST",
+            "/pulls",
+            &[],
+            Some(&json!({ "head": head_ref, "base": base, "title": title, "body": body })),
+        )?;
+        let pr = pr_context(&created).ok_or_else(|| {
+            Error::Submission("GitHub did not return the created pull request".to_string())
+        })?;
+        info!(url = %pr.url, "created PR");
+        Ok(SubmitResult {
+            url: pr.url,
+            number: Some(pr.number),
+        })
+    }
+
+    fn find_existing_pr_for_issue(&self, issue_number: u64) -> Result<Option<u64>> {
+        Ok(self
+            .open_prs()?
+            .iter()
+            .find(|pr| pr_body_references_issue(&pr.body, issue_number))
+            .map(|pr| pr.number))
+    }
+
+    fn upsert_review_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.upsert_marked_comment(pr_number, REVIEW_MARKER, body)?;
+        info!(pr_number, "upserted review comment on PR");
+        Ok(())
+    }
+
+    fn fetch_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        let comments = self
+            .api
+            .fetch_all(&format!("/issues/{pr_number}/comments"), &[], usize::MAX)
+            .map_err(Error::Submission)?;
+        serde_json::from_value(Value::Array(comments))
+            .map_err(|e| Error::Submission(format!("failed to parse comments json: {e}")))
+    }
+
+    fn fetch_pr_review_feedback(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        let listing = |path: &str| {
+            self.api
+                .fetch_all(&format!("/pulls/{pr_number}/{path}"), &[], usize::MAX)
+                .map_err(Error::Submission)
+        };
+        Ok(review_feedback(&listing("comments")?, &listing("reviews")?))
+    }
+
+    fn list_open_prs(&self) -> Result<Vec<PrContext>> {
+        self.open_prs()
+    }
+
+    fn post_pr_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.call(
+            "POST",
+            &format!("/issues/{pr_number}/comments"),
+            &[],
+            Some(&json!({ "body": body })),
+        )?;
+        info!(pr_number, "posted comment on PR");
+        Ok(())
+    }
+
+    fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.call(
+            "PATCH",
+            &format!("/pulls/{pr_number}"),
+            &[],
+            Some(&json!({ "body": body })),
+        )?;
+        info!(pr_number, "updated PR body");
+        Ok(())
+    }
+
+    fn post_suggestions(&self, pr_number: u64, suggestions: &[SuggestionComment]) -> Result<usize> {
+        if suggestions.is_empty() {
+            return Ok(0);
+        }
+        // Rebuild the unified diff from the per-file patches.
+        let files = self
+            .api
+            .fetch_all(&format!("/pulls/{pr_number}/files"), &[], usize::MAX)
+            .map_err(Error::Submission)?;
+        let diff: String = files
+            .iter()
+            .filter_map(|f| {
+                let path = f.get("filename").and_then(Value::as_str)?;
+                let patch = f.get("patch").and_then(Value::as_str)?;
+                Some(format!("+++ b/{path}\n{patch}\n"))
+            })
+            .collect();
+        let ranges = diff_line_ranges(&diff);
+        let head = self.head_sha(pr_number)?;
+        let endpoint = format!("/pulls/{pr_number}/comments");
+        let existing = self
+            .api
+            .fetch_all(&endpoint, &[], usize::MAX)
+            .map_err(Error::Submission)?;
+        let posted = posted_suggestion_ids(
+            existing
+                .iter()
+                .filter_map(|c| c.get("body").and_then(Value::as_str)),
+        );
+
+        let mut count = 0;
+        for suggestion in suggestions {
+            if posted.contains(&suggestion.finding_id) {
+                continue;
+            }
+            if !suggestion_in_diff(suggestion, &ranges) {
+                warn!(
+                    finding = %suggestion.finding_id,
+                    path = %suggestion.path,
+                    "suggested patch is outside the PR diff — skipping"
+                );
+                continue;
+            }
+            let mut comment = json!({
+                "body": suggestion.body,
+                "commit_id": head,
+                "path": suggestion.path,
+                "line": suggestion.end_line,
+                "side": "RIGHT",
+            });
+            if suggestion.start_line < suggestion.end_line {
+                comment["start_line"] = json!(suggestion.start_line);
+                comment["start_side"] = json!("RIGHT");
+            }
+            if let Err(e) = self.call("POST", &endpoint, &[], Some(&comment)) {
+                warn!(finding = %suggestion.finding_id, "failed to post suggestion: {e}");
+                continue;
+            }
+            count += 1;
+        }
+
+        info!(pr_number, count, "posted suggested changes on PR");
+        Ok(count)
+    }
+
+    fn pr_state(&self, pr_number: u64) -> Result<PrState> {
+        let pr = self.pull(pr_number)?;
+        if pr.get("merged").and_then(Value::as_bool) == Some(true)
+            || pr.get("merged_at").is_some_and(|m| !m.is_null())
+        {
+            return Ok(PrState::Merged);
+        }
+        match pr.get("state").and_then(Value::as_str) {
+            Some("open") => Ok(PrState::Open),
+            Some("closed") => Ok(PrState::Closed),
+            other => Err(Error::Submission(format!(
+                "unknown GitHub PR state: {other:?}"
+            ))),
+        }
+    }
+
+    fn close_pr(&self, pr_number: u64, comment: &str) -> Result<()> {
+        self.post_pr_comment(pr_number, comment)?;
+        self.call(
+            "PATCH",
+            &format!("/pulls/{pr_number}"),
+            &[],
+            Some(&json!({ "state": "closed" })),
+        )?;
+        info!(pr_number, "closed PR");
+        Ok(())
+    }
+
+    fn publish_review_check(&self, pr_number: u64, check: &ReviewCheck) -> Result<()> {
+        let head_sha = self.head_sha(pr_number)?;
+
+        // GitHub caps annotations per request: the first batch creates the check
+        // run and the rest are appended to it.
+        let mut check_run_id: Option<u64> = None;
+        for batch in annotation_batches(&check.annotations) {
+            let output = json!({
+                "title": check.title,
+                "summary": check.summary,
+                "annotations": batch,
+            });
+            match check_run_id {
+                None => {
+                    let created = self.call(
+                        "POST",
+                        "/check-runs",
+                        &[],
+                        Some(&json!({
+                            "name": CHECK_RUN_NAME,
+                            "head_sha": head_sha,
+                            "status": "completed",
+                            "conclusion": check.conclusion,
+                            "output": output,
+                        })),
+                    )?;
+                    check_run_id = Some(created["id"].as_u64().ok_or_else(|| {
+                        Error::Submission("check run response has no id".to_string())
+                    })?);
+                }
+                Some(id) => {
+                    self.call(
+                        "PATCH",
+                        &format!("/check-runs/{id}"),
+                        &[],
+                        Some(&json!({ "output": output })),
+                    )?;
+                }
+            }
+        }
+
+        info!(
+            pr_number,
+            annotations = check.annotations.len(),
+            "published review check run"
+        );
+        Ok(())
+    }
+
+    fn upsert_heartbeat_comment(&self, number: u64, body: &str) -> Result<()> {
+        self.upsert_marked_comment(number, HEARTBEAT_MARKER, body)
+    }
+
+    fn delete_heartbeat_comment(&self, number: u64) -> Result<()> {
+        if let Some(id) = self
+            .api
+            .find_marked_comment(number, HEARTBEAT_MARKER)
+            .map_err(Error::Submission)?
+        {
+            self.call("DELETE", &format!("/issues/comments/{id}"), &[], None)?;
+        }
+        Ok(())
+    }
+
+    fn ci_status(&self, pr_number: u64) -> Result<CiStatus> {
+        let sha = self.head_sha(pr_number)?;
+        let check_runs = self.call(
+            "GET",
+            &format!("/commits/{sha}/check-runs"),
+            &[("per_page", "100")],
+            None,
+        )?;
+        let status = self.call("GET", &format!("/commits/{sha}/status"), &[], None)?;
+        Ok(ci_status_from(&check_runs, &status))
+    }
+
+    /// Auto-merge is only exposed through GraphQL.
+    fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        let pr = self.pull(pr_number)?;
+        let id = pr
+            .get("node_id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::Submission(format!("GitHub PR {pr_number} has no node id")))?;
+        let method = match method {
+            MergeMethod::Rebase => "REBASE",
+            MergeMethod::Squash => "SQUASH",
+            MergeMethod::Merge => "MERGE",
+        };
+        let response = self
+            .api
+            .call(
+                "POST",
+                "/graphql",
+                Some(&json!({
+                    "query": "mutation($id: ID!, $method: PullRequestMergeMethod!) { enablePullRequestAutoMerge(input: {pullRequestId: $id, mergeMethod: $method}) { clientMutationId } }",
+                    "variables": { "id": id, "method": method },
+                })),
+            )
+            .map_err(Error::Submission)?;
+        if let Some(errors) = response.get("errors").filter(|e| !e.is_null()) {
+            return Err(Error::Submission(format!(
+                "enabling auto-merge failed: {errors}"
+            )));
+        }
+        info!(pr_number, "enabled auto-merge");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::MockHttpClient;
+    use std::sync::Arc;
+
+    impl GitHubApiClient for MockHttpClient {
+        fn request(
+            &self,
+            method: &str,
+            path: &str,
+            query: &[(&str, &str)],
+            body: Option<&Value>,
+        ) -> std::result::Result<Value, String> {
+            self.reply(method, path, query, body)
+        }
+    }
+
+    impl GitHubApiClient for Arc<MockHttpClient> {
+        fn request(
+            &self,
+            method: &str,
+            path: &str,
+            query: &[(&str, &str)],
+            body: Option<&Value>,
+        ) -> std::result::Result<Value, String> {
+            (**self).request(method, path, query, body)
+        }
+    }
+
+    fn pr_json(number: u64, branch: &str, body: &str) -> Value {
+        json!({
+            "number": number,
+            "node_id": format!("PR_{number}"),
+            "title": "Fix login",
+            "body": body,
+            "state": "open",
+            "merged_at": null,
+            "html_url": format!("https://github.com/o/r/pull/{number}"),
+            "head": { "ref": branch, "sha": "abc123" },
+            "base": { "ref": "main" },
+        })
+    }
+
+    #[test]
+    fn test_parse_remote_repo_and_rate_limit_delay() {
+        for url in [
+            "https://github.com/o/r.git",
+            "https://github.com/o/r/",
+            "git@github.com:o/r.git",
+            "ssh://git@github.com/o/r",
+        ] {
+            assert_eq!(parse_remote_repo(url).as_deref(), Some("o/r"), "{url}");
+        }
+        assert_eq!(parse_remote_repo("r"), None);
+
+        assert_eq!(rate_limit_delay(404, None, None, None, 0), None);
+        assert_eq!(rate_limit_delay(403, None, Some("12"), None, 0), None);
+        assert_eq!(
+            rate_limit_delay(429, Some("7"), None, None, 0),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            rate_limit_delay(403, None, Some("0"), Some("1030"), 1000),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            rate_limit_delay(429, None, None, None, 0),
+            Some(DEFAULT_RETRY_AFTER)
+        );
+    }
+
+    #[test]
+    fn test_default_branch_and_file_at() {
+        let client = Arc::new(
+            MockHttpClient::default()
+                .respond("GET /repos/o/r", json!({ "default_branch": "trunk" }))
+                .respond(
+                    "GET /repos/x/y/contents/src/lib.rs",
+                    json!({ "content": "b25lCnR3\nbwo=\n" }),
+                ),
+        );
+        let api = GitHubApi::with_client(Box::new(client.clone()), "o/r");
+        assert_eq!(api.default_branch().unwrap(), "trunk");
+        assert_eq!(
+            api.file_at("x/y", "src/lib.rs", "abc").unwrap(),
+            "one\ntwo\n"
+        );
+        assert_eq!(
+            client.requests.lock().unwrap()[1].1,
+            vec![("ref".to_string(), "abc".to_string())]
+        );
+        assert!(api.default_branch().is_err());
+    }
+
+    #[test]
+    fn test_fetch_all_reads_until_limit_or_short_page() {
+        let full: Vec<Value> = (0..PAGE_LIMIT).map(|i| json!({ "id": i })).collect();
+        let client = Arc::new(
+            MockHttpClient::default()
+                .respond("GET /repos/o/r/issues", Value::Array(full.clone()))
+                .respond("GET /repos/o/r/issues", json!([{ "id": 99 }])),
+        );
+        let api = GitHubApi::with_client(Box::new(client.clone()), "o/r");
+        let items = api.fetch_all("/issues", &[("state", "open")], 500).unwrap();
+        assert_eq!(items.len(), PAGE_LIMIT + 1);
+        let requests = client.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(
+            requests[1]
+                .1
+                .contains(&("page".to_string(), "2".to_string()))
+        );
+        drop(requests);
+
+        let client = MockHttpClient::default()
+            .respond("GET /repos/o/r/issues", Value::Array(full.clone()))
+            .respond("GET /repos/o/r/issues", Value::Array(full));
+        let api = GitHubApi::with_client(Box::new(client), "o/r");
+        assert_eq!(api.fetch_all("/issues", &[], 150).unwrap().len(), 150);
+    }
+
+    #[test]
+    fn test_submit_reuses_open_pr_for_branch_or_creates_one() {
+        let client = Arc::new(MockHttpClient::default().respond(
+            "GET /repos/o/r/pulls",
+            json!([pr_json(4, "rlph-3-fix", "Resolves #3")]),
+        ));
+        let submission =
+            GitHubApiSubmission::with_api(GitHubApi::with_client(Box::new(client.clone()), "o/r"));
+        let result = submission.submit("rlph-3-fix", "main", "t", "b").unwrap();
+        assert_eq!(result.number, Some(4));
+        assert!(
+            client.requests.lock().unwrap()[0]
+                .1
+                .contains(&("head".to_string(), "o:rlph-3-fix".to_string()))
+        );
+
+        let client = Arc::new(
+            MockHttpClient::default()
+                .respond("GET /repos/o/r/pulls", json!([]))
+                .respond(
+                    "POST /repos/o/r/pulls",
+                    pr_json(8, "rlph-5-x", "Resolves #5"),
+                ),
+        );
+        let submission =
+            GitHubApiSubmission::with_api(GitHubApi::with_client(Box::new(client.clone()), "o/r"))
+                .with_head_owner(Some("fork".to_string()));
+        let result = submission.submit("rlph-5-x", "main", "t", "b").unwrap();
+        assert_eq!(result.url, "https://github.com/o/r/pull/8");
+        let requests = client.requests.lock().unwrap();
+        let body = requests[1].2.as_ref().unwrap();
+        assert_eq!(body["head"], "fork:rlph-5-x");
+        assert_eq!(body["base"], "main");
+    }
+
+    #[test]
+    fn test_ci_status_combines_check_runs_and_statuses() {
+        let runs = json!({ "check_runs": [
+            { "name": "build", "status": "completed", "conclusion": "success" },
+            { "name": "lint", "status": "completed", "conclusion": "skipped" },
+        ] });
+        let passed = json!({ "statuses": [{ "context": "ci/docs", "state": "success" }] });
+        assert_eq!(ci_status_from(&runs, &passed), CiStatus::Passed);
+        assert_eq!(ci_status_from(&json!({}), &json!({})), CiStatus::Passed);
+
+        let running = json!({ "check_runs": [{ "name": "test", "status": "in_progress" }] });
+        assert_eq!(ci_status_from(&running, &passed), CiStatus::Pending);
+
+        let failed = json!({ "statuses": [{ "context": "ci/docs", "state": "error" }] });
+        assert_eq!(
+            ci_status_from(&running, &failed),
+            CiStatus::Failed(vec!["ci/docs".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_enable_auto_merge_uses_graphql() {
+        let client = Arc::new(
+            MockHttpClient::default()
+                .respond("GET /repos/o/r/pulls/4", pr_json(4, "b", ""))
+                .respond(
+                    "POST /graphql",
+                    json!({ "data": { "enablePullRequestAutoMerge": null } }),
+                )
+                .respond("GET /repos/o/r/pulls/4", pr_json(4, "b", ""))
+                .respond(
+                    "POST /graphql",
+                    json!({ "errors": [{ "message": "auto-merge is not allowed" }] }),
+                ),
+        );
+        let submission =
+            GitHubApiSubmission::with_api(GitHubApi::with_client(Box::new(client.clone()), "o/r"));
+        submission
+            .enable_auto_merge(4, MergeMethod::Squash)
+            .unwrap();
+        let variables = client.requests.lock().unwrap()[1].2.as_ref().unwrap()["variables"].clone();
+        assert_eq!(variables, json!({ "id": "PR_4", "method": "SQUASH" }));
+
+        let err = submission
+            .enable_auto_merge(4, MergeMethod::Squash)
+            .unwrap_err();
+        assert!(err.to_string().contains("auto-merge is not allowed"));
+    }
+}
@@ -0,0 +1,73 @@
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::submission::HEARTBEAT_MARKER;
+
+/// Await `fut`, calling `beat` with the elapsed time every `every` until it
+/// completes. Returns the output and whether `beat` was called at all.
+pub async fn with_heartbeat<F: Future>(
+    fut: F,
+    every: Duration,
+    mut beat: impl FnMut(Duration),
+) -> (F::Output, bool) {
+    let started = Instant::now();
+    let mut next = started + every;
+    let mut beaten = false;
+    tokio::pin!(fut);
+    loop {
+        tokio::select! {
+            output = &mut fut => return (output, beaten),
+            _ = tokio::time::sleep_until(next) => {
+                beat(started.elapsed());
+                beaten = true;
+                next += every;
+            }
+        }
+    }
+}
+
+/// Body of the comment kept on an issue or PR while `phase` runs.
+pub fn heartbeat_comment(phase: &str, elapsed: Duration) -> String {
+    format!(
+        "{HEARTBEAT_MARKER}\n_rlph is still working on {phase} (elapsed {}m). This comment is removed when the phase finishes._",
+        elapsed.as_secs() / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_with_heartbeat_beats_until_done() {
+        let mut beats = Vec::new();
+        let (output, beaten) = with_heartbeat(
+            async {
+                tokio::time::sleep(Duration::from_millis(110)).await;
+                7
+            },
+            Duration::from_millis(30),
+            |elapsed| beats.push(elapsed),
+        )
+        .await;
+        assert_eq!(output, 7);
+        assert!(beaten);
+        assert!(beats.len() >= 2, "{beats:?}");
+        assert!(beats.windows(2).all(|w| w[0] < w[1]));
+
+        let (_, beaten) = with_heartbeat(async {}, Duration::from_secs(60), |_| {}).await;
+        assert!(!beaten);
+    }
+
+    #[test]
+    fn test_heartbeat_comment() {
+        let body = heartbeat_comment("review", Duration::from_secs(12 * 60 + 5));
+        assert!(body.starts_with(HEARTBEAT_MARKER));
+        assert!(
+            body.contains("still working on review (elapsed 12m)"),
+            "{body}"
+        );
+    }
+}
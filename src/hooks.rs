@@ -0,0 +1,299 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::{Value, json};
+use tracing::{debug, warn};
+
+use crate::config::HooksConfig;
+use crate::dry_run::DryRunAction;
+use crate::error::{Error, Result};
+use crate::orchestrator::ProgressReporter;
+use crate::poll::QueueDelta;
+use crate::runs::RunDigest;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Lifecycle events that can trigger a hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    TaskSelected,
+    PrCreated,
+    ReviewFailed,
+    IterationComplete,
+    QueueChanged,
+    RunDigest,
+}
+
+impl HookEvent {
+    /// Event name as sent in the payload's `event` field.
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::TaskSelected => "task_selected",
+            HookEvent::PrCreated => "pr_created",
+            HookEvent::ReviewFailed => "review_failed",
+            HookEvent::IterationComplete => "iteration_complete",
+            HookEvent::QueueChanged => "queue_changed",
+            HookEvent::RunDigest => "run_digest",
+        }
+    }
+
+    fn command(self, hooks: &HooksConfig) -> Option<&str> {
+        match self {
+            HookEvent::TaskSelected => hooks.on_task_selected.as_deref(),
+            HookEvent::PrCreated => hooks.on_pr_created.as_deref(),
+            HookEvent::ReviewFailed => hooks.on_review_failed.as_deref(),
+            HookEvent::IterationComplete => hooks.on_iteration_complete.as_deref(),
+            HookEvent::QueueChanged => hooks.on_queue_changed.as_deref(),
+            HookEvent::RunDigest => hooks.on_run_digest.as_deref(),
+        }
+    }
+}
+
+/// Run `command` with `sh -c` in `working_dir`, writing `payload` to its stdin.
+/// Output goes to rlph's own stdout/stderr. The command is killed after `timeout`.
+pub fn run_hook(command: &str, payload: &str, working_dir: &Path, timeout: Duration) -> Result<()> {
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .current_dir(working_dir)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::Process(format!("failed to spawn hook `{command}`: {e}")))?;
+
+    // Write from a thread so a hook that never reads stdin cannot block us.
+    if let Some(mut stdin) = child.stdin.take() {
+        let payload = payload.to_string();
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(payload.as_bytes());
+        });
+    }
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let status = child
+            .try_wait()
+            .map_err(|e| Error::Process(format!("failed to wait for hook `{command}`: {e}")))?;
+        match status {
+            Some(status) if status.success() => return Ok(()),
+            Some(status) => {
+                return Err(Error::Process(format!("hook `{command}` failed: {status}")));
+            }
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(Error::Process(format!(
+                    "hook `{command}` timed out after {}s",
+                    timeout.as_secs()
+                )));
+            }
+            None => std::thread::sleep(POLL_INTERVAL),
+        }
+    }
+}
+
+/// Reporter that runs the configured `[hooks]` commands, then forwards every
+/// event to `inner`.
+///
+/// Payloads are JSON objects with an `event` name plus the event's fields. Events
+/// after `task_selected` also carry that task's `issue_number` and `title`. Hook
+/// failures are logged and never fail the iteration.
+pub struct HookReporter<P> {
+    inner: P,
+    hooks: HooksConfig,
+    working_dir: PathBuf,
+    current_task: Mutex<Option<(u64, String)>>,
+}
+
+impl<P> HookReporter<P> {
+    pub fn new(inner: P, hooks: HooksConfig, working_dir: PathBuf) -> Self {
+        Self {
+            inner,
+            hooks,
+            working_dir,
+            current_task: Mutex::new(None),
+        }
+    }
+
+    fn fire(&self, event: HookEvent, fields: Value) {
+        let Some(command) = event.command(&self.hooks) else {
+            return;
+        };
+        let mut payload = json!({ "event": event.name() });
+        if let Some((issue_number, title)) = self.current_task.lock().unwrap().as_ref() {
+            payload["issue_number"] = json!(issue_number);
+            payload["title"] = json!(title);
+        }
+        if let (Value::Object(payload), Value::Object(fields)) = (&mut payload, fields) {
+            payload.extend(fields);
+        }
+        debug!(event = event.name(), command, "running hook");
+        let timeout = Duration::from_secs(self.hooks.timeout_seconds);
+        if let Err(e) = run_hook(command, &payload.to_string(), &self.working_dir, timeout) {
+            warn!(event = event.name(), error = %e, "hook failed");
+        }
+    }
+}
+
+impl<P: ProgressReporter> ProgressReporter for HookReporter<P> {
+    fn fetching_tasks(&self) {
+        self.inner.fetching_tasks();
+    }
+
+    fn tasks_found(&self, count: usize) {
+        self.inner.tasks_found(count);
+    }
+
+    fn queue_changed(&self, delta: &QueueDelta) {
+        self.fire(HookEvent::QueueChanged, json!(delta));
+        self.inner.queue_changed(delta);
+    }
+
+    fn task_selected(&self, issue_number: u64, title: &str) {
+        *self.current_task.lock().unwrap() = Some((issue_number, title.to_string()));
+        self.fire(HookEvent::TaskSelected, json!({}));
+        self.inner.task_selected(issue_number, title);
+    }
+
+    fn implement_started(&self) {
+        self.inner.implement_started();
+    }
+
+    fn pr_created(&self, url: &str) {
+        self.fire(HookEvent::PrCreated, json!({ "pr_url": url }));
+        self.inner.pr_created(url);
+    }
+
+    fn iteration_complete(&self, issue_number: u64, title: &str) {
+        self.fire(HookEvent::IterationComplete, json!({}));
+        *self.current_task.lock().unwrap() = None;
+        self.inner.iteration_complete(issue_number, title);
+    }
+
+    fn phases_started(&self, names: &[String]) {
+        self.inner.phases_started(names);
+    }
+
+    fn phase_complete(&self, name: &str) {
+        self.inner.phase_complete(name);
+    }
+
+    fn review_summary(&self, body: &str) {
+        self.inner.review_summary(body);
+    }
+
+    fn review_failed(&self, pr_url: Option<&str>, reason: &str) {
+        self.fire(
+            HookEvent::ReviewFailed,
+            json!({ "pr_url": pr_url, "reason": reason }),
+        );
+        self.inner.review_failed(pr_url, reason);
+    }
+
+    fn pr_url(&self, url: &str) {
+        self.inner.pr_url(url);
+    }
+
+    fn dry_run_action(&self, action: &DryRunAction) {
+        self.inner.dry_run_action(action);
+    }
+
+    fn run_digest(&self, digest: &RunDigest) {
+        *self.current_task.lock().unwrap() = None;
+        let mut fields = json!(digest);
+        fields["report"] = json!(digest.render());
+        self.fire(HookEvent::RunDigest, fields);
+        self.inner.run_digest(digest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::StderrReporter;
+
+    fn read_payloads(path: &Path) -> Vec<Value> {
+        std::fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_run_hook_passes_payload_on_stdin() {
+        let dir = tempfile::tempdir().unwrap();
+        run_hook(
+            "cat > payload.json",
+            r#"{"event":"pr_created"}"#,
+            dir.path(),
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        let written = std::fs::read_to_string(dir.path().join("payload.json")).unwrap();
+        assert_eq!(written, r#"{"event":"pr_created"}"#);
+    }
+
+    #[test]
+    fn test_run_hook_reports_failure_and_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = run_hook("exit 3", "{}", dir.path(), Duration::from_secs(5)).unwrap_err();
+        assert!(err.to_string().contains("hook `exit 3` failed"), "{err}");
+
+        let started = Instant::now();
+        let err = run_hook("sleep 10", "{}", dir.path(), Duration::from_millis(100)).unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_hook_reporter_fires_configured_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = HooksConfig {
+            on_task_selected: Some("cat >> events.jsonl; echo >> events.jsonl".to_string()),
+            on_pr_created: Some("cat >> events.jsonl; echo >> events.jsonl".to_string()),
+            on_review_failed: Some("cat >> events.jsonl; echo >> events.jsonl".to_string()),
+            ..Default::default()
+        };
+        let reporter = HookReporter::new(StderrReporter, hooks, dir.path().to_path_buf());
+
+        reporter.fetching_tasks();
+        reporter.task_selected(42, "Fix bug");
+        reporter.pr_created("https://github.com/o/r/pull/7");
+        reporter.review_failed(
+            Some("https://github.com/o/r/pull/7"),
+            "2 unresolved findings",
+        );
+        // No on_iteration_complete hook is configured.
+        reporter.iteration_complete(42, "Fix bug");
+
+        let payloads = read_payloads(&dir.path().join("events.jsonl"));
+        assert_eq!(payloads.len(), 3);
+        assert_eq!(
+            payloads[0],
+            json!({ "event": "task_selected", "issue_number": 42, "title": "Fix bug" })
+        );
+        assert_eq!(payloads[1]["event"], "pr_created");
+        assert_eq!(payloads[1]["issue_number"], 42);
+        assert_eq!(payloads[1]["pr_url"], "https://github.com/o/r/pull/7");
+        assert_eq!(payloads[2]["event"], "review_failed");
+        assert_eq!(payloads[2]["reason"], "2 unresolved findings");
+    }
+
+    #[test]
+    fn test_failing_hook_does_not_stop_reporting() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = HooksConfig {
+            on_task_selected: Some("exit 1".to_string()),
+            on_iteration_complete: Some("cat > done.json".to_string()),
+            ..Default::default()
+        };
+        let reporter = HookReporter::new(StderrReporter, hooks, dir.path().to_path_buf());
+        reporter.task_selected(7, "Task");
+        reporter.iteration_complete(7, "Task");
+        let payloads = read_payloads(&dir.path().join("done.json"));
+        assert_eq!(payloads[0]["event"], "iteration_complete");
+        assert_eq!(payloads[0]["issue_number"], 7);
+    }
+}
@@ -0,0 +1,56 @@
+use std::path::Path;
+use std::process::Stdio;
+
+use tracing::info;
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+
+/// Build the agent command for an interactive session seeded with `prompt`.
+///
+/// Returns `(binary, args)` suitable for spawning with inherited stdio. The
+/// prompt is passed as a positional argument so the session stays interactive.
+pub fn interactive_command(config: &Config, prompt: &str) -> (String, Vec<String>) {
+    let mut args = Vec::new();
+    if let Some(ref model) = config.agent_model {
+        args.push("--model".to_string());
+        args.push(model.clone());
+    }
+    args.push(prompt.to_string());
+    (config.agent_binary.clone(), args)
+}
+
+/// Run `binary` attached to this terminal, in `dir` when given.
+///
+/// Blocks until the agent exits, then returns its exit code.
+pub async fn run_interactive(binary: &str, args: &[String], dir: Option<&Path>) -> Result<i32> {
+    info!(
+        binary,
+        dir = ?dir,
+        args = ?args.iter().take(3).collect::<Vec<_>>(),
+        "launching interactive agent session"
+    );
+
+    let mut cmd = tokio::process::Command::new(binary);
+    cmd.args(args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+    if let Some(dir) = dir {
+        cmd.current_dir(dir);
+    }
+
+    // Remove CLAUDECODE env var to allow nested CLI invocation.
+    cmd.env_remove("CLAUDECODE");
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| Error::Process(format!("failed to spawn '{binary}': {e}")))?;
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| Error::Process(format!("failed to wait for agent: {e}")))?;
+
+    Ok(status.code().unwrap_or(1))
+}
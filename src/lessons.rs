@@ -0,0 +1,195 @@
+//! `.rlph/lessons.md`: review findings that needed a fix, kept across tasks.
+//!
+//! When a task's review is approved, each finding a fix round addressed is
+//! appended as one line together with what the fix did. The file is injected
+//! into later implement prompts as `past_lessons`, so the agent does not keep
+//! repeating feedback this repo's reviewers have already given.
+
+use std::path::Path;
+
+use tracing::{debug, warn};
+
+use crate::error::Result;
+use crate::review_schema::{ReviewFinding, Severity};
+
+/// Repo-relative path of the lessons file.
+pub const LESSONS_FILE: &str = ".rlph/lessons.md";
+
+/// Cap on the lessons file; the oldest lessons are dropped first.
+pub const MAX_LESSONS_CHARS: usize = 8_000;
+
+/// Cap on each half of a lesson line.
+const MAX_LESSON_PART_CHARS: usize = 240;
+
+const HEADER: &str = "# Lessons from past reviews\n\n";
+
+/// One review finding and how it was resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lesson {
+    /// `[severity/category] description`, collapsed to one line.
+    pub finding: String,
+    pub resolution: String,
+}
+
+impl Lesson {
+    /// Lessons for the findings a successful fix round addressed. Info findings
+    /// are not blocking, so they are not worth remembering.
+    pub fn from_round(findings: &[ReviewFinding], fix_summary: &str) -> Vec<Lesson> {
+        let resolution = one_line(fix_summary);
+        findings
+            .iter()
+            .filter(|f| f.severity != Severity::Info)
+            .map(|f| {
+                let tag = match &f.category {
+                    Some(category) => format!("{}/{category}", f.severity.label()),
+                    None => f.severity.label().to_string(),
+                };
+                Lesson {
+                    finding: format!("[{tag}] {}", one_line(&f.description)),
+                    resolution: resolution.clone(),
+                }
+            })
+            .collect()
+    }
+
+    fn line(&self) -> String {
+        if self.resolution.is_empty() {
+            format!("- {}", self.finding)
+        } else {
+            format!("- {} — fixed: {}", self.finding, self.resolution)
+        }
+    }
+}
+
+/// Collapse whitespace and truncate to [`MAX_LESSON_PART_CHARS`].
+fn one_line(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= MAX_LESSON_PART_CHARS {
+        return text;
+    }
+    let mut truncated: String = text.chars().take(MAX_LESSON_PART_CHARS).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Dedup key of a lesson line: its finding, case-insensitive.
+fn dedup_key(line: &str) -> String {
+    let finding = line.split(" — fixed: ").next().unwrap_or(line);
+    finding.to_lowercase()
+}
+
+/// Read the lessons file for the `past_lessons` prompt variable. Returns an
+/// empty string when there is none.
+pub fn load_lessons(repo_root: &Path) -> String {
+    let path = repo_root.join(LESSONS_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(content) => lesson_lines(&content).join("\n"),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            debug!(path = %path.display(), "no lessons file");
+            String::new()
+        }
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "failed to read lessons file");
+            String::new()
+        }
+    }
+}
+
+fn lesson_lines(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .filter(|line| line.starts_with("- "))
+        .collect()
+}
+
+/// Append `lessons` to the lessons file, skipping ones whose finding is
+/// already recorded and dropping the oldest lines to stay under
+/// [`MAX_LESSONS_CHARS`]. Returns how many lessons were added.
+pub fn record_lessons(repo_root: &Path, lessons: &[Lesson]) -> Result<usize> {
+    let path = repo_root.join(LESSONS_FILE);
+    let existing = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let mut lines: Vec<String> = lesson_lines(&existing)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let mut added = 0;
+    for lesson in lessons {
+        let line = lesson.line();
+        let key = dedup_key(&line);
+        if lines.iter().all(|l| dedup_key(l) != key) {
+            lines.push(line);
+            added += 1;
+        }
+    }
+    if added == 0 {
+        return Ok(0);
+    }
+
+    let mut total: usize = lines.iter().map(|l| l.chars().count() + 1).sum();
+    let mut drop = 0;
+    while total > MAX_LESSONS_CHARS && drop < lines.len() {
+        total -= lines[drop].chars().count() + 1;
+        drop += 1;
+    }
+    lines.drain(..drop);
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, format!("{HEADER}{}\n", lines.join("\n")))?;
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn finding(severity: Severity, description: &str) -> ReviewFinding {
+        ReviewFinding {
+            id: "f1".to_string(),
+            file: "src/lib.rs".to_string(),
+            line: 1,
+            severity,
+            description: description.to_string(),
+            category: Some("error-handling".to_string()),
+            depends_on: Vec::new(),
+            extra: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_record_lessons_dedupes_and_caps() {
+        let dir = TempDir::new().unwrap();
+        let findings = [
+            finding(Severity::Warning, "Do not\n unwrap the config"),
+            finding(Severity::Info, "Consider a doc comment"),
+        ];
+        let lessons = Lesson::from_round(&findings, "Propagated the error with `?`.");
+        assert_eq!(lessons.len(), 1);
+        assert_eq!(record_lessons(dir.path(), &lessons).unwrap(), 1);
+
+        let again = Lesson::from_round(&findings[..1], "Something else");
+        assert_eq!(record_lessons(dir.path(), &again).unwrap(), 0);
+        assert_eq!(
+            load_lessons(dir.path()),
+            "- [WARNING/error-handling] Do not unwrap the config — fixed: Propagated the error with `?`."
+        );
+
+        let many: Vec<Lesson> = (0..100)
+            .map(|i| Lesson {
+                finding: format!("finding {i} {}", "x".repeat(100)),
+                resolution: String::new(),
+            })
+            .collect();
+        record_lessons(dir.path(), &many).unwrap();
+        let text = load_lessons(dir.path());
+        assert!(text.chars().count() <= MAX_LESSONS_CHARS);
+        assert!(text.ends_with(&format!("finding 99 {}", "x".repeat(100))));
+        assert!(!text.contains("unwrap the config"));
+    }
+}
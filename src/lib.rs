@@ -1,19 +1,77 @@
+pub mod analyzer;
+pub mod api;
+pub mod automated_checks;
+pub mod base_branch;
+pub mod bitbucket;
+pub mod builder;
+pub mod check_run;
 pub mod cli;
 pub mod config;
+pub mod context;
+pub mod conventions;
+pub mod coverage;
 pub mod deps;
+pub mod disk_usage;
+pub mod dry_run;
 pub mod error;
+pub mod events;
+pub mod exit_status;
 pub mod fix;
 pub mod fix_comment;
+pub mod fix_trailers;
+pub mod gh_quota;
+pub mod gitea;
+pub mod github_api;
+pub mod heartbeat;
+pub mod hooks;
+pub mod interactive;
+pub mod lessons;
+pub mod multiplex;
 pub mod orchestrator;
+pub mod pause;
+pub mod phase_env;
+pub mod poll;
 pub mod prd;
+pub mod preprocess;
 pub mod process;
+pub mod prompt_vars;
 pub mod prompts;
+pub mod protected_paths;
+pub mod push;
+pub mod review_budget;
+pub mod review_context;
+pub mod review_explain;
+pub mod review_history;
+pub mod review_presets;
 pub mod review_schema;
+pub mod rlphignore;
 pub mod runner;
+pub mod runs;
+pub mod sanitize;
+pub mod self_update;
+pub mod setup;
 pub mod sources;
+pub mod squash;
 pub mod state;
+pub mod stream;
 pub mod submission;
+pub mod suggestion;
+pub mod task_cache;
+pub mod test_plan;
+pub mod testing;
+pub mod time_box;
+pub mod toolchain;
+pub mod workspace;
 pub mod worktree;
 
 #[doc(hidden)]
 pub mod test_helpers;
+
+pub use builder::OrchestratorBuilder;
+pub use config::Config;
+pub use error::{Error, Result};
+pub use hooks::HookReporter;
+pub use orchestrator::{Orchestrator, ProgressReporter, ReviewRunnerFactory};
+pub use runner::{AgentRunner, AnyRunner, CallbackRunner, DynRunner};
+pub use sources::{AnySource, Task, TaskSource};
+pub use submission::SubmissionBackend;
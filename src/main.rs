@@ -1,28 +1,39 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
 use clap::Parser;
+use tokio::net::TcpListener;
 use tokio::sync::watch;
 use tracing::{debug, info};
 use tracing_subscriber::EnvFilter;
 
-use rlph::cli::{Cli, CliCommand};
-use rlph::config::{Config, resolve_init_config};
+use rlph::OrchestratorBuilder;
+use rlph::api::{ApiServer, EventReporter, EventSink, Job, JobRunner};
+use rlph::base_branch::resolve_base_branch;
+use rlph::builder::{FromConfig, IntoSource, IntoSubmission};
+use rlph::cli::{Cli, CliCommand, PromptsAction, RunsAction};
+use rlph::config::{Config, load_file_config, resolve_init_config};
+use rlph::context::ContextBundle;
+use rlph::exit_status::{ExitStatus, porcelain_line};
 use rlph::fix;
 use rlph::fix_comment::{format_fix_items_for_display, parse_fix_items};
+use rlph::hooks::HookReporter;
 use rlph::orchestrator::{
-    DefaultCorrectionRunner, Orchestrator, ReviewInvocation, build_task_vars,
+    DefaultCorrectionRunner, IterationOutcome, Orchestrator, ProgressReporter, QuietReporter,
+    ReviewInvocation, StderrReporter, build_task_vars,
 };
 use rlph::prd;
-use rlph::prompts::PromptEngine;
+use rlph::preprocess::{SourceContext, preprocess_body, task_referenced_code};
+use rlph::prompt_vars::format_phase_vars;
+use rlph::prompts::{PromptEngine, diff_prompt_versions};
+use rlph::review_explain::explain_review;
 use rlph::runner::build_runner;
-use rlph::sources::AnySource;
-use rlph::sources::github::GitHubSource;
-use rlph::sources::linear::LinearSource;
+use rlph::runs::{RunStore, format_run_list};
+use rlph::self_update::{UpdateOptions, self_update};
 use rlph::sources::{Task, TaskSource};
 use rlph::state::StateManager;
-use rlph::submission::{GitHubSubmission, REVIEW_MARKER, SubmissionBackend};
+use rlph::submission::{REVIEW_MARKER, SubmissionBackend};
 use rlph::worktree::WorktreeManager;
 
 /// Parse a PR reference that is either a plain number or a GitHub PR URL.
@@ -45,6 +56,238 @@ fn parse_pr_ref_or_exit(s: &str) -> u64 {
     })
 }
 
+/// Review an existing GitHub PR, as `rlph review` does: check out its branch in a
+/// worktree and run the review pipeline against it.
+async fn review_pr<P: ProgressReporter>(
+    mut config: Config,
+    pr_number: u64,
+    allow_writes: bool,
+    reporter: P,
+) -> rlph::Result<()> {
+    if config.source != "github" {
+        return Err(rlph::Error::ConfigValidation(
+            "'rlph review' supports only source = \"github\"".to_string(),
+        ));
+    }
+
+    let repo_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let state_mgr = StateManager::new(StateManager::default_dir(&repo_root));
+    resolve_base_branch(&mut config, &repo_root, &state_mgr);
+    let source = FromConfig.into_source(&config)?;
+    let submission = FromConfig.into_submission(&config)?;
+    let pr_context = submission.get_pr_context(pr_number)?;
+
+    let worktree_base = PathBuf::from(&config.worktree_dir);
+    let worktree_mgr =
+        WorktreeManager::new(repo_root.clone(), worktree_base, config.base_branch.clone())
+            .with_push_remote(config.push_remote.clone())
+            .with_shared_caches(
+                PathBuf::from(&config.cache_dir),
+                config.shared_cache_dirs.clone(),
+            )
+            .with_git_identity(config.git_identity.clone());
+    let worktree_info =
+        worktree_mgr.create_for_branch(pr_context.number, &pr_context.head_branch)?;
+
+    let mut issue_title = pr_context.title.clone();
+    let mut issue_body = pr_context.body.clone();
+    let mut issue_number = pr_context.number.to_string();
+    let mut issue_url = pr_context.url.clone();
+    let mut task_id_for_state = format!("pr-{}", pr_context.number);
+    let mut mark_in_review_task_id: Option<String> = None;
+
+    if let Some(linked_issue_number) = pr_context.linked_issue_number {
+        let linked_issue_id = linked_issue_number.to_string();
+        if let Ok(task) = source.get_task_details(&linked_issue_id) {
+            issue_title = task.title;
+            issue_body = task.body;
+            issue_number = task.id.clone();
+            issue_url = task.url;
+            task_id_for_state = format!("gh-{linked_issue_number}");
+            mark_in_review_task_id = Some(task.id);
+        } else {
+            task_id_for_state = format!("gh-{linked_issue_number}");
+            mark_in_review_task_id = Some(linked_issue_id);
+        }
+    }
+
+    let ctx = SourceContext::new(&source, &repo_root, config.github_transport);
+    let referenced_code = task_referenced_code(
+        &issue_body,
+        config.permalink_context,
+        &config.preprocess,
+        &ctx,
+    );
+    let issue_body = preprocess_body(&issue_body, &config.preprocess, &ctx);
+    let task = Task {
+        id: issue_number,
+        title: issue_title,
+        body: issue_body,
+        url: issue_url,
+        labels: vec![],
+        priority: None,
+        created_at: None,
+        updated_at: None,
+        milestone: None,
+        reactions: None,
+        assignee: None,
+        estimate: None,
+        project: None,
+    };
+    let mut vars = build_task_vars(
+        &task,
+        &repo_root,
+        &worktree_info.branch,
+        &worktree_info.path,
+        pr_context
+            .base_branch
+            .as_deref()
+            .unwrap_or(&config.base_branch),
+    );
+    vars.insert("pr_number".to_string(), pr_context.number.to_string());
+    vars.insert("pr_branch".to_string(), pr_context.head_branch.clone());
+    vars.insert("pr_url".to_string(), pr_context.url.clone());
+    vars.insert("referenced_code".to_string(), referenced_code);
+
+    let prompt_engine = PromptEngine::new(None).with_version(&config.prompt_version);
+    let timeout = config.implement_timeout.map(Duration::from_secs);
+    let factory = rlph::orchestrator::DefaultReviewRunnerFactory {
+        stream: !config.quiet,
+        stream_mode: config.review_output,
+    };
+    let reporter = HookReporter::new(reporter, config.hooks.clone(), repo_root.clone());
+    let orchestrator = Orchestrator::new(
+        source,
+        build_runner(
+            config.runner,
+            &config.agent_binary,
+            config.agent_model.as_deref(),
+            config.agent_effort.as_deref(),
+            config.agent_variant.as_deref(),
+            timeout,
+            config.agent_timeout_retries,
+        )
+        .with_permission_mode(config.permission_mode)
+        .with_identity(config.agent_identity.clone())
+        .with_sampling(config.agent_temperature, config.agent_extra_args.clone()),
+        submission,
+        worktree_mgr,
+        state_mgr,
+        prompt_engine,
+        config,
+        repo_root,
+    )
+    .with_review_factory(factory)
+    .with_reporter(reporter);
+
+    let invocation = ReviewInvocation {
+        task_id_for_state,
+        mark_in_review_task_id,
+        worktree_info,
+        vars,
+        comment_pr_number: Some(pr_context.number),
+        push_remote_branch: Some(pr_context.head_branch),
+        allow_writes,
+    };
+    orchestrator.run_review_for_existing_pr(invocation).await
+}
+
+/// Adopt a human-authored GitHub PR, as `rlph adopt-pr` does: record it in
+/// state and run the full review loop on it.
+async fn adopt_pr(config: Config, pr_number: u64) -> rlph::Result<()> {
+    if config.source != "github" {
+        return Err(rlph::Error::ConfigValidation(
+            "'rlph adopt-pr' supports only source = \"github\"".to_string(),
+        ));
+    }
+    let pr_context = FromConfig
+        .into_submission(&config)?
+        .get_pr_context(pr_number)?;
+
+    let repo_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let hooks = config.hooks.clone();
+    let quiet = config.quiet;
+    let orchestrator = OrchestratorBuilder::new(config, repo_root.clone()).build()?;
+    if quiet {
+        orchestrator
+            .with_reporter(HookReporter::new(QuietReporter, hooks, repo_root))
+            .adopt_pr(&pr_context)
+            .await
+    } else {
+        orchestrator.adopt_pr(&pr_context).await
+    }
+}
+
+/// `rlph run` and `rlph --task`: one iteration, for `task` when given, with the
+/// implement phase attached to the terminal when `interactive` is set. A given
+/// task must match the configured label and source unless `force` is set.
+async fn run_single(
+    mut config: Config,
+    task: Option<&str>,
+    interactive: bool,
+    force: bool,
+) -> rlph::Result<IterationOutcome> {
+    config.interactive = interactive;
+    if interactive {
+        eprintln!(
+            "[rlph] The implement phase opens an agent session in the task worktree. Commit your work and exit the agent to continue with push, PR, and review."
+        );
+    }
+    let repo_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let hooks = config.hooks.clone();
+    let quiet = config.quiet;
+    let orchestrator = OrchestratorBuilder::new(config, repo_root.clone()).build()?;
+    if let Some(task) = task
+        && !force
+    {
+        orchestrator.check_task_target(task)?;
+    }
+    match (task, quiet) {
+        (Some(task), true) => {
+            orchestrator
+                .with_reporter(HookReporter::new(QuietReporter, hooks, repo_root))
+                .run_task(task)
+                .await
+        }
+        (Some(task), false) => orchestrator.run_task(task).await,
+        (None, true) => {
+            orchestrator
+                .with_reporter(HookReporter::new(QuietReporter, hooks, repo_root))
+                .run_once()
+                .await
+        }
+        (None, false) => orchestrator.run_once().await,
+    }
+}
+
+/// Runs `rlph api` jobs, building a fresh orchestrator for each so progress
+/// events are tagged with the job that produced them.
+struct ApiJobRunner {
+    config: Config,
+    repo_root: PathBuf,
+}
+
+impl JobRunner for ApiJobRunner {
+    async fn run(&self, job: &Job, events: EventSink) -> rlph::Result<IterationOutcome> {
+        let reporter = EventReporter::new(StderrReporter, events);
+        match job {
+            Job::Task { task_id } => {
+                let hooks =
+                    HookReporter::new(reporter, self.config.hooks.clone(), self.repo_root.clone());
+                let orchestrator =
+                    OrchestratorBuilder::new(self.config.clone(), self.repo_root.clone())
+                        .build()?;
+                orchestrator.check_task_target(task_id)?;
+                orchestrator.with_reporter(hooks).run_task(task_id).await
+            }
+            Job::Review { pr_number } => {
+                review_pr(self.config.clone(), *pr_number, false, reporter).await?;
+                Ok(IterationOutcome::ProcessedTask)
+            }
+        }
+    }
+}
+
 fn init_logging() {
     tracing_subscriber::fmt()
         .with_env_filter(
@@ -81,121 +324,112 @@ async fn main() {
             }
             return;
         }
-        Some(CliCommand::Review { ref pr_ref }) => {
-            let pr_number = parse_pr_ref_or_exit(pr_ref);
-            let config = match Config::load(&cli) {
-                Ok(c) => c,
+        Some(CliCommand::Setup) => {
+            let choices = match rlph::setup::run(Path::new(".")) {
+                Ok(choices) => choices,
                 Err(e) => {
                     eprintln!("error: {e}");
                     std::process::exit(1);
                 }
             };
-            if config.source != "github" {
-                eprintln!("error: 'rlph review' supports only source = \"github\"");
-                std::process::exit(1);
+            if !choices.dry_run {
+                eprintln!("Setup complete. Start with `rlph --once --dry-run`.");
+                return;
             }
-
-            let repo_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-            let source: AnySource = AnySource::GitHub(GitHubSource::new(&config));
-
-            let submission = GitHubSubmission::new();
-            let pr_context = match submission.get_pr_context(pr_number) {
-                Ok(c) => c,
-                Err(e) => {
-                    eprintln!("error: {e}");
+            let status = std::env::current_exe()
+                .and_then(|exe| {
+                    std::process::Command::new(exe)
+                        .args(["--once", "--dry-run"])
+                        .status()
+                })
+                .unwrap_or_else(|e| {
+                    eprintln!("error: failed to start dry run: {e}");
                     std::process::exit(1);
-                }
-            };
-
-            let worktree_base = PathBuf::from(&config.worktree_dir);
-            let worktree_mgr =
-                WorktreeManager::new(repo_root.clone(), worktree_base, config.base_branch.clone());
-            let worktree_info =
-                match worktree_mgr.create_for_branch(pr_context.number, &pr_context.head_branch) {
-                    Ok(w) => w,
+                });
+            std::process::exit(status.code().unwrap_or(1));
+        }
+        Some(CliCommand::Review {
+            ref pr_ref,
+            allow_writes,
+            explain,
+        }) => {
+            let pr_number = parse_pr_ref_or_exit(pr_ref);
+            if explain {
+                let result = Config::load(&cli).and_then(|config| {
+                    let file = load_file_config(&cli, Path::new("."))?;
+                    let prompts = PromptEngine::new(None).with_version(&config.prompt_version);
+                    explain_review(&format!("PR #{pr_number}"), &file, &cli, &config, &prompts)
+                });
+                match result {
+                    Ok(text) => print!("{text}"),
                     Err(e) => {
                         eprintln!("error: {e}");
                         std::process::exit(1);
                     }
-                };
-
-            let mut issue_title = pr_context.title.clone();
-            let mut issue_body = pr_context.body.clone();
-            let mut issue_number = pr_context.number.to_string();
-            let mut issue_url = pr_context.url.clone();
-            let mut task_id_for_state = format!("pr-{}", pr_context.number);
-            let mut mark_in_review_task_id: Option<String> = None;
-
-            if let Some(linked_issue_number) = pr_context.linked_issue_number {
-                let linked_issue_id = linked_issue_number.to_string();
-                if let Ok(task) = source.get_task_details(&linked_issue_id) {
-                    issue_title = task.title;
-                    issue_body = task.body;
-                    issue_number = task.id.clone();
-                    issue_url = task.url;
-                    task_id_for_state = format!("gh-{linked_issue_number}");
-                    mark_in_review_task_id = Some(task.id);
-                } else {
-                    task_id_for_state = format!("gh-{linked_issue_number}");
-                    mark_in_review_task_id = Some(linked_issue_id);
                 }
+                return;
             }
-
-            let task = Task {
-                id: issue_number,
-                title: issue_title,
-                body: issue_body,
-                url: issue_url,
-                labels: vec![],
-                priority: None,
+            let result = match Config::load(&cli) {
+                Ok(config) if config.quiet => {
+                    review_pr(config, pr_number, allow_writes, QuietReporter).await
+                }
+                Ok(config) => review_pr(config, pr_number, allow_writes, StderrReporter).await,
+                Err(e) => Err(e),
             };
-            let mut vars = build_task_vars(
-                &task,
-                &repo_root,
-                &worktree_info.branch,
-                &worktree_info.path,
-                &config.base_branch,
-            );
-            vars.insert("pr_number".to_string(), pr_context.number.to_string());
-            vars.insert("pr_branch".to_string(), pr_context.head_branch.clone());
-            vars.insert("pr_url".to_string(), pr_context.url.clone());
-
-            let state_mgr = StateManager::new(StateManager::default_dir(&repo_root));
-            let prompt_engine = PromptEngine::new(None);
-            let timeout = config.implement_timeout.map(Duration::from_secs);
-            let factory = rlph::orchestrator::DefaultReviewRunnerFactory { stream: true };
-            let orchestrator = Orchestrator::new(
-                source,
-                build_runner(
-                    config.runner,
-                    &config.agent_binary,
-                    config.agent_model.as_deref(),
-                    config.agent_effort.as_deref(),
-                    config.agent_variant.as_deref(),
-                    timeout,
-                    config.agent_timeout_retries,
-                ),
-                submission,
-                worktree_mgr,
-                state_mgr,
-                prompt_engine,
+            exit_with(&cli, result.map(|()| IterationOutcome::ProcessedTask));
+        }
+        Some(CliCommand::AdoptPr { ref pr_ref }) => {
+            let pr_number = parse_pr_ref_or_exit(pr_ref);
+            let result = match Config::load(&cli) {
+                Ok(config) => adopt_pr(config, pr_number).await,
+                Err(e) => Err(e),
+            };
+            exit_with(&cli, result.map(|()| IterationOutcome::ProcessedTask));
+        }
+        Some(CliCommand::Run {
+            ref task_arg,
+            ref task,
+            interactive,
+            force,
+        }) => {
+            let task = task_arg.as_deref().or(task.as_deref());
+            let result = match Config::load(&cli) {
+                Ok(config) => run_single(config, task, interactive, force).await,
+                Err(e) => Err(e),
+            };
+            exit_with(&cli, result);
+        }
+        Some(CliCommand::Api { ref host, port }) => {
+            let config = match Config::load(&cli) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    std::process::exit(1);
+                }
+            };
+            let listener = match TcpListener::bind((host.as_str(), port)).await {
+                Ok(l) => l,
+                Err(e) => {
+                    eprintln!("error: failed to listen on {host}:{port}: {e}");
+                    std::process::exit(1);
+                }
+            };
+            let server = ApiServer::new().with_token(std::env::var("RLPH_API_TOKEN").ok());
+            let runner = ApiJobRunner {
                 config,
-                repo_root,
-            )
-            .with_review_factory(factory);
-
-            let invocation = ReviewInvocation {
-                task_id_for_state,
-                mark_in_review_task_id,
-                worktree_info,
-                vars,
-                comment_pr_number: Some(pr_context.number),
-                push_remote_branch: Some(pr_context.head_branch),
+                repo_root: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
             };
-
-            if let Err(e) = orchestrator.run_review_for_existing_pr(invocation).await {
-                eprintln!("error: {e}");
-                std::process::exit(1);
+            eprintln!("[rlph] API listening on http://{host}:{port}");
+            tokio::select! {
+                result = server.serve(listener, runner) => {
+                    if let Err(e) = result {
+                        eprintln!("error: {e}");
+                        std::process::exit(1);
+                    }
+                }
+                _ = tokio::signal::ctrl_c() => {
+                    eprintln!("[rlph] SIGINT received; stopping API server");
+                }
             }
             return;
         }
@@ -204,7 +438,20 @@ async fn main() {
             dry_run,
         }) => {
             let pr_number = parse_pr_ref_or_exit(pr_ref);
-            let submission = GitHubSubmission::new();
+            let config = match Config::load(&cli) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    std::process::exit(1);
+                }
+            };
+            let submission = match FromConfig.into_submission(&config) {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    std::process::exit(1);
+                }
+            };
 
             if dry_run {
                 let comments = match submission.fetch_pr_comments(pr_number) {
@@ -227,14 +474,6 @@ async fn main() {
             }
 
             // Non-dry-run: run the fix agent
-            let config = match Config::load(&cli) {
-                Ok(c) => c,
-                Err(e) => {
-                    eprintln!("error: {e}");
-                    std::process::exit(1);
-                }
-            };
-
             // Get PR context to determine the head branch
             let pr_context = match submission.get_pr_context(pr_number) {
                 Ok(c) => c,
@@ -245,7 +484,7 @@ async fn main() {
             };
 
             let repo_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-            let prompt_engine = PromptEngine::new(None);
+            let prompt_engine = PromptEngine::new(None).with_version(&config.prompt_version);
 
             if let Err(e) = fix::run_fix(
                 pr_number,
@@ -315,64 +554,184 @@ async fn main() {
 
             std::process::exit(exit_code);
         }
+        Some(CliCommand::Resume) => {
+            let config = match Config::load(&cli) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    std::process::exit(1);
+                }
+            };
+            let repo_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+            let result = match OrchestratorBuilder::new(config, repo_root).build() {
+                Ok(orchestrator) => orchestrator.resume().await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                eprintln!("error: {e}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(CliCommand::Pause { ref reason }) => {
+            let repo_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+            match rlph::pause::pause(&repo_root, reason.as_deref()) {
+                Ok(path) => println!(
+                    "paused: {} created; running loops stop picking up tasks before their next iteration",
+                    path.display()
+                ),
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        Some(CliCommand::Unpause) => {
+            let repo_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+            match rlph::pause::unpause(&repo_root) {
+                Ok(true) => println!("unpaused"),
+                Ok(false) => println!("not paused"),
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        Some(CliCommand::Runs { ref action }) => {
+            let repo_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+            let store = RunStore::new(RunStore::default_dir(&repo_root));
+            match action {
+                RunsAction::List { limit } => {
+                    let mut runs = match store.list() {
+                        Ok(runs) => runs,
+                        Err(e) => {
+                            eprintln!("error: {e}");
+                            std::process::exit(1);
+                        }
+                    };
+                    if let Some(limit) = limit {
+                        runs.drain(..runs.len().saturating_sub(*limit));
+                    }
+                    if runs.is_empty() {
+                        eprintln!("no runs recorded in {}", store.dir().display());
+                    }
+                    print!("{}", format_run_list(&runs));
+                }
+                RunsAction::Show { id } => match store.load(id) {
+                    Ok(summary) => println!(
+                        "{}",
+                        serde_json::to_string_pretty(&summary).unwrap_or_default()
+                    ),
+                    Err(e) => {
+                        eprintln!("error: {e}");
+                        std::process::exit(1);
+                    }
+                },
+            }
+            return;
+        }
+        Some(CliCommand::Context { ref task, ref out }) => {
+            let config = match Config::load(&cli) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    std::process::exit(1);
+                }
+            };
+            let repo_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+            let result = OrchestratorBuilder::new(config, repo_root.clone())
+                .build()
+                .and_then(|orchestrator| orchestrator.task_context(task))
+                .and_then(|bundle| {
+                    let dir = out
+                        .as_ref()
+                        .map(PathBuf::from)
+                        .unwrap_or_else(|| ContextBundle::default_dir(&repo_root, &bundle.task_id));
+                    bundle.write_to(&dir).map(|files| (dir, files))
+                });
+            match result {
+                Ok((dir, files)) => {
+                    println!("context for {task} written to {}", dir.display());
+                    for file in files {
+                        println!("  {}", file.display());
+                    }
+                }
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        Some(CliCommand::Prompts {
+            action: PromptsAction::Vars { phase },
+        }) => {
+            print!("{}", format_phase_vars(phase));
+            return;
+        }
+        Some(CliCommand::Prompts {
+            action: PromptsAction::Diff { from, to },
+        }) => {
+            match diff_prompt_versions(&from, &to) {
+                Ok(diff) if diff.is_empty() => println!("No prompt changes from {from} to {to}"),
+                Ok(diff) => print!("{diff}"),
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        Some(CliCommand::SelfUpdate {
+            channel,
+            check,
+            force,
+            skip_attestation,
+        }) => {
+            let opts = UpdateOptions {
+                channel,
+                check_only: check,
+                force,
+                skip_attestation,
+            };
+            match self_update(&opts) {
+                Ok(outcome) => println!("{outcome}"),
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
         None => {}
     }
 
     let config = match Config::load(&cli) {
         Ok(c) => c,
-        Err(e) => {
-            eprintln!("error: {e}");
-            std::process::exit(1);
-        }
+        Err(e) => exit_with(&cli, Err(e)),
     };
 
     info!(?config, "config loaded");
 
+    if let Some(ref task) = cli.task {
+        let result = run_single(config, Some(task), false, cli.force).await;
+        exit_with(&cli, result);
+    }
+
     if !config.once && !config.continuous && config.max_iterations.is_none() {
         eprintln!("error: specify one of --once, --continuous, or --max-iterations");
         std::process::exit(1);
     }
 
     let repo_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-
-    let source: AnySource = match config.source.as_str() {
-        "linear" => match LinearSource::new(&config) {
-            Ok(s) => AnySource::Linear(s),
-            Err(e) => {
-                eprintln!("error: {e}");
-                std::process::exit(1);
-            }
-        },
-        _ => AnySource::GitHub(GitHubSource::new(&config)),
+    let quiet = config.quiet;
+    let hooks = config.hooks.clone();
+    let orchestrator = match OrchestratorBuilder::new(config, repo_root.clone()).build() {
+        Ok(o) => o,
+        Err(e) => exit_with(&cli, Err(e)),
     };
-    let timeout = config.implement_timeout.map(Duration::from_secs);
-    let runner = build_runner(
-        config.runner,
-        &config.agent_binary,
-        config.agent_model.as_deref(),
-        config.agent_effort.as_deref(),
-        config.agent_variant.as_deref(),
-        timeout,
-        config.agent_timeout_retries,
-    )
-    .with_stream_prefix("implement".to_string());
-    let submission = GitHubSubmission::new();
-    let worktree_base = PathBuf::from(&config.worktree_dir);
-    let worktree_mgr =
-        WorktreeManager::new(repo_root.clone(), worktree_base, config.base_branch.clone());
-    let state_mgr = StateManager::new(StateManager::default_dir(&repo_root));
-    let prompt_engine = PromptEngine::new(None);
-
-    let orchestrator = Orchestrator::new(
-        source,
-        runner,
-        submission,
-        worktree_mgr,
-        state_mgr,
-        prompt_engine,
-        config,
-        repo_root,
-    );
 
     let (shutdown_tx, shutdown_rx) = watch::channel(false);
     tokio::spawn(async move {
@@ -384,17 +743,33 @@ async fn main() {
         // Second SIGINT: force exit
         if tokio::signal::ctrl_c().await.is_ok() {
             eprintln!("[rlph] Second SIGINT received; exiting immediately");
-            std::process::exit(130);
+            std::process::exit(ExitStatus::Interrupted.code());
         }
     });
 
-    if let Err(e) = orchestrator.run_loop(Some(shutdown_rx)).await {
-        if matches!(&e, rlph::error::Error::Interrupted) {
-            std::process::exit(130);
-        }
+    let result = if quiet {
+        orchestrator
+            .with_reporter(HookReporter::new(QuietReporter, hooks, repo_root))
+            .run_loop(Some(shutdown_rx))
+            .await
+    } else {
+        orchestrator.run_loop(Some(shutdown_rx)).await
+    };
+    exit_with(&cli, result);
+}
+
+/// Exit with the code for `result`, printing the error to stderr and, with
+/// `--porcelain`, the result line to stdout.
+fn exit_with(cli: &Cli, result: rlph::Result<IterationOutcome>) -> ! {
+    if let Err(e) = &result
+        && !matches!(e, rlph::Error::Interrupted)
+    {
         eprintln!("error: {e}");
-        std::process::exit(1);
     }
+    if cli.porcelain {
+        println!("{}", porcelain_line(&result));
+    }
+    std::process::exit(ExitStatus::from_result(&result).code());
 }
 
 #[cfg(test)]
@@ -0,0 +1,272 @@
+//! Progress reporting for several tasks running at once.
+//!
+//! A [`MultiplexReporter`] is shared by every task of a parallel run. Each task
+//! reports through its own [`TaskReporter`], whose lines are prefixed with the
+//! task id and written whole, so output from concurrent tasks never
+//! interleaves mid-line. Lines can also be mirrored to one log file per task
+//! and streamed to subscribers such as a fleet dashboard or TUI.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::dry_run::DryRunAction;
+use crate::orchestrator::ProgressReporter;
+use crate::poll::QueueDelta;
+use crate::runs::RunDigest;
+use crate::sources::format_iso_timestamp;
+
+/// Lines buffered per subscriber before the slowest one starts missing lines.
+const LINE_BUFFER: usize = 1024;
+
+/// One line of progress output from a task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskLine {
+    pub task_id: String,
+    pub line: String,
+}
+
+/// Serializes progress output from concurrently running tasks.
+#[derive(Clone)]
+pub struct MultiplexReporter {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    stderr: bool,
+    log_dir: Option<PathBuf>,
+    /// Open log files by task id. Also held while writing to stderr so that
+    /// each line is written whole.
+    files: Mutex<HashMap<String, Option<File>>>,
+    lines: broadcast::Sender<TaskLine>,
+}
+
+impl MultiplexReporter {
+    /// A reporter that prints prefixed lines to stderr.
+    pub fn new() -> Self {
+        Self::build(true, None)
+    }
+
+    /// `.rlph/logs` under the repository root.
+    pub fn default_log_dir(repo_root: &Path) -> PathBuf {
+        repo_root.join(".rlph").join("logs")
+    }
+
+    /// Also append each task's lines, timestamped, to `<dir>/<task_id>.log`.
+    pub fn with_log_dir(self, dir: PathBuf) -> Self {
+        Self::build(self.shared.stderr, Some(dir))
+    }
+
+    /// Stop printing to stderr, e.g. when a TUI owns the terminal and reads
+    /// lines through [`subscribe`](Self::subscribe) instead.
+    pub fn without_stderr(self) -> Self {
+        Self::build(false, self.shared.log_dir.clone())
+    }
+
+    fn build(stderr: bool, log_dir: Option<PathBuf>) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                stderr,
+                log_dir,
+                files: Mutex::default(),
+                lines: broadcast::channel(LINE_BUFFER).0,
+            }),
+        }
+    }
+
+    /// Receive every line reported from now on, from all tasks.
+    pub fn subscribe(&self) -> broadcast::Receiver<TaskLine> {
+        self.shared.lines.subscribe()
+    }
+
+    /// The reporter one task's orchestrator should use.
+    pub fn for_task(&self, task_id: &str) -> TaskReporter {
+        TaskReporter {
+            task_id: task_id.to_string(),
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl Default for MultiplexReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shared {
+    fn emit(&self, task_id: &str, message: &str) {
+        let mut files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+        if self.stderr {
+            let mut stderr = std::io::stderr().lock();
+            for line in message.lines() {
+                let _ = writeln!(stderr, "[rlph {task_id}] {line}");
+            }
+        }
+        if let Some(dir) = &self.log_dir {
+            let file = files
+                .entry(task_id.to_string())
+                .or_insert_with(|| open_log(dir, task_id));
+            if let Some(file) = file {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                let stamp = format_iso_timestamp(now);
+                for line in message.lines() {
+                    let _ = writeln!(file, "{stamp} {line}");
+                }
+            }
+        }
+        drop(files);
+        for line in message.lines() {
+            // Nobody listening is not an error.
+            let _ = self.lines.send(TaskLine {
+                task_id: task_id.to_string(),
+                line: line.to_string(),
+            });
+        }
+    }
+}
+
+/// Best-effort: a log that cannot be opened is reported once and skipped.
+fn open_log(dir: &Path, task_id: &str) -> Option<File> {
+    let path = dir.join(format!("{task_id}.log"));
+    let opened = std::fs::create_dir_all(dir)
+        .and_then(|()| OpenOptions::new().create(true).append(true).open(&path));
+    match opened {
+        Ok(file) => Some(file),
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "failed to open task log");
+            None
+        }
+    }
+}
+
+/// Progress reporter for one task of a [`MultiplexReporter`].
+pub struct TaskReporter {
+    task_id: String,
+    shared: Arc<Shared>,
+}
+
+impl TaskReporter {
+    pub fn task_id(&self) -> &str {
+        &self.task_id
+    }
+
+    fn emit(&self, message: &str) {
+        self.shared.emit(&self.task_id, message);
+    }
+}
+
+impl ProgressReporter for TaskReporter {
+    fn fetching_tasks(&self) {
+        self.emit("Fetching eligible tasks...");
+    }
+
+    fn tasks_found(&self, count: usize) {
+        self.emit(&format!("Found {count} eligible task(s)"));
+    }
+
+    fn queue_changed(&self, delta: &QueueDelta) {
+        self.emit(&format!("Task queue changed: {delta}"));
+    }
+
+    fn task_selected(&self, issue_number: u64, title: &str) {
+        self.emit(&format!("Selected #{issue_number}: {title}"));
+    }
+
+    fn implement_started(&self) {
+        self.emit("Implementing...");
+    }
+
+    fn pr_created(&self, url: &str) {
+        self.emit(&format!("PR created: {url}"));
+    }
+
+    fn iteration_complete(&self, issue_number: u64, title: &str) {
+        self.emit(&format!("Done with #{issue_number}: {title}"));
+    }
+
+    fn phases_started(&self, names: &[String]) {
+        self.emit(&format!(
+            "Running {} review agents: {}",
+            names.len(),
+            names.join(", ")
+        ));
+    }
+
+    fn phase_complete(&self, name: &str) {
+        self.emit(&format!("Review phase complete: {name}"));
+    }
+
+    fn review_summary(&self, body: &str) {
+        self.emit(&format!("Review summary:\n{body}"));
+    }
+
+    fn review_failed(&self, _pr_url: Option<&str>, reason: &str) {
+        self.emit(&format!("Review failed: {reason}"));
+    }
+
+    fn pr_url(&self, url: &str) {
+        self.emit(&format!("PR: {url}"));
+    }
+
+    fn dry_run_action(&self, action: &DryRunAction) {
+        self.emit(&format!("Dry run: would {action}"));
+    }
+
+    fn run_digest(&self, digest: &RunDigest) {
+        self.emit(&format!("Run digest:\n{}", digest.render()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_task_reporters_tag_lines_and_write_per_task_logs() {
+        let dir = TempDir::new().unwrap();
+        let mux = MultiplexReporter::new()
+            .without_stderr()
+            .with_log_dir(dir.path().join("logs"));
+        let mut lines = mux.subscribe();
+
+        let a = mux.for_task("gh-1");
+        let b = mux.for_task("gh-2");
+        a.implement_started();
+        b.review_summary("first\nsecond");
+        a.pr_url("https://example.com/pr/1");
+
+        let received: Vec<TaskLine> = std::iter::from_fn(|| lines.try_recv().ok()).collect();
+        let tagged: Vec<(&str, &str)> = received
+            .iter()
+            .map(|l| (l.task_id.as_str(), l.line.as_str()))
+            .collect();
+        assert_eq!(
+            tagged,
+            [
+                ("gh-1", "Implementing..."),
+                ("gh-2", "Review summary:"),
+                ("gh-2", "first"),
+                ("gh-2", "second"),
+                ("gh-1", "PR: https://example.com/pr/1"),
+            ]
+        );
+
+        let log = std::fs::read_to_string(dir.path().join("logs/gh-1.log")).unwrap();
+        let log_lines: Vec<&str> = log.lines().collect();
+        assert_eq!(log_lines.len(), 2);
+        assert!(log_lines[0].ends_with("Z Implementing..."), "{log}");
+        let log = std::fs::read_to_string(dir.path().join("logs/gh-2.log")).unwrap();
+        assert_eq!(log.lines().count(), 3);
+    }
+}
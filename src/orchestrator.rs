@@ -1,33 +1,194 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use std::time::{Duration, Instant};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use serde::Deserialize;
-use tokio::sync::watch;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, watch};
 use tracing::{info, warn};
 
-use crate::config::{Config, ReviewPhaseConfig, ReviewStepConfig};
-use crate::deps::DependencyGraph;
+use crate::analyzer::run_analyzer;
+use crate::automated_checks::{AutomatedCheck, render_automated_checks};
+use crate::check_run::review_check;
+use crate::config::{
+    ChooseStrategy, Config, ImplementSlicesConfig, OnError, PriorityPolicy, PrioritySignal,
+    ReviewContext, ReviewPhaseConfig, ReviewPhaseKind, ReviewRoundPolicy, ReviewStepConfig,
+    ReviewTarget,
+};
+use crate::context::ContextBundle;
+use crate::conventions::load_conventions;
+use crate::coverage::run_coverage_gate;
+use crate::deps::{DependencyGraph, UnblockingPower, parse_dependencies};
+use crate::disk_usage::{QUOTA_CHECK_INTERVAL, dir_size_mb, with_disk_quota};
+use crate::dry_run::{DryRunAction, DryRunLog, DryRunSource, DryRunSubmission};
 use crate::error::{Error, Result};
-use crate::prompts::PromptEngine;
+use crate::events::{EventTap, PipelineEvent};
+use crate::fix_trailers::{FixAttribution, attribute_fix_commits};
+use crate::heartbeat::{heartbeat_comment, with_heartbeat};
+use crate::interactive::{interactive_command, run_interactive};
+use crate::lessons::{Lesson, load_lessons, record_lessons};
+use crate::pause;
+use crate::phase_env::{AgentSettings, PhaseEnvironment};
+use crate::poll::{PollActivity, PollBackoff, QueueDelta, QueueSnapshot, is_rate_limited};
+use crate::preprocess::{SourceContext, preprocess_body, task_referenced_code};
+use crate::prompt_vars::{PromptPhase, pr_vars, referenced_vars};
+use crate::prompts::{PromptEngine, findings_schema, render_template};
+use crate::protected_paths::{ProtectedPaths, revert_instructions};
+use crate::push::{PUSH_INITIAL_BACKOFF, force_push, push_with_retry};
+use crate::review_budget::{ReviewBudget, unchanged_fix_reason};
+use crate::review_context::{build_review_context, context_var};
+use crate::review_history::{ReviewRound, findings_delta, parse_history, render_history};
 use crate::review_schema::{
-    SchemaName, Verdict, correction_prompt, parse_aggregator_output, parse_fix_output,
-    parse_phase_output, render_findings_for_github, render_findings_for_prompt,
+    Complexity, EstimateOutput, FixOutput, FixStatus, ReviewFinding, SchemaName, Severity, Verdict,
+    check_required_fields, correction_prompt, export_findings, fix_report_correction_prompt,
+    fix_report_mismatch, parse_aggregator_output, parse_estimate_output, parse_fix_output,
+    parse_phase_output, parse_translation_output, render_findings_for_github_with,
+    render_findings_for_prompt,
 };
+use crate::rlphignore::RlphIgnore;
 use crate::runner::{
-    AgentRunner, AnyRunner, Phase, RunResult, RunnerKind, build_runner, resume_with_correction,
+    AgentIdentity, AgentRunner, AnyRunner, PermissionMode, Phase, RunResult, RunnerKind,
+    TokenUsage, build_runner, resume_with_correction,
+};
+use crate::runs::{RunDigest, RunOutcome, RunRecorder, RunStore};
+use crate::sanitize::sanitize_untrusted;
+use crate::sources::{Priority, Task, TaskSource};
+use crate::squash::squash_branch;
+use crate::state::{
+    AdoptedPr, ClosedTaskCache, DeliveredTask, FailedAttempt, MAX_PREVIOUS_ATTEMPTS,
+    ReviewCheckpoint, StateManager, TrackedPr,
+};
+use crate::stream::StreamMode;
+use crate::submission::{
+    CiStatus, FEEDBACK_MARKER, FeedbackKind, PrComment, PrContext, PrState, REVIEW_MARKER,
+    SubmissionBackend, SubmitResult, format_pr_comments_for_prompt,
+};
+use crate::suggestion::{SuggestionComment, suggestion_from_finding};
+use crate::task_cache::CachedSource;
+use crate::test_plan::{
+    TEST_PLAN_INSTRUCTIONS, missing_plan_for_review, missing_plan_note, parse_test_plan,
+};
+use crate::time_box::{
+    CHECKPOINT_TIMEOUT, checkpoint_prompt, commit_wip, continuation_prompt, followup_task,
+    parse_progress, partial_pr_note, slice_notice,
+};
+use crate::toolchain::{coverage_command, describe_toolchain};
+use crate::workspace::branch_affected_crates;
+use crate::worktree::{
+    WorktreeInfo, WorktreeManager, WorktreeSnapshot, branch_user, git_in_dir, render_branch_name,
+    validate_branch_name,
 };
-use crate::sources::{Task, TaskSource};
-use crate::state::StateManager;
-use crate::submission::{REVIEW_MARKER, SubmissionBackend, format_pr_comments_for_prompt};
-use crate::worktree::{WorktreeInfo, WorktreeManager, validate_branch_name};
 
 #[derive(Debug)]
 struct ReviewPhaseOutput {
     name: String,
     stdout: String,
     session_id: Option<String>,
+    /// Produced by an analyzer command rather than an agent.
+    from_command: bool,
+    elapsed: Duration,
+    usage: TokenUsage,
+}
+
+/// A task as listed to the choose agent: the fields it ranks by, a truncated body,
+/// and its dependency-graph ranking.
+#[derive(Serialize)]
+struct ChooseCandidate<'a> {
+    id: &'a str,
+    title: &'a str,
+    priority: Option<Priority>,
+    /// `priority` after the configured age and SLA-label boosts.
+    effective_priority: Option<Priority>,
+    labels: &'a [String],
+    age_days: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    updated_at: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    milestone: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignee: Option<&'a str>,
+    /// Size estimate set in the source, e.g. Linear points.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimate: Option<f64>,
+    /// 👍 reactions or votes; listed only when `priority_signal` includes them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reactions: Option<u32>,
+    /// Body cut to `choose_body_chars`; omitted when that is 0 or the body is empty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    /// Issues the body names as blockers, which truncation may have cut off.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    depends_on: Vec<u64>,
+    /// Open tasks that depend on this one, directly or transitively.
+    unblocks: usize,
+    critical_path_depth: usize,
+}
+
+/// The choose agent's candidates: the `max_tasks` ranked highest by `signals`,
+/// kept in unblocking order, with bodies cut to `body_chars` characters.
+fn choose_candidates<'a>(
+    ranked: &'a [(Task, UnblockingPower)],
+    signals: &[PrioritySignal],
+    policy: &PriorityPolicy,
+    now: u64,
+    max_tasks: usize,
+    body_chars: usize,
+) -> Vec<ChooseCandidate<'a>> {
+    let mut candidates: Vec<(usize, ChooseCandidate<'a>)> = ranked
+        .iter()
+        .enumerate()
+        .map(|(index, (task, power))| {
+            let body = task.body.trim();
+            let body = (body_chars > 0 && !body.is_empty()).then(|| {
+                if body.chars().count() <= body_chars {
+                    body.to_string()
+                } else {
+                    let head: String = body.chars().take(body_chars).collect();
+                    format!("{}... [truncated]", head.trim_end())
+                }
+            });
+            let candidate = ChooseCandidate {
+                id: &task.id,
+                title: &task.title,
+                priority: task.priority,
+                effective_priority: task.effective_priority(policy, now),
+                labels: &task.labels,
+                age_days: task.age_days(now),
+                updated_at: task.updated_at.as_deref(),
+                milestone: task.milestone.as_deref(),
+                project: task.project.as_deref(),
+                assignee: task.assignee.as_deref(),
+                estimate: task.estimate,
+                reactions: task
+                    .reactions
+                    .filter(|_| signals.contains(&PrioritySignal::Reactions)),
+                body,
+                depends_on: parse_dependencies(&task.body),
+                unblocks: power.dependents,
+                critical_path_depth: power.depth,
+            };
+            (index, candidate)
+        })
+        .collect();
+    if candidates.len() > max_tasks {
+        // Stable sort, so equal ranks keep the unblocking order.
+        candidates.sort_by_cached_key(|(index, _)| ranked[*index].0.rank_key(signals, policy, now));
+        candidates.truncate(max_tasks);
+        candidates.sort_by_key(|(index, _)| *index);
+    }
+    candidates.into_iter().map(|(_, c)| c).collect()
+}
+
+/// Serialize choose candidates as a JSON array with one compact object per line.
+fn render_issues_json(candidates: &[ChooseCandidate]) -> Result<String> {
+    let lines = candidates
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<serde_json::Result<Vec<_>>>()
+        .map_err(|e| Error::Orchestrator(format!("failed to serialize tasks: {e}")))?;
+    Ok(format!("[\n{}\n]", lines.join(",\n")))
 }
 
 #[derive(Deserialize)]
@@ -39,6 +200,8 @@ struct TaskSelection {
 pub enum IterationOutcome {
     ProcessedTask,
     NoEligibleTasks,
+    /// The selected task was estimated above `max_complexity` and left untouched.
+    SkippedTask,
 }
 
 pub struct ReviewInvocation {
@@ -48,6 +211,9 @@ pub struct ReviewInvocation {
     pub vars: HashMap<String, String>,
     pub comment_pr_number: Option<u64>,
     pub push_remote_branch: Option<String>,
+    /// Permit review agents to modify the worktree (e.g. autofix review setups).
+    /// Otherwise any modification fails the run before the review is posted.
+    pub allow_writes: bool,
 }
 
 /// Factory for creating review-phase runners. Defaults to `build_runner`.
@@ -67,6 +233,8 @@ pub trait ReviewRunnerFactory: Send + Sync {
 pub struct DefaultReviewRunnerFactory {
     /// When true, runners stream formatted agent messages to stderr.
     pub stream: bool,
+    /// How the parallel review phases write their streamed output.
+    pub stream_mode: StreamMode,
 }
 
 impl ReviewRunnerFactory for DefaultReviewRunnerFactory {
@@ -79,9 +247,14 @@ impl ReviewRunnerFactory for DefaultReviewRunnerFactory {
             phase.agent_variant.as_deref(),
             phase.agent_timeout.map(Duration::from_secs),
             timeout_retries,
-        );
+        )
+        .with_permission_mode(phase.permission_mode)
+        .with_identity(phase.agent_identity.clone())
+        .with_sampling(phase.agent_temperature, phase.agent_extra_args.clone());
         if self.stream {
-            runner.with_stream_prefix(format!("review:{}", phase.name))
+            runner
+                .with_stream_prefix(format!("review:{}", phase.name))
+                .with_stream_mode(self.stream_mode)
         } else {
             runner
         }
@@ -101,7 +274,10 @@ impl ReviewRunnerFactory for DefaultReviewRunnerFactory {
             step.agent_variant.as_deref(),
             step.agent_timeout.map(Duration::from_secs),
             timeout_retries,
-        );
+        )
+        .with_permission_mode(step.permission_mode)
+        .with_identity(step.agent_identity.clone())
+        .with_sampling(step.agent_temperature, step.agent_extra_args.clone());
         if self.stream {
             runner.with_stream_prefix(format!("review:{name}"))
         } else {
@@ -121,6 +297,8 @@ pub trait CorrectionRunner: Send + Sync {
         model: Option<&str>,
         effort: Option<&str>,
         variant: Option<&str>,
+        permission_mode: PermissionMode,
+        identity: &AgentIdentity,
         session_id: &str,
         correction_prompt: &str,
         working_dir: &Path,
@@ -139,6 +317,8 @@ impl CorrectionRunner for DefaultCorrectionRunner {
         model: Option<&str>,
         effort: Option<&str>,
         variant: Option<&str>,
+        permission_mode: PermissionMode,
+        identity: &AgentIdentity,
         session_id: &str,
         correction_prompt: &str,
         working_dir: &Path,
@@ -150,6 +330,8 @@ impl CorrectionRunner for DefaultCorrectionRunner {
             model,
             effort,
             variant,
+            permission_mode,
+            identity,
             session_id,
             correction_prompt,
             working_dir,
@@ -164,6 +346,9 @@ pub trait ProgressReporter: Send + Sync {
     // Iteration-level
     fn fetching_tasks(&self);
     fn tasks_found(&self, count: usize);
+    /// Fires in continuous mode when the eligible tasks differ from the
+    /// previous poll.
+    fn queue_changed(&self, delta: &QueueDelta);
     fn task_selected(&self, issue_number: u64, title: &str);
     fn implement_started(&self);
     /// Fires after a new PR is submitted (inside `run_implement_review`).
@@ -175,9 +360,20 @@ pub trait ProgressReporter: Send + Sync {
     fn phases_started(&self, names: &[String]);
     fn phase_complete(&self, name: &str);
     fn review_summary(&self, body: &str);
+    /// Fires when the review pipeline gives up without approval (the iteration
+    /// then fails with `reason`).
+    fn review_failed(&self, pr_url: Option<&str>, reason: &str);
     /// Fires at the end of `run_review_pipeline` after all review rounds complete.
     /// Fires even when an existing PR was reused.
     fn pr_url(&self, url: &str);
+
+    // Dry run
+    /// Fires for each mutation a dry run skipped, with its exact payload.
+    fn dry_run_action(&self, action: &DryRunAction);
+
+    // Loop
+    /// Fires when a multi-iteration loop exits after running at least one task.
+    fn run_digest(&self, digest: &RunDigest);
 }
 
 /// Default reporter that prints to stderr.
@@ -192,6 +388,10 @@ impl ProgressReporter for StderrReporter {
         eprintln!("[rlph] Found {count} eligible task(s)");
     }
 
+    fn queue_changed(&self, delta: &QueueDelta) {
+        eprintln!("[rlph] Task queue changed: {delta}");
+    }
+
     fn task_selected(&self, issue_number: u64, title: &str) {
         eprintln!("[rlph] Selected #{issue_number}: {title}");
     }
@@ -224,9 +424,41 @@ impl ProgressReporter for StderrReporter {
         eprintln!("[rlph] Review summary:\n{body}");
     }
 
+    fn review_failed(&self, _pr_url: Option<&str>, reason: &str) {
+        eprintln!("[rlph] Review failed: {reason}");
+    }
+
     fn pr_url(&self, url: &str) {
         eprintln!("[rlph] PR: {url}");
     }
+
+    fn dry_run_action(&self, action: &DryRunAction) {
+        eprintln!("[rlph] Dry run: would {action}");
+    }
+
+    fn run_digest(&self, digest: &RunDigest) {
+        eprintln!("[rlph] Run digest:\n{}", digest.render());
+    }
+}
+
+/// Reporter that prints nothing, for `--quiet` and `--porcelain` scripting.
+pub struct QuietReporter;
+
+impl ProgressReporter for QuietReporter {
+    fn fetching_tasks(&self) {}
+    fn tasks_found(&self, _count: usize) {}
+    fn queue_changed(&self, _delta: &QueueDelta) {}
+    fn task_selected(&self, _issue_number: u64, _title: &str) {}
+    fn implement_started(&self) {}
+    fn pr_created(&self, _url: &str) {}
+    fn iteration_complete(&self, _issue_number: u64, _title: &str) {}
+    fn phases_started(&self, _names: &[String]) {}
+    fn phase_complete(&self, _name: &str) {}
+    fn review_summary(&self, _body: &str) {}
+    fn review_failed(&self, _pr_url: Option<&str>, _reason: &str) {}
+    fn pr_url(&self, _url: &str) {}
+    fn dry_run_action(&self, _action: &DryRunAction) {}
+    fn run_digest(&self, _digest: &RunDigest) {}
 }
 
 pub struct Orchestrator<
@@ -237,17 +469,28 @@ pub struct Orchestrator<
     P = StderrReporter,
     C = DefaultCorrectionRunner,
 > {
-    source: S,
+    /// Cached per iteration; see [`CachedSource`].
+    source: CachedSource<DryRunSource<S>>,
     runner: R,
-    submission: B,
+    submission: DryRunSubmission<B>,
     worktree_mgr: WorktreeManager,
     state_mgr: StateManager,
     prompt_engine: PromptEngine,
     config: Config,
     repo_root: PathBuf,
     review_factory: F,
-    reporter: P,
+    /// Broadcasts to [`Orchestrator::subscribe`] receivers before reporting.
+    reporter: EventTap<P>,
     correction_runner: C,
+    run_recorder: RunRecorder,
+    /// `.rlphignore` patterns of the repository.
+    ignore: RlphIgnore,
+    /// Configured `protected_paths`, checked before every push.
+    protected: ProtectedPaths,
+    /// Mutations skipped in a dry run, not yet reported.
+    dry_run_log: Option<DryRunLog>,
+    /// Eligible tasks seen by the previous poll in continuous mode.
+    last_queue: Mutex<Option<QueueSnapshot>>,
 }
 
 impl<S: TaskSource, R: AgentRunner, B: SubmissionBackend> Orchestrator<S, R, B> {
@@ -262,18 +505,31 @@ impl<S: TaskSource, R: AgentRunner, B: SubmissionBackend> Orchestrator<S, R, B>
         config: Config,
         repo_root: PathBuf,
     ) -> Self {
+        let stream_mode = config.review_output;
+        let stream = !config.quiet;
+        let ignore = RlphIgnore::load(&repo_root);
+        let protected = ProtectedPaths::new(&config.protected_paths);
+        let dry_run_log = config.dry_run.then(DryRunLog::default);
         Self {
-            source,
+            source: CachedSource::new(DryRunSource::new(source, dry_run_log.clone())),
             runner,
-            submission,
+            submission: DryRunSubmission::new(submission, dry_run_log.clone()),
             worktree_mgr,
             state_mgr,
             prompt_engine,
             config,
             repo_root,
-            review_factory: DefaultReviewRunnerFactory { stream: true },
-            reporter: StderrReporter,
+            review_factory: DefaultReviewRunnerFactory {
+                stream,
+                stream_mode,
+            },
+            reporter: EventTap::new(StderrReporter),
             correction_runner: DefaultCorrectionRunner,
+            run_recorder: RunRecorder::default(),
+            ignore,
+            protected,
+            dry_run_log,
+            last_queue: Mutex::new(None),
         }
     }
 }
@@ -292,6 +548,11 @@ impl<S: TaskSource, R: AgentRunner, B: SubmissionBackend, F, P, C> Orchestrator<
             review_factory,
             reporter: self.reporter,
             correction_runner: self.correction_runner,
+            run_recorder: self.run_recorder,
+            ignore: self.ignore,
+            protected: self.protected,
+            dry_run_log: self.dry_run_log,
+            last_queue: self.last_queue,
         }
     }
 
@@ -306,11 +567,22 @@ impl<S: TaskSource, R: AgentRunner, B: SubmissionBackend, F, P, C> Orchestrator<
             config: self.config,
             repo_root: self.repo_root,
             review_factory: self.review_factory,
-            reporter,
+            reporter: self.reporter.with_inner(reporter),
             correction_runner: self.correction_runner,
+            run_recorder: self.run_recorder,
+            ignore: self.ignore,
+            protected: self.protected,
+            dry_run_log: self.dry_run_log,
+            last_queue: self.last_queue,
         }
     }
 
+    /// Receive every progress event from now on as a typed [`PipelineEvent`],
+    /// alongside the reporter. Subscribers survive `with_reporter`.
+    pub fn subscribe(&self) -> broadcast::Receiver<PipelineEvent> {
+        self.reporter.subscribe()
+    }
+
     pub fn with_correction_runner<C2>(
         self,
         correction_runner: C2,
@@ -327,6 +599,11 @@ impl<S: TaskSource, R: AgentRunner, B: SubmissionBackend, F, P, C> Orchestrator<
             review_factory: self.review_factory,
             reporter: self.reporter,
             correction_runner,
+            run_recorder: self.run_recorder,
+            ignore: self.ignore,
+            protected: self.protected,
+            dry_run_log: self.dry_run_log,
+            last_queue: self.last_queue,
         }
     }
 }
@@ -343,60 +620,192 @@ impl<
     /// Run according to configured loop mode.
     ///
     /// When `shutdown` becomes true, the orchestrator exits between iterations.
-    pub async fn run_loop(&self, mut shutdown: Option<watch::Receiver<bool>>) -> Result<()> {
+    /// Returns `ProcessedTask` if any iteration processed a task, and otherwise the
+    /// last iteration's outcome. Failures recovered under `on_error = "continue"`
+    /// do not count.
+    pub async fn run_loop(
+        &self,
+        mut shutdown: Option<watch::Receiver<bool>>,
+    ) -> Result<IterationOutcome> {
+        if self.config.refresh_deps {
+            self.state_mgr.clear_closed_task_cache()?;
+        }
         if self.config.once {
+            if self.log_if_paused() {
+                return Ok(IterationOutcome::NoEligibleTasks);
+            }
             return self.run_once().await;
         }
 
         let mut iterations = 0u32;
+        let mut outcome = IterationOutcome::NoEligibleTasks;
+        let mut backoff = PollBackoff::new(
+            Duration::from_secs(self.config.poll_seconds),
+            Duration::from_secs(self.config.poll_max_seconds),
+        );
+        let mut was_paused = false;
+        self.run_recorder.begin_digest();
 
-        loop {
+        let exit_reason = loop {
             if Self::shutdown_requested(shutdown.as_ref()) {
                 info!("shutdown requested, exiting loop");
-                break;
+                break "shutdown";
+            }
+
+            // The pause file is checked before every iteration; while it exists the
+            // loop idles at the base poll interval without counting iterations.
+            if let Some(note) = pause::paused(&self.repo_root) {
+                if !was_paused {
+                    self.log_paused(&note);
+                    was_paused = true;
+                }
+                if !self.config.continuous {
+                    break "paused";
+                }
+                let delay = Duration::from_secs(self.config.poll_seconds);
+                if Self::wait_for_poll_or_shutdown(delay, &mut shutdown).await {
+                    info!("shutdown requested, exiting loop");
+                    break "shutdown";
+                }
+                continue;
+            }
+            if was_paused {
+                warn!("pause file removed, resuming");
+                was_paused = false;
             }
 
-            self.run_iteration().await?;
+            let activity = match self.run_iteration().await {
+                Ok(latest) => {
+                    if outcome != IterationOutcome::ProcessedTask {
+                        outcome = latest;
+                    }
+                    if latest == IterationOutcome::NoEligibleTasks {
+                        PollActivity::Idle
+                    } else {
+                        PollActivity::Active
+                    }
+                }
+                // In continuous mode a rate limit is waited out, whatever `on_error` says.
+                Err(e) if self.config.continuous && is_rate_limited(&e) => {
+                    warn!(error = %e, "task source rate limited, backing off");
+                    self.recover_failed_iteration();
+                    PollActivity::RateLimited
+                }
+                Err(e) => {
+                    if self.config.on_error == OnError::Stop {
+                        self.report_digest("error");
+                        return Err(e);
+                    }
+                    warn!(error = %e, "iteration failed, continuing with the next task");
+                    self.recover_failed_iteration();
+                    PollActivity::Active
+                }
+            };
             iterations += 1;
 
             if let Some(max) = self.config.max_iterations
                 && iterations >= max
             {
                 info!(max, "reached max iterations, exiting");
-                break;
+                break "max_iterations";
             }
 
             if !self.config.continuous {
+                // A single iteration needs no digest.
                 if self.config.max_iterations.is_none() {
-                    break;
+                    return Ok(outcome);
                 }
                 continue;
             }
 
             if Self::shutdown_requested(shutdown.as_ref()) {
                 info!("shutdown requested, exiting loop");
-                break;
+                break "shutdown";
             }
 
-            info!(poll_seconds = self.config.poll_seconds, "polling again");
-            let stop = Self::wait_for_poll_or_shutdown(
-                Duration::from_secs(self.config.poll_seconds),
-                &mut shutdown,
-            )
-            .await;
+            let delay = backoff.next_delay(activity);
+            info!(
+                delay_ms = delay.as_millis() as u64,
+                ?activity,
+                "polling again"
+            );
+            let stop = Self::wait_for_poll_or_shutdown(delay, &mut shutdown).await;
             if stop {
                 info!("shutdown requested, exiting loop");
-                break;
+                break "shutdown";
+            }
+        };
+
+        self.report_digest(exit_reason);
+        Ok(outcome)
+    }
+
+    /// Report the digest of the loop's runs, and post it to `digest_issue`
+    /// when set. Loops that ran no task report nothing.
+    fn report_digest(&self, exit_reason: &str) {
+        let digest = self.run_recorder.take_digest(exit_reason);
+        if digest.tasks() == 0 {
+            return;
+        }
+        self.reporter.run_digest(&digest);
+        if let Some(issue) = &self.config.digest_issue
+            && let Err(e) = self.source.add_comment(issue, &digest.render())
+        {
+            warn!(error = %e, issue, "failed to post run digest");
+        }
+        self.flush_source();
+    }
+
+    /// Log and return `true` when `.rlph/PAUSE` exists.
+    fn log_if_paused(&self) -> bool {
+        match pause::paused(&self.repo_root) {
+            Some(note) => {
+                self.log_paused(&note);
+                true
             }
+            None => false,
         }
+    }
 
-        Ok(())
+    fn log_paused(&self, note: &str) {
+        warn!(
+            path = %pause::pause_file(&self.repo_root).display(),
+            note,
+            "rlph is paused; not picking up tasks until `rlph unpause`"
+        );
+    }
+
+    /// Undo a failed iteration so the loop can move on: remove its worktree,
+    /// clear the current task, and return the task to the eligible queue.
+    fn recover_failed_iteration(&self) {
+        let Some(current) = self.state_mgr.load().current_task else {
+            return;
+        };
+        if !current.worktree_path.is_empty() {
+            info!(path = current.worktree_path, "removing failed worktree");
+            if let Err(e) = self.worktree_mgr.remove(Path::new(&current.worktree_path)) {
+                warn!(error = %e, "failed to clean up worktree");
+            }
+        }
+        let _ = self.state_mgr.remove_worktree_mapping(&current.id);
+        let _ = self.state_mgr.clear_review_checkpoint();
+        if let Err(e) = self.state_mgr.clear_current_task() {
+            warn!(error = %e, "failed to clear current task");
+        }
+        match parse_issue_number(&current.id) {
+            Ok(number) => {
+                if let Err(e) = self.source.release_task(&number.to_string()) {
+                    warn!(error = %e, task_id = current.id, "failed to release task");
+                }
+            }
+            Err(e) => warn!(error = %e, "cannot release failed task"),
+        }
+        self.flush_dry_run();
     }
 
     /// Run a single iteration of the orchestrator loop.
-    pub async fn run_once(&self) -> Result<()> {
-        let _ = self.run_iteration().await?;
-        Ok(())
+    pub async fn run_once(&self) -> Result<IterationOutcome> {
+        self.run_iteration().await
     }
 
     /// Run only the review pipeline for an already-selected PR/worktree context.
@@ -407,24 +816,33 @@ impl<
             &invocation.worktree_info.path.display().to_string(),
         )?;
 
-        if !self.config.dry_run
-            && let Some(task_id) = invocation.mark_in_review_task_id.as_deref()
-        {
+        if let Some(task_id) = invocation.mark_in_review_task_id.as_deref() {
             self.source.mark_in_review(task_id)?;
+            self.flush_source();
         }
 
+        let write_guard = if invocation.allow_writes {
+            None
+        } else {
+            Some(WorktreeSnapshot::capture(&invocation.worktree_info.path)?)
+        };
+
         let result = self
             .run_review_pipeline(
+                &invocation.task_id_for_state,
                 &invocation.vars,
                 &invocation.worktree_info,
                 invocation.comment_pr_number,
                 invocation.push_remote_branch.as_deref(),
                 true,
+                write_guard.as_ref(),
             )
             .await;
+        self.flush_source();
+        self.flush_dry_run();
 
         match result {
-            Ok(()) => {
+            Ok(_) => {
                 self.state_mgr.complete_current_task()?;
 
                 info!("cleaning up worktree");
@@ -445,32 +863,343 @@ impl<
         }
     }
 
+    /// Resume an interrupted review from its checkpoint, starting at the round it
+    /// reached and reusing the phases that already finished. On success the task
+    /// is completed and its worktree removed, as at the end of an iteration.
+    pub async fn resume(&self) -> Result<()> {
+        let Some(checkpoint) = self.state_mgr.review_checkpoint() else {
+            return Err(Error::Orchestrator(
+                "no interrupted review to resume".to_string(),
+            ));
+        };
+        let worktree_info = WorktreeInfo {
+            path: PathBuf::from(&checkpoint.worktree_path),
+            branch: checkpoint.branch.clone(),
+        };
+        if !worktree_info.path.exists() {
+            return Err(Error::Orchestrator(format!(
+                "cannot resume review of {}: worktree {} no longer exists",
+                checkpoint.task_id, checkpoint.worktree_path
+            )));
+        }
+        info!(
+            task_id = checkpoint.task_id,
+            round = checkpoint.round,
+            "resuming interrupted review"
+        );
+        self.state_mgr.set_current_task(
+            &checkpoint.task_id,
+            "review",
+            &checkpoint.worktree_path,
+        )?;
+
+        let write_guard = if checkpoint.guard_writes {
+            Some(WorktreeSnapshot::capture(&worktree_info.path)?)
+        } else {
+            None
+        };
+        self.run_review_pipeline(
+            &checkpoint.task_id,
+            &checkpoint.vars,
+            &worktree_info,
+            checkpoint.pr_number,
+            checkpoint.push_remote_branch.as_deref(),
+            checkpoint.review_only,
+            write_guard.as_ref(),
+        )
+        .await?;
+
+        self.state_mgr.complete_current_task()?;
+        if let Err(e) = self.state_mgr.clear_retry(&checkpoint.task_id) {
+            warn!(error = %e, "failed to clear retry entry");
+        }
+        info!("cleaning up worktree");
+        if let Err(e) = self.worktree_mgr.remove(&worktree_info.path) {
+            warn!(error = %e, "failed to clean up worktree");
+        }
+        let _ = self.state_mgr.remove_worktree_mapping(&checkpoint.task_id);
+        info!("resumed review complete");
+        Ok(())
+    }
+
     async fn run_iteration(&self) -> Result<IterationOutcome> {
+        self.source.clear();
+        if self.config.cleanup.enabled && !self.config.dry_run {
+            match self.cleanup_finished_prs() {
+                Ok(cleaned) => info!(cleaned, "finished PR cleanup complete"),
+                Err(e) => warn!(error = %e, "finished PR cleanup failed"),
+            }
+        }
+
+        if self.config.abandon_after_days.is_some() && !self.config.dry_run {
+            match self.abandon_stale_prs() {
+                Ok(0) => {}
+                Ok(abandoned) => info!(abandoned, "abandoned stale PRs"),
+                Err(e) => warn!(error = %e, "stale PR check failed"),
+            }
+        }
+
+        match self.process_adopted_prs().await {
+            Ok(0) => {}
+            Ok(reviewed) => info!(reviewed, "adopted PR review complete"),
+            Err(e) => warn!(error = %e, "adopted PR check failed"),
+        }
+
+        if self.config.pr_feedback {
+            match self.process_pr_feedback().await {
+                Ok(handled) => info!(handled, "PR feedback check complete"),
+                Err(e) => warn!(error = %e, "PR feedback check failed"),
+            }
+        }
+        self.flush_dry_run();
+
+        // A task claimed by someone else is excluded and selection runs again.
+        let mut claimed_elsewhere = HashSet::new();
+        loop {
+            match self.run_task_iteration(&claimed_elsewhere).await {
+                Err(Error::TaskClaimConflict { task_id, reason }) => {
+                    info!(task_id, reason, "task claimed elsewhere, picking another");
+                    claimed_elsewhere.insert(task_id);
+                }
+                outcome => return outcome,
+            }
+        }
+    }
+
+    /// Select one task (skipping `excluded` IDs), run it through the pipeline and
+    /// write its run summary to `.rlph/runs/`.
+    async fn run_task_iteration(&self, excluded: &HashSet<String>) -> Result<IterationOutcome> {
+        self.run_recorder.start();
+        let result = self.select_and_run_task(excluded).await;
+        self.finish_run(result)
+    }
+
+    /// Run one specific task (`gh-42` or `42`) through the pipeline, bypassing
+    /// selection, and write its run summary. Used by schedulers that decide what
+    /// runs when (`rlph api`) and by `rlph --task`.
+    pub async fn run_task(&self, task_id: &str) -> Result<IterationOutcome> {
+        let task_id = normalize_task_id(task_id);
+        let issue_number = parse_issue_number(&task_id)?;
+        if !self.config.redo
+            && let Some((delivered, state)) = self.live_delivery(&task_id)
+        {
+            self.note_delivered_skip(&issue_number.to_string(), &task_id, &delivered, state);
+            return Ok(IterationOutcome::SkippedTask);
+        }
+        self.run_recorder.start();
+        let result = self.run_selected_task(&task_id).await;
+        self.finish_run(result)
+    }
+
+    /// Check that a task named on the command line is one this configuration
+    /// would pick itself: it carries the configured label and the source reports
+    /// it eligible (open, not on hold, not claimed).
+    pub fn check_task_target(&self, task_id: &str) -> Result<()> {
+        let task_id = normalize_task_id(task_id);
+        let issue_number = parse_issue_number(&task_id)?.to_string();
+        let task = self.source.get_task_details(&issue_number)?;
+        let reason = if !task.labels.iter().any(|l| l == &self.config.label) {
+            Some(format!("is not labeled '{}'", self.config.label))
+        } else if !self.source.is_task_eligible(&issue_number)? {
+            Some(format!(
+                "is not eligible in source '{}' (closed, on hold, or already claimed)",
+                self.config.source
+            ))
+        } else {
+            None
+        };
+        match reason {
+            Some(reason) => Err(Error::Orchestrator(format!(
+                "task {task_id} {reason}; pass --force to run it anyway"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Assemble the context the implement phase would receive for `task_id`,
+    /// built with the same variables a run uses. An existing task worktree is
+    /// used as is and its diff included; otherwise the repository root stands
+    /// in for the worktree. Nothing is created, claimed, or run.
+    pub fn task_context(&self, task_id: &str) -> Result<ContextBundle> {
+        let task_id = normalize_task_id(task_id);
+        let issue_number = parse_issue_number(&task_id)?;
+        let task = self.source.get_task_details(&issue_number.to_string())?;
+        let base_branch = self.config.base_branch_for(&task).to_string();
+
+        let existing = self.worktree_mgr.find_existing(issue_number)?;
+        let worktree_info = match &existing {
+            Some(info) => info.clone(),
+            None => WorktreeInfo {
+                path: self.repo_root.clone(),
+                branch: render_branch_name(
+                    &self.config.branch_template,
+                    issue_number,
+                    &WorktreeManager::slugify(&task.title),
+                    &branch_user(),
+                )?,
+            },
+        };
+        let diff = existing.as_ref().and_then(|info| {
+            git_in_dir(&info.path, &["diff", &format!("{base_branch}...HEAD")])
+                .map_err(|e| warn!(error = %e, "failed to diff task worktree"))
+                .ok()
+        });
+
+        let vars = self.initial_task_vars(&task, &worktree_info);
+        let prompt = self.prompt_engine.render_phase("implement", &vars)?;
+        let mut task_markdown = format!("# {}\n\n{}\n", task.title, task.url);
+        if !task.labels.is_empty() {
+            task_markdown.push_str(&format!("\nLabels: {}\n", task.labels.join(", ")));
+        }
+        task_markdown.push_str(&format!("\n{}\n", task.body.trim_end()));
+        Ok(ContextBundle {
+            task_id,
+            prompt,
+            vars: vars.into_iter().collect::<BTreeMap<_, _>>(),
+            task_markdown,
+            dependencies: parse_dependencies(&task.body),
+            diff,
+        })
+    }
+
+    /// Record the outcome of an iteration in its run summary.
+    fn finish_run(&self, result: Result<IterationOutcome>) -> Result<IterationOutcome> {
+        self.flush_source();
+        self.flush_dry_run();
+        let outcome = match &result {
+            Ok(IterationOutcome::ProcessedTask) => Some(RunOutcome::Completed),
+            Ok(IterationOutcome::SkippedTask) => Some(RunOutcome::Skipped),
+            Ok(IterationOutcome::NoEligibleTasks) => None,
+            // Another task is picked; the claimed one was never worked on.
+            Err(Error::TaskClaimConflict { .. }) => None,
+            Err(e) => {
+                self.run_recorder.error(&e.to_string());
+                Some(RunOutcome::Failed)
+            }
+        };
+        if let Some(outcome) = outcome {
+            self.write_run_summary(outcome);
+        }
+        result
+    }
+
+    /// In continuous mode, report how the eligible tasks changed since the
+    /// previous poll. The first poll only sets the baseline, and selection
+    /// retried after a claim conflict (`excluded` non-empty) is not a new poll.
+    fn track_queue(&self, excluded: &HashSet<String>, snapshot: QueueSnapshot) {
+        if !self.config.continuous || !excluded.is_empty() {
+            return;
+        }
+        let previous = self.last_queue.lock().unwrap().replace(snapshot.clone());
+        let Some(previous) = previous else {
+            return;
+        };
+        let delta = QueueDelta::between(&previous, &snapshot);
+        if !delta.is_empty() {
+            info!(%delta, "task queue changed");
+            self.reporter.queue_changed(&delta);
+        }
+    }
+
+    /// Send the task source's queued mutations. A failure is only logged: the
+    /// task itself is done, and its tracker status can be fixed by hand.
+    fn flush_source(&self) {
+        if let Err(e) = self.source.flush() {
+            warn!(error = %e, "failed to send queued task source updates");
+        }
+    }
+
+    /// Report the mutations a dry run skipped since the last flush and add them
+    /// to the run summary in progress, if any.
+    fn flush_dry_run(&self) {
+        let Some(log) = &self.dry_run_log else {
+            return;
+        };
+        for action in log.drain() {
+            self.reporter.dry_run_action(&action);
+            self.run_recorder.dry_run_action(action);
+        }
+    }
+
+    /// Best-effort: a summary that cannot be written is logged, never fatal.
+    fn write_run_summary(&self, outcome: RunOutcome) {
+        let Some(mut summary) = self.run_recorder.finish(outcome) else {
+            return;
+        };
+        summary.prompt_version = Some(self.prompt_engine.version().to_string());
+        let store = RunStore::new(RunStore::default_dir(&self.repo_root));
+        match store.write(&summary) {
+            Ok(path) => info!(path = %path.display(), "run summary written"),
+            Err(e) => warn!(error = %e, "failed to write run summary"),
+        }
+    }
+
+    async fn select_and_run_task(&self, excluded: &HashSet<String>) -> Result<IterationOutcome> {
         // 1. Fetch eligible tasks and filter by dependency graph
         self.reporter.fetching_tasks();
         info!("fetching eligible tasks");
-        let tasks = self.source.fetch_eligible_tasks()?;
+        let tasks: Vec<Task> = self
+            .source
+            .fetch_eligible_tasks()?
+            .into_iter()
+            .filter(|task| !excluded.contains(&task.id))
+            .collect();
         if tasks.is_empty() {
+            self.track_queue(excluded, QueueSnapshot::default());
             info!("no eligible tasks found");
             return Ok(IterationOutcome::NoEligibleTasks);
         }
 
-        let done_ids = self.source.fetch_closed_task_ids()?;
+        let done_ids = self.closed_task_ids()?;
         let graph = DependencyGraph::build(&tasks);
+        let fetched = tasks.clone();
         let tasks = graph.filter_eligible(tasks, &done_ids);
+        self.track_queue(excluded, QueueSnapshot::new(&fetched, &tasks));
         if tasks.is_empty() {
             info!("no unblocked tasks found");
             return Ok(IterationOutcome::NoEligibleTasks);
         }
+        let tasks = self.filter_over_complexity(tasks);
+        if tasks.is_empty() {
+            info!("all eligible tasks exceed max_complexity");
+            return Ok(IterationOutcome::NoEligibleTasks);
+        }
+        let tasks = self.filter_retry_queue(tasks);
+        if tasks.is_empty() {
+            info!("all eligible tasks are failed or waiting to be retried");
+            return Ok(IterationOutcome::NoEligibleTasks);
+        }
+        let tasks = self.filter_delivered(tasks);
+        if tasks.is_empty() {
+            info!("all eligible tasks were already delivered");
+            return Ok(IterationOutcome::NoEligibleTasks);
+        }
         info!(count = tasks.len(), "found eligible tasks");
         self.reporter.tasks_found(tasks.len());
 
-        // 2. Choose phase — agent selects a task (skip if only one)
+        // 2. Choose phase — agent or ranking selects a task (skip if only one)
         let task_id = if tasks.len() == 1 {
             let only = &tasks[0];
             let id = format!("gh-{}", only.id);
             info!(task_id = id, "auto-selected only eligible task");
             id
+        } else if self.config.choose_strategy == ChooseStrategy::Ranked {
+            let ranked = graph.rank_by_unblocking_power(tasks);
+            let now = unix_now();
+            let policy = &self.config.priority_policy;
+            // Ranking is stable, so equal priorities keep the unblocking order.
+            let (top, power) = ranked
+                .iter()
+                .min_by_key(|(task, _)| task.rank_key(&self.config.priority_signal, policy, now))
+                .expect("more than one eligible task");
+            let id = format!("gh-{}", top.id);
+            info!(
+                task_id = id,
+                dependents = power.dependents,
+                depth = power.depth,
+                "selected top-ranked task"
+            );
+            id
         } else {
             info!("running choose phase");
             let mut choose_vars = HashMap::new();
@@ -478,14 +1207,37 @@ impl<
                 "repo_path".to_string(),
                 self.repo_root.display().to_string(),
             );
-            let issues_json = serde_json::to_string_pretty(&tasks)
-                .map_err(|e| Error::Orchestrator(format!("failed to serialize tasks: {e}")))?;
-            choose_vars.insert("issues_json".to_string(), issues_json);
+            let ranked = graph.rank_by_unblocking_power(tasks);
+            let candidates = choose_candidates(
+                &ranked,
+                &self.config.priority_signal,
+                &self.config.priority_policy,
+                unix_now(),
+                self.config.choose_max_tasks,
+                self.config.choose_body_chars,
+            );
+            if candidates.len() < ranked.len() {
+                info!(
+                    listed = candidates.len(),
+                    eligible = ranked.len(),
+                    "choose prompt limited to the highest-priority tasks"
+                );
+            }
+            choose_vars.insert("issues_json".to_string(), render_issues_json(&candidates)?);
             let choose_prompt = self.prompt_engine.render_phase("choose", &choose_vars)?;
+            self.record_environment(
+                "choose",
+                &self.repo_root,
+                AgentSettings::from_config(&self.config),
+                &choose_prompt,
+            );
             let choose_started = Instant::now();
-            self.runner
+            let choose_result = self
+                .runner
                 .run(Phase::Choose, &choose_prompt, &self.repo_root)
                 .await?;
+            self.run_recorder
+                .phase("choose", choose_started.elapsed(), choose_result.usage);
             info!(
                 elapsed_secs = choose_started.elapsed().as_secs(),
                 "choose phase complete"
@@ -494,36 +1246,55 @@ impl<
             // Parse task selection from .rlph/task.toml
             self.parse_task_selection()?
         };
-        let issue_number = parse_issue_number(&task_id)?;
+        self.run_selected_task(&task_id).await
+    }
+
+    /// Everything after the choose phase: estimate, claim, implement, review.
+    async fn run_selected_task(&self, task_id: &str) -> Result<IterationOutcome> {
+        let issue_number = parse_issue_number(task_id)?;
         info!(task_id, issue_number, "selected task");
-        let existing_pr_number = if self.config.dry_run {
-            info!("dry run — skipping existing PR lookup");
-            None
+        let existing_pr_number = self.submission.find_existing_pr_for_issue(issue_number)?;
+        if let Some(pr) = existing_pr_number {
+            info!(pr, issue_number, "existing PR found");
         } else {
-            let pr_number = self.submission.find_existing_pr_for_issue(issue_number)?;
-            if let Some(pr) = pr_number {
-                info!(pr, issue_number, "existing PR found");
-            } else {
-                info!(issue_number, "no existing PR found");
-            }
-            pr_number
-        };
+            info!(issue_number, "no existing PR found");
+        }
 
         // 4. Get task details
         let task = self.source.get_task_details(&issue_number.to_string())?;
         info!(id = task.id, title = task.title, "task details");
+        self.run_recorder.set_task(task_id, &task.title, &task.url);
         self.reporter.task_selected(issue_number, &task.title);
 
-        // 5. Mark in-progress
-        if !self.config.dry_run {
-            info!("marking task in-progress");
-            self.source.mark_in_progress(&task.id)?;
+        // Optional estimate — skip tasks above the configured complexity
+        let estimate = self.estimate_task(&task, task_id).await?;
+        if let (Some(estimate), Some(max)) = (&estimate, self.config.max_complexity)
+            && estimate.complexity > max
+        {
+            info!(
+                task_id,
+                complexity = %estimate.complexity,
+                max_complexity = %max,
+                "task exceeds max_complexity, skipping"
+            );
+            return Ok(IterationOutcome::SkippedTask);
+        }
+
+        if self.config.runner_probe {
+            self.probe_runner().await?;
         }
 
+        // 5. Mark in-progress
+        info!("marking task in-progress");
+        self.source.mark_in_progress(&task.id)?;
+
         // 6. Create worktree
         info!("creating worktree");
         let slug = WorktreeManager::slugify(&task.title);
-        let worktree_info = self.worktree_mgr.create(issue_number, &slug)?;
+        let base_branch = self.config.base_branch_for(&task);
+        let worktree_info = self
+            .worktree_mgr
+            .create_on(issue_number, &slug, base_branch)?;
         info!(
             path = %worktree_info.path.display(),
             branch = worktree_info.branch,
@@ -532,27 +1303,37 @@ impl<
 
         // Update state
         self.state_mgr.set_current_task(
-            &task_id,
+            task_id,
             "implement",
             &worktree_info.path.display().to_string(),
         )?;
 
         // Run the implement → submit → review pipeline, cleaning up on success
         let result = self
-            .run_implement_review(&task, issue_number, &worktree_info, existing_pr_number)
+            .run_implement_review(
+                &task,
+                task_id,
+                &worktree_info,
+                existing_pr_number,
+                estimate.as_ref(),
+            )
             .await;
 
         match result {
             Ok(()) => {
                 // 11. Mark done — skipped; GitHub auto-closes the issue when the PR merges
                 self.state_mgr.complete_current_task()?;
+                self.record_delivery(task_id, &worktree_info);
+                if let Err(e) = self.state_mgr.clear_retry(task_id) {
+                    warn!(error = %e, "failed to clear retry entry");
+                }
 
                 // 12. Clean up worktree
                 info!("cleaning up worktree");
                 if let Err(e) = self.worktree_mgr.remove(&worktree_info.path) {
                     warn!(error = %e, "failed to clean up worktree");
                 }
-                let _ = self.state_mgr.remove_worktree_mapping(&task_id);
+                let _ = self.state_mgr.remove_worktree_mapping(task_id);
 
                 info!("iteration complete");
                 self.reporter.iteration_complete(issue_number, &task.title);
@@ -560,6 +1341,10 @@ impl<
             }
             Err(e) => {
                 warn!(error = %e, "iteration failed");
+                if let Err(record_err) = self.state_mgr.record_failed_attempt(&e.to_string()) {
+                    warn!(error = %record_err, "failed to record failed attempt");
+                }
+                self.schedule_retry(&task, task_id, &e);
                 Err(e)
             }
         }
@@ -590,94 +1375,685 @@ impl<
         }
     }
 
+    /// Translate the task title and body in `vars` into `agent_language` when the
+    /// task is written in another language, recording the original language as
+    /// `issue_language`. PR text keeps using the untranslated task. Best-effort:
+    /// on any failure the task is worked on as written.
+    async fn translate_task_vars(&self, vars: &mut HashMap<String, String>) {
+        let (Some(language), Some(step)) = (&self.config.agent_language, &self.config.translate)
+        else {
+            return;
+        };
+
+        info!("running translate phase");
+        let mut translate_vars = vars.clone();
+        translate_vars.insert("agent_language".to_string(), language.clone());
+        let prompt = match self.prompt_engine.render_step(
+            PromptPhase::Translate,
+            &step.prompt,
+            &translate_vars,
+        ) {
+            Ok(prompt) => prompt,
+            Err(e) => {
+                warn!(error = %e, "failed to render translate prompt, continuing untranslated");
+                return;
+            }
+        };
+        let runner = self.review_factory.create_step_runner(
+            step,
+            self.config.agent_timeout_retries,
+            "translate",
+        );
+        self.record_environment(
+            "translate",
+            &self.repo_root,
+            AgentSettings::from_step(step),
+            &prompt,
+        );
+        let started = Instant::now();
+        let result = match runner.run(Phase::Translate, &prompt, &self.repo_root).await {
+            Ok(result) => result,
+            Err(e) => {
+                warn!(error = %e, "translate phase failed, continuing untranslated");
+                return;
+            }
+        };
+        self.run_recorder
+            .phase("translate", started.elapsed(), result.usage);
+
+        let translation = match parse_translation_output(&result.stdout) {
+            Ok(translation) => translation,
+            Err(e) => {
+                warn!(error = %e, "translate output unparseable, continuing untranslated");
+                return;
+            }
+        };
+        let original = translation.language.trim();
+        if original.is_empty()
+            || original.eq_ignore_ascii_case(language.trim())
+            || (translation.title.trim().is_empty() && translation.body.trim().is_empty())
+        {
+            info!("task already in agent_language, no translation needed");
+            return;
+        }
+        info!(language = original, "task translated");
+        if !translation.title.trim().is_empty() {
+            vars.insert(
+                "issue_title".to_string(),
+                sanitize_untrusted(&translation.title).text,
+            );
+        }
+        if !translation.body.trim().is_empty() {
+            vars.insert(
+                "issue_body".to_string(),
+                sanitize_untrusted(&translation.body).text,
+            );
+        }
+        vars.insert("issue_language".to_string(), original.to_string());
+    }
+
     /// Implement, submit PR, and review — the inner pipeline after worktree creation.
     async fn run_implement_review(
         &self,
         task: &Task,
-        issue_number: u64,
+        task_id: &str,
         worktree_info: &WorktreeInfo,
         existing_pr_number: Option<u64>,
+        estimate: Option<&EstimateOutput>,
     ) -> Result<()> {
         let mut vars = self.initial_task_vars(task, worktree_info);
+        self.translate_task_vars(&mut vars).await;
 
         // 7. Implement phase
         self.reporter.implement_started();
         info!("running implement phase");
         let impl_prompt = self.prompt_engine.render_phase("implement", &vars)?;
-        self.runner
-            .run(Phase::Implement, &impl_prompt, &worktree_info.path)
-            .await?;
+        self.record_environment(
+            "implement",
+            &worktree_info.path,
+            AgentSettings::from_config(&self.config),
+            &impl_prompt,
+        );
+        let impl_started = Instant::now();
+        // Progress summary when the last implement slice ran out of time.
+        let mut unfinished = None;
+        let impl_result = if self.config.interactive {
+            self.run_interactive_implement(&impl_prompt, &vars, worktree_info)
+                .await?
+        } else if let Some(slices) = self.config.implement_slices {
+            let thread = existing_pr_number.or_else(|| parse_issue_number(task_id).ok());
+            let (result, progress) = self
+                .run_sliced_implement(&impl_prompt, worktree_info, thread, slices)
+                .await?;
+            unfinished = progress;
+            result
+        } else {
+            let thread = existing_pr_number.or_else(|| parse_issue_number(task_id).ok());
+            self.disk_guard(
+                &worktree_info.path,
+                self.heartbeat(
+                    "implement",
+                    thread,
+                    self.runner
+                        .run(Phase::Implement, &impl_prompt, &worktree_info.path),
+                ),
+            )
+            .await??
+        };
+        self.run_recorder
+            .phase("implement", impl_started.elapsed(), impl_result.usage);
+        let test_plan_section = self.record_test_plan(&impl_result.stdout, &mut vars);
+        self.state_mgr.update_phase("submit")?;
+        self.guard_protected_paths(&vars, worktree_info).await?;
 
         // 8. Push branch
-        if !self.config.dry_run {
-            info!("pushing branch");
-            self.push_branch(worktree_info)?;
-        }
+        info!("pushing branch");
+        self.push_branch(worktree_info)?;
 
         // 9. Submit PR (skip if choose agent reported an existing PR)
         let pr_number = if let Some(pr) = existing_pr_number {
             info!(pr, "skipping PR submission — existing PR");
+            self.track_pr(pr, task_id, &worktree_info.branch, "");
             Some(pr)
-        } else if !self.config.dry_run {
+        } else {
             info!("submitting PR");
-            let pr_body = format!("Resolves #{issue_number}\n\nAutomated implementation by rlph.");
+            let pr_vars = pr_template_vars(&vars, task, estimate, "", self.run_recorder.usage());
+            let mut pr_title = render_template(&self.config.pr_title_template, &pr_vars)?;
+            let mut pr_body = render_pr_body(
+                &self.config.pr_body_template,
+                &pr_vars,
+                test_plan_section.as_deref(),
+            )?;
+            if let Some(progress) = &unfinished {
+                pr_title = format!("[partial] {}", pr_title.trim());
+                pr_body = format!("{}\n\n{}", pr_body.trim_end(), partial_pr_note(progress));
+            }
             let result = self.submission.submit(
                 &worktree_info.branch,
-                &self.config.base_branch,
-                &task.title,
+                self.config.base_branch_for(task),
+                pr_title.trim(),
                 &pr_body,
             )?;
-            info!(url = result.url, "PR created");
-            self.reporter.pr_created(&result.url);
-            vars.insert("pr_url".to_string(), result.url);
-            result.number
-        } else {
-            info!("dry run — skipping PR submission");
-            None
-        };
-
-        // 10. Mark in-review
-        if !self.config.dry_run {
-            self.source.mark_in_review(&task.id)?;
+            if self.config.dry_run {
+                // Nothing was created, so there is no PR to track or link.
+                None
+            } else {
+                info!(url = result.url, "PR created");
+                self.reporter.pr_created(&result.url);
+                self.run_recorder.pr_url(&result.url);
+                if let Some(number) = result.number {
+                    self.track_pr(number, task_id, &worktree_info.branch, &result.url);
+                }
+                if self.config.issue_pr_comments {
+                    let summary = implementation_summary(&impl_result.stdout)
+                        .unwrap_or_else(|| pr_title.trim().to_string());
+                    self.link_pr_to_issue(task, &result, &worktree_info.branch, &summary);
+                }
+                vars.insert("pr_url".to_string(), result.url);
+                result.number
+            }
+        };
+
+        // 10. Mark in-review, and send it (with the PR link comments) now rather
+        // than after a review that may be cut short.
+        self.source.mark_in_review(&task.id)?;
+        self.flush_source();
+
+        if let Some(progress) = &unfinished {
+            info!("implement ran out of time slices, filing a follow-up instead of reviewing");
+            let pr_url = vars.get("pr_url").map(String::as_str).unwrap_or_default();
+            self.file_unfinished_followup(task, pr_url, progress);
+            return Ok(());
         }
 
-        self.run_review_pipeline(&vars, worktree_info, pr_number, None, false)
-            .await
+        let review_start = worktree_head(worktree_info);
+        let review_summary = if self.config.skip_review {
+            info!("skipping review phases (--skip-review)");
+            self.note_review_skipped(pr_number);
+            String::new()
+        } else {
+            self.run_review_pipeline(task_id, &vars, worktree_info, pr_number, None, false, None)
+                .await?
+        };
+
+        // Re-render the PR body once the review summary and full usage are known.
+        if existing_pr_number.is_none()
+            && !self.config.skip_review
+            && let Some(pr) = pr_number
+            && referenced_vars(&self.config.pr_body_template)
+                .iter()
+                .any(|v| v == "review_summary" || v.starts_with("usage_"))
+        {
+            let pr_vars = pr_template_vars(
+                &vars,
+                task,
+                estimate,
+                &review_summary,
+                self.run_recorder.usage(),
+            );
+            let pr_body = render_pr_body(
+                &self.config.pr_body_template,
+                &pr_vars,
+                test_plan_section.as_deref(),
+            )?;
+            if let Err(e) = self.submission.update_pr_body(pr, &pr_body) {
+                warn!(error = %e, "failed to update PR body with review summary");
+            }
+        }
+
+        if self.config.squash.enabled
+            && !self.config.skip_review
+            && !self.config.dry_run
+            && let Some(pr) = pr_number
+            && let Some(review_start) = review_start.as_deref()
+        {
+            self.squash_and_merge(task, worktree_info, pr, review_start)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Run implement in up to `slices.max_slices` slices of `slices.slice_minutes`.
+    /// When a slice runs out, the agent is stopped and made to commit its work in
+    /// progress, and the next slice continues from that summary. Returns the run
+    /// that finished, or the progress summary when the last slice ran out too.
+    async fn run_sliced_implement(
+        &self,
+        implement_prompt: &str,
+        worktree_info: &WorktreeInfo,
+        thread: Option<u64>,
+        slices: ImplementSlicesConfig,
+    ) -> Result<(RunResult, Option<String>)> {
+        let slice_time = Duration::from_secs(slices.slice_minutes * 60);
+        let mut usage = TokenUsage::default();
+        let mut progress = String::new();
+        for slice in 1..=slices.max_slices {
+            let prompt = if slice == 1 {
+                format!(
+                    "{implement_prompt}{}",
+                    slice_notice(slice, slices.max_slices, slices.slice_minutes)
+                )
+            } else {
+                continuation_prompt(
+                    implement_prompt,
+                    slice,
+                    slices.max_slices,
+                    slices.slice_minutes,
+                    &progress,
+                )
+            };
+            info!(
+                slice,
+                max_slices = slices.max_slices,
+                "running implement slice"
+            );
+            let run = tokio::time::timeout(
+                slice_time,
+                self.runner
+                    .run(Phase::Implement, &prompt, &worktree_info.path),
+            );
+            match self
+                .disk_guard(
+                    &worktree_info.path,
+                    self.heartbeat("implement", thread, run),
+                )
+                .await?
+            {
+                Ok(result) => {
+                    let mut result = result?;
+                    result.usage.add(&usage);
+                    return Ok((result, None));
+                }
+                Err(_) => {
+                    warn!(slice, "implement slice ran out of time, checkpointing");
+                    progress = self
+                        .checkpoint_implement(implement_prompt, worktree_info, slice, &mut usage)
+                        .await;
+                }
+            }
+        }
+        warn!(
+            max_slices = slices.max_slices,
+            "implement did not finish in its last slice"
+        );
+        let result = RunResult {
+            exit_code: 0,
+            stdout: String::new(),
+            stderr: String::new(),
+            session_id: None,
+            usage,
+        };
+        Ok((result, Some(progress)))
+    }
+
+    /// Have the implement agent commit and summarize its work after `slice` ran
+    /// out, then commit anything it left behind. Returns the progress summary.
+    async fn checkpoint_implement(
+        &self,
+        implement_prompt: &str,
+        worktree_info: &WorktreeInfo,
+        slice: u32,
+        usage: &mut TokenUsage,
+    ) -> String {
+        let prompt = checkpoint_prompt(implement_prompt);
+        let run = self
+            .runner
+            .run(Phase::Implement, &prompt, &worktree_info.path);
+        let progress = match tokio::time::timeout(CHECKPOINT_TIMEOUT, run).await {
+            Ok(Ok(result)) => {
+                usage.add(&result.usage);
+                parse_progress(&result.stdout)
+            }
+            Ok(Err(e)) => {
+                warn!(slice, error = %e, "implement checkpoint failed");
+                None
+            }
+            Err(_) => {
+                warn!(slice, "implement checkpoint timed out");
+                None
+            }
+        };
+        match commit_wip(&worktree_info.path, slice) {
+            Ok(true) => info!(slice, "committed work in progress left by implement"),
+            Ok(false) => {}
+            Err(e) => warn!(slice, error = %e, "failed to commit implement work in progress"),
+        }
+        progress.unwrap_or_else(|| {
+            format!("Slice {slice} ran out of time without a progress summary.\n")
+        })
+    }
+
+    /// With `test_plan` enabled, parse the implement agent's test plan into the
+    /// `test_plan` review variable and return the PR body section for it. A
+    /// missing or malformed plan is logged and noted in both instead.
+    fn record_test_plan(
+        &self,
+        implement_stdout: &str,
+        vars: &mut HashMap<String, String>,
+    ) -> Option<String> {
+        if !self.config.test_plan {
+            return None;
+        }
+        match parse_test_plan(implement_stdout) {
+            Ok(plan) => {
+                info!(
+                    items = plan.items.len(),
+                    "implement agent reported a test plan"
+                );
+                vars.insert("test_plan".to_string(), plan.for_review());
+                Some(plan.checklist())
+            }
+            Err(reason) => {
+                warn!(reason, "implement agent did not report a valid test plan");
+                vars.insert("test_plan".to_string(), missing_plan_for_review(&reason));
+                Some(missing_plan_note(&reason))
+            }
+        }
+    }
+
+    /// Best-effort: file the issue that picks up a task left unfinished after its
+    /// last implement slice.
+    fn file_unfinished_followup(&self, task: &Task, pr_url: &str, progress: &str) {
+        let (title, body) = followup_task(task, pr_url, progress);
+        match self
+            .source
+            .create_task(&title, &body, std::slice::from_ref(&self.config.label))
+        {
+            Ok(followup) => info!(
+                id = followup.id,
+                title, "filed follow-up for unfinished task"
+            ),
+            Err(e) => warn!(error = %e, "failed to file follow-up for unfinished task"),
+        }
+    }
+
+    /// Squash an approved PR's fix-round commits, force-push the result, and
+    /// enable auto-merge once CI passes on the new head. Failures are logged and
+    /// leave the PR open for a human.
+    async fn squash_and_merge(
+        &self,
+        task: &Task,
+        worktree_info: &WorktreeInfo,
+        pr_number: u64,
+        review_start: &str,
+    ) {
+        let squash = &self.config.squash;
+        let old_head = worktree_head(worktree_info);
+        let base_ref = format!("origin/{}", self.config.base_branch_for(task));
+        match squash_branch(
+            &worktree_info.path,
+            squash.mode,
+            &base_ref,
+            review_start,
+            &task.title,
+        ) {
+            Ok(true) => {
+                if let Err(e) = self.force_push_branch(worktree_info, old_head.as_deref()) {
+                    warn!(error = %e, "failed to push squashed branch");
+                    if let Some(old_head) = &old_head {
+                        let _ = git_in_dir(&worktree_info.path, &["reset", "--soft", old_head]);
+                    }
+                    return;
+                }
+                // Checks for the new head take a moment to be registered.
+                tokio::time::sleep(CI_SETTLE_DELAY).await;
+            }
+            Ok(false) => {}
+            Err(e) => {
+                warn!(error = %e, "failed to squash fix rounds");
+                return;
+            }
+        }
+        if !squash.auto_merge {
+            return;
+        }
+
+        match self.wait_for_ci(pr_number).await {
+            Ok(CiStatus::Passed) => {
+                if let Err(e) = self
+                    .submission
+                    .enable_auto_merge(pr_number, squash.merge_method)
+                {
+                    warn!(error = %e, pr_number, "failed to enable auto-merge");
+                }
+            }
+            Ok(CiStatus::Failed(checks)) => {
+                warn!(pr_number, checks = %checks.join(", "), "CI failed after squash");
+                let comment = format!(
+                    "CI failed on the squashed branch ({}); auto-merge was not enabled.",
+                    checks.join(", ")
+                );
+                if let Err(e) = self.submission.post_pr_comment(pr_number, &comment) {
+                    warn!(error = %e, "failed to post CI failure comment");
+                }
+            }
+            Ok(CiStatus::Pending) => {
+                warn!(
+                    pr_number,
+                    timeout_secs = squash.ci_timeout_secs,
+                    "CI still pending after squash; auto-merge was not enabled"
+                );
+            }
+            Err(e) => warn!(error = %e, pr_number, "failed to read CI status"),
+        }
+    }
+
+    /// Poll the PR's CI status until it settles or `squash.ci_timeout_secs`
+    /// passes.
+    async fn wait_for_ci(&self, pr_number: u64) -> Result<CiStatus> {
+        let deadline = Instant::now() + Duration::from_secs(self.config.squash.ci_timeout_secs);
+        loop {
+            let status = self.submission.ci_status(pr_number)?;
+            if status != CiStatus::Pending || Instant::now() >= deadline {
+                return Ok(status);
+            }
+            tokio::time::sleep(CI_POLL_INTERVAL).await;
+        }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn run_review_pipeline(
         &self,
+        task_id: &str,
         vars: &HashMap<String, String>,
         worktree_info: &WorktreeInfo,
         pr_number: Option<u64>,
         push_remote_branch: Option<&str>,
         review_only: bool,
-    ) -> Result<()> {
+        write_guard: Option<&WorktreeSnapshot>,
+    ) -> Result<String> {
         self.state_mgr.update_phase("review")?;
         let max_reviews = if review_only {
             1
         } else {
             self.config.max_review_rounds
         };
-        let mut review_passed = false;
+        let mut review_summary: Option<String> = None;
         let mut last_json_failure: Option<String> = None;
+        let mut last_findings: Vec<ReviewFinding> = Vec::new();
+        // Findings fixed this run, recorded in the lessons file once review is approved.
+        let mut lessons: Vec<Lesson> = Vec::new();
+        // Rows of the review comment's history table, carried over from earlier runs.
+        let mut history_rows: Vec<String> = Vec::new();
+        let issue_number = vars.get("issue_number").and_then(|n| n.parse::<u64>().ok());
+
+        // Pick up where an interrupted review of this worktree left off, or start a
+        // fresh checkpoint.
+        let checkpoint = self
+            .state_mgr
+            .review_checkpoint()
+            .filter(|c| c.task_id == task_id && Path::new(&c.worktree_path) == worktree_info.path);
+        let resuming = checkpoint.is_some();
+        let (first_round, mut resumed_phases) = match checkpoint {
+            Some(checkpoint) => {
+                info!(
+                    round = checkpoint.round,
+                    phases = checkpoint.phases.len(),
+                    "resuming review from checkpoint"
+                );
+                last_findings = serde_json::from_str(&checkpoint.last_findings).unwrap_or_default();
+                (checkpoint.round, checkpoint.phases)
+            }
+            None => {
+                self.state_mgr.set_review_checkpoint(ReviewCheckpoint {
+                    task_id: task_id.to_string(),
+                    round: 1,
+                    worktree_path: worktree_info.path.display().to_string(),
+                    branch: worktree_info.branch.clone(),
+                    pr_number,
+                    push_remote_branch: push_remote_branch.map(str::to_string),
+                    review_only,
+                    guard_writes: write_guard.is_some(),
+                    vars: vars.clone(),
+                    last_findings: String::new(),
+                    phases: HashMap::new(),
+                })?;
+                (1, HashMap::new())
+            }
+        };
+
+        // With `differential_review`, a round only reviews what changed since the
+        // commit the last aggregated round of the PR covered, and the aggregator
+        // carries that round's findings forward.
+        let mut reviewed_sha: Option<String> = None;
+        if self.config.differential_review
+            && let Some(head) = pr_number.and_then(|n| self.state_mgr.reviewed_head(n))
+        {
+            if !resuming {
+                last_findings = serde_json::from_str(&head.findings).unwrap_or_default();
+            }
+            reviewed_sha = Some(head.sha);
+        }
+
+        let required_fields = &self.config.finding_required_fields;
+        let mut vars = vars.clone();
+        vars.insert(
+            "findings_schema".to_string(),
+            findings_schema(&self.config.prompt_version, required_fields),
+        );
+        vars.insert("ignored_paths".to_string(), self.ignore.prompt_list());
+        vars.insert("diff_excludes".to_string(), self.ignore.diff_excludes());
+        vars.entry("repo_conventions".to_string())
+            .or_insert_with(|| {
+                load_conventions(&worktree_info.path, &self.config.conventions_files)
+            });
+        // Worktrees branch from the remote base, so diff against it.
+        let affected = branch_affected_crates(
+            &worktree_info.path,
+            &format!(
+                "origin/{}",
+                vars.get("base_branch").map_or("main", String::as_str)
+            ),
+        );
+        vars.insert("affected_crates".to_string(), affected.join(", "));
+        vars.insert(
+            "project_toolchain".to_string(),
+            describe_toolchain(&worktree_info.path, &self.config.toolchain, &affected),
+        );
+        let vars = &vars;
+        // Missing extension fields are treated like malformed JSON so the agent gets a
+        // correction prompt naming them. Findings on `.rlphignore`d paths are dropped.
+        let parse_phase = |raw: &str| -> Result<_> {
+            let mut output = parse_phase_output(raw)?;
+            check_required_fields(&output.findings, required_fields)?;
+            output.findings.retain(|f| !self.ignore.is_ignored(&f.file));
+            Ok(output)
+        };
+        let parse_aggregator = |raw: &str| -> Result<_> {
+            let mut output = parse_aggregator_output(raw)?;
+            check_required_fields(&output.findings, required_fields)?;
+            output.findings.retain(|f| !self.ignore.is_ignored(&f.file));
+            Ok(output)
+        };
+        // A `fail_fast` phase with a critical finding short-circuits the round with
+        // all of that phase's findings.
+        let blocking_findings = |o: &ReviewPhaseOutput| -> Option<Vec<ReviewFinding>> {
+            let phase = self
+                .config
+                .review_phases
+                .iter()
+                .find(|p| p.name == o.name)?;
+            if !phase.fail_fast {
+                return None;
+            }
+            let mut parsed = if o.from_command {
+                parse_phase_output(&o.stdout).ok()?
+            } else {
+                parse_phase(&o.stdout).ok()?
+            };
+            parsed.findings.retain(|f| !self.ignore.is_ignored(&f.file));
+            parsed
+                .findings
+                .iter()
+                .any(|f| f.severity == Severity::Critical)
+                .then_some(parsed.findings)
+        };
 
         // Report phase names once before the loop (they don't change between rounds).
         let phase_names: Vec<String> = self
             .config
-            .review_phases
-            .iter()
+            .active_review_phases()
             .map(|p| p.name.clone())
             .collect();
         self.reporter.phases_started(&phase_names);
 
-        for round in 1..=max_reviews {
+        // Under the adaptive policy, a fix that changes nothing or findings that stop
+        // decreasing end the review before `max_review_rounds`.
+        let adaptive = self.config.review_round_policy == ReviewRoundPolicy::Adaptive;
+        let mut budget = ReviewBudget::default();
+        let mut stop_reason: Option<String> = None;
+        let snapshot_before_fix = || -> Result<Option<WorktreeSnapshot>> {
+            adaptive
+                .then(|| WorktreeSnapshot::capture(&worktree_info.path))
+                .transpose()
+        };
+        let fix_changed_nothing = |before: &Option<WorktreeSnapshot>| -> Result<bool> {
+            match before {
+                Some(snapshot) => Ok(snapshot
+                    .changes_since(&worktree_info.path, &self.ignore)?
+                    .is_none()),
+                None => Ok(false),
+            }
+        };
+
+        for round in first_round..=max_reviews {
             info!(round, max_reviews, "review round");
+            self.run_recorder.review_round(round);
+            let head = if self.config.differential_review {
+                git_in_dir(&worktree_info.path, &["rev-parse", "HEAD"])
+                    .ok()
+                    .map(|sha| sha.trim().to_string())
+            } else {
+                None
+            };
+            // A force-push can drop the reviewed commit; the whole branch is
+            // reviewed again then.
+            let since = reviewed_sha.clone().filter(|sha| {
+                head.is_some()
+                    && git_in_dir(
+                        &worktree_info.path,
+                        &["merge-base", "--is-ancestor", sha, "HEAD"],
+                    )
+                    .is_ok()
+            });
+
+            // Phases checkpointed before an interruption are reused, not rerun.
+            let resumed = std::mem::take(&mut resumed_phases);
+            if resumed.is_empty() {
+                let findings_json = serde_json::to_string(&last_findings).unwrap_or_default();
+                self.state_mgr.begin_review_round(round, &findings_json)?;
+            }
 
             // Fetch current PR comments for this round
             let (pr_comments_text, has_pr_comments) = if let Some(pr_num) = pr_number {
                 match self.submission.fetch_pr_comments(pr_num) {
                     Ok(comments) => {
+                        if round == first_round
+                            && let Some(existing) =
+                                comments.iter().find(|c| c.body.contains(REVIEW_MARKER))
+                        {
+                            history_rows = parse_history(&existing.body);
+                        }
                         let has = !comments.is_empty();
                         (format_pr_comments_for_prompt(&comments, pr_num), has)
                     }
@@ -692,8 +2068,125 @@ impl<
 
             let pr_number_str = pr_number.map(|n| n.to_string()).unwrap_or_default();
 
+            // Context artifacts change with every fix, so they are rebuilt each round.
+            let mut context_kinds: Vec<ReviewContext> = Vec::new();
+            for kind in self
+                .config
+                .active_review_phases()
+                .filter(|p| !resumed.contains_key(&p.name))
+                .flat_map(|p| &p.context)
+            {
+                if !context_kinds.contains(kind) {
+                    context_kinds.push(*kind);
+                }
+            }
+            let review_base = since.clone().unwrap_or_else(|| {
+                format!(
+                    "origin/{}",
+                    vars.get("base_branch").map_or("main", String::as_str)
+                )
+            });
+            let review_context = build_review_context(
+                &worktree_info.path,
+                &review_base,
+                &context_kinds,
+                &self.ignore,
+            );
+
+            // Phases start in declared order. With `review_concurrency` set, a phase
+            // waits for a running one to finish, so `1` runs them one after another.
+            let limit = self.config.review_concurrency.unwrap_or(usize::MAX);
+            let mut review_outputs = Vec::new();
+            let mut blocked: Option<(String, Vec<ReviewFinding>)> = None;
             let mut join_set = tokio::task::JoinSet::new();
-            for phase_config in &self.config.review_phases {
+            'spawn: for phase_config in self.config.active_review_phases() {
+                if let Some(saved) = resumed.get(&phase_config.name) {
+                    info!(phase = %phase_config.name, "reusing checkpointed phase output");
+                    self.reporter.phase_complete(&phase_config.name);
+                    let output = ReviewPhaseOutput {
+                        name: phase_config.name.clone(),
+                        stdout: saved.stdout.clone(),
+                        session_id: None,
+                        from_command: saved.from_command,
+                        elapsed: Duration::ZERO,
+                        usage: TokenUsage::default(),
+                    };
+                    if let Some(findings) = blocking_findings(&output) {
+                        blocked = Some((output.name, findings));
+                        break 'spawn;
+                    }
+                    review_outputs.push(output);
+                    continue;
+                }
+                while join_set.len() >= limit {
+                    let Some(result) = join_set.join_next().await else {
+                        break;
+                    };
+                    let output = self.finish_review_phase(result)?;
+                    if let Some(findings) = blocking_findings(&output) {
+                        blocked = Some((output.name, findings));
+                        break 'spawn;
+                    }
+                    review_outputs.push(output);
+                }
+                if let ReviewPhaseKind::Command { command, format } = &phase_config.kind {
+                    let command = command.clone();
+                    let format = *format;
+                    let working_dir = worktree_info.path.clone();
+                    let phase_name = phase_config.name.clone();
+                    let timeout = phase_config.agent_timeout.map(Duration::from_secs);
+                    join_set.spawn(async move {
+                        let started = Instant::now();
+                        let log_prefix = format!("review:{phase_name}");
+                        let findings =
+                            run_analyzer(&command, format, &working_dir, timeout, &log_prefix)
+                                .await?;
+                        Ok::<ReviewPhaseOutput, Error>(ReviewPhaseOutput {
+                            name: phase_name,
+                            stdout: serde_json::json!({ "findings": findings }).to_string(),
+                            session_id: None,
+                            from_command: true,
+                            elapsed: started.elapsed(),
+                            usage: TokenUsage::default(),
+                        })
+                    });
+                    continue;
+                }
+                if let ReviewPhaseKind::Coverage { command, threshold } = &phase_config.kind {
+                    let command = command.clone().unwrap_or_else(|| {
+                        coverage_command(&worktree_info.path, &self.config.toolchain, &affected)
+                    });
+                    let threshold = *threshold;
+                    // Worktrees branch from the remote base, so diff against it.
+                    let base = vars.get("base_branch").map_or("main", String::as_str);
+                    let base_ref = format!("origin/{base}");
+                    let working_dir = worktree_info.path.clone();
+                    let phase_name = phase_config.name.clone();
+                    let timeout = phase_config.agent_timeout.map(Duration::from_secs);
+                    join_set.spawn(async move {
+                        let started = Instant::now();
+                        let log_prefix = format!("review:{phase_name}");
+                        let findings = run_coverage_gate(
+                            &command,
+                            threshold,
+                            &base_ref,
+                            &working_dir,
+                            timeout,
+                            &log_prefix,
+                        )
+                        .await?;
+                        Ok::<ReviewPhaseOutput, Error>(ReviewPhaseOutput {
+                            name: phase_name,
+                            stdout: serde_json::json!({ "findings": findings }).to_string(),
+                            session_id: None,
+                            from_command: true,
+                            elapsed: started.elapsed(),
+                            usage: TokenUsage::default(),
+                        })
+                    });
+                    continue;
+                }
+
                 let phase_runner = self
                     .review_factory
                     .create_phase_runner(phase_config, self.config.agent_timeout_retries);
@@ -702,6 +2195,22 @@ impl<
                 phase_vars.insert("review_phase_name".to_string(), phase_config.name.clone());
                 phase_vars.insert("pr_comments".to_string(), pr_comments_text.clone());
                 phase_vars.insert("pr_number".to_string(), pr_number_str.clone());
+                phase_vars.insert(
+                    "review_since".to_string(),
+                    since.clone().unwrap_or_default(),
+                );
+                for kind in [
+                    ReviewContext::Diff,
+                    ReviewContext::FullFiles,
+                    ReviewContext::Blame,
+                ] {
+                    let value = if phase_config.context.contains(&kind) {
+                        review_context.get(context_var(kind)).cloned()
+                    } else {
+                        None
+                    };
+                    phase_vars.insert(context_var(kind).to_string(), value.unwrap_or_default());
+                }
                 // upon templates treat empty strings as falsy in {% if has_pr_comments %}
                 phase_vars.insert(
                     "has_pr_comments".to_string(),
@@ -712,13 +2221,22 @@ impl<
                     },
                 );
 
-                let prompt = self
-                    .prompt_engine
-                    .render_phase(&phase_config.prompt, &phase_vars)?;
+                let prompt = self.prompt_engine.render_step(
+                    PromptPhase::Review,
+                    &phase_config.prompt,
+                    &phase_vars,
+                )?;
+                self.record_environment(
+                    &format!("review:{}", phase_config.name),
+                    &worktree_info.path,
+                    AgentSettings::from_review_phase(phase_config),
+                    &prompt,
+                );
                 let working_dir = worktree_info.path.clone();
                 let phase_name = phase_config.name.clone();
 
                 join_set.spawn(async move {
+                    let started = Instant::now();
                     let result = phase_runner
                         .run(Phase::Review, &prompt, &working_dir)
                         .await?;
@@ -726,22 +2244,125 @@ impl<
                         name: phase_name,
                         stdout: result.stdout,
                         session_id: result.session_id,
+                        from_command: false,
+                        elapsed: started.elapsed(),
+                        usage: result.usage,
                     })
                 });
             }
 
-            let mut review_outputs = Vec::new();
-            while let Some(result) = join_set.join_next().await {
-                let output = result.map_err(|e| Error::AgentRunner(e.to_string()))??;
-                self.reporter.phase_complete(&output.name);
-                review_outputs.push(output);
+            let reviews = self.heartbeat("review", pr_number, async {
+                while blocked.is_none()
+                    && let Some(result) = join_set.join_next().await
+                {
+                    let output = self.finish_review_phase(result)?;
+                    if let Some(findings) = blocking_findings(&output) {
+                        blocked = Some((output.name, findings));
+                        break;
+                    }
+                    review_outputs.push(output);
+                }
+                Ok::<(), Error>(())
+            });
+            self.disk_guard(&worktree_info.path, reviews).await??;
+
+            if let Some((phase_name, findings)) = blocked {
+                // Dropping the remaining phases kills their agent processes.
+                join_set.shutdown().await;
+                info!(round, phase = %phase_name, "fail-fast phase reported a critical finding — skipping aggregation");
+                self.check_write_guard(write_guard, worktree_info)?;
+
+                let summary = format!(
+                    "Review stopped early: the `{phase_name}` phase reported a critical finding."
+                );
+                let mut history_entry = self.record_review_round(
+                    &mut history_rows,
+                    &last_findings,
+                    &findings,
+                    &Verdict::NeedsFix,
+                );
+                last_findings.clone_from(&findings);
+                self.export_round_findings(task_id, round, &findings);
+                self.run_recorder
+                    .verdict(&Verdict::NeedsFix.to_string(), findings.len());
+                self.reporter.review_summary(&summary);
+                self.upsert_review_comment(
+                    pr_number,
+                    issue_number,
+                    &findings,
+                    &summary,
+                    &history_rows,
+                );
+
+                if review_only {
+                    info!("review-only mode — skipping fix phase");
+                    break;
+                }
+
+                if adaptive && let Some(reason) = budget.record_findings(round, findings.len()) {
+                    stop_reason = Some(reason);
+                    break;
+                }
+
+                let fix_instructions = render_findings_for_prompt(&findings, Some(&phase_name));
+                let attribution = FixAttribution {
+                    round: round.to_string(),
+                    reviewers: vec![phase_name.clone()],
+                    requesters: Vec::new(),
+                };
+                let before_fix = snapshot_before_fix()?;
+                let fix = self
+                    .fix_and_push(
+                        vars,
+                        fix_instructions,
+                        worktree_info,
+                        push_remote_branch,
+                        &attribution,
+                    )
+                    .await?;
+                if let Ok(fix) = &fix
+                    && fix.status == FixStatus::Fixed
+                {
+                    lessons.extend(Lesson::from_round(&findings, &fix.summary));
+                }
+                history_entry.fix_summary = Some(fix_round_summary(fix, &mut last_json_failure));
+                if let Some(row) = history_rows.last_mut() {
+                    *row = history_entry.row();
+                }
+                self.upsert_review_comment(
+                    pr_number,
+                    issue_number,
+                    &findings,
+                    &summary,
+                    &history_rows,
+                );
+                if fix_changed_nothing(&before_fix)? {
+                    stop_reason = Some(unchanged_fix_reason(round));
+                    break;
+                }
+                continue;
             }
 
             let mut review_texts = Vec::new();
+            let mut checks = Vec::new();
             let mut phase_parse_failed = false;
             for o in &review_outputs {
-                let rendered = match parse_phase_output(&o.stdout) {
-                    Ok(phase) => render_findings_for_prompt(&phase.findings, Some(&o.name)),
+                // Analyzer findings never carry configured extension fields.
+                let parsed = if o.from_command {
+                    parse_phase_output(&o.stdout).map(|mut phase| {
+                        phase.findings.retain(|f| !self.ignore.is_ignored(&f.file));
+                        phase
+                    })
+                } else {
+                    parse_phase(&o.stdout)
+                };
+                let rendered = match parsed {
+                    Ok(phase) => {
+                        if o.from_command {
+                            checks.push(AutomatedCheck::from_gate(&o.name, &phase.findings));
+                        }
+                        render_findings_for_prompt(&phase.findings, Some(&o.name))
+                    }
                     Err(e) => {
                         // Try correction via session resume
                         let phase_config =
@@ -755,11 +2376,13 @@ impl<
                                 pc.agent_model.as_deref(),
                                 pc.agent_effort.as_deref(),
                                 pc.agent_variant.as_deref(),
+                                pc.permission_mode,
+                                &pc.agent_identity,
                                 pc.agent_timeout,
                                 SchemaName::Phase,
                                 &e.to_string(),
                                 &worktree_info.path,
-                                parse_phase_output,
+                                &parse_phase,
                             )
                             .await
                         } else {
@@ -785,6 +2408,12 @@ impl<
                 continue;
             }
             let review_outputs_text = review_texts.join("\n\n---\n\n");
+            if let Some(number) = pr_number {
+                match self.submission.ci_status(number) {
+                    Ok(status) => checks.push(AutomatedCheck::from_ci(&status)),
+                    Err(e) => warn!(error = %e, "failed to fetch CI status for the aggregate"),
+                }
+            }
 
             let agg_config = &self.config.review_aggregate;
             let agg_runner = self.review_factory.create_step_runner(
@@ -797,15 +2426,47 @@ impl<
             agg_vars.insert("review_outputs".to_string(), review_outputs_text);
             agg_vars.insert("pr_comments".to_string(), pr_comments_text.clone());
             agg_vars.insert("pr_number".to_string(), pr_number_str.clone());
+            agg_vars.insert(
+                "automated_checks".to_string(),
+                render_automated_checks(&checks),
+            );
+            let previous_findings = if since.is_some() && !last_findings.is_empty() {
+                render_findings_for_prompt(&last_findings, None)
+            } else {
+                String::new()
+            };
+            agg_vars.insert(
+                "review_since".to_string(),
+                since.clone().unwrap_or_default(),
+            );
+            agg_vars.insert("previous_findings".to_string(), previous_findings);
 
-            let agg_prompt = self
-                .prompt_engine
-                .render_phase(&agg_config.prompt, &agg_vars)?;
-            let agg_result = agg_runner
-                .run(Phase::ReviewAggregate, &agg_prompt, &worktree_info.path)
-                .await?;
+            let agg_prompt = self.prompt_engine.render_step(
+                PromptPhase::ReviewAggregate,
+                &agg_config.prompt,
+                &agg_vars,
+            )?;
+            self.record_environment(
+                "aggregate",
+                &worktree_info.path,
+                AgentSettings::from_step(agg_config),
+                &agg_prompt,
+            );
+            let agg_started = Instant::now();
+            let agg_result = self
+                .disk_guard(
+                    &worktree_info.path,
+                    self.heartbeat(
+                        "aggregate",
+                        pr_number,
+                        agg_runner.run(Phase::ReviewAggregate, &agg_prompt, &worktree_info.path),
+                    ),
+                )
+                .await??;
+            self.run_recorder
+                .phase("aggregate", agg_started.elapsed(), agg_result.usage);
 
-            let agg_output = match parse_aggregator_output(&agg_result.stdout) {
+            let agg_output = match parse_aggregator(&agg_result.stdout) {
                 Ok(output) => output,
                 Err(e) => {
                     // Attempt session resume with correction prompt
@@ -817,11 +2478,13 @@ impl<
                         agg_config.agent_model.as_deref(),
                         agg_config.agent_effort.as_deref(),
                         agg_config.agent_variant.as_deref(),
+                        agg_config.permission_mode,
+                        &agg_config.agent_identity,
                         agg_config.agent_timeout,
                         SchemaName::Aggregator,
                         &e.to_string(),
                         &worktree_info.path,
-                        parse_aggregator_output,
+                        &parse_aggregator,
                     )
                     .await;
                     match recovered {
@@ -835,25 +2498,65 @@ impl<
                 }
             };
 
-            let comment_body = format!(
-                "{REVIEW_MARKER}\n{}",
-                render_findings_for_github(&agg_output.findings, &agg_output.comment),
+            self.check_write_guard(write_guard, worktree_info)?;
+
+            let mut history_entry = self.record_review_round(
+                &mut history_rows,
+                &last_findings,
+                &agg_output.findings,
+                &agg_output.verdict,
             );
+            last_findings.clone_from(&agg_output.findings);
+            if let Some(head) = head {
+                if let Some(n) = pr_number
+                    && let Err(e) = self.state_mgr.set_reviewed_head(
+                        n,
+                        &head,
+                        &serde_json::to_string(&last_findings).unwrap_or_default(),
+                    )
+                {
+                    warn!(pr = n, error = %e, "failed to record reviewed head");
+                }
+                reviewed_sha = Some(head);
+            }
+            self.export_round_findings(task_id, round, &agg_output.findings);
+            self.run_recorder
+                .verdict(&agg_output.verdict.to_string(), agg_output.findings.len());
             let summary = agg_output.comment.trim();
             if !summary.is_empty() {
                 self.reporter.review_summary(summary);
             }
 
-            if let Some(pr_num) = pr_number
-                && !self.config.dry_run
-                && let Err(e) = self.submission.upsert_review_comment(pr_num, &comment_body)
+            self.upsert_review_comment(
+                pr_number,
+                issue_number,
+                &agg_output.findings,
+                &agg_output.comment,
+                &history_rows,
+            );
+
+            // Findings left standing (approved, or review-only) get one-click suggestions.
+            if (agg_output.verdict == Verdict::Approved || review_only)
+                && self.config.suggestion_comments
+                && let Some(pr_num) = pr_number
             {
-                warn!(error = %e, "failed to comment on PR");
+                self.post_suggestions(&agg_output.findings, pr_num);
             }
 
             if agg_output.verdict == Verdict::Approved {
                 info!(round, "review approved");
-                review_passed = true;
+                if self.config.followup_issues
+                    && let Some(pr_num) = pr_number
+                {
+                    self.file_followup_issues(
+                        &agg_output.findings,
+                        pr_num,
+                        vars.get("pr_url")
+                            .map(String::as_str)
+                            .filter(|url| !url.is_empty()),
+                    );
+                }
+                review_summary = Some(agg_output.comment.clone());
                 break;
             }
 
@@ -872,78 +2575,59 @@ impl<
                 }
             };
 
-            info!(round, "review needs fix, running fix agent");
-
-            let fix_config = &self.config.review_fix;
-            let fix_runner = self.review_factory.create_step_runner(
-                fix_config,
-                self.config.agent_timeout_retries,
-                "fix",
-            );
+            if adaptive
+                && let Some(reason) = budget.record_findings(round, agg_output.findings.len())
+            {
+                stop_reason = Some(reason);
+                break;
+            }
 
-            let mut fix_vars = vars.clone();
-            fix_vars.insert("fix_instructions".to_string(), fix_instructions);
+            info!(round, "review needs fix, running fix agent");
 
-            let fix_prompt = self
-                .prompt_engine
-                .render_phase(&fix_config.prompt, &fix_vars)?;
-            let fix_result = fix_runner
-                .run(Phase::ReviewFix, &fix_prompt, &worktree_info.path)
+            let attribution = FixAttribution {
+                round: round.to_string(),
+                reviewers: review_outputs.iter().map(|o| o.name.clone()).collect(),
+                requesters: Vec::new(),
+            };
+            let before_fix = snapshot_before_fix()?;
+            let fix = self
+                .fix_and_push(
+                    vars,
+                    fix_instructions,
+                    worktree_info,
+                    push_remote_branch,
+                    &attribution,
+                )
                 .await?;
-
-            match parse_fix_output(&fix_result.stdout) {
-                Ok(fix_output) => {
-                    info!(
-                        status = ?fix_output.status,
-                        summary = fix_output.summary,
-                        files_changed = ?fix_output.files_changed,
-                        "fix agent complete"
-                    );
-                }
-                Err(e) => {
-                    // Attempt session resume with correction prompt for fix output
-                    let recovered = retry_with_correction(
-                        &self.correction_runner,
-                        fix_result.session_id.as_deref(),
-                        fix_config.runner,
-                        &fix_config.agent_binary,
-                        fix_config.agent_model.as_deref(),
-                        fix_config.agent_effort.as_deref(),
-                        fix_config.agent_variant.as_deref(),
-                        fix_config.agent_timeout,
-                        SchemaName::Fix,
-                        &e.to_string(),
-                        &worktree_info.path,
-                        parse_fix_output,
-                    )
-                    .await;
-                    match recovered {
-                        Some(fix_output) => {
-                            info!(
-                                status = ?fix_output.status,
-                                summary = fix_output.summary,
-                                files_changed = ?fix_output.files_changed,
-                                "fix agent complete (after correction)"
-                            );
-                        }
-                        None => {
-                            warn!(error = %e, "fix agent JSON correction failed — retrying round");
-                            last_json_failure = Some(format!("fix agent malformed JSON: {e}"));
-                            continue;
-                        }
-                    }
-                }
+            if let Ok(fix) = &fix
+                && fix.status == FixStatus::Fixed
+            {
+                lessons.extend(Lesson::from_round(&agg_output.findings, &fix.summary));
+            }
+            history_entry.fix_summary = Some(fix_round_summary(fix, &mut last_json_failure));
+            if let Some(row) = history_rows.last_mut() {
+                *row = history_entry.row();
+            }
+            self.upsert_review_comment(
+                pr_number,
+                issue_number,
+                &agg_output.findings,
+                &agg_output.comment,
+                &history_rows,
+            );
+            if fix_changed_nothing(&before_fix)? {
+                stop_reason = Some(unchanged_fix_reason(round));
+                break;
             }
+        }
 
-            if !self.config.dry_run {
-                let push_result = if let Some(remote_branch) = push_remote_branch {
-                    self.push_branch_to(worktree_info, remote_branch)
-                } else {
-                    self.push_branch(worktree_info)
-                };
-                if let Err(e) = push_result {
-                    warn!(error = %e, "failed to push review fixes");
-                }
+        self.state_mgr.clear_review_checkpoint()?;
+
+        if review_summary.is_some() && self.config.review_lessons && !self.config.dry_run {
+            match record_lessons(&self.repo_root, &lessons) {
+                Ok(0) => {}
+                Ok(added) => info!(added, "recorded review lessons"),
+                Err(e) => warn!(error = %e, "failed to record review lessons"),
             }
         }
 
@@ -954,61 +2638,1570 @@ impl<
             self.reporter.pr_url(url);
         }
 
-        if !review_passed {
+        let Some(review_summary) = review_summary else {
             let reason = last_json_failure
                 .map(|f| format!(" (last failure: {f})"))
                 .unwrap_or_default();
+            let unresolved = if last_findings.is_empty() {
+                String::new()
+            } else {
+                let list: Vec<String> = last_findings
+                    .iter()
+                    .map(|f| {
+                        format!(
+                            "[{}] {} ({}:{}): {}",
+                            f.severity.label(),
+                            f.id,
+                            f.file,
+                            f.line,
+                            f.description.trim()
+                        )
+                    })
+                    .collect();
+                format!("; unresolved findings: {}", list.join("; "))
+            };
+            let reason = match stop_reason {
+                Some(stop) => format!("review stopped early: {stop}{unresolved}"),
+                None => format!(
+                    "review did not complete after {max_reviews} round(s){reason}{unresolved}"
+                ),
+            };
+            let pr_url = vars.get("pr_url").filter(|url| !url.is_empty());
+            self.reporter
+                .review_failed(pr_url.map(String::as_str), &reason);
+            return Err(Error::ReviewExhausted(reason));
+        };
+
+        Ok(review_summary)
+    }
+
+    /// Fail if review agents changed the worktree since `write_guard` was taken.
+    fn check_write_guard(
+        &self,
+        write_guard: Option<&WorktreeSnapshot>,
+        worktree_info: &WorktreeInfo,
+    ) -> Result<()> {
+        if let Some(snapshot) = write_guard
+            && let Some(changes) = snapshot.changes_since(&worktree_info.path, &self.ignore)?
+        {
             return Err(Error::Orchestrator(format!(
-                "review did not complete after {max_reviews} round(s){reason}"
+                "review agents modified the worktree at {} ({changes}); reviews must be side-effect-free — rerun with --allow-writes if this is intended",
+                worktree_info.path.display()
             )));
         }
-
         Ok(())
     }
 
-    /// Parse the task selection from `.rlph/task.toml` written by the choose agent.
-    fn parse_task_selection(&self) -> Result<String> {
-        let path = self.repo_root.join(".rlph").join("task.toml");
-        let content = std::fs::read_to_string(&path).map_err(|e| {
-            Error::Orchestrator(format!(
-                "failed to read task selection {}: {e}",
-                path.display()
-            ))
-        })?;
-        let selection: TaskSelection = toml::from_str(&content)
-            .map_err(|e| Error::Orchestrator(format!("failed to parse task selection: {e}")))?;
-
-        // Clean up the selection file
-        let _ = std::fs::remove_file(&path);
-
-        Ok(selection.id)
+    /// Append a row for this round to the review comment's history and return it,
+    /// so the fix summary can be filled in once the fix agent has run.
+    fn record_review_round(
+        &self,
+        history_rows: &mut Vec<String>,
+        previous: &[ReviewFinding],
+        findings: &[ReviewFinding],
+        verdict: &Verdict,
+    ) -> ReviewRound {
+        let (new_findings, resolved_findings) = findings_delta(previous, findings);
+        let entry = ReviewRound {
+            round: history_rows.len() + 1,
+            verdict: verdict.to_string(),
+            new_findings,
+            resolved_findings,
+            open_findings: findings.len(),
+            fix_summary: None,
+        };
+        history_rows.push(entry.row());
+        entry
     }
 
-    fn initial_task_vars(&self, task: &Task, worktree: &WorktreeInfo) -> HashMap<String, String> {
-        let mut vars = build_task_vars(
+    /// Publish the review to the configured `review_target`. Comments carry the
+    /// findings under `summary`, followed by the collapsible round history, and are
+    /// upserted on the PR or on `issue_number`. Skipped without a PR or in dry-run
+    /// mode.
+    fn upsert_review_comment(
+        &self,
+        pr_number: Option<u64>,
+        issue_number: Option<u64>,
+        findings: &[ReviewFinding],
+        summary: &str,
+        history_rows: &[String],
+    ) {
+        let Some(pr_num) = pr_number else {
+            return;
+        };
+        let skipped = self.config.skipped_review_phases();
+        let summary = if skipped.is_empty() {
+            summary.to_string()
+        } else {
+            format!(
+                "{summary}\n\n_Review phases skipped for this run: {}._",
+                skipped
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+        let summary = summary.as_str();
+        if self.config.review_target == ReviewTarget::CheckRun {
+            let summary = render_findings_for_github_with(
+                findings,
+                summary,
+                self.config.finding_template.as_deref(),
+            );
+            let check = review_check(findings, &summary);
+            if let Err(e) = self.submission.publish_review_check(pr_num, &check) {
+                warn!(error = %e, "failed to publish review check run");
+            }
+            return;
+        }
+        let target = match (self.config.review_target, issue_number) {
+            (ReviewTarget::IssueComment, Some(issue)) => issue,
+            (ReviewTarget::IssueComment, None) => {
+                warn!(
+                    pr_number = pr_num,
+                    "no source issue for the review comment — commenting on the PR"
+                );
+                pr_num
+            }
+            _ => pr_num,
+        };
+        let mut body = format!(
+            "{REVIEW_MARKER}\n{}",
+            render_findings_for_github_with(
+                findings,
+                summary,
+                self.config.finding_template.as_deref(),
+            ),
+        );
+        let history = render_history(history_rows);
+        if !history.is_empty() {
+            body.push_str("\n\n");
+            body.push_str(&history);
+        }
+        if let Err(e) = self.submission.upsert_review_comment(target, &body) {
+            warn!(error = %e, "failed to post review comment");
+        }
+    }
+
+    /// Write a round's findings to `findings_export`, if configured. Best-effort:
+    /// a file that cannot be written is logged, never fatal.
+    fn export_round_findings(&self, task_id: &str, round: u32, findings: &[ReviewFinding]) {
+        let Some(export) = &self.config.findings_export else {
+            return;
+        };
+        let path = self.repo_root.join(
+            export
+                .path
+                .replace("{task_id}", task_id)
+                .replace("{round}", &round.to_string()),
+        );
+        let contents = export_findings(export.format, task_id, round, findings);
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(&path, contents));
+        match written {
+            Ok(()) => info!(path = %path.display(), round, "review findings exported"),
+            Err(e) => warn!(path = %path.display(), error = %e, "failed to export review findings"),
+        }
+    }
+
+    /// Leave a review comment on the PR saying this run was not reviewed, so
+    /// nobody mistakes the missing findings for a clean review.
+    fn note_review_skipped(&self, pr_number: Option<u64>) {
+        let Some(pr_num) = pr_number else {
+            return;
+        };
+        let body = format!(
+            "{REVIEW_MARKER}\nReview skipped for this run (`--skip-review`). Run `rlph review {pr_num}` to review it."
+        );
+        if let Err(e) = self.submission.upsert_review_comment(pr_num, &body) {
+            warn!(error = %e, "failed to post review-skipped comment");
+        }
+    }
+
+    /// Await `fut` (a running `phase`), keeping a heartbeat comment on issue or PR
+    /// `thread` once it has run for `heartbeat_minutes`, and deleting it when done.
+    async fn heartbeat<T>(
+        &self,
+        phase: &str,
+        thread: Option<u64>,
+        fut: impl std::future::Future<Output = T>,
+    ) -> T {
+        let (Some(minutes), Some(number)) = (self.config.heartbeat_minutes, thread) else {
+            return fut.await;
+        };
+        let every = Duration::from_secs(minutes * 60);
+        let (output, beaten) = with_heartbeat(fut, every, |elapsed| {
+            let body = heartbeat_comment(phase, elapsed);
+            if let Err(e) = self.submission.upsert_heartbeat_comment(number, &body) {
+                warn!(phase, error = %e, "failed to post heartbeat comment");
+            }
+        })
+        .await;
+        if beaten && let Err(e) = self.submission.delete_heartbeat_comment(number) {
+            warn!(phase, error = %e, "failed to remove heartbeat comment");
+        }
+        output
+    }
+
+    /// Await `fut` (an agent running in worktree `dir`), stopping it once `dir`
+    /// grows past `max_worktree_disk_mb`. The worktree's size is recorded in the
+    /// run summary either way.
+    async fn disk_guard<T>(
+        &self,
+        dir: &Path,
+        fut: impl std::future::Future<Output = T>,
+    ) -> Result<T> {
+        let Some(limit_mb) = self.config.max_worktree_disk_mb else {
+            let output = fut.await;
+            self.run_recorder.worktree_disk(dir_size_mb(dir).await);
+            return Ok(output);
+        };
+        match with_disk_quota(fut, dir, limit_mb, QUOTA_CHECK_INTERVAL).await {
+            Ok(output) => {
+                self.run_recorder.worktree_disk(dir_size_mb(dir).await);
+                Ok(output)
+            }
+            Err(used_mb) => {
+                self.run_recorder.worktree_disk(used_mb);
+                Err(Error::Orchestrator(format!(
+                    "worktree {} used {used_mb} MB, over max_worktree_disk_mb = {limit_mb}; agent stopped",
+                    dir.display()
+                )))
+            }
+        }
+    }
+
+    /// Run the review-fix agent on `fix_instructions` and push the result, with
+    /// its commits credited to `attribution` when `[fix_trailers]` is on. Returns
+    /// `Ok(Err(reason))` when the fix agent's JSON could not be recovered.
+    async fn fix_and_push(
+        &self,
+        vars: &HashMap<String, String>,
+        fix_instructions: String,
+        worktree_info: &WorktreeInfo,
+        push_remote_branch: Option<&str>,
+        attribution: &FixAttribution,
+    ) -> Result<std::result::Result<FixOutput, String>> {
+        let mut fix_vars = vars.clone();
+        fix_vars.insert("fix_instructions".to_string(), fix_instructions);
+
+        let head_before = worktree_head(worktree_info);
+        let fix = match self.run_review_fix(&fix_vars, worktree_info).await? {
+            Ok(fix) => fix,
+            Err(e) => return Ok(Err(e)),
+        };
+        self.guard_protected_paths(vars, worktree_info).await?;
+        attribute_fix_commits(
+            &self.config.fix_trailers,
+            &worktree_info.path,
+            head_before.as_deref(),
+            attribution,
+        );
+
+        let push_result = if let Some(remote_branch) = push_remote_branch {
+            self.push_branch_to(worktree_info, remote_branch)
+        } else {
+            self.push_branch(worktree_info)
+        };
+        if let Err(e) = push_result {
+            warn!(error = %e, "failed to push review fixes");
+        }
+        Ok(Ok(fix))
+    }
+
+    /// Keep changes to `protected_paths` from being pushed: ask the review-fix agent
+    /// to revert them, then fail if any protected file still differs from the base.
+    async fn guard_protected_paths(
+        &self,
+        vars: &HashMap<String, String>,
+        worktree_info: &WorktreeInfo,
+    ) -> Result<()> {
+        let base_branch = vars.get("base_branch").unwrap_or(&self.config.base_branch);
+        let base = format!("origin/{base_branch}");
+        let changed = self.protected.changed(&worktree_info.path, &base)?;
+        if changed.is_empty() {
+            return Ok(());
+        }
+        warn!(paths = ?changed, "agent changed protected paths; asking fix agent to revert them");
+        let mut fix_vars = vars.clone();
+        fix_vars.insert(
+            "fix_instructions".to_string(),
+            revert_instructions(&changed, &base),
+        );
+        if let Err(reason) = self.run_review_fix(&fix_vars, worktree_info).await? {
+            warn!(reason, "protected path revert did not report cleanly");
+        }
+
+        let remaining = self.protected.changed(&worktree_info.path, &base)?;
+        if !remaining.is_empty() {
+            return Err(Error::Orchestrator(format!(
+                "refusing to push {}: protected paths changed: {}",
+                worktree_info.branch,
+                remaining.join(", ")
+            )));
+        }
+        info!("protected path changes reverted");
+        Ok(())
+    }
+
+    /// Report and record a joined review phase, surfacing its error if it failed.
+    fn finish_review_phase(
+        &self,
+        joined: std::result::Result<Result<ReviewPhaseOutput>, tokio::task::JoinError>,
+    ) -> Result<ReviewPhaseOutput> {
+        let output = joined.map_err(|e| Error::AgentRunner(e.to_string()))??;
+        self.state_mgr
+            .record_review_phase(&output.name, &output.stdout, output.from_command)?;
+        self.reporter.phase_complete(&output.name);
+        self.run_recorder.phase(
+            &format!("review:{}", output.name),
+            output.elapsed,
+            output.usage,
+        );
+        Ok(output)
+    }
+
+    /// Run the review-fix step with the given vars (which must include
+    /// `fix_instructions`). Returns `Ok(Err(reason))` when the fix agent's JSON
+    /// could not be recovered or its report disagrees with what it changed, so
+    /// callers can decide whether to retry.
+    async fn run_review_fix(
+        &self,
+        fix_vars: &HashMap<String, String>,
+        worktree_info: &WorktreeInfo,
+    ) -> Result<std::result::Result<FixOutput, String>> {
+        let fix_config = &self.config.review_fix;
+        let fix_runner = self.review_factory.create_step_runner(
+            fix_config,
+            self.config.agent_timeout_retries,
+            "fix",
+        );
+
+        let fix_prompt =
+            self.prompt_engine
+                .render_step(PromptPhase::ReviewFix, &fix_config.prompt, fix_vars)?;
+        let before_fix = WorktreeSnapshot::capture(&worktree_info.path)?;
+        self.record_environment(
+            "fix",
+            &worktree_info.path,
+            AgentSettings::from_step(fix_config),
+            &fix_prompt,
+        );
+        let fix_started = Instant::now();
+        // Review rounds put `pr_number` only in the aggregate's vars, so outside
+        // PR feedback the heartbeat goes on the issue.
+        let thread = ["pr_number", "issue_number"]
+            .iter()
+            .find_map(|key| fix_vars.get(*key)?.parse::<u64>().ok());
+        let fix_result = self
+            .disk_guard(
+                &worktree_info.path,
+                self.heartbeat(
+                    "fix",
+                    thread,
+                    fix_runner.run(Phase::ReviewFix, &fix_prompt, &worktree_info.path),
+                ),
+            )
+            .await??;
+        self.run_recorder
+            .phase("fix", fix_started.elapsed(), fix_result.usage);
+
+        let fix_output = match parse_fix_output(&fix_result.stdout) {
+            Ok(fix_output) => {
+                info!(
+                    status = ?fix_output.status,
+                    summary = fix_output.summary,
+                    files_changed = ?fix_output.files_changed,
+                    "fix agent complete"
+                );
+                fix_output
+            }
+            Err(e) => {
+                // Attempt session resume with correction prompt for fix output
+                let recovered = retry_with_correction(
+                    &self.correction_runner,
+                    fix_result.session_id.as_deref(),
+                    fix_config.runner,
+                    &fix_config.agent_binary,
+                    fix_config.agent_model.as_deref(),
+                    fix_config.agent_effort.as_deref(),
+                    fix_config.agent_variant.as_deref(),
+                    fix_config.permission_mode,
+                    &fix_config.agent_identity,
+                    fix_config.agent_timeout,
+                    SchemaName::Fix,
+                    &e.to_string(),
+                    &worktree_info.path,
+                    parse_fix_output,
+                )
+                .await;
+                match recovered {
+                    Some(fix_output) => {
+                        info!(
+                            status = ?fix_output.status,
+                            summary = fix_output.summary,
+                            files_changed = ?fix_output.files_changed,
+                            "fix agent complete (after correction)"
+                        );
+                        fix_output
+                    }
+                    None => {
+                        warn!(error = %e, "fix agent JSON correction failed");
+                        return Ok(Err(format!("fix agent malformed JSON: {e}")));
+                    }
+                }
+            }
+        };
+
+        self.verify_fix_report(
+            fix_output,
+            &before_fix,
+            fix_result.session_id.as_deref(),
+            worktree_info,
+        )
+        .await
+    }
+
+    /// Cross-check the fix agent's `files_changed` against the worktree. On a
+    /// mismatch the agent's session is resumed once to finish or correct its
+    /// report; if that does not reconcile them, the mismatch is returned as
+    /// `Ok(Err(reason))`.
+    async fn verify_fix_report(
+        &self,
+        fix_output: FixOutput,
+        before_fix: &WorktreeSnapshot,
+        session_id: Option<&str>,
+        worktree_info: &WorktreeInfo,
+    ) -> Result<std::result::Result<FixOutput, String>> {
+        let changed = before_fix.changed_files_since(&worktree_info.path)?;
+        let Some(mut mismatch) = fix_report_mismatch(&fix_output, &changed) else {
+            return Ok(Ok(fix_output));
+        };
+        warn!(mismatch, "fix agent report disagrees with worktree changes");
+
+        let fix_config = &self.config.review_fix;
+        if let Some(session_id) = session_id {
+            let corrected = self
+                .correction_runner
+                .resume(
+                    fix_config.runner,
+                    &fix_config.agent_binary,
+                    fix_config.agent_model.as_deref(),
+                    fix_config.agent_effort.as_deref(),
+                    fix_config.agent_variant.as_deref(),
+                    fix_config.permission_mode,
+                    &fix_config.agent_identity,
+                    session_id,
+                    &fix_report_correction_prompt(&mismatch),
+                    &worktree_info.path,
+                    fix_config.agent_timeout.map(Duration::from_secs),
+                )
+                .await;
+            match corrected.map(|result| parse_fix_output(&result.stdout)) {
+                Ok(Ok(corrected)) => {
+                    let changed = before_fix.changed_files_since(&worktree_info.path)?;
+                    match fix_report_mismatch(&corrected, &changed) {
+                        None => {
+                            info!("fix agent report reconciled with worktree changes");
+                            return Ok(Ok(corrected));
+                        }
+                        Some(still) => mismatch = still,
+                    }
+                }
+                Ok(Err(e)) => warn!(error = %e, "corrected fix report is not valid JSON"),
+                Err(e) => warn!(error = %e, "fix report correction resume failed"),
+            }
+        }
+        Ok(Err(format!(
+            "fix agent report disagrees with worktree: {mismatch}"
+        )))
+    }
+
+    /// Post inline suggested changes for findings that carry a `suggested_patch`.
+    /// Best-effort: failures are logged and never fail the review.
+    fn post_suggestions(&self, findings: &[ReviewFinding], pr_number: u64) {
+        let suggestions: Vec<SuggestionComment> = findings
+            .iter()
+            .filter_map(suggestion_from_finding)
+            .collect();
+        if suggestions.is_empty() {
+            return;
+        }
+        match self.submission.post_suggestions(pr_number, &suggestions) {
+            Ok(count) => info!(count, "posted suggested changes"),
+            Err(e) => warn!(error = %e, "failed to post suggested changes"),
+        }
+    }
+
+    /// File the non-critical findings left on an approved review as labelled
+    /// follow-up issues, skipping any whose title hash matches an open follow-up.
+    /// Best-effort: failures are logged and never fail the review.
+    fn file_followup_issues(
+        &self,
+        findings: &[ReviewFinding],
+        pr_number: u64,
+        pr_url: Option<&str>,
+    ) {
+        let deferred: Vec<&ReviewFinding> = findings
+            .iter()
+            .filter(|f| f.severity != Severity::Critical)
+            .collect();
+        if deferred.is_empty() {
+            return;
+        }
+
+        let label = &self.config.followup_label;
+        let mut open_hashes: HashSet<String> = match self.source.fetch_open_tasks_with_label(label)
+        {
+            Ok(tasks) => tasks
+                .iter()
+                .map(|t| followup_title_hash(&t.title))
+                .collect(),
+            Err(e) => {
+                warn!(error = %e, "failed to list open follow-ups, not filing");
+                return;
+            }
+        };
+
+        for finding in deferred {
+            let title = followup_title(finding);
+            if !open_hashes.insert(followup_title_hash(&title)) {
+                info!(title, "follow-up already open, skipping");
+                continue;
+            }
+            let body = format_followup_body(finding, pr_number, pr_url);
+            match self
+                .source
+                .create_task(&title, &body, std::slice::from_ref(label))
+            {
+                Ok(task) => info!(id = task.id, title, "filed follow-up issue"),
+                Err(e) => warn!(error = %e, title, "failed to file follow-up issue"),
+            }
+        }
+    }
+
+    /// Closed task IDs for dependency resolution, kept in the state cache. They
+    /// are fetched in full when the cache is missing, was filled for another
+    /// source or label, or is older than `closed_tasks_ttl_seconds`; otherwise
+    /// only tasks closed since the last sync are fetched and added. The full
+    /// refetch also drops tasks that were reopened.
+    fn closed_task_ids(&self) -> Result<HashSet<u64>> {
+        let scope = format!("{}:{}", self.config.source, self.config.label);
+        let now = unix_now();
+        let cached = self.state_mgr.closed_task_cache().filter(|cache| {
+            cache.scope == scope
+                && now.saturating_sub(cache.full_sync_at) < self.config.closed_tasks_ttl_seconds
+        });
+
+        let (ids, full_sync_at) = match cached {
+            Some(cache) => {
+                let since = cache
+                    .synced_at
+                    .saturating_sub(CLOSED_TASKS_SYNC_OVERLAP_SECS);
+                let mut ids: HashSet<u64> = cache.ids.into_iter().collect();
+                let recent = self.source.fetch_closed_task_ids_since(since)?;
+                info!(
+                    cached = ids.len(),
+                    recent = recent.len(),
+                    "updated cached closed task ids"
+                );
+                ids.extend(recent);
+                (ids, cache.full_sync_at)
+            }
+            None => {
+                let ids = self.source.fetch_closed_task_ids()?;
+                info!(count = ids.len(), "fetched all closed task ids");
+                (ids, now)
+            }
+        };
+
+        let mut sorted: Vec<u64> = ids.iter().copied().collect();
+        sorted.sort_unstable();
+        let cache = ClosedTaskCache {
+            scope,
+            ids: sorted,
+            full_sync_at,
+            synced_at: now,
+        };
+        if let Err(e) = self.state_mgr.set_closed_task_cache(cache) {
+            warn!(error = %e, "failed to cache closed task ids");
+        }
+        Ok(ids)
+    }
+
+    /// Drop tasks rlph already delivered through a PR that is still open or
+    /// merged, unless `--redo` is set.
+    fn filter_delivered(&self, tasks: Vec<Task>) -> Vec<Task> {
+        if self.config.redo {
+            return tasks;
+        }
+        tasks
+            .into_iter()
+            .filter(|task| {
+                let task_id = format!("gh-{}", task.id);
+                match self.live_delivery(&task_id) {
+                    Some((delivered, state)) => {
+                        self.note_delivered_skip(&task.id, &task_id, &delivered, state);
+                        false
+                    }
+                    None => true,
+                }
+            })
+            .collect()
+    }
+
+    /// The recorded delivery of `task_id` and its PR's state, when that PR is
+    /// still open or merged. A delivery whose PR was closed unmerged is
+    /// forgotten. When the state cannot be read the delivery counts as live.
+    fn live_delivery(&self, task_id: &str) -> Option<(DeliveredTask, PrState)> {
+        let delivered = self.state_mgr.delivery(task_id)?;
+        match self.submission.pr_state(delivered.pr_number) {
+            Ok(PrState::Closed) => {
+                info!(
+                    task_id,
+                    pr = delivered.pr_number,
+                    "delivered PR was closed unmerged, task can be redone"
+                );
+                if let Err(e) = self.state_mgr.forget_delivery(task_id) {
+                    warn!(task_id, error = %e, "failed to forget delivery");
+                }
+                None
+            }
+            Ok(state) => Some((delivered, state)),
+            Err(e) => {
+                warn!(task_id, pr = delivered.pr_number, error = %e, "failed to check delivered PR");
+                Some((delivered, PrState::Open))
+            }
+        }
+    }
+
+    /// Log why a delivered task is skipped and, once per delivery, explain it on
+    /// the issue.
+    fn note_delivered_skip(
+        &self,
+        source_id: &str,
+        task_id: &str,
+        delivered: &DeliveredTask,
+        state: PrState,
+    ) {
+        let state = match state {
+            PrState::Merged => "merged",
+            _ => "still open",
+        };
+        warn!(
+            task_id,
+            pr = delivered.pr_number,
+            state,
+            "task was already delivered, skipping (pass --redo to work on it again)"
+        );
+        if delivered.skip_noted {
+            return;
+        }
+        let pr = if delivered.url.is_empty() {
+            format!("#{}", delivered.pr_number)
+        } else {
+            delivered.url.clone()
+        };
+        let body = format!(
+            "rlph already delivered this task in {pr}, which is {state}, so it will not work on it again. Run rlph with `--redo` to redo it anyway."
+        );
+        if let Err(e) = self.source.add_comment(source_id, &body) {
+            warn!(task_id, error = %e, "failed to comment on delivered task");
+            return;
+        }
+        if !self.config.dry_run
+            && let Err(e) = self.state_mgr.mark_delivery_skip_noted(task_id)
+        {
+            warn!(task_id, error = %e, "failed to record delivered-task comment");
+        }
+    }
+
+    /// Best-effort: remember the PR that delivered `task_id` along with the
+    /// branch head, so re-adding the label later does not redo it.
+    fn record_delivery(&self, task_id: &str, worktree_info: &WorktreeInfo) {
+        let Some((number, pr)) = self
+            .state_mgr
+            .tracked_prs()
+            .into_iter()
+            .filter(|(_, pr)| pr.task_id == task_id)
+            .max_by_key(|(number, _)| *number)
+        else {
+            return;
+        };
+        let head_sha = git_in_dir(&worktree_info.path, &["rev-parse", "HEAD"])
+            .map(|out| out.trim().to_string())
+            .unwrap_or_default();
+        if let Err(e) = self
+            .state_mgr
+            .record_delivery(task_id, number, &pr.url, &head_sha)
+        {
+            warn!(task_id, error = %e, "failed to record delivery");
+        }
+    }
+
+    /// Drop tasks labeled as failed and tasks still waiting out their retry backoff.
+    /// A task that gave up but no longer carries the failed label was re-queued by a
+    /// human, so its retry entry is reset.
+    fn filter_retry_queue(&self, tasks: Vec<Task>) -> Vec<Task> {
+        let failed_label = &self.config.failed_label;
+        tasks
+            .into_iter()
+            .filter(|task| {
+                if task
+                    .labels
+                    .iter()
+                    .any(|l| l.eq_ignore_ascii_case(failed_label))
+                {
+                    info!(
+                        task_id = task.id,
+                        label = failed_label,
+                        "skipping failed task"
+                    );
+                    return false;
+                }
+                let task_id = format!("gh-{}", task.id);
+                let Some(entry) = self.state_mgr.retry_entry(&task_id) else {
+                    return true;
+                };
+                if entry.gave_up {
+                    info!(task_id, "failed label removed, re-queuing task");
+                    if let Err(e) = self.state_mgr.clear_retry(&task_id) {
+                        warn!(error = %e, task_id, "failed to clear retry entry");
+                    }
+                    return true;
+                }
+                if entry.attempts >= self.config.max_task_attempts {
+                    info!(
+                        task_id,
+                        attempts = entry.attempts,
+                        "skipping task out of attempts"
+                    );
+                    return false;
+                }
+                if !entry.is_due() {
+                    info!(
+                        task_id,
+                        attempts = entry.attempts,
+                        next_retry_at = entry.next_retry_at,
+                        "skipping task waiting for retry"
+                    );
+                    return false;
+                }
+                true
+            })
+            .collect()
+    }
+
+    /// Record a failed iteration in the retry queue; once `max_task_attempts` is
+    /// reached, label the task with `failed_label` so it leaves the queue.
+    fn schedule_retry(&self, task: &Task, task_id: &str, error: &Error) {
+        let entry = match self.state_mgr.schedule_retry(
+            task_id,
+            &error.to_string(),
+            self.config.retry_backoff_seconds,
+        ) {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!(error = %e, "failed to schedule retry");
+                return;
+            }
+        };
+        if entry.attempts < self.config.max_task_attempts {
+            info!(
+                task_id,
+                attempts = entry.attempts,
+                next_retry_at = entry.next_retry_at,
+                "task scheduled for retry"
+            );
+            return;
+        }
+        warn!(
+            task_id,
+            attempts = entry.attempts,
+            label = self.config.failed_label,
+            "task out of attempts, giving up"
+        );
+        match self.source.add_label(&task.id, &self.config.failed_label) {
+            Ok(()) if self.config.dry_run => {}
+            Ok(()) => {
+                if let Err(e) = self.state_mgr.mark_retry_gave_up(task_id) {
+                    warn!(error = %e, "failed to record retry give-up");
+                }
+            }
+            Err(e) => warn!(error = %e, task_id, "failed to add failed label"),
+        }
+    }
+
+    /// Drop tasks whose recorded estimate (for unchanged task content) exceeds
+    /// `max_complexity`, so the choose agent never sees them.
+    fn filter_over_complexity(&self, tasks: Vec<Task>) -> Vec<Task> {
+        let Some(max) = self.config.max_complexity else {
+            return tasks;
+        };
+        tasks
+            .into_iter()
+            .filter(|task| {
+                let Some(cached) = self.state_mgr.get_estimate(&format!("gh-{}", task.id)) else {
+                    return true;
+                };
+                if cached.fingerprint != task_fingerprint(task) {
+                    return true;
+                }
+                match cached.complexity.parse::<Complexity>() {
+                    Ok(complexity) if complexity > max => {
+                        info!(
+                            task_id = task.id,
+                            %complexity,
+                            "skipping task above max_complexity (cached estimate)"
+                        );
+                        false
+                    }
+                    _ => true,
+                }
+            })
+            .collect()
+    }
+
+    /// Hand the implement prompt to an agent session attached to the terminal and
+    /// wait for it to exit. The session must leave its work committed: uncommitted
+    /// changes or a branch without new commits fail the iteration, keeping the
+    /// worktree.
+    async fn run_interactive_implement(
+        &self,
+        prompt: &str,
+        vars: &HashMap<String, String>,
+        worktree_info: &WorktreeInfo,
+    ) -> Result<RunResult> {
+        let (binary, args) = interactive_command(&self.config, prompt);
+        info!(worktree = %worktree_info.path.display(), "starting interactive implement session");
+        let exit_code = run_interactive(&binary, &args, Some(&worktree_info.path)).await?;
+        if exit_code != 0 {
+            return Err(Error::AgentRunner(format!(
+                "interactive session exited with status {exit_code}"
+            )));
+        }
+
+        let dir = &worktree_info.path;
+        let status = git_in_dir(dir, &["status", "--porcelain"]).map_err(Error::Orchestrator)?;
+        if !status.trim().is_empty() {
+            return Err(Error::Orchestrator(format!(
+                "interactive session left uncommitted changes in {}",
+                dir.display()
+            )));
+        }
+        let base_branch = vars.get("base_branch").unwrap_or(&self.config.base_branch);
+        let range = format!("origin/{base_branch}..HEAD");
+        let commits =
+            git_in_dir(dir, &["rev-list", "--count", &range]).map_err(Error::Orchestrator)?;
+        if commits.trim() == "0" {
+            return Err(Error::Orchestrator(format!(
+                "interactive session made no commits on {}",
+                worktree_info.branch
+            )));
+        }
+        info!(commits = commits.trim(), "interactive session finished");
+
+        Ok(RunResult {
+            exit_code,
+            stdout: String::new(),
+            stderr: String::new(),
+            session_id: None,
+            usage: TokenUsage::default(),
+        })
+    }
+
+    /// Add the environment `phase` starts in to the run summary.
+    fn record_environment(&self, phase: &str, dir: &Path, agent: AgentSettings<'_>, prompt: &str) {
+        self.run_recorder
+            .environment(PhaseEnvironment::capture(phase, dir, agent, prompt));
+    }
+
+    /// Send the implement runner a one-word prompt so expired auth or a model the
+    /// account cannot use fails the iteration before the task is claimed.
+    async fn probe_runner(&self) -> Result<()> {
+        let started = Instant::now();
+        let result = tokio::time::timeout(
+            PROBE_TIMEOUT,
+            self.runner.run(Phase::Probe, PROBE_PROMPT, &self.repo_root),
+        )
+        .await
+        .map_err(|_| {
+            Error::AgentRunner(format!(
+                "runner probe failed: no reply within {}s",
+                PROBE_TIMEOUT.as_secs()
+            ))
+        })?
+        .map_err(|e| Error::AgentRunner(format!("runner probe failed: {e}")))?;
+        self.run_recorder
+            .phase("probe", started.elapsed(), result.usage);
+        info!(
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "runner probe passed"
+        );
+        Ok(())
+    }
+
+    /// Run the estimation step for a task, reusing the recorded estimate when the
+    /// task content is unchanged. Returns `None` when estimation is disabled or the
+    /// agent's output could not be parsed — estimation never blocks a run.
+    async fn estimate_task(&self, task: &Task, task_id: &str) -> Result<Option<EstimateOutput>> {
+        let Some(est_config) = &self.config.estimate else {
+            return Ok(None);
+        };
+
+        let fingerprint = task_fingerprint(task);
+        if let Some(cached) = self.state_mgr.get_estimate(task_id)
+            && cached.fingerprint == fingerprint
+            && let Ok(complexity) = cached.complexity.parse()
+        {
+            info!(task_id, %complexity, "using recorded estimate");
+            return Ok(Some(EstimateOutput {
+                complexity,
+                risk_notes: cached.risk_notes,
+            }));
+        }
+
+        info!("running estimate phase");
+        let vars = HashMap::from([
+            ("issue_title".to_string(), task.title.clone()),
+            ("issue_body".to_string(), task.body.clone()),
+            ("issue_number".to_string(), task.id.clone()),
+            ("issue_url".to_string(), task.url.clone()),
+            (
+                "repo_path".to_string(),
+                self.repo_root.display().to_string(),
+            ),
+        ]);
+        let prompt =
+            self.prompt_engine
+                .render_step(PromptPhase::Estimate, &est_config.prompt, &vars)?;
+        let runner = self.review_factory.create_step_runner(
+            est_config,
+            self.config.agent_timeout_retries,
+            "estimate",
+        );
+        self.record_environment(
+            "estimate",
+            &self.repo_root,
+            AgentSettings::from_step(est_config),
+            &prompt,
+        );
+        let started = Instant::now();
+        let result = runner
+            .run(Phase::Estimate, &prompt, &self.repo_root)
+            .await?;
+        self.run_recorder
+            .phase("estimate", started.elapsed(), result.usage);
+
+        let estimate = match parse_estimate_output(&result.stdout) {
+            Ok(estimate) => Some(estimate),
+            Err(e) => {
+                let recovered = retry_with_correction(
+                    &self.correction_runner,
+                    result.session_id.as_deref(),
+                    est_config.runner,
+                    &est_config.agent_binary,
+                    est_config.agent_model.as_deref(),
+                    est_config.agent_effort.as_deref(),
+                    est_config.agent_variant.as_deref(),
+                    est_config.permission_mode,
+                    &est_config.agent_identity,
+                    est_config.agent_timeout,
+                    SchemaName::Estimate,
+                    &e.to_string(),
+                    &self.repo_root,
+                    parse_estimate_output,
+                )
+                .await;
+                if recovered.is_none() {
+                    warn!(error = %e, "estimate output unparseable, continuing without estimate");
+                }
+                recovered
+            }
+        };
+
+        if let Some(estimate) = &estimate {
+            info!(
+                task_id,
+                complexity = %estimate.complexity,
+                "estimate complete"
+            );
+            self.state_mgr.set_estimate(
+                task_id,
+                &estimate.complexity.to_string(),
+                &estimate.risk_notes,
+                &fingerprint,
+            )?;
+        }
+        Ok(estimate)
+    }
+
+    /// Clean up after tracked PRs that have been merged or closed: remove their
+    /// worktrees and state, delete their branches (remotely too when
+    /// `cleanup.delete_remote_branches` is set), and release the tasks of PRs
+    /// closed without merging back to the eligible queue. Returns the number of
+    /// PRs cleaned up; open PRs and PRs whose state cannot be read are kept.
+    /// Tasks of merged PRs are marked done in sources that track it.
+    pub fn cleanup_finished_prs(&self) -> Result<usize> {
+        let mut cleaned = 0;
+        for (number, pr) in self.state_mgr.tracked_prs() {
+            let state = match self.submission.pr_state(number) {
+                Ok(PrState::Open) => continue,
+                Ok(state) => state,
+                Err(e) => {
+                    warn!(pr = number, error = %e, "failed to check PR state");
+                    continue;
+                }
+            };
+            info!(
+                pr = number,
+                ?state,
+                task_id = pr.task_id,
+                "cleaning up finished PR"
+            );
+
+            let issue_number = parse_issue_number(&pr.task_id).ok();
+            self.remove_pr_checkout(number, &pr)?;
+
+            if state == PrState::Merged
+                && let Some(n) = issue_number
+                && let Err(e) = self.source.mark_done(&n.to_string())
+            {
+                warn!(pr = number, error = %e, "failed to mark task done");
+            }
+            if state == PrState::Closed && self.config.cleanup.requeue_closed {
+                match issue_number {
+                    Some(n) => {
+                        if let Err(e) = self.source.release_task(&n.to_string()) {
+                            warn!(pr = number, error = %e, "failed to requeue task");
+                        }
+                    }
+                    None => warn!(task_id = pr.task_id, "cannot requeue task"),
+                }
+            }
+            self.state_mgr.untrack_pr(number)?;
+            cleaned += 1;
+        }
+        Ok(cleaned)
+    }
+
+    /// Remove a tracked PR's worktree, mapping, and branch (remotely too when
+    /// `cleanup.delete_remote_branches` is set). Failures to remove the worktree or
+    /// branch are logged.
+    fn remove_pr_checkout(&self, number: u64, pr: &TrackedPr) -> Result<()> {
+        let worktree = match self.state_mgr.get_worktree_path(&pr.task_id) {
+            Some(path) => Some(PathBuf::from(path)),
+            None => parse_issue_number(&pr.task_id)
+                .ok()
+                .and_then(|n| self.worktree_mgr.find_existing(n).ok().flatten())
+                .map(|info| info.path),
+        };
+        if let Some(path) = worktree.filter(|p| p.exists())
+            && let Err(e) = self.worktree_mgr.remove(&path)
+        {
+            warn!(pr = number, error = %e, "failed to remove worktree");
+        }
+        if let Err(e) = self
+            .worktree_mgr
+            .delete_branch(&pr.branch, self.config.cleanup.delete_remote_branches)
+        {
+            warn!(pr = number, error = %e, "failed to delete branch");
+        }
+        self.state_mgr.remove_worktree_mapping(&pr.task_id)
+    }
+
+    /// Close open tracked PRs that `abandon_after_days` gives up on: those whose
+    /// issue was closed or lost the configured label (unless tasks come from a
+    /// `[github.project]` board), and those left unmerged for
+    /// longer than the limit. Each is closed with a comment, its checkout removed,
+    /// and its issue released. An issue that is still open and labeled also gets
+    /// `failed_label`, so the task is not picked up again until a human removes it.
+    /// Returns the number of PRs abandoned.
+    pub fn abandon_stale_prs(&self) -> Result<usize> {
+        let Some(days) = self.config.abandon_after_days else {
+            return Ok(0);
+        };
+        let now = unix_now();
+        let mut closed_ids: Option<HashSet<u64>> = None;
+        let mut abandoned = 0;
+        for (number, pr) in self.state_mgr.tracked_prs() {
+            match self.submission.pr_state(number) {
+                Ok(PrState::Open) => {}
+                Ok(_) => continue,
+                Err(e) => {
+                    warn!(pr = number, error = %e, "failed to check PR state");
+                    continue;
+                }
+            }
+            if pr.opened_at == 0 {
+                // Tracked before opening times were recorded: start the clock now.
+                self.state_mgr.set_pr_opened_at(number, now)?;
+                continue;
+            }
+
+            let issue_number = parse_issue_number(&pr.task_id).ok();
+            let mut issue_open = true;
+            let reason = if let Some(n) = issue_number
+                && closed_ids
+                    .get_or_insert_with(|| {
+                        self.closed_task_ids().unwrap_or_else(|e| {
+                            warn!(error = %e, "failed to fetch closed tasks");
+                            HashSet::new()
+                        })
+                    })
+                    .contains(&n)
+            {
+                issue_open = false;
+                Some(format!("issue #{n} was closed"))
+            } else if let Some(n) = issue_number
+                && self.config.github_project.is_none()
+                && let Ok(task) = self.source.get_task_details(&n.to_string())
+                && !task
+                    .labels
+                    .iter()
+                    .any(|l| l.eq_ignore_ascii_case(&self.config.label))
+            {
+                issue_open = false;
+                Some(format!(
+                    "issue #{n} no longer has the `{}` label",
+                    self.config.label
+                ))
+            } else if now.saturating_sub(pr.opened_at) > days * 86_400 {
+                Some(format!(
+                    "it has been open without merging for over {days} days"
+                ))
+            } else {
+                None
+            };
+            let Some(reason) = reason else {
+                continue;
+            };
+
+            info!(pr = number, task_id = pr.task_id, reason, "abandoning PR");
+            let comment = format!("rlph is abandoning this PR: {reason}.");
+            if let Err(e) = self.submission.close_pr(number, &comment) {
+                warn!(pr = number, error = %e, "failed to close PR");
+                continue;
+            }
+            self.remove_pr_checkout(number, &pr)?;
+            if let Some(n) = issue_number {
+                let id = n.to_string();
+                if let Err(e) = self.source.release_task(&id) {
+                    warn!(pr = number, error = %e, "failed to release abandoned task");
+                }
+                if issue_open && let Err(e) = self.source.add_label(&id, &self.config.failed_label)
+                {
+                    warn!(pr = number, error = %e, "failed to label abandoned task");
+                }
+            }
+            self.state_mgr.untrack_pr(number)?;
+            abandoned += 1;
+        }
+        Ok(abandoned)
+    }
+
+    /// Best-effort: comment the PR link back onto the issue and, where the source
+    /// supports it, attach the link. Failures are logged, never fatal.
+    fn link_pr_to_issue(&self, task: &Task, pr: &SubmitResult, branch: &str, summary: &str) {
+        let body = format_issue_pr_comment(&pr.url, branch, summary);
+        if let Err(e) = self.source.add_comment(&task.id, &body) {
+            warn!(task_id = task.id, error = %e, "failed to comment PR link on issue");
+        }
+        let title = match pr.number {
+            Some(n) => format!("PR #{n}"),
+            None => "Pull request".to_string(),
+        };
+        if let Err(e) = self.source.attach_link(&task.id, &pr.url, &title) {
+            warn!(task_id = task.id, error = %e, "failed to attach PR link to issue");
+        }
+    }
+
+    /// Best-effort: an untracked PR only misses its post-merge cleanup.
+    fn track_pr(&self, pr_number: u64, task_id: &str, branch: &str, url: &str) {
+        if let Err(e) = self.state_mgr.track_pr(
+            pr_number,
+            task_id,
+            branch,
+            url,
+            self.prompt_engine.version(),
+        ) {
+            warn!(pr = pr_number, error = %e, "failed to track PR");
+        }
+    }
+
+    /// Take over a human-authored PR: record it in state, then check out its
+    /// branch and run the full review loop on it, fix rounds included. The PR's
+    /// linked issue, if its body references one, is marked in review. Later
+    /// iterations review the PR again whenever its head moves, and with
+    /// `pr_feedback` they address collaborator comments on it.
+    pub async fn adopt_pr(&self, pr: &PrContext) -> Result<()> {
+        let task_id = match pr.linked_issue_number {
+            Some(n) => format!("gh-{n}"),
+            None => format!("pr-{}", pr.number),
+        };
+        if self.config.dry_run {
+            info!(pr = pr.number, "dry run — not recording adopted PR");
+        } else {
+            self.state_mgr.adopt_pr(
+                pr.number,
+                AdoptedPr {
+                    task_id: task_id.clone(),
+                    branch: pr.head_branch.clone(),
+                    url: pr.url.clone(),
+                    reviewed_sha: String::new(),
+                },
+            )?;
+            info!(pr = pr.number, task_id, "adopted PR");
+        }
+        let result = self.review_adopted_pr(pr, &task_id).await;
+        self.flush_dry_run();
+        result
+    }
+
+    /// Review adopted PRs whose head moved since their last review, and release
+    /// the ones that were merged or closed. Returns the number of PRs reviewed.
+    pub async fn process_adopted_prs(&self) -> Result<usize> {
+        let adopted = self.state_mgr.adopted_prs();
+        if adopted.is_empty() {
+            return Ok(0);
+        }
+        let open = self.submission.list_open_prs()?;
+        let mut reviewed = 0;
+
+        for (number, record) in adopted {
+            let Some(pr) = open.iter().find(|pr| pr.number == number) else {
+                // The open PR list is capped, so confirm before letting go.
+                match self.submission.pr_state(number) {
+                    Ok(PrState::Open) => {}
+                    Ok(state) => {
+                        info!(pr = number, ?state, "adopted PR finished, releasing it");
+                        self.state_mgr.release_adopted_pr(number)?;
+                    }
+                    Err(e) => warn!(pr = number, error = %e, "failed to check PR state"),
+                }
+                continue;
+            };
+            if pr
+                .head_sha
+                .as_deref()
+                .is_none_or(|sha| sha == record.reviewed_sha)
+            {
+                continue;
+            }
+
+            info!(pr = number, "adopted PR has new commits, reviewing");
+            match self.review_adopted_pr(pr, &record.task_id).await {
+                Ok(()) => reviewed += 1,
+                Err(e) => warn!(pr = number, error = %e, "adopted PR review failed"),
+            }
+        }
+
+        Ok(reviewed)
+    }
+
+    /// Run the review loop on an adopted PR's current head and remember the commit
+    /// it finished on. A review that ran out of rounds has still posted its
+    /// findings, so it waits for the next push like an approved one.
+    async fn review_adopted_pr(&self, pr: &PrContext, task_id: &str) -> Result<()> {
+        let worktree_info = self
+            .worktree_mgr
+            .create_for_branch(pr.number, &pr.head_branch)?;
+        let task = self.pr_task(pr);
+        let mut vars = self.initial_task_vars(&task, &worktree_info);
+        vars.insert("pr_number".to_string(), pr.number.to_string());
+        vars.insert("pr_branch".to_string(), pr.head_branch.clone());
+        vars.insert("pr_url".to_string(), pr.url.clone());
+        if let Some(base) = &pr.base_branch {
+            vars.insert("base_branch".to_string(), base.clone());
+        }
+
+        self.state_mgr.set_current_task(
+            task_id,
+            "review",
+            &worktree_info.path.display().to_string(),
+        )?;
+        if pr.linked_issue_number.is_some()
+            && let Err(e) = self.source.mark_in_review(&task.id)
+        {
+            warn!(task_id = task.id, error = %e, "failed to mark linked issue in review");
+        }
+        self.flush_source();
+
+        let result = self
+            .run_review_pipeline(
+                task_id,
+                &vars,
+                &worktree_info,
+                Some(pr.number),
+                Some(&pr.head_branch),
+                false,
+                None,
+            )
+            .await;
+
+        if matches!(result, Ok(_) | Err(Error::ReviewExhausted(_))) {
+            match git_in_dir(&worktree_info.path, &["rev-parse", "HEAD"]) {
+                Ok(sha) => self
+                    .state_mgr
+                    .set_adopted_reviewed_sha(pr.number, sha.trim())?,
+                Err(e) => warn!(
+                    pr = pr.number,
+                    error = e.trim(),
+                    "failed to read reviewed head"
+                ),
+            }
+        }
+        match &result {
+            Ok(_) => self.state_mgr.complete_current_task()?,
+            Err(_) => {
+                let _ = self.state_mgr.clear_review_checkpoint();
+                self.state_mgr.clear_current_task()?;
+            }
+        }
+        if let Err(e) = self.worktree_mgr.remove(&worktree_info.path) {
+            warn!(error = %e, "failed to clean up adopted PR worktree");
+        }
+        let _ = self.state_mgr.remove_worktree_mapping(task_id);
+        result.map(|_| ())
+    }
+
+    /// The task a PR works on: its linked issue when that can be fetched, and
+    /// otherwise the PR itself.
+    fn pr_task(&self, pr: &PrContext) -> Task {
+        pr.linked_issue_number
+            .and_then(|n| self.source.get_task_details(&n.to_string()).ok())
+            .unwrap_or_else(|| Task {
+                id: pr.number.to_string(),
+                title: pr.title.clone(),
+                body: pr.body.clone(),
+                labels: vec![],
+                url: pr.url.clone(),
+                priority: None,
+                created_at: None,
+                updated_at: None,
+                milestone: None,
+                reactions: None,
+                assignee: None,
+                estimate: None,
+                project: None,
+            })
+    }
+
+    /// Address new collaborator feedback on rlph's own open PRs and adopted PRs.
+    ///
+    /// For every open PR whose head branch was created by rlph or that was
+    /// adopted with `rlph adopt-pr`, trusted comments
+    /// newer than the stored cursor are handed to the review-fix step as
+    /// `fix_instructions`. The fixes are pushed to the PR branch and a reply is
+    /// posted. Returns the number of PRs that had feedback addressed.
+    pub async fn process_pr_feedback(&self) -> Result<usize> {
+        info!("checking open PRs for new feedback");
+        let prs = self.submission.list_open_prs()?;
+        let adopted: HashSet<u64> = self
+            .state_mgr
+            .adopted_prs()
+            .into_iter()
+            .map(|(number, _)| number)
+            .collect();
+        let mut handled = 0;
+
+        for pr in prs.iter().filter(|pr| {
+            self.worktree_mgr.is_managed_branch(&pr.head_branch) || adopted.contains(&pr.number)
+        }) {
+            let comments = self
+                .submission
+                .fetch_pr_comments(pr.number)
+                .and_then(|mut comments| {
+                    comments.extend(self.submission.fetch_pr_review_feedback(pr.number)?);
+                    Ok(comments)
+                });
+            let comments = match comments {
+                Ok(c) => c,
+                Err(e) => {
+                    warn!(pr = pr.number, error = %e, "failed to fetch PR comments");
+                    continue;
+                }
+            };
+            let cursors: HashMap<FeedbackKind, u64> = FeedbackKind::ALL
+                .into_iter()
+                .map(|kind| (kind, self.state_mgr.pr_feedback_cursor(pr.number, kind)))
+                .collect();
+            let feedback = new_human_feedback(&comments, |kind| cursors[&kind]);
+            if feedback.is_empty() {
+                continue;
+            }
+
+            info!(
+                pr = pr.number,
+                comments = feedback.len(),
+                "addressing new PR feedback"
+            );
+            match self.address_pr_feedback(pr, &feedback).await {
+                Ok(()) => {
+                    if !self.config.dry_run {
+                        for kind in FeedbackKind::ALL {
+                            let latest = feedback.iter().filter(|c| c.kind == kind).map(|c| c.id);
+                            if let Some(latest_id) = latest.max() {
+                                self.state_mgr
+                                    .set_pr_feedback_cursor(pr.number, kind, latest_id)?;
+                            }
+                        }
+                    }
+                    handled += 1;
+                }
+                Err(e) => warn!(pr = pr.number, error = %e, "failed to address PR feedback"),
+            }
+        }
+
+        Ok(handled)
+    }
+
+    async fn address_pr_feedback(&self, pr: &PrContext, feedback: &[&PrComment]) -> Result<()> {
+        let worktree_info = self
+            .worktree_mgr
+            .create_for_branch(pr.number, &pr.head_branch)?;
+
+        let task = self.pr_task(pr);
+        let mut fix_vars = self.initial_task_vars(&task, &worktree_info);
+        fix_vars.insert("pr_number".to_string(), pr.number.to_string());
+        fix_vars.insert("pr_branch".to_string(), pr.head_branch.clone());
+        fix_vars.insert("pr_url".to_string(), pr.url.clone());
+        if let Some(base) = &pr.base_branch {
+            fix_vars.insert("base_branch".to_string(), base.clone());
+        }
+        fix_vars.insert(
+            "fix_instructions".to_string(),
+            format_feedback_instructions(feedback),
+        );
+
+        let head_before = worktree_head(&worktree_info);
+        let result = match self.run_review_fix(&fix_vars, &worktree_info).await {
+            Ok(Ok(fix_output)) => self
+                .guard_protected_paths(&fix_vars, &worktree_info)
+                .await
+                .map(|()| Ok(fix_output)),
+            other => other,
+        };
+        if matches!(result, Ok(Ok(_))) {
+            let attribution = FixAttribution {
+                round: "feedback".to_string(),
+                reviewers: Vec::new(),
+                requesters: feedback.iter().map(|c| c.author().to_string()).collect(),
+            };
+            attribute_fix_commits(
+                &self.config.fix_trailers,
+                &worktree_info.path,
+                head_before.as_deref(),
+                &attribution,
+            );
+        }
+        let result = match result {
+            Ok(Ok(fix_output)) => self
+                .push_branch_to(&worktree_info, &pr.head_branch)
+                .and_then(|()| {
+                    let reply = format_feedback_reply(feedback, &fix_output);
+                    self.submission.post_pr_comment(pr.number, &reply)
+                }),
+            Ok(Err(reason)) => Err(Error::Orchestrator(reason)),
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = self.worktree_mgr.remove(&worktree_info.path) {
+            warn!(error = %e, "failed to clean up feedback worktree");
+        }
+        result
+    }
+
+    /// Parse the task selection from `.rlph/task.toml` written by the choose agent.
+    fn parse_task_selection(&self) -> Result<String> {
+        let path = self.repo_root.join(".rlph").join("task.toml");
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            Error::Orchestrator(format!(
+                "failed to read task selection {}: {e}",
+                path.display()
+            ))
+        })?;
+        let selection: TaskSelection = toml::from_str(&content)
+            .map_err(|e| Error::Orchestrator(format!("failed to parse task selection: {e}")))?;
+
+        // Clean up the selection file
+        let _ = std::fs::remove_file(&path);
+
+        Ok(selection.id)
+    }
+
+    fn initial_task_vars(&self, task: &Task, worktree: &WorktreeInfo) -> HashMap<String, String> {
+        let preprocessed;
+        let task = if self.config.preprocess.steps.is_empty() {
+            task
+        } else {
+            let ctx =
+                SourceContext::new(&self.source, &self.repo_root, self.config.github_transport);
+            preprocessed = Task {
+                body: preprocess_body(&task.body, &self.config.preprocess, &ctx),
+                ..task.clone()
+            };
+            &preprocessed
+        };
+        let mut vars = build_task_vars(
             task,
             &self.repo_root,
             &worktree.branch,
             &worktree.path,
-            &self.config.base_branch,
+            self.config.base_branch_for(task),
         );
         vars.insert("pr_number".to_string(), String::new());
         vars.insert("pr_branch".to_string(), String::new());
+        vars.insert("pr_url".to_string(), String::new());
+        let attempts = self.state_mgr.failed_attempts(&format!("gh-{}", task.id));
+        vars.insert(
+            "previous_attempts".to_string(),
+            format_previous_attempts(&attempts),
+        );
+        vars.insert(
+            "referenced_code".to_string(),
+            task_referenced_code(
+                &task.body,
+                self.config.permalink_context,
+                &self.config.preprocess,
+                &SourceContext::new(&self.source, &self.repo_root, self.config.github_transport),
+            ),
+        );
+        if self.config.review_lessons {
+            vars.insert("past_lessons".to_string(), load_lessons(&self.repo_root));
+        }
+        if self.config.test_plan {
+            vars.insert(
+                "test_plan_instructions".to_string(),
+                TEST_PLAN_INSTRUCTIONS.to_string(),
+            );
+        }
+        vars.insert("ignored_paths".to_string(), self.ignore.prompt_list());
+        vars.insert(
+            "repo_conventions".to_string(),
+            load_conventions(&worktree.path, &self.config.conventions_files),
+        );
+        vars.insert(
+            "project_toolchain".to_string(),
+            describe_toolchain(&worktree.path, &self.config.toolchain, &[]),
+        );
         vars
     }
 
     fn push_branch(&self, worktree: &WorktreeInfo) -> Result<()> {
-        let output = Command::new("git")
-            .args(["push", "-u", "origin", &worktree.branch])
-            .current_dir(&worktree.path)
-            .output()
-            .map_err(|e| Error::Orchestrator(format!("failed to run git push: {e}")))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(Error::Orchestrator(format!("git push failed: {stderr}")));
+        if self.record_dry_run_push(&worktree.branch) {
+            return Ok(());
         }
-
+        push_with_retry(
+            &worktree.path,
+            &self.config.push_remote,
+            &worktree.branch,
+            &worktree.branch,
+            PUSH_INITIAL_BACKOFF,
+        )?;
         info!(branch = worktree.branch, "pushed branch");
         Ok(())
     }
@@ -1018,20 +4211,48 @@ impl<
             .map_err(|e| Error::Orchestrator(format!("invalid remote branch name: {e}")))?;
 
         let refspec = format!("HEAD:{remote_branch}");
-        let output = Command::new("git")
-            .args(["push", "-u", "origin", &refspec])
-            .current_dir(&worktree.path)
-            .output()
-            .map_err(|e| Error::Orchestrator(format!("failed to run git push: {e}")))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(Error::Orchestrator(format!("git push failed: {stderr}")));
+        if self.record_dry_run_push(&refspec) {
+            return Ok(());
         }
-
+        push_with_retry(
+            &worktree.path,
+            &self.config.push_remote,
+            "HEAD",
+            remote_branch,
+            PUSH_INITIAL_BACKOFF,
+        )?;
         info!(branch = worktree.branch, remote_branch, "pushed branch");
         Ok(())
     }
+
+    /// In a dry run, record a push of `refspec` instead of running it and
+    /// return `true`.
+    fn record_dry_run_push(&self, refspec: &str) -> bool {
+        let Some(log) = &self.dry_run_log else {
+            return false;
+        };
+        log.record(
+            "git",
+            "push",
+            serde_json::json!({ "remote": self.config.push_remote, "refspec": refspec }),
+        );
+        true
+    }
+
+    /// Force-push rewritten history, refusing if the remote branch moved past
+    /// `expected` in the meantime.
+    fn force_push_branch(&self, worktree: &WorktreeInfo, expected: Option<&str>) -> Result<()> {
+        force_push(
+            &worktree.path,
+            &self.config.push_remote,
+            "HEAD",
+            &worktree.branch,
+            expected,
+            PUSH_INITIAL_BACKOFF,
+        )?;
+        info!(branch = worktree.branch, "force-pushed squashed branch");
+        Ok(())
+    }
 }
 
 /// Attempt to resume a session with a correction prompt when JSON parsing fails.
@@ -1049,6 +4270,8 @@ pub async fn retry_with_correction<T>(
     agent_model: Option<&str>,
     agent_effort: Option<&str>,
     agent_variant: Option<&str>,
+    permission_mode: PermissionMode,
+    agent_identity: &AgentIdentity,
     agent_timeout: Option<u64>,
     schema: SchemaName,
     initial_error: &str,
@@ -1076,6 +4299,8 @@ pub async fn retry_with_correction<T>(
                 agent_model,
                 agent_effort,
                 agent_variant,
+                permission_mode,
+                agent_identity,
                 session_id,
                 &prompt,
                 working_dir,
@@ -1111,9 +4336,18 @@ pub fn build_task_vars(
     worktree_path: &Path,
     base_branch: &str,
 ) -> HashMap<String, String> {
+    let title = sanitize_untrusted(&task.title);
+    let body = sanitize_untrusted(&task.body);
+    if title.flagged + body.flagged > 0 {
+        warn!(
+            task_id = %task.id,
+            flagged_lines = title.flagged + body.flagged,
+            "task contains possible prompt-injection directives"
+        );
+    }
     HashMap::from([
-        ("issue_title".to_string(), task.title.clone()),
-        ("issue_body".to_string(), task.body.clone()),
+        ("issue_title".to_string(), title.text),
+        ("issue_body".to_string(), body.text),
         ("issue_number".to_string(), task.id.clone()),
         ("issue_url".to_string(), task.url.clone()),
         ("repo_path".to_string(), repo_path.display().to_string()),
@@ -1123,9 +4357,308 @@ pub fn build_task_vars(
             worktree_path.display().to_string(),
         ),
         ("base_branch".to_string(), base_branch.to_string()),
+        ("previous_attempts".to_string(), String::new()),
+        ("referenced_code".to_string(), String::new()),
+        ("past_lessons".to_string(), String::new()),
+        ("test_plan_instructions".to_string(), String::new()),
+        ("test_plan".to_string(), String::new()),
+        ("review_since".to_string(), String::new()),
+        ("previous_findings".to_string(), String::new()),
+        ("issue_language".to_string(), String::new()),
+        (
+            "issue_assignee".to_string(),
+            task.assignee.clone().unwrap_or_default(),
+        ),
+        (
+            "issue_estimate".to_string(),
+            task.estimate.map(|e| e.to_string()).unwrap_or_default(),
+        ),
+        (
+            "issue_project".to_string(),
+            task.project.clone().unwrap_or_default(),
+        ),
+        (
+            "issue_milestone".to_string(),
+            task.milestone.clone().unwrap_or_default(),
+        ),
+        (
+            "issue_created_at".to_string(),
+            task.created_at.clone().unwrap_or_default(),
+        ),
+        (
+            "issue_updated_at".to_string(),
+            task.updated_at.clone().unwrap_or_default(),
+        ),
     ])
 }
 
+/// Prompt sent by the runner probe; any successful reply passes.
+const PROBE_PROMPT: &str = "Reply with the single word OK and nothing else. Do not use any tools.";
+
+/// How long the runner probe may take before the iteration fails.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Incremental closed-task fetches reach this far before the last sync, so
+/// tasks closed around it are not missed to clock skew.
+const CLOSED_TASKS_SYNC_OVERLAP_SECS: u64 = 300;
+
+/// How long CI gets to register checks on a force-pushed head before its
+/// status is read.
+const CI_SETTLE_DELAY: Duration = Duration::from_secs(15);
+/// Interval between CI status checks while waiting to enable auto-merge.
+const CI_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Per-attempt cap on the error text included in prompts.
+const MAX_ATTEMPT_ERROR_CHARS: usize = 1500;
+
+/// Summarize the most recent failed attempts for the `previous_attempts` var.
+/// Returns an empty string when there are none.
+pub fn format_previous_attempts(attempts: &[FailedAttempt]) -> String {
+    let skip = attempts.len().saturating_sub(MAX_PREVIOUS_ATTEMPTS);
+    attempts
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .map(|(i, a)| {
+            let mut error: String = a.error.chars().take(MAX_ATTEMPT_ERROR_CHARS).collect();
+            if error.len() < a.error.len() {
+                error.push('…');
+            }
+            format!(
+                "- Attempt {} failed during `{}`: {}",
+                i + 1,
+                a.phase,
+                error.trim()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Stable fingerprint of a task's title and body (FNV-1a), used to detect edits
+/// that invalidate a recorded estimate.
+pub fn task_fingerprint(task: &Task) -> String {
+    fnv1a_hex(
+        task.title
+            .bytes()
+            .chain(std::iter::once(0))
+            .chain(task.body.bytes()),
+    )
+}
+
+fn fnv1a_hex(bytes: impl Iterator<Item = u8>) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{hash:016x}")
+}
+
+/// Title for a follow-up issue filed from a deferred review finding.
+pub fn followup_title(finding: &ReviewFinding) -> String {
+    format!("Follow-up: {} in {}", finding.id, finding.file)
+}
+
+/// Hash of a follow-up title, insensitive to case and whitespace, used to
+/// de-duplicate against open follow-ups.
+pub fn followup_title_hash(title: &str) -> String {
+    let normalized = title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    fnv1a_hex(normalized.bytes())
+}
+
+fn format_followup_body(finding: &ReviewFinding, pr_number: u64, pr_url: Option<&str>) -> String {
+    let source = match pr_url {
+        Some(url) => format!("#{pr_number} ({url})"),
+        None => format!("#{pr_number}"),
+    };
+    let category = finding
+        .category
+        .as_deref()
+        .map(|c| format!(" · {c}"))
+        .unwrap_or_default();
+    format!(
+        "Deferred from the rlph review of {source}.\n\n**{}** `{}` L{}{category}\n\n{}",
+        finding.severity.label(),
+        finding.file,
+        finding.line,
+        finding.description.trim()
+    )
+}
+
+/// Text after the implement agent's `IMPLEMENTATION_COMPLETE:` line, if any.
+pub fn implementation_summary(stdout: &str) -> Option<String> {
+    stdout
+        .lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix("IMPLEMENTATION_COMPLETE:"))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Comment posted on the source issue when rlph opens a PR for it.
+pub fn format_issue_pr_comment(pr_url: &str, branch: &str, summary: &str) -> String {
+    format!(
+        "rlph opened a pull request for this issue: {pr_url}\n\n**Branch:** `{branch}`\n**Summary:** {}",
+        summary.trim()
+    )
+}
+
+/// Render `pr_body_template`, followed by the test plan section when there is one.
+fn render_pr_body(
+    template: &str,
+    pr_vars: &HashMap<String, String>,
+    test_plan_section: Option<&str>,
+) -> Result<String> {
+    let body = render_template(template, pr_vars)?;
+    Ok(match test_plan_section {
+        Some(section) => format!("{}\n\n{section}", body.trim_end()),
+        None => body,
+    })
+}
+
+/// Variables available to `pr_title_template` / `pr_body_template`: the task
+/// vars plus `issue_labels`, estimate fields, `review_summary`, and the
+/// tokens and agent time in `usage` (see [`pr_vars`]).
+pub fn pr_template_vars(
+    task_vars: &HashMap<String, String>,
+    task: &Task,
+    estimate: Option<&EstimateOutput>,
+    review_summary: &str,
+    usage: (TokenUsage, Duration),
+) -> HashMap<String, String> {
+    let mut vars = task_vars.clone();
+    // PR text is for humans, so use the task as written rather than the prompt-safe copy.
+    vars.insert("issue_title".to_string(), task.title.clone());
+    vars.insert("issue_body".to_string(), task.body.clone());
+    vars.insert("issue_labels".to_string(), task.labels.join(", "));
+    vars.insert(
+        "estimate_complexity".to_string(),
+        estimate
+            .map(|e| e.complexity.to_string())
+            .unwrap_or_default(),
+    );
+    vars.insert(
+        "estimate_risk_notes".to_string(),
+        estimate
+            .map(|e| e.risk_notes.trim().to_string())
+            .unwrap_or_default(),
+    );
+    vars.insert("review_summary".to_string(), review_summary.to_string());
+    let (tokens, agent_time) = usage;
+    vars.insert(
+        "usage_input_tokens".to_string(),
+        tokens.input_tokens.to_string(),
+    );
+    vars.insert(
+        "usage_output_tokens".to_string(),
+        tokens.output_tokens.to_string(),
+    );
+    vars.insert("usage_total_tokens".to_string(), tokens.total().to_string());
+    vars.insert(
+        "usage_agent_seconds".to_string(),
+        agent_time.as_secs().to_string(),
+    );
+    // Config validation accepts every PR var, so those not known yet (e.g.
+    // `pr_url` before the PR exists) render empty rather than failing.
+    for var in pr_vars() {
+        vars.entry(var.name.to_string()).or_default();
+    }
+    vars
+}
+
+/// Select trusted human comments newer than the cursor for their kind,
+/// excluding rlph's own review comments and feedback replies.
+pub fn new_human_feedback(
+    comments: &[PrComment],
+    cursor: impl Fn(FeedbackKind) -> u64,
+) -> Vec<&PrComment> {
+    comments
+        .iter()
+        .filter(|c| c.id > cursor(c.kind) && c.is_trusted())
+        .filter(|c| !c.body.contains(REVIEW_MARKER) && !c.body.contains(FEEDBACK_MARKER))
+        .collect()
+}
+
+/// Render human PR comments as `fix_instructions` for the review-fix step.
+pub fn format_feedback_instructions(feedback: &[&PrComment]) -> String {
+    let mut out = String::from(
+        "Address the following review feedback left by repository collaborators on the PR:\n",
+    );
+    for c in feedback {
+        out.push_str(&format!(
+            "\n---\n**@{}** ({}):\n{}\n",
+            c.author(),
+            c.created_at,
+            c.body
+        ));
+    }
+    out
+}
+
+/// HEAD of `worktree`, or `None` if it cannot be read.
+fn worktree_head(worktree: &WorktreeInfo) -> Option<String> {
+    git_in_dir(&worktree.path, &["rev-parse", "HEAD"])
+        .ok()
+        .map(|sha| sha.trim().to_string())
+}
+
+/// The review history's fix cell for a round. A rejected fix is recorded as the
+/// round's failure so the review can retry and report it.
+fn fix_round_summary(
+    fix: std::result::Result<FixOutput, String>,
+    last_failure: &mut Option<String>,
+) -> String {
+    match fix {
+        Ok(fix) => fix.summary,
+        Err(reason) => {
+            let summary = format!("Fix rejected: {reason}");
+            *last_failure = Some(reason);
+            summary
+        }
+    }
+}
+
+fn format_feedback_reply(feedback: &[&PrComment], fix_output: &FixOutput) -> String {
+    let mut authors: Vec<String> = Vec::new();
+    for c in feedback {
+        let author = format!("@{}", c.author());
+        if !authors.contains(&author) {
+            authors.push(author);
+        }
+    }
+    let outcome = match fix_output.status {
+        FixStatus::Fixed => "Addressed feedback from",
+        FixStatus::Error => "Could not fully address feedback from",
+    };
+    format!(
+        "{FEEDBACK_MARKER}\n{outcome} {}.\n\n{}",
+        authors.join(", "),
+        fix_output.summary.trim()
+    )
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// `42` → `gh-42`; ids that already carry the prefix are returned unchanged.
+fn normalize_task_id(task_id: &str) -> String {
+    if task_id.starts_with("gh-") {
+        task_id.to_string()
+    } else {
+        format!("gh-{task_id}")
+    }
+}
+
 /// Extract the issue number from a task ID like "gh-42".
 pub fn parse_issue_number(task_id: &str) -> Result<u64> {
     task_id
@@ -1156,6 +4689,58 @@ mod tests {
         assert!(parse_issue_number("linear-42").is_err());
     }
 
+    #[test]
+    fn test_implementation_summary() {
+        assert_eq!(
+            implementation_summary("working...\nIMPLEMENTATION_COMPLETE: added retries\n")
+                .as_deref(),
+            Some("added retries")
+        );
+        assert_eq!(implementation_summary("IMPLEMENTATION_COMPLETE:   "), None);
+        assert_eq!(implementation_summary("done"), None);
+    }
+
+    #[test]
+    fn test_format_issue_pr_comment() {
+        let body = format_issue_pr_comment(
+            "https://github.com/o/r/pull/7",
+            "rlph-42-fix",
+            "added retries\n",
+        );
+        assert!(body.contains("https://github.com/o/r/pull/7"));
+        assert!(body.contains("**Branch:** `rlph-42-fix`"));
+        assert!(body.ends_with("**Summary:** added retries"));
+    }
+
+    #[test]
+    fn test_format_previous_attempts_keeps_latest() {
+        assert_eq!(format_previous_attempts(&[]), "");
+        let attempts: Vec<FailedAttempt> = (0..5)
+            .map(|i| FailedAttempt {
+                id: "gh-1".to_string(),
+                phase: "review".to_string(),
+                error: format!("error {i}"),
+                failed_at: i,
+            })
+            .collect();
+        let text = format_previous_attempts(&attempts);
+        assert_eq!(text.lines().count(), 3);
+        assert!(text.starts_with("- Attempt 3 failed during `review`: error 2"));
+        assert!(text.ends_with("error 4"));
+    }
+
+    #[test]
+    fn test_followup_title_hash_normalizes() {
+        assert_eq!(
+            followup_title_hash("Follow-up: unused-import in src/a.rs"),
+            followup_title_hash("  follow-up:  UNUSED-import in src/a.rs ")
+        );
+        assert_ne!(
+            followup_title_hash("Follow-up: a in src/a.rs"),
+            followup_title_hash("Follow-up: b in src/a.rs")
+        );
+    }
+
     #[test]
     fn test_parse_aggregator_approved_json() {
         use crate::review_schema::{Verdict, parse_aggregator_output};
@@ -1184,4 +4769,138 @@ mod tests {
 
         assert!(parse_aggregator_output("not json at all").is_err());
     }
+
+    fn ranked_task(
+        id: u64,
+        priority: Option<u8>,
+        body: &str,
+        dependents: usize,
+    ) -> (Task, UnblockingPower) {
+        (
+            Task {
+                id: id.to_string(),
+                title: format!("Task {id}"),
+                body: body.to_string(),
+                labels: vec!["rlph".to_string()],
+                url: format!("https://github.com/o/r/issues/{id}"),
+                priority: priority.map(Priority),
+                created_at: None,
+                updated_at: None,
+                milestone: None,
+                reactions: None,
+                assignee: None,
+                estimate: None,
+                project: None,
+            },
+            UnblockingPower {
+                dependents,
+                depth: dependents,
+            },
+        )
+    }
+
+    #[test]
+    fn test_pr_template_vars_include_usage() {
+        let (task, _) = ranked_task(7, None, "body", 0);
+        let tokens = TokenUsage {
+            input_tokens: 1200,
+            output_tokens: 300,
+        };
+        let vars = pr_template_vars(
+            &HashMap::new(),
+            &task,
+            None,
+            "All good.",
+            (tokens, Duration::from_millis(61_500)),
+        );
+        assert_eq!(vars["issue_labels"], "rlph");
+        assert_eq!(vars["estimate_complexity"], "");
+        assert_eq!(vars["review_summary"], "All good.");
+        assert_eq!(vars["usage_input_tokens"], "1200");
+        assert_eq!(vars["usage_output_tokens"], "300");
+        assert_eq!(vars["usage_total_tokens"], "1500");
+        assert_eq!(vars["usage_agent_seconds"], "61");
+        // Every var config validation accepts is provided, if only empty.
+        for var in pr_vars() {
+            assert!(vars.contains_key(var.name), "{}", var.name);
+        }
+        assert_eq!(vars["pr_url"], "");
+    }
+
+    #[test]
+    fn test_choose_candidates_keep_top_priorities_in_ranked_order() {
+        let ranked = vec![
+            ranked_task(1, None, "", 2),
+            ranked_task(2, Some(3), "", 1),
+            ranked_task(3, Some(1), "", 0),
+            ranked_task(4, Some(3), "", 0),
+        ];
+        let policy = PriorityPolicy::default();
+        let ids = |max| -> Vec<String> {
+            choose_candidates(&ranked, &[PrioritySignal::Labels], &policy, 0, max, 100)
+                .iter()
+                .map(|c| c.id.to_string())
+                .collect()
+        };
+        assert_eq!(ids(10), ["1", "2", "3", "4"]);
+        assert_eq!(ids(2), ["2", "3"]);
+        assert_eq!(ids(3), ["2", "3", "4"]);
+
+        let mut ranked = ranked;
+        ranked[0].0.reactions = Some(5);
+        let signals = [PrioritySignal::Reactions, PrioritySignal::Labels];
+        let candidates = choose_candidates(&ranked, &signals, &policy, 0, 2, 100);
+        let ids: Vec<&str> = candidates.iter().map(|c| c.id).collect();
+        assert_eq!(ids, ["1", "3"]);
+        assert_eq!(candidates[0].reactions, Some(5));
+    }
+
+    #[test]
+    fn test_choose_candidates_truncate_bodies() {
+        let ranked = vec![
+            ranked_task(1, None, "Short body.", 0),
+            ranked_task(2, None, "Depends on #7. Then a long tail of text", 0),
+        ];
+        let policy = PriorityPolicy::default();
+
+        let candidates = choose_candidates(&ranked, &[PrioritySignal::Labels], &policy, 0, 10, 15);
+        assert_eq!(candidates[0].body.as_deref(), Some("Short body."));
+        assert_eq!(
+            candidates[1].body.as_deref(),
+            Some("Depends on #7.... [truncated]")
+        );
+        assert_eq!(candidates[1].depends_on, vec![7]);
+
+        let json = render_issues_json(&choose_candidates(
+            &ranked,
+            &[PrioritySignal::Labels],
+            &policy,
+            0,
+            10,
+            0,
+        ))
+        .unwrap();
+        assert!(!json.contains("body"));
+        assert!(!json.contains("url"));
+        assert_eq!(json.lines().count(), 4);
+        assert!(json.contains(r#""depends_on":[7]"#));
+    }
+
+    #[test]
+    fn test_issues_json_lists_task_metadata() {
+        let mut ranked = vec![ranked_task(1, None, "", 0), ranked_task(2, None, "", 0)];
+        ranked[0].0.assignee = Some("ada".to_string());
+        ranked[0].0.estimate = Some(3.0);
+        ranked[0].0.project = Some("Roadmap".to_string());
+        let policy = PriorityPolicy::default();
+
+        let candidates = choose_candidates(&ranked, &[PrioritySignal::Labels], &policy, 0, 10, 0);
+        let json = render_issues_json(&candidates).unwrap();
+        let lines: Vec<&str> = json.lines().collect();
+        assert!(
+            lines[0].contains(r#""project":"Roadmap","assignee":"ada","estimate":3.0"#),
+            "{json}"
+        );
+        assert!(!lines[1].contains("assignee"));
+    }
 }
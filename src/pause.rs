@@ -0,0 +1,89 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::{Error, Result};
+use crate::sources::format_iso_timestamp;
+
+/// `.rlph/PAUSE` under the repository root. While it exists, the loop picks up
+/// no new tasks.
+pub fn pause_file(repo_root: &Path) -> PathBuf {
+    repo_root.join(".rlph").join("PAUSE")
+}
+
+/// Contents of the pause file, or `None` when rlph is not paused. An empty
+/// file still pauses.
+pub fn paused(repo_root: &Path) -> Option<String> {
+    let path = pause_file(repo_root);
+    if !path.exists() {
+        return None;
+    }
+    Some(
+        std::fs::read_to_string(&path)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default(),
+    )
+}
+
+/// Create the pause file, recording when and why. Pausing again replaces the
+/// note.
+pub fn pause(repo_root: &Path, reason: Option<&str>) -> Result<PathBuf> {
+    let path = pause_file(repo_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| Error::State(format!("failed to create {}: {e}", parent.display())))?;
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let mut note = format!("paused at {}", format_iso_timestamp(now));
+    if let Ok(user) = std::env::var("USER") {
+        note.push_str(&format!(" by {user}"));
+    }
+    if let Some(reason) = reason.map(str::trim).filter(|r| !r.is_empty()) {
+        note.push_str(&format!(": {reason}"));
+    }
+    note.push('\n');
+    std::fs::write(&path, note)
+        .map_err(|e| Error::State(format!("failed to write {}: {e}", path.display())))?;
+    Ok(path)
+}
+
+/// Remove the pause file. Returns whether rlph was paused.
+pub fn unpause(repo_root: &Path) -> Result<bool> {
+    let path = pause_file(repo_root);
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(Error::State(format!(
+            "failed to remove {}: {e}",
+            path.display()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_pause_and_unpause() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(paused(dir.path()), None);
+        assert!(!unpause(dir.path()).unwrap());
+
+        let path = pause(dir.path(), Some("incident 123")).unwrap();
+        assert_eq!(path, dir.path().join(".rlph/PAUSE"));
+        let note = paused(dir.path()).unwrap();
+        assert!(note.starts_with("paused at "), "{note}");
+        assert!(note.ends_with(": incident 123"), "{note}");
+
+        assert!(unpause(dir.path()).unwrap());
+        assert_eq!(paused(dir.path()), None);
+
+        // A hand-made empty file pauses too.
+        std::fs::write(pause_file(dir.path()), "").unwrap();
+        assert_eq!(paused(dir.path()).as_deref(), Some(""));
+    }
+}
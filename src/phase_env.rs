@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::{Config, ReviewPhaseConfig, ReviewStepConfig};
+use crate::runner::RunnerKind;
+use crate::worktree::git_in_dir;
+
+/// The context an agent phase started in, kept in the run summary so a
+/// post-mortem can tell exactly what the agent saw.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhaseEnvironment {
+    pub phase: String,
+    pub started_at: u64,
+    /// `HEAD` of the directory the agent ran in; empty outside a git checkout.
+    pub head: String,
+    /// Whether that checkout had uncommitted changes.
+    pub dirty: bool,
+    pub runner: String,
+    /// First line of `<agent_binary> --version`; empty when it cannot be run.
+    pub runner_version: String,
+    pub model: Option<String>,
+    pub effort: Option<String>,
+    /// SHA-256 of the rendered prompt.
+    pub prompt_sha256: String,
+}
+
+/// Runner settings of a phase, as configured.
+#[derive(Debug, Clone, Copy)]
+pub struct AgentSettings<'a> {
+    pub runner: RunnerKind,
+    pub binary: &'a str,
+    pub model: Option<&'a str>,
+    pub effort: Option<&'a str>,
+}
+
+impl<'a> AgentSettings<'a> {
+    /// The top-level agent settings, used by choose and implement.
+    pub fn from_config(config: &'a Config) -> Self {
+        Self {
+            runner: config.runner,
+            binary: &config.agent_binary,
+            model: config.agent_model.as_deref(),
+            effort: config.agent_effort.as_deref(),
+        }
+    }
+
+    pub fn from_step(step: &'a ReviewStepConfig) -> Self {
+        Self {
+            runner: step.runner,
+            binary: &step.agent_binary,
+            model: step.agent_model.as_deref(),
+            effort: step.agent_effort.as_deref(),
+        }
+    }
+
+    pub fn from_review_phase(phase: &'a ReviewPhaseConfig) -> Self {
+        Self {
+            runner: phase.runner,
+            binary: &phase.agent_binary,
+            model: phase.agent_model.as_deref(),
+            effort: phase.agent_effort.as_deref(),
+        }
+    }
+}
+
+impl PhaseEnvironment {
+    /// Snapshot `dir` and `agent` right before `phase` runs `prompt`.
+    pub fn capture(phase: &str, dir: &Path, agent: AgentSettings<'_>, prompt: &str) -> Self {
+        let head = git_in_dir(dir, &["rev-parse", "HEAD"])
+            .map(|out| out.trim().to_string())
+            .unwrap_or_default();
+        let dirty = git_in_dir(dir, &["status", "--porcelain"])
+            .map(|out| !out.trim().is_empty())
+            .unwrap_or(false);
+        Self {
+            phase: phase.to_string(),
+            started_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            head,
+            dirty,
+            runner: agent.runner.to_string(),
+            runner_version: runner_version(agent.binary),
+            model: agent.model.map(str::to_string),
+            effort: agent.effort.map(str::to_string),
+            prompt_sha256: format!("{:x}", Sha256::digest(prompt.as_bytes())),
+        }
+    }
+}
+
+/// `<binary> --version`, run once per binary and process.
+fn runner_version(binary: &str) -> String {
+    static VERSIONS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    let versions = VERSIONS.get_or_init(Mutex::default);
+    if let Some(version) = versions.lock().unwrap().get(binary) {
+        return version.clone();
+    }
+    let version = Command::new(binary)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| {
+            String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .next()
+                .map(|line| line.trim().to_string())
+        })
+        .unwrap_or_default();
+    versions
+        .lock()
+        .unwrap()
+        .insert(binary.to_string(), version.clone());
+    version
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_capture_records_head_dirty_state_and_prompt_hash() {
+        let repo = TempDir::new().unwrap();
+        let git = |args: &[&str]| git_in_dir(repo.path(), args).unwrap();
+        git(&["init", "-q"]);
+        git(&[
+            "-c",
+            "user.name=t",
+            "-c",
+            "user.email=t@t",
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "init",
+        ]);
+        let agent = AgentSettings {
+            runner: RunnerKind::Claude,
+            binary: "rlph-no-such-binary",
+            model: Some("opus"),
+            effort: None,
+        };
+
+        let env = PhaseEnvironment::capture("implement", repo.path(), agent, "do it");
+        assert_eq!(env.head, git(&["rev-parse", "HEAD"]).trim());
+        assert!(!env.dirty);
+        assert_eq!(env.runner, "claude");
+        assert_eq!(env.runner_version, "");
+        assert_eq!(env.model.as_deref(), Some("opus"));
+        assert_eq!(
+            env.prompt_sha256,
+            "f51c362d5cc7defb17b2b1290bb8e9b5a4d7e1319f85b90741ff3f8578fc6bba"
+        );
+
+        std::fs::write(repo.path().join("scratch.txt"), "x").unwrap();
+        let env = PhaseEnvironment::capture("fix", repo.path(), agent, "do it again");
+        assert!(env.dirty);
+        assert_ne!(
+            env.prompt_sha256,
+            "f51c362d5cc7defb17b2b1290bb8e9b5a4d7e1319f85b90741ff3f8578fc6bba"
+        );
+    }
+}
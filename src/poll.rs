@@ -0,0 +1,305 @@
+use std::collections::BTreeMap;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::sources::Task;
+
+/// Largest random share added on top of each poll delay, in percent.
+pub const POLL_JITTER_PERCENT: u64 = 20;
+
+/// What the last iteration saw, as far as polling is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollActivity {
+    /// A task was picked up (processed, skipped, or failed).
+    Active,
+    /// No eligible task was found.
+    Idle,
+    /// The task source reported a rate limit.
+    RateLimited,
+}
+
+/// Adaptive delay between polls in continuous mode.
+///
+/// Starts at `min`, doubles after each idle or rate-limited poll up to `max`, and
+/// drops back to `min` after activity. Each delay gets up to
+/// [`POLL_JITTER_PERCENT`] added so that several loops drift apart.
+#[derive(Debug, Clone)]
+pub struct PollBackoff {
+    min: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl PollBackoff {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        let max = max.max(min);
+        Self {
+            min,
+            max,
+            current: min,
+        }
+    }
+
+    /// Record the last iteration and return the delay before the next poll,
+    /// without jitter.
+    pub fn record(&mut self, activity: PollActivity) -> Duration {
+        self.current = match activity {
+            PollActivity::Active => self.min,
+            PollActivity::Idle | PollActivity::RateLimited => {
+                self.current.saturating_mul(2).min(self.max)
+            }
+        };
+        self.current
+    }
+
+    /// Record the last iteration and return the jittered delay before the next poll.
+    pub fn next_delay(&mut self, activity: PollActivity) -> Duration {
+        let delay = self.record(activity);
+        with_jitter(delay, random_seed())
+    }
+}
+
+/// Add `seed`-derived jitter of up to [`POLL_JITTER_PERCENT`] to `delay`.
+pub fn with_jitter(delay: Duration, seed: u64) -> Duration {
+    let max_jitter_ms = delay.as_millis() as u64 * POLL_JITTER_PERCENT / 100;
+    if max_jitter_ms == 0 {
+        return delay;
+    }
+    delay + Duration::from_millis(seed % (max_jitter_ms + 1))
+}
+
+fn random_seed() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// An HTTP 429 as the API clients (`status 429`), ureq (`status code 429`),
+/// and `gh` (`HTTP 429`) report it, not any number containing 429.
+static STATUS_429_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:status(?:\s+code)?|http)[\s:]*429\b").expect("valid status regex")
+});
+
+/// Whether `error` is a task source rate limit (HTTP 429 or a "rate limit" message).
+pub fn is_rate_limited(error: &Error) -> bool {
+    let Error::TaskSource(message) = error else {
+        return false;
+    };
+    message.to_ascii_lowercase().contains("rate limit") || STATUS_429_RE.is_match(message)
+}
+
+/// Eligible tasks seen by one poll, by id, split by whether their dependencies
+/// are done.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueueSnapshot {
+    ready: BTreeMap<String, String>,
+    blocked: BTreeMap<String, String>,
+}
+
+impl QueueSnapshot {
+    /// Snapshot `all` fetched tasks, of which `ready` are unblocked.
+    pub fn new(all: &[Task], ready: &[Task]) -> Self {
+        let mut snapshot = Self::default();
+        for task in all {
+            let bucket = if ready.iter().any(|r| r.id == task.id) {
+                &mut snapshot.ready
+            } else {
+                &mut snapshot.blocked
+            };
+            bucket.insert(task.id.clone(), task.title.clone());
+        }
+        snapshot
+    }
+
+    fn title(&self, id: &str) -> Option<&String> {
+        self.ready.get(id).or_else(|| self.blocked.get(id))
+    }
+}
+
+/// A task named in a [`QueueDelta`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueuedTask {
+    pub id: String,
+    pub title: String,
+}
+
+/// How the eligible tasks changed between two polls.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct QueueDelta {
+    /// Newly eligible tasks, blocked or not.
+    pub added: Vec<QueuedTask>,
+    /// Tasks no longer eligible (picked up, closed, or relabeled).
+    pub removed: Vec<QueuedTask>,
+    /// Tasks that stayed eligible but now wait on a dependency.
+    pub blocked: Vec<QueuedTask>,
+    /// Tasks that stayed eligible and whose dependencies are now done.
+    pub unblocked: Vec<QueuedTask>,
+}
+
+impl QueueDelta {
+    pub fn between(before: &QueueSnapshot, after: &QueueSnapshot) -> Self {
+        let task = |id: &String, title: &String| QueuedTask {
+            id: id.clone(),
+            title: title.clone(),
+        };
+        let mut delta = Self::default();
+        for (id, title) in after.ready.iter().chain(&after.blocked) {
+            if before.title(id).is_none() {
+                delta.added.push(task(id, title));
+            } else if after.blocked.contains_key(id) && before.ready.contains_key(id) {
+                delta.blocked.push(task(id, title));
+            } else if after.ready.contains_key(id) && before.blocked.contains_key(id) {
+                delta.unblocked.push(task(id, title));
+            }
+        }
+        for (id, title) in before.ready.iter().chain(&before.blocked) {
+            if after.title(id).is_none() {
+                delta.removed.push(task(id, title));
+            }
+        }
+        delta
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.blocked.is_empty()
+            && self.unblocked.is_empty()
+    }
+}
+
+impl fmt::Display for QueueDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = [
+            ("new", &self.added),
+            ("gone", &self.removed),
+            ("blocked", &self.blocked),
+            ("unblocked", &self.unblocked),
+        ];
+        let mut first = true;
+        for (label, tasks) in parts {
+            if tasks.is_empty() {
+                continue;
+            }
+            if !first {
+                write!(f, "; ")?;
+            }
+            first = false;
+            let ids: Vec<String> = tasks.iter().map(|t| format!("#{}", t.id)).collect();
+            write!(f, "{} {label} ({})", tasks.len(), ids.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_to_max_and_resets() {
+        let mut backoff = PollBackoff::new(Duration::from_secs(30), Duration::from_secs(200));
+        assert_eq!(backoff.record(PollActivity::Idle), Duration::from_secs(60));
+        assert_eq!(
+            backoff.record(PollActivity::RateLimited),
+            Duration::from_secs(120)
+        );
+        assert_eq!(backoff.record(PollActivity::Idle), Duration::from_secs(200));
+        assert_eq!(backoff.record(PollActivity::Idle), Duration::from_secs(200));
+        assert_eq!(
+            backoff.record(PollActivity::Active),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn test_backoff_max_below_min_stays_at_min() {
+        let mut backoff = PollBackoff::new(Duration::from_secs(30), Duration::from_secs(10));
+        assert_eq!(backoff.record(PollActivity::Idle), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_jitter_is_bounded() {
+        let delay = Duration::from_secs(10);
+        assert_eq!(with_jitter(delay, 0), delay);
+        assert_eq!(with_jitter(delay, 2000), Duration::from_secs(12));
+        assert_eq!(with_jitter(delay, 2001), delay);
+        for seed in [1, 999, u64::MAX] {
+            let jittered = with_jitter(delay, seed);
+            assert!(jittered >= delay && jittered <= Duration::from_secs(12));
+        }
+        assert_eq!(with_jitter(Duration::ZERO, 7), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_is_rate_limited() {
+        assert!(is_rate_limited(&Error::TaskSource(
+            "gh issue list failed: API rate limit exceeded".to_string()
+        )));
+        assert!(is_rate_limited(&Error::TaskSource(
+            "HTTP 429 Too Many Requests".to_string()
+        )));
+        assert!(is_rate_limited(&Error::TaskSource(
+            "Trello API GET /boards failed: status 429: slow down".to_string()
+        )));
+        assert!(!is_rate_limited(&Error::TaskSource(
+            "failed to parse issue #4291: missing field `title`".to_string()
+        )));
+        assert!(!is_rate_limited(&Error::TaskSource(
+            "GitHub API GET /issues/1429 failed: status 404: Not Found".to_string()
+        )));
+        assert!(!is_rate_limited(&Error::TaskSource(
+            "network unreachable".to_string()
+        )));
+        assert!(!is_rate_limited(&Error::Submission(
+            "rate limit".to_string()
+        )));
+    }
+
+    fn task(id: &str) -> Task {
+        Task {
+            id: id.to_string(),
+            title: format!("Task {id}"),
+            body: String::new(),
+            labels: Vec::new(),
+            url: String::new(),
+            priority: None,
+            created_at: None,
+            updated_at: None,
+            milestone: None,
+            reactions: None,
+            assignee: None,
+            estimate: None,
+            project: None,
+        }
+    }
+
+    #[test]
+    fn test_queue_delta_between_polls() {
+        let before = QueueSnapshot::new(
+            &[task("1"), task("2"), task("3"), task("4")],
+            &[task("1"), task("2")],
+        );
+        let after = QueueSnapshot::new(
+            &[task("2"), task("3"), task("4"), task("5")],
+            &[task("3"), task("5")],
+        );
+        let delta = QueueDelta::between(&before, &after);
+        let ids = |tasks: &[QueuedTask]| tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&delta.added), ["5"]);
+        assert_eq!(ids(&delta.removed), ["1"]);
+        assert_eq!(ids(&delta.blocked), ["2"]);
+        assert_eq!(ids(&delta.unblocked), ["3"]);
+        assert_eq!(delta.added[0].title, "Task 5");
+        assert_eq!(
+            delta.to_string(),
+            "1 new (#5); 1 gone (#1); 1 blocked (#2); 1 unblocked (#3)"
+        );
+        assert!(QueueDelta::between(&after, &after).is_empty());
+    }
+}
@@ -1,10 +1,10 @@
 use std::collections::HashMap;
-use std::process::Stdio;
 
 use tracing::info;
 
 use crate::config::Config;
-use crate::error::{Error, Result};
+use crate::error::Result;
+use crate::interactive::{interactive_command, run_interactive};
 use crate::prompts::PromptEngine;
 
 const PROMPT_OVERRIDE_DIR: &str = ".rlph/prompts";
@@ -39,8 +39,6 @@ pub fn build_prd_command(
     rendered_prompt: &str,
     description: Option<&str>,
 ) -> (String, Vec<String>) {
-    let mut args = Vec::new();
-
     // Build the initial user prompt: template + optional description.
     // Passed as a positional argument so the session stays interactive.
     let prompt = match description {
@@ -50,14 +48,7 @@ pub fn build_prd_command(
         _ => rendered_prompt.to_string(),
     };
 
-    if let Some(ref model) = config.agent_model {
-        args.push("--model".to_string());
-        args.push(model.clone());
-    }
-
-    args.push(prompt);
-
-    (config.agent_binary.clone(), args)
+    interactive_command(config, &prompt)
 }
 
 /// Run an interactive PRD session.
@@ -70,7 +61,8 @@ pub async fn run_prd(config: &Config, description: Option<&str>) -> Result<i32>
         override_dir
             .is_dir()
             .then(|| override_dir.to_string_lossy().to_string()),
-    );
+    )
+    .with_version(&config.prompt_version);
 
     let mut vars = HashMap::new();
     vars.insert(
@@ -82,31 +74,8 @@ pub async fn run_prd(config: &Config, description: Option<&str>) -> Result<i32>
 
     let (binary, args) = build_prd_command(config, &rendered, description);
 
-    info!(
-        binary = %binary,
-        args = ?args.iter().take(3).collect::<Vec<_>>(),
-        "launching interactive PRD session"
-    );
-
-    let mut cmd = tokio::process::Command::new(&binary);
-    cmd.args(&args)
-        .stdin(Stdio::inherit())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit());
-
-    // Remove CLAUDECODE env var to allow nested CLI invocation.
-    cmd.env_remove("CLAUDECODE");
-
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| Error::Process(format!("failed to spawn '{}': {e}", binary)))?;
-
-    let status = child
-        .wait()
-        .await
-        .map_err(|e| Error::Process(format!("failed to wait for agent: {e}")))?;
-
-    Ok(status.code().unwrap_or(1))
+    info!("launching interactive PRD session");
+    run_interactive(&binary, &args, None).await
 }
 
 #[cfg(test)]
@@ -203,7 +172,9 @@ mod tests {
     }
 
     fn test_config(binary: &str, source: &str, model: Option<&str>) -> Config {
-        use crate::config::{default_review_phases, default_review_step};
+        use crate::config::{
+            PrioritySignal, default_hold_labels, default_review_phases, default_review_step,
+        };
         use crate::runner::RunnerKind;
         Config {
             source: source.to_string(),
@@ -211,25 +182,101 @@ mod tests {
             submission: "github".to_string(),
             label: "rlph".to_string(),
             poll_seconds: 30,
+            poll_max_seconds: 600,
             worktree_dir: "../wt".to_string(),
             base_branch: "main".to_string(),
+            detect_base_branch: false,
+            base_branches: Default::default(),
             max_iterations: None,
             dry_run: false,
             once: false,
             continuous: false,
+            quiet: false,
+            interactive: false,
             agent_binary: binary.to_string(),
             agent_model: model.map(str::to_string),
             agent_timeout: Some(600),
             implement_timeout: Some(1800),
             agent_effort: Some("high".to_string()),
             agent_variant: None,
+            agent_temperature: None,
+            agent_extra_args: Vec::new(),
+            agent_identity: Default::default(),
             max_review_rounds: 3,
+            review_round_policy: Default::default(),
             agent_timeout_retries: 2,
             review_phases: default_review_phases(),
+            review_phase_filter: None,
+            skip_review: false,
             review_aggregate: default_review_step("review-aggregate"),
             review_fix: default_review_step("review-fix"),
             fix: default_review_step("fix"),
+            pr_feedback: false,
+            hold_labels: default_hold_labels(),
+            estimate: None,
+            max_complexity: None,
+            agent_language: None,
+            translate: None,
+            followup_issues: false,
+            followup_label: "rlph-followup".to_string(),
+            branch_template: crate::worktree::DEFAULT_BRANCH_TEMPLATE.to_string(),
+            pr_title_template: crate::submission::DEFAULT_PR_TITLE_TEMPLATE.to_string(),
+            pr_body_template: crate::submission::DEFAULT_PR_BODY_TEMPLATE.to_string(),
+            shared_cache_dirs: vec![],
+            conventions_files: vec![],
+            protected_paths: vec![],
+            cache_dir: ".rlph-cache".to_string(),
+            finding_required_fields: vec![],
+            prompt_version: crate::prompts::PROMPT_VERSION.to_string(),
+            finding_template: None,
+            suggestion_comments: false,
+            review_lessons: true,
+            issue_pr_comments: false,
+            test_plan: false,
+            differential_review: false,
+            untrusted_sources: false,
+            allow_dangerous_permissions: false,
+            permission_mode: crate::runner::PermissionMode::Full,
+            max_task_attempts: 3,
+            retry_backoff_seconds: 300,
+            closed_tasks_ttl_seconds: 86_400,
+            refresh_deps: false,
+            redo: false,
+            abandon_after_days: None,
+            heartbeat_minutes: None,
+            max_worktree_disk_mb: None,
+            digest_issue: None,
+            failed_label: "rlph-failed".to_string(),
+            on_error: crate::config::OnError::Stop,
+            claim_strategy: crate::config::ClaimStrategy::Label,
+            review_concurrency: None,
+            review_output: crate::stream::StreamMode::Interleaved,
+            review_target: crate::config::ReviewTarget::PrComment,
+            findings_export: None,
+            runner_probe: false,
+            push_remote: "origin".to_string(),
+            pr_head_owner: None,
+            github_rps: crate::gh_quota::DEFAULT_GITHUB_RPS,
+            github_transport: crate::config::GitHubTransport::Auto,
+            git_identity: Default::default(),
+            choose_strategy: crate::config::ChooseStrategy::Agent,
+            choose_max_tasks: 30,
+            choose_body_chars: 1000,
+            priority_policy: Default::default(),
+            priority_signal: vec![PrioritySignal::Labels],
+            hooks: Default::default(),
+            cleanup: Default::default(),
+            preprocess: Default::default(),
+            permalink_context: true,
+            fix_trailers: Default::default(),
+            squash: Default::default(),
+            implement_slices: None,
+            toolchain: Default::default(),
             linear: None,
+            bitbucket: None,
+            trello: None,
+            gitea: None,
+            github_project: None,
         }
     }
 
@@ -0,0 +1,394 @@
+//! Configurable transformations of task bodies before they reach a prompt.
+//!
+//! `[preprocess] steps` lists the steps to run, in order. Each step rewrites
+//! the raw body; sanitization still runs afterwards, so nothing a step
+//! inlines can escape the `<untrusted-content>` block.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+use tracing::debug;
+
+use crate::config::GitHubTransport;
+use crate::error::{Error, Result};
+use crate::gh_quota::gh_output;
+use crate::github_api::{GitHubApi, use_api};
+use crate::sanitize::neutralize;
+use crate::sources::TaskSource;
+
+/// Most distinct `#N` references looked up per body, to bound API calls.
+const MAX_REFERENCE_LOOKUPS: usize = 10;
+
+/// Most permalinks quoted in `referenced_code`.
+const MAX_REFERENCED_SNIPPETS: usize = 5;
+
+static HTML_COMMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<!--.*?-->").expect("valid HTML comment regex"));
+
+/// A GitHub blob link pinned to a commit, with a line anchor.
+static PERMALINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"https://github\.com/([\w.-]+)/([\w.-]+)/blob/([0-9a-f]{7,40})/([^\s#?)]+)#L(\d+)(?:-L(\d+))?",
+    )
+    .expect("valid permalink regex")
+});
+
+/// `#N` not preceded by a word character, `&` (HTML entities) or `/` (URLs),
+/// and not already followed by a title.
+static REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(^|[^\w&/])#(\d+)\b( \()?").expect("valid reference regex"));
+
+/// One body transformation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreprocessStep {
+    /// Remove `<!-- ... -->` blocks, so later steps do not act on hidden text.
+    StripHtmlComments,
+    /// Quote the lines a commit-pinned GitHub permalink points at below it.
+    InlinePermalinks,
+    /// Follow each `#N` with the referenced task's title.
+    ExpandReferences,
+}
+
+impl std::str::FromStr for PreprocessStep {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "strip_html_comments" => Ok(PreprocessStep::StripHtmlComments),
+            "inline_permalinks" => Ok(PreprocessStep::InlinePermalinks),
+            "expand_references" => Ok(PreprocessStep::ExpandReferences),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown preprocess step: {other} (expected: strip_html_comments, inline_permalinks, expand_references)"
+            ))),
+        }
+    }
+}
+
+/// A permalink found in a body: `owner/repo`, commit, path and line range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permalink {
+    pub repo: String,
+    pub sha: String,
+    pub path: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Every commit-pinned permalink in `text`, in order, without duplicates.
+pub fn find_permalinks(text: &str) -> Vec<(String, Permalink)> {
+    let mut links: Vec<(String, Permalink)> = Vec::new();
+    for caps in PERMALINK.captures_iter(text) {
+        let url = caps[0].to_string();
+        if links.iter().any(|(seen, _)| *seen == url) {
+            continue;
+        }
+        let start: usize = caps[5].parse().unwrap_or(1).max(1);
+        let end = caps
+            .get(6)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(start)
+            .max(start);
+        links.push((
+            url,
+            Permalink {
+                repo: format!("{}/{}", &caps[1], &caps[2]),
+                sha: caps[3].to_string(),
+                path: caps[4].to_string(),
+                start,
+                end,
+            },
+        ));
+    }
+    links
+}
+
+/// Lookups the steps need outside the body itself.
+pub trait PreprocessContext {
+    /// Contents of the permalink's file at its commit.
+    fn file_at(&self, link: &Permalink) -> Option<String>;
+    /// Title of task `number` in the task source.
+    fn task_title(&self, number: u64) -> Option<String>;
+}
+
+/// Settings for the `[preprocess]` config section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreprocessConfig {
+    pub steps: Vec<PreprocessStep>,
+    /// Longest snippet `inline_permalinks` quotes; longer ranges are cut.
+    pub max_snippet_lines: usize,
+}
+
+impl Default for PreprocessConfig {
+    fn default() -> Self {
+        Self {
+            steps: Vec::new(),
+            max_snippet_lines: 40,
+        }
+    }
+}
+
+/// Run the configured steps over `body`, in order.
+pub fn preprocess_body(
+    body: &str,
+    config: &PreprocessConfig,
+    ctx: &dyn PreprocessContext,
+) -> String {
+    config
+        .steps
+        .iter()
+        .fold(body.to_string(), |text, step| match step {
+            PreprocessStep::StripHtmlComments => HTML_COMMENT.replace_all(&text, "").into_owned(),
+            PreprocessStep::InlinePermalinks => {
+                inline_permalinks(&text, config.max_snippet_lines, ctx)
+            }
+            PreprocessStep::ExpandReferences => expand_references(&text, ctx),
+        })
+}
+
+/// A quoted snippet of the lines `link` points at, or `None` when the file
+/// cannot be fetched or the range is past its end.
+pub fn permalink_snippet(
+    link: &Permalink,
+    max_lines: usize,
+    ctx: &dyn PreprocessContext,
+) -> Option<String> {
+    let content = ctx.file_at(link)?;
+    let lines: Vec<&str> = content
+        .lines()
+        .skip(link.start - 1)
+        .take(link.end - link.start + 1)
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    let shown = &lines[..lines.len().min(max_lines)];
+    let lang = Path::new(&link.path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    let range = if link.end > link.start {
+        format!("L{}-L{}", link.start, link.end)
+    } else {
+        format!("L{}", link.start)
+    };
+    let short_sha = &link.sha[..link.sha.len().min(12)];
+    let mut snippet = format!(
+        "`{}` {range} at {short_sha}:\n```{lang}\n{}\n```",
+        link.path,
+        shown.join("\n")
+    );
+    if shown.len() < lines.len() {
+        snippet.push_str(&format!(
+            "\n[{} of {} lines shown]",
+            shown.len(),
+            lines.len()
+        ));
+    }
+    Some(snippet)
+}
+
+/// The `referenced_code` prompt variable for a raw task body. Empty when
+/// `enabled` is off, or when the `inline_permalinks` step already quoted the
+/// links in the body. Links hidden in HTML comments are skipped.
+pub fn task_referenced_code(
+    body: &str,
+    enabled: bool,
+    config: &PreprocessConfig,
+    ctx: &dyn PreprocessContext,
+) -> String {
+    if !enabled || config.steps.contains(&PreprocessStep::InlinePermalinks) {
+        return String::new();
+    }
+    let visible = HTML_COMMENT.replace_all(body, "");
+    referenced_code(&visible, config.max_snippet_lines, ctx)
+}
+
+/// Snippets for up to [`MAX_REFERENCED_SNIPPETS`] permalinks in `text`. Empty
+/// when there are none or none resolve.
+pub fn referenced_code(text: &str, max_lines: usize, ctx: &dyn PreprocessContext) -> String {
+    find_permalinks(text)
+        .iter()
+        .take(MAX_REFERENCED_SNIPPETS)
+        .filter_map(|(url, link)| {
+            let snippet = permalink_snippet(link, max_lines, ctx);
+            if snippet.is_none() {
+                debug!(url, "permalink could not be resolved");
+            }
+            snippet
+        })
+        .map(|snippet| neutralize(&snippet))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Put a snippet for each permalink after the line containing it.
+fn inline_permalinks(text: &str, max_lines: usize, ctx: &dyn PreprocessContext) -> String {
+    let mut inlined: Vec<String> = Vec::new();
+    let mut out = Vec::new();
+    for line in text.lines() {
+        out.push(line.to_string());
+        for (url, link) in find_permalinks(line) {
+            if inlined.contains(&url) {
+                continue;
+            }
+            match permalink_snippet(&link, max_lines, ctx) {
+                Some(snippet) => {
+                    out.push(String::new());
+                    out.push(snippet);
+                    out.push(String::new());
+                    inlined.push(url);
+                }
+                None => debug!(url, "permalink could not be resolved"),
+            }
+        }
+    }
+    out.join("\n")
+}
+
+/// Rewrite `#N` as `#N (Title)` for up to [`MAX_REFERENCE_LOOKUPS`] tasks.
+fn expand_references(text: &str, ctx: &dyn PreprocessContext) -> String {
+    let mut titles: HashMap<u64, Option<String>> = HashMap::new();
+    REFERENCE
+        .replace_all(text, |caps: &Captures| {
+            let original = caps[0].to_string();
+            if caps.get(3).is_some() {
+                return original;
+            }
+            let Ok(number) = caps[2].parse::<u64>() else {
+                return original;
+            };
+            if !titles.contains_key(&number) && titles.len() >= MAX_REFERENCE_LOOKUPS {
+                return original;
+            }
+            let title = titles
+                .entry(number)
+                .or_insert_with(|| ctx.task_title(number));
+            match title {
+                Some(title) => format!("{}#{number} ({})", &caps[1], title.trim()),
+                None => original,
+            }
+        })
+        .into_owned()
+}
+
+/// [`PreprocessContext`] over a task source and the local checkout. Files are
+/// read from the local repo when it has the commit, and otherwise from GitHub,
+/// through `gh api` or the REST API per `transport`.
+pub struct SourceContext<'a, S> {
+    source: &'a S,
+    repo_root: &'a Path,
+    transport: GitHubTransport,
+}
+
+impl<'a, S> SourceContext<'a, S> {
+    pub fn new(source: &'a S, repo_root: &'a Path, transport: GitHubTransport) -> Self {
+        Self {
+            source,
+            repo_root,
+            transport,
+        }
+    }
+}
+
+impl<S: TaskSource> PreprocessContext for SourceContext<'_, S> {
+    fn file_at(&self, link: &Permalink) -> Option<String> {
+        let object = format!("{}:{}", link.sha, link.path);
+        let local = Command::new("git")
+            .args(["show", &object])
+            .current_dir(self.repo_root)
+            .output()
+            .ok()
+            .filter(|o| o.status.success());
+        if let Some(output) = local {
+            return Some(String::from_utf8_lossy(&output.stdout).into_owned());
+        }
+        if use_api(self.transport) {
+            return GitHubApi::from_env()
+                .ok()?
+                .file_at(&link.repo, &link.path, &link.sha)
+                .inspect_err(|e| debug!(error = %e, "failed to fetch permalinked file"))
+                .ok();
+        }
+        let endpoint = format!(
+            "repos/{}/contents/{}?ref={}",
+            link.repo, link.path, link.sha
+        );
+        let output = gh_output(Command::new("gh").args([
+            "api",
+            "-H",
+            "Accept: application/vnd.github.raw",
+            &endpoint,
+        ]))
+        .ok()
+        .filter(|o| o.status.success())?;
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn task_title(&self, number: u64) -> Option<String> {
+        self.source
+            .get_task_details(&number.to_string())
+            .map(|task| task.title)
+            .inspect_err(|e| debug!(number, error = %e, "referenced task not found"))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeContext;
+
+    impl PreprocessContext for FakeContext {
+        fn file_at(&self, link: &Permalink) -> Option<String> {
+            (link.path == "src/lib.rs").then(|| "one\ntwo\nthree\nfour\n".to_string())
+        }
+
+        fn task_title(&self, number: u64) -> Option<String> {
+            (number == 7).then(|| "Add retries".to_string())
+        }
+    }
+
+    #[test]
+    fn test_preprocess_body_runs_steps_in_order() {
+        let body = "See https://github.com/acme/app/blob/0123abcd/src/lib.rs#L2-L4 and #7, #8.\n\
+                    <!-- https://github.com/acme/app/blob/0123abcd/src/lib.rs#L1 -->\n\
+                    Missing: https://github.com/acme/app/blob/0123abcd/src/gone.rs#L1 &#39;";
+        let config = PreprocessConfig {
+            steps: vec![
+                PreprocessStep::StripHtmlComments,
+                PreprocessStep::InlinePermalinks,
+                PreprocessStep::ExpandReferences,
+            ],
+            max_snippet_lines: 2,
+        };
+        assert_eq!(
+            preprocess_body(body, &config, &FakeContext),
+            "See https://github.com/acme/app/blob/0123abcd/src/lib.rs#L2-L4 and #7 (Add retries), #8.\n\
+             \n\
+             `src/lib.rs` L2-L4 at 0123abcd:\n```rs\ntwo\nthree\n```\n[2 of 3 lines shown]\n\
+             \n\
+             \n\
+             Missing: https://github.com/acme/app/blob/0123abcd/src/gone.rs#L1 &#39;"
+        );
+        assert_eq!(
+            preprocess_body(body, &PreprocessConfig::default(), &FakeContext),
+            body
+        );
+    }
+
+    #[test]
+    fn test_referenced_code_quotes_resolvable_links() {
+        let body = "Broken at https://github.com/acme/app/blob/0123abcd/src/lib.rs#L3 \
+                    (not https://github.com/acme/app/blob/main/src/lib.rs#L3), \
+                    see also https://github.com/acme/app/blob/0123abcd/src/gone.rs#L1";
+        assert_eq!(
+            referenced_code(body, 40, &FakeContext),
+            "`src/lib.rs` L3 at 0123abcd:\n```rs\nthree\n```"
+        );
+        assert_eq!(referenced_code("No links here.", 40, &FakeContext), "");
+    }
+}
@@ -185,6 +185,13 @@ pub async fn spawn_and_stream(config: ProcessConfig) -> Result<ProcessOutput> {
     });
 
     let mut wait_task = tokio::spawn(async move { child.wait().await });
+    #[cfg(unix)]
+    let mut kill_guard = KillOnDrop {
+        pid: pid as i32,
+        use_process_group,
+        log_prefix: log_prefix.clone(),
+        armed: true,
+    };
 
     #[cfg(unix)]
     let status_result = wait_for_exit_unix(
@@ -197,6 +204,10 @@ pub async fn spawn_and_stream(config: ProcessConfig) -> Result<ProcessOutput> {
     .await;
     #[cfg(not(unix))]
     let status_result = wait_for_exit_non_unix(config.timeout, &mut wait_task).await;
+    #[cfg(unix)]
+    {
+        kill_guard.armed = false;
+    }
 
     heartbeat_task.abort();
 
@@ -537,6 +548,34 @@ async fn handle_timeout_unix(
     })
 }
 
+/// SIGKILLs the child, and its process group when it has one, if
+/// [`spawn_and_stream`] is dropped before the child exits, as when a disk
+/// quota cancels an agent run. The child is owned by a detached wait task, so
+/// `kill_on_drop` alone would not stop it, and would never reach descendants.
+#[cfg(unix)]
+struct KillOnDrop {
+    pid: i32,
+    use_process_group: bool,
+    log_prefix: String,
+    armed: bool,
+}
+
+#[cfg(unix)]
+impl Drop for KillOnDrop {
+    fn drop(&mut self) {
+        if self.armed {
+            warn!(prefix = %self.log_prefix, child_pid = self.pid, "run cancelled, sending SIGKILL");
+            send_signal_unix(
+                self.pid,
+                libc::SIGKILL,
+                &self.log_prefix,
+                "SIGKILL",
+                self.use_process_group,
+            );
+        }
+    }
+}
+
 #[cfg(unix)]
 fn send_signal_unix(
     child_pid: i32,
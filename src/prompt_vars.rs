@@ -0,0 +1,557 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::error::{Error, Result};
+
+/// A variable a prompt template can reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromptVar {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+const fn var(name: &'static str, description: &'static str) -> PromptVar {
+    PromptVar { name, description }
+}
+
+/// Injected into every phase by `PromptEngine` (empty unless noted).
+const BUILTIN_VARS: &[PromptVar] = &[
+    var(
+        "findings_schema",
+        "Instructions for the findings JSON, including required extension fields",
+    ),
+    var(
+        "ignored_paths",
+        "Paths matching `.rlphignore`, one per line",
+    ),
+    var(
+        "diff_excludes",
+        "`git diff` pathspecs excluding `.rlphignore` paths, with a leading space",
+    ),
+    var(
+        "repo_conventions",
+        "Contents of the configured `conventions_files`",
+    ),
+    var(
+        "project_toolchain",
+        "Detected project type and build/test/lint commands",
+    ),
+];
+
+const ISSUE_VARS: &[PromptVar] = &[
+    var("issue_title", "Task title (untrusted, sanitized)"),
+    var("issue_body", "Task description (untrusted, sanitized)"),
+    var("issue_number", "Task id in the source, e.g. `42`"),
+    var("issue_url", "Task URL"),
+    var(
+        "issue_assignee",
+        "Assignee in the source; empty when unassigned",
+    ),
+    var(
+        "issue_estimate",
+        "Size estimate set in the source (e.g. Linear points); empty when unset",
+    ),
+    var(
+        "issue_project",
+        "Project the task is filed under; empty when unknown",
+    ),
+    var("issue_milestone", "Milestone of the task; empty when unset"),
+    var(
+        "issue_created_at",
+        "When the task was created (ISO 8601); empty when unknown",
+    ),
+    var(
+        "issue_updated_at",
+        "When the task was last updated (ISO 8601); empty when unknown",
+    ),
+    var("repo_path", "Path of the main checkout"),
+];
+
+const LANGUAGE_VARS: &[PromptVar] = &[var(
+    "issue_language",
+    "Language the task was written in when it was translated into `agent_language`; empty otherwise",
+)];
+
+const WORKTREE_VARS: &[PromptVar] = &[
+    var("branch_name", "Task branch"),
+    var("worktree_path", "Path of the task worktree"),
+    var("base_branch", "Branch the PR targets"),
+    var(
+        "previous_attempts",
+        "Summary of earlier failed attempts at the task",
+    ),
+    var(
+        "referenced_code",
+        "Code behind commit-pinned GitHub permalinks in the task body, as quoted snippets",
+    ),
+    var(
+        "past_lessons",
+        "Review findings fixed on earlier tasks in this repo (`.rlph/lessons.md`)",
+    ),
+    var(
+        "test_plan_instructions",
+        "How to report a test plan; empty unless `test_plan` is enabled",
+    ),
+    var(
+        "test_plan",
+        "Numbered test plan the implement agent reported; empty until it has, or unless `test_plan` is enabled",
+    ),
+    var("pr_number", "PR number; empty until a PR exists"),
+    var(
+        "pr_branch",
+        "PR head branch of an existing PR; empty otherwise",
+    ),
+    var("pr_url", "PR URL; empty until a PR exists"),
+];
+
+const DIFF_VARS: &[PromptVar] = &[var(
+    "affected_crates",
+    "Cargo workspace members the change touches, comma-separated; empty outside a workspace",
+)];
+
+const REVIEW_VARS: &[PromptVar] = &[
+    var("review_phase_name", "Name of the running review phase"),
+    var(
+        "review_since",
+        "Commit the last review covered, when only changes since it are reviewed; empty unless `differential_review` applies",
+    ),
+    var("pr_comments", "Existing PR comments"),
+    var(
+        "has_pr_comments",
+        "Non-empty when there are PR comments, for `{% if %}`",
+    ),
+    var(
+        "context_diff",
+        "Branch diff against its base; empty unless the phase's `context` lists `diff`",
+    ),
+    var(
+        "context_full_files",
+        "Contents of every changed file; empty unless `context` lists `full_files`",
+    ),
+    var(
+        "context_blame",
+        "`git blame` of the lines the branch modifies or removes; empty unless `context` lists `blame`",
+    ),
+];
+
+const AGGREGATE_VARS: &[PromptVar] = &[
+    var(
+        "review_outputs",
+        "Findings of every review phase this round",
+    ),
+    var(
+        "review_since",
+        "Commit the last review covered, when this round's reviews only cover changes since it; empty otherwise",
+    ),
+    var(
+        "previous_findings",
+        "Findings of the last review, to carry forward; empty unless `review_since` is set",
+    ),
+    var("pr_comments", "Existing PR comments"),
+    var(
+        "automated_checks",
+        "Pass/fail of command review phases and the PR's CI checks, with failing findings and check names",
+    ),
+];
+
+const REVIEW_FIX_VARS: &[PromptVar] = &[var(
+    "fix_instructions",
+    "Findings or PR feedback to address",
+)];
+
+const CHOOSE_VARS: &[PromptVar] = &[
+    var("repo_path", "Path of the main checkout"),
+    var(
+        "issues_json",
+        "Eligible tasks, one compact JSON object per line",
+    ),
+];
+
+const FIX_VARS: &[PromptVar] = &[
+    var("finding_id", "Finding id"),
+    var("finding_file", "File the finding is in"),
+    var("finding_line", "Line of the finding"),
+    var("finding_severity", "`CRITICAL`, `WARNING`, or `INFO`"),
+    var("finding_description", "What is wrong"),
+    var(
+        "finding_depends_on",
+        "Comma-separated ids of findings this one depends on",
+    ),
+];
+
+const TRANSLATE_VARS: &[PromptVar] = &[var(
+    "agent_language",
+    "Language prompts are written in, e.g. `English`",
+)];
+
+const PRD_VARS: &[PromptVar] = &[var(
+    "submission_instructions",
+    "How to file the finished PRD with the configured source",
+)];
+
+/// Added to the task vars for `pr_title_template` / `pr_body_template`.
+const PR_VARS: &[PromptVar] = &[
+    var("issue_labels", "Task labels, comma-separated"),
+    var(
+        "estimate_complexity",
+        "Complexity from the estimate phase; empty unless estimation is enabled",
+    ),
+    var(
+        "estimate_risk_notes",
+        "Risk notes from the estimate phase; empty unless estimation is enabled",
+    ),
+    var(
+        "review_summary",
+        "Approved review's summary; empty until the review passes",
+    ),
+    var(
+        "usage_input_tokens",
+        "Prompt tokens the task's agent phases have used so far",
+    ),
+    var(
+        "usage_output_tokens",
+        "Output tokens the task's agent phases have used so far",
+    ),
+    var("usage_total_tokens", "Input plus output tokens used so far"),
+    var(
+        "usage_agent_seconds",
+        "Time spent in the task's agent phases so far, in seconds",
+    ),
+];
+
+/// Every variable a PR title or body template can reference, in documentation
+/// order.
+pub fn pr_vars() -> Vec<PromptVar> {
+    let mut seen = BTreeSet::new();
+    [ISSUE_VARS, LANGUAGE_VARS, WORKTREE_VARS, PR_VARS]
+        .into_iter()
+        .flatten()
+        .filter(|v| seen.insert(v.name))
+        .copied()
+        .collect()
+}
+
+/// Reject a PR title or body template referencing variables rlph does not
+/// provide to it.
+pub fn check_pr_template(template: &str) -> Result<()> {
+    let unknown = unknown_vars(&pr_vars(), template);
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(Error::Prompt(format!(
+        "template uses {} not provided to PR templates",
+        format_names(&unknown)
+    )))
+}
+
+/// The kinds of prompt rlph renders, each with a fixed set of variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptPhase {
+    Choose,
+    Implement,
+    /// Any `[[review_phases]]` agent prompt.
+    Review,
+    ReviewAggregate,
+    ReviewFix,
+    Fix,
+    Estimate,
+    Translate,
+    Prd,
+}
+
+impl PromptPhase {
+    pub const ALL: [PromptPhase; 9] = [
+        PromptPhase::Choose,
+        PromptPhase::Implement,
+        PromptPhase::Review,
+        PromptPhase::ReviewAggregate,
+        PromptPhase::ReviewFix,
+        PromptPhase::Fix,
+        PromptPhase::Estimate,
+        PromptPhase::Translate,
+        PromptPhase::Prd,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PromptPhase::Choose => "choose",
+            PromptPhase::Implement => "implement",
+            PromptPhase::Review => "review",
+            PromptPhase::ReviewAggregate => "review-aggregate",
+            PromptPhase::ReviewFix => "review-fix",
+            PromptPhase::Fix => "fix",
+            PromptPhase::Estimate => "estimate",
+            PromptPhase::Translate => "translate",
+            PromptPhase::Prd => "prd",
+        }
+    }
+
+    /// The phase a built-in prompt name belongs to. Custom prompt names have none.
+    pub fn of_prompt(prompt: &str) -> Option<Self> {
+        match prompt {
+            "correctness-review" | "security-review" | "hygiene-review" | "unsafe-review"
+            | "perf-review" => Some(PromptPhase::Review),
+            other => other.parse().ok(),
+        }
+    }
+
+    /// Every variable the phase receives, in documentation order.
+    pub fn vars(self) -> Vec<PromptVar> {
+        let groups: &[&[PromptVar]] = match self {
+            PromptPhase::Choose => &[CHOOSE_VARS],
+            PromptPhase::Implement => &[ISSUE_VARS, LANGUAGE_VARS, WORKTREE_VARS],
+            PromptPhase::Review => &[
+                ISSUE_VARS,
+                LANGUAGE_VARS,
+                WORKTREE_VARS,
+                DIFF_VARS,
+                REVIEW_VARS,
+            ],
+            PromptPhase::ReviewAggregate => &[
+                ISSUE_VARS,
+                LANGUAGE_VARS,
+                WORKTREE_VARS,
+                DIFF_VARS,
+                AGGREGATE_VARS,
+            ],
+            PromptPhase::ReviewFix => &[
+                ISSUE_VARS,
+                LANGUAGE_VARS,
+                WORKTREE_VARS,
+                DIFF_VARS,
+                REVIEW_FIX_VARS,
+            ],
+            PromptPhase::Fix => &[FIX_VARS, DIFF_VARS],
+            PromptPhase::Estimate => &[ISSUE_VARS],
+            PromptPhase::Translate => &[ISSUE_VARS, TRANSLATE_VARS],
+            PromptPhase::Prd => &[PRD_VARS],
+        };
+        let mut seen = BTreeSet::new();
+        groups
+            .iter()
+            .copied()
+            .chain(std::iter::once(BUILTIN_VARS))
+            .flatten()
+            .filter(|v| seen.insert(v.name))
+            .copied()
+            .collect()
+    }
+
+    /// Reject a template referencing variables the phase does not provide.
+    pub fn check_template(self, prompt: &str, template: &str) -> Result<()> {
+        let unknown = unknown_vars(&self.vars(), template);
+        if unknown.is_empty() {
+            return Ok(());
+        }
+        Err(Error::Prompt(format!(
+            "prompt '{prompt}' uses {} not provided to the {} phase (see `rlph prompts vars {}`)",
+            format_names(&unknown),
+            self.name(),
+            self.name()
+        )))
+    }
+}
+
+impl FromStr for PromptPhase {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        PromptPhase::ALL
+            .into_iter()
+            .find(|p| p.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = PromptPhase::ALL.iter().map(|p| p.name()).collect();
+                Error::Prompt(format!(
+                    "unknown prompt phase: {s} (expected: {})",
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+impl fmt::Display for PromptPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Variables `template` references that are not among `known`.
+fn unknown_vars(known: &[PromptVar], template: &str) -> Vec<String> {
+    let known: BTreeSet<&str> = known.iter().map(|v| v.name).collect();
+    referenced_vars(template)
+        .into_iter()
+        .filter(|name| !known.contains(name.as_str()))
+        .collect()
+}
+
+fn format_names(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|n| format!("`{n}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Render the `rlph prompts vars` listing for `phase`.
+pub fn format_phase_vars(phase: PromptPhase) -> String {
+    let vars = phase.vars();
+    let width = vars.iter().map(|v| v.name.len()).max().unwrap_or(0);
+    vars.iter()
+        .map(|v| format!("{:width$}  {}\n", v.name, v.description))
+        .collect()
+}
+
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)\{\{-?(.*?)-?\}\}|\{%-?(.*?)-?%\}").expect("valid tag regex")
+});
+static IDENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#""(?:[^"\\]|\\.)*"|\.?[A-Za-z_][A-Za-z0-9_]*"#).expect("valid identifier regex")
+});
+
+/// Top-level variables a template references in `{{ }}` expressions and
+/// `{% if %}` / `{% for %}` / `{% with %}` blocks. Loop and `with` bindings,
+/// members (`a.b`), filters, and literals are not counted.
+pub fn referenced_vars(template: &str) -> BTreeSet<String> {
+    let mut referenced = BTreeSet::new();
+    let mut bound = BTreeSet::new();
+    for caps in TAG_RE.captures_iter(template) {
+        let (expr, is_block) = match (caps.get(1), caps.get(2)) {
+            (Some(expr), _) => (expr.as_str().trim(), false),
+            (_, Some(block)) => (block.as_str().trim(), true),
+            _ => continue,
+        };
+        let expr = if is_block {
+            let (keyword, rest) = expr.split_once(char::is_whitespace).unwrap_or((expr, ""));
+            match keyword {
+                "if" | "elif" => rest,
+                "for" => match rest.split_once(" in ") {
+                    Some((bindings, iterable)) => {
+                        bound.extend(bindings.split(',').map(|b| b.trim().to_string()));
+                        iterable
+                    }
+                    None => continue,
+                },
+                "with" => match rest.rsplit_once(" as ") {
+                    Some((value, binding)) => {
+                        bound.insert(binding.trim().to_string());
+                        value
+                    }
+                    None => continue,
+                },
+                _ => continue,
+            }
+        } else {
+            expr
+        };
+        let expr = expr.split('|').next().unwrap_or_default();
+        for token in IDENT_RE.find_iter(expr).map(|m| m.as_str()) {
+            if token.starts_with(['"', '.']) || matches!(token, "not" | "true" | "false") {
+                continue;
+            }
+            referenced.insert(token.to_string());
+        }
+    }
+    referenced.retain(|name| !bound.contains(name));
+    referenced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompts::PromptEngine;
+
+    #[test]
+    fn test_referenced_vars() {
+        let template = r#"{{ issue_title }} {{pr_url}} {%- if not has_pr_comments -%}
+{% for item, n in tasks %}{{ item.name | upper }}{{ n }}{% endfor %}
+{% with pr.number as num %}{{ num }}{% endwith %}{{ "literal" }}{% endif %}"#;
+        let vars: Vec<String> = referenced_vars(template).into_iter().collect();
+        assert_eq!(
+            vars,
+            ["has_pr_comments", "issue_title", "pr", "pr_url", "tasks"]
+        );
+    }
+
+    #[test]
+    fn test_default_templates_use_only_registered_vars() {
+        let engine = PromptEngine::new(None);
+        let prompts = [
+            ("choose", PromptPhase::Choose),
+            ("implement", PromptPhase::Implement),
+            ("correctness-review", PromptPhase::Review),
+            ("security-review", PromptPhase::Review),
+            ("hygiene-review", PromptPhase::Review),
+            ("unsafe-review", PromptPhase::Review),
+            ("perf-review", PromptPhase::Review),
+            ("review-aggregate", PromptPhase::ReviewAggregate),
+            ("review-fix", PromptPhase::ReviewFix),
+            ("fix", PromptPhase::Fix),
+            ("estimate", PromptPhase::Estimate),
+            ("translate", PromptPhase::Translate),
+            ("prd", PromptPhase::Prd),
+        ];
+        for (prompt, phase) in prompts {
+            assert_eq!(PromptPhase::of_prompt(prompt), Some(phase), "{prompt}");
+            let template = engine.load_template(prompt).unwrap();
+            phase
+                .check_template(prompt, &template)
+                .unwrap_or_else(|e| panic!("{e}"));
+        }
+    }
+
+    #[test]
+    fn test_check_template_names_unknown_vars() {
+        let err = PromptPhase::Fix
+            .check_template("fix", "{{ finding_id }} {{ issue_title }} {{ branch }}")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "prompt error: prompt 'fix' uses `branch`, `issue_title` not provided to the fix phase (see `rlph prompts vars fix`)"
+        );
+    }
+
+    #[test]
+    fn test_check_pr_template() {
+        use crate::submission::{DEFAULT_PR_BODY_TEMPLATE, DEFAULT_PR_TITLE_TEMPLATE};
+
+        check_pr_template(DEFAULT_PR_TITLE_TEMPLATE).unwrap();
+        check_pr_template(DEFAULT_PR_BODY_TEMPLATE).unwrap();
+        check_pr_template(
+            "{{ issue_labels }} {{ review_summary }} {{ usage_total_tokens }} {{ branch_name }}",
+        )
+        .unwrap();
+
+        let err = check_pr_template("{{ issue_number }} {{ tokens }}").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "prompt error: template uses `tokens` not provided to PR templates"
+        );
+        // Prompt-only vars are not available either.
+        assert!(check_pr_template("{{ findings_schema }}").is_err());
+    }
+
+    #[test]
+    fn test_parse_phase_and_format_vars() {
+        assert_eq!(
+            "review-aggregate".parse::<PromptPhase>().unwrap(),
+            PromptPhase::ReviewAggregate
+        );
+        assert!(
+            "deploy"
+                .parse::<PromptPhase>()
+                .unwrap_err()
+                .to_string()
+                .contains("expected: choose, implement, review")
+        );
+        assert_eq!(PromptPhase::of_prompt("team-review"), None);
+
+        let listing = format_phase_vars(PromptPhase::Choose);
+        assert!(listing.starts_with("repo_path          Path of the main checkout\n"));
+        assert!(listing.contains("\nissues_json        Eligible tasks"));
+        assert!(listing.contains("\nfindings_schema    Instructions"));
+    }
+}
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
+use std::fmt::Write;
 use std::path::Path;
 
 use crate::error::{Error, Result};
+use crate::prompt_vars::{PromptPhase, referenced_vars};
 
 const DEFAULT_CHOOSE: &str = include_str!("default_prompts/choose-issue.md");
 const DEFAULT_IMPLEMENT: &str = include_str!("default_prompts/implement-issue.md");
@@ -9,27 +11,107 @@ const DEFAULT_CORRECTNESS_REVIEW: &str =
     include_str!("default_prompts/correctness-review-issue.md");
 const DEFAULT_SECURITY_REVIEW: &str = include_str!("default_prompts/security-review-issue.md");
 const DEFAULT_HYGIENE_REVIEW: &str = include_str!("default_prompts/hygiene-review-issue.md");
+const DEFAULT_UNSAFE_REVIEW: &str = include_str!("default_prompts/unsafe-review-issue.md");
+const DEFAULT_PERF_REVIEW: &str = include_str!("default_prompts/perf-review-issue.md");
 const DEFAULT_REVIEW_AGGREGATE: &str = include_str!("default_prompts/review-aggregate-issue.md");
 const DEFAULT_REVIEW_FIX: &str = include_str!("default_prompts/review-fix-issue.md");
 const DEFAULT_FIX: &str = include_str!("default_prompts/fix-issue.md");
+const DEFAULT_ESTIMATE: &str = include_str!("default_prompts/estimate-issue.md");
+const DEFAULT_TRANSLATE: &str = include_str!("default_prompts/translate-issue.md");
 const DEFAULT_PRD: &str = include_str!("default_prompts/prd.md");
 const FINDINGS_SCHEMA: &str = include_str!("default_prompts/_findings-schema.md");
 
-fn default_template(phase: &str) -> Option<&'static str> {
+// v1 wording of the prompts v2 rewords (test plans, differential review).
+const V1_IMPLEMENT: &str = include_str!("default_prompts/v1/implement-issue.md");
+const V1_CORRECTNESS_REVIEW: &str = include_str!("default_prompts/v1/correctness-review-issue.md");
+const V1_SECURITY_REVIEW: &str = include_str!("default_prompts/v1/security-review-issue.md");
+const V1_HYGIENE_REVIEW: &str = include_str!("default_prompts/v1/hygiene-review-issue.md");
+const V1_UNSAFE_REVIEW: &str = include_str!("default_prompts/v1/unsafe-review-issue.md");
+const V1_PERF_REVIEW: &str = include_str!("default_prompts/v1/perf-review-issue.md");
+const V1_REVIEW_AGGREGATE: &str = include_str!("default_prompts/v1/review-aggregate-issue.md");
+
+/// Version of the built-in prompts and findings schema. Bumped whenever their
+/// wording changes; earlier versions stay embedded so a repo that pins one
+/// with `prompt_version` keeps the prompts it was tuned against.
+pub const PROMPT_VERSION: &str = "v2";
+
+/// Every embedded prompt version, oldest first.
+pub const PROMPT_VERSIONS: [&str; 2] = ["v1", PROMPT_VERSION];
+
+/// Context lines around each change in [`diff_prompt_versions`].
+const DIFF_CONTEXT: usize = 3;
+
+/// Names of the built-in prompts. Each can be overridden by a template named
+/// `<name>-issue.md` (`prd.md` for the PRD prompt) in the override directory.
+pub const BUILTIN_PROMPTS: [&str; 13] = [
+    "choose",
+    "implement",
+    "correctness-review",
+    "security-review",
+    "hygiene-review",
+    "unsafe-review",
+    "perf-review",
+    "review-aggregate",
+    "review-fix",
+    "fix",
+    "estimate",
+    "translate",
+    "prd",
+];
+
+/// Whether `version` is one of [`PROMPT_VERSIONS`].
+pub fn is_prompt_version(version: &str) -> bool {
+    PROMPT_VERSIONS.contains(&version)
+}
+
+fn unknown_version(version: &str) -> Error {
+    Error::Prompt(format!(
+        "unknown prompt version {version:?} (this rlph embeds {})",
+        PROMPT_VERSIONS.join(", ")
+    ))
+}
+
+fn default_template(version: &str, phase: &str) -> Option<&'static str> {
+    if !is_prompt_version(version) {
+        return None;
+    }
+    if version == "v1" {
+        let v1 = match phase {
+            "implement" => Some(V1_IMPLEMENT),
+            "correctness-review" => Some(V1_CORRECTNESS_REVIEW),
+            "security-review" => Some(V1_SECURITY_REVIEW),
+            "hygiene-review" => Some(V1_HYGIENE_REVIEW),
+            "unsafe-review" => Some(V1_UNSAFE_REVIEW),
+            "perf-review" => Some(V1_PERF_REVIEW),
+            "review-aggregate" => Some(V1_REVIEW_AGGREGATE),
+            _ => None,
+        };
+        if v1.is_some() {
+            return v1;
+        }
+    }
     match phase {
         "choose" => Some(DEFAULT_CHOOSE),
         "implement" => Some(DEFAULT_IMPLEMENT),
         "correctness-review" => Some(DEFAULT_CORRECTNESS_REVIEW),
         "security-review" => Some(DEFAULT_SECURITY_REVIEW),
         "hygiene-review" => Some(DEFAULT_HYGIENE_REVIEW),
+        "unsafe-review" => Some(DEFAULT_UNSAFE_REVIEW),
+        "perf-review" => Some(DEFAULT_PERF_REVIEW),
         "review-aggregate" => Some(DEFAULT_REVIEW_AGGREGATE),
         "review-fix" => Some(DEFAULT_REVIEW_FIX),
         "fix" => Some(DEFAULT_FIX),
+        "estimate" => Some(DEFAULT_ESTIMATE),
+        "translate" => Some(DEFAULT_TRANSLATE),
         "prd" => Some(DEFAULT_PRD),
         _ => None,
     }
 }
 
+fn default_findings_schema(version: &str) -> Option<&'static str> {
+    is_prompt_version(version).then_some(FINDINGS_SCHEMA)
+}
+
 fn template_filename(phase: &str) -> String {
     match phase {
         "prd" => "prd.md".to_string(),
@@ -40,11 +122,26 @@ fn template_filename(phase: &str) -> String {
 /// Prompt template engine with default templates and user overrides.
 pub struct PromptEngine {
     override_dir: Option<String>,
+    /// Version of the built-in templates used when there is no override.
+    version: String,
 }
 
 impl PromptEngine {
     pub fn new(override_dir: Option<String>) -> Self {
-        Self { override_dir }
+        Self {
+            override_dir,
+            version: PROMPT_VERSION.to_string(),
+        }
+    }
+
+    /// Use the built-in templates of `version` (see [`PROMPT_VERSIONS`]).
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
     }
 
     /// Load a prompt template for the given phase.
@@ -68,24 +165,194 @@ impl PromptEngine {
         }
 
         // Fall back to embedded default
-        default_template(phase)
+        if !is_prompt_version(&self.version) {
+            return Err(unknown_version(&self.version));
+        }
+        default_template(&self.version, phase)
             .map(|s| s.to_string())
             .ok_or_else(|| Error::Prompt(format!("unknown prompt phase: {phase}")))
     }
 
+    /// Where [`Self::load_template`] would read `phase`'s template from, for
+    /// display.
+    pub fn template_origin(&self, phase: &str) -> String {
+        if let Some(ref dir) = self.override_dir {
+            let path = Path::new(dir).join(template_filename(phase));
+            if path.exists() {
+                return format!("override {}", path.display());
+            }
+        }
+        if is_prompt_version(&self.version) && default_template(&self.version, phase).is_some() {
+            format!("built-in {}", self.version)
+        } else {
+            "no template".to_string()
+        }
+    }
+
     /// Load a template and render it with the given variables.
     ///
-    /// Built-in variables like `findings_schema` are auto-injected when not
-    /// already present in `vars`, so templates can reference them without
-    /// callers having to supply them.
+    /// Built-in prompt names are checked against their phase's variables (see
+    /// [`PromptPhase::of_prompt`]); custom names render unchecked. Callers that
+    /// know the phase of a configurable prompt should use [`Self::render_step`].
     pub fn render_phase(&self, phase: &str, vars: &HashMap<String, String>) -> Result<String> {
-        let template = self.load_template(phase)?;
+        match PromptPhase::of_prompt(phase) {
+            Some(kind) => self.render_step(kind, phase, vars),
+            None => render_named(
+                phase,
+                &self.load_template(phase)?,
+                &self.with_builtin_vars(vars),
+            ),
+        }
+    }
+
+    /// Load the `prompt` template used for `phase` and render it.
+    ///
+    /// A template referencing a variable the phase does not provide is rejected
+    /// before rendering. Built-in variables like `findings_schema` (and the
+    /// `.rlphignore` variables `ignored_paths` / `diff_excludes`, `repo_conventions`,
+    /// `project_toolchain` and `affected_crates`, empty by default) are
+    /// auto-injected when not already present in `vars`, so templates can
+    /// reference them without callers having to supply them.
+    pub fn render_step(
+        &self,
+        phase: PromptPhase,
+        prompt: &str,
+        vars: &HashMap<String, String>,
+    ) -> Result<String> {
+        let template = self.load_template(prompt)?;
+        phase.check_template(prompt, &template)?;
+        render_named(prompt, &template, &self.with_builtin_vars(vars))
+    }
+
+    fn with_builtin_vars(&self, vars: &HashMap<String, String>) -> HashMap<String, String> {
         let mut all_vars = vars.clone();
         all_vars
             .entry("findings_schema".to_string())
-            .or_insert_with(|| FINDINGS_SCHEMA.to_string());
-        render_template(&template, &all_vars)
+            .or_insert_with(|| findings_schema(&self.version, &[]));
+        for name in [
+            "ignored_paths",
+            "diff_excludes",
+            "repo_conventions",
+            "project_toolchain",
+            "affected_crates",
+        ] {
+            all_vars.entry(name.to_string()).or_default();
+        }
+        all_vars
+    }
+}
+
+/// The findings schema partial of prompt `version`, extended with any
+/// configured required extension fields. Unknown versions get the current
+/// schema; [`PromptEngine::load_template`] rejects them before it matters.
+pub fn findings_schema(version: &str, required_fields: &[String]) -> String {
+    let schema = default_findings_schema(version).unwrap_or(FINDINGS_SCHEMA);
+    if required_fields.is_empty() {
+        return schema.to_string();
+    }
+    let fields: Vec<String> = required_fields.iter().map(|f| format!("`{f}`")).collect();
+    format!(
+        "{}- Every finding must also include these fields: {}.\n",
+        schema.trim_end(),
+        fields.join(", ")
+    )
+}
+
+/// Diff the built-in prompts and findings schema of two prompt versions, as
+/// unified-style hunks per changed file. Empty when they are identical.
+pub fn diff_prompt_versions(from: &str, to: &str) -> Result<String> {
+    for version in [from, to] {
+        if !is_prompt_version(version) {
+            return Err(unknown_version(version));
+        }
+    }
+    let mut files: Vec<(String, &str, &str)> = BUILTIN_PROMPTS
+        .iter()
+        .map(|name| {
+            (
+                template_filename(name),
+                default_template(from, name).unwrap_or_default(),
+                default_template(to, name).unwrap_or_default(),
+            )
+        })
+        .collect();
+    files.push((
+        "_findings-schema.md".to_string(),
+        default_findings_schema(from).unwrap_or_default(),
+        default_findings_schema(to).unwrap_or_default(),
+    ));
+
+    let mut out = String::new();
+    for (file, old, new) in files {
+        if old == new {
+            continue;
+        }
+        let _ = writeln!(out, "--- {from}/{file}\n+++ {to}/{file}");
+        out.push_str(&line_diff(old, new));
+    }
+    Ok(out)
+}
+
+/// Line diff of `old` and `new` (longest common subsequence), printed as
+/// `@@ -old_line +new_line @@` hunks with [`DIFF_CONTEXT`] lines of context.
+fn line_diff(old: &str, new: &str) -> String {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    // lcs[i][j]: length of the longest common subsequence of a[i..] and b[j..].
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    // (tag, old line index, new line index, text) for every line of either side.
+    let mut ops: Vec<(char, usize, usize, &str)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push((' ', i, j, a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', i, j, a[i]));
+            i += 1;
+        } else {
+            ops.push(('+', i, j, b[j]));
+            j += 1;
+        }
     }
+
+    let changed: Vec<usize> = (0..ops.len()).filter(|&k| ops[k].0 != ' ').collect();
+    let mut out = String::new();
+    let mut k = 0;
+    while k < changed.len() {
+        let start = changed[k].saturating_sub(DIFF_CONTEXT);
+        let mut end = changed[k];
+        while k + 1 < changed.len() && changed[k + 1] - end <= 2 * DIFF_CONTEXT {
+            k += 1;
+            end = changed[k];
+        }
+        let end = (end + DIFF_CONTEXT + 1).min(ops.len());
+        let (_, old_line, new_line, _) = ops[start];
+        let _ = writeln!(out, "@@ -{} +{} @@", old_line + 1, new_line + 1);
+        for (tag, _, _, text) in &ops[start..end] {
+            let _ = writeln!(out, "{tag}{text}");
+        }
+        k += 1;
+    }
+    out
+}
+
+/// Check that a template string compiles, without rendering it.
+pub fn validate_template(template: &str) -> Result<()> {
+    upon::Engine::new()
+        .compile(template)
+        .map(|_| ())
+        .map_err(|e| Error::Prompt(format!("template compile error: {e}")))
 }
 
 /// Render a template string using the `upon` template engine.
@@ -104,6 +371,32 @@ pub fn render_template(template: &str, vars: &HashMap<String, String>) -> Result
         .map_err(|e| Error::Prompt(format!("template render error: {e}")))
 }
 
+/// [`render_template`] for the prompt `name`, failing with
+/// [`Error::PromptRender`] so the error names the template, the variable it
+/// lacked, and the variables it had.
+fn render_named(name: &str, template: &str, vars: &HashMap<String, String>) -> Result<String> {
+    render_template(template, vars).map_err(|e| {
+        let mut provided: Vec<String> = vars.keys().cloned().collect();
+        provided.sort();
+        let missing = referenced_vars(template)
+            .into_iter()
+            .find(|var| !vars.contains_key(var));
+        let message = match e {
+            Error::Prompt(message) => message,
+            other => other.to_string(),
+        };
+        // upon may point at the offending snippet over several lines; keep the
+        // error to one log line.
+        let message = message.split_whitespace().collect::<Vec<_>>().join(" ");
+        Error::PromptRender {
+            template: name.to_string(),
+            missing,
+            provided,
+            message,
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,6 +445,17 @@ mod tests {
         assert!(template.contains("{{base_branch}}"));
     }
 
+    #[test]
+    fn test_load_default_preset_reviews() {
+        let engine = PromptEngine::new(None);
+        let template = engine.load_template("unsafe-review").unwrap();
+        assert!(template.contains("Unsafe & Panic Review Agent"));
+        assert!(template.contains("{{findings_schema}}"));
+        let template = engine.load_template("perf-review").unwrap();
+        assert!(template.contains("Performance Review Agent"));
+        assert!(template.contains("{{findings_schema}}"));
+    }
+
     #[test]
     fn test_load_default_review_aggregate() {
         let engine = PromptEngine::new(None);
@@ -168,6 +472,14 @@ mod tests {
         assert!(template.contains("{{fix_instructions}}"));
     }
 
+    #[test]
+    fn test_load_default_estimate() {
+        let engine = PromptEngine::new(None);
+        let template = engine.load_template("estimate").unwrap();
+        assert!(template.contains("Task Estimation Agent"));
+        assert!(template.contains("\"complexity\""));
+    }
+
     #[test]
     fn test_load_unknown_phase() {
         let engine = PromptEngine::new(None);
@@ -175,6 +487,53 @@ mod tests {
         assert!(err.to_string().contains("unknown prompt phase"));
     }
 
+    #[test]
+    fn test_prompt_versions() {
+        let engine = PromptEngine::new(None);
+        assert_eq!(engine.version(), PROMPT_VERSION);
+        let pinned = PromptEngine::new(None).with_version("v1");
+        assert_eq!(
+            pinned.load_template("fix").unwrap(),
+            engine.load_template("fix").unwrap()
+        );
+        assert_ne!(
+            pinned.load_template("implement").unwrap(),
+            engine.load_template("implement").unwrap()
+        );
+        for prompt in BUILTIN_PROMPTS {
+            let phase = PromptPhase::of_prompt(prompt).unwrap();
+            phase
+                .check_template(prompt, &pinned.load_template(prompt).unwrap())
+                .unwrap_or_else(|e| panic!("{e}"));
+        }
+        let err = PromptEngine::new(None)
+            .with_version("v0")
+            .load_template("fix")
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("unknown prompt version \"v0\""),
+            "{err}"
+        );
+
+        assert_eq!(diff_prompt_versions("v1", "v1").unwrap(), "");
+        let diff = diff_prompt_versions("v1", "v2").unwrap();
+        assert!(diff.contains("--- v1/implement-issue.md\n+++ v2/implement-issue.md\n"));
+        assert!(!diff.contains("fix-issue.md"));
+        assert!(diff_prompt_versions("v1", "v9").is_err());
+    }
+
+    #[test]
+    fn test_line_diff_hunks() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\n";
+        assert_eq!(
+            line_diff(old, new),
+            "@@ -1 +1 @@\n a\n-b\n+B\n c\n d\n e\n\
+             @@ -10 +10 @@\n j\n k\n l\n+m\n"
+        );
+        assert_eq!(line_diff(old, old), "");
+    }
+
     #[test]
     fn test_override_takes_precedence() {
         let dir = TempDir::new().unwrap();
@@ -328,6 +687,47 @@ mod tests {
         assert!(result.contains("[{\"id\":\"1\"}]"));
     }
 
+    #[test]
+    fn test_repo_conventions_and_toolchain_sections_are_optional() {
+        let engine = PromptEngine::new(None);
+        let mut vars: HashMap<String, String> = [
+            "issue_title",
+            "issue_body",
+            "issue_number",
+            "issue_url",
+            "repo_path",
+            "branch_name",
+            "worktree_path",
+            "previous_attempts",
+            "referenced_code",
+            "past_lessons",
+            "test_plan_instructions",
+        ]
+        .into_iter()
+        .map(|name| (name.to_string(), String::new()))
+        .collect();
+
+        let result = engine.render_phase("implement", &vars).unwrap();
+        assert!(!result.contains("## Repository Conventions"));
+        assert!(!result.contains("## Toolchain"));
+
+        vars.insert(
+            "repo_conventions".to_string(),
+            "### AGENTS.md\n\nRun cargo fmt.".to_string(),
+        );
+        let result = engine.render_phase("implement", &vars).unwrap();
+        assert!(result.contains("## Repository Conventions"));
+        assert!(result.contains("Run cargo fmt."));
+
+        vars.insert(
+            "project_toolchain".to_string(),
+            "Project type: Rust (`Cargo.toml`)\n- Test: `cargo test`".to_string(),
+        );
+        let result = engine.render_phase("implement", &vars).unwrap();
+        assert!(result.contains("## Toolchain"));
+        assert!(result.contains("- Test: `cargo test`"));
+    }
+
     #[test]
     fn test_load_default_prd() {
         let engine = PromptEngine::new(None);
@@ -361,6 +761,42 @@ mod tests {
         assert!(!result.contains("{{submission_instructions}}"));
     }
 
+    #[test]
+    fn test_render_phase_error_names_template_and_vars() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("triage-issue.md"),
+            "{{ issue_title }} {{ owner }}",
+        )
+        .unwrap();
+        let engine = PromptEngine::new(Some(dir.path().to_string_lossy().to_string()));
+        let vars = HashMap::from([("issue_title".to_string(), "Fix bug".to_string())]);
+
+        let err = engine.render_phase("triage", &vars).unwrap_err();
+        let Error::PromptRender {
+            template,
+            missing,
+            provided,
+            ..
+        } = &err
+        else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(template, "triage");
+        assert_eq!(missing.as_deref(), Some("owner"));
+        assert_eq!(
+            provided.first().map(String::as_str),
+            Some("affected_crates")
+        );
+        assert!(provided.windows(2).all(|w| w[0] < w[1]));
+        let line = err.to_string();
+        assert!(
+            line.contains("in template 'triage' (missing `owner`)"),
+            "{line}"
+        );
+        assert!(!line.contains('\n'), "{line}");
+    }
+
     #[test]
     fn test_override_with_unknown_var_loads_but_fails_at_render() {
         let dir = TempDir::new().unwrap();
@@ -377,4 +813,15 @@ mod tests {
         let err = render_template(&template, &vars).unwrap_err();
         assert!(err.to_string().contains("render error"), "got: {err}");
     }
+
+    #[test]
+    fn test_findings_schema_lists_required_fields() {
+        assert_eq!(findings_schema(PROMPT_VERSION, &[]), FINDINGS_SCHEMA);
+        let schema = findings_schema(
+            PROMPT_VERSION,
+            &["confidence".to_string(), "category".to_string()],
+        );
+        assert!(schema.starts_with(FINDINGS_SCHEMA.trim_end()));
+        assert!(schema.contains("must also include these fields: `confidence`, `category`."));
+    }
 }
@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::rlphignore::RlphIgnore;
+use crate::worktree::git_in_dir;
+
+/// Configured `protected_paths` patterns (gitignore syntax) that agents must not
+/// change. A branch touching them is never pushed.
+#[derive(Debug, Clone, Default)]
+pub struct ProtectedPaths {
+    matcher: RlphIgnore,
+}
+
+impl ProtectedPaths {
+    pub fn new(patterns: &[String]) -> Self {
+        Self {
+            matcher: RlphIgnore::parse(&patterns.join("\n")),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matcher.is_empty()
+    }
+
+    pub fn is_protected(&self, path: &str) -> bool {
+        self.matcher.is_ignored(path)
+    }
+
+    /// Protected files the branch checked out at `worktree` changes relative to
+    /// `base` (e.g. `origin/main`), including added, deleted, and renamed files.
+    pub fn changed(&self, worktree: &Path, base: &str) -> Result<Vec<String>> {
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+        let range = format!("{base}...HEAD");
+        let output = git_in_dir(worktree, &["diff", "--name-only", "--no-renames", &range])
+            .map_err(|e| {
+                Error::Orchestrator(format!(
+                    "failed to diff {} against {base}: {}",
+                    worktree.display(),
+                    e.trim()
+                ))
+            })?;
+        Ok(output
+            .lines()
+            .map(str::trim)
+            .filter(|path| !path.is_empty() && self.is_protected(path))
+            .map(str::to_string)
+            .collect())
+    }
+}
+
+/// Fix instructions asking the agent to restore `paths` to their `base` versions.
+pub fn revert_instructions(paths: &[String], base: &str) -> String {
+    let list: Vec<String> = paths.iter().map(|p| format!("- `{p}`")).collect();
+    format!(
+        "This branch changes protected paths, which rlph will not push:\n\n{}\n\n\
+         Restore each of these files to its exact state on `{base}` (delete files that \
+         do not exist there), keep every other change, and commit the result. Do not \
+         work around the protection by moving or copying the changes elsewhere.",
+        list.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_protected() {
+        let protected =
+            ProtectedPaths::new(&[".github/workflows/**".to_string(), "Cargo.lock".to_string()]);
+        assert!(protected.is_protected(".github/workflows/ci.yml"));
+        assert!(protected.is_protected("crates/core/Cargo.lock"));
+        assert!(!protected.is_protected(".github/CODEOWNERS"));
+        assert!(!protected.is_protected("src/main.rs"));
+        assert!(ProtectedPaths::new(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_revert_instructions_list_paths() {
+        let text = revert_instructions(
+            &[
+                ".github/workflows/ci.yml".to_string(),
+                "Cargo.lock".to_string(),
+            ],
+            "origin/main",
+        );
+        assert!(text.contains("- `.github/workflows/ci.yml`\n- `Cargo.lock`"));
+        assert!(text.contains("state on `origin/main`"));
+    }
+}
@@ -0,0 +1,331 @@
+//! `git push` with retries for transient failures.
+//!
+//! Every successful push is remembered under `refs/rlph/pushed/<remote>/<branch>`.
+//! When a push is rejected because the remote branch moved, and it still points
+//! at what rlph itself last pushed there (e.g. a retried task rebuilt its
+//! worktree), the push is repeated with `--force-with-lease` on that commit.
+//! Anyone else's commits on the branch make the push fail instead.
+
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+use crate::error::{Error, Result};
+use crate::worktree::git_in_dir;
+
+/// Attempts for a push that keeps failing transiently.
+pub const PUSH_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry; doubled for each one after.
+pub const PUSH_INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Why a push failed, which decides whether it is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushFailure {
+    /// Credentials were missing or refused; retrying will not help.
+    Auth,
+    /// The remote refused the update, e.g. a non-fast-forward.
+    Rejected,
+    /// Network or server trouble that may pass.
+    Transient,
+    Other,
+}
+
+impl fmt::Display for PushFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PushFailure::Auth => "authentication",
+            PushFailure::Rejected => "rejected",
+            PushFailure::Transient => "transient",
+            PushFailure::Other => "other",
+        })
+    }
+}
+
+const AUTH_PATTERNS: &[&str] = &[
+    "authentication failed",
+    "could not read username",
+    "could not read password",
+    "invalid username or password",
+    "permission denied",
+    "permission to",
+    "the requested url returned error: 401",
+    "the requested url returned error: 403",
+    "repository not found",
+];
+
+const REJECTED_PATTERNS: &[&str] = &[
+    "[rejected]",
+    "non-fast-forward",
+    "fetch first",
+    "stale info",
+];
+
+const TRANSIENT_PATTERNS: &[&str] = &[
+    "could not resolve host",
+    "connection reset",
+    "connection refused",
+    "connection timed out",
+    "operation timed out",
+    "timed out",
+    "early eof",
+    "the remote end hung up unexpectedly",
+    "rpc failed",
+    "broken pipe",
+    "temporarily unavailable",
+    "the requested url returned error: 5",
+    "internal server error",
+    "bad gateway",
+    "service unavailable",
+];
+
+/// Classify a failed push by its stderr.
+pub fn classify_push_error(stderr: &str) -> PushFailure {
+    let stderr = stderr.to_lowercase();
+    let has = |patterns: &[&str]| patterns.iter().any(|p| stderr.contains(p));
+    if has(AUTH_PATTERNS) {
+        PushFailure::Auth
+    } else if has(REJECTED_PATTERNS) {
+        PushFailure::Rejected
+    } else if has(TRANSIENT_PATTERNS) {
+        PushFailure::Transient
+    } else {
+        PushFailure::Other
+    }
+}
+
+/// Ref remembering the commit rlph last pushed to `remote_branch` on `remote`.
+fn pushed_ref(remote: &str, remote_branch: &str) -> String {
+    format!("refs/rlph/pushed/{remote}/{remote_branch}")
+}
+
+/// Push `local` (a branch or `HEAD`) to `remote_branch` on `remote`, setting
+/// the upstream. Transient failures are retried up to [`PUSH_ATTEMPTS`] times,
+/// waiting `backoff`, then twice as long. A non-fast-forward over rlph's own
+/// last push is retried once with `--force-with-lease`.
+pub fn push_with_retry(
+    dir: &Path,
+    remote: &str,
+    local: &str,
+    remote_branch: &str,
+    backoff: Duration,
+) -> Result<()> {
+    let refspec = format!("{local}:refs/heads/{remote_branch}");
+    let mut lease: Option<String> = None;
+    let mut backoff = backoff;
+    let mut attempt = 1;
+    loop {
+        let mut args = vec!["push", "-u"];
+        if let Some(lease) = &lease {
+            args.push(lease);
+        }
+        args.extend([remote, refspec.as_str()]);
+        let (kind, message) = match run_push(dir, &args) {
+            Ok(()) => {
+                record_push(dir, remote, local, remote_branch);
+                return Ok(());
+            }
+            Err(failure) => failure,
+        };
+        match kind {
+            PushFailure::Transient if attempt < PUSH_ATTEMPTS => {
+                warn!(attempt, remote_branch, backoff_ms = backoff.as_millis() as u64, error = %message.trim(), "retrying git push after transient error");
+                std::thread::sleep(backoff);
+                backoff *= 2;
+                attempt += 1;
+            }
+            PushFailure::Rejected if lease.is_none() => {
+                let Some(ours) = own_remote_head(dir, remote, remote_branch) else {
+                    return Err(Error::GitPush { kind, message });
+                };
+                info!(
+                    remote_branch,
+                    sha = ours,
+                    "remote branch moved by an earlier rlph push; retrying with --force-with-lease"
+                );
+                lease = Some(format!("--force-with-lease={remote_branch}:{ours}"));
+            }
+            _ => return Err(Error::GitPush { kind, message }),
+        }
+    }
+}
+
+/// Force-push `local` to `remote_branch`, refusing if the remote moved past
+/// `expected` (or, without it, past the remote-tracking ref). Transient
+/// failures are retried like [`push_with_retry`].
+pub fn force_push(
+    dir: &Path,
+    remote: &str,
+    local: &str,
+    remote_branch: &str,
+    expected: Option<&str>,
+    backoff: Duration,
+) -> Result<()> {
+    let lease = match expected {
+        Some(sha) => format!("--force-with-lease={remote_branch}:{sha}"),
+        None => "--force-with-lease".to_string(),
+    };
+    let refspec = format!("{local}:refs/heads/{remote_branch}");
+    let mut backoff = backoff;
+    for attempt in 1..=PUSH_ATTEMPTS {
+        match run_push(dir, &["push", &lease, remote, &refspec]) {
+            Ok(()) => {
+                record_push(dir, remote, local, remote_branch);
+                return Ok(());
+            }
+            Err((PushFailure::Transient, message)) if attempt < PUSH_ATTEMPTS => {
+                warn!(attempt, remote_branch, error = %message.trim(), "retrying git push after transient error");
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+            Err((kind, message)) => return Err(Error::GitPush { kind, message }),
+        }
+    }
+    unreachable!()
+}
+
+fn run_push(dir: &Path, args: &[&str]) -> std::result::Result<(), (PushFailure, String)> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| (PushFailure::Other, format!("failed to run git push: {e}")))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    Err((classify_push_error(&stderr), stderr))
+}
+
+/// Remember the pushed commit; failing to is only logged.
+fn record_push(dir: &Path, remote: &str, local: &str, remote_branch: &str) {
+    let recorded = git_in_dir(dir, &["rev-parse", local]).and_then(|sha| {
+        git_in_dir(
+            dir,
+            &["update-ref", &pushed_ref(remote, remote_branch), sha.trim()],
+        )
+    });
+    if let Err(e) = recorded {
+        warn!(remote_branch, error = %e.trim(), "failed to record pushed commit");
+    }
+}
+
+/// The remote head of `remote_branch`, if it is the commit rlph last pushed there.
+fn own_remote_head(dir: &Path, remote: &str, remote_branch: &str) -> Option<String> {
+    let ours = git_in_dir(
+        dir,
+        &[
+            "rev-parse",
+            "-q",
+            "--verify",
+            &pushed_ref(remote, remote_branch),
+        ],
+    )
+    .ok()?;
+    let listed = git_in_dir(
+        dir,
+        &["ls-remote", remote, &format!("refs/heads/{remote_branch}")],
+    )
+    .ok()?;
+    let theirs = listed.split_whitespace().next()?;
+    (theirs == ours.trim()).then(|| theirs.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_classify_push_error() {
+        assert_eq!(
+            classify_push_error(
+                "remote: Invalid username or password.\nfatal: Authentication failed for 'https://github.com/o/r.git/'"
+            ),
+            PushFailure::Auth
+        );
+        assert_eq!(
+            classify_push_error(
+                " ! [rejected]        rlph-1 -> rlph-1 (non-fast-forward)\nerror: failed to push some refs"
+            ),
+            PushFailure::Rejected
+        );
+        assert_eq!(
+            classify_push_error(
+                "fatal: unable to access 'https://github.com/o/r.git/': Could not resolve host: github.com"
+            ),
+            PushFailure::Transient
+        );
+        assert_eq!(
+            classify_push_error(
+                "error: RPC failed; HTTP 502 curl 22 The requested URL returned error: 502"
+            ),
+            PushFailure::Transient
+        );
+        assert_eq!(
+            classify_push_error("error: src refspec nope does not match any"),
+            PushFailure::Other
+        );
+    }
+
+    #[test]
+    fn test_rejected_push_forces_only_over_own_commit() {
+        let tmp = TempDir::new().unwrap();
+        let remote = tmp.path().join("remote.git");
+        let git = |dir: &Path, args: &[&str]| git_in_dir(dir, args).unwrap();
+        git(
+            tmp.path(),
+            &["init", "-q", "--bare", remote.to_str().unwrap()],
+        );
+        let clone = |name: &str| {
+            let dir = tmp.path().join(name);
+            git(tmp.path(), &["clone", "-q", remote.to_str().unwrap(), name]);
+            git(&dir, &["config", "user.name", "t"]);
+            git(&dir, &["config", "user.email", "t@t"]);
+            git(&dir, &["checkout", "-q", "-b", "rlph-1"]);
+            dir
+        };
+        let commit =
+            |dir: &Path, msg: &str| git(dir, &["commit", "-q", "--allow-empty", "-m", msg]);
+        let push = |dir: &Path| push_with_retry(dir, "origin", "HEAD", "rlph-1", Duration::ZERO);
+
+        let repo = clone("repo");
+        commit(&repo, "first attempt");
+        push(&repo).unwrap();
+
+        // A retried attempt rewrote the branch it pushed before.
+        git(
+            &repo,
+            &[
+                "commit",
+                "-q",
+                "--amend",
+                "--allow-empty",
+                "-m",
+                "second attempt",
+            ],
+        );
+        push(&repo).unwrap();
+        let head = git(&repo, &["rev-parse", "HEAD"]);
+        assert_eq!(git(&remote, &["rev-parse", "rlph-1"]), head);
+
+        // Someone else's commit on the branch is never overwritten.
+        let other = clone("other");
+        commit(&other, "not ours");
+        let err = push(&other).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::GitPush {
+                    kind: PushFailure::Rejected,
+                    ..
+                }
+            ),
+            "{err}"
+        );
+        assert_eq!(git(&remote, &["rev-parse", "rlph-1"]), head);
+    }
+}
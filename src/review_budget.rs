@@ -0,0 +1,67 @@
+/// Consecutive needs-fix rounds without fewer findings before an adaptive review
+/// gives up.
+pub const STALLED_ROUND_LIMIT: u32 = 2;
+
+/// Progress of the review loop under `review_round_policy = "adaptive"`, which
+/// stops early once further rounds look unlikely to converge.
+#[derive(Debug, Default)]
+pub struct ReviewBudget {
+    last_count: Option<usize>,
+    stalled: u32,
+}
+
+impl ReviewBudget {
+    /// Record the finding count of a round that needs fixing. Returns the stop
+    /// reason once the count has not decreased for `STALLED_ROUND_LIMIT`
+    /// consecutive rounds.
+    pub fn record_findings(&mut self, round: u32, count: usize) -> Option<String> {
+        match self.last_count {
+            Some(last) if count >= last => self.stalled += 1,
+            _ => self.stalled = 0,
+        }
+        self.last_count = Some(count);
+        (self.stalled >= STALLED_ROUND_LIMIT).then(|| {
+            format!(
+                "findings did not decrease for {STALLED_ROUND_LIMIT} consecutive rounds ({count} open after round {round})"
+            )
+        })
+    }
+}
+
+/// Stop reason for a fix round that left the worktree unchanged.
+pub fn unchanged_fix_reason(round: u32) -> String {
+    format!("fix agent changed nothing in round {round}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stops_after_two_rounds_without_progress() {
+        let mut budget = ReviewBudget::default();
+        assert_eq!(budget.record_findings(1, 3), None);
+        assert_eq!(budget.record_findings(2, 3), None);
+        let reason = budget.record_findings(3, 4).unwrap();
+        assert!(reason.contains("did not decrease for 2 consecutive rounds"));
+        assert!(reason.contains("4 open after round 3"));
+    }
+
+    #[test]
+    fn test_progress_resets_stall() {
+        let mut budget = ReviewBudget::default();
+        assert_eq!(budget.record_findings(1, 5), None);
+        assert_eq!(budget.record_findings(2, 5), None);
+        assert_eq!(budget.record_findings(3, 2), None);
+        assert_eq!(budget.record_findings(4, 2), None);
+        assert!(budget.record_findings(5, 2).is_some());
+    }
+
+    #[test]
+    fn test_unchanged_fix_reason() {
+        assert_eq!(
+            unchanged_fix_reason(2),
+            "fix agent changed nothing in round 2"
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Artifacts precomputed for review phases that declare `context`, so they can
+//! reason about the change without tool calls of their own.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use tracing::warn;
+
+use crate::config::ReviewContext;
+use crate::rlphignore::RlphIgnore;
+use crate::worktree::git_in_dir;
+
+/// Files larger than this are left out of `context_full_files`.
+const MAX_FILE_BYTES: usize = 64 * 1024;
+/// Cap on the whole of each context variable.
+const MAX_CONTEXT_CHARS: usize = 200_000;
+
+/// The prompt variable a context kind is injected as.
+pub fn context_var(kind: ReviewContext) -> &'static str {
+    match kind {
+        ReviewContext::Diff => "context_diff",
+        ReviewContext::FullFiles => "context_full_files",
+        ReviewContext::Blame => "context_blame",
+    }
+}
+
+/// Build each of `kinds` for the branch at `worktree` relative to `base`
+/// (e.g. `origin/main`), keyed by [`context_var`]. Files matched by
+/// `.rlphignore` are left out. Best-effort: a kind that fails is empty.
+pub fn build_review_context(
+    worktree: &Path,
+    base: &str,
+    kinds: &[ReviewContext],
+    ignore: &RlphIgnore,
+) -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    if kinds.is_empty() {
+        return vars;
+    }
+    let range = format!("{base}...HEAD");
+    let files: Vec<String> = match git_in_dir(worktree, &["diff", "--name-only", &range]) {
+        Ok(out) => out
+            .lines()
+            .map(str::trim)
+            .filter(|f| !f.is_empty() && !ignore.is_ignored(f))
+            .map(str::to_string)
+            .collect(),
+        Err(e) => {
+            warn!(
+                base,
+                error = e.trim(),
+                "failed to list changed files for review context"
+            );
+            return vars;
+        }
+    };
+    for &kind in kinds {
+        let value = if files.is_empty() {
+            String::new()
+        } else {
+            match kind {
+                ReviewContext::Diff => diff(worktree, &range, &files),
+                ReviewContext::FullFiles => full_files(worktree, &files),
+                ReviewContext::Blame => blame(worktree, base, &range, &files),
+            }
+        };
+        vars.insert(context_var(kind).to_string(), truncate(value));
+    }
+    vars
+}
+
+fn diff(worktree: &Path, range: &str, files: &[String]) -> String {
+    let mut args = vec!["diff", range, "--"];
+    args.extend(files.iter().map(String::as_str));
+    git_in_dir(worktree, &args).unwrap_or_else(|e| {
+        warn!(error = e.trim(), "failed to diff branch for review context");
+        String::new()
+    })
+}
+
+/// Current contents of each changed file still present, as fenced blocks.
+fn full_files(worktree: &Path, files: &[String]) -> String {
+    let mut out = String::new();
+    for file in files {
+        let Ok(content) = std::fs::read_to_string(worktree.join(file)) else {
+            continue;
+        };
+        if content.len() > MAX_FILE_BYTES {
+            out.push_str(&format!(
+                "### {file}\n(omitted: {} bytes)\n\n",
+                content.len()
+            ));
+            continue;
+        }
+        out.push_str(&format!("### {file}\n```\n{}\n```\n\n", content.trim_end()));
+    }
+    out
+}
+
+/// `git blame` at the merge base of the lines the branch modifies or removes,
+/// showing who last changed them and when.
+fn blame(worktree: &Path, base: &str, range: &str, files: &[String]) -> String {
+    let Ok(merge_base) = git_in_dir(worktree, &["merge-base", base, "HEAD"]) else {
+        return String::new();
+    };
+    let merge_base = merge_base.trim();
+    let mut args = vec!["diff", "--unified=0", range, "--"];
+    args.extend(files.iter().map(String::as_str));
+    let Ok(diff) = git_in_dir(worktree, &args) else {
+        return String::new();
+    };
+    let mut out = String::new();
+    for (file, ranges) in old_line_ranges(&diff) {
+        let mut blame_args = vec!["blame".to_string(), "--date=short".to_string()];
+        for (start, end) in &ranges {
+            blame_args.push(format!("-L{start},{end}"));
+        }
+        blame_args.extend([merge_base.to_string(), "--".to_string(), file.clone()]);
+        let blame_args: Vec<&str> = blame_args.iter().map(String::as_str).collect();
+        match git_in_dir(worktree, &blame_args) {
+            Ok(lines) => out.push_str(&format!("### {file}\n```\n{}\n```\n\n", lines.trim_end())),
+            Err(e) => warn!(
+                file,
+                error = e.trim(),
+                "failed to blame file for review context"
+            ),
+        }
+    }
+    out
+}
+
+/// Old-side line ranges (inclusive) each file's hunks modify or remove in a
+/// `--unified=0` diff. Pure additions and new files have none.
+fn old_line_ranges(diff: &str) -> BTreeMap<String, Vec<(u32, u32)>> {
+    let mut ranges: BTreeMap<String, Vec<(u32, u32)>> = BTreeMap::new();
+    let mut current: Option<String> = None;
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            current = None;
+        } else if let Some(source) = line.strip_prefix("--- ") {
+            current = source.trim().strip_prefix("a/").map(str::to_string);
+        } else if line.starts_with("@@")
+            && let Some(path) = &current
+            && let Some(old) = line.split_whitespace().nth(1)
+            && let Some(old) = old.strip_prefix('-')
+        {
+            let (start, count): (u32, u32) = match old.split_once(',') {
+                Some((s, c)) => (s.parse().unwrap_or(0), c.parse().unwrap_or(0)),
+                None => (old.parse().unwrap_or(0), 1),
+            };
+            if count > 0 && start > 0 {
+                ranges
+                    .entry(path.clone())
+                    .or_default()
+                    .push((start, start + count - 1));
+            }
+        }
+    }
+    ranges
+}
+
+fn truncate(mut value: String) -> String {
+    if value.len() > MAX_CONTEXT_CHARS {
+        let mut end = MAX_CONTEXT_CHARS;
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+        value.truncate(end);
+        value.push_str("\n(truncated)\n");
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_old_line_ranges() {
+        let diff = "\
+diff --git a/src/a.rs b/src/a.rs
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -3,2 +3,1 @@ fn a() {
+@@ -10 +9,0 @@
+@@ -20,0 +19,4 @@
+diff --git a/src/new.rs b/src/new.rs
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1,5 @@
+";
+        let ranges = old_line_ranges(diff);
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges["src/a.rs"], [(3, 4), (10, 10)]);
+    }
+
+    #[test]
+    fn test_build_review_context_from_branch() {
+        let repo = TempDir::new().unwrap();
+        let git = |args: &[&str]| git_in_dir(repo.path(), args).unwrap();
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.name", "t"]);
+        git(&["config", "user.email", "t@t"]);
+        std::fs::write(repo.path().join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        std::fs::write(repo.path().join("skip.txt"), "x\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "base"]);
+        git(&["checkout", "-q", "-b", "feature"]);
+        std::fs::write(repo.path().join("a.txt"), "one\n2\nthree\n").unwrap();
+        std::fs::write(repo.path().join("skip.txt"), "y\n").unwrap();
+        git(&["commit", "-q", "-am", "change"]);
+
+        let kinds = [
+            ReviewContext::Diff,
+            ReviewContext::FullFiles,
+            ReviewContext::Blame,
+        ];
+        let ignore = RlphIgnore::parse("skip.txt\n");
+        let vars = build_review_context(repo.path(), "main", &kinds, &ignore);
+        assert!(vars["context_diff"].contains("+2"));
+        assert!(!vars["context_diff"].contains("skip.txt"));
+        assert!(vars["context_full_files"].contains("### a.txt\n```\none\n2\nthree\n```"));
+        let blame = &vars["context_blame"];
+        assert!(blame.starts_with("### a.txt\n"), "{blame}");
+        assert!(blame.contains("two"), "{blame}");
+        assert!(!blame.contains("three"), "{blame}");
+    }
+}
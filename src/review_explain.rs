@@ -0,0 +1,518 @@
+//! `rlph review --explain`: the review phases a run would execute, with the
+//! runner settings each one resolves to and the config level every setting
+//! came from. Nothing is run and no PR is fetched.
+
+use std::fmt::Write;
+
+use crate::cli::Cli;
+use crate::config::{
+    Config, ConfigFile, ReviewPhaseConfig, ReviewPhaseConfigFile, ReviewPhaseKind,
+    ReviewStepConfig, ReviewStepConfigFile,
+};
+use crate::error::Result;
+use crate::prompts::PromptEngine;
+use crate::review_presets::{DEFAULT_REVIEW_PRESET, review_preset};
+use crate::runner::RunnerKind;
+
+/// Runner settings shared by review phases and steps, as written at the phase
+/// or step level of the config file.
+struct Overrides<'a> {
+    runner: bool,
+    agent_binary: bool,
+    agent_model: bool,
+    agent_effort: bool,
+    agent_variant: bool,
+    agent_temperature: bool,
+    agent_extra_args: bool,
+    agent_timeout: bool,
+    permission_mode: bool,
+    /// How the level is named in the output, e.g. `[review_fix]`.
+    label: &'a str,
+}
+
+impl<'a> Overrides<'a> {
+    fn of_phase(phase: Option<&ReviewPhaseConfigFile>, label: &'a str) -> Self {
+        let set = |f: fn(&ReviewPhaseConfigFile) -> bool| phase.is_some_and(f);
+        Overrides {
+            runner: set(|p| p.runner.is_some()),
+            agent_binary: set(|p| p.agent_binary.is_some()),
+            agent_model: set(|p| p.agent_model.is_some()),
+            agent_effort: set(|p| p.agent_effort.is_some()),
+            agent_variant: set(|p| p.agent_variant.is_some()),
+            agent_temperature: set(|p| p.agent_temperature.is_some()),
+            agent_extra_args: set(|p| p.agent_extra_args.is_some()),
+            agent_timeout: set(|p| p.agent_timeout.is_some()),
+            permission_mode: set(|p| p.permission_mode.is_some()),
+            label,
+        }
+    }
+
+    fn of_step(step: Option<&ReviewStepConfigFile>, label: &'a str) -> Self {
+        let set = |f: fn(&ReviewStepConfigFile) -> bool| step.is_some_and(f);
+        Overrides {
+            runner: set(|s| s.runner.is_some()),
+            agent_binary: set(|s| s.agent_binary.is_some()),
+            agent_model: set(|s| s.agent_model.is_some()),
+            agent_effort: set(|s| s.agent_effort.is_some()),
+            agent_variant: set(|s| s.agent_variant.is_some()),
+            agent_temperature: set(|s| s.agent_temperature.is_some()),
+            agent_extra_args: set(|s| s.agent_extra_args.is_some()),
+            agent_timeout: set(|s| s.agent_timeout.is_some()),
+            permission_mode: set(|s| s.permission_mode.is_some()),
+            label,
+        }
+    }
+}
+
+/// Resolved runner settings of a phase or step.
+struct Resolved<'a> {
+    runner: RunnerKind,
+    agent_binary: &'a str,
+    agent_model: Option<&'a str>,
+    agent_effort: Option<&'a str>,
+    agent_variant: Option<&'a str>,
+    agent_temperature: Option<f64>,
+    agent_extra_args: &'a [String],
+    agent_timeout: Option<u64>,
+    permission_mode: String,
+}
+
+impl<'a> From<&'a ReviewPhaseConfig> for Resolved<'a> {
+    fn from(p: &'a ReviewPhaseConfig) -> Self {
+        Resolved {
+            runner: p.runner,
+            agent_binary: &p.agent_binary,
+            agent_model: p.agent_model.as_deref(),
+            agent_effort: p.agent_effort.as_deref(),
+            agent_variant: p.agent_variant.as_deref(),
+            agent_temperature: p.agent_temperature,
+            agent_extra_args: &p.agent_extra_args,
+            agent_timeout: p.agent_timeout,
+            permission_mode: p.permission_mode.to_string(),
+        }
+    }
+}
+
+impl<'a> From<&'a ReviewStepConfig> for Resolved<'a> {
+    fn from(s: &'a ReviewStepConfig) -> Self {
+        Resolved {
+            runner: s.runner,
+            agent_binary: &s.agent_binary,
+            agent_model: s.agent_model.as_deref(),
+            agent_effort: s.agent_effort.as_deref(),
+            agent_variant: s.agent_variant.as_deref(),
+            agent_temperature: s.agent_temperature,
+            agent_extra_args: &s.agent_extra_args,
+            agent_timeout: s.agent_timeout,
+            permission_mode: s.permission_mode.to_string(),
+        }
+    }
+}
+
+/// The first level of `chain` that sets the value, else `fallback`.
+fn origin(chain: &[(&str, bool)], fallback: &str) -> String {
+    chain
+        .iter()
+        .find(|(_, set)| *set)
+        .map_or(fallback, |(label, _)| label)
+        .to_string()
+}
+
+fn line(out: &mut String, key: &str, value: &str, from: &str) {
+    let _ = writeln!(out, "    {key:<12}{value:<24} <- {from}");
+}
+
+/// Describe what `rlph review` would run for `target` (e.g. `PR #12`) under
+/// `config`, which was merged from `file` and `cli`.
+pub fn explain_review(
+    target: &str,
+    file: &ConfigFile,
+    cli: &Cli,
+    config: &Config,
+    prompts: &PromptEngine,
+) -> Result<String> {
+    let mut out = String::new();
+    let _ = writeln!(out, "Review of {target} (nothing is run)");
+    if config.skip_review {
+        let from = if cli.skip_review {
+            "--skip-review"
+        } else {
+            "skip_review"
+        };
+        let _ = writeln!(out, "Review is skipped ({from}); nothing below would run.");
+    }
+    let _ = writeln!(
+        out,
+        "Rounds: up to {} ({} policy), phases run {}",
+        config.max_review_rounds,
+        format!("{:?}", config.review_round_policy).to_lowercase(),
+        config
+            .review_concurrency
+            .map_or("all at once".to_string(), |n| format!("{n} at a time")),
+    );
+
+    let phase_files: Vec<ReviewPhaseConfigFile> = match &file.review_phases {
+        Some(phases) => phases.clone(),
+        None => review_preset(
+            file.review_preset
+                .as_deref()
+                .unwrap_or(DEFAULT_REVIEW_PRESET),
+        )?,
+    };
+    let filter_from = if cli.review_phases.is_some() {
+        "--review-phases"
+    } else {
+        "review_phase_filter"
+    };
+    let skipped = config.skipped_review_phases();
+    for (i, phase) in config.review_phases.iter().enumerate() {
+        let label = match (&file.review_phases, &file.review_preset) {
+            (Some(_), _) => format!("[[review_phases]] {}", phase.name),
+            (None, Some(preset)) => format!("review_preset \"{preset}\""),
+            (None, None) => format!("default preset \"{DEFAULT_REVIEW_PRESET}\""),
+        };
+        if skipped.contains(&phase.name.as_str()) {
+            let _ = writeln!(out, "\nphase {}: skipped by {filter_from}", phase.name);
+            continue;
+        }
+        match &phase.kind {
+            ReviewPhaseKind::Agent => {
+                let _ = writeln!(out, "\nphase {} (agent)", phase.name);
+                prompt_line(&mut out, prompts, &phase.prompt, &label);
+                let phase_file = phase_files.get(i);
+                let overrides = Overrides::of_phase(phase_file, &label);
+                runner_lines(&mut out, file, cli, config, &overrides, &phase.into());
+                line(
+                    &mut out,
+                    "fail_fast",
+                    &phase.fail_fast.to_string(),
+                    &origin(
+                        &[(
+                            label.as_str(),
+                            phase_file.is_some_and(|p| p.fail_fast.is_some()),
+                        )],
+                        "default",
+                    ),
+                );
+                let context: Vec<String> = phase
+                    .context
+                    .iter()
+                    .map(|c| format!("{c:?}").to_lowercase())
+                    .collect();
+                if !context.is_empty() {
+                    line(&mut out, "context", &context.join(", "), &label);
+                }
+            }
+            ReviewPhaseKind::Command { command, format } => {
+                let _ = writeln!(out, "\nphase {} (command)", phase.name);
+                line(&mut out, "command", command, &label);
+                line(
+                    &mut out,
+                    "format",
+                    &format!("{format:?}").to_lowercase(),
+                    &label,
+                );
+            }
+            ReviewPhaseKind::Coverage { command, threshold } => {
+                let _ = writeln!(out, "\nphase {} (coverage)", phase.name);
+                match command {
+                    Some(command) => line(&mut out, "command", command, &label),
+                    None => line(&mut out, "command", "detected", "[toolchain] or toolchain"),
+                }
+                line(&mut out, "threshold", &format!("{threshold}%"), &label);
+            }
+        }
+    }
+
+    for (name, step, step_file, label) in [
+        (
+            "aggregate",
+            &config.review_aggregate,
+            file.review_aggregate.as_ref(),
+            "[review_aggregate]",
+        ),
+        (
+            "fix",
+            &config.review_fix,
+            file.review_fix.as_ref(),
+            "[review_fix]",
+        ),
+    ] {
+        let _ = writeln!(out, "\n{name} step");
+        let prompt_from = origin(
+            &[(label, step_file.is_some_and(|s| s.prompt.is_some()))],
+            "default",
+        );
+        prompt_line(&mut out, prompts, &step.prompt, &prompt_from);
+        let overrides = Overrides::of_step(step_file, label);
+        runner_lines(&mut out, file, cli, config, &overrides, &step.into());
+    }
+    Ok(out)
+}
+
+fn prompt_line(out: &mut String, prompts: &PromptEngine, name: &str, from: &str) {
+    line(
+        out,
+        "prompt",
+        &format!("{name} ({})", prompts.template_origin(name)),
+        from,
+    );
+}
+
+/// The runner settings of one phase or step, each with the level that set it:
+/// the phase or step itself, a command-line flag, the top level of the config
+/// file, or the runner's default.
+fn runner_lines(
+    out: &mut String,
+    file: &ConfigFile,
+    cli: &Cli,
+    config: &Config,
+    overrides: &Overrides,
+    resolved: &Resolved,
+) {
+    let at = overrides.label;
+    let runner_default = format!("{} default", resolved.runner);
+    let unset_or_default = |value: Option<&str>| {
+        if value.is_some() {
+            runner_default.clone()
+        } else {
+            "unset".to_string()
+        }
+    };
+    let show = |value: Option<&str>| value.unwrap_or("-").to_string();
+
+    line(
+        out,
+        "runner",
+        &resolved.runner.to_string(),
+        &origin(
+            &[
+                (at, overrides.runner),
+                ("--runner", cli.runner.is_some()),
+                ("runner", file.runner.is_some()),
+            ],
+            "default",
+        ),
+    );
+    line(
+        out,
+        "binary",
+        resolved.agent_binary,
+        &origin(
+            &[
+                (at, overrides.agent_binary),
+                ("--agent-binary", cli.agent_binary.is_some()),
+                ("agent_binary", file.agent_binary.is_some()),
+            ],
+            &runner_default,
+        ),
+    );
+    line(
+        out,
+        "model",
+        &show(resolved.agent_model),
+        &origin(
+            &[
+                (at, overrides.agent_model),
+                ("--agent-model", cli.agent_model.is_some()),
+                ("agent_model", file.agent_model.is_some()),
+            ],
+            &unset_or_default(resolved.agent_model),
+        ),
+    );
+    line(
+        out,
+        "effort",
+        &show(resolved.agent_effort),
+        &origin(
+            &[
+                (at, overrides.agent_effort),
+                ("--agent-effort", cli.agent_effort.is_some()),
+                ("agent_effort", file.agent_effort.is_some()),
+            ],
+            &unset_or_default(resolved.agent_effort),
+        ),
+    );
+    line(
+        out,
+        "variant",
+        &show(resolved.agent_variant),
+        &origin(
+            &[
+                (at, overrides.agent_variant),
+                ("--agent-variant", cli.agent_variant.is_some()),
+                ("agent_variant", file.agent_variant.is_some()),
+            ],
+            "unset",
+        ),
+    );
+    line(
+        out,
+        "temperature",
+        &resolved
+            .agent_temperature
+            .map_or("-".to_string(), |t| t.to_string()),
+        &origin(
+            &[
+                (at, overrides.agent_temperature),
+                ("agent_temperature", file.agent_temperature.is_some()),
+            ],
+            "unset",
+        ),
+    );
+    if !resolved.agent_extra_args.is_empty() || overrides.agent_extra_args {
+        line(
+            out,
+            "extra_args",
+            &resolved.agent_extra_args.join(" "),
+            &origin(
+                &[
+                    (at, overrides.agent_extra_args),
+                    ("agent_extra_args", file.agent_extra_args.is_some()),
+                ],
+                "unset",
+            ),
+        );
+    }
+    line(
+        out,
+        "timeout",
+        &resolved
+            .agent_timeout
+            .map_or("none".to_string(), |t| format!("{t}s")),
+        &origin(
+            &[
+                (at, overrides.agent_timeout),
+                ("--agent-timeout", cli.agent_timeout.is_some()),
+                ("agent_timeout", file.agent_timeout.is_some()),
+            ],
+            "default",
+        ),
+    );
+    let permission_default = if config.untrusted_sources && !config.allow_dangerous_permissions {
+        "untrusted_sources default"
+    } else {
+        "default"
+    };
+    line(
+        out,
+        "permission",
+        &resolved.permission_mode,
+        &origin(
+            &[
+                (at, overrides.permission_mode),
+                ("--permission-mode", cli.permission_mode.is_some()),
+                ("permission_mode", file.permission_mode.is_some()),
+            ],
+            permission_default,
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::config::{merge, parse_config};
+
+    #[test]
+    fn test_explain_traces_each_setting_to_its_level() {
+        let file = parse_config(
+            r#"
+agent_effort = "medium"
+agent_timeout = 900
+
+[[review_phases]]
+name = "correctness"
+prompt = "correctness-review"
+agent_model = "sonnet"
+context = ["diff"]
+
+[[review_phases]]
+name = "security"
+prompt = "security-review"
+
+[[review_phases]]
+name = "clippy"
+type = "command"
+command = "cargo clippy --message-format=json"
+format = "clippy"
+
+[review_fix]
+runner = "codex"
+"#,
+        )
+        .unwrap();
+        let cli = Cli::parse_from([
+            "rlph",
+            "--agent-timeout",
+            "1200",
+            "--review-phases",
+            "correctness,clippy",
+        ]);
+        let config = merge(file.clone(), &cli).unwrap();
+        let out = explain_review(
+            "PR #12",
+            &file,
+            &cli,
+            &config,
+            &PromptEngine::new(None).with_version(&config.prompt_version),
+        )
+        .unwrap();
+
+        let setting = |phase: &str, key: &str| -> String {
+            out.split("\n\n")
+                .find(|block| block.starts_with(phase))
+                .and_then(|block| block.lines().find(|l| l.trim_start().starts_with(key)))
+                .unwrap_or_else(|| panic!("no {key} under {phase}:\n{out}"))
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        assert!(
+            out.starts_with("Review of PR #12 (nothing is run)\n"),
+            "{out}"
+        );
+        assert_eq!(
+            setting("phase correctness", "model"),
+            "model sonnet <- [[review_phases]] correctness"
+        );
+        assert_eq!(
+            setting("phase correctness", "effort"),
+            "effort medium <- agent_effort"
+        );
+        assert_eq!(
+            setting("phase correctness", "timeout"),
+            "timeout 1200s <- --agent-timeout"
+        );
+        assert_eq!(
+            setting("phase correctness", "prompt"),
+            "prompt correctness-review (built-in v1) <- [[review_phases]] correctness"
+        );
+        assert_eq!(
+            setting("phase correctness", "binary"),
+            "binary claude <- claude default"
+        );
+        assert!(
+            out.contains("phase security: skipped by --review-phases"),
+            "{out}"
+        );
+        assert_eq!(
+            setting("phase clippy", "command"),
+            "command cargo clippy --message-format=json <- [[review_phases]] clippy"
+        );
+        assert_eq!(
+            setting("fix step", "runner"),
+            "runner codex <- [review_fix]"
+        );
+        assert_eq!(
+            setting("fix step", "effort"),
+            "effort medium <- agent_effort"
+        );
+        assert_eq!(
+            setting("aggregate step", "prompt"),
+            "prompt review-aggregate (built-in v1) <- default"
+        );
+    }
+}
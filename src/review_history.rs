@@ -0,0 +1,157 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::review_schema::ReviewFinding;
+
+/// HTML markers around the history table in the review comment, so later rounds
+/// (and later runs) can find the rows and append to them.
+pub const HISTORY_START: &str = "<!-- rlph-review-history -->";
+pub const HISTORY_END: &str = "<!-- /rlph-review-history -->";
+
+/// One review round as listed in the review comment's history table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewRound {
+    pub round: usize,
+    pub verdict: String,
+    /// Findings whose ID was not reported by the previous round.
+    pub new_findings: usize,
+    /// Findings of the previous round that are no longer reported.
+    pub resolved_findings: usize,
+    pub open_findings: usize,
+    /// Summary reported by the fix agent, once it has run.
+    pub fix_summary: Option<String>,
+}
+
+impl ReviewRound {
+    /// The round as a markdown table row.
+    pub fn row(&self) -> String {
+        let fix = self
+            .fix_summary
+            .as_deref()
+            .and_then(|s| s.lines().map(str::trim).find(|l| !l.is_empty()))
+            .unwrap_or_default()
+            .replace('|', "\\|");
+        let cells = [
+            self.round.to_string(),
+            self.verdict.clone(),
+            self.new_findings.to_string(),
+            self.resolved_findings.to_string(),
+            self.open_findings.to_string(),
+            fix,
+        ];
+        let mut row = String::from("|");
+        for cell in cells {
+            if cell.is_empty() {
+                row.push_str(" |");
+            } else {
+                write!(row, " {cell} |").unwrap();
+            }
+        }
+        row
+    }
+}
+
+/// Count findings new in `current` and resolved since `previous`, matched by ID.
+pub fn findings_delta(previous: &[ReviewFinding], current: &[ReviewFinding]) -> (usize, usize) {
+    let previous_ids: HashSet<&str> = previous.iter().map(|f| f.id.as_str()).collect();
+    let current_ids: HashSet<&str> = current.iter().map(|f| f.id.as_str()).collect();
+    (
+        current_ids.difference(&previous_ids).count(),
+        previous_ids.difference(&current_ids).count(),
+    )
+}
+
+/// Rows of the history table in an existing review comment, oldest first.
+pub fn parse_history(body: &str) -> Vec<String> {
+    let Some((_, rest)) = body.split_once(HISTORY_START) else {
+        return Vec::new();
+    };
+    let table = rest
+        .split_once(HISTORY_END)
+        .map_or(rest, |(table, _)| table);
+    table
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            line.strip_prefix('|')
+                .and_then(|cells| cells.split('|').next())
+                .is_some_and(|round| round.trim().parse::<usize>().is_ok())
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// Render the collapsible history block for the review comment. Empty without rows.
+pub fn render_history(rows: &[String]) -> String {
+    if rows.is_empty() {
+        return String::new();
+    }
+    let rounds = if rows.len() == 1 { "round" } else { "rounds" };
+    let mut out = format!(
+        "<details>\n<summary>Review history ({} {rounds})</summary>\n\n{HISTORY_START}\n\
+         | Round | Verdict | New | Resolved | Open | Fix |\n\
+         | --- | --- | --- | --- | --- | --- |",
+        rows.len()
+    );
+    for row in rows {
+        write!(out, "\n{row}").unwrap();
+    }
+    write!(out, "\n{HISTORY_END}\n</details>").unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review_schema::Severity;
+
+    fn finding(id: &str) -> ReviewFinding {
+        ReviewFinding {
+            id: id.to_string(),
+            file: "src/lib.rs".to_string(),
+            line: 1,
+            severity: Severity::Warning,
+            description: "issue".to_string(),
+            category: None,
+            depends_on: vec![],
+            extra: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_findings_delta_matches_by_id() {
+        let previous = [finding("a"), finding("b")];
+        let current = [finding("b"), finding("c"), finding("d")];
+        assert_eq!(findings_delta(&previous, &current), (2, 1));
+        assert_eq!(findings_delta(&[], &current), (3, 0));
+    }
+
+    #[test]
+    fn test_row_escapes_fix_summary() {
+        let mut round = ReviewRound {
+            round: 2,
+            verdict: "needs_fix".to_string(),
+            new_findings: 1,
+            resolved_findings: 3,
+            open_findings: 1,
+            fix_summary: None,
+        };
+        assert_eq!(round.row(), "| 2 | needs_fix | 1 | 3 | 1 | |");
+        round.fix_summary = Some("\nguard a|b\nsecond line".to_string());
+        assert_eq!(round.row(), "| 2 | needs_fix | 1 | 3 | 1 | guard a\\|b |");
+    }
+
+    #[test]
+    fn test_render_and_parse_round_trip() {
+        assert_eq!(render_history(&[]), "");
+        assert!(parse_history("no history here").is_empty());
+
+        let rows = vec![
+            "| 1 | needs_fix | 2 | 0 | 2 | fixed both |".to_string(),
+            "| 2 | approved | 0 | 2 | 0 | |".to_string(),
+        ];
+        let body = format!("summary\n\n{}", render_history(&rows));
+        assert!(body.contains("<summary>Review history (2 rounds)</summary>"));
+        assert_eq!(parse_history(&body), rows);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::config::ReviewPhaseConfigFile;
+use crate::error::{Error, Result};
+
+/// Preset used when neither `review_preset` nor `[[review_phases]]` is configured.
+pub const DEFAULT_REVIEW_PRESET: &str = "default";
+
+/// Names accepted by `review_preset`.
+pub const REVIEW_PRESETS: [&str; 4] = ["default", "minimal", "rust-strict", "security"];
+
+/// Expand a built-in preset into its review phases. Phase settings not listed
+/// here (runner, model, permissions) inherit the global config as usual.
+pub fn review_preset(name: &str) -> Result<Vec<ReviewPhaseConfigFile>> {
+    let phases = match name {
+        "default" => vec![
+            agent_phase("correctness", "correctness-review"),
+            agent_phase("security", "security-review"),
+            agent_phase("hygiene", "hygiene-review"),
+        ],
+        "minimal" => vec![agent_phase("correctness", "correctness-review")],
+        "rust-strict" => vec![
+            ReviewPhaseConfigFile {
+                kind: Some("command".to_string()),
+                command: Some(
+                    "cargo clippy --all-targets --message-format=json -- -D warnings".to_string(),
+                ),
+                format: Some("clippy".to_string()),
+                ..agent_phase("clippy", "")
+            },
+            ReviewPhaseConfigFile {
+                fail_fast: Some(true),
+                ..agent_phase("correctness", "correctness-review")
+            },
+            ReviewPhaseConfigFile {
+                fail_fast: Some(true),
+                ..agent_phase("unsafe", "unsafe-review")
+            },
+            agent_phase("perf", "perf-review"),
+            agent_phase("hygiene", "hygiene-review"),
+        ],
+        "security" => vec![
+            ReviewPhaseConfigFile {
+                fail_fast: Some(true),
+                ..agent_phase("security", "security-review")
+            },
+            agent_phase("correctness", "correctness-review"),
+        ],
+        other => {
+            return Err(Error::ConfigValidation(format!(
+                "unknown review_preset: {other} (expected: {})",
+                REVIEW_PRESETS.join(", ")
+            )));
+        }
+    };
+    Ok(phases)
+}
+
+fn agent_phase(name: &str, prompt: &str) -> ReviewPhaseConfigFile {
+    ReviewPhaseConfigFile {
+        name: name.to_string(),
+        prompt: prompt.to_string(),
+        kind: None,
+        command: None,
+        format: None,
+        threshold: None,
+        runner: None,
+        agent_binary: None,
+        agent_model: None,
+        agent_effort: None,
+        agent_variant: None,
+        agent_temperature: None,
+        agent_extra_args: None,
+        agent_timeout: None,
+        permission_mode: None,
+        fail_fast: None,
+        context: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompts::PromptEngine;
+
+    #[test]
+    fn test_every_preset_expands_with_known_prompts() {
+        let engine = PromptEngine::new(None);
+        for name in REVIEW_PRESETS {
+            let phases = review_preset(name).unwrap();
+            assert!(!phases.is_empty(), "{name}");
+            for phase in phases.iter().filter(|p| p.kind.is_none()) {
+                engine
+                    .load_template(&phase.prompt)
+                    .unwrap_or_else(|e| panic!("{name}/{}: {e}", phase.name));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rust_strict_phases() {
+        let phases = review_preset("rust-strict").unwrap();
+        let names: Vec<&str> = phases.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["clippy", "correctness", "unsafe", "perf", "hygiene"]
+        );
+        assert_eq!(phases[0].kind.as_deref(), Some("command"));
+        assert_eq!(phases[2].fail_fast, Some(true));
+    }
+
+    #[test]
+    fn test_unknown_preset_rejected() {
+        let err = review_preset("go-strict").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("unknown review_preset: go-strict (expected: default, minimal")
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Write;
 
 use serde::{Deserialize, Deserializer, Serialize};
@@ -70,6 +70,15 @@ pub enum Verdict {
     NeedsFix,
 }
 
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Approved => write!(f, "approved"),
+            Verdict::NeedsFix => write!(f, "needs_fix"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ReviewFinding {
     pub id: String,
@@ -80,6 +89,52 @@ pub struct ReviewFinding {
     pub category: Option<String>,
     #[serde(default, deserialize_with = "deserialize_null_as_empty_vec")]
     pub depends_on: Vec<String>,
+    /// Extension fields outside the core schema (e.g. `confidence`), carried through verbatim.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// Core finding fields, which cannot be configured as extension fields.
+pub const CORE_FINDING_FIELDS: &[&str] = &[
+    "id",
+    "file",
+    "line",
+    "severity",
+    "description",
+    "category",
+    "depends_on",
+];
+
+impl ReviewFinding {
+    /// Value of an extension field as display text. Strings are returned without quotes;
+    /// `null` and empty strings count as absent.
+    pub fn extra_text(&self, field: &str) -> Option<String> {
+        match self.extra.get(field)? {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(s) if s.trim().is_empty() => None,
+            serde_json::Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+}
+
+/// Check that every finding carries the configured extension fields.
+pub fn check_required_fields(findings: &[ReviewFinding], required: &[String]) -> Result<()> {
+    for f in findings {
+        let missing: Vec<&str> = required
+            .iter()
+            .filter(|field| f.extra_text(field).is_none())
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(Error::Orchestrator(format!(
+                "finding '{}' is missing required field(s): {}",
+                f.id,
+                missing.join(", ")
+            )));
+        }
+    }
+    Ok(())
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
@@ -138,6 +193,10 @@ pub fn render_findings_for_prompt(
         if !f.depends_on.is_empty() {
             write!(result, " (depends on: {})", f.depends_on.join(", ")).unwrap();
         }
+        if !f.extra.is_empty() {
+            let extras: Vec<String> = f.extra.iter().map(|(k, v)| format!("{k}: {v}")).collect();
+            write!(result, " {{{}}}", extras.join(", ")).unwrap();
+        }
     }
     result
 }
@@ -156,6 +215,19 @@ pub fn capitalize_first(s: &str) -> String {
 /// headings with checklist items sorted by severity (critical first), then
 /// file+line.
 pub fn render_findings_for_github(findings: &[ReviewFinding], summary: &str) -> String {
+    render_findings_for_github_with(findings, summary, None)
+}
+
+/// Like [`render_findings_for_github`], but renders each finding's visible text with
+/// `finding_template` when given. The template sees `id`, `file`, `line`, `severity`,
+/// `description`, `category`, `depends_on`, and every extension field of the finding.
+/// Findings the template cannot render (e.g. a referenced field is absent) fall back to
+/// the default format. The embedded finding JSON is always appended.
+pub fn render_findings_for_github_with(
+    findings: &[ReviewFinding],
+    summary: &str,
+    finding_template: Option<&str>,
+) -> String {
     let mut body = summary.trim().to_string();
 
     if findings.is_empty() {
@@ -176,17 +248,22 @@ pub fn render_findings_for_github(findings: &[ReviewFinding], summary: &str) ->
 
         write!(body, "\n\n### {}", capitalize_first(category)).unwrap();
         for f in sorted {
-            write!(
-                body,
-                "\n- [ ] **{}** `{}` L{}: {}",
-                f.severity.label(),
-                f.file,
-                f.line,
-                f.description
-            )
-            .unwrap();
-            if !f.depends_on.is_empty() {
-                write!(body, " *(depends on: {})*", f.depends_on.join(", ")).unwrap();
+            let custom = finding_template.and_then(|t| render_finding_template(t, f, category));
+            if let Some(text) = custom {
+                write!(body, "\n- [ ] {}", text.trim()).unwrap();
+            } else {
+                write!(
+                    body,
+                    "\n- [ ] **{}** `{}` L{}: {}",
+                    f.severity.label(),
+                    f.file,
+                    f.line,
+                    f.description
+                )
+                .unwrap();
+                if !f.depends_on.is_empty() {
+                    write!(body, " *(depends on: {})*", f.depends_on.join(", ")).unwrap();
+                }
             }
             let json = serde_json::to_string(f).expect("ReviewFinding serializes to JSON");
             let json = json.replace("--", r"\u002d\u002d");
@@ -197,6 +274,23 @@ pub fn render_findings_for_github(findings: &[ReviewFinding], summary: &str) ->
     body
 }
 
+/// Render one finding with a user template, or `None` if rendering fails.
+fn render_finding_template(template: &str, f: &ReviewFinding, category: &str) -> Option<String> {
+    let mut vars: HashMap<String, String> = f
+        .extra
+        .keys()
+        .filter_map(|k| Some((k.clone(), f.extra_text(k)?)))
+        .collect();
+    vars.insert("id".to_string(), f.id.clone());
+    vars.insert("file".to_string(), f.file.clone());
+    vars.insert("line".to_string(), f.line.to_string());
+    vars.insert("severity".to_string(), f.severity.label().to_string());
+    vars.insert("description".to_string(), f.description.clone());
+    vars.insert("category".to_string(), category.to_string());
+    vars.insert("depends_on".to_string(), f.depends_on.join(", "));
+    crate::prompts::render_template(template, &vars).ok()
+}
+
 /// Strip markdown code fences (` ```json ... ``` `) that Claude sometimes wraps output in,
 /// then parse as `AggregatorOutput`.
 pub fn parse_aggregator_output(raw: &str) -> Result<AggregatorOutput> {
@@ -239,6 +333,50 @@ pub fn parse_fix_output(raw: &str) -> Result<FixOutput> {
         .map_err(|e| Error::Orchestrator(format!("failed to parse fix JSON: {e}")))
 }
 
+/// How a fix agent's report disagrees with the files it actually changed
+/// (repo-relative paths), or `None` when it matches. Claiming `fixed` without
+/// changing anything counts as a disagreement.
+pub fn fix_report_mismatch(output: &FixOutput, changed: &[String]) -> Option<String> {
+    let normalize = |path: &str| path.trim().trim_start_matches("./").to_string();
+    let reported: Vec<String> = output.files_changed.iter().map(|f| normalize(f)).collect();
+    if changed.is_empty() {
+        return (output.status == FixStatus::Fixed)
+            .then(|| "status is \"fixed\" but no files changed".to_string());
+    }
+    let unchanged: Vec<&str> = reported
+        .iter()
+        .filter(|f| !changed.contains(f))
+        .map(String::as_str)
+        .collect();
+    let unreported: Vec<&str> = changed
+        .iter()
+        .filter(|f| !reported.contains(f))
+        .map(String::as_str)
+        .collect();
+    let mut problems = Vec::new();
+    if !unchanged.is_empty() {
+        problems.push(format!("reported but unchanged: {}", unchanged.join(", ")));
+    }
+    if !unreported.is_empty() {
+        problems.push(format!(
+            "changed but not reported: {}",
+            unreported.join(", ")
+        ));
+    }
+    (!problems.is_empty()).then(|| problems.join("; "))
+}
+
+/// Prompt resuming a fix agent whose report disagrees with the worktree.
+pub fn fix_report_correction_prompt(mismatch: &str) -> String {
+    format!(
+        "Your fix report does not match the changes in the worktree: {mismatch}.\n\n\
+         Finish any fixes you reported but did not make, then return ONLY a JSON object \
+         listing every file you changed (no markdown fences, no extra text):\n\
+         {example}",
+        example = SchemaName::Fix.example_json(),
+    )
+}
+
 /// Parse the standalone fix agent's JSON output into `StandaloneFixOutput`.
 pub fn parse_standalone_fix_output(raw: &str) -> Result<StandaloneFixOutput> {
     let json = strip_markdown_fences(raw);
@@ -246,6 +384,79 @@ pub fn parse_standalone_fix_output(raw: &str) -> Result<StandaloneFixOutput> {
         .map_err(|e| Error::Orchestrator(format!("failed to parse standalone fix JSON: {e}")))
 }
 
+/// Task size estimate produced by the estimation agent. Ordered `S < M < L`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Complexity {
+    S,
+    M,
+    L,
+}
+
+impl std::str::FromStr for Complexity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "s" | "small" => Ok(Complexity::S),
+            "m" | "medium" => Ok(Complexity::M),
+            "l" | "large" => Ok(Complexity::L),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown complexity: {other} (expected: S, M, L)"
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for Complexity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Complexity::S => write!(f, "S"),
+            Complexity::M => write!(f, "M"),
+            Complexity::L => write!(f, "L"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Complexity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Structured output from the estimation agent.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EstimateOutput {
+    pub complexity: Complexity,
+    #[serde(default)]
+    pub risk_notes: String,
+}
+
+/// Parse the estimation agent's JSON output into `EstimateOutput`.
+pub fn parse_estimate_output(raw: &str) -> Result<EstimateOutput> {
+    let json = strip_markdown_fences(raw);
+    serde_json::from_str(&json)
+        .map_err(|e| Error::Orchestrator(format!("failed to parse estimate JSON: {e}")))
+}
+
+/// Structured output from the translation agent.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TranslationOutput {
+    /// Language the task was written in, e.g. `German`.
+    pub language: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+}
+
+/// Parse the translation agent's JSON output into `TranslationOutput`.
+pub fn parse_translation_output(raw: &str) -> Result<TranslationOutput> {
+    let json = strip_markdown_fences(raw);
+    serde_json::from_str(&json)
+        .map_err(|e| Error::Orchestrator(format!("failed to parse translation JSON: {e}")))
+}
+
 /// Schema names for the correction prompt generator.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SchemaName {
@@ -253,6 +464,7 @@ pub enum SchemaName {
     Aggregator,
     Fix,
     StandaloneFix,
+    Estimate,
 }
 
 impl SchemaName {
@@ -271,6 +483,9 @@ impl SchemaName {
             SchemaName::StandaloneFix => {
                 r#"{"status": "fixed", "commit_message": "finding-id: description of fix"}"#
             }
+            SchemaName::Estimate => {
+                r#"{"complexity": "M", "risk_notes": "touches the config loader"}"#
+            }
         }
     }
 }
@@ -303,6 +518,133 @@ pub fn group_by_category<'a, T>(
     groups
 }
 
+/// File format for exported review findings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// SARIF 2.1.0, as accepted by GitHub code scanning.
+    #[default]
+    Sarif,
+    /// rlph's own finding schema, wrapped with the task and round.
+    Json,
+}
+
+impl ExportFormat {
+    /// Conventional file extension, without the dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Sarif => "sarif",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "sarif" => Ok(ExportFormat::Sarif),
+            "json" => Ok(ExportFormat::Json),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown findings_export format: {other} (expected: sarif, json)"
+            ))),
+        }
+    }
+}
+
+/// Serialize one review round's findings of `task_id` in `format`.
+pub fn export_findings(
+    format: ExportFormat,
+    task_id: &str,
+    round: u32,
+    findings: &[ReviewFinding],
+) -> String {
+    let document = match format {
+        ExportFormat::Sarif => findings_to_sarif(task_id, round, findings),
+        ExportFormat::Json => serde_json::json!({
+            "task_id": task_id,
+            "round": round,
+            "findings": findings,
+        }),
+    };
+    serde_json::to_string_pretty(&document).unwrap_or_default()
+}
+
+/// SARIF level for a finding severity.
+fn sarif_level(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+/// A SARIF 2.1.0 log with one run. Each finding's category becomes its rule
+/// (`general` without one); findings without a file have no location.
+fn findings_to_sarif(task_id: &str, round: u32, findings: &[ReviewFinding]) -> serde_json::Value {
+    let rule_id = |f: &ReviewFinding| {
+        f.category
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .unwrap_or("general")
+            .to_lowercase()
+    };
+    let rules: Vec<serde_json::Value> = findings
+        .iter()
+        .map(rule_id)
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .map(|id| {
+            serde_json::json!({
+                "id": id,
+                "shortDescription": { "text": format!("rlph {id} review") },
+            })
+        })
+        .collect();
+    let results: Vec<serde_json::Value> = findings
+        .iter()
+        .map(|f| {
+            let mut result = serde_json::json!({
+                "ruleId": rule_id(f),
+                "level": sarif_level(&f.severity),
+                "message": { "text": f.description.trim() },
+                "partialFingerprints": { "rlphFindingId": f.id },
+                "properties": {
+                    "id": f.id,
+                    "severity": f.severity,
+                    "depends_on": f.depends_on,
+                    "extra": f.extra,
+                },
+            });
+            if !f.file.trim().is_empty() {
+                result["locations"] = serde_json::json!([{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": f.file.trim() },
+                        "region": { "startLine": f.line.max(1) },
+                    }
+                }]);
+            }
+            result
+        })
+        .collect();
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "rlph",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "properties": { "task_id": task_id, "round": round },
+            "results": results,
+        }],
+    })
+}
+
 /// Extract the raw JSON payload from a `<!-- rlph-finding:{json} -->` marker in a line.
 ///
 /// Returns the JSON slice between the marker and ` -->`, or `None` if not found.
@@ -340,6 +682,7 @@ fn strip_markdown_fences(input: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_helpers::make_finding;
 
     #[test]
     fn test_parse_valid_approved() {
@@ -548,6 +891,36 @@ mod tests {
         assert!(parse_phase_output("not json").is_err());
     }
 
+    #[test]
+    fn test_extension_fields_carried_through() {
+        let json = r#"{"findings": [{"id": "a", "file": "a.rs", "line": 1, "severity": "info", "description": "d", "confidence": 0.9, "suggested_patch": "x"}]}"#;
+        let output = parse_phase_output(json).unwrap();
+        let f = &output.findings[0];
+        assert_eq!(f.extra_text("confidence").as_deref(), Some("0.9"));
+        assert_eq!(f.extra_text("suggested_patch").as_deref(), Some("x"));
+        assert_eq!(f.extra_text("missing"), None);
+
+        // Extras survive the JSON embedded in GitHub comments
+        let serialized = serde_json::to_string(f).unwrap();
+        let back: ReviewFinding = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(&back, f);
+    }
+
+    #[test]
+    fn test_check_required_fields() {
+        let json = r#"{"findings": [{"id": "a", "file": "a.rs", "line": 1, "severity": "info", "description": "d", "confidence": "high", "owner": ""}]}"#;
+        let findings = parse_phase_output(json).unwrap().findings;
+        assert!(check_required_fields(&findings, &[]).is_ok());
+        assert!(check_required_fields(&findings, &["confidence".to_string()]).is_ok());
+        let err =
+            check_required_fields(&findings, &["confidence".to_string(), "owner".to_string()])
+                .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("finding 'a' is missing required field(s): owner")
+        );
+    }
+
     // ---- render_findings_for_prompt tests ----
 
     #[test]
@@ -565,6 +938,7 @@ mod tests {
             description: "SQL injection vulnerability".to_string(),
             category: None,
             depends_on: vec![],
+            extra: Default::default(),
         }];
         let rendered = render_findings_for_prompt(&findings, Some("security"));
         assert_eq!(
@@ -584,6 +958,7 @@ mod tests {
                 description: "Bug".to_string(),
                 category: Some("correctness".to_string()),
                 depends_on: vec![],
+                extra: Default::default(),
             },
             ReviewFinding {
                 id: "unused-import".to_string(),
@@ -593,6 +968,7 @@ mod tests {
                 description: "Unused import".to_string(),
                 category: None,
                 depends_on: vec![],
+                extra: Default::default(),
             },
             ReviewFinding {
                 id: "nit-util".to_string(),
@@ -602,6 +978,7 @@ mod tests {
                 description: "Nit".to_string(),
                 category: None,
                 depends_on: vec![],
+                extra: Default::default(),
             },
         ];
         let rendered = render_findings_for_prompt(&findings, Some("style"));
@@ -622,6 +999,7 @@ mod tests {
             description: "nit".to_string(),
             category: None,
             depends_on: vec![],
+            extra: Default::default(),
         }];
         let rendered = render_findings_for_prompt(&findings, None);
         assert_eq!(
@@ -645,6 +1023,42 @@ mod tests {
         assert_eq!(output.files_changed, vec!["src/main.rs", "src/db.rs"]);
     }
 
+    #[test]
+    fn test_fix_report_mismatch() {
+        let output = |status: FixStatus, files: &[&str]| FixOutput {
+            status,
+            summary: String::new(),
+            files_changed: files.iter().map(|f| f.to_string()).collect(),
+        };
+        let changed = vec!["src/db.rs".to_string(), "src/main.rs".to_string()];
+
+        assert_eq!(
+            fix_report_mismatch(
+                &output(FixStatus::Fixed, &["./src/main.rs", "src/db.rs"]),
+                &changed
+            ),
+            None
+        );
+        assert_eq!(
+            fix_report_mismatch(&output(FixStatus::Fixed, &["src/main.rs"]), &[]),
+            Some("status is \"fixed\" but no files changed".to_string())
+        );
+        assert_eq!(
+            fix_report_mismatch(&output(FixStatus::Error, &[]), &[]),
+            None
+        );
+        assert_eq!(
+            fix_report_mismatch(
+                &output(FixStatus::Fixed, &["src/main.rs", "src/lib.rs"]),
+                &changed
+            ),
+            Some(
+                "reported but unchanged: src/lib.rs; changed but not reported: src/db.rs"
+                    .to_string()
+            )
+        );
+    }
+
     #[test]
     fn test_parse_fix_output_empty_files_changed() {
         let json = r#"{
@@ -761,6 +1175,7 @@ mod tests {
             description: "Redundant clone inside loop".to_string(),
             category: Some("efficiency".to_string()),
             depends_on: vec![],
+            extra: Default::default(),
         }];
         let rendered = render_findings_for_prompt(&findings, None);
         assert_eq!(
@@ -779,6 +1194,7 @@ mod tests {
             description: "Null pointer dereference".to_string(),
             category: Some("correctness".to_string()),
             depends_on: vec!["null-check-missing".to_string()],
+            extra: Default::default(),
         }];
         let rendered = render_findings_for_prompt(&findings, None);
         assert_eq!(
@@ -812,6 +1228,39 @@ mod tests {
         assert!(serde_json::from_str::<AggregatorOutput>(example).is_ok());
     }
 
+    #[test]
+    fn test_parse_estimate_output() {
+        let output =
+            parse_estimate_output("```json\n{\"complexity\":\"l\",\"risk_notes\":\"big\"}\n```")
+                .unwrap();
+        assert_eq!(output.complexity, Complexity::L);
+        assert_eq!(output.risk_notes, "big");
+        assert!(parse_estimate_output(r#"{"complexity":"XL"}"#).is_err());
+        let example = SchemaName::Estimate.example_json();
+        assert!(parse_estimate_output(example).is_ok());
+    }
+
+    #[test]
+    fn test_parse_translation_output() {
+        let output = parse_translation_output(
+            "```json\n{\"language\":\"German\",\"title\":\"Fix login\",\"body\":\"It crashes.\"}\n```",
+        )
+        .unwrap();
+        assert_eq!(output.language, "German");
+        assert_eq!(output.title, "Fix login");
+        assert_eq!(output.body, "It crashes.");
+        assert!(parse_translation_output(r#"{"title":"x"}"#).is_err());
+    }
+
+    #[test]
+    fn test_complexity_ordering_and_parse() {
+        assert!(Complexity::S < Complexity::M);
+        assert!(Complexity::M < Complexity::L);
+        assert_eq!("medium".parse::<Complexity>().unwrap(), Complexity::M);
+        assert_eq!(Complexity::M.to_string(), "M");
+        assert!("huge".parse::<Complexity>().is_err());
+    }
+
     #[test]
     fn test_correction_prompt_contains_schema_example_fix() {
         let prompt = correction_prompt(SchemaName::Fix, "trailing comma");
@@ -841,6 +1290,35 @@ mod tests {
 
     // ---- render_findings_for_github tests ----
 
+    #[test]
+    fn test_render_github_with_finding_template() {
+        let mut finding = make_finding("low-confidence");
+        finding
+            .extra
+            .insert("confidence".to_string(), serde_json::json!("high"));
+        let template = "[{{ confidence }}] {{ severity }} {{ file }}:{{ line }} {{ description }}";
+        let result = render_findings_for_github_with(&[finding.clone()], "S.", Some(template));
+        assert!(
+            result.contains(&format!(
+                "- [ ] [high] WARNING {}:{} {}",
+                finding.file, finding.line, finding.description
+            )),
+            "got: {result}"
+        );
+        assert!(result.contains(r#""confidence":"high""#));
+
+        // A template referencing an absent field falls back to the default format
+        let fallback = render_findings_for_github_with(
+            &[make_finding("other")],
+            "S.",
+            Some("{{ confidence }}"),
+        );
+        assert_eq!(
+            fallback,
+            render_findings_for_github(&[make_finding("other")], "S.")
+        );
+    }
+
     #[test]
     fn test_github_render_empty_findings() {
         let result = render_findings_for_github(&[], "All good.");
@@ -857,6 +1335,7 @@ mod tests {
             description: "SQL injection".to_string(),
             category: Some("correctness".to_string()),
             depends_on: vec![],
+            extra: Default::default(),
         }];
         let result = render_findings_for_github(&findings, "Issues found.");
         let json = serde_json::to_string(&findings[0])
@@ -879,6 +1358,7 @@ mod tests {
                 description: "Style issue".to_string(),
                 category: Some("style".to_string()),
                 depends_on: vec![],
+                extra: Default::default(),
             },
             ReviewFinding {
                 id: "b".to_string(),
@@ -888,6 +1368,7 @@ mod tests {
                 description: "Bug".to_string(),
                 category: Some("correctness".to_string()),
                 depends_on: vec![],
+                extra: Default::default(),
             },
         ];
         let result = render_findings_for_github(&findings, "Summary.");
@@ -906,6 +1387,7 @@ mod tests {
                 description: "Nit".to_string(),
                 category: Some("correctness".to_string()),
                 depends_on: vec![],
+                extra: Default::default(),
             },
             ReviewFinding {
                 id: "crit-one".to_string(),
@@ -915,6 +1397,7 @@ mod tests {
                 description: "Bug".to_string(),
                 category: Some("correctness".to_string()),
                 depends_on: vec![],
+                extra: Default::default(),
             },
         ];
         let result = render_findings_for_github(&findings, "S.");
@@ -933,6 +1416,7 @@ mod tests {
             description: "Null deref".to_string(),
             category: Some("correctness".to_string()),
             depends_on: vec!["null-check".to_string(), "init-val".to_string()],
+            extra: Default::default(),
         }];
         let result = render_findings_for_github(&findings, "S.");
         assert!(result.contains("*(depends on: null-check, init-val)*"));
@@ -948,6 +1432,7 @@ mod tests {
             description: "Unused import".to_string(),
             category: None,
             depends_on: vec![],
+            extra: Default::default(),
         }];
         let result = render_findings_for_github(&findings, "S.");
         assert!(result.contains("### General"));
@@ -968,6 +1453,7 @@ mod tests {
             description: "Connection leak".to_string(),
             category: Some("correctness".to_string()),
             depends_on: vec!["pool-init".to_string()],
+            extra: Default::default(),
         }];
         let result = render_findings_for_github(&findings, "Review.");
 
@@ -986,6 +1472,7 @@ mod tests {
             description: "Use after free".to_string(),
             category: Some("security".to_string()),
             depends_on: vec!["alloc".to_string(), "dealloc".to_string()],
+            extra: Default::default(),
         };
         let json = serde_json::to_string(&finding).unwrap();
         let round_tripped: ReviewFinding = serde_json::from_str(&json).unwrap();
@@ -1011,6 +1498,7 @@ mod tests {
             description: "Nit".to_string(),
             category: None,
             depends_on: vec![],
+            extra: Default::default(),
         }];
         let result = render_findings_for_github(&findings, "S.");
 
@@ -1029,6 +1517,7 @@ mod tests {
             description: "Outputs --> and --!> unescaped -- dangerous".to_string(),
             category: Some("security".to_string()),
             depends_on: vec!["html--parse".to_string()],
+            extra: Default::default(),
         }];
         let result = render_findings_for_github(&findings, "Review.");
 
@@ -1113,4 +1602,57 @@ mod tests {
         let example = SchemaName::StandaloneFix.example_json();
         assert!(serde_json::from_str::<StandaloneFixOutput>(example).is_ok());
     }
+
+    #[test]
+    fn test_export_findings_sarif_maps_severity_and_location() {
+        let mut critical = make_finding("sql-injection");
+        critical.severity = Severity::Critical;
+        critical.category = Some("Security".to_string());
+        let mut general = make_finding("no-file");
+        general.file = String::new();
+        general.severity = Severity::Info;
+        general.category = None;
+        let findings = [critical, make_finding("off-by-one"), general];
+
+        let raw = export_findings(ExportFormat::Sarif, "gh-42", 2, &findings);
+        let sarif: serde_json::Value = serde_json::from_str(&raw).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["properties"]["round"], 2);
+        let rules: Vec<&str> = run["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(rules, vec!["correctness", "general", "security"]);
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(results[0]["ruleId"], "security");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/main.rs");
+        assert_eq!(location["region"]["startLine"], 42);
+        assert_eq!(results[1]["level"], "warning");
+        assert_eq!(results[2]["level"], "note");
+        assert!(results[2].get("locations").is_none());
+        assert_eq!(
+            results[2]["partialFingerprints"]["rlphFindingId"],
+            "no-file"
+        );
+    }
+
+    #[test]
+    fn test_export_findings_json_roundtrips() {
+        let findings = vec![make_finding("a"), make_finding("b")];
+        let raw = export_findings(ExportFormat::Json, "gh-7", 1, &findings);
+        let value: serde_json::Value = serde_json::from_str(&raw).unwrap();
+        assert_eq!(value["task_id"], "gh-7");
+        assert_eq!(value["round"], 1);
+        let parsed: Vec<ReviewFinding> = serde_json::from_value(value["findings"].clone()).unwrap();
+        assert_eq!(parsed, findings);
+
+        assert_eq!("json".parse::<ExportFormat>().unwrap(), ExportFormat::Json);
+        assert!("csv".parse::<ExportFormat>().is_err());
+    }
 }
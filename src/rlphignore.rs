@@ -0,0 +1,248 @@
+use std::path::Path;
+
+use regex::Regex;
+use tracing::warn;
+
+/// File in the repository root listing paths agents should leave alone.
+pub const RLPHIGNORE_FILE: &str = ".rlphignore";
+
+#[derive(Debug, Clone)]
+struct Rule {
+    /// The line as written in `.rlphignore`.
+    source: String,
+    pattern: String,
+    regex: Regex,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+/// Parsed `.rlphignore` patterns (gitignore syntax).
+///
+/// Matching paths are kept out of review diffs and findings, do not trip the
+/// review write guard, and are listed to agents as "do not modify".
+#[derive(Debug, Clone, Default)]
+pub struct RlphIgnore {
+    rules: Vec<Rule>,
+}
+
+impl RlphIgnore {
+    /// Load `.rlphignore` from `repo_root`. A missing or unreadable file yields
+    /// no patterns.
+    pub fn load(repo_root: &Path) -> Self {
+        let path = repo_root.join(RLPHIGNORE_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "failed to read .rlphignore");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let rules = content.lines().filter_map(parse_rule).collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether a repo-relative file path is ignored. A path is also ignored
+    /// when one of its parent directories is.
+    pub fn is_ignored(&self, path: &str) -> bool {
+        let path = path.trim_start_matches("./");
+        let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+        if parts.is_empty() {
+            return false;
+        }
+        for end in 1..parts.len() {
+            if self.verdict(&parts[..end].join("/"), true) == Some(true) {
+                return true;
+            }
+        }
+        self.verdict(&parts.join("/"), false) == Some(true)
+    }
+
+    /// The patterns as a markdown list for prompts; empty when there are none.
+    pub fn prompt_list(&self) -> String {
+        self.rules
+            .iter()
+            .map(|r| format!("- `{}`", r.source))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Pathspec arguments that drop ignored paths from `git diff`, e.g.
+    /// ` -- . ':(exclude,glob)**/Cargo.lock'`. Empty when there are no patterns.
+    /// Negated patterns cannot be expressed as exclusions and are left out.
+    pub fn diff_excludes(&self) -> String {
+        let excludes: Vec<String> = self
+            .rules
+            .iter()
+            .filter(|r| !r.negated && !r.pattern.contains('\''))
+            .map(|r| {
+                let mut glob = if r.anchored {
+                    r.pattern.clone()
+                } else {
+                    format!("**/{}", r.pattern)
+                };
+                if r.dir_only {
+                    glob.push_str("/**");
+                }
+                format!(" ':(exclude,glob){glob}'")
+            })
+            .collect();
+        if excludes.is_empty() {
+            return String::new();
+        }
+        format!(" -- .{}", excludes.concat())
+    }
+
+    /// Verdict of the last rule matching `path`, if any.
+    fn verdict(&self, path: &str, is_dir: bool) -> Option<bool> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|r| (is_dir || !r.dir_only) && r.regex.is_match(path))
+            .map(|r| !r.negated)
+    }
+}
+
+fn parse_rule(line: &str) -> Option<Rule> {
+    let line = line.trim_end();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let source = line.to_string();
+    let (negated, line) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line.strip_prefix('\\').unwrap_or(line)),
+    };
+    let (dir_only, line) = match line.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    // A slash anywhere but the end anchors the pattern to the repo root.
+    let anchored = line.contains('/');
+    let pattern = line.trim_start_matches('/').to_string();
+    if pattern.is_empty() {
+        return None;
+    }
+    let prefix = if anchored { "" } else { "(?:.*/)?" };
+    let regex = Regex::new(&format!("^{prefix}{}$", glob_to_regex(&pattern))).ok()?;
+    Some(Rule {
+        source,
+        pattern,
+        regex,
+        negated,
+        dir_only,
+        anchored,
+    })
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut out = String::new();
+    let chars: Vec<char> = glob.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                if chars.get(i + 2) == Some(&'/') {
+                    out.push_str("(?:.*/)?");
+                    i += 3;
+                } else {
+                    out.push_str(".*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '[' => match chars[i..].iter().position(|&c| c == ']') {
+                Some(len) if len > 1 => {
+                    let class: String = chars[i + 1..i + len].iter().collect();
+                    let class = class
+                        .strip_prefix('!')
+                        .map_or(class.clone(), |rest| format!("^{rest}"));
+                    out.push('[');
+                    out.push_str(&class.replace('\\', "\\\\"));
+                    out.push(']');
+                    i += len + 1;
+                    continue;
+                }
+                _ => out.push_str("\\["),
+            },
+            c => out.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basename_patterns_match_at_any_depth() {
+        let ignore = RlphIgnore::parse("# lockfiles\nCargo.lock\n*.min.js\n");
+        assert!(ignore.is_ignored("Cargo.lock"));
+        assert!(ignore.is_ignored("crates/core/Cargo.lock"));
+        assert!(ignore.is_ignored("web/dist/app.min.js"));
+        assert!(!ignore.is_ignored("src/main.rs"));
+        assert!(!ignore.is_ignored("Cargo.lock.bak"));
+    }
+
+    #[test]
+    fn test_anchored_and_directory_patterns() {
+        let ignore = RlphIgnore::parse("/vendor/\ndocs/generated/**\ntarget/\n");
+        assert!(ignore.is_ignored("vendor/lib/a.rs"));
+        assert!(!ignore.is_ignored("src/vendor/a.rs"));
+        assert!(ignore.is_ignored("docs/generated/api/x.md"));
+        assert!(!ignore.is_ignored("docs/guide.md"));
+        assert!(ignore.is_ignored("crates/x/target/debug/out"));
+        // A directory-only pattern does not match a file with that name.
+        assert!(!ignore.is_ignored("target"));
+    }
+
+    #[test]
+    fn test_negation_last_match_wins() {
+        let ignore = RlphIgnore::parse("*.json\n!package.json\n");
+        assert!(ignore.is_ignored("data/fixtures.json"));
+        assert!(!ignore.is_ignored("package.json"));
+        assert!(!ignore.is_ignored("./app/package.json"));
+    }
+
+    #[test]
+    fn test_wildcards_and_classes() {
+        let ignore = RlphIgnore::parse("src/**/gen_*.rs\nfile?.txt\nlog[0-9].txt\n");
+        assert!(ignore.is_ignored("src/gen_a.rs"));
+        assert!(ignore.is_ignored("src/a/b/gen_b.rs"));
+        assert!(!ignore.is_ignored("lib/gen_a.rs"));
+        assert!(ignore.is_ignored("file1.txt"));
+        assert!(!ignore.is_ignored("file10.txt"));
+        assert!(ignore.is_ignored("log3.txt"));
+        assert!(!ignore.is_ignored("logx.txt"));
+    }
+
+    #[test]
+    fn test_prompt_list_and_diff_excludes() {
+        let empty = RlphIgnore::parse("\n# nothing\n");
+        assert!(empty.is_empty());
+        assert_eq!(empty.prompt_list(), "");
+        assert_eq!(empty.diff_excludes(), "");
+
+        let ignore = RlphIgnore::parse("Cargo.lock\n/vendor/\n!keep.lock\n");
+        assert_eq!(
+            ignore.prompt_list(),
+            "- `Cargo.lock`\n- `/vendor/`\n- `!keep.lock`"
+        );
+        assert_eq!(
+            ignore.diff_excludes(),
+            " -- . ':(exclude,glob)**/Cargo.lock' ':(exclude,glob)vendor/**'"
+        );
+    }
+}
@@ -5,12 +5,13 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 use tracing::warn;
 
 use crate::error::{Error, Result};
 use crate::process::{ProcessConfig, spawn_and_stream};
+use crate::stream::{PhaseOutput, StreamMode, color_enabled, prefix_label};
 
 /// Which agent backend to dispatch to.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
@@ -54,6 +55,11 @@ pub enum Phase {
     ReviewAggregate,
     ReviewFix,
     Fix,
+    Estimate,
+    /// Translating a task into `agent_language`.
+    Translate,
+    /// One-word check that the runner can answer before a task is claimed.
+    Probe,
 }
 
 impl fmt::Display for Phase {
@@ -65,6 +71,46 @@ impl fmt::Display for Phase {
             Phase::ReviewAggregate => write!(f, "review-aggregate"),
             Phase::ReviewFix => write!(f, "review-fix"),
             Phase::Fix => write!(f, "fix"),
+            Phase::Estimate => write!(f, "estimate"),
+            Phase::Translate => write!(f, "translate"),
+            Phase::Probe => write!(f, "probe"),
+        }
+    }
+}
+
+/// How much autonomy an agent CLI is granted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PermissionMode {
+    /// Skip all permission prompts and sandboxing (`--dangerously-*` flags).
+    #[default]
+    Full,
+    /// Allow edits inside the working directory and local git commands only.
+    WorkspaceWrite,
+    /// Allow reading files and inspecting git history, but no edits.
+    ReadOnly,
+}
+
+impl fmt::Display for PermissionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PermissionMode::Full => write!(f, "full"),
+            PermissionMode::WorkspaceWrite => write!(f, "workspace-write"),
+            PermissionMode::ReadOnly => write!(f, "read-only"),
+        }
+    }
+}
+
+impl FromStr for PermissionMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(PermissionMode::Full),
+            "workspace-write" => Ok(PermissionMode::WorkspaceWrite),
+            "read-only" => Ok(PermissionMode::ReadOnly),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown permission_mode: {other} (expected: full, workspace-write, read-only)"
+            ))),
         }
     }
 }
@@ -110,6 +156,27 @@ pub struct RunResult {
     pub stdout: String,
     pub stderr: String,
     pub session_id: Option<String>,
+    /// Tokens reported by the agent; zero when its output carries no usage.
+    pub usage: TokenUsage,
+}
+
+/// Token counts reported by an agent run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    /// Prompt tokens, including cache reads and writes.
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+    }
+
+    pub fn total(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
 }
 
 pub trait AgentRunner {
@@ -122,19 +189,82 @@ pub trait AgentRunner {
     ) -> impl std::future::Future<Output = Result<RunResult>> + Send;
 }
 
+/// Boxed future returned by [`DynRunner::run_boxed`].
+pub type BoxFuture<'a, T> = Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/// Object-safe counterpart of [`AgentRunner`].
+///
+/// Every `AgentRunner + Send + Sync` is a `DynRunner`, so a runner from another
+/// crate can be boxed as `Box<dyn DynRunner>` (itself an `AgentRunner`) or
+/// wrapped with [`AnyRunner::custom`].
+pub trait DynRunner: Send + Sync {
+    fn run_boxed<'a>(
+        &'a self,
+        phase: Phase,
+        prompt: &'a str,
+        working_dir: &'a Path,
+    ) -> BoxFuture<'a, Result<RunResult>>;
+}
+
+impl<T: AgentRunner + Send + Sync> DynRunner for T {
+    fn run_boxed<'a>(
+        &'a self,
+        phase: Phase,
+        prompt: &'a str,
+        working_dir: &'a Path,
+    ) -> BoxFuture<'a, Result<RunResult>> {
+        Box::pin(self.run(phase, prompt, working_dir))
+    }
+}
+
+impl AgentRunner for Box<dyn DynRunner> {
+    async fn run(&self, phase: Phase, prompt: &str, working_dir: &Path) -> Result<RunResult> {
+        // The box is itself a `DynRunner` via the blanket impl; deref to reach
+        // the inner runner instead of recursing.
+        (**self).run_boxed(phase, prompt, working_dir).await
+    }
+}
+
+impl AgentRunner for Arc<dyn DynRunner> {
+    async fn run(&self, phase: Phase, prompt: &str, working_dir: &Path) -> Result<RunResult> {
+        (**self).run_boxed(phase, prompt, working_dir).await
+    }
+}
+
 /// Build the base Claude CLI flags shared by all command builders.
 ///
-/// Returns: `[--print, --verbose, --output-format, stream-json, --dangerously-skip-permissions]`
-/// plus optional `--model` and `--effort`.
-fn base_claude_args(model: Option<&str>, effort: Option<&str>) -> Vec<String> {
+/// Returns: `[--print, --verbose, --output-format, stream-json]`, the permission
+/// flags for `mode`, plus optional `--model` and `--effort`.
+fn base_claude_args(
+    model: Option<&str>,
+    effort: Option<&str>,
+    mode: PermissionMode,
+) -> Vec<String> {
     let mut args = vec![
         "--print".to_string(),
         "--verbose".to_string(),
         "--output-format".to_string(),
         "stream-json".to_string(),
-        "--dangerously-skip-permissions".to_string(),
     ];
 
+    match mode {
+        PermissionMode::Full => args.push("--dangerously-skip-permissions".to_string()),
+        PermissionMode::WorkspaceWrite => {
+            args.push("--permission-mode".to_string());
+            args.push("acceptEdits".to_string());
+            args.push("--allowedTools".to_string());
+            args.extend(CLAUDE_GIT_TOOLS.iter().map(|t| t.to_string()));
+        }
+        PermissionMode::ReadOnly => {
+            args.push("--permission-mode".to_string());
+            args.push("default".to_string());
+            args.push("--allowedTools".to_string());
+            args.extend(CLAUDE_READ_ONLY_TOOLS.iter().map(|t| t.to_string()));
+            args.push("--disallowedTools".to_string());
+            args.extend(CLAUDE_WRITE_TOOLS.iter().map(|t| t.to_string()));
+        }
+    }
+
     if let Some(model) = model {
         args.push("--model".to_string());
         args.push(model.to_string());
@@ -148,6 +278,30 @@ fn base_claude_args(model: Option<&str>, effort: Option<&str>) -> Vec<String> {
     args
 }
 
+/// Local git commands pre-approved for Claude in [`PermissionMode::WorkspaceWrite`].
+const CLAUDE_GIT_TOOLS: &[&str] = &[
+    "Bash(git add:*)",
+    "Bash(git commit:*)",
+    "Bash(git status:*)",
+    "Bash(git diff:*)",
+    "Bash(git log:*)",
+];
+
+/// Tools pre-approved for Claude in [`PermissionMode::ReadOnly`].
+const CLAUDE_READ_ONLY_TOOLS: &[&str] = &[
+    "Read",
+    "Glob",
+    "Grep",
+    "Task",
+    "Bash(git status:*)",
+    "Bash(git diff:*)",
+    "Bash(git log:*)",
+    "Bash(git show:*)",
+];
+
+/// Tools denied to Claude in [`PermissionMode::ReadOnly`].
+const CLAUDE_WRITE_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write", "NotebookEdit"];
+
 /// Claude runner — invokes the claude CLI directly.
 pub struct ClaudeRunner {
     agent_binary: String,
@@ -155,8 +309,13 @@ pub struct ClaudeRunner {
     effort: Option<String>,
     timeout: Option<Duration>,
     max_timeout_retries: u32,
+    permission_mode: PermissionMode,
+    identity: AgentIdentity,
+    /// Flags appended after rlph's own, before the prompt.
+    extra_args: Vec<String>,
     /// When set, stream formatted agent messages to stderr with this prefix.
     stream_prefix: Option<String>,
+    stream_mode: StreamMode,
 }
 
 impl ClaudeRunner {
@@ -173,13 +332,22 @@ impl ClaudeRunner {
             effort,
             timeout,
             max_timeout_retries,
+            permission_mode: PermissionMode::Full,
+            identity: AgentIdentity::default(),
+            extra_args: Vec::new(),
             stream_prefix: None,
+            stream_mode: StreamMode::Interleaved,
         }
     }
 
     /// Build the command and arguments for a given phase and prompt.
     pub fn build_command(&self, prompt: &str) -> (String, Vec<String>) {
-        let mut args = base_claude_args(self.model.as_deref(), self.effort.as_deref());
+        let mut args = base_claude_args(
+            self.model.as_deref(),
+            self.effort.as_deref(),
+            self.permission_mode,
+        );
+        args.extend(self.extra_args.iter().cloned());
         args.push("-p".to_string());
         args.push(prompt.to_string());
         (self.agent_binary.clone(), args)
@@ -187,7 +355,12 @@ impl ClaudeRunner {
 
     /// Build a resume command for a timed-out session.
     pub fn build_resume_command(&self, session_id: &str) -> (String, Vec<String>) {
-        let mut args = base_claude_args(self.model.as_deref(), self.effort.as_deref());
+        let mut args = base_claude_args(
+            self.model.as_deref(),
+            self.effort.as_deref(),
+            self.permission_mode,
+        );
+        args.extend(self.extra_args.iter().cloned());
         args.push("--resume".to_string());
         args.push(session_id.to_string());
         (self.agent_binary.clone(), args)
@@ -211,6 +384,41 @@ pub fn extract_session_id(stdout_lines: &[String]) -> Option<String> {
     last_id
 }
 
+/// Sum the token usage reported in agent stdout lines.
+///
+/// Understands Claude `{"type":"result","usage":{...}}`, Codex
+/// `{"type":"turn.completed","usage":{...}}` and OpenCode
+/// `{"type":"step_finish","part":{"tokens":{...}}}` events.
+pub fn extract_token_usage(stdout_lines: &[String]) -> TokenUsage {
+    let count = |v: &serde_json::Value, key: &str| v.get(key).and_then(|n| n.as_u64()).unwrap_or(0);
+    let mut usage = TokenUsage::default();
+    for line in stdout_lines {
+        let Ok(val) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        match val.get("type").and_then(|v| v.as_str()) {
+            Some("result") | Some("turn.completed") => {
+                if let Some(u) = val.get("usage") {
+                    usage.input_tokens += count(u, "input_tokens")
+                        + count(u, "cache_creation_input_tokens")
+                        + count(u, "cache_read_input_tokens");
+                    usage.output_tokens += count(u, "output_tokens");
+                }
+            }
+            Some("step_finish") => {
+                if let Some(t) = val.get("part").and_then(|p| p.get("tokens")) {
+                    let cache = t.get("cache");
+                    usage.input_tokens += count(t, "input")
+                        + cache.map_or(0, |c| count(c, "read") + count(c, "write"));
+                    usage.output_tokens += count(t, "output");
+                }
+            }
+            _ => {}
+        }
+    }
+    usage
+}
+
 /// Extract the final human-readable result from Claude stream-json output.
 ///
 /// Claude emits many JSON events when using `--output-format stream-json`.
@@ -279,19 +487,23 @@ fn format_claude_line(prefix: &str, line: &str, sink: &mut impl std::io::Write)
 }
 
 /// Spawn a task that reads Claude stream-json lines from a channel and prints
-/// formatted agent messages to stderr, prefixed with `[prefix]`.
+/// formatted agent messages to stderr, prefixed with a colored `[prefix]` and
+/// written in whole lines (or one block) according to `mode`.
 ///
 /// Extracts text content from `assistant` events and tool names from `tool_use`
 /// content blocks. All other event types are silently skipped.
 fn spawn_claude_stream_formatter(
     prefix: String,
+    mode: StreamMode,
     mut rx: mpsc::UnboundedReceiver<String>,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
-        let mut stderr = std::io::stderr();
+        let label = prefix_label(&prefix, color_enabled());
+        let mut out = PhaseOutput::new(mode, std::io::stderr());
         while let Some(line) = rx.recv().await {
-            format_claude_line(&prefix, &line, &mut stderr);
+            format_claude_line(&label, &line, &mut out);
         }
+        let _ = out.finish();
     })
 }
 
@@ -305,7 +517,7 @@ impl AgentRunner for ClaudeRunner {
         // Set up streaming channel if a stream prefix is configured.
         let (stdout_tx, stream_handle) = if let Some(ref prefix) = self.stream_prefix {
             let (tx, rx) = mpsc::unbounded_channel();
-            let handle = spawn_claude_stream_formatter(prefix.clone(), rx);
+            let handle = spawn_claude_stream_formatter(prefix.clone(), self.stream_mode, rx);
             (Some(tx), Some(handle))
         } else {
             (None, None)
@@ -341,7 +553,7 @@ impl AgentRunner for ClaudeRunner {
                     timeout: self.timeout,
                     log_prefix: log_prefix.clone(),
                     stream_output: false,
-                    env: vec![],
+                    env: self.identity.claude_env(),
                     stdin_data: None,
                     quiet: true,
                     stdout_tx: stdout_tx.clone(),
@@ -370,12 +582,14 @@ impl AgentRunner for ClaudeRunner {
                         }
 
                         let session_id = extract_session_id(&all_stdout);
+                        let usage = extract_token_usage(&all_stdout);
 
                         break 'attempts Ok(RunResult {
                             exit_code: output.exit_code,
                             stdout,
                             stderr,
                             session_id,
+                            usage,
                         });
                     }
                     Err(Error::ProcessTimeout {
@@ -417,10 +631,11 @@ pub fn build_claude_resume_with_prompt_command(
     agent_binary: &str,
     model: Option<&str>,
     effort: Option<&str>,
+    permission_mode: PermissionMode,
     session_id: &str,
     prompt: &str,
 ) -> (String, Vec<String>) {
-    let mut args = base_claude_args(model, effort);
+    let mut args = base_claude_args(model, effort, permission_mode);
     args.push("--resume".to_string());
     args.push(session_id.to_string());
     args.push("-p".to_string());
@@ -436,9 +651,12 @@ pub fn build_codex_resume_with_prompt_command(
     agent_binary: &str,
     model: Option<&str>,
     effort: Option<&str>,
+    permission_mode: PermissionMode,
+    identity: &AgentIdentity,
     thread_id: &str,
 ) -> (String, Vec<String>) {
-    let mut args = base_codex_args(model, effort);
+    let mut args = base_codex_args(model, effort, permission_mode);
+    args.extend(identity.codex_args());
     args.push("resume".to_string());
     args.push(thread_id.to_string());
     args.push("-".to_string());
@@ -461,6 +679,8 @@ pub async fn resume_with_correction(
     model: Option<&str>,
     effort: Option<&str>,
     variant: Option<&str>,
+    permission_mode: PermissionMode,
+    identity: &AgentIdentity,
     session_id: &str,
     correction_prompt: &str,
     working_dir: &Path,
@@ -468,8 +688,14 @@ pub async fn resume_with_correction(
 ) -> Result<RunResult> {
     let (command, args, stdin_data) = match runner_type {
         RunnerKind::Codex => {
-            let (cmd, a) =
-                build_codex_resume_with_prompt_command(agent_binary, model, effort, session_id);
+            let (cmd, a) = build_codex_resume_with_prompt_command(
+                agent_binary,
+                model,
+                effort,
+                permission_mode,
+                identity,
+                session_id,
+            );
             (cmd, a, Some(correction_prompt.to_string()))
         }
         RunnerKind::Claude => {
@@ -477,6 +703,7 @@ pub async fn resume_with_correction(
                 agent_binary,
                 model,
                 effort,
+                permission_mode,
                 session_id,
                 correction_prompt,
             );
@@ -501,7 +728,10 @@ pub async fn resume_with_correction(
         timeout,
         log_prefix: "agent:correction".to_string(),
         stream_output: false,
-        env: vec![],
+        env: match runner_type {
+            RunnerKind::Claude => identity.claude_env(),
+            _ => vec![],
+        },
         stdin_data,
         quiet: true,
         stdout_tx: None,
@@ -526,6 +756,7 @@ pub async fn resume_with_correction(
             extract_opencode_session_id(&output.stdout_lines),
         ),
     };
+    let usage = extract_token_usage(&output.stdout_lines);
     let stderr = output.stderr_lines.join("\n");
 
     if let Some(sig) = output.signal {
@@ -546,6 +777,7 @@ pub async fn resume_with_correction(
         stdout,
         stderr,
         session_id,
+        usage,
     })
 }
 
@@ -576,15 +808,54 @@ impl AgentRunner for CallbackRunner {
     }
 }
 
-/// Enum dispatching to either Claude, Codex, OpenCode, or callback runner.
+/// Which agent account and settings rlph's own runs use, kept apart from the
+/// developer's personal setup. Each field only applies to its own CLI.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AgentIdentity {
+    /// Passed to codex as `--profile`.
+    pub codex_profile: Option<String>,
+    /// Exported to claude as `CLAUDE_CONFIG_DIR`.
+    pub claude_config_dir: Option<PathBuf>,
+}
+
+impl AgentIdentity {
+    /// Flags selecting the codex profile.
+    fn codex_args(&self) -> Vec<String> {
+        match &self.codex_profile {
+            Some(profile) => vec!["--profile".to_string(), profile.clone()],
+            None => Vec::new(),
+        }
+    }
+
+    /// Environment selecting the claude settings dir.
+    fn claude_env(&self) -> Vec<(String, String)> {
+        match &self.claude_config_dir {
+            Some(dir) => vec![(
+                "CLAUDE_CONFIG_DIR".to_string(),
+                dir.to_string_lossy().into_owned(),
+            )],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Enum dispatching to either Claude, Codex, OpenCode, callback, or custom runner.
 pub enum AnyRunner {
     Claude(ClaudeRunner),
     Codex(CodexRunner),
     OpenCode(OpencodeRunner),
     Callback(CallbackRunner),
+    /// Any other runner, e.g. one provided by a crate embedding rlph.
+    Custom(Arc<dyn DynRunner>),
 }
 
 impl AnyRunner {
+    /// Wrap a third-party runner so it can be used wherever `AnyRunner` is,
+    /// including from a `ReviewRunnerFactory`.
+    pub fn custom(runner: impl AgentRunner + Send + Sync + 'static) -> Self {
+        AnyRunner::Custom(Arc::new(runner))
+    }
+
     /// Enable streaming of formatted agent messages to stderr with the given prefix.
     pub fn with_stream_prefix(mut self, prefix: String) -> Self {
         match self {
@@ -594,6 +865,54 @@ impl AnyRunner {
         }
         self
     }
+
+    /// Choose how streamed output is written; see [`StreamMode`].
+    pub fn with_stream_mode(mut self, mode: StreamMode) -> Self {
+        match self {
+            AnyRunner::Claude(ref mut r) => r.stream_mode = mode,
+            AnyRunner::Codex(ref mut r) => r.stream_mode = mode,
+            _ => {}
+        }
+        self
+    }
+
+    /// Set the permission flags passed to the agent CLI. OpenCode, callback, and
+    /// custom runners have no equivalent flags and are left unchanged.
+    pub fn with_permission_mode(mut self, mode: PermissionMode) -> Self {
+        match self {
+            AnyRunner::Claude(ref mut r) => r.permission_mode = mode,
+            AnyRunner::Codex(ref mut r) => r.permission_mode = mode,
+            _ => {}
+        }
+        self
+    }
+
+    /// Run the agent CLI under `identity`. OpenCode, callback, and custom
+    /// runners are left unchanged.
+    pub fn with_identity(mut self, identity: AgentIdentity) -> Self {
+        match self {
+            AnyRunner::Claude(ref mut r) => r.identity = identity,
+            AnyRunner::Codex(ref mut r) => r.identity = identity,
+            _ => {}
+        }
+        self
+    }
+
+    /// Set the sampling temperature and extra flags passed to the agent CLI.
+    /// Only codex takes a temperature (config validation rejects it for the
+    /// others); callback and custom runners are left unchanged.
+    pub fn with_sampling(mut self, temperature: Option<f64>, extra_args: Vec<String>) -> Self {
+        match self {
+            AnyRunner::Claude(ref mut r) => r.extra_args = extra_args,
+            AnyRunner::Codex(ref mut r) => {
+                r.temperature = temperature;
+                r.extra_args = extra_args;
+            }
+            AnyRunner::OpenCode(ref mut r) => r.extra_args = extra_args,
+            _ => {}
+        }
+        self
+    }
 }
 
 impl AgentRunner for AnyRunner {
@@ -603,6 +922,7 @@ impl AgentRunner for AnyRunner {
             AnyRunner::Codex(r) => r.run(phase, prompt, working_dir).await,
             AnyRunner::OpenCode(r) => r.run(phase, prompt, working_dir).await,
             AnyRunner::Callback(r) => r.run(phase, prompt, working_dir).await,
+            AnyRunner::Custom(r) => (**r).run_boxed(phase, prompt, working_dir).await,
         }
     }
 }
@@ -637,6 +957,7 @@ pub struct OpencodeRunner {
     variant: Option<String>,
     timeout: Option<Duration>,
     max_timeout_retries: u32,
+    extra_args: Vec<String>,
 }
 
 impl OpencodeRunner {
@@ -653,12 +974,14 @@ impl OpencodeRunner {
             variant,
             timeout,
             max_timeout_retries,
+            extra_args: Vec::new(),
         }
     }
 
     /// Build the command and arguments for a given prompt.
     pub fn build_command(&self, prompt: &str) -> (String, Vec<String>) {
         let mut args = base_opencode_args(self.model.as_deref(), self.variant.as_deref());
+        args.extend(self.extra_args.iter().cloned());
         args.push(prompt.to_string());
         (self.agent_binary.clone(), args)
     }
@@ -666,6 +989,7 @@ impl OpencodeRunner {
     /// Build a resume command for a timed-out session (prompt-less continue).
     pub fn build_resume_command(&self, session_id: &str) -> (String, Vec<String>) {
         let mut args = base_opencode_args(self.model.as_deref(), self.variant.as_deref());
+        args.extend(self.extra_args.iter().cloned());
         args.push("--session".to_string());
         args.push(session_id.to_string());
         (self.agent_binary.clone(), args)
@@ -734,12 +1058,14 @@ impl AgentRunner for OpencodeRunner {
                     }
 
                     let session_id = extract_opencode_session_id(&all_stdout);
+                    let usage = extract_token_usage(&all_stdout);
 
                     return Ok(RunResult {
                         exit_code: output.exit_code,
                         stdout,
                         stderr,
                         session_id,
+                        usage,
                     });
                 }
                 Err(Error::ProcessTimeout {
@@ -817,14 +1143,24 @@ pub fn build_opencode_resume_with_prompt_command(
 
 /// Build the base Codex CLI flags shared by all command builders.
 ///
-/// Returns: `["exec", "--dangerously-bypass-approvals-and-sandbox", "--json"]`
-/// plus optional `--model` and `--config model_reasoning_effort`.
-fn base_codex_args(model: Option<&str>, effort: Option<&str>) -> Vec<String> {
-    let mut args = vec![
-        "exec".to_string(),
-        "--dangerously-bypass-approvals-and-sandbox".to_string(),
-        "--json".to_string(),
-    ];
+/// Returns: `["exec", <sandbox flags for mode>, "--json"]` plus optional
+/// `--model` and `--config model_reasoning_effort`.
+fn base_codex_args(model: Option<&str>, effort: Option<&str>, mode: PermissionMode) -> Vec<String> {
+    let mut args = vec!["exec".to_string()];
+    match mode {
+        PermissionMode::Full => {
+            args.push("--dangerously-bypass-approvals-and-sandbox".to_string());
+        }
+        PermissionMode::WorkspaceWrite => {
+            args.push("--sandbox".to_string());
+            args.push("workspace-write".to_string());
+        }
+        PermissionMode::ReadOnly => {
+            args.push("--sandbox".to_string());
+            args.push("read-only".to_string());
+        }
+    }
+    args.push("--json".to_string());
 
     if let Some(model) = model {
         args.push("--model".to_string());
@@ -940,19 +1276,23 @@ fn format_codex_line(prefix: &str, line: &str, sink: &mut impl std::io::Write) -
 }
 
 /// Spawn a task that reads Codex JSON event lines from a channel and prints
-/// formatted agent messages to stderr, prefixed with `[prefix]`.
+/// formatted agent messages to stderr, prefixed with a colored `[prefix]` and
+/// written in whole lines (or one block) according to `mode`.
 ///
 /// Extracts text from `item.completed` agent_message events and command names
 /// from `item.started` command_execution events.
 fn spawn_codex_stream_formatter(
     prefix: String,
+    mode: StreamMode,
     mut rx: mpsc::UnboundedReceiver<String>,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
-        let mut stderr = std::io::stderr();
+        let label = prefix_label(&prefix, color_enabled());
+        let mut out = PhaseOutput::new(mode, std::io::stderr());
         while let Some(line) = rx.recv().await {
-            format_codex_line(&prefix, &line, &mut stderr);
+            format_codex_line(&label, &line, &mut out);
         }
+        let _ = out.finish();
     })
 }
 
@@ -963,7 +1303,12 @@ pub struct CodexRunner {
     effort: Option<String>,
     timeout: Option<Duration>,
     max_timeout_retries: u32,
+    permission_mode: PermissionMode,
+    identity: AgentIdentity,
+    temperature: Option<f64>,
+    extra_args: Vec<String>,
     stream_prefix: Option<String>,
+    stream_mode: StreamMode,
 }
 
 impl CodexRunner {
@@ -980,13 +1325,36 @@ impl CodexRunner {
             effort,
             timeout,
             max_timeout_retries,
+            permission_mode: PermissionMode::Full,
+            identity: AgentIdentity::default(),
+            temperature: None,
+            extra_args: Vec::new(),
             stream_prefix: None,
+            stream_mode: StreamMode::Interleaved,
         }
     }
 
+    /// Flags for the sampling temperature and any extra args, placed after the
+    /// base flags.
+    fn sampling_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(temperature) = self.temperature {
+            args.push("--config".to_string());
+            args.push(format!("model_temperature={temperature}"));
+        }
+        args.extend(self.extra_args.iter().cloned());
+        args
+    }
+
     /// Build the command and arguments for codex invocation.
     pub fn build_command(&self) -> (String, Vec<String>) {
-        let mut args = base_codex_args(self.model.as_deref(), self.effort.as_deref());
+        let mut args = base_codex_args(
+            self.model.as_deref(),
+            self.effort.as_deref(),
+            self.permission_mode,
+        );
+        args.extend(self.identity.codex_args());
+        args.extend(self.sampling_args());
         args.push("-".to_string());
         (self.agent_binary.clone(), args)
     }
@@ -995,7 +1363,13 @@ impl CodexRunner {
     /// Uses `codex exec resume --last` which resumes the most recent session
     /// scoped to the current working directory.
     pub fn build_resume_command(&self) -> (String, Vec<String>) {
-        let mut args = base_codex_args(self.model.as_deref(), self.effort.as_deref());
+        let mut args = base_codex_args(
+            self.model.as_deref(),
+            self.effort.as_deref(),
+            self.permission_mode,
+        );
+        args.extend(self.identity.codex_args());
+        args.extend(self.sampling_args());
         args.push("resume".to_string());
         args.push("--last".to_string());
         (self.agent_binary.clone(), args)
@@ -1012,7 +1386,7 @@ impl AgentRunner for CodexRunner {
         // Set up streaming channel if a stream prefix is configured.
         let (stdout_tx, stream_handle) = if let Some(ref prefix) = self.stream_prefix {
             let (tx, rx) = mpsc::unbounded_channel();
-            let handle = spawn_codex_stream_formatter(prefix.clone(), rx);
+            let handle = spawn_codex_stream_formatter(prefix.clone(), self.stream_mode, rx);
             (Some(tx), Some(handle))
         } else {
             (None, None)
@@ -1069,12 +1443,14 @@ impl AgentRunner for CodexRunner {
                         }
 
                         let session_id = extract_thread_id(&all_stdout);
+                        let usage = extract_token_usage(&all_stdout);
 
                         break 'attempts Ok(RunResult {
                             exit_code: output.exit_code,
                             stdout,
                             stderr,
                             session_id,
+                            usage,
                         });
                     }
                     Err(Error::ProcessTimeout {
@@ -1254,6 +1630,35 @@ mod tests {
         assert_eq!(extract_claude_result(&lines), None);
     }
 
+    #[test]
+    fn test_extract_token_usage_claude_result() {
+        let lines = vec![
+            r#"{"type":"assistant","message":{"usage":{"input_tokens":999}}}"#.to_string(),
+            r#"{"type":"result","result":"ok","usage":{"input_tokens":10,"cache_read_input_tokens":90,"output_tokens":25}}"#.to_string(),
+        ];
+        assert_eq!(
+            extract_token_usage(&lines),
+            TokenUsage {
+                input_tokens: 100,
+                output_tokens: 25,
+            }
+        );
+    }
+
+    #[test]
+    fn test_extract_token_usage_sums_codex_turns_and_opencode_steps() {
+        let lines = vec![
+            r#"{"type":"turn.completed","usage":{"input_tokens":40,"cached_input_tokens":30,"output_tokens":5}}"#.to_string(),
+            r#"{"type":"turn.completed","usage":{"input_tokens":60,"output_tokens":15}}"#.to_string(),
+            r#"{"type":"step_finish","part":{"tokens":{"input":7,"output":3,"cache":{"read":3,"write":0}}}}"#.to_string(),
+            "not json".to_string(),
+        ];
+        let usage = extract_token_usage(&lines);
+        assert_eq!(usage.input_tokens, 110);
+        assert_eq!(usage.output_tokens, 23);
+        assert_eq!(usage.total(), 133);
+    }
+
     #[test]
     fn test_phase_display() {
         assert_eq!(Phase::Choose.to_string(), "choose");
@@ -1262,6 +1667,9 @@ mod tests {
         assert_eq!(Phase::ReviewAggregate.to_string(), "review-aggregate");
         assert_eq!(Phase::ReviewFix.to_string(), "review-fix");
         assert_eq!(Phase::Fix.to_string(), "fix");
+        assert_eq!(Phase::Estimate.to_string(), "estimate");
+        assert_eq!(Phase::Translate.to_string(), "translate");
+        assert_eq!(Phase::Probe.to_string(), "probe");
     }
 
     #[test]
@@ -1333,6 +1741,33 @@ mod tests {
         assert_eq!(cmd, "/usr/local/bin/codex");
     }
 
+    #[test]
+    fn test_identity_selects_codex_profile_and_claude_dir() {
+        let identity = AgentIdentity {
+            codex_profile: Some("rlph-bot".to_string()),
+            claude_config_dir: Some(PathBuf::from("/etc/rlph/claude")),
+        };
+        let AnyRunner::Codex(codex) =
+            build_runner(RunnerKind::Codex, "codex", None, None, None, None, 2)
+                .with_identity(identity.clone())
+        else {
+            panic!("expected codex runner");
+        };
+        for (_cmd, args) in [codex.build_command(), codex.build_resume_command()] {
+            let at = args.iter().position(|a| a == "--profile").unwrap();
+            assert_eq!(args[at + 1], "rlph-bot");
+        }
+        assert_eq!(
+            identity.claude_env(),
+            vec![(
+                "CLAUDE_CONFIG_DIR".to_string(),
+                "/etc/rlph/claude".to_string()
+            )]
+        );
+        assert!(AgentIdentity::default().codex_args().is_empty());
+        assert!(AgentIdentity::default().claude_env().is_empty());
+    }
+
     #[test]
     fn test_codex_build_resume_command() {
         let runner = CodexRunner::new("codex".to_string(), None, None, None, 2);
@@ -1369,6 +1804,7 @@ mod tests {
             "claude",
             None,
             None,
+            PermissionMode::Full,
             "sess-123",
             "fix your JSON",
         );
@@ -1391,6 +1827,7 @@ mod tests {
             "claude",
             Some("opus"),
             Some("high"),
+            PermissionMode::Full,
             "sess-456",
             "correction",
         );
@@ -1486,7 +1923,14 @@ mod tests {
 
     #[test]
     fn test_codex_build_resume_with_prompt_command() {
-        let (cmd, args) = build_codex_resume_with_prompt_command("codex", None, None, "thread-abc");
+        let (cmd, args) = build_codex_resume_with_prompt_command(
+            "codex",
+            None,
+            None,
+            PermissionMode::Full,
+            &AgentIdentity::default(),
+            "thread-abc",
+        );
         assert_eq!(cmd, "codex");
         assert!(args.contains(&"exec".to_string()));
         assert!(args.contains(&"--dangerously-bypass-approvals-and-sandbox".to_string()));
@@ -1498,6 +1942,106 @@ mod tests {
         assert!(!args.contains(&"-p".to_string()));
     }
 
+    #[test]
+    fn test_workspace_write_mode_drops_dangerous_flags() {
+        let claude = build_runner(RunnerKind::Claude, "claude", None, None, None, None, 2)
+            .with_permission_mode(PermissionMode::WorkspaceWrite);
+        let AnyRunner::Claude(claude) = claude else {
+            panic!("expected claude runner");
+        };
+        let (_cmd, args) = claude.build_command("prompt");
+        assert!(!args.iter().any(|a| a.starts_with("--dangerously")));
+        assert!(
+            args.windows(2)
+                .any(|w| w == ["--permission-mode", "acceptEdits"])
+        );
+        assert!(args.contains(&"Bash(git commit:*)".to_string()));
+        assert!(!args.iter().any(|a| a.contains("git push")));
+
+        let codex = build_runner(RunnerKind::Codex, "codex", None, None, None, None, 2)
+            .with_permission_mode(PermissionMode::WorkspaceWrite);
+        let AnyRunner::Codex(codex) = codex else {
+            panic!("expected codex runner");
+        };
+        let (_cmd, args) = codex.build_resume_command();
+        assert!(!args.iter().any(|a| a.starts_with("--dangerously")));
+        assert!(
+            args.windows(2)
+                .any(|w| w == ["--sandbox", "workspace-write"])
+        );
+    }
+
+    #[test]
+    fn test_sampling_flags_precede_prompt() {
+        let extra = vec!["--flag".to_string(), "val".to_string()];
+        let codex = build_runner(RunnerKind::Codex, "codex", None, None, None, None, 2)
+            .with_sampling(Some(0.2), extra.clone());
+        let AnyRunner::Codex(codex) = codex else {
+            panic!("expected codex runner");
+        };
+        let (_cmd, args) = codex.build_command();
+        assert!(
+            args.windows(2)
+                .any(|w| w == ["--config", "model_temperature=0.2"])
+        );
+        assert_eq!(args[args.len() - 3..], ["--flag", "val", "-"]);
+        let (_cmd, args) = codex.build_resume_command();
+        assert!(args.windows(3).any(|w| w == ["val", "resume", "--last"]));
+
+        let claude = build_runner(RunnerKind::Claude, "claude", None, None, None, None, 2)
+            .with_sampling(None, extra.clone());
+        let AnyRunner::Claude(claude) = claude else {
+            panic!("expected claude runner");
+        };
+        let (_cmd, args) = claude.build_command("prompt");
+        assert_eq!(args[args.len() - 4..], ["--flag", "val", "-p", "prompt"]);
+
+        let opencode = build_runner(RunnerKind::OpenCode, "opencode", None, None, None, None, 2)
+            .with_sampling(None, extra);
+        let AnyRunner::OpenCode(opencode) = opencode else {
+            panic!("expected opencode runner");
+        };
+        let (_cmd, args) = opencode.build_resume_command("ses_1");
+        assert_eq!(
+            args[args.len() - 4..],
+            ["--flag", "val", "--session", "ses_1"]
+        );
+    }
+
+    #[test]
+    fn test_read_only_mode_denies_edits() {
+        let claude = build_runner(RunnerKind::Claude, "claude", None, None, None, None, 2)
+            .with_permission_mode(PermissionMode::ReadOnly);
+        let AnyRunner::Claude(claude) = claude else {
+            panic!("expected claude runner");
+        };
+        let (_cmd, args) = claude.build_command("prompt");
+        assert!(!args.iter().any(|a| a.starts_with("--dangerously")));
+        assert!(args.contains(&"--disallowedTools".to_string()));
+        assert!(args.contains(&"Write".to_string()));
+        assert!(!args.iter().any(|a| a.contains("git commit")));
+
+        let codex = build_runner(RunnerKind::Codex, "codex", None, None, None, None, 2)
+            .with_permission_mode(PermissionMode::ReadOnly);
+        let AnyRunner::Codex(codex) = codex else {
+            panic!("expected codex runner");
+        };
+        let (_cmd, args) = codex.build_command();
+        assert!(args.windows(2).any(|w| w == ["--sandbox", "read-only"]));
+    }
+
+    #[test]
+    fn test_permission_mode_round_trips() {
+        for mode in [
+            PermissionMode::Full,
+            PermissionMode::WorkspaceWrite,
+            PermissionMode::ReadOnly,
+        ] {
+            assert_eq!(mode.to_string().parse::<PermissionMode>().unwrap(), mode);
+        }
+        assert!("yolo".parse::<PermissionMode>().is_err());
+    }
+
     #[test]
     fn test_codex_effort_flag() {
         let runner = CodexRunner::new("codex".to_string(), None, Some("low".to_string()), None, 2);
@@ -1538,6 +2082,8 @@ mod tests {
             "codex",
             Some("gpt-5.3"),
             Some("medium"),
+            PermissionMode::Full,
+            &AgentIdentity::default(),
             "thread-xyz",
         );
         assert!(args.contains(&"--model".to_string()));
@@ -1965,4 +2511,43 @@ mod tests {
         );
         assert_eq!(out, "");
     }
+
+    /// A runner defined outside the `AnyRunner` enum, as a downstream crate would.
+    struct EchoRunner;
+
+    impl AgentRunner for EchoRunner {
+        async fn run(&self, phase: Phase, prompt: &str, _dir: &Path) -> Result<RunResult> {
+            Ok(RunResult {
+                exit_code: 0,
+                stdout: format!("{phase}: {prompt}"),
+                stderr: String::new(),
+                session_id: None,
+                usage: Default::default(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_boxed_dyn_runner_is_an_agent_runner() {
+        let runners: Vec<Box<dyn DynRunner>> = vec![Box::new(EchoRunner), Box::new(EchoRunner)];
+        for runner in &runners {
+            let result = runner
+                .run(Phase::Review, "hi", Path::new("."))
+                .await
+                .unwrap();
+            assert_eq!(result.stdout, "review: hi");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_any_runner_custom_dispatches() {
+        let runner = AnyRunner::custom(EchoRunner)
+            .with_stream_prefix("implement".to_string())
+            .with_permission_mode(PermissionMode::ReadOnly);
+        let result = runner
+            .run(Phase::Implement, "go", Path::new("."))
+            .await
+            .unwrap();
+        assert_eq!(result.stdout, "implement: go");
+    }
 }
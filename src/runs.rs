@@ -0,0 +1,590 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::dry_run::DryRunAction;
+use crate::error::{Error, Result};
+use crate::phase_env::PhaseEnvironment;
+use crate::runner::TokenUsage;
+
+/// How an iteration ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunOutcome {
+    Completed,
+    Failed,
+    /// Skipped before implementation (e.g. over the complexity threshold).
+    Skipped,
+}
+
+impl std::fmt::Display for RunOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunOutcome::Completed => write!(f, "completed"),
+            RunOutcome::Failed => write!(f, "failed"),
+            RunOutcome::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+/// Wall-clock time and tokens spent in one agent phase.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhaseTiming {
+    pub name: String,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub tokens: TokenUsage,
+}
+
+/// Structured record of one loop iteration, written to `.rlph/runs/`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunSummary {
+    pub task_id: String,
+    pub title: String,
+    pub url: String,
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub outcome: RunOutcome,
+    pub phases: Vec<PhaseTiming>,
+    /// What each agent phase started from, in start order.
+    #[serde(default)]
+    pub environments: Vec<PhaseEnvironment>,
+    pub review_rounds: u32,
+    /// Verdict of the last aggregated review round.
+    pub verdict: Option<String>,
+    /// Findings reported by the last aggregated review round.
+    pub findings_count: usize,
+    pub pr_url: Option<String>,
+    /// Sum of the tokens of all phases.
+    pub tokens: TokenUsage,
+    pub errors: Vec<String>,
+    /// Mutations skipped because the run was a dry run, in order.
+    #[serde(default)]
+    pub dry_run_actions: Vec<DryRunAction>,
+    /// Largest worktree size measured during the run, in megabytes.
+    #[serde(default)]
+    pub worktree_disk_mb: Option<u64>,
+    /// Version of the built-in prompts the run used.
+    #[serde(default)]
+    pub prompt_version: Option<String>,
+}
+
+impl RunSummary {
+    fn new(started_at: u64) -> Self {
+        Self {
+            task_id: String::new(),
+            title: String::new(),
+            url: String::new(),
+            started_at,
+            finished_at: started_at,
+            outcome: RunOutcome::Completed,
+            phases: Vec::new(),
+            environments: Vec::new(),
+            review_rounds: 0,
+            verdict: None,
+            findings_count: 0,
+            pr_url: None,
+            tokens: TokenUsage::default(),
+            errors: Vec::new(),
+            dry_run_actions: Vec::new(),
+            worktree_disk_mb: None,
+            prompt_version: None,
+        }
+    }
+
+    /// Run id: the summary's file name without the `.json` extension.
+    pub fn id(&self) -> String {
+        let task: String = self
+            .task_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}-{task}", format_timestamp(self.started_at))
+    }
+
+    pub fn duration_secs(&self) -> u64 {
+        self.finished_at.saturating_sub(self.started_at)
+    }
+}
+
+/// A failed run, as listed in a [`RunDigest`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DigestFailure {
+    pub task_id: String,
+    pub title: String,
+    /// Last error of the run.
+    pub reason: String,
+}
+
+/// Totals over the runs of one `rlph` loop, reported when the loop exits.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RunDigest {
+    pub started_at: u64,
+    pub finished_at: u64,
+    /// Why the loop stopped: `max_iterations`, `shutdown`, `paused`, or
+    /// `error`.
+    pub exit_reason: String,
+    pub completed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// PRs of the runs, in run order.
+    pub pr_urls: Vec<String>,
+    pub review_rounds: u32,
+    /// Runs whose last review verdict was `approved`.
+    pub approved: usize,
+    /// Findings of the last review round, summed over runs.
+    pub findings: usize,
+    pub tokens: TokenUsage,
+    pub failures: Vec<DigestFailure>,
+}
+
+impl RunDigest {
+    fn add(&mut self, run: &RunSummary) {
+        match run.outcome {
+            RunOutcome::Completed => self.completed += 1,
+            RunOutcome::Failed => self.failed += 1,
+            RunOutcome::Skipped => self.skipped += 1,
+        }
+        if let Some(url) = &run.pr_url
+            && !self.pr_urls.contains(url)
+        {
+            self.pr_urls.push(url.clone());
+        }
+        self.review_rounds += run.review_rounds;
+        if run.verdict.as_deref() == Some("approved") {
+            self.approved += 1;
+        }
+        self.findings += run.findings_count;
+        self.tokens.add(&run.tokens);
+        if run.outcome == RunOutcome::Failed {
+            self.failures.push(DigestFailure {
+                task_id: run.task_id.clone(),
+                title: run.title.clone(),
+                reason: run.errors.last().cloned().unwrap_or_default(),
+            });
+        }
+    }
+
+    /// Number of runs.
+    pub fn tasks(&self) -> usize {
+        self.completed + self.failed + self.skipped
+    }
+
+    /// Markdown report, for stderr and issue comments.
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "## rlph run digest\n\n\
+             Ran {} task(s) in {}s ({}): {} completed, {} failed, {} skipped.\n\
+             Review: {} round(s), {} approved, {} finding(s) left in last rounds.\n\
+             Tokens: {} ({} input, {} output).\n",
+            self.tasks(),
+            self.finished_at.saturating_sub(self.started_at),
+            self.exit_reason,
+            self.completed,
+            self.failed,
+            self.skipped,
+            self.review_rounds,
+            self.approved,
+            self.findings,
+            self.tokens.total(),
+            self.tokens.input_tokens,
+            self.tokens.output_tokens,
+        );
+        if !self.pr_urls.is_empty() {
+            out.push_str("\n### PRs\n\n");
+            for url in &self.pr_urls {
+                out.push_str(&format!("- {url}\n"));
+            }
+        }
+        if !self.failures.is_empty() {
+            out.push_str("\n### Failures\n\n");
+            for f in &self.failures {
+                let reason = f.reason.lines().next().unwrap_or_default();
+                out.push_str(&format!("- {} {}: {reason}\n", f.task_id, f.title));
+            }
+        }
+        out
+    }
+}
+
+/// Collects the summary of the iteration in progress, and the [`RunDigest`]
+/// of the finished ones.
+///
+/// Every method is a no-op until [`RunRecorder::start`] is called, so code
+/// paths outside a task iteration (e.g. `rlph review`) can record freely.
+#[derive(Default)]
+pub struct RunRecorder {
+    current: Mutex<Option<RunSummary>>,
+    digest: Mutex<RunDigest>,
+}
+
+impl RunRecorder {
+    /// Begin a new summary, discarding any unfinished one.
+    pub fn start(&self) {
+        *self.current.lock().unwrap() = Some(RunSummary::new(now_secs()));
+    }
+
+    pub fn set_task(&self, task_id: &str, title: &str, url: &str) {
+        self.update(|s| {
+            s.task_id = task_id.to_string();
+            s.title = title.to_string();
+            s.url = url.to_string();
+        });
+    }
+
+    pub fn phase(&self, name: &str, elapsed: Duration, tokens: TokenUsage) {
+        self.update(|s| {
+            s.tokens.add(&tokens);
+            s.phases.push(PhaseTiming {
+                name: name.to_string(),
+                duration_ms: elapsed.as_millis() as u64,
+                tokens,
+            });
+        });
+    }
+
+    /// Tokens and agent time the current run's phases have used so far.
+    pub fn usage(&self) -> (TokenUsage, Duration) {
+        self.current
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|s| {
+                let ms = s.phases.iter().map(|p| p.duration_ms).sum();
+                (s.tokens, Duration::from_millis(ms))
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn environment(&self, environment: PhaseEnvironment) {
+        self.update(|s| s.environments.push(environment));
+    }
+
+    pub fn review_round(&self, round: u32) {
+        self.update(|s| s.review_rounds = s.review_rounds.max(round));
+    }
+
+    pub fn verdict(&self, verdict: &str, findings_count: usize) {
+        self.update(|s| {
+            s.verdict = Some(verdict.to_string());
+            s.findings_count = findings_count;
+        });
+    }
+
+    pub fn pr_url(&self, url: &str) {
+        self.update(|s| s.pr_url = Some(url.to_string()));
+    }
+
+    pub fn error(&self, message: &str) {
+        self.update(|s| s.errors.push(message.to_string()));
+    }
+
+    pub fn dry_run_action(&self, action: DryRunAction) {
+        self.update(|s| s.dry_run_actions.push(action));
+    }
+
+    /// Record a worktree size measurement, keeping the largest.
+    pub fn worktree_disk(&self, mb: u64) {
+        self.update(|s| s.worktree_disk_mb = s.worktree_disk_mb.max(Some(mb)));
+    }
+
+    /// Close the summary. Returns `None` when nothing was started or no task
+    /// was selected.
+    pub fn finish(&self, outcome: RunOutcome) -> Option<RunSummary> {
+        let mut summary = self.current.lock().unwrap().take()?;
+        if summary.task_id.is_empty() {
+            return None;
+        }
+        summary.outcome = outcome;
+        summary.finished_at = now_secs();
+        self.digest.lock().unwrap().add(&summary);
+        Some(summary)
+    }
+
+    /// Start a new digest, discarding the runs counted so far.
+    pub fn begin_digest(&self) {
+        *self.digest.lock().unwrap() = RunDigest {
+            started_at: now_secs(),
+            ..Default::default()
+        };
+    }
+
+    /// Close the digest of runs finished since [`RunRecorder::begin_digest`].
+    pub fn take_digest(&self, exit_reason: &str) -> RunDigest {
+        let mut digest = std::mem::take(&mut *self.digest.lock().unwrap());
+        digest.finished_at = now_secs();
+        digest.exit_reason = exit_reason.to_string();
+        digest
+    }
+
+    fn update(&self, f: impl FnOnce(&mut RunSummary)) {
+        if let Some(summary) = self.current.lock().unwrap().as_mut() {
+            f(summary);
+        }
+    }
+}
+
+/// Directory of run summary files, one JSON file per iteration.
+pub struct RunStore {
+    dir: PathBuf,
+}
+
+impl RunStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `.rlph/runs` under the repository root.
+    pub fn default_dir(repo_root: &Path) -> PathBuf {
+        repo_root.join(".rlph").join("runs")
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write `summary` as `<timestamp>-<task>.json`, returning the path.
+    pub fn write(&self, summary: &RunSummary) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| Error::State(format!("failed to create runs dir: {e}")))?;
+        let content = serde_json::to_string_pretty(summary)
+            .map_err(|e| Error::State(format!("failed to serialize run summary: {e}")))?;
+        let path = self.dir.join(format!("{}.json", summary.id()));
+        std::fs::write(&path, content)
+            .map_err(|e| Error::State(format!("failed to write run summary: {e}")))?;
+        Ok(path)
+    }
+
+    /// All readable summaries, oldest first. Unparseable files are skipped.
+    pub fn list(&self) -> Result<Vec<RunSummary>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Error::State(format!("failed to read runs dir: {e}"))),
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        Ok(paths
+            .iter()
+            .filter_map(|p| std::fs::read_to_string(p).ok())
+            .filter_map(|content| serde_json::from_str(&content).ok())
+            .collect())
+    }
+
+    /// Load a summary by run id, or the latest run of a task id.
+    pub fn load(&self, id: &str) -> Result<RunSummary> {
+        let path = self.dir.join(format!("{id}.json"));
+        if path.is_file() {
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| Error::State(format!("failed to read run summary: {e}")))?;
+            return serde_json::from_str(&content)
+                .map_err(|e| Error::State(format!("failed to parse run summary: {e}")));
+        }
+        self.list()?
+            .into_iter()
+            .rev()
+            .find(|s| s.task_id == id)
+            .ok_or_else(|| Error::State(format!("no run found for {id}")))
+    }
+}
+
+/// One line per run for `rlph runs list`.
+pub fn format_run_list(runs: &[RunSummary]) -> String {
+    let mut out = String::new();
+    for run in runs {
+        out.push_str(&format!(
+            "{}  {:<9}  {:>5}s  rounds={}  verdict={}  findings={}  tokens={}{}{}{}\n",
+            run.id(),
+            run.outcome.to_string(),
+            run.duration_secs(),
+            run.review_rounds,
+            run.verdict.as_deref().unwrap_or("-"),
+            run.findings_count,
+            run.tokens.total(),
+            run.worktree_disk_mb
+                .map(|mb| format!("  disk={mb}MB"))
+                .unwrap_or_default(),
+            run.prompt_version
+                .as_deref()
+                .map(|v| format!("  prompts={v}"))
+                .unwrap_or_default(),
+            run.pr_url
+                .as_deref()
+                .map(|url| format!("  {url}"))
+                .unwrap_or_default(),
+        ));
+    }
+    out
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Format unix seconds as a sortable UTC timestamp, e.g. `20261016T093005Z`.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Civil-from-days (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "19700101T000000Z");
+        assert_eq!(format_timestamp(951_782_400), "20000229T000000Z");
+        assert_eq!(format_timestamp(1_792_143_005), "20261016T093005Z");
+    }
+
+    #[test]
+    fn test_recorder_is_noop_until_started() {
+        let recorder = RunRecorder::default();
+        recorder.set_task("gh-1", "t", "u");
+        recorder.phase("implement", Duration::from_secs(1), TokenUsage::default());
+        assert!(recorder.finish(RunOutcome::Completed).is_none());
+    }
+
+    #[test]
+    fn test_recorder_collects_summary() {
+        let recorder = RunRecorder::default();
+        recorder.start();
+        recorder.set_task("gh-7", "Fix it", "https://example.com/7");
+        let tokens = TokenUsage {
+            input_tokens: 10,
+            output_tokens: 2,
+        };
+        recorder.phase("implement", Duration::from_millis(1500), tokens);
+        recorder.phase("review:correctness", Duration::from_millis(500), tokens);
+        recorder.review_round(1);
+        recorder.verdict("needs_fix", 3);
+        recorder.review_round(2);
+        recorder.verdict("approved", 0);
+        recorder.pr_url("https://example.com/pr/9");
+        recorder.worktree_disk(120);
+        recorder.worktree_disk(80);
+
+        let summary = recorder.finish(RunOutcome::Completed).unwrap();
+        assert_eq!(summary.task_id, "gh-7");
+        assert_eq!(summary.phases.len(), 2);
+        assert_eq!(summary.phases[0].duration_ms, 1500);
+        assert_eq!(summary.tokens.total(), 24);
+        assert_eq!(summary.review_rounds, 2);
+        assert_eq!(summary.verdict.as_deref(), Some("approved"));
+        assert_eq!(summary.findings_count, 0);
+        assert_eq!(summary.pr_url.as_deref(), Some("https://example.com/pr/9"));
+        assert_eq!(summary.worktree_disk_mb, Some(120));
+        assert!(recorder.finish(RunOutcome::Completed).is_none());
+    }
+
+    #[test]
+    fn test_recorder_usage_so_far() {
+        let recorder = RunRecorder::default();
+        assert_eq!(recorder.usage(), (TokenUsage::default(), Duration::ZERO));
+
+        recorder.start();
+        let tokens = TokenUsage {
+            input_tokens: 10,
+            output_tokens: 2,
+        };
+        recorder.phase("implement", Duration::from_millis(1500), tokens);
+        recorder.phase("review:correctness", Duration::from_millis(500), tokens);
+        let (used, agent_time) = recorder.usage();
+        assert_eq!(used.total(), 24);
+        assert_eq!(agent_time, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_recorder_digest_totals_finished_runs() {
+        let recorder = RunRecorder::default();
+        recorder.begin_digest();
+        recorder.start();
+        recorder.set_task("gh-1", "Add flag", "u1");
+        recorder.review_round(1);
+        recorder.verdict("approved", 1);
+        recorder.pr_url("https://example.com/pr/1");
+        recorder.finish(RunOutcome::Completed);
+        recorder.start();
+        recorder.set_task("gh-2", "Fix crash", "u2");
+        recorder.error("implement failed\nexit status 1");
+        recorder.finish(RunOutcome::Failed);
+
+        let digest = recorder.take_digest("max_iterations");
+        assert_eq!(digest.tasks(), 2);
+        assert_eq!((digest.completed, digest.failed), (1, 1));
+        assert_eq!(digest.approved, 1);
+        assert_eq!(digest.findings, 1);
+        assert_eq!(digest.pr_urls, vec!["https://example.com/pr/1"]);
+        let report = digest.render();
+        assert!(report.contains("Ran 2 task(s)"), "{report}");
+        assert!(report.contains("(max_iterations)"), "{report}");
+        assert!(
+            report.contains("- gh-2 Fix crash: implement failed\n"),
+            "{report}"
+        );
+        assert_eq!(recorder.take_digest("shutdown").tasks(), 0);
+    }
+
+    #[test]
+    fn test_store_write_list_load() {
+        let tmp = TempDir::new().unwrap();
+        let store = RunStore::new(tmp.path().join("runs"));
+        assert!(store.list().unwrap().is_empty());
+
+        let mut first = RunSummary::new(1_000);
+        first.task_id = "gh-1".to_string();
+        let mut second = RunSummary::new(2_000);
+        second.task_id = "gh-1".to_string();
+        second.outcome = RunOutcome::Failed;
+        second.errors.push("boom".to_string());
+        second.worktree_disk_mb = Some(512);
+        second.prompt_version = Some("v1".to_string());
+
+        let path = store.write(&first).unwrap();
+        assert!(path.ends_with("19700101T001640Z-gh-1.json"));
+        store.write(&second).unwrap();
+        std::fs::write(store.dir().join("junk.json"), "not json").unwrap();
+
+        let runs = store.list().unwrap();
+        assert_eq!(runs, vec![first.clone(), second.clone()]);
+        assert_eq!(store.load(&first.id()).unwrap(), first);
+        assert_eq!(store.load("gh-1").unwrap(), second);
+        assert!(store.load("gh-2").is_err());
+
+        let listing = format_run_list(&runs);
+        assert!(listing.contains("19700101T003320Z-gh-1  failed"));
+        assert!(listing.contains("tokens=0  disk=512MB  prompts=v1"));
+    }
+}
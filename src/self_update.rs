@@ -0,0 +1,511 @@
+//! `rlph self-update`: replace the running binary with a newer GitHub release.
+//!
+//! Releases publish `rlph-<tag>-<target>.tar.gz` archives next to a
+//! `.sha256` checksum file and a build provenance attestation. An update
+//! downloads the archive for this platform, verifies both, and renames the new
+//! binary over `std::env::current_exe()`.
+
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use semver::Version;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tracing::{debug, info};
+
+use crate::error::{Error, Result};
+use crate::gh_quota::gh_output;
+
+const REPO: &str = "hsubra89/rlph";
+const RELEASES_URL: &str = "https://api.github.com/repos/hsubra89/rlph/releases?per_page=50";
+const BINARY_NAME: &str = "rlph";
+
+/// Which releases `self-update` considers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Channel {
+    /// Tagged `vX.Y.Z` releases only.
+    #[default]
+    Stable,
+    /// Stable releases plus `vX.Y.Z-nightly.YYYYMMDD` prereleases.
+    Nightly,
+}
+
+impl FromStr for Channel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "stable" => Ok(Channel::Stable),
+            "nightly" => Ok(Channel::Nightly),
+            other => Err(Error::SelfUpdate(format!(
+                "unknown channel: {other} (expected stable or nightly)"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Channel::Stable => f.write_str("stable"),
+            Channel::Nightly => f.write_str("nightly"),
+        }
+    }
+}
+
+/// A GitHub release as returned by the REST API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    /// Version from a `vX.Y.Z[-pre]` tag, or `None` for other tags.
+    pub fn version(&self) -> Option<Version> {
+        Version::parse(self.tag_name.strip_prefix('v')?).ok()
+    }
+
+    fn asset(&self, name: &str) -> Result<&ReleaseAsset> {
+        self.assets.iter().find(|a| a.name == name).ok_or_else(|| {
+            Error::SelfUpdate(format!("release {} has no asset {name}", self.tag_name))
+        })
+    }
+}
+
+/// Newest release on `channel`. Drafts and tags that are not versions are ignored.
+pub fn select_release(releases: &[Release], channel: Channel) -> Option<&Release> {
+    releases
+        .iter()
+        .filter(|r| !r.draft)
+        .filter_map(|r| Some((r.version()?, r)))
+        .filter(|(v, r)| match channel {
+            Channel::Stable => !r.prerelease && v.pre.is_empty(),
+            Channel::Nightly => true,
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, r)| r)
+}
+
+/// Release target triple for this build, if rlph publishes one.
+pub fn current_target() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => Some("x86_64-unknown-linux-gnu"),
+        ("linux", "aarch64") => Some("aarch64-unknown-linux-gnu"),
+        ("macos", "x86_64") => Some("x86_64-apple-darwin"),
+        ("macos", "aarch64") => Some("aarch64-apple-darwin"),
+        _ => None,
+    }
+}
+
+/// Archive directory name, as packaged by the release workflow.
+pub fn archive_root(tag: &str, target: &str) -> String {
+    format!("{BINARY_NAME}-{tag}-{target}")
+}
+
+/// Options for [`self_update`].
+#[derive(Debug, Clone, Default)]
+pub struct UpdateOptions {
+    pub channel: Channel,
+    /// Report whether an update is available without installing it.
+    pub check_only: bool,
+    /// Install the selected release even if it is not newer.
+    pub force: bool,
+    /// Verify the checksum only, without `gh attestation verify`.
+    pub skip_attestation: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    UpToDate {
+        current: Version,
+        latest: Version,
+    },
+    Available {
+        current: Version,
+        latest: Version,
+    },
+    Updated {
+        from: Version,
+        to: Version,
+        path: PathBuf,
+    },
+}
+
+impl fmt::Display for UpdateOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateOutcome::UpToDate { current, latest } => {
+                write!(f, "rlph {current} is up to date (latest: {latest})")
+            }
+            UpdateOutcome::Available { current, latest } => {
+                write!(f, "rlph {latest} is available (installed: {current})")
+            }
+            UpdateOutcome::Updated { from, to, path } => {
+                write!(f, "updated rlph {from} -> {to} at {}", path.display())
+            }
+        }
+    }
+}
+
+/// Check GitHub releases on `opts.channel` and, unless `check_only`, replace
+/// the running executable with the selected release.
+pub fn self_update(opts: &UpdateOptions) -> Result<UpdateOutcome> {
+    let current = Version::parse(env!("CARGO_PKG_VERSION"))
+        .map_err(|e| Error::SelfUpdate(format!("invalid build version: {e}")))?;
+    let releases = fetch_releases()?;
+    let release = select_release(&releases, opts.channel).ok_or_else(|| {
+        Error::SelfUpdate(format!("no {} release found for {REPO}", opts.channel))
+    })?;
+    let latest = release.version().expect("selected releases have versions");
+
+    if latest <= current && !opts.force {
+        return Ok(UpdateOutcome::UpToDate { current, latest });
+    }
+    if opts.check_only {
+        return Ok(UpdateOutcome::Available { current, latest });
+    }
+
+    let target = current_target().ok_or_else(|| {
+        Error::SelfUpdate(format!(
+            "no release builds for {}-{}",
+            std::env::consts::ARCH,
+            std::env::consts::OS
+        ))
+    })?;
+    let root = archive_root(&release.tag_name, target);
+    let archive_name = format!("{root}.tar.gz");
+    let archive = release.asset(&archive_name)?;
+    let checksum = release.asset(&format!("{archive_name}.sha256"))?;
+
+    let exe = std::env::current_exe()
+        .and_then(|p| p.canonicalize())
+        .map_err(|e| Error::SelfUpdate(format!("cannot locate current executable: {e}")))?;
+    let exe_dir = exe
+        .parent()
+        .ok_or_else(|| Error::SelfUpdate(format!("{} has no parent", exe.display())))?;
+
+    // Stage next to the executable so the final rename stays on one filesystem.
+    let staging = StagingDir::create(exe_dir.join(format!(".rlph-update-{}", std::process::id())))?;
+    let archive_path = staging.path().join(&archive_name);
+
+    info!(tag = %release.tag_name, %target, "downloading {archive_name}");
+    let bytes = download(&archive.browser_download_url)?;
+    let expected = String::from_utf8(download(&checksum.browser_download_url)?)
+        .ok()
+        .and_then(|text| parse_checksum(&text, &archive_name))
+        .ok_or_else(|| Error::SelfUpdate(format!("malformed checksum file for {archive_name}")))?;
+    verify_checksum(&bytes, &expected, &archive_name)?;
+    fs::write(&archive_path, &bytes)?;
+
+    if opts.skip_attestation {
+        debug!("skipping attestation verification");
+    } else {
+        verify_attestation(&archive_path)?;
+    }
+
+    extract(&archive_path, staging.path())?;
+    install_binary(&staging.path().join(&root).join(BINARY_NAME), &exe)?;
+
+    Ok(UpdateOutcome::Updated {
+        from: current,
+        to: latest,
+        path: exe,
+    })
+}
+
+fn fetch_releases() -> Result<Vec<Release>> {
+    debug!(url = RELEASES_URL, "fetching releases");
+    with_auth(ureq::get(RELEASES_URL))
+        .set("Accept", "application/vnd.github+json")
+        .call()
+        .map_err(|e| Error::SelfUpdate(format!("failed to list releases: {e}")))?
+        .into_json()
+        .map_err(|e| Error::SelfUpdate(format!("failed to parse releases: {e}")))
+}
+
+fn download(url: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    with_auth(ureq::get(url))
+        .call()
+        .map_err(|e| Error::SelfUpdate(format!("failed to download {url}: {e}")))?
+        .into_reader()
+        .read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Unauthenticated API calls are rate limited per IP, which fleets share.
+fn with_auth(request: ureq::Request) -> ureq::Request {
+    let request = request.set("User-Agent", BINARY_NAME);
+    match std::env::var("GH_TOKEN").or_else(|_| std::env::var("GITHUB_TOKEN")) {
+        Ok(token) if !token.is_empty() => request.set("Authorization", &format!("Bearer {token}")),
+        _ => request,
+    }
+}
+
+/// Digest for `file_name` from `sha256sum` output. A bare digest is accepted too.
+pub fn parse_checksum(text: &str, file_name: &str) -> Option<String> {
+    let is_digest = |s: &str| s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit());
+    text.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let digest = parts.next()?;
+        let name = parts.next().map(|n| n.trim_start_matches('*'));
+        (is_digest(digest) && name.is_none_or(|n| n == file_name))
+            .then(|| digest.to_ascii_lowercase())
+    })
+}
+
+fn verify_checksum(bytes: &[u8], expected: &str, name: &str) -> Result<()> {
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if actual != expected {
+        return Err(Error::SelfUpdate(format!(
+            "checksum mismatch for {name}: expected {expected}, got {actual}"
+        )));
+    }
+    Ok(())
+}
+
+fn verify_attestation(archive: &Path) -> Result<()> {
+    let output = gh_output(
+        Command::new("gh")
+            .args(["attestation", "verify"])
+            .arg(archive)
+            .args(["--repo", REPO]),
+    )
+    .map_err(|e| {
+        Error::SelfUpdate(format!(
+            "failed to run `gh attestation verify` ({e}); install gh or pass --skip-attestation"
+        ))
+    })?;
+    if !output.status.success() {
+        return Err(Error::SelfUpdate(format!(
+            "attestation verification failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+fn extract(archive: &Path, dest: &Path) -> Result<()> {
+    let output = Command::new("tar")
+        .arg("-xzf")
+        .arg(archive)
+        .arg("-C")
+        .arg(dest)
+        .output()
+        .map_err(|e| Error::SelfUpdate(format!("failed to run tar: {e}")))?;
+    if !output.status.success() {
+        return Err(Error::SelfUpdate(format!(
+            "failed to extract {}: {}",
+            archive.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Copy `new_binary` beside `exe` and rename it into place, so a running
+/// process keeps its old inode and no caller ever sees a partial file.
+pub fn install_binary(new_binary: &Path, exe: &Path) -> Result<()> {
+    if !new_binary.is_file() {
+        return Err(Error::SelfUpdate(format!(
+            "archive does not contain {}",
+            new_binary.display()
+        )));
+    }
+    let file_name = exe
+        .file_name()
+        .ok_or_else(|| Error::SelfUpdate(format!("invalid executable path {}", exe.display())))?;
+    let tmp = exe.with_file_name(format!(
+        ".{}.new-{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    fs::copy(new_binary, &tmp)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp, fs::Permissions::from_mode(0o755))?;
+    }
+    if let Err(e) = fs::rename(&tmp, exe) {
+        let _ = fs::remove_file(&tmp);
+        return Err(Error::SelfUpdate(format!(
+            "failed to replace {}: {e}",
+            exe.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Download directory removed on drop, whether or not the update succeeded.
+struct StagingDir(PathBuf);
+
+impl StagingDir {
+    fn create(path: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&path)
+            .map_err(|e| Error::SelfUpdate(format!("cannot write to {}: {e}", path.display())))?;
+        Ok(Self(path))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str, prerelease: bool) -> Release {
+        Release {
+            tag_name: tag.to_string(),
+            prerelease,
+            draft: false,
+            assets: vec![],
+        }
+    }
+
+    #[test]
+    fn test_channel_from_str() {
+        assert_eq!("stable".parse::<Channel>().unwrap(), Channel::Stable);
+        assert_eq!("nightly".parse::<Channel>().unwrap(), Channel::Nightly);
+        assert!("beta".parse::<Channel>().is_err());
+    }
+
+    #[test]
+    fn test_select_release_by_channel() {
+        let mut draft = release("v0.3.0", false);
+        draft.draft = true;
+        let releases = vec![
+            release("v0.2.1-nightly.20261015", true),
+            release("v0.2.0", false),
+            draft,
+            release("nightly", true),
+            release("v0.1.9", false),
+        ];
+
+        let stable = select_release(&releases, Channel::Stable).unwrap();
+        assert_eq!(stable.tag_name, "v0.2.0");
+        let nightly = select_release(&releases, Channel::Nightly).unwrap();
+        assert_eq!(nightly.tag_name, "v0.2.1-nightly.20261015");
+
+        let only_nightlies = vec![release("v0.2.1-nightly.20261015", true)];
+        assert!(select_release(&only_nightlies, Channel::Stable).is_none());
+    }
+
+    #[test]
+    fn test_nightly_channel_prefers_newer_stable() {
+        let releases = vec![
+            release("v0.2.1-nightly.20261015", true),
+            release("v0.2.1-nightly.20261016", true),
+            release("v0.2.1", false),
+        ];
+        let nightly = select_release(&releases, Channel::Nightly).unwrap();
+        assert_eq!(nightly.tag_name, "v0.2.1");
+    }
+
+    #[test]
+    fn test_release_asset_lookup() {
+        let mut r = release("v0.2.0", false);
+        r.assets.push(ReleaseAsset {
+            name: "rlph-v0.2.0-x86_64-unknown-linux-gnu.tar.gz".to_string(),
+            browser_download_url: "https://example.com/a.tar.gz".to_string(),
+        });
+        let name = format!(
+            "{}.tar.gz",
+            archive_root(&r.tag_name, "x86_64-unknown-linux-gnu")
+        );
+        assert_eq!(
+            r.asset(&name).unwrap().browser_download_url,
+            "https://example.com/a.tar.gz"
+        );
+        let err = r.asset(&format!("{name}.sha256")).unwrap_err();
+        assert!(err.to_string().contains("has no asset"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_checksum_formats() {
+        let digest = "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD";
+        let lower = digest.to_ascii_lowercase();
+        assert_eq!(
+            parse_checksum(&format!("{digest}  rlph.tar.gz\n"), "rlph.tar.gz"),
+            Some(lower.clone())
+        );
+        assert_eq!(
+            parse_checksum(&format!("{digest} *rlph.tar.gz"), "rlph.tar.gz"),
+            Some(lower.clone())
+        );
+        assert_eq!(parse_checksum(digest, "rlph.tar.gz"), Some(lower));
+        assert_eq!(
+            parse_checksum(&format!("{digest}  other.tar.gz"), "rlph.tar.gz"),
+            None
+        );
+        assert_eq!(
+            parse_checksum("not-a-digest  rlph.tar.gz", "rlph.tar.gz"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_verify_checksum_rejects_mismatch() {
+        let good = format!("{:x}", Sha256::digest(b"archive"));
+        assert!(verify_checksum(b"archive", &good, "a.tar.gz").is_ok());
+        let err = verify_checksum(b"tampered", &good, "a.tar.gz").unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{err}");
+    }
+
+    #[test]
+    fn test_install_binary_replaces_executable() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("rlph");
+        let new_binary = dir.path().join("staged");
+        fs::write(&exe, "old").unwrap();
+        fs::write(&new_binary, "new").unwrap();
+
+        install_binary(&new_binary, &exe).unwrap();
+
+        assert_eq!(fs::read_to_string(&exe).unwrap(), "new");
+        let leftovers: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(leftovers.len(), 2, "{leftovers:?}");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&exe).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+    }
+
+    #[test]
+    fn test_install_binary_requires_extracted_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("rlph");
+        fs::write(&exe, "old").unwrap();
+        let err = install_binary(&dir.path().join("missing"), &exe).unwrap_err();
+        assert!(err.to_string().contains("does not contain"), "{err}");
+        assert_eq!(fs::read_to_string(&exe).unwrap(), "old");
+    }
+}
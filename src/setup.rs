@@ -0,0 +1,336 @@
+//! `rlph setup`: first-run wizard that inspects the repo, asks for the task
+//! source, runner and label, checks credentials, writes `.rlph/config.toml`
+//! and creates the labels rlph needs.
+
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{Error, Result};
+use crate::gh_quota::gh_output;
+use crate::runner::RunnerKind;
+use crate::toolchain::{Toolchain, detect_toolchains};
+
+/// Sources the wizard can configure end to end. The others need sections
+/// that are easier to write by hand.
+const SOURCES: &[&str] = &["github", "linear"];
+
+const RUNNERS: [RunnerKind; 3] = [RunnerKind::Claude, RunnerKind::Codex, RunnerKind::OpenCode];
+
+/// Workflow labels the GitHub source moves tasks through.
+const GITHUB_STATE_LABELS: &[&str] = &["in-progress", "in-review"];
+
+/// What the wizard found before asking anything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepoSurvey {
+    /// `owner/repo` of the GitHub repo, when `gh` can resolve one.
+    pub github_repo: Option<String>,
+    /// Runners whose CLI is on `PATH`.
+    pub runners: Vec<RunnerKind>,
+    pub toolchains: Vec<Toolchain>,
+    /// Whether `.rlph/config.toml` already exists; the wizard merges into it.
+    pub has_config: bool,
+}
+
+/// The answers the wizard collects.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetupChoices {
+    pub source: String,
+    pub runner: RunnerKind,
+    pub label: String,
+    /// Run `rlph --once --dry-run` after setup.
+    pub dry_run: bool,
+}
+
+/// Inspect the repo at `repo_root`.
+pub fn survey(repo_root: &Path) -> RepoSurvey {
+    let github_repo = gh_output(
+        Command::new("gh")
+            .args([
+                "repo",
+                "view",
+                "--json",
+                "nameWithOwner",
+                "-q",
+                ".nameWithOwner",
+            ])
+            .current_dir(repo_root),
+    )
+    .ok()
+    .filter(|o| o.status.success())
+    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+    .filter(|repo| !repo.is_empty());
+    RepoSurvey {
+        github_repo,
+        runners: RUNNERS
+            .into_iter()
+            .filter(|r| on_path(&r.to_string()))
+            .collect(),
+        toolchains: detect_toolchains(repo_root),
+        has_config: repo_root.join(".rlph/config.toml").is_file(),
+    }
+}
+
+pub(crate) fn on_path(binary: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(binary).is_file()))
+}
+
+/// Print the survey and ask for the setup choices, offering what the survey
+/// found as defaults.
+pub fn ask_choices(
+    survey: &RepoSurvey,
+    stdin: &mut dyn BufRead,
+    stderr: &mut dyn Write,
+) -> Result<SetupChoices> {
+    if let Some(repo) = &survey.github_repo {
+        writeln!(stderr, "GitHub repo: {repo}").ok();
+    } else {
+        writeln!(stderr, "No GitHub repo found for this directory").ok();
+    }
+    if !survey.toolchains.is_empty() {
+        let names: Vec<_> = survey.toolchains.iter().map(|t| t.name()).collect();
+        writeln!(stderr, "Project: {}", names.join(", ")).ok();
+    }
+    if survey.has_config {
+        writeln!(
+            stderr,
+            "Found .rlph/config.toml; your answers are merged into it"
+        )
+        .ok();
+    }
+
+    let source = ask_choice(
+        "Task source",
+        SOURCES,
+        if survey.github_repo.is_some() { 0 } else { 1 },
+        stdin,
+        stderr,
+    )?
+    .to_string();
+
+    let runner_names: Vec<String> = RUNNERS.iter().map(|r| r.to_string()).collect();
+    let runner_names: Vec<&str> = runner_names.iter().map(String::as_str).collect();
+    let default_runner = survey
+        .runners
+        .first()
+        .and_then(|r| RUNNERS.iter().position(|k| k == r))
+        .unwrap_or(0);
+    let runner: RunnerKind =
+        ask_choice("Runner", &runner_names, default_runner, stdin, stderr)?.parse()?;
+    if !survey.runners.contains(&runner) {
+        writeln!(stderr, "warning: `{runner}` is not on PATH").ok();
+    }
+
+    let label = ask("Label for tasks rlph should pick up", "rlph", stdin, stderr)?;
+    if label.contains(char::is_whitespace) {
+        return Err(Error::Setup(format!("invalid label: {label:?}")));
+    }
+    let dry_run = ask(
+        "Run a dry-run iteration afterwards? [y/N]",
+        "n",
+        stdin,
+        stderr,
+    )?
+    .eq_ignore_ascii_case("y");
+
+    Ok(SetupChoices {
+        source,
+        runner,
+        label,
+        dry_run,
+    })
+}
+
+/// Ask a free-form question; an empty answer takes `default`.
+fn ask(
+    question: &str,
+    default: &str,
+    stdin: &mut dyn BufRead,
+    stderr: &mut dyn Write,
+) -> Result<String> {
+    write!(stderr, "{question} [{default}]: ").ok();
+    stderr.flush().ok();
+    let mut line = String::new();
+    stdin
+        .read_line(&mut line)
+        .map_err(|e| Error::Setup(format!("failed to read stdin: {e}")))?;
+    let answer = line.trim();
+    Ok(if answer.is_empty() { default } else { answer }.to_string())
+}
+
+/// Ask to pick one of `options` by number or name.
+fn ask_choice<'a>(
+    question: &str,
+    options: &[&'a str],
+    default: usize,
+    stdin: &mut dyn BufRead,
+    stderr: &mut dyn Write,
+) -> Result<&'a str> {
+    writeln!(stderr, "{question}:").ok();
+    for (i, option) in options.iter().enumerate() {
+        writeln!(stderr, "  {}) {option}", i + 1).ok();
+    }
+    let answer = ask("Choice", options[default], stdin, stderr)?;
+    answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|i| options.get(i))
+        .or_else(|| options.iter().find(|o| o.eq_ignore_ascii_case(&answer)))
+        .copied()
+        .ok_or_else(|| {
+            Error::Setup(format!(
+                "invalid choice: {answer} (expected 1-{} or a name)",
+                options.len()
+            ))
+        })
+}
+
+/// Check that `gh` is logged in. Linear credentials are checked when its team
+/// is looked up.
+pub fn check_github_auth() -> Result<()> {
+    let output = gh_output(Command::new("gh").args(["auth", "status"]))
+        .map_err(|e| Error::Setup(format!("failed to run gh: {e}")))?;
+    if !output.status.success() {
+        return Err(Error::Setup(format!(
+            "gh is not logged in; run `gh auth login` first: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Create the task label and the workflow labels in the GitHub repo,
+/// leaving existing ones as they are.
+pub fn create_github_labels(repo_root: &Path, label: &str) -> Result<()> {
+    for name in std::iter::once(label).chain(GITHUB_STATE_LABELS.iter().copied()) {
+        let output = gh_output(
+            Command::new("gh")
+                .args(["label", "create", name])
+                .current_dir(repo_root),
+        )
+        .map_err(|e| Error::Setup(format!("failed to run gh: {e}")))?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() && !stderr.contains("already exists") {
+            return Err(Error::Setup(format!(
+                "failed to create label '{name}': {}",
+                stderr.trim()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Write the choices into `<repo_root>/.rlph/config.toml`, keeping any other
+/// settings already there.
+pub fn write_config(repo_root: &Path, choices: &SetupChoices) -> Result<PathBuf> {
+    let dir = repo_root.join(".rlph");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("config.toml");
+    let mut table = match std::fs::read_to_string(&path) {
+        Ok(existing) => existing
+            .parse::<toml::Table>()
+            .map_err(|e| Error::Setup(format!("failed to parse {}: {e}", path.display())))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => toml::Table::new(),
+        Err(e) => return Err(e.into()),
+    };
+    for (key, value) in [
+        ("source", choices.source.clone()),
+        ("runner", choices.runner.to_string()),
+        ("label", choices.label.clone()),
+    ] {
+        table.insert(key.to_string(), toml::Value::String(value));
+    }
+    let content = toml::to_string(&table)
+        .map_err(|e| Error::Setup(format!("failed to serialize {}: {e}", path.display())))?;
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// Run the wizard against the current directory. Returns the choices so the
+/// caller can start the dry run.
+pub fn run(repo_root: &Path) -> Result<SetupChoices> {
+    let survey = survey(repo_root);
+    let mut stderr = std::io::stderr();
+    let choices = ask_choices(&survey, &mut std::io::stdin().lock(), &mut stderr)?;
+
+    if choices.source == "github" {
+        check_github_auth()?;
+        if survey.github_repo.is_none() {
+            return Err(Error::Setup(
+                "source 'github' needs a GitHub remote that gh can resolve".to_string(),
+            ));
+        }
+    }
+    let path = write_config(repo_root, &choices)?;
+    match choices.source.as_str() {
+        "github" => create_github_labels(repo_root, &choices.label)?,
+        // Verifies the API key, picks a team, creates the label and adds the
+        // `[linear]` section.
+        _ => crate::sources::linear::init_interactive(&choices.label)?,
+    }
+    writeln!(stderr, "Wrote {}", path.display()).ok();
+    Ok(choices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn answer(survey: &RepoSurvey, input: &str) -> Result<SetupChoices> {
+        ask_choices(survey, &mut Cursor::new(input.as_bytes()), &mut Vec::new())
+    }
+
+    #[test]
+    fn test_ask_choices_defaults_from_survey() {
+        let survey = RepoSurvey {
+            github_repo: Some("acme/app".to_string()),
+            runners: vec![RunnerKind::Codex],
+            ..Default::default()
+        };
+        assert_eq!(
+            answer(&survey, "\n\n\n\n").unwrap(),
+            SetupChoices {
+                source: "github".to_string(),
+                runner: RunnerKind::Codex,
+                label: "rlph".to_string(),
+                dry_run: false,
+            }
+        );
+
+        let choices = answer(&survey, "linear\n1\nbot\ny\n").unwrap();
+        assert_eq!(choices.source, "linear");
+        assert_eq!(choices.runner, RunnerKind::Claude);
+        assert_eq!(choices.label, "bot");
+        assert!(choices.dry_run);
+
+        let err = answer(&survey, "9\n").unwrap_err();
+        assert!(err.to_string().contains("invalid choice"), "{err}");
+    }
+
+    #[test]
+    fn test_write_config_merges_into_existing() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".rlph")).unwrap();
+        std::fs::write(
+            tmp.path().join(".rlph/config.toml"),
+            "label = \"old\"\nmax_review_rounds = 2\n",
+        )
+        .unwrap();
+        let choices = SetupChoices {
+            source: "github".to_string(),
+            runner: RunnerKind::Claude,
+            label: "rlph".to_string(),
+            dry_run: false,
+        };
+        let path = write_config(tmp.path(), &choices).unwrap();
+
+        let table: toml::Table = std::fs::read_to_string(path).unwrap().parse().unwrap();
+        assert_eq!(table["source"].as_str(), Some("github"));
+        assert_eq!(table["runner"].as_str(), Some("claude"));
+        assert_eq!(table["label"].as_str(), Some("rlph"));
+        assert_eq!(table["max_review_rounds"].as_integer(), Some(2));
+    }
+}
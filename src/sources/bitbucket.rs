@@ -0,0 +1,326 @@
+use std::collections::HashSet;
+
+use serde_json::{Value, json};
+use tracing::debug;
+
+use crate::bitbucket::{BitbucketClient, bbql_string, client_from_config, encode_query, fetch_all};
+use crate::config::Config;
+use crate::error::{Error, Result};
+
+use super::{Priority, Task, TaskSource, is_on_hold, parse_timestamp};
+
+/// Issue states a task can be picked up from.
+const ELIGIBLE_STATE: &str = "new";
+/// State set while rlph works on a task and after its PR is opened.
+const CLAIMED_STATE: &str = "open";
+/// Issue states counted as done for dependency resolution.
+const CLOSED_STATES: &[&str] = &["resolved", "closed", "invalid", "duplicate", "wontfix"];
+
+/// Bitbucket Cloud issue tracker.
+///
+/// Bitbucket issues have no labels; an issue's component stands in for one, so
+/// the configured `label` must exist as a component of the repository.
+/// `new` issues with that component are eligible, and claimed issues move to
+/// `open` until they are resolved.
+pub struct BitbucketSource {
+    label: String,
+    hold_labels: Vec<String>,
+    client: Box<dyn BitbucketClient>,
+}
+
+impl BitbucketSource {
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self {
+            label: config.label.clone(),
+            hold_labels: config.hold_labels.clone(),
+            client: client_from_config(config)?,
+        })
+    }
+
+    #[cfg(test)]
+    fn with_client(label: &str, client: Box<dyn BitbucketClient>) -> Self {
+        Self {
+            label: label.to_string(),
+            hold_labels: crate::config::default_hold_labels(),
+            client,
+        }
+    }
+
+    fn call(&self, method: &str, path: &str, body: Option<&Value>) -> Result<Value> {
+        self.client
+            .request(method, path, body)
+            .map_err(Error::TaskSource)
+    }
+
+    fn issues(&self, query: &str) -> Result<Vec<Value>> {
+        fetch_all(
+            self.client.as_ref(),
+            &format!("/issues?q={}", encode_query(query)),
+        )
+        .map_err(Error::TaskSource)
+    }
+
+    fn set_state(&self, task_id: &str, state: &str) -> Result<()> {
+        self.call(
+            "PUT",
+            &format!("/issues/{task_id}"),
+            Some(&json!({ "state": state })),
+        )?;
+        Ok(())
+    }
+
+    fn is_held(&self, issue: &Value, task: &Task) -> Result<bool> {
+        if is_on_hold(&task.labels, std::iter::empty(), &self.hold_labels) {
+            return Ok(true);
+        }
+        let id = issue.get("id").and_then(Value::as_u64).unwrap_or_default();
+        let comments = fetch_all(self.client.as_ref(), &format!("/issues/{id}/comments"))
+            .map_err(Error::TaskSource)?;
+        Ok(is_on_hold(
+            &[],
+            comments
+                .iter()
+                .filter_map(|c| c.pointer("/content/raw").and_then(Value::as_str)),
+            &self.hold_labels,
+        ))
+    }
+
+    /// Map Bitbucket issue priority to our Priority (1-9).
+    fn map_priority(priority: &str) -> Option<Priority> {
+        match priority {
+            "blocker" | "critical" => Some(Priority(1)),
+            "major" => Some(Priority(3)),
+            "minor" => Some(Priority(6)),
+            "trivial" => Some(Priority(9)),
+            _ => None,
+        }
+    }
+
+    fn parse_issue(issue: &Value) -> Option<Task> {
+        let str_at = |pointer: &str| issue.pointer(pointer).and_then(Value::as_str);
+        let labels: Vec<String> = str_at("/component/name")
+            .map(|c| vec![c.to_string()])
+            .unwrap_or_default();
+        Some(Task {
+            id: issue.get("id").and_then(Value::as_u64)?.to_string(),
+            title: str_at("/title").unwrap_or_default().to_string(),
+            body: str_at("/content/raw").unwrap_or_default().to_string(),
+            url: str_at("/links/html/href").unwrap_or_default().to_string(),
+            priority: str_at("/priority").and_then(Self::map_priority),
+            created_at: str_at("/created_on").map(str::to_string),
+            updated_at: str_at("/updated_on").map(str::to_string),
+            milestone: str_at("/milestone/name").map(str::to_string),
+            reactions: None,
+            assignee: str_at("/assignee/display_name").map(str::to_string),
+            estimate: None,
+            project: None,
+            labels,
+        })
+    }
+
+    fn closed_issue_ids(&self, since: Option<u64>) -> Result<HashSet<u64>> {
+        let states: Vec<String> = CLOSED_STATES
+            .iter()
+            .map(|s| format!("state={}", bbql_string(s)))
+            .collect();
+        let issues = self.issues(&format!("({})", states.join(" OR ")))?;
+        Ok(issues
+            .iter()
+            .filter(|issue| {
+                since.is_none_or(|since| {
+                    issue
+                        .get("updated_on")
+                        .and_then(Value::as_str)
+                        .and_then(parse_timestamp)
+                        .is_none_or(|updated| updated >= since)
+                })
+            })
+            .filter_map(|issue| issue.get("id").and_then(Value::as_u64))
+            .collect())
+    }
+}
+
+impl TaskSource for BitbucketSource {
+    fn fetch_eligible_tasks(&self) -> Result<Vec<Task>> {
+        let issues = self.issues(&format!(
+            "component.name={} AND state={}",
+            bbql_string(&self.label),
+            bbql_string(ELIGIBLE_STATE)
+        ))?;
+        let mut tasks = Vec::new();
+        for issue in &issues {
+            let Some(task) = Self::parse_issue(issue) else {
+                continue;
+            };
+            if self.is_held(issue, &task)? {
+                debug!(issue = %task.id, "skipping task on hold");
+                continue;
+            }
+            tasks.push(task);
+        }
+        debug!(count = tasks.len(), "fetched eligible Bitbucket tasks");
+        Ok(tasks)
+    }
+
+    fn mark_in_progress(&self, task_id: &str) -> Result<()> {
+        self.set_state(task_id, CLAIMED_STATE)
+    }
+
+    /// Issues stay `open` while their PR is in review; Bitbucket has no review
+    /// state.
+    fn mark_in_review(&self, _task_id: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_task_details(&self, task_id: &str) -> Result<Task> {
+        let issue = self.call("GET", &format!("/issues/{task_id}"), None)?;
+        Self::parse_issue(&issue)
+            .ok_or_else(|| Error::TaskSource(format!("Bitbucket issue {task_id} not found")))
+    }
+
+    fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>> {
+        self.closed_issue_ids(None)
+    }
+
+    fn fetch_closed_task_ids_since(&self, since: u64) -> Result<HashSet<u64>> {
+        self.closed_issue_ids(Some(since))
+    }
+
+    fn fetch_open_tasks_with_label(&self, label: &str) -> Result<Vec<Task>> {
+        let issues = self.issues(&format!(
+            "component.name={} AND (state={} OR state={})",
+            bbql_string(label),
+            bbql_string(ELIGIBLE_STATE),
+            bbql_string(CLAIMED_STATE)
+        ))?;
+        Ok(issues.iter().filter_map(Self::parse_issue).collect())
+    }
+
+    /// Only the first label is kept, as the issue's component.
+    fn create_task(&self, title: &str, body: &str, labels: &[String]) -> Result<Task> {
+        let mut issue = json!({
+            "title": title,
+            "content": { "raw": body },
+            "kind": "task",
+        });
+        if let Some(label) = labels.first() {
+            issue["component"] = json!({ "name": label });
+        }
+        let created = self.call("POST", "/issues", Some(&issue))?;
+        Self::parse_issue(&created).ok_or_else(|| {
+            Error::TaskSource("Bitbucket did not return the created issue".to_string())
+        })
+    }
+
+    /// Sets the issue's component, replacing any other. Bitbucket has no API
+    /// for creating components, so it must already exist.
+    fn add_label(&self, task_id: &str, label: &str) -> Result<()> {
+        self.call(
+            "PUT",
+            &format!("/issues/{task_id}"),
+            Some(&json!({ "component": { "name": label } })),
+        )?;
+        Ok(())
+    }
+
+    fn release_task(&self, task_id: &str) -> Result<()> {
+        self.set_state(task_id, ELIGIBLE_STATE)
+    }
+
+    fn add_comment(&self, task_id: &str, body: &str) -> Result<()> {
+        self.call(
+            "POST",
+            &format!("/issues/{task_id}/comments"),
+            Some(&json!({ "content": { "raw": body } })),
+        )?;
+        Ok(())
+    }
+
+    /// Bitbucket links issues mentioned in a PR description on its own.
+    fn attach_link(&self, _task_id: &str, _url: &str, _title: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::MockHttpClient;
+    use std::sync::Arc;
+
+    fn issue(id: u64, priority: &str) -> Value {
+        json!({
+            "id": id,
+            "title": format!("Issue {id}"),
+            "content": { "raw": "Do the thing" },
+            "state": "new",
+            "priority": priority,
+            "component": { "name": "rlph" },
+            "milestone": { "name": "v1" },
+            "assignee": { "display_name": "Ada" },
+            "created_on": "2026-01-02T03:04:05.000000+00:00",
+            "links": { "html": { "href": format!("https://bitbucket.org/w/r/issues/{id}") } },
+        })
+    }
+
+    #[test]
+    fn test_fetch_eligible_maps_issues_and_skips_held() {
+        let client = MockHttpClient::default()
+            .respond(
+                "GET /issues?q=",
+                json!({ "values": [issue(1, "critical"), issue(2, "minor")] }),
+            )
+            .respond("GET /issues/1/comments", json!({ "values": [] }))
+            .respond(
+                "GET /issues/2/comments",
+                json!({ "values": [{ "content": { "raw": "/rlph hold" } }] }),
+            );
+        let source = BitbucketSource::with_client("rlph", Box::new(client));
+        let tasks = source.fetch_eligible_tasks().unwrap();
+        assert_eq!(tasks.len(), 1);
+        let task = &tasks[0];
+        assert_eq!(task.id, "1");
+        assert_eq!(task.labels, ["rlph"]);
+        assert_eq!(task.priority, Some(Priority(1)));
+        assert_eq!(task.milestone.as_deref(), Some("v1"));
+        assert_eq!(task.assignee.as_deref(), Some("Ada"));
+        assert_eq!(task.url, "https://bitbucket.org/w/r/issues/1");
+    }
+
+    #[test]
+    fn test_claim_and_release_set_issue_state() {
+        let client = Arc::new(MockHttpClient::default());
+        let source = BitbucketSource::with_client("rlph", Box::new(client.clone()));
+        source.mark_in_progress("4").unwrap();
+        source.mark_in_review("4").unwrap();
+        source.release_task("4").unwrap();
+        let requests = client.requests.lock().unwrap();
+        let states: Vec<(&str, &Value)> = requests
+            .iter()
+            .map(|(key, _, body)| (key.as_str(), &body.as_ref().unwrap()["state"]))
+            .collect();
+        assert_eq!(
+            states,
+            [
+                ("PUT /issues/4", &json!("open")),
+                ("PUT /issues/4", &json!("new"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fetch_closed_task_ids_since_filters_by_update_time() {
+        let mut old = issue(1, "major");
+        old["updated_on"] = json!("2026-01-01T00:00:00+00:00");
+        let mut recent = issue(2, "major");
+        recent["updated_on"] = json!("2026-03-01T00:00:00+00:00");
+        let client =
+            MockHttpClient::default().respond("GET /issues?q=", json!({ "values": [old, recent] }));
+        let source = BitbucketSource::with_client("rlph", Box::new(client));
+        let since = parse_timestamp("2026-02-01T00:00:00Z").unwrap();
+        assert_eq!(
+            source.fetch_closed_task_ids_since(since).unwrap(),
+            HashSet::from([2])
+        );
+    }
+}
@@ -0,0 +1,406 @@
+use std::collections::HashSet;
+
+use serde_json::{Value, json};
+use tracing::{debug, warn};
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::gitea::{GiteaClient, client_from_config, fetch_all};
+
+use super::{Priority, Task, TaskSource, format_iso_timestamp, is_on_hold};
+
+const IN_PROGRESS_LABEL: &str = "in-progress";
+const IN_REVIEW_LABEL: &str = "in-review";
+/// Color given to labels rlph creates.
+const LABEL_COLOR: &str = "#0075ca";
+
+/// Gitea or Forgejo issues, e.g. on Codeberg.
+///
+/// Works like the GitHub source: open issues with the configured label are
+/// eligible unless labeled `in-progress` or `in-review`, which rlph sets as a
+/// task moves through the loop.
+pub struct GiteaSource {
+    label: String,
+    hold_labels: Vec<String>,
+    client: Box<dyn GiteaClient>,
+}
+
+impl GiteaSource {
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self {
+            label: config.label.clone(),
+            hold_labels: config.hold_labels.clone(),
+            client: client_from_config(config)?,
+        })
+    }
+
+    #[cfg(test)]
+    fn with_client(label: &str, client: Box<dyn GiteaClient>) -> Self {
+        Self {
+            label: label.to_string(),
+            hold_labels: crate::config::default_hold_labels(),
+            client,
+        }
+    }
+
+    fn call(&self, method: &str, path: &str, body: Option<&Value>) -> Result<Value> {
+        self.client
+            .request(method, path, &[], body)
+            .map_err(Error::TaskSource)
+    }
+
+    fn issues(&self, query: &[(&str, &str)]) -> Result<Vec<Value>> {
+        let mut query = query.to_vec();
+        query.push(("type", "issues"));
+        fetch_all(self.client.as_ref(), "/issues", &query).map_err(Error::TaskSource)
+    }
+
+    fn issue(&self, task_id: &str) -> Result<Value> {
+        self.call("GET", &format!("/issues/{task_id}"), None)
+    }
+
+    fn issue_labels(issue: &Value) -> Vec<String> {
+        issue
+            .get("labels")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|l| l.get("name").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn has_label(issue: &Value, label: &str) -> bool {
+        Self::issue_labels(issue)
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(label))
+    }
+
+    /// Id of the repository label named `name`, created if missing.
+    fn label_id(&self, name: &str) -> Result<u64> {
+        let labels = fetch_all(self.client.as_ref(), "/labels", &[]).map_err(Error::TaskSource)?;
+        let existing = labels.iter().find_map(|l| {
+            let matches = l
+                .get("name")
+                .and_then(Value::as_str)
+                .is_some_and(|n| n.eq_ignore_ascii_case(name));
+            if matches {
+                l.get("id").and_then(Value::as_u64)
+            } else {
+                None
+            }
+        });
+        if let Some(id) = existing {
+            return Ok(id);
+        }
+        self.call(
+            "POST",
+            "/labels",
+            Some(&json!({ "name": name, "color": LABEL_COLOR })),
+        )?
+        .get("id")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| Error::TaskSource(format!("failed to create Gitea label '{name}'")))
+    }
+
+    fn add_labels(&self, task_id: &str, labels: &[&str]) -> Result<()> {
+        let ids = labels
+            .iter()
+            .map(|l| self.label_id(l))
+            .collect::<Result<Vec<_>>>()?;
+        self.call(
+            "POST",
+            &format!("/issues/{task_id}/labels"),
+            Some(&json!({ "labels": ids })),
+        )?;
+        Ok(())
+    }
+
+    /// Remove `label` from the issue if it carries it.
+    fn remove_label(&self, issue: &Value, task_id: &str, label: &str) -> Result<()> {
+        let id = issue
+            .get("labels")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .find(|l| {
+                l.get("name")
+                    .and_then(Value::as_str)
+                    .is_some_and(|n| n.eq_ignore_ascii_case(label))
+            })
+            .and_then(|l| l.get("id").and_then(Value::as_u64));
+        if let Some(id) = id {
+            self.call("DELETE", &format!("/issues/{task_id}/labels/{id}"), None)?;
+        }
+        Ok(())
+    }
+
+    fn is_held(&self, issue: &Value) -> Result<bool> {
+        let labels = Self::issue_labels(issue);
+        if is_on_hold(&labels, std::iter::empty(), &self.hold_labels) {
+            return Ok(true);
+        }
+        let number = issue
+            .get("number")
+            .and_then(Value::as_u64)
+            .unwrap_or_default();
+        let comments = fetch_all(
+            self.client.as_ref(),
+            &format!("/issues/{number}/comments"),
+            &[],
+        )
+        .map_err(Error::TaskSource)?;
+        Ok(is_on_hold(
+            &[],
+            comments
+                .iter()
+                .filter_map(|c| c.get("body").and_then(Value::as_str)),
+            &self.hold_labels,
+        ))
+    }
+
+    fn parse_issue(issue: &Value) -> Option<Task> {
+        let str_at = |pointer: &str| issue.pointer(pointer).and_then(Value::as_str);
+        let labels = Self::issue_labels(issue);
+        let priority = labels.iter().find_map(|l| Priority::from_label(l));
+        Some(Task {
+            id: issue.get("number").and_then(Value::as_u64)?.to_string(),
+            title: str_at("/title").unwrap_or_default().to_string(),
+            body: str_at("/body").unwrap_or_default().to_string(),
+            url: str_at("/html_url").unwrap_or_default().to_string(),
+            priority,
+            created_at: str_at("/created_at").map(str::to_string),
+            updated_at: str_at("/updated_at").map(str::to_string),
+            milestone: str_at("/milestone/title").map(str::to_string),
+            reactions: None,
+            assignee: str_at("/assignee/login").map(str::to_string),
+            estimate: None,
+            project: None,
+            labels,
+        })
+    }
+
+    fn closed_issue_numbers(&self, since: Option<u64>) -> Result<HashSet<u64>> {
+        let since = since.map(format_iso_timestamp);
+        let mut query = vec![("state", "closed")];
+        if let Some(since) = &since {
+            query.push(("since", since.as_str()));
+        }
+        Ok(self
+            .issues(&query)?
+            .iter()
+            .filter_map(|issue| issue.get("number").and_then(Value::as_u64))
+            .collect())
+    }
+}
+
+impl TaskSource for GiteaSource {
+    fn fetch_eligible_tasks(&self) -> Result<Vec<Task>> {
+        let issues = self.issues(&[("state", "open"), ("labels", self.label.as_str())])?;
+        let mut tasks = Vec::new();
+        for issue in &issues {
+            if Self::has_label(issue, IN_PROGRESS_LABEL) || Self::has_label(issue, IN_REVIEW_LABEL)
+            {
+                continue;
+            }
+            let Some(task) = Self::parse_issue(issue) else {
+                continue;
+            };
+            if self.is_held(issue)? {
+                debug!(issue = %task.id, "skipping task on hold");
+                continue;
+            }
+            tasks.push(task);
+        }
+        debug!(count = tasks.len(), "fetched eligible Gitea tasks");
+        Ok(tasks)
+    }
+
+    fn mark_in_progress(&self, task_id: &str) -> Result<()> {
+        let before = self.issue(task_id)?;
+        if let Some(claim) = [IN_PROGRESS_LABEL, IN_REVIEW_LABEL]
+            .into_iter()
+            .find(|l| Self::has_label(&before, l))
+        {
+            return Err(Error::TaskClaimConflict {
+                task_id: task_id.to_string(),
+                reason: format!("issue is already labeled '{claim}'"),
+            });
+        }
+        self.add_labels(task_id, &[IN_PROGRESS_LABEL])?;
+        // Read back: label edits can be undone by other automation.
+        if !Self::has_label(&self.issue(task_id)?, IN_PROGRESS_LABEL) {
+            return Err(Error::TaskSource(format!(
+                "in-progress label was not applied to issue #{task_id}"
+            )));
+        }
+        debug!(task_id, "marked in-progress");
+        Ok(())
+    }
+
+    fn mark_in_review(&self, task_id: &str) -> Result<()> {
+        let issue = self.issue(task_id)?;
+        if let Err(e) = self
+            .add_labels(task_id, &[IN_REVIEW_LABEL])
+            .and_then(|()| self.remove_label(&issue, task_id, IN_PROGRESS_LABEL))
+        {
+            warn!(task_id, error = %e, "failed to update labels for in-review");
+        }
+        debug!(task_id, "marked in-review");
+        Ok(())
+    }
+
+    fn get_task_details(&self, task_id: &str) -> Result<Task> {
+        Self::parse_issue(&self.issue(task_id)?)
+            .ok_or_else(|| Error::TaskSource(format!("Gitea issue #{task_id} not found")))
+    }
+
+    fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>> {
+        self.closed_issue_numbers(None)
+    }
+
+    /// Gitea's `since` filters by update time, which a close always bumps.
+    fn fetch_closed_task_ids_since(&self, since: u64) -> Result<HashSet<u64>> {
+        self.closed_issue_numbers(Some(since))
+    }
+
+    fn fetch_open_tasks_with_label(&self, label: &str) -> Result<Vec<Task>> {
+        Ok(self
+            .issues(&[("state", "open"), ("labels", label)])?
+            .iter()
+            .filter_map(Self::parse_issue)
+            .collect())
+    }
+
+    fn create_task(&self, title: &str, body: &str, labels: &[String]) -> Result<Task> {
+        let label_ids = labels
+            .iter()
+            .map(|l| self.label_id(l))
+            .collect::<Result<Vec<_>>>()?;
+        let created = self.call(
+            "POST",
+            "/issues",
+            Some(&json!({ "title": title, "body": body, "labels": label_ids })),
+        )?;
+        Self::parse_issue(&created)
+            .ok_or_else(|| Error::TaskSource("Gitea did not return the created issue".to_string()))
+    }
+
+    fn add_label(&self, task_id: &str, label: &str) -> Result<()> {
+        self.add_labels(task_id, &[label])
+    }
+
+    fn release_task(&self, task_id: &str) -> Result<()> {
+        let issue = self.issue(task_id)?;
+        self.remove_label(&issue, task_id, IN_PROGRESS_LABEL)?;
+        self.remove_label(&issue, task_id, IN_REVIEW_LABEL)?;
+        debug!(task_id, "released task");
+        Ok(())
+    }
+
+    fn add_comment(&self, task_id: &str, body: &str) -> Result<()> {
+        self.call(
+            "POST",
+            &format!("/issues/{task_id}/comments"),
+            Some(&json!({ "body": body })),
+        )?;
+        Ok(())
+    }
+
+    /// Gitea links a PR to the issue its `Resolves #N` body references.
+    fn attach_link(&self, _task_id: &str, _url: &str, _title: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::MockHttpClient;
+    use std::sync::Arc;
+
+    fn issue(number: u64, labels: &[(u64, &str)]) -> Value {
+        json!({
+            "number": number,
+            "title": format!("Issue {number}"),
+            "body": "Do the thing",
+            "html_url": format!("https://codeberg.org/o/r/issues/{number}"),
+            "labels": labels.iter().map(|(id, name)| json!({ "id": id, "name": name })).collect::<Vec<_>>(),
+            "milestone": { "title": "v2" },
+            "created_at": "2026-01-02T03:04:05Z",
+        })
+    }
+
+    #[test]
+    fn test_fetch_eligible_skips_claimed_and_held_issues() {
+        let client = Arc::new(
+            MockHttpClient::default()
+                .respond(
+                    "GET /issues",
+                    json!([
+                        issue(1, &[(1, "rlph"), (9, "p3")]),
+                        issue(2, &[(1, "rlph"), (2, "in-progress")]),
+                        issue(3, &[(1, "rlph")]),
+                    ]),
+                )
+                .respond("GET /issues/1/comments", json!([]))
+                .respond(
+                    "GET /issues/3/comments",
+                    json!([{ "id": 5, "body": "/rlph hold" }]),
+                ),
+        );
+        let source = GiteaSource::with_client("rlph", Box::new(client.clone()));
+        let tasks = source.fetch_eligible_tasks().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, "1");
+        assert_eq!(tasks[0].priority, Some(Priority(3)));
+        assert_eq!(tasks[0].milestone.as_deref(), Some("v2"));
+        let requests = client.requests.lock().unwrap();
+        assert!(
+            requests[0]
+                .1
+                .contains(&("labels".to_string(), "rlph".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_mark_in_progress_adds_label_and_reports_conflicts() {
+        let client = Arc::new(
+            MockHttpClient::default()
+                .respond("GET /issues/1", issue(1, &[(1, "rlph")]))
+                .respond("GET /labels", json!([{ "id": 2, "name": "in-progress" }]))
+                .respond(
+                    "GET /issues/1",
+                    issue(1, &[(1, "rlph"), (2, "in-progress")]),
+                ),
+        );
+        let source = GiteaSource::with_client("rlph", Box::new(client.clone()));
+        source.mark_in_progress("1").unwrap();
+        let requests = client.requests.lock().unwrap();
+        let (key, _, body) = &requests[2];
+        assert_eq!(key, "POST /issues/1/labels");
+        assert_eq!(body.as_ref().unwrap()["labels"], json!([2]));
+
+        let client = MockHttpClient::default()
+            .respond("GET /issues/1", issue(1, &[(1, "rlph"), (3, "in-review")]));
+        let source = GiteaSource::with_client("rlph", Box::new(client));
+        let err = source.mark_in_progress("1").unwrap_err();
+        assert!(matches!(err, Error::TaskClaimConflict { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_release_task_removes_workflow_labels() {
+        let client = Arc::new(MockHttpClient::default().respond(
+            "GET /issues/4",
+            issue(4, &[(1, "rlph"), (2, "in-progress"), (3, "in-review")]),
+        ));
+        let source = GiteaSource::with_client("rlph", Box::new(client.clone()));
+        source.release_task("4").unwrap();
+        let requests = client.requests.lock().unwrap();
+        let keys: Vec<&str> = requests.iter().skip(1).map(|r| r.0.as_str()).collect();
+        assert_eq!(
+            keys,
+            ["DELETE /issues/4/labels/2", "DELETE /issues/4/labels/3"]
+        );
+    }
+}
@@ -1,15 +1,18 @@
 use std::collections::HashSet;
 use std::process::Command;
+use std::sync::OnceLock;
 use std::thread;
 use std::time::Duration;
 
 use serde::Deserialize;
 use tracing::{debug, warn};
 
-use crate::config::Config;
+use crate::config::{ClaimStrategy, Config};
 use crate::error::{Error, Result};
+use crate::gh_quota::gh_output;
 
-use super::{Priority, Task, TaskSource};
+use super::github_project::ProjectBoard;
+use super::{Priority, Task, TaskSource, format_iso_timestamp, is_on_hold};
 
 const MAX_RETRIES: u32 = 3;
 const INITIAL_BACKOFF_MS: u64 = 500;
@@ -19,6 +22,37 @@ struct GhLabel {
     name: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct GhUser {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhMilestone {
+    title: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhReactionGroup {
+    content: String,
+    users: GhTotalCount,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhTotalCount {
+    #[serde(rename = "totalCount")]
+    total_count: u32,
+}
+
+/// 👍 count of an issue's `reactionGroups`.
+fn thumbs_up(groups: &[GhReactionGroup]) -> u32 {
+    groups
+        .iter()
+        .filter(|g| g.content == "THUMBS_UP")
+        .map(|g| g.users.total_count)
+        .sum()
+}
+
 #[derive(Debug, Deserialize)]
 struct GhIssue {
     number: u64,
@@ -26,8 +60,61 @@ struct GhIssue {
     body: Option<String>,
     labels: Vec<GhLabel>,
     url: String,
+    #[serde(rename = "createdAt", default)]
+    created_at: Option<String>,
+    #[serde(rename = "updatedAt", default)]
+    updated_at: Option<String>,
+    #[serde(default)]
+    milestone: Option<GhMilestone>,
+    #[serde(rename = "reactionGroups", default)]
+    reaction_groups: Vec<GhReactionGroup>,
+    #[serde(default)]
+    assignees: Vec<GhUser>,
+    #[serde(default)]
+    comments: Vec<GhComment>,
+    /// `OPEN` or `CLOSED`; only requested when checking a single issue.
+    #[serde(default)]
+    state: Option<String>,
+}
+
+/// Claim-relevant fields of an issue, read fresh before and after claiming it.
+#[derive(Debug, Deserialize)]
+struct GhIssueClaim {
+    labels: Vec<GhLabel>,
+    #[serde(default)]
+    assignees: Vec<GhUser>,
 }
 
+/// First assignee that is not `me` (GitHub logins are case-insensitive).
+fn other_assignee<'a>(assignees: &'a [GhUser], me: &str) -> Option<&'a str> {
+    assignees
+        .iter()
+        .map(|a| a.login.as_str())
+        .find(|login| !login.eq_ignore_ascii_case(me))
+}
+
+/// Issue numbers of a `gh issue list --json number` result.
+fn parse_issue_numbers(json: &str) -> Result<HashSet<u64>> {
+    #[derive(Deserialize)]
+    struct Num {
+        number: u64,
+    }
+
+    let nums: Vec<Num> = serde_json::from_str(json)
+        .map_err(|e| Error::TaskSource(format!("failed to parse closed issues: {e}")))?;
+    Ok(nums.into_iter().map(|n| n.number).collect())
+}
+
+#[derive(Debug, Deserialize)]
+struct GhComment {
+    body: String,
+    #[serde(rename = "authorAssociation", default)]
+    author_association: Option<String>,
+}
+
+/// Author associations allowed to put tasks on hold via comment commands.
+const TRUSTED_ASSOCIATIONS: &[&str] = &["OWNER", "MEMBER", "COLLABORATOR"];
+
 /// Abstraction over `gh` CLI execution for testability.
 pub trait GhClient {
     fn run(&self, args: &[&str]) -> Result<String>;
@@ -39,9 +126,7 @@ struct DefaultGhClient;
 impl GhClient for DefaultGhClient {
     fn run(&self, args: &[&str]) -> Result<String> {
         retry_with_backoff(|| {
-            let output = Command::new("gh")
-                .args(args)
-                .output()
+            let output = gh_output(Command::new("gh").args(args))
                 .map_err(|e| Error::TaskSource(format!("failed to run gh: {e}")))?;
 
             if output.status.success() {
@@ -57,6 +142,13 @@ impl GhClient for DefaultGhClient {
 
 pub struct GitHubSource {
     label: String,
+    hold_labels: Vec<String>,
+    claim_strategy: ClaimStrategy,
+    /// Login of the authenticated `gh` user, fetched on first use.
+    viewer_login: OnceLock<String>,
+    /// `[github.project]` board whose columns replace the label and workflow
+    /// labels.
+    project: Option<ProjectBoard>,
     client: Box<dyn GhClient>,
 }
 
@@ -64,6 +156,10 @@ impl GitHubSource {
     pub fn new(config: &Config) -> Self {
         Self {
             label: config.label.clone(),
+            hold_labels: config.hold_labels.clone(),
+            claim_strategy: config.claim_strategy,
+            viewer_login: OnceLock::new(),
+            project: config.github_project.clone().map(ProjectBoard::new),
             client: Box::new(DefaultGhClient),
         }
     }
@@ -72,6 +168,10 @@ impl GitHubSource {
     fn with_client(label: &str, client: Box<dyn GhClient>) -> Self {
         Self {
             label: label.to_string(),
+            hold_labels: crate::config::default_hold_labels(),
+            claim_strategy: ClaimStrategy::Label,
+            viewer_login: OnceLock::new(),
+            project: None,
             client,
         }
     }
@@ -86,6 +186,15 @@ impl GitHubSource {
             labels,
             url: gh.url,
             priority,
+            created_at: gh.created_at,
+            updated_at: gh.updated_at,
+            milestone: gh.milestone.map(|m| m.title),
+            reactions: Some(thumbs_up(&gh.reaction_groups)),
+            assignee: gh.assignees.first().map(|a| a.login.clone()),
+            // Project items need the `read:project` token scope, which `gh
+            // auth login` does not grant by default.
+            estimate: None,
+            project: None,
         }
     }
 
@@ -96,29 +205,182 @@ impl GitHubSource {
                 || l.name.eq_ignore_ascii_case("done")
         })
     }
+
+    /// Current labels and assignees of an issue, read fresh from GitHub.
+    fn issue_claim(&self, task_id: &str) -> Result<GhIssueClaim> {
+        let json = self
+            .client
+            .run(&["issue", "view", task_id, "--json", "labels,assignees"])?;
+        serde_json::from_str(&json)
+            .map_err(|e| Error::TaskSource(format!("failed to parse gh output: {e}")))
+    }
+
+    fn viewer_login(&self) -> Result<&str> {
+        if let Some(login) = self.viewer_login.get() {
+            return Ok(login);
+        }
+        let login = self
+            .client
+            .run(&["api", "user", "--jq", ".login"])?
+            .trim()
+            .to_string();
+        if login.is_empty() {
+            return Err(Error::TaskSource(
+                "could not determine the authenticated gh user".to_string(),
+            ));
+        }
+        Ok(self.viewer_login.get_or_init(|| login))
+    }
+
+    /// Compare-and-set claim through assignees: assign the authenticated user only
+    /// if nobody else is assigned, then confirm nobody else was assigned meanwhile.
+    /// On a lost race the assignment is withdrawn so neither worker keeps the issue.
+    fn claim_by_assignee(&self, task_id: &str, before: &GhIssueClaim) -> Result<()> {
+        let me = self.viewer_login()?;
+        if let Some(other) = other_assignee(&before.assignees, me) {
+            return Err(Error::TaskClaimConflict {
+                task_id: task_id.to_string(),
+                reason: format!("issue is assigned to '{other}'"),
+            });
+        }
+        self.client
+            .run(&["issue", "edit", task_id, "--add-assignee", me])?;
+        let after = self.issue_claim(task_id)?;
+        if let Some(other) = other_assignee(&after.assignees, me) {
+            if let Err(e) = self
+                .client
+                .run(&["issue", "edit", task_id, "--remove-assignee", me])
+            {
+                warn!(task_id, error = %e, "failed to withdraw assignee after lost claim");
+            }
+            return Err(Error::TaskClaimConflict {
+                task_id: task_id.to_string(),
+                reason: format!("issue was claimed concurrently by '{other}'"),
+            });
+        }
+        if !after
+            .assignees
+            .iter()
+            .any(|a| a.login.eq_ignore_ascii_case(me))
+        {
+            return Err(Error::TaskSource(format!(
+                "assignee '{me}' was not applied to issue #{task_id}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Claim a task on the project board: its card must be in the ready column
+    /// and is moved to the in-progress one.
+    fn claim_card(&self, board: &ProjectBoard, task_id: &str) -> Result<()> {
+        let columns = board.config();
+        let client = self.client.as_ref();
+        let column = board.column_of(client, task_id)?;
+        if !column
+            .as_deref()
+            .is_some_and(|c| c.eq_ignore_ascii_case(&columns.ready_column))
+        {
+            return Err(Error::TaskClaimConflict {
+                task_id: task_id.to_string(),
+                reason: format!(
+                    "card is in '{}', not '{}'",
+                    column.as_deref().unwrap_or("no column"),
+                    columns.ready_column
+                ),
+            });
+        }
+        if self.claim_strategy == ClaimStrategy::Assignee {
+            let before = self.issue_claim(task_id)?;
+            self.claim_by_assignee(task_id, &before)?;
+        }
+        board.move_to(client, task_id, &columns.in_progress_column)?;
+        // Read back: project workflows and people move cards too.
+        let column = board.column_of(client, task_id)?;
+        if !column
+            .as_deref()
+            .is_some_and(|c| c.eq_ignore_ascii_case(&columns.in_progress_column))
+        {
+            return Err(Error::TaskSource(format!(
+                "card for issue #{task_id} was not moved to '{}'",
+                columns.in_progress_column
+            )));
+        }
+        debug!(task_id, "marked in-progress");
+        Ok(())
+    }
+
+    /// The authenticated user, when claims are made through assignees.
+    fn claimant(&self) -> Result<Option<&str>> {
+        match self.claim_strategy {
+            ClaimStrategy::Assignee => self.viewer_login().map(Some),
+            ClaimStrategy::Label => Ok(None),
+        }
+    }
+
+    /// Not assigned to anyone other than `me`, and not on hold.
+    fn is_unclaimed(&self, issue: &GhIssue, me: Option<&str>) -> bool {
+        if let Some(other) = me.and_then(|me| other_assignee(&issue.assignees, me)) {
+            debug!(
+                issue = issue.number,
+                assignee = other,
+                "skipping task assigned elsewhere"
+            );
+            return false;
+        }
+        if self.is_held(issue) {
+            debug!(issue = issue.number, "skipping task on hold");
+            return false;
+        }
+        true
+    }
+
+    fn is_held(&self, issue: &GhIssue) -> bool {
+        let labels: Vec<String> = issue.labels.iter().map(|l| l.name.clone()).collect();
+        let trusted_comments = issue
+            .comments
+            .iter()
+            .filter(|c| {
+                c.author_association
+                    .as_deref()
+                    .is_some_and(|a| TRUSTED_ASSOCIATIONS.contains(&a))
+            })
+            .map(|c| c.body.as_str());
+        is_on_hold(&labels, trusted_comments, &self.hold_labels)
+    }
 }
 
 impl TaskSource for GitHubSource {
     fn fetch_eligible_tasks(&self) -> Result<Vec<Task>> {
-        let json = self.client.run(&[
-            "issue",
-            "list",
-            "--label",
-            &self.label,
-            "--state",
-            "open",
-            "--json",
-            "number,title,body,labels,url",
-            "--limit",
-            "100",
-        ])?;
-
-        let issues: Vec<GhIssue> = serde_json::from_str(&json)
-            .map_err(|e| Error::TaskSource(format!("failed to parse gh output: {e}")))?;
+        let issues: Vec<GhIssue> = match &self.project {
+            Some(board) => {
+                let items = board.issues_in(self.client.as_ref(), &board.config().ready_column)?;
+                serde_json::from_value(serde_json::Value::Array(items))
+                    .map_err(|e| Error::TaskSource(format!("failed to parse project items: {e}")))?
+            }
+            None => {
+                let json = self.client.run(&[
+                    "issue",
+                    "list",
+                    "--label",
+                    &self.label,
+                    "--state",
+                    "open",
+                    "--json",
+                    "number,title,body,labels,url,createdAt,updatedAt,milestone,reactionGroups,assignees,comments",
+                    "--limit",
+                    "100",
+                ])?;
+                serde_json::from_str(&json)
+                    .map_err(|e| Error::TaskSource(format!("failed to parse gh output: {e}")))?
+            }
+        };
 
+        let me = self.claimant()?;
         let tasks: Vec<Task> = issues
             .into_iter()
-            .filter(Self::is_eligible)
+            // On a board the column is the workflow state.
+            .filter(|issue| self.project.is_some() || Self::is_eligible(issue))
+            .filter(|issue| self.is_unclaimed(issue, me))
             .map(Self::parse_issue)
             .collect();
 
@@ -126,7 +388,49 @@ impl TaskSource for GitHubSource {
         Ok(tasks)
     }
 
+    /// Checks the one issue, so tasks past the listing limit are not missed.
+    /// On a board, eligibility is the card's column, so the column is listed.
+    fn is_task_eligible(&self, task_id: &str) -> Result<bool> {
+        if self.project.is_some() {
+            return Ok(self.fetch_eligible_tasks()?.iter().any(|t| t.id == task_id));
+        }
+        let json = self.client.run(&[
+            "issue",
+            "view",
+            task_id,
+            "--json",
+            "number,title,body,labels,url,state,assignees,comments",
+        ])?;
+        let issue: GhIssue = serde_json::from_str(&json)
+            .map_err(|e| Error::TaskSource(format!("failed to parse gh output: {e}")))?;
+        Ok(issue
+            .state
+            .as_deref()
+            .is_some_and(|s| s.eq_ignore_ascii_case("open"))
+            && issue
+                .labels
+                .iter()
+                .any(|l| l.name.eq_ignore_ascii_case(&self.label))
+            && Self::is_eligible(&issue)
+            && self.is_unclaimed(&issue, self.claimant()?))
+    }
+
     fn mark_in_progress(&self, task_id: &str) -> Result<()> {
+        if let Some(board) = &self.project {
+            return self.claim_card(board, task_id);
+        }
+        let before = self.issue_claim(task_id)?;
+        if let Some(claim) = before.labels.iter().find(|l| {
+            l.name.eq_ignore_ascii_case("in-progress") || l.name.eq_ignore_ascii_case("in-review")
+        }) {
+            return Err(Error::TaskClaimConflict {
+                task_id: task_id.to_string(),
+                reason: format!("issue is already labeled '{}'", claim.name),
+            });
+        }
+        if self.claim_strategy == ClaimStrategy::Assignee {
+            self.claim_by_assignee(task_id, &before)?;
+        }
         if let Err(e) = self.client.run(&["issue", "reopen", task_id]) {
             warn!(task_id, error = %e, "failed to reopen issue");
         }
@@ -141,11 +445,30 @@ impl TaskSource for GitHubSource {
         ]) {
             warn!(task_id, error = %e, "failed to update labels for in-progress");
         }
+        // Read back: label edits can fail silently or be undone by other automation.
+        if !self
+            .issue_claim(task_id)?
+            .labels
+            .iter()
+            .any(|l| l.name.eq_ignore_ascii_case("in-progress"))
+        {
+            return Err(Error::TaskSource(format!(
+                "in-progress label was not applied to issue #{task_id}"
+            )));
+        }
         debug!(task_id, "marked in-progress");
         Ok(())
     }
 
     fn mark_in_review(&self, task_id: &str) -> Result<()> {
+        if let Some(board) = &self.project {
+            let column = &board.config().in_review_column;
+            if let Err(e) = board.move_to(self.client.as_ref(), task_id, column) {
+                warn!(task_id, error = %e, "failed to move card to in-review");
+            }
+            debug!(task_id, "marked in-review");
+            return Ok(());
+        }
         if let Err(e) = self.client.run(&[
             "issue",
             "edit",
@@ -165,27 +488,29 @@ impl TaskSource for GitHubSource {
         let json = self.client.run(&[
             "issue", "list", "--state", "closed", "--json", "number", "--limit", "200",
         ])?;
-
-        #[derive(Deserialize)]
-        struct Num {
-            number: u64,
-        }
-
-        let nums: Vec<Num> = serde_json::from_str(&json)
-            .map_err(|e| Error::TaskSource(format!("failed to parse closed issues: {e}")))?;
-
-        let ids = nums.into_iter().map(|n| n.number).collect();
+        let ids = parse_issue_numbers(&json)?;
         debug!(?ids, "fetched closed task ids");
         Ok(ids)
     }
 
+    fn fetch_closed_task_ids_since(&self, since: u64) -> Result<HashSet<u64>> {
+        let search = format!("closed:>={}", format_iso_timestamp(since));
+        let json = self.client.run(&[
+            "issue", "list", "--state", "closed", "--search", &search, "--json", "number",
+            "--limit", "1000",
+        ])?;
+        let ids = parse_issue_numbers(&json)?;
+        debug!(?ids, since, "fetched recently closed task ids");
+        Ok(ids)
+    }
+
     fn get_task_details(&self, task_id: &str) -> Result<Task> {
         let json = self.client.run(&[
             "issue",
             "view",
             task_id,
             "--json",
-            "number,title,body,labels,url",
+            "number,title,body,labels,url,createdAt,updatedAt,milestone,reactionGroups,assignees",
         ])?;
 
         let issue: GhIssue = serde_json::from_str(&json)
@@ -193,6 +518,118 @@ impl TaskSource for GitHubSource {
 
         Ok(Self::parse_issue(issue))
     }
+
+    fn fetch_open_tasks_with_label(&self, label: &str) -> Result<Vec<Task>> {
+        let json = self.client.run(&[
+            "issue",
+            "list",
+            "--label",
+            label,
+            "--state",
+            "open",
+            "--json",
+            "number,title,body,labels,url,createdAt,updatedAt,milestone,reactionGroups,assignees",
+            "--limit",
+            "200",
+        ])?;
+
+        let issues: Vec<GhIssue> = serde_json::from_str(&json)
+            .map_err(|e| Error::TaskSource(format!("failed to parse gh output: {e}")))?;
+
+        Ok(issues.into_iter().map(Self::parse_issue).collect())
+    }
+
+    fn create_task(&self, title: &str, body: &str, labels: &[String]) -> Result<Task> {
+        // `gh issue create --label` fails on unknown labels; creating an
+        // existing label errors too, which is fine to ignore.
+        for label in labels {
+            if let Err(e) = self.client.run(&["label", "create", label]) {
+                debug!(label, error = %e, "label create skipped");
+            }
+        }
+
+        let mut args = vec!["issue", "create", "--title", title, "--body", body];
+        for label in labels {
+            args.push("--label");
+            args.push(label);
+        }
+        let url = self.client.run(&args)?;
+        let number = url
+            .trim()
+            .rsplit('/')
+            .next()
+            .and_then(|n| n.parse::<u64>().ok())
+            .ok_or_else(|| {
+                Error::TaskSource(format!("unexpected gh issue create output: {url}"))
+            })?;
+        debug!(number, "created issue");
+        self.get_task_details(&number.to_string())
+    }
+
+    fn add_label(&self, task_id: &str, label: &str) -> Result<()> {
+        if let Err(e) = self.client.run(&["label", "create", label]) {
+            debug!(label, error = %e, "label create skipped");
+        }
+        self.client
+            .run(&["issue", "edit", task_id, "--add-label", label])?;
+        debug!(task_id, label, "added label");
+        Ok(())
+    }
+
+    fn release_task(&self, task_id: &str) -> Result<()> {
+        if let Some(board) = &self.project {
+            board.move_to(self.client.as_ref(), task_id, &board.config().ready_column)?;
+            if self.claim_strategy == ClaimStrategy::Assignee {
+                self.client.run(&[
+                    "issue",
+                    "edit",
+                    task_id,
+                    "--remove-assignee",
+                    self.viewer_login()?,
+                ])?;
+            }
+            debug!(task_id, "released task");
+            return Ok(());
+        }
+        let mut args = vec![
+            "issue",
+            "edit",
+            task_id,
+            "--remove-label",
+            "in-progress",
+            "--remove-label",
+            "in-review",
+        ];
+        if self.claim_strategy == ClaimStrategy::Assignee {
+            args.extend(["--remove-assignee", self.viewer_login()?]);
+        }
+        self.client.run(&args)?;
+        debug!(task_id, "released task");
+        Ok(())
+    }
+
+    /// Moves the card to the done column; without a project board the issue
+    /// closes with its PR.
+    fn mark_done(&self, task_id: &str) -> Result<()> {
+        let Some(board) = &self.project else {
+            return Ok(());
+        };
+        board.move_to(self.client.as_ref(), task_id, &board.config().done_column)?;
+        debug!(task_id, "marked done");
+        Ok(())
+    }
+
+    fn add_comment(&self, task_id: &str, body: &str) -> Result<()> {
+        self.client
+            .run(&["issue", "comment", task_id, "--body", body])?;
+        debug!(task_id, "commented on issue");
+        Ok(())
+    }
+
+    /// GitHub links a PR to the issue its body references, so there is nothing to attach.
+    fn attach_link(&self, _task_id: &str, _url: &str, _title: &str) -> Result<()> {
+        Ok(())
+    }
 }
 
 fn retry_with_backoff<F, T>(f: F) -> Result<T>
@@ -227,21 +664,26 @@ where
 mod tests {
     use super::*;
     use std::cell::RefCell;
+    use std::rc::Rc;
 
     struct MockGhClient {
         responses: RefCell<Vec<Result<String>>>,
+        /// Arguments of each call, space-joined.
+        calls: Rc<RefCell<Vec<String>>>,
     }
 
     impl MockGhClient {
         fn new(responses: Vec<Result<String>>) -> Self {
             Self {
                 responses: RefCell::new(responses),
+                calls: Rc::default(),
             }
         }
     }
 
     impl GhClient for MockGhClient {
-        fn run(&self, _args: &[&str]) -> Result<String> {
+        fn run(&self, args: &[&str]) -> Result<String> {
+            self.calls.borrow_mut().push(args.join(" "));
             let mut responses = self.responses.borrow_mut();
             if responses.is_empty() {
                 Err(Error::TaskSource("no more mock responses".to_string()))
@@ -294,6 +736,35 @@ mod tests {
         assert_eq!(tasks[0].id, "1");
     }
 
+    #[test]
+    fn test_fetch_parses_timestamps() {
+        let mut dated = issue_json(1, "Dated", &["rlph"], "body");
+        dated["createdAt"] = "2026-01-02T03:04:05Z".into();
+        dated["updatedAt"] = "2026-02-03T04:05:06Z".into();
+        let json = mock_issues_json(&[dated, issue_json(2, "Undated", &["rlph"], "body")]);
+        let client = MockGhClient::new(vec![Ok(json)]);
+        let source = GitHubSource::with_client("rlph", Box::new(client));
+        let tasks = source.fetch_eligible_tasks().unwrap();
+        assert_eq!(tasks[0].created_at.as_deref(), Some("2026-01-02T03:04:05Z"));
+        assert_eq!(tasks[0].updated_at.as_deref(), Some("2026-02-03T04:05:06Z"));
+        assert_eq!(tasks[1].created_at, None);
+    }
+
+    #[test]
+    fn test_fetch_parses_milestone_and_assignee() {
+        let mut planned = issue_json(1, "Planned", &["rlph"], "body");
+        planned["milestone"] = serde_json::json!({"title": "v1.2", "number": 3});
+        planned["assignees"] = serde_json::json!([{"login": "octocat"}, {"login": "hubot"}]);
+        let json = mock_issues_json(&[planned, issue_json(2, "Unplanned", &["rlph"], "body")]);
+        let client = MockGhClient::new(vec![Ok(json)]);
+        let source = GitHubSource::with_client("rlph", Box::new(client));
+        let tasks = source.fetch_eligible_tasks().unwrap();
+        assert_eq!(tasks[0].milestone.as_deref(), Some("v1.2"));
+        assert_eq!(tasks[0].assignee.as_deref(), Some("octocat"));
+        assert_eq!(tasks[1].milestone, None);
+        assert_eq!(tasks[1].assignee, None);
+    }
+
     #[test]
     fn test_fetch_parses_priority() {
         let json = mock_issues_json(&[
@@ -309,13 +780,150 @@ mod tests {
         assert_eq!(tasks[2].priority, None);
     }
 
+    fn labels_json(labels: &[&str]) -> String {
+        claim_json(labels, &[])
+    }
+
+    fn claim_json(labels: &[&str], assignees: &[&str]) -> String {
+        let labels: Vec<_> = labels
+            .iter()
+            .map(|l| serde_json::json!({ "name": l }))
+            .collect();
+        let assignees: Vec<_> = assignees
+            .iter()
+            .map(|a| serde_json::json!({ "login": a }))
+            .collect();
+        serde_json::json!({ "labels": labels, "assignees": assignees }).to_string()
+    }
+
+    fn assignee_source(responses: Vec<Result<String>>) -> GitHubSource {
+        let mut source = GitHubSource::with_client("rlph", Box::new(MockGhClient::new(responses)));
+        source.claim_strategy = ClaimStrategy::Assignee;
+        source
+    }
+
+    #[test]
+    fn test_mark_in_progress_claims_assignee() {
+        let source = assignee_source(vec![
+            Ok(claim_json(&["rlph"], &[])),
+            Ok("rlph-bot\n".to_string()),
+            Ok(String::new()),
+            Ok(claim_json(&["rlph"], &["RLPH-bot"])),
+            Ok(String::new()),
+            Ok(String::new()),
+            Ok(claim_json(&["rlph", "in-progress"], &["rlph-bot"])),
+        ]);
+        source.mark_in_progress("42").unwrap();
+    }
+
+    #[test]
+    fn test_mark_in_progress_skips_issue_assigned_elsewhere() {
+        let source = assignee_source(vec![
+            Ok(claim_json(&["rlph"], &["alice"])),
+            Ok("rlph-bot".to_string()),
+        ]);
+        let err = source.mark_in_progress("42").unwrap_err();
+        assert!(
+            err.to_string().contains("assigned to 'alice'"),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn test_mark_in_progress_withdraws_on_lost_race() {
+        let source = assignee_source(vec![
+            Ok(claim_json(&["rlph"], &[])),
+            Ok("rlph-bot".to_string()),
+            Ok(String::new()),
+            Ok(claim_json(&["rlph"], &["worker-2", "rlph-bot"])),
+            Ok(String::new()),
+        ]);
+        let err = source.mark_in_progress("42").unwrap_err();
+        assert!(
+            matches!(&err, Error::TaskClaimConflict { reason, .. } if reason.contains("worker-2")),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn test_fetch_skips_tasks_assigned_elsewhere() {
+        let mut mine = issue_json(1, "Mine", &["rlph"], "b");
+        mine["assignees"] = serde_json::json!([{ "login": "rlph-bot" }]);
+        let mut theirs = issue_json(2, "Theirs", &["rlph"], "b");
+        theirs["assignees"] = serde_json::json!([{ "login": "alice" }]);
+        let free = issue_json(3, "Free", &["rlph"], "b");
+        let source = assignee_source(vec![
+            Ok(mock_issues_json(&[mine, theirs, free])),
+            Ok("rlph-bot".to_string()),
+        ]);
+        let ids: Vec<String> = source
+            .fetch_eligible_tasks()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec!["1", "3"]);
+    }
+
     #[test]
     fn test_mark_in_progress() {
-        let client = MockGhClient::new(vec![Ok(String::new()), Ok(String::new())]);
+        let client = MockGhClient::new(vec![
+            Ok(labels_json(&["rlph"])),
+            Ok(String::new()),
+            Ok(String::new()),
+            Ok(labels_json(&["rlph", "in-progress"])),
+        ]);
         let source = GitHubSource::with_client("rlph", Box::new(client));
         source.mark_in_progress("42").unwrap();
     }
 
+    #[test]
+    fn test_mark_in_progress_conflict_when_already_claimed() {
+        let client = MockGhClient::new(vec![Ok(labels_json(&["rlph", "In-Progress"]))]);
+        let source = GitHubSource::with_client("rlph", Box::new(client));
+        let err = source.mark_in_progress("42").unwrap_err();
+        assert!(
+            matches!(&err, Error::TaskClaimConflict { task_id, .. } if task_id == "42"),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn test_mark_in_progress_verifies_label_applied() {
+        let client = MockGhClient::new(vec![
+            Ok(labels_json(&["rlph"])),
+            Ok(String::new()),
+            Err(Error::TaskSource("gh failed: HTTP 502".to_string())),
+            Ok(labels_json(&["rlph"])),
+        ]);
+        let source = GitHubSource::with_client("rlph", Box::new(client));
+        let err = source.mark_in_progress("42").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("in-progress label was not applied")
+        );
+    }
+
+    #[test]
+    fn test_release_task() {
+        let client = MockGhClient::new(vec![Ok(String::new())]);
+        let source = GitHubSource::with_client("rlph", Box::new(client));
+        source.release_task("42").unwrap();
+    }
+
+    #[test]
+    fn test_add_comment() {
+        let client = MockGhClient::new(vec![Ok(
+            "https://github.com/test/repo/issues/42#issuecomment-1\n".to_string(),
+        )]);
+        let source = GitHubSource::with_client("rlph", Box::new(client));
+        source.add_comment("42", "PR opened").unwrap();
+
+        let client = MockGhClient::new(vec![Err(Error::TaskSource("HTTP 404".to_string()))]);
+        let source = GitHubSource::with_client("rlph", Box::new(client));
+        assert!(source.add_comment("42", "PR opened").is_err());
+    }
+
     #[test]
     fn test_get_task_details() {
         let json = serde_json::to_string(&issue_json(
@@ -334,6 +942,70 @@ mod tests {
         assert_eq!(task.priority, Some(Priority(3)));
     }
 
+    #[test]
+    fn test_is_task_eligible_views_the_issue() {
+        let with_state = |mut issue: serde_json::Value, state: &str| {
+            issue["state"] = serde_json::json!(state);
+            serde_json::to_string(&issue).unwrap()
+        };
+        let mut held = issue_json(4, "Held", &["rlph"], "body");
+        held["comments"] = serde_json::json!([
+            {"body": "/rlph hold", "authorAssociation": "OWNER"}
+        ]);
+        let client = MockGhClient::new(vec![
+            Ok(with_state(issue_json(1, "Open", &["rlph"], "body"), "OPEN")),
+            Ok(with_state(
+                issue_json(2, "Closed", &["rlph"], "body"),
+                "CLOSED",
+            )),
+            Ok(with_state(
+                issue_json(3, "Claimed", &["rlph", "in-progress"], "body"),
+                "OPEN",
+            )),
+            Ok(with_state(held, "OPEN")),
+        ]);
+        let calls = Rc::clone(&client.calls);
+        let source = GitHubSource::with_client("rlph", Box::new(client));
+        assert!(source.is_task_eligible("1").unwrap());
+        assert!(!source.is_task_eligible("2").unwrap());
+        assert!(!source.is_task_eligible("3").unwrap());
+        assert!(!source.is_task_eligible("4").unwrap());
+        // Each check views its issue rather than listing (and truncating) them all.
+        assert!(calls.borrow().iter().all(|c| c.starts_with("issue view ")));
+    }
+
+    #[test]
+    fn test_create_task_parses_url_and_loads_details() {
+        let details = serde_json::to_string(&issue_json(
+            51,
+            "Follow-up: tidy",
+            &["rlph-followup"],
+            "details",
+        ))
+        .unwrap();
+        let client = MockGhClient::new(vec![
+            Err(Error::TaskSource("label already exists".to_string())),
+            Ok("https://github.com/test/repo/issues/51\n".to_string()),
+            Ok(details),
+        ]);
+        let source = GitHubSource::with_client("rlph", Box::new(client));
+        let task = source
+            .create_task("Follow-up: tidy", "details", &["rlph-followup".to_string()])
+            .unwrap();
+        assert_eq!(task.id, "51");
+        assert_eq!(task.labels, vec!["rlph-followup".to_string()]);
+    }
+
+    #[test]
+    fn test_add_label_tolerates_existing_label() {
+        let client = MockGhClient::new(vec![
+            Err(Error::TaskSource("label already exists".to_string())),
+            Ok(String::new()),
+        ]);
+        let source = GitHubSource::with_client("rlph", Box::new(client));
+        source.add_label("42", "rlph-failed").unwrap();
+    }
+
     #[test]
     fn test_fetch_includes_issues_without_active_labels() {
         let json = mock_issues_json(&[
@@ -362,6 +1034,29 @@ mod tests {
         assert_eq!(tasks[0].id, "4");
     }
 
+    #[test]
+    fn test_fetch_excludes_hold_labels_and_hold_comments() {
+        let mut held_by_comment = issue_json(3, "Held by comment", &["rlph"], "body");
+        held_by_comment["comments"] = serde_json::json!([
+            {"body": "/rlph hold", "authorAssociation": "MEMBER"}
+        ]);
+        let mut untrusted_hold = issue_json(4, "Untrusted hold", &["rlph"], "body");
+        untrusted_hold["comments"] = serde_json::json!([
+            {"body": "/rlph hold", "authorAssociation": "NONE"}
+        ]);
+        let json = mock_issues_json(&[
+            issue_json(1, "Held", &["rlph", "rlph-hold"], "body"),
+            issue_json(2, "Skipped", &["rlph", "RLPH-SKIP"], "body"),
+            held_by_comment,
+            untrusted_hold,
+        ]);
+        let client = MockGhClient::new(vec![Ok(json)]);
+        let source = GitHubSource::with_client("rlph", Box::new(client));
+        let tasks = source.fetch_eligible_tasks().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, "4");
+    }
+
     #[test]
     fn test_fetch_error_propagated() {
         let client = MockGhClient::new(vec![Err(Error::TaskSource("gh not found".to_string()))]);
@@ -406,4 +1101,136 @@ mod tests {
             retry_with_backoff_ms(|| Err(Error::TaskSource("permanent".to_string())), 1, 3);
         assert!(result.is_err());
     }
+
+    fn project_source(responses: Vec<Result<String>>) -> (GitHubSource, Rc<RefCell<Vec<String>>>) {
+        let client = MockGhClient::new(responses);
+        let calls = Rc::clone(&client.calls);
+        let mut source = GitHubSource::with_client("rlph", Box::new(client));
+        source.project = Some(ProjectBoard::new(crate::config::GitHubProjectConfig {
+            owner: "acme".to_string(),
+            number: 7,
+            status_field: "Status".to_string(),
+            ready_column: "Ready".to_string(),
+            in_progress_column: "In Progress".to_string(),
+            in_review_column: "In Review".to_string(),
+            done_column: "Done".to_string(),
+        }));
+        (source, calls)
+    }
+
+    fn board_json() -> String {
+        let options: Vec<_> = [
+            ("o_ready", "Ready"),
+            ("o_prog", "In Progress"),
+            ("o_rev", "In Review"),
+            ("o_done", "Done"),
+        ]
+        .iter()
+        .map(|(id, name)| serde_json::json!({ "id": id, "name": name }))
+        .collect();
+        serde_json::json!({ "data": { "repositoryOwner": { "projectV2": {
+            "id": "PVT_1",
+            "field": { "id": "FLD_1", "options": options }
+        } } } })
+        .to_string()
+    }
+
+    fn board_item(number: u64, column: &str, repo: &str, state: &str) -> serde_json::Value {
+        serde_json::json!({
+            "fieldValueByName": { "name": column },
+            "content": {
+                "number": number,
+                "title": format!("Task {number}"),
+                "body": "b",
+                "url": format!("https://github.com/{repo}/issues/{number}"),
+                "state": state,
+                "repository": { "nameWithOwner": repo },
+                "labels": { "nodes": [] },
+                "assignees": { "nodes": [] },
+                "milestone": null,
+                "reactionGroups": [],
+                "comments": { "nodes": [] }
+            }
+        })
+    }
+
+    fn issue_item_json(column: &str) -> String {
+        serde_json::json!({ "data": { "repository": { "issue": { "projectItems": { "nodes": [
+            { "id": "PVTI_other", "project": { "id": "PVT_9" }, "fieldValueByName": { "name": "Ready" } },
+            { "id": "PVTI_1", "project": { "id": "PVT_1" }, "fieldValueByName": { "name": column } }
+        ] } } } } })
+        .to_string()
+    }
+
+    const MOVED_JSON: &str =
+        r#"{"data":{"updateProjectV2ItemFieldValue":{"projectV2Item":{"id":"PVTI_1"}}}}"#;
+
+    #[test]
+    fn test_project_fetch_takes_open_repo_issues_in_ready_column() {
+        let items = serde_json::json!({ "data": { "node": { "items": {
+            "nodes": [
+                board_item(1, "Ready", "test/repo", "OPEN"),
+                board_item(2, "In Progress", "test/repo", "OPEN"),
+                board_item(3, "ready", "other/repo", "OPEN"),
+                board_item(4, "Ready", "test/repo", "CLOSED"),
+                { "fieldValueByName": { "name": "Ready" }, "content": { "title": "draft" } },
+                board_item(5, "ready", "Test/Repo", "OPEN"),
+            ],
+            "pageInfo": { "hasNextPage": false, "endCursor": null }
+        } } } });
+        let (source, calls) = project_source(vec![
+            Ok(board_json()),
+            Ok("test/repo\n".to_string()),
+            Ok(items.to_string()),
+        ]);
+        let ids: Vec<String> = source
+            .fetch_eligible_tasks()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec!["1", "5"]);
+        assert!(calls.borrow()[0].contains("owner=acme"));
+        assert!(!calls.borrow()[2].contains("after="));
+    }
+
+    #[test]
+    fn test_project_claim_moves_card_and_done_moves_it_again() {
+        let (source, calls) = project_source(vec![
+            Ok(board_json()),
+            Ok("test/repo".to_string()),
+            Ok(issue_item_json("Ready")),
+            Ok(issue_item_json("Ready")),
+            Ok(MOVED_JSON.to_string()),
+            Ok(issue_item_json("In Progress")),
+            Ok(issue_item_json("In Review")),
+            Ok(MOVED_JSON.to_string()),
+        ]);
+        source.mark_in_progress("42").unwrap();
+        source.mark_done("42").unwrap();
+
+        let calls = calls.borrow();
+        let moves: Vec<&String> = calls
+            .iter()
+            .filter(|c| c.contains("updateProjectV2ItemFieldValue"))
+            .collect();
+        assert_eq!(moves.len(), 2);
+        assert!(moves[0].contains("item=PVTI_1") && moves[0].contains("option=o_prog"));
+        assert!(moves[1].contains("option=o_done"));
+        assert!(calls[2].contains("number=42"));
+    }
+
+    #[test]
+    fn test_project_claim_conflicts_when_card_left_ready_column() {
+        let (source, _) = project_source(vec![
+            Ok(board_json()),
+            Ok("test/repo".to_string()),
+            Ok(issue_item_json("In Progress")),
+        ]);
+        let err = source.mark_in_progress("42").unwrap_err();
+        assert!(
+            matches!(&err, Error::TaskClaimConflict { reason, .. } if reason.contains("'In Progress'")),
+            "got: {err}"
+        );
+    }
 }
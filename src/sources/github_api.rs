@@ -0,0 +1,578 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use serde_json::{Value, json};
+use tracing::{debug, warn};
+
+use crate::config::{ClaimStrategy, Config};
+use crate::error::{Error, Result};
+use crate::github_api::GitHubApi;
+
+use super::{Priority, Task, TaskSource, format_iso_timestamp, is_on_hold, parse_timestamp};
+
+const IN_PROGRESS_LABEL: &str = "in-progress";
+const IN_REVIEW_LABEL: &str = "in-review";
+const DONE_LABEL: &str = "done";
+/// Author associations allowed to put tasks on hold via comment commands.
+const TRUSTED_ASSOCIATIONS: &[&str] = &["OWNER", "MEMBER", "COLLABORATOR"];
+
+/// GitHub issues over the REST API, for when `gh` is unavailable.
+///
+/// Behaves like [`GitHubSource`](super::github::GitHubSource): the same
+/// labels, claim strategies, and hold rules, with the same listing limits.
+pub struct GitHubApiSource {
+    label: String,
+    hold_labels: Vec<String>,
+    claim_strategy: ClaimStrategy,
+    /// Login of the token's user, fetched on first use.
+    viewer_login: OnceLock<String>,
+    api: GitHubApi,
+}
+
+impl GitHubApiSource {
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self {
+            label: config.label.clone(),
+            hold_labels: config.hold_labels.clone(),
+            claim_strategy: config.claim_strategy,
+            viewer_login: OnceLock::new(),
+            api: GitHubApi::from_env()?,
+        })
+    }
+
+    #[cfg(test)]
+    fn with_api(label: &str, claim_strategy: ClaimStrategy, api: GitHubApi) -> Self {
+        Self {
+            label: label.to_string(),
+            hold_labels: crate::config::default_hold_labels(),
+            claim_strategy,
+            viewer_login: OnceLock::new(),
+            api,
+        }
+    }
+
+    fn call(&self, method: &str, path: &str, body: Option<&Value>) -> Result<Value> {
+        self.api
+            .repo_call(method, path, &[], body)
+            .map_err(Error::TaskSource)
+    }
+
+    /// Up to `limit` issues matching `query`. The issues endpoint also lists
+    /// pull requests, which are dropped.
+    fn issues(&self, query: &[(&str, &str)], limit: usize) -> Result<Vec<Value>> {
+        Ok(self
+            .api
+            .fetch_all("/issues", query, limit)
+            .map_err(Error::TaskSource)?
+            .into_iter()
+            .filter(|issue| issue.get("pull_request").is_none())
+            .collect())
+    }
+
+    fn issue(&self, task_id: &str) -> Result<Value> {
+        self.call("GET", &format!("/issues/{task_id}"), None)
+    }
+
+    fn logins(issue: &Value) -> Vec<&str> {
+        issue
+            .get("assignees")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|a| a.get("login").and_then(Value::as_str))
+            .collect()
+    }
+
+    /// First assignee that is not `me` (GitHub logins are case-insensitive).
+    fn other_assignee<'a>(issue: &'a Value, me: &str) -> Option<&'a str> {
+        Self::logins(issue)
+            .into_iter()
+            .find(|login| !login.eq_ignore_ascii_case(me))
+    }
+
+    fn issue_labels(issue: &Value) -> Vec<String> {
+        issue
+            .get("labels")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|l| l.get("name").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn has_label(issue: &Value, label: &str) -> bool {
+        Self::issue_labels(issue)
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(label))
+    }
+
+    /// Adding a label that does not exist yet creates it.
+    fn add_labels(&self, task_id: &str, labels: &[&str]) -> Result<()> {
+        self.call(
+            "POST",
+            &format!("/issues/{task_id}/labels"),
+            Some(&json!({ "labels": labels })),
+        )?;
+        Ok(())
+    }
+
+    /// Remove `label` from the issue if it carries it.
+    fn remove_label(&self, issue: &Value, task_id: &str, label: &str) -> Result<()> {
+        if Self::has_label(issue, label) {
+            self.call("DELETE", &format!("/issues/{task_id}/labels/{label}"), None)?;
+        }
+        Ok(())
+    }
+
+    fn viewer_login(&self) -> Result<&str> {
+        if let Some(login) = self.viewer_login.get() {
+            return Ok(login);
+        }
+        let user = self
+            .api
+            .call("GET", "/user", None)
+            .map_err(Error::TaskSource)?;
+        let login = user
+            .get("login")
+            .and_then(Value::as_str)
+            .filter(|l| !l.is_empty())
+            .ok_or_else(|| {
+                Error::TaskSource("could not determine the GitHub token's user".to_string())
+            })?;
+        Ok(self.viewer_login.get_or_init(|| login.to_string()))
+    }
+
+    /// Compare-and-set claim through assignees, as the `gh` source does.
+    fn claim_by_assignee(&self, task_id: &str, before: &Value) -> Result<()> {
+        let me = self.viewer_login()?;
+        if let Some(other) = Self::other_assignee(before, me) {
+            return Err(Error::TaskClaimConflict {
+                task_id: task_id.to_string(),
+                reason: format!("issue is assigned to '{other}'"),
+            });
+        }
+        let assignees = json!({ "assignees": [me] });
+        self.call(
+            "POST",
+            &format!("/issues/{task_id}/assignees"),
+            Some(&assignees),
+        )?;
+        let after = self.issue(task_id)?;
+        if let Some(other) = Self::other_assignee(&after, me) {
+            if let Err(e) = self.call(
+                "DELETE",
+                &format!("/issues/{task_id}/assignees"),
+                Some(&assignees),
+            ) {
+                warn!(task_id, error = %e, "failed to withdraw assignee after lost claim");
+            }
+            return Err(Error::TaskClaimConflict {
+                task_id: task_id.to_string(),
+                reason: format!("issue was claimed concurrently by '{other}'"),
+            });
+        }
+        if !Self::logins(&after)
+            .iter()
+            .any(|login| login.eq_ignore_ascii_case(me))
+        {
+            return Err(Error::TaskSource(format!(
+                "assignee '{me}' was not applied to issue #{task_id}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Hold labels, or a hold command in a comment from a collaborator.
+    fn is_held(&self, issue: &Value) -> Result<bool> {
+        let labels = Self::issue_labels(issue);
+        if is_on_hold(&labels, std::iter::empty(), &self.hold_labels) {
+            return Ok(true);
+        }
+        if issue.get("comments").and_then(Value::as_u64).unwrap_or(0) == 0 {
+            return Ok(false);
+        }
+        let number = issue
+            .get("number")
+            .and_then(Value::as_u64)
+            .unwrap_or_default();
+        let comments = self
+            .api
+            .fetch_all(&format!("/issues/{number}/comments"), &[], usize::MAX)
+            .map_err(Error::TaskSource)?;
+        Ok(is_on_hold(
+            &[],
+            comments
+                .iter()
+                .filter(|c| {
+                    c.get("author_association")
+                        .and_then(Value::as_str)
+                        .is_some_and(|a| TRUSTED_ASSOCIATIONS.contains(&a))
+                })
+                .filter_map(|c| c.get("body").and_then(Value::as_str)),
+            &self.hold_labels,
+        ))
+    }
+
+    /// The authenticated user, when claims are made through assignees.
+    fn claimant(&self) -> Result<Option<&str>> {
+        match self.claim_strategy {
+            ClaimStrategy::Assignee => self.viewer_login().map(Some),
+            ClaimStrategy::Label => Ok(None),
+        }
+    }
+
+    /// The task for an open, labeled issue, unless it is already claimed
+    /// (by workflow label, or by an assignee other than `me`) or on hold.
+    fn unclaimed_task(&self, issue: &Value, me: Option<&str>) -> Result<Option<Task>> {
+        if [IN_PROGRESS_LABEL, IN_REVIEW_LABEL, DONE_LABEL]
+            .iter()
+            .any(|l| Self::has_label(issue, l))
+        {
+            return Ok(None);
+        }
+        let Some(task) = Self::parse_issue(issue) else {
+            return Ok(None);
+        };
+        if let Some(other) = me.and_then(|me| Self::other_assignee(issue, me)) {
+            debug!(issue = %task.id, assignee = other, "skipping task assigned elsewhere");
+            return Ok(None);
+        }
+        if self.is_held(issue)? {
+            debug!(issue = %task.id, "skipping task on hold");
+            return Ok(None);
+        }
+        Ok(Some(task))
+    }
+
+    fn parse_issue(issue: &Value) -> Option<Task> {
+        let str_at = |pointer: &str| issue.pointer(pointer).and_then(Value::as_str);
+        let labels = Self::issue_labels(issue);
+        let priority = labels.iter().find_map(|l| Priority::from_label(l));
+        Some(Task {
+            id: issue.get("number").and_then(Value::as_u64)?.to_string(),
+            title: str_at("/title").unwrap_or_default().to_string(),
+            body: str_at("/body").unwrap_or_default().to_string(),
+            url: str_at("/html_url").unwrap_or_default().to_string(),
+            priority,
+            created_at: str_at("/created_at").map(str::to_string),
+            updated_at: str_at("/updated_at").map(str::to_string),
+            milestone: str_at("/milestone/title").map(str::to_string),
+            reactions: Some(
+                issue
+                    .pointer("/reactions/+1")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(0) as u32,
+            ),
+            assignee: Self::logins(issue).first().map(|l| l.to_string()),
+            estimate: None,
+            project: None,
+            labels,
+        })
+    }
+
+    fn closed_issue_numbers(&self, since: Option<u64>, limit: usize) -> Result<HashSet<u64>> {
+        let since_iso = since.map(format_iso_timestamp);
+        let mut query = vec![("state", "closed")];
+        if let Some(since) = &since_iso {
+            query.push(("since", since.as_str()));
+        }
+        Ok(self
+            .issues(&query, limit)?
+            .iter()
+            // `since` filters by update time; keep only issues closed since then.
+            .filter(|issue| {
+                since.is_none_or(|since| {
+                    issue
+                        .get("closed_at")
+                        .and_then(Value::as_str)
+                        .and_then(parse_timestamp)
+                        .is_none_or(|closed| closed >= since)
+                })
+            })
+            .filter_map(|issue| issue.get("number").and_then(Value::as_u64))
+            .collect())
+    }
+}
+
+impl TaskSource for GitHubApiSource {
+    fn fetch_eligible_tasks(&self) -> Result<Vec<Task>> {
+        let issues = self.issues(&[("state", "open"), ("labels", self.label.as_str())], 100)?;
+        let me = self.claimant()?;
+        let mut tasks = Vec::new();
+        for issue in &issues {
+            if let Some(task) = self.unclaimed_task(issue, me)? {
+                tasks.push(task);
+            }
+        }
+        debug!(count = tasks.len(), "fetched eligible tasks");
+        Ok(tasks)
+    }
+
+    /// Checks the one issue, so tasks past the listing limit are not missed.
+    fn is_task_eligible(&self, task_id: &str) -> Result<bool> {
+        let issue = self.issue(task_id)?;
+        if issue.get("pull_request").is_some()
+            || issue.get("state").and_then(Value::as_str) != Some("open")
+            || !Self::has_label(&issue, &self.label)
+        {
+            return Ok(false);
+        }
+        Ok(self.unclaimed_task(&issue, self.claimant()?)?.is_some())
+    }
+
+    fn mark_in_progress(&self, task_id: &str) -> Result<()> {
+        let before = self.issue(task_id)?;
+        if let Some(claim) = [IN_PROGRESS_LABEL, IN_REVIEW_LABEL]
+            .into_iter()
+            .find(|l| Self::has_label(&before, l))
+        {
+            return Err(Error::TaskClaimConflict {
+                task_id: task_id.to_string(),
+                reason: format!("issue is already labeled '{claim}'"),
+            });
+        }
+        if self.claim_strategy == ClaimStrategy::Assignee {
+            self.claim_by_assignee(task_id, &before)?;
+        }
+        if before.get("state").and_then(Value::as_str) == Some("closed")
+            && let Err(e) = self.call(
+                "PATCH",
+                &format!("/issues/{task_id}"),
+                Some(&json!({ "state": "open" })),
+            )
+        {
+            warn!(task_id, error = %e, "failed to reopen issue");
+        }
+        if let Err(e) = self.add_labels(task_id, &[IN_PROGRESS_LABEL]) {
+            warn!(task_id, error = %e, "failed to update labels for in-progress");
+        }
+        // Read back: label edits can be undone by other automation.
+        if !Self::has_label(&self.issue(task_id)?, IN_PROGRESS_LABEL) {
+            return Err(Error::TaskSource(format!(
+                "in-progress label was not applied to issue #{task_id}"
+            )));
+        }
+        debug!(task_id, "marked in-progress");
+        Ok(())
+    }
+
+    fn mark_in_review(&self, task_id: &str) -> Result<()> {
+        let issue = self.issue(task_id)?;
+        if let Err(e) = self
+            .add_labels(task_id, &[IN_REVIEW_LABEL])
+            .and_then(|()| self.remove_label(&issue, task_id, IN_PROGRESS_LABEL))
+        {
+            warn!(task_id, error = %e, "failed to update labels for in-review");
+        }
+        debug!(task_id, "marked in-review");
+        Ok(())
+    }
+
+    fn get_task_details(&self, task_id: &str) -> Result<Task> {
+        Self::parse_issue(&self.issue(task_id)?)
+            .ok_or_else(|| Error::TaskSource(format!("GitHub issue #{task_id} not found")))
+    }
+
+    fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>> {
+        let ids = self.closed_issue_numbers(None, 200)?;
+        debug!(?ids, "fetched closed task ids");
+        Ok(ids)
+    }
+
+    fn fetch_closed_task_ids_since(&self, since: u64) -> Result<HashSet<u64>> {
+        let ids = self.closed_issue_numbers(Some(since), 1000)?;
+        debug!(?ids, since, "fetched recently closed task ids");
+        Ok(ids)
+    }
+
+    fn fetch_open_tasks_with_label(&self, label: &str) -> Result<Vec<Task>> {
+        Ok(self
+            .issues(&[("state", "open"), ("labels", label)], 200)?
+            .iter()
+            .filter_map(Self::parse_issue)
+            .collect())
+    }
+
+    fn create_task(&self, title: &str, body: &str, labels: &[String]) -> Result<Task> {
+        let created = self.call(
+            "POST",
+            "/issues",
+            Some(&json!({ "title": title, "body": body, "labels": labels })),
+        )?;
+        let task = Self::parse_issue(&created).ok_or_else(|| {
+            Error::TaskSource("GitHub did not return the created issue".to_string())
+        })?;
+        debug!(number = %task.id, "created issue");
+        Ok(task)
+    }
+
+    fn add_label(&self, task_id: &str, label: &str) -> Result<()> {
+        self.add_labels(task_id, &[label])?;
+        debug!(task_id, label, "added label");
+        Ok(())
+    }
+
+    fn release_task(&self, task_id: &str) -> Result<()> {
+        let issue = self.issue(task_id)?;
+        self.remove_label(&issue, task_id, IN_PROGRESS_LABEL)?;
+        self.remove_label(&issue, task_id, IN_REVIEW_LABEL)?;
+        if self.claim_strategy == ClaimStrategy::Assignee {
+            self.call(
+                "DELETE",
+                &format!("/issues/{task_id}/assignees"),
+                Some(&json!({ "assignees": [self.viewer_login()?] })),
+            )?;
+        }
+        debug!(task_id, "released task");
+        Ok(())
+    }
+
+    fn add_comment(&self, task_id: &str, body: &str) -> Result<()> {
+        self.call(
+            "POST",
+            &format!("/issues/{task_id}/comments"),
+            Some(&json!({ "body": body })),
+        )?;
+        debug!(task_id, "commented on issue");
+        Ok(())
+    }
+
+    /// GitHub links a PR to the issue its body references, so there is nothing to attach.
+    fn attach_link(&self, _task_id: &str, _url: &str, _title: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::MockHttpClient;
+    use std::sync::Arc;
+
+    fn issue_json(number: u64, labels: &[&str], assignees: &[&str]) -> Value {
+        json!({
+            "number": number,
+            "title": format!("Issue {number}"),
+            "body": null,
+            "html_url": format!("https://github.com/o/r/issues/{number}"),
+            "state": "open",
+            "labels": labels.iter().map(|l| json!({ "name": l })).collect::<Vec<_>>(),
+            "assignees": assignees.iter().map(|a| json!({ "login": a })).collect::<Vec<_>>(),
+            "reactions": { "+1": 2 },
+            "comments": 0,
+        })
+    }
+
+    fn source(client: &Arc<MockHttpClient>, claim_strategy: ClaimStrategy) -> GitHubApiSource {
+        GitHubApiSource::with_api(
+            "rlph",
+            claim_strategy,
+            GitHubApi::with_client(Box::new(client.clone()), "o/r"),
+        )
+    }
+
+    #[test]
+    fn test_fetch_skips_claimed_held_and_pull_requests() {
+        let mut pr = issue_json(5, &[], &[]);
+        pr["pull_request"] = json!({ "url": "https://api.github.com/repos/o/r/pulls/5" });
+        let mut commented = issue_json(6, &[], &[]);
+        commented["comments"] = json!(2);
+        let client = Arc::new(
+            MockHttpClient::default()
+                .respond(
+                    "GET /repos/o/r/issues",
+                    json!([
+                        issue_json(1, &["rlph", "p1"], &[]),
+                        issue_json(2, &["in-progress"], &[]),
+                        issue_json(3, &["rlph-hold"], &[]),
+                        pr,
+                        commented,
+                    ]),
+                )
+                .respond(
+                    "GET /repos/o/r/issues/6/comments",
+                    json!([
+                        { "body": "/rlph hold", "author_association": "MEMBER" },
+                        { "body": "/rlph resume", "author_association": "NONE" },
+                    ]),
+                ),
+        );
+        let tasks = source(&client, ClaimStrategy::Label)
+            .fetch_eligible_tasks()
+            .unwrap();
+        let ids: Vec<&str> = tasks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, ["1"]);
+        assert_eq!(tasks[0].priority, Some(Priority(1)));
+        assert_eq!(tasks[0].reactions, Some(2));
+        assert_eq!(tasks[0].body, "");
+        let requests = client.requests.lock().unwrap();
+        assert!(
+            requests[0]
+                .1
+                .contains(&("labels".to_string(), "rlph".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_is_task_eligible_checks_the_issue_itself() {
+        let mut closed = issue_json(251, &["rlph"], &[]);
+        closed["state"] = json!("closed");
+        let client = Arc::new(
+            MockHttpClient::default()
+                .respond("GET /repos/o/r/issues/250", issue_json(250, &["rlph"], &[]))
+                .respond("GET /repos/o/r/issues/251", closed)
+                .respond(
+                    "GET /repos/o/r/issues/252",
+                    issue_json(252, &["rlph", "in-review"], &[]),
+                )
+                .respond("GET /repos/o/r/issues/253", issue_json(253, &[], &[])),
+        );
+        let source = source(&client, ClaimStrategy::Label);
+        assert!(source.is_task_eligible("250").unwrap());
+        assert!(!source.is_task_eligible("251").unwrap());
+        assert!(!source.is_task_eligible("252").unwrap());
+        assert!(!source.is_task_eligible("253").unwrap());
+        // No listing, so a task past its first page is judged the same way.
+        let requests = client.requests.lock().unwrap();
+        assert!(requests.iter().all(|r| r.0 != "GET /repos/o/r/issues"));
+    }
+
+    #[test]
+    fn test_mark_in_progress_claims_assignee_and_label() {
+        let claimed = issue_json(4, &["in-progress"], &["me"]);
+        let client = Arc::new(
+            MockHttpClient::default()
+                .respond("GET /repos/o/r/issues/4", issue_json(4, &[], &[]))
+                .respond("GET /user", json!({ "login": "me" }))
+                .respond("GET /repos/o/r/issues/4", issue_json(4, &[], &["me"]))
+                .respond("GET /repos/o/r/issues/4", claimed),
+        );
+        source(&client, ClaimStrategy::Assignee)
+            .mark_in_progress("4")
+            .unwrap();
+        let requests = client.requests.lock().unwrap();
+        let keys: Vec<&str> = requests.iter().map(|r| r.0.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "GET /repos/o/r/issues/4",
+                "GET /user",
+                "POST /repos/o/r/issues/4/assignees",
+                "GET /repos/o/r/issues/4",
+                "POST /repos/o/r/issues/4/labels",
+                "GET /repos/o/r/issues/4",
+            ]
+        );
+        assert_eq!(requests[4].2, Some(json!({ "labels": ["in-progress"] })));
+
+        let client = Arc::new(MockHttpClient::default().respond(
+            "GET /repos/o/r/issues/4",
+            issue_json(4, &["in-review"], &[]),
+        ));
+        let err = source(&client, ClaimStrategy::Label)
+            .mark_in_progress("4")
+            .unwrap_err();
+        assert!(matches!(err, Error::TaskClaimConflict { .. }), "{err}");
+    }
+}
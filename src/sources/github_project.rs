@@ -0,0 +1,434 @@
+//! A GitHub Projects (v2) board as the workflow of the GitHub source
+//! (`[github.project]`).
+//!
+//! Eligible tasks are the open issues of this repository whose card sits in
+//! the board's ready column, whatever their labels. Claiming, review, release
+//! and completion move the card between columns (options of the board's
+//! single-select status field) through the GraphQL API, called with `gh api
+//! graphql`.
+
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tracing::debug;
+
+use crate::config::GitHubProjectConfig;
+use crate::error::{Error, Result};
+
+use super::github::GhClient;
+
+/// Items requested per page of the board.
+const PAGE_SIZE: u32 = 100;
+/// Most pages of board items read per listing.
+const MAX_PAGES: usize = 20;
+
+const BOARD_QUERY: &str = r#"
+query($owner: String!, $number: Int!, $field: String!) {
+  repositoryOwner(login: $owner) {
+    ... on ProjectV2Owner {
+      projectV2(number: $number) {
+        id
+        field(name: $field) {
+          ... on ProjectV2SingleSelectField { id options { id name } }
+        }
+      }
+    }
+  }
+}"#;
+
+const ITEMS_QUERY: &str = r#"
+query($project: ID!, $field: String!, $first: Int!, $after: String) {
+  node(id: $project) {
+    ... on ProjectV2 {
+      items(first: $first, after: $after) {
+        nodes {
+          fieldValueByName(name: $field) {
+            ... on ProjectV2ItemFieldSingleSelectValue { name }
+          }
+          content {
+            ... on Issue {
+              number title body url state createdAt updatedAt
+              repository { nameWithOwner }
+              labels(first: 50) { nodes { name } }
+              assignees(first: 10) { nodes { login } }
+              milestone { title }
+              reactionGroups { content users { totalCount } }
+              comments(last: 50) { nodes { body authorAssociation } }
+            }
+          }
+        }
+        pageInfo { hasNextPage endCursor }
+      }
+    }
+  }
+}"#;
+
+const ISSUE_ITEM_QUERY: &str = r#"
+query($owner: String!, $name: String!, $number: Int!, $field: String!) {
+  repository(owner: $owner, name: $name) {
+    issue(number: $number) {
+      projectItems(first: 50) {
+        nodes {
+          id
+          project { id }
+          fieldValueByName(name: $field) {
+            ... on ProjectV2ItemFieldSingleSelectValue { name }
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+const MOVE_MUTATION: &str = r#"
+mutation($project: ID!, $item: ID!, $field: ID!, $option: String!) {
+  updateProjectV2ItemFieldValue(input: {
+    projectId: $project, itemId: $item, fieldId: $field,
+    value: { singleSelectOptionId: $option }
+  }) {
+    projectV2Item { id }
+  }
+}"#;
+
+#[derive(Debug, Deserialize)]
+struct StatusOption {
+    id: String,
+    name: String,
+}
+
+/// Node IDs of the board and its status field, resolved once.
+#[derive(Debug)]
+struct BoardIds {
+    project: String,
+    field: String,
+    options: Vec<StatusOption>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StatusValue {
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Nodes<T> {
+    nodes: Vec<T>,
+}
+
+/// An issue on the board, as returned by [`ITEMS_QUERY`].
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BoardIssue {
+    number: u64,
+    title: String,
+    body: Option<String>,
+    url: String,
+    state: String,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+    repository: RepositoryRef,
+    labels: Nodes<Value>,
+    assignees: Nodes<Value>,
+    milestone: Option<Value>,
+    #[serde(default)]
+    reaction_groups: Vec<Value>,
+    comments: Nodes<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepositoryRef {
+    name_with_owner: String,
+}
+
+impl BoardIssue {
+    /// The issue in the shape `gh issue list --json` prints it.
+    fn into_cli_json(self) -> Value {
+        json!({
+            "number": self.number,
+            "title": self.title,
+            "body": self.body,
+            "url": self.url,
+            "createdAt": self.created_at,
+            "updatedAt": self.updated_at,
+            "labels": self.labels.nodes,
+            "assignees": self.assignees.nodes,
+            "milestone": self.milestone,
+            "reactionGroups": self.reaction_groups,
+            "comments": self.comments.nodes,
+        })
+    }
+}
+
+pub(crate) struct ProjectBoard {
+    config: GitHubProjectConfig,
+    ids: OnceLock<BoardIds>,
+    /// `owner/name` of the current repository; the board may hold issues of
+    /// other repositories too.
+    repo: OnceLock<String>,
+}
+
+impl ProjectBoard {
+    pub(crate) fn new(config: GitHubProjectConfig) -> Self {
+        Self {
+            config,
+            ids: OnceLock::new(),
+            repo: OnceLock::new(),
+        }
+    }
+
+    pub(crate) fn config(&self) -> &GitHubProjectConfig {
+        &self.config
+    }
+
+    /// Run a GraphQL request through `gh api graphql` and return its `data`.
+    /// String variables are sent raw (`-f`), others as JSON (`-F`); `null`
+    /// variables are left out.
+    fn graphql(client: &dyn GhClient, query: &str, variables: &[(&str, Value)]) -> Result<Value> {
+        let mut args = vec![
+            "api".to_string(),
+            "graphql".to_string(),
+            "-f".to_string(),
+            format!("query={query}"),
+        ];
+        for (name, value) in variables {
+            match value {
+                Value::Null => continue,
+                Value::String(s) => args.extend(["-f".to_string(), format!("{name}={s}")]),
+                other => args.extend(["-F".to_string(), format!("{name}={other}")]),
+            }
+        }
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let out = client.run(&args)?;
+        let response: Value = serde_json::from_str(&out).map_err(|e| {
+            Error::TaskSource(format!("failed to parse gh api graphql output: {e}"))
+        })?;
+        if let Some(errors) = response.get("errors") {
+            return Err(Error::TaskSource(format!(
+                "GitHub GraphQL errors: {errors}"
+            )));
+        }
+        response
+            .get("data")
+            .cloned()
+            .ok_or_else(|| Error::TaskSource("GitHub GraphQL response missing data".to_string()))
+    }
+
+    fn ids(&self, client: &dyn GhClient) -> Result<&BoardIds> {
+        if let Some(ids) = self.ids.get() {
+            return Ok(ids);
+        }
+        let c = &self.config;
+        let data = Self::graphql(
+            client,
+            BOARD_QUERY,
+            &[
+                ("owner", json!(c.owner)),
+                ("number", json!(c.number)),
+                ("field", json!(c.status_field)),
+            ],
+        )?;
+        let project = data
+            .pointer("/repositoryOwner/projectV2")
+            .filter(|p| !p.is_null())
+            .ok_or_else(|| {
+                Error::TaskSource(format!(
+                    "GitHub project {} of '{}' not found",
+                    c.number, c.owner
+                ))
+            })?;
+        let field = project
+            .get("field")
+            .filter(|f| f.get("id").is_some())
+            .ok_or_else(|| {
+                Error::TaskSource(format!(
+                    "project {} has no single-select field '{}'",
+                    c.number, c.status_field
+                ))
+            })?;
+        let options: Vec<StatusOption> =
+            serde_json::from_value(field.get("options").cloned().unwrap_or_default())
+                .map_err(|e| Error::TaskSource(format!("failed to parse project field: {e}")))?;
+        for column in [
+            &c.ready_column,
+            &c.in_progress_column,
+            &c.in_review_column,
+            &c.done_column,
+        ] {
+            if !options.iter().any(|o| o.name.eq_ignore_ascii_case(column)) {
+                return Err(Error::TaskSource(format!(
+                    "project {} has no '{column}' option in '{}' (options: {})",
+                    c.number,
+                    c.status_field,
+                    options
+                        .iter()
+                        .map(|o| o.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )));
+            }
+        }
+        let ids = BoardIds {
+            project: project["id"].as_str().unwrap_or_default().to_string(),
+            field: field["id"].as_str().unwrap_or_default().to_string(),
+            options,
+        };
+        Ok(self.ids.get_or_init(|| ids))
+    }
+
+    fn repo(&self, client: &dyn GhClient) -> Result<&str> {
+        if let Some(repo) = self.repo.get() {
+            return Ok(repo);
+        }
+        let repo = client
+            .run(&[
+                "repo",
+                "view",
+                "--json",
+                "nameWithOwner",
+                "-q",
+                ".nameWithOwner",
+            ])?
+            .trim()
+            .to_string();
+        if !repo.contains('/') {
+            return Err(Error::TaskSource(format!(
+                "unexpected gh repo view output: {repo}"
+            )));
+        }
+        Ok(self.repo.get_or_init(|| repo))
+    }
+
+    /// Open issues of this repository in `column`, shaped like `gh issue list
+    /// --json` output.
+    pub(crate) fn issues_in(&self, client: &dyn GhClient, column: &str) -> Result<Vec<Value>> {
+        let project = self.ids(client)?.project.clone();
+        let repo = self.repo(client)?.to_string();
+        let mut issues = Vec::new();
+        let mut after = Value::Null;
+        for _ in 0..MAX_PAGES {
+            let data = Self::graphql(
+                client,
+                ITEMS_QUERY,
+                &[
+                    ("project", json!(project)),
+                    ("field", json!(self.config.status_field)),
+                    ("first", json!(PAGE_SIZE)),
+                    ("after", after),
+                ],
+            )?;
+            let items = data.pointer("/node/items").ok_or_else(|| {
+                Error::TaskSource("GitHub project items missing from response".to_string())
+            })?;
+            for item in items["nodes"].as_array().into_iter().flatten() {
+                let status = item
+                    .get("fieldValueByName")
+                    .and_then(|v| serde_json::from_value::<StatusValue>(v.clone()).ok())
+                    .and_then(|v| v.name);
+                if !status.is_some_and(|s| s.eq_ignore_ascii_case(column)) {
+                    continue;
+                }
+                // Draft issues and pull requests have no issue fields.
+                let Ok(issue) = serde_json::from_value::<BoardIssue>(item["content"].clone())
+                else {
+                    continue;
+                };
+                if issue.state == "OPEN"
+                    && issue.repository.name_with_owner.eq_ignore_ascii_case(&repo)
+                {
+                    issues.push(issue.into_cli_json());
+                }
+            }
+            let page = &items["pageInfo"];
+            match page["endCursor"].as_str() {
+                Some(cursor) if page["hasNextPage"].as_bool() == Some(true) => {
+                    after = json!(cursor);
+                }
+                _ => break,
+            }
+        }
+        debug!(column, count = issues.len(), "listed GitHub project column");
+        Ok(issues)
+    }
+
+    /// The board item of issue `task_id` and the column it is in, or `None`
+    /// when the issue is not on the board.
+    fn item(
+        &self,
+        client: &dyn GhClient,
+        task_id: &str,
+    ) -> Result<Option<(String, Option<String>)>> {
+        let number: u64 = task_id
+            .parse()
+            .map_err(|_| Error::TaskSource(format!("invalid issue number: {task_id}")))?;
+        let project = self.ids(client)?.project.clone();
+        let repo = self.repo(client)?;
+        let (owner, name) = repo.split_once('/').unwrap_or_default();
+        let data = Self::graphql(
+            client,
+            ISSUE_ITEM_QUERY,
+            &[
+                ("owner", json!(owner)),
+                ("name", json!(name)),
+                ("number", json!(number)),
+                ("field", json!(self.config.status_field)),
+            ],
+        )?;
+        let item = data
+            .pointer("/repository/issue/projectItems/nodes")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .find(|item| {
+                item.pointer("/project/id").and_then(Value::as_str) == Some(project.as_str())
+            });
+        Ok(item.map(|item| {
+            let column = item
+                .get("fieldValueByName")
+                .and_then(|v| serde_json::from_value::<StatusValue>(v.clone()).ok())
+                .and_then(|v| v.name);
+            (item["id"].as_str().unwrap_or_default().to_string(), column)
+        }))
+    }
+
+    /// Column issue `task_id` is in; `None` when it is on the board without a
+    /// status. Fails when the issue is not on the board.
+    pub(crate) fn column_of(&self, client: &dyn GhClient, task_id: &str) -> Result<Option<String>> {
+        match self.item(client, task_id)? {
+            Some((_, column)) => Ok(column),
+            None => Err(self.not_on_board(task_id)),
+        }
+    }
+
+    /// Move issue `task_id`'s card to `column`.
+    pub(crate) fn move_to(&self, client: &dyn GhClient, task_id: &str, column: &str) -> Result<()> {
+        let Some((item, _)) = self.item(client, task_id)? else {
+            return Err(self.not_on_board(task_id));
+        };
+        let ids = self.ids(client)?;
+        let option = ids
+            .options
+            .iter()
+            .find(|o| o.name.eq_ignore_ascii_case(column))
+            .ok_or_else(|| Error::TaskSource(format!("project has no '{column}' option")))?;
+        Self::graphql(
+            client,
+            MOVE_MUTATION,
+            &[
+                ("project", json!(ids.project)),
+                ("item", json!(item)),
+                ("field", json!(ids.field)),
+                ("option", json!(option.id)),
+            ],
+        )?;
+        debug!(task_id, column, "moved GitHub project card");
+        Ok(())
+    }
+
+    fn not_on_board(&self, task_id: &str) -> Error {
+        Error::TaskSource(format!(
+            "issue #{task_id} is not on GitHub project {} of '{}'",
+            self.config.number, self.config.owner
+        ))
+    }
+}
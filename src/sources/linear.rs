@@ -1,20 +1,28 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::{BufRead, Write};
+use std::sync::{Mutex, OnceLock};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use serde::Deserialize;
+use serde::de::DeserializeOwned;
 use tracing::{debug, info, warn};
 
-use crate::config::Config;
+use crate::config::{ClaimStrategy, Config};
 use crate::error::{Error, Result};
 
-use super::{Priority, Task, TaskSource};
+use super::{Priority, Task, TaskSource, format_iso_timestamp, is_on_hold};
 
 const LINEAR_API_URL: &str = "https://api.linear.app/graphql";
 const LINEAR_CLI_CREDENTIALS: &str = ".config/linear/credentials.toml";
 const MAX_RETRIES: u32 = 3;
 const INITIAL_BACKOFF_MS: u64 = 500;
+/// Longest wait for a rate-limit reset before the next request.
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+/// Issues requested per page of a paginated query.
+const PAGE_SIZE: u32 = 100;
+/// Most pages read from one paginated query.
+const MAX_PAGES: usize = 50;
 
 /// Resolve the Linear API key: env var first, then Linear CLI credentials file.
 fn resolve_api_key(api_key_env: &str) -> Result<String> {
@@ -53,6 +61,40 @@ pub trait LinearClient {
 
 struct DefaultLinearClient {
     api_key: String,
+    /// Earliest time the next request may go out, set when the last response
+    /// showed the rate-limit budget could not cover another request like it.
+    resume_at: Mutex<Option<Instant>>,
+}
+
+impl DefaultLinearClient {
+    fn new(api_key: String) -> Self {
+        Self {
+            api_key,
+            resume_at: Mutex::new(None),
+        }
+    }
+
+    /// Sleep until a pause set by an earlier response has passed.
+    fn wait_for_budget(&self) {
+        let resume_at = self
+            .resume_at
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(wait) = resume_at.and_then(|at| at.checked_duration_since(Instant::now())) {
+            info!(
+                wait_ms = wait.as_millis() as u64,
+                "Linear rate-limit budget exhausted, waiting for reset"
+            );
+            thread::sleep(wait);
+        }
+    }
+
+    fn pause_for(&self, wait: Option<Duration>) {
+        if let Some(wait) = wait {
+            *self.resume_at.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now() + wait);
+        }
+    }
 }
 
 impl LinearClient for DefaultLinearClient {
@@ -64,26 +106,52 @@ impl LinearClient for DefaultLinearClient {
 
         let mut backoff_ms = INITIAL_BACKOFF_MS;
         for attempt in 1..=MAX_RETRIES {
+            self.wait_for_budget();
             // Linear API uses raw API key, not "Bearer <key>"
-            match ureq::post(LINEAR_API_URL)
+            let limits = match ureq::post(LINEAR_API_URL)
                 .set("Authorization", &self.api_key)
                 .set("Content-Type", "application/json")
                 .send_json(&body)
             {
                 Ok(response) => {
+                    let limits = RateLimit::from_response(&response);
                     let json: serde_json::Value = response.into_json().map_err(|e| {
                         Error::TaskSource(format!("failed to parse Linear response: {e}"))
                     })?;
 
-                    if let Some(errors) = json.get("errors") {
-                        return Err(Error::TaskSource(format!("Linear API errors: {errors}")));
+                    match json.get("errors") {
+                        Some(errors) if is_rate_limit_error(&errors.to_string()) => limits,
+                        Some(errors) => {
+                            return Err(Error::TaskSource(format!("Linear API errors: {errors}")));
+                        }
+                        None => {
+                            self.pause_for(limits.pause(epoch_ms()));
+                            return json.get("data").cloned().ok_or_else(|| {
+                                Error::TaskSource("Linear API response missing data".to_string())
+                            });
+                        }
+                    }
+                }
+                Err(ureq::Error::Status(code, response)) => {
+                    let limits = RateLimit::from_response(&response);
+                    let body = response.into_string().unwrap_or_default();
+                    if code == 429 || is_rate_limit_error(&body) {
+                        limits
+                    } else if code >= 500 && attempt < MAX_RETRIES {
+                        warn!(
+                            attempt,
+                            code, backoff_ms, "retrying Linear API after server error"
+                        );
+                        thread::sleep(Duration::from_millis(backoff_ms));
+                        backoff_ms *= 2;
+                        continue;
+                    } else {
+                        return Err(Error::TaskSource(format!(
+                            "Linear API request failed: status {code}: {body}"
+                        )));
                     }
-
-                    return json.get("data").cloned().ok_or_else(|| {
-                        Error::TaskSource("Linear API response missing data".to_string())
-                    });
                 }
-                Err(ref e) if attempt < MAX_RETRIES && is_retryable(e) => {
+                Err(ref e) if attempt < MAX_RETRIES => {
                     warn!(
                         attempt,
                         error = %e,
@@ -92,28 +160,127 @@ impl LinearClient for DefaultLinearClient {
                     );
                     thread::sleep(Duration::from_millis(backoff_ms));
                     backoff_ms *= 2;
+                    continue;
                 }
                 Err(e) => {
                     return Err(Error::TaskSource(format!("Linear API request failed: {e}")));
                 }
+            };
+
+            if attempt == MAX_RETRIES {
+                break;
             }
+            let wait = limits
+                .reset_wait(epoch_ms())
+                .unwrap_or(Duration::from_millis(backoff_ms));
+            warn!(
+                attempt,
+                wait_ms = wait.as_millis() as u64,
+                "Linear API rate limited, waiting before retrying"
+            );
+            thread::sleep(wait);
+            backoff_ms *= 2;
         }
-        unreachable!()
+        Err(Error::TaskSource(
+            "Linear API rate limit exceeded; retries exhausted".to_string(),
+        ))
     }
 }
 
-/// Only retry rate-limits (429), server errors (5xx), and transport/network errors.
-fn is_retryable(err: &ureq::Error) -> bool {
-    match err {
-        ureq::Error::Status(code, _) => *code == 429 || *code >= 500,
-        ureq::Error::Transport(_) => true,
+/// Whether a Linear error payload reports a rate limit. Linear answers with
+/// HTTP 400 and a `RATELIMITED` error code rather than 429.
+fn is_rate_limit_error(errors: &str) -> bool {
+    errors.contains("RATELIMITED")
+}
+
+fn epoch_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Rate-limit headers of a Linear response. Linear budgets both the number of
+/// requests and the summed complexity of queries per hour.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct RateLimit {
+    requests_remaining: Option<u64>,
+    /// When the request budget refills, in milliseconds since the epoch.
+    requests_reset_ms: Option<u64>,
+    /// Complexity of the query just answered.
+    complexity: Option<u64>,
+    complexity_remaining: Option<u64>,
+    /// When the complexity budget refills, in milliseconds since the epoch.
+    complexity_reset_ms: Option<u64>,
+}
+
+impl RateLimit {
+    fn from_response(response: &ureq::Response) -> Self {
+        Self::from_headers(|name| response.header(name))
+    }
+
+    fn from_headers<'a>(header: impl Fn(&str) -> Option<&'a str>) -> Self {
+        let number = |name: &str| header(name).and_then(|v| v.trim().parse().ok());
+        Self {
+            requests_remaining: number("X-RateLimit-Requests-Remaining"),
+            requests_reset_ms: number("X-RateLimit-Requests-Reset"),
+            complexity: number("X-Complexity"),
+            complexity_remaining: number("X-RateLimit-Complexity-Remaining"),
+            complexity_reset_ms: number("X-RateLimit-Complexity-Reset"),
+        }
+    }
+
+    /// How long to hold the next request after a successful one: until the
+    /// budget resets when it cannot cover another request or another query as
+    /// complex as this one.
+    fn pause(&self, now_ms: u64) -> Option<Duration> {
+        let requests_spent = self.requests_remaining == Some(0);
+        let complexity_spent = matches!(
+            (self.complexity_remaining, self.complexity),
+            (Some(left), Some(cost)) if left < cost
+        );
+        let resets = [
+            self.requests_reset_ms.filter(|_| requests_spent),
+            self.complexity_reset_ms.filter(|_| complexity_spent),
+        ];
+        wait_until(resets.into_iter().flatten().max()?, now_ms)
+    }
+
+    /// How long to wait after a rate-limited response: until the later reset
+    /// time it reports.
+    fn reset_wait(&self, now_ms: u64) -> Option<Duration> {
+        let reset = [self.requests_reset_ms, self.complexity_reset_ms]
+            .into_iter()
+            .flatten()
+            .filter(|&ms| ms > now_ms)
+            .max()?;
+        wait_until(reset, now_ms)
     }
 }
 
+fn wait_until(reset_ms: u64, now_ms: u64) -> Option<Duration> {
+    let wait = Duration::from_millis(reset_ms.checked_sub(now_ms)?);
+    Some(wait.min(MAX_RATE_LIMIT_WAIT))
+}
+
 // ---------------------------------------------------------------------------
 // GraphQL response types
 // ---------------------------------------------------------------------------
 
+#[derive(Debug, Deserialize)]
+struct MilestoneNode {
+    name: String,
+}
+
+/// A reaction on an issue; only counted, as the issue's votes.
+#[derive(Debug, Deserialize)]
+struct ReactionNode {}
+
+#[derive(Debug, Deserialize)]
+struct ProjectNode {
+    name: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct IssueNode {
     #[allow(dead_code)]
@@ -123,9 +290,62 @@ struct IssueNode {
     description: Option<String>,
     url: String,
     priority: u8,
+    #[serde(rename = "createdAt", default)]
+    created_at: Option<String>,
+    #[serde(rename = "updatedAt", default)]
+    updated_at: Option<String>,
+    #[serde(rename = "projectMilestone", default)]
+    project_milestone: Option<MilestoneNode>,
+    #[serde(default)]
+    reactions: Option<Vec<ReactionNode>>,
+    #[serde(default)]
+    estimate: Option<f64>,
+    #[serde(default)]
+    project: Option<ProjectNode>,
     #[allow(dead_code)]
     state: StateNode,
     labels: LabelConnection,
+    #[serde(default)]
+    assignee: Option<UserNode>,
+    #[serde(default)]
+    comments: Option<CommentConnection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserNode {
+    id: String,
+    #[serde(rename = "displayName", default)]
+    display_name: Option<String>,
+}
+
+/// Claim-relevant fields of an issue, read fresh before and after claiming it.
+#[derive(Debug, Deserialize)]
+struct IssueClaimNode {
+    state: IssueStateName,
+    #[serde(default)]
+    assignee: Option<UserNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueStateName {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueClaimConnection {
+    nodes: Vec<IssueClaimNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommentConnection {
+    nodes: Vec<CommentNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommentNode {
+    body: String,
+    #[serde(rename = "createdAt", default)]
+    created_at: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -147,9 +367,12 @@ struct LabelNode {
     name: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct IssueConnection {
-    nodes: Vec<IssueNode>,
+#[derive(Debug, Default, Deserialize)]
+struct PageInfo {
+    #[serde(rename = "hasNextPage", default)]
+    has_next_page: bool,
+    #[serde(rename = "endCursor", default)]
+    end_cursor: Option<String>,
 }
 
 /// Lightweight types for queries that only need the issue UUID.
@@ -163,6 +386,12 @@ struct IssueIdConnection {
     nodes: Vec<IssueIdNode>,
 }
 
+#[derive(Debug, Deserialize)]
+struct IssueRefNode {
+    id: String,
+    number: u64,
+}
+
 #[derive(Debug, Deserialize)]
 struct WorkflowStateNode {
     id: String,
@@ -190,14 +419,52 @@ struct TeamConnection {
 
 pub struct LinearSource {
     label: String,
+    hold_labels: Vec<String>,
     team: String,
     project: Option<String>,
+    todo_state: String,
     in_progress_state: String,
     in_review_state: String,
     done_state: String,
+    claim_strategy: ClaimStrategy,
+    /// ID of the user owning the API key, fetched on first use.
+    viewer_id: OnceLock<String>,
+    /// The team's workflow states, fetched on first use.
+    workflow_states: OnceLock<Vec<WorkflowStateNode>>,
+    /// Issue number → UUID; an issue's UUID never changes.
+    issue_ids: Mutex<HashMap<String, String>>,
+    /// Mutations deferred until [`TaskSource::flush`], in the order queued.
+    pending: Mutex<Vec<PendingMutation>>,
     client: Box<dyn LinearClient>,
 }
 
+/// A mutation that can wait for the next flush: nothing reads its result
+/// before then.
+#[derive(Debug)]
+enum PendingMutation {
+    State { task_id: String, state: String },
+    Comment { task_id: String, body: String },
+}
+
+impl PendingMutation {
+    fn task_id(&self) -> &str {
+        match self {
+            PendingMutation::State { task_id, .. } | PendingMutation::Comment { task_id, .. } => {
+                task_id
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            PendingMutation::State { task_id, state } => {
+                format!("move issue #{task_id} to state '{state}'")
+            }
+            PendingMutation::Comment { task_id, .. } => format!("comment on issue #{task_id}"),
+        }
+    }
+}
+
 impl LinearSource {
     pub fn new(config: &Config) -> Result<Self> {
         let linear = config.linear.as_ref().ok_or_else(|| {
@@ -210,14 +477,19 @@ impl LinearSource {
 
         Ok(Self {
             label: config.label.clone(),
+            hold_labels: config.hold_labels.clone(),
             team: linear.team.clone(),
             project: linear.project.clone(),
+            todo_state: linear.todo_state.clone(),
             in_progress_state: linear.in_progress_state.clone(),
             in_review_state: linear.in_review_state.clone(),
             done_state: linear.done_state.clone(),
-            client: Box::new(DefaultLinearClient {
-                api_key: api_key.to_string(),
-            }),
+            claim_strategy: config.claim_strategy,
+            viewer_id: OnceLock::new(),
+            workflow_states: OnceLock::new(),
+            issue_ids: Mutex::new(HashMap::new()),
+            pending: Mutex::new(Vec::new()),
+            client: Box::new(DefaultLinearClient::new(api_key)),
         })
     }
 
@@ -225,11 +497,18 @@ impl LinearSource {
     fn with_client(label: &str, team: &str, client: Box<dyn LinearClient>) -> Self {
         Self {
             label: label.to_string(),
+            hold_labels: crate::config::default_hold_labels(),
             team: team.to_string(),
             project: None,
+            todo_state: "Todo".to_string(),
             in_progress_state: "In Progress".to_string(),
             in_review_state: "In Review".to_string(),
             done_state: "Done".to_string(),
+            claim_strategy: ClaimStrategy::Label,
+            viewer_id: OnceLock::new(),
+            workflow_states: OnceLock::new(),
+            issue_ids: Mutex::new(HashMap::new()),
+            pending: Mutex::new(Vec::new()),
             client,
         }
     }
@@ -258,29 +537,42 @@ impl LinearSource {
             labels,
             url: node.url.clone(),
             priority,
+            created_at: node.created_at.clone(),
+            updated_at: node.updated_at.clone(),
+            milestone: node.project_milestone.as_ref().map(|m| m.name.clone()),
+            reactions: node.reactions.as_ref().map(|r| r.len() as u32),
+            assignee: node.assignee.as_ref().and_then(|a| a.display_name.clone()),
+            estimate: node.estimate,
+            project: node.project.as_ref().map(|p| p.name.clone()),
         }
     }
 
-    /// Resolve a workflow state name → UUID for the configured team.
+    /// Resolve a workflow state name → UUID for the configured team. The
+    /// team's states are fetched once.
     fn find_state_id(&self, state_name: &str) -> Result<String> {
-        let query = r#"
-            query WorkflowStates($team: String!) {
-                workflowStates(filter: { team: { key: { eq: $team } } }) {
-                    nodes { id name }
-                }
+        let states = match self.workflow_states.get() {
+            Some(states) => states,
+            None => {
+                let query = r#"
+                    query WorkflowStates($team: String!) {
+                        workflowStates(filter: { team: { key: { eq: $team } } }) {
+                            nodes { id name }
+                        }
+                    }
+                "#;
+                let data = self
+                    .client
+                    .graphql(query, serde_json::json!({ "team": self.team }))?;
+                let states: WorkflowStateConnection =
+                    serde_json::from_value(data.get("workflowStates").cloned().unwrap_or_default())
+                        .map_err(|e| {
+                            Error::TaskSource(format!("failed to parse workflow states: {e}"))
+                        })?;
+                self.workflow_states.get_or_init(|| states.nodes)
             }
-        "#;
-
-        let data = self
-            .client
-            .graphql(query, serde_json::json!({ "team": self.team }))?;
-
-        let states: WorkflowStateConnection =
-            serde_json::from_value(data.get("workflowStates").cloned().unwrap_or_default())
-                .map_err(|e| Error::TaskSource(format!("failed to parse workflow states: {e}")))?;
+        };
 
         states
-            .nodes
             .iter()
             .find(|s| s.name.eq_ignore_ascii_case(state_name))
             .map(|s| s.id.clone())
@@ -294,6 +586,9 @@ impl LinearSource {
 
     /// Resolve an issue number → UUID within the configured team.
     fn find_issue_id(&self, issue_number: &str) -> Result<String> {
+        if let Some(id) = self.issue_ids.lock().unwrap().get(issue_number) {
+            return Ok(id.clone());
+        }
         let number: f64 = issue_number
             .parse::<u64>()
             .map_err(|_| Error::TaskSource(format!("invalid issue number: {issue_number}")))?
@@ -319,7 +614,109 @@ impl LinearSource {
             serde_json::from_value(data.get("issues").cloned().unwrap_or_default())
                 .map_err(|e| Error::TaskSource(format!("failed to parse issue lookup: {e}")))?;
 
-        issues.nodes.first().map(|i| i.id.clone()).ok_or_else(|| {
+        let id = issues.nodes.first().map(|i| i.id.clone()).ok_or_else(|| {
+            Error::TaskSource(format!(
+                "issue #{issue_number} not found in team '{}'",
+                self.team
+            ))
+        })?;
+        self.issue_ids
+            .lock()
+            .unwrap()
+            .insert(issue_number.to_string(), id.clone());
+        Ok(id)
+    }
+
+    /// Resolve several issue numbers → UUIDs, looking up the uncached ones
+    /// in a single query.
+    fn find_issue_ids(&self, issue_numbers: &[&str]) -> Result<HashMap<String, String>> {
+        let missing: Vec<f64> = {
+            let cache = self.issue_ids.lock().unwrap();
+            let mut missing = Vec::new();
+            for number in issue_numbers {
+                if cache.contains_key(*number) {
+                    continue;
+                }
+                let n = number
+                    .parse::<u64>()
+                    .map_err(|_| Error::TaskSource(format!("invalid issue number: {number}")))?
+                    as f64;
+                if !missing.contains(&n) {
+                    missing.push(n);
+                }
+            }
+            missing
+        };
+
+        if !missing.is_empty() {
+            let query = r#"
+                query IssueIds($team: String!, $numbers: [Float!]!, $first: Int!, $after: String) {
+                    issues(
+                        filter: { team: { key: { eq: $team } }, number: { in: $numbers } }
+                        first: $first
+                        after: $after
+                    ) {
+                        nodes { id number }
+                        pageInfo { hasNextPage endCursor }
+                    }
+                }
+            "#;
+            let nodes: Vec<IssueRefNode> = self.fetch_all_issues(
+                query,
+                serde_json::json!({ "team": self.team, "numbers": missing }),
+                "issue lookup",
+            )?;
+            let mut cache = self.issue_ids.lock().unwrap();
+            for node in nodes {
+                cache.insert(node.number.to_string(), node.id);
+            }
+        }
+
+        let cache = self.issue_ids.lock().unwrap();
+        issue_numbers
+            .iter()
+            .map(|number| {
+                cache
+                    .get(*number)
+                    .map(|id| (number.to_string(), id.clone()))
+                    .ok_or_else(|| {
+                        Error::TaskSource(format!(
+                            "issue #{number} not found in team '{}'",
+                            self.team
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    /// Current workflow state and assignee of an issue.
+    fn issue_claim(&self, issue_number: &str) -> Result<IssueClaimNode> {
+        let number: f64 = issue_number
+            .parse::<u64>()
+            .map_err(|_| Error::TaskSource(format!("invalid issue number: {issue_number}")))?
+            as f64;
+
+        let query = r#"
+            query IssueClaim($team: String!, $number: Float!) {
+                issues(
+                    filter: { team: { key: { eq: $team } }, number: { eq: $number } }
+                    first: 1
+                ) {
+                    nodes { state { name } assignee { id } }
+                }
+            }
+        "#;
+
+        let data = self.client.graphql(
+            query,
+            serde_json::json!({ "team": self.team, "number": number }),
+        )?;
+
+        let issues: IssueClaimConnection =
+            serde_json::from_value(data.get("issues").cloned().unwrap_or_default())
+                .map_err(|e| Error::TaskSource(format!("failed to parse issue lookup: {e}")))?;
+
+        issues.nodes.into_iter().next().ok_or_else(|| {
             Error::TaskSource(format!(
                 "issue #{issue_number} not found in team '{}'",
                 self.team
@@ -327,14 +724,87 @@ impl LinearSource {
         })
     }
 
-    /// Update an issue's workflow state by name.
-    fn update_issue_state(&self, task_id: &str, state_name: &str) -> Result<()> {
+    fn viewer_id(&self) -> Result<&str> {
+        if let Some(id) = self.viewer_id.get() {
+            return Ok(id);
+        }
+        let data = self
+            .client
+            .graphql("query Viewer { viewer { id } }", serde_json::json!({}))?;
+        let id = data
+            .pointer("/viewer/id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| Error::TaskSource("failed to read Linear viewer id".to_string()))?;
+        Ok(self.viewer_id.get_or_init(|| id.to_string()))
+    }
+
+    /// Set (or with `None`, clear) an issue's assignee.
+    fn set_assignee(&self, task_id: &str, assignee_id: Option<&str>) -> Result<()> {
+        let issue_id = self.find_issue_id(task_id)?;
+        let query = r#"
+            mutation SetAssignee($issueId: String!, $assigneeId: String) {
+                issueUpdate(id: $issueId, input: { assigneeId: $assigneeId }) {
+                    success
+                }
+            }
+        "#;
+        let data = self.client.graphql(
+            query,
+            serde_json::json!({ "issueId": issue_id, "assigneeId": assignee_id }),
+        )?;
+        let success = data
+            .pointer("/issueUpdate/success")
+            .and_then(|s| s.as_bool())
+            .unwrap_or(false);
+        if !success {
+            return Err(Error::TaskSource(format!(
+                "failed to update assignee of issue #{task_id}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Claim through the assignee: assign the API key's user only if the issue is
+    /// unassigned, then read it back to confirm no other worker overwrote it.
+    fn claim_by_assignee(&self, task_id: &str, before: &IssueClaimNode) -> Result<()> {
+        let me = self.viewer_id()?;
+        if let Some(other) = before.assignee.as_ref().filter(|a| a.id != me) {
+            return Err(Error::TaskClaimConflict {
+                task_id: task_id.to_string(),
+                reason: format!("issue is assigned to user {}", other.id),
+            });
+        }
+        self.set_assignee(task_id, Some(me))?;
+        match self.issue_claim(task_id)?.assignee {
+            Some(a) if a.id == me => Ok(()),
+            Some(other) => Err(Error::TaskClaimConflict {
+                task_id: task_id.to_string(),
+                reason: format!("issue was claimed concurrently by user {}", other.id),
+            }),
+            None => Err(Error::TaskSource(format!(
+                "assignee was not applied to issue #{task_id}"
+            ))),
+        }
+    }
+
+    /// Update an issue's workflow state by name, clearing its assignee too
+    /// when `clear_assignee` is set.
+    fn update_issue_state(
+        &self,
+        task_id: &str,
+        state_name: &str,
+        clear_assignee: bool,
+    ) -> Result<()> {
         let issue_id = self.find_issue_id(task_id)?;
         let state_id = self.find_state_id(state_name)?;
 
+        let mut input = serde_json::json!({ "stateId": state_id });
+        if clear_assignee {
+            input["assigneeId"] = serde_json::Value::Null;
+        }
         let query = r#"
-            mutation UpdateIssueState($issueId: String!, $stateId: String!) {
-                issueUpdate(id: $issueId, input: { stateId: $stateId }) {
+            mutation UpdateIssueState($issueId: String!, $input: IssueUpdateInput!) {
+                issueUpdate(id: $issueId, input: $input) {
                     success
                 }
             }
@@ -342,7 +812,7 @@ impl LinearSource {
 
         let data = self.client.graphql(
             query,
-            serde_json::json!({ "issueId": issue_id, "stateId": state_id }),
+            serde_json::json!({ "issueId": issue_id, "input": input }),
         )?;
 
         let success = data
@@ -360,6 +830,181 @@ impl LinearSource {
         Ok(())
     }
 
+    /// Queue a mutation for the next flush. The issue number is checked now so
+    /// a bad id fails at the call site.
+    fn enqueue(&self, mutation: PendingMutation) -> Result<()> {
+        let task_id = mutation.task_id();
+        task_id
+            .parse::<u64>()
+            .map_err(|_| Error::TaskSource(format!("invalid issue number: {task_id}")))?;
+        self.pending.lock().unwrap().push(mutation);
+        Ok(())
+    }
+
+    /// Send every queued mutation as one GraphQL document of aliased fields.
+    /// Linear runs the fields of a mutation in order, so queued changes to the
+    /// same issue land as they were made. The queue is emptied even when the
+    /// request fails.
+    fn send_pending(&self) -> Result<()> {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        if pending.is_empty() {
+            return Ok(());
+        }
+        let numbers: Vec<&str> = pending.iter().map(PendingMutation::task_id).collect();
+        let issue_ids = self.find_issue_ids(&numbers)?;
+
+        let mut params = Vec::new();
+        let mut fields = Vec::new();
+        let mut variables = serde_json::Map::new();
+        for (i, mutation) in pending.iter().enumerate() {
+            params.push(format!("$issue{i}: String!"));
+            variables.insert(
+                format!("issue{i}"),
+                issue_ids[mutation.task_id()].clone().into(),
+            );
+            match mutation {
+                PendingMutation::State { state, .. } => {
+                    params.push(format!("$state{i}: String!"));
+                    variables.insert(format!("state{i}"), self.find_state_id(state)?.into());
+                    fields.push(format!(
+                        "m{i}: issueUpdate(id: $issue{i}, input: {{ stateId: $state{i} }}) {{ success }}"
+                    ));
+                }
+                PendingMutation::Comment { body, .. } => {
+                    params.push(format!("$body{i}: String!"));
+                    variables.insert(format!("body{i}"), body.clone().into());
+                    fields.push(format!(
+                        "m{i}: commentCreate(input: {{ issueId: $issue{i}, body: $body{i} }}) {{ success }}"
+                    ));
+                }
+            }
+        }
+        let query = format!(
+            "mutation Batch({}) {{\n{}\n}}",
+            params.join(", "),
+            fields.join("\n")
+        );
+
+        let data = self
+            .client
+            .graphql(&query, serde_json::Value::Object(variables))?;
+
+        let failed: Vec<String> = pending
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                !data
+                    .pointer(&format!("/m{i}/success"))
+                    .and_then(|s| s.as_bool())
+                    .unwrap_or(false)
+            })
+            .map(|(_, mutation)| mutation.describe())
+            .collect();
+        if !failed.is_empty() {
+            return Err(Error::TaskSource(format!(
+                "failed to {}",
+                failed.join(", ")
+            )));
+        }
+
+        debug!(count = pending.len(), "flushed queued Linear mutations");
+        Ok(())
+    }
+
+    /// Send queued mutations before an immediate one on `task_id`, which must
+    /// land after them. Their failure does not block it.
+    fn flush_before(&self, task_id: &str) {
+        if let Err(e) = self.send_pending() {
+            warn!(task_id, error = %e, "failed to send queued Linear mutations");
+        }
+    }
+
+    fn is_held(&self, node: &IssueNode) -> bool {
+        let labels: Vec<String> = node.labels.nodes.iter().map(|l| l.name.clone()).collect();
+        // Linear does not guarantee comment order; hold commands are
+        // evaluated oldest-first.
+        let mut comments: Vec<&CommentNode> =
+            node.comments.iter().flat_map(|c| c.nodes.iter()).collect();
+        comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        is_on_hold(
+            &labels,
+            comments.iter().map(|c| c.body.as_str()),
+            &self.hold_labels,
+        )
+    }
+
+    /// Run an `issues` query page by page and collect the nodes of every page.
+    /// The query takes `$first: Int!` and `$after: String` and selects
+    /// `pageInfo { hasNextPage endCursor }` next to `nodes`.
+    fn fetch_all_issues<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        mut variables: serde_json::Value,
+        what: &str,
+    ) -> Result<Vec<T>> {
+        #[derive(Deserialize)]
+        struct Page<T> {
+            nodes: Vec<T>,
+            #[serde(rename = "pageInfo", default)]
+            page_info: PageInfo,
+        }
+
+        variables["first"] = PAGE_SIZE.into();
+        let mut nodes = Vec::new();
+        for _ in 0..MAX_PAGES {
+            let data = self.client.graphql(query, variables.clone())?;
+            let page: Page<T> =
+                serde_json::from_value(data.get("issues").cloned().unwrap_or_default())
+                    .map_err(|e| Error::TaskSource(format!("failed to parse {what}: {e}")))?;
+            nodes.extend(page.nodes);
+            match page.page_info {
+                PageInfo {
+                    has_next_page: true,
+                    end_cursor: Some(cursor),
+                } => variables["after"] = cursor.into(),
+                _ => return Ok(nodes),
+            }
+        }
+        warn!(
+            what,
+            pages = MAX_PAGES,
+            fetched = nodes.len(),
+            "stopped paging Linear issues at the page limit"
+        );
+        Ok(nodes)
+    }
+
+    /// Numbers of completed or canceled issues, optionally only those updated
+    /// at or after `since` (unix seconds).
+    fn closed_issue_numbers(&self, since: Option<u64>) -> Result<HashSet<u64>> {
+        let mut filter = self.build_issue_filter();
+        filter["state"] = serde_json::json!({ "type": { "in": ["completed", "canceled"] } });
+        if let Some(since) = since {
+            filter["updatedAt"] = serde_json::json!({ "gte": format_iso_timestamp(since) });
+        }
+
+        let query = r#"
+            query ClosedIssues($filter: IssueFilter!, $first: Int!, $after: String) {
+                issues(filter: $filter, first: $first, after: $after) {
+                    nodes { number }
+                    pageInfo { hasNextPage endCursor }
+                }
+            }
+        "#;
+
+        #[derive(Deserialize)]
+        struct NumberNode {
+            number: u64,
+        }
+
+        let nodes: Vec<NumberNode> = self.fetch_all_issues(
+            query,
+            serde_json::json!({ "filter": filter }),
+            "closed issues",
+        )?;
+        Ok(nodes.into_iter().map(|n| n.number).collect())
+    }
+
     fn build_issue_filter(&self) -> serde_json::Value {
         let mut filter = serde_json::json!({
             "team": { "key": { "eq": self.team } },
@@ -383,115 +1028,330 @@ impl TaskSource for LinearSource {
         });
 
         let query = r#"
-            query Issues($filter: IssueFilter!) {
-                issues(filter: $filter, first: 100) {
+            query Issues($filter: IssueFilter!, $first: Int!, $after: String) {
+                issues(filter: $filter, first: $first, after: $after) {
                     nodes {
-                        id identifier number title description url priority
+                        id identifier number title description url priority createdAt updatedAt projectMilestone { name } reactions { id }
+                        estimate project { name } assignee { id displayName }
                         state { name type }
                         labels { nodes { name } }
+                        comments { nodes { body createdAt } }
                     }
+                    pageInfo { hasNextPage endCursor }
                 }
             }
         "#;
 
-        let data = self
-            .client
-            .graphql(query, serde_json::json!({ "filter": filter }))?;
-
-        let issues: IssueConnection =
-            serde_json::from_value(data.get("issues").cloned().unwrap_or_default())
-                .map_err(|e| Error::TaskSource(format!("failed to parse Linear issues: {e}")))?;
+        let nodes: Vec<IssueNode> = self.fetch_all_issues(
+            query,
+            serde_json::json!({ "filter": filter }),
+            "Linear issues",
+        )?;
 
-        let tasks: Vec<Task> = issues.nodes.iter().map(Self::parse_issue).collect();
+        let me = match self.claim_strategy {
+            ClaimStrategy::Assignee => Some(self.viewer_id()?),
+            ClaimStrategy::Label => None,
+        };
+        let tasks: Vec<Task> = nodes
+            .iter()
+            .filter(|node| {
+                let held = self.is_held(node);
+                if held {
+                    debug!(issue = node.number, "skipping task on hold");
+                }
+                !held
+            })
+            .filter(|node| {
+                let assigned_elsewhere =
+                    me.is_some_and(|me| node.assignee.as_ref().is_some_and(|a| a.id != me));
+                if assigned_elsewhere {
+                    debug!(issue = node.number, "skipping task assigned elsewhere");
+                }
+                !assigned_elsewhere
+            })
+            .map(Self::parse_issue)
+            .collect();
 
         debug!(count = tasks.len(), "fetched eligible Linear tasks");
         Ok(tasks)
     }
 
     fn mark_in_progress(&self, task_id: &str) -> Result<()> {
-        self.update_issue_state(task_id, &self.in_progress_state)?;
+        self.flush_before(task_id);
+        let before = self.issue_claim(task_id)?;
+        let current = &before.state.name;
+        if [
+            &self.in_progress_state,
+            &self.in_review_state,
+            &self.done_state,
+        ]
+        .iter()
+        .any(|s| s.eq_ignore_ascii_case(current))
+        {
+            return Err(Error::TaskClaimConflict {
+                task_id: task_id.to_string(),
+                reason: format!("issue is already in state '{current}'"),
+            });
+        }
+        if self.claim_strategy == ClaimStrategy::Assignee {
+            self.claim_by_assignee(task_id, &before)?;
+        }
+        self.update_issue_state(task_id, &self.in_progress_state, false)?;
+        // Read back: another automation may have moved the issue concurrently.
+        let after = self.issue_claim(task_id)?.state.name;
+        if !after.eq_ignore_ascii_case(&self.in_progress_state) {
+            return Err(Error::TaskSource(format!(
+                "issue #{task_id} is in state '{after}' after marking it '{}'",
+                self.in_progress_state
+            )));
+        }
         debug!(task_id, "marked in-progress on Linear");
         Ok(())
     }
 
+    /// Queued until the next [`TaskSource::flush`].
     fn mark_in_review(&self, task_id: &str) -> Result<()> {
-        self.update_issue_state(task_id, &self.in_review_state)?;
-        debug!(task_id, "marked in-review on Linear");
+        self.enqueue(PendingMutation::State {
+            task_id: task_id.to_string(),
+            state: self.in_review_state.clone(),
+        })?;
+        debug!(task_id, "queued in-review transition on Linear");
+        Ok(())
+    }
+
+    fn release_task(&self, task_id: &str) -> Result<()> {
+        // Queued mutations go first so they cannot undo the release.
+        self.flush_before(task_id);
+        self.update_issue_state(
+            task_id,
+            &self.todo_state,
+            self.claim_strategy == ClaimStrategy::Assignee,
+        )?;
+        debug!(task_id, "released task on Linear");
         Ok(())
     }
 
     fn get_task_details(&self, task_id: &str) -> Result<Task> {
-        let number: f64 = task_id
-            .parse::<u64>()
-            .map_err(|_| Error::TaskSource(format!("invalid task id: {task_id}")))?
-            as f64;
+        self.get_tasks_details(&[task_id])?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                Error::TaskSource(format!(
+                    "issue #{task_id} not found in team '{}'",
+                    self.team
+                ))
+            })
+    }
+
+    /// Fetches up to `PAGE_SIZE` issues per request, filtered by number.
+    fn get_tasks_details(&self, task_ids: &[&str]) -> Result<Vec<Task>> {
+        let numbers = task_ids
+            .iter()
+            .map(|id| {
+                id.parse::<u64>()
+                    .map(|n| n as f64)
+                    .map_err(|_| Error::TaskSource(format!("invalid task id: {id}")))
+            })
+            .collect::<Result<Vec<f64>>>()?;
 
         let query = r#"
-            query IssueDetails($team: String!, $number: Float!) {
+            query IssueDetails($team: String!, $numbers: [Float!]!, $first: Int!, $after: String) {
                 issues(
-                    filter: { team: { key: { eq: $team } }, number: { eq: $number } }
-                    first: 1
+                    filter: { team: { key: { eq: $team } }, number: { in: $numbers } }
+                    first: $first
+                    after: $after
                 ) {
                     nodes {
-                        id identifier number title description url priority
+                        id identifier number title description url priority createdAt updatedAt projectMilestone { name } reactions { id }
+                        estimate project { name } assignee { id displayName }
+                        state { name type }
+                        labels { nodes { name } }
+                    }
+                    pageInfo { hasNextPage endCursor }
+                }
+            }
+        "#;
+
+        let mut tasks = Vec::with_capacity(numbers.len());
+        for batch in numbers.chunks(PAGE_SIZE as usize) {
+            let nodes: Vec<IssueNode> = self.fetch_all_issues(
+                query,
+                serde_json::json!({ "team": self.team, "numbers": batch }),
+                "issue details",
+            )?;
+            tasks.extend(nodes.iter().map(Self::parse_issue));
+        }
+        Ok(tasks)
+    }
+
+    fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>> {
+        let ids = self.closed_issue_numbers(None)?;
+        debug!(?ids, "fetched closed Linear task ids");
+        Ok(ids)
+    }
+
+    /// Linear has no single close timestamp for completed and canceled issues,
+    /// so this filters on `updatedAt`, which closing always bumps.
+    fn fetch_closed_task_ids_since(&self, since: u64) -> Result<HashSet<u64>> {
+        let ids = self.closed_issue_numbers(Some(since))?;
+        debug!(?ids, since, "fetched recently closed Linear task ids");
+        Ok(ids)
+    }
+
+    fn fetch_open_tasks_with_label(&self, label: &str) -> Result<Vec<Task>> {
+        let filter = serde_json::json!({
+            "team": { "key": { "eq": self.team } },
+            "labels": { "name": { "eq": label } },
+            "state": { "type": { "nin": ["completed", "canceled"] } },
+        });
+
+        let query = r#"
+            query LabelledIssues($filter: IssueFilter!, $first: Int!, $after: String) {
+                issues(filter: $filter, first: $first, after: $after) {
+                    nodes {
+                        id identifier number title description url priority createdAt updatedAt projectMilestone { name } reactions { id }
+                        estimate project { name } assignee { id displayName }
                         state { name type }
                         labels { nodes { name } }
                     }
+                    pageInfo { hasNextPage endCursor }
+                }
+            }
+        "#;
+
+        let nodes: Vec<IssueNode> = self.fetch_all_issues(
+            query,
+            serde_json::json!({ "filter": filter }),
+            "Linear issues",
+        )?;
+        Ok(nodes.iter().map(Self::parse_issue).collect())
+    }
+
+    fn create_task(&self, title: &str, body: &str, labels: &[String]) -> Result<Task> {
+        let label_ids = labels
+            .iter()
+            .map(|l| ensure_label_with_client(l, &self.team, self.client.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+        let team_id = find_team_id(&self.team, self.client.as_ref())?;
+
+        let query = r#"
+            mutation CreateIssue($input: IssueCreateInput!) {
+                issueCreate(input: $input) {
+                    success
+                    issue {
+                        id identifier number title description url priority createdAt updatedAt projectMilestone { name } reactions { id }
+                        estimate project { name } assignee { id displayName }
+                        state { name type }
+                        labels { nodes { name } }
+                    }
+                }
+            }
+        "#;
+
+        let data = self.client.graphql(
+            query,
+            serde_json::json!({
+                "input": {
+                    "teamId": team_id,
+                    "title": title,
+                    "description": body,
+                    "labelIds": label_ids,
+                }
+            }),
+        )?;
+
+        let node: IssueNode = serde_json::from_value(
+            data.pointer("/issueCreate/issue")
+                .cloned()
+                .unwrap_or_default(),
+        )
+        .map_err(|e| Error::TaskSource(format!("failed to parse created issue: {e}")))?;
+
+        debug!(number = node.number, "created Linear issue");
+        Ok(Self::parse_issue(&node))
+    }
+
+    fn add_label(&self, task_id: &str, label: &str) -> Result<()> {
+        let issue_id = self.find_issue_id(task_id)?;
+        let label_id = ensure_label_with_client(label, &self.team, self.client.as_ref())?;
+
+        let query = r#"
+            mutation AddIssueLabel($issueId: String!, $labelId: String!) {
+                issueAddLabel(id: $issueId, labelId: $labelId) {
+                    success
                 }
             }
         "#;
 
         let data = self.client.graphql(
             query,
-            serde_json::json!({ "team": self.team, "number": number }),
+            serde_json::json!({ "issueId": issue_id, "labelId": label_id }),
         )?;
 
-        let issues: IssueConnection =
-            serde_json::from_value(data.get("issues").cloned().unwrap_or_default())
-                .map_err(|e| Error::TaskSource(format!("failed to parse issue details: {e}")))?;
-
-        let node = issues.nodes.first().ok_or_else(|| {
-            Error::TaskSource(format!(
-                "issue #{task_id} not found in team '{}'",
-                self.team
-            ))
-        })?;
+        let success = data
+            .pointer("/issueAddLabel/success")
+            .and_then(|s| s.as_bool())
+            .unwrap_or(false);
+        if !success {
+            return Err(Error::TaskSource(format!(
+                "failed to add label '{label}' to issue #{task_id}"
+            )));
+        }
 
-        Ok(Self::parse_issue(node))
+        debug!(task_id, label, "added label on Linear");
+        Ok(())
     }
 
-    fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>> {
-        let mut filter = self.build_issue_filter();
-        filter["state"] = serde_json::json!({ "type": { "in": ["completed", "canceled"] } });
+    /// Queued until the next [`TaskSource::flush`].
+    fn add_comment(&self, task_id: &str, body: &str) -> Result<()> {
+        self.enqueue(PendingMutation::Comment {
+            task_id: task_id.to_string(),
+            body: body.to_string(),
+        })?;
+        debug!(task_id, "queued comment on Linear issue");
+        Ok(())
+    }
 
+    fn attach_link(&self, task_id: &str, url: &str, title: &str) -> Result<()> {
+        let issue_id = self.find_issue_id(task_id)?;
         let query = r#"
-            query ClosedIssues($filter: IssueFilter!) {
-                issues(filter: $filter, first: 200) {
-                    nodes { number }
+            mutation AttachLink($issueId: String!, $url: String!, $title: String) {
+                attachmentLinkURL(issueId: $issueId, url: $url, title: $title) {
+                    success
                 }
             }
         "#;
 
-        let data = self
-            .client
-            .graphql(query, serde_json::json!({ "filter": filter }))?;
+        let data = self.client.graphql(
+            query,
+            serde_json::json!({ "issueId": issue_id, "url": url, "title": title }),
+        )?;
 
-        #[derive(Deserialize)]
-        struct NumberNode {
-            number: u64,
-        }
-        #[derive(Deserialize)]
-        struct NumberConnection {
-            nodes: Vec<NumberNode>,
+        let success = data
+            .pointer("/attachmentLinkURL/success")
+            .and_then(|s| s.as_bool())
+            .unwrap_or(false);
+        if !success {
+            return Err(Error::TaskSource(format!(
+                "failed to attach {url} to issue #{task_id}"
+            )));
         }
 
-        let nums: NumberConnection =
-            serde_json::from_value(data.get("issues").cloned().unwrap_or_default())
-                .map_err(|e| Error::TaskSource(format!("failed to parse closed issues: {e}")))?;
+        debug!(task_id, url, "attached link on Linear");
+        Ok(())
+    }
 
-        let ids: HashSet<u64> = nums.nodes.into_iter().map(|n| n.number).collect();
-        debug!(?ids, "fetched closed Linear task ids");
-        Ok(ids)
+    fn flush(&self) -> Result<()> {
+        self.send_pending()
+    }
+}
+
+/// Mutations still queued when the source goes away are sent rather than lost.
+impl Drop for LinearSource {
+    fn drop(&mut self) {
+        if let Err(e) = self.send_pending() {
+            warn!(error = %e, "failed to send queued Linear mutations");
+        }
     }
 }
 
@@ -507,13 +1367,20 @@ pub fn init_label(config: &Config) -> Result<()> {
 
     let api_key = resolve_api_key(&linear.api_key_env)?;
 
-    let client = DefaultLinearClient {
-        api_key: api_key.to_string(),
-    };
+    let client = DefaultLinearClient::new(api_key);
     init_label_with_client(&config.label, &linear.team, &client)
 }
 
 fn init_label_with_client(label: &str, team_key: &str, client: &dyn LinearClient) -> Result<()> {
+    ensure_label_with_client(label, team_key, client).map(|_| ())
+}
+
+/// Return the ID of `label` in a team, creating the label if it doesn't exist.
+fn ensure_label_with_client(
+    label: &str,
+    team_key: &str,
+    client: &dyn LinearClient,
+) -> Result<String> {
     let label_name = label;
 
     // Check if label already exists
@@ -532,7 +1399,6 @@ fn init_label_with_client(label: &str, team_key: &str, client: &dyn LinearClient
 
     #[derive(Deserialize)]
     struct LabelCheckNode {
-        #[allow(dead_code)]
         id: String,
     }
     #[derive(Deserialize)]
@@ -544,15 +1410,42 @@ fn init_label_with_client(label: &str, team_key: &str, client: &dyn LinearClient
         serde_json::from_value(data.get("issueLabels").cloned().unwrap_or_default())
             .map_err(|e| Error::TaskSource(format!("failed to parse labels: {e}")))?;
 
-    if !labels.nodes.is_empty() {
+    if let Some(existing) = labels.nodes.into_iter().next() {
         info!(
             "Label '{}' already exists in team '{}'; skipping",
             label_name, team_key
         );
-        return Ok(());
+        return Ok(existing.id);
     }
 
-    // Resolve team key → UUID
+    let team_id = find_team_id(team_key, client)?;
+
+    // Create label
+    let create_query = r#"
+        mutation CreateLabel($teamId: String!, $name: String!) {
+            issueLabelCreate(input: { teamId: $teamId, name: $name }) {
+                success
+                issueLabel { id name }
+            }
+        }
+    "#;
+
+    let created = client.graphql(
+        create_query,
+        serde_json::json!({ "teamId": team_id, "name": label_name }),
+    )?;
+    let label_id = created
+        .pointer("/issueLabelCreate/issueLabel/id")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| Error::TaskSource(format!("failed to create label '{label_name}'")))?;
+
+    info!("Created label '{}' in team '{}'", label_name, team_key);
+    Ok(label_id)
+}
+
+/// Resolve a team key → UUID.
+fn find_team_id(team_key: &str, client: &dyn LinearClient) -> Result<String> {
     let team_query = r#"
         query FindTeam($key: String!) {
             teams(filter: { key: { eq: $key } }) {
@@ -567,29 +1460,11 @@ fn init_label_with_client(label: &str, team_key: &str, client: &dyn LinearClient
         serde_json::from_value(team_data.get("teams").cloned().unwrap_or_default())
             .map_err(|e| Error::TaskSource(format!("failed to parse teams: {e}")))?;
 
-    let team_id = teams
+    teams
         .nodes
         .first()
         .map(|t| t.id.clone())
-        .ok_or_else(|| Error::TaskSource(format!("team '{team_key}' not found")))?;
-
-    // Create label
-    let create_query = r#"
-        mutation CreateLabel($teamId: String!, $name: String!) {
-            issueLabelCreate(input: { teamId: $teamId, name: $name }) {
-                success
-                issueLabel { id name }
-            }
-        }
-    "#;
-
-    client.graphql(
-        create_query,
-        serde_json::json!({ "teamId": team_id, "name": label_name }),
-    )?;
-
-    info!("Created label '{}' in team '{}'", label_name, team_key);
-    Ok(())
+        .ok_or_else(|| Error::TaskSource(format!("team '{team_key}' not found")))
 }
 
 // ---------------------------------------------------------------------------
@@ -599,9 +1474,7 @@ fn init_label_with_client(label: &str, team_key: &str, client: &dyn LinearClient
 /// Interactive init: discover teams, prompt user to pick one, create label, write config.
 pub fn init_interactive(label: &str) -> Result<()> {
     let api_key = resolve_api_key("LINEAR_API_KEY")?;
-    let client = DefaultLinearClient {
-        api_key: api_key.to_string(),
-    };
+    let client = DefaultLinearClient::new(api_key);
 
     let teams = list_teams(&client)?;
     if teams.is_empty() {
@@ -777,25 +1650,26 @@ where
 mod tests {
     use super::*;
     use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
 
     struct MockLinearClient {
         responses: RefCell<Vec<Result<serde_json::Value>>>,
+        variables: Rc<RefCell<Vec<serde_json::Value>>>,
     }
 
     impl MockLinearClient {
         fn new(responses: Vec<Result<serde_json::Value>>) -> Self {
             Self {
                 responses: RefCell::new(responses),
+                variables: Rc::default(),
             }
         }
     }
 
     impl LinearClient for MockLinearClient {
-        fn graphql(
-            &self,
-            _query: &str,
-            _variables: serde_json::Value,
-        ) -> Result<serde_json::Value> {
+        fn graphql(&self, _query: &str, variables: serde_json::Value) -> Result<serde_json::Value> {
+            self.variables.borrow_mut().push(variables);
             let mut responses = self.responses.borrow_mut();
             if responses.is_empty() {
                 Err(Error::TaskSource("no more mock responses".to_string()))
@@ -845,6 +1719,24 @@ mod tests {
         assert_eq!(tasks[1].id, "4");
     }
 
+    #[test]
+    fn test_fetch_eligible_skips_held_tasks() {
+        let mut held_by_comment = issue_node(3, "Task 3", 0, "Todo", "unstarted", &["rlph"]);
+        held_by_comment["comments"] = serde_json::json!({
+            "nodes": [{ "body": "/rlph hold" }]
+        });
+        let data = issues_response(vec![
+            issue_node(1, "Task 1", 0, "Todo", "unstarted", &["rlph"]),
+            issue_node(2, "Task 2", 0, "Todo", "unstarted", &["rlph", "rlph-hold"]),
+            held_by_comment,
+        ]);
+        let client = MockLinearClient::new(vec![Ok(data)]);
+        let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        let tasks = source.fetch_eligible_tasks().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, "1");
+    }
+
     #[test]
     fn test_priority_mapping() {
         assert_eq!(LinearSource::map_priority(0), None);
@@ -870,6 +1762,31 @@ mod tests {
         assert_eq!(tasks[2].priority, None);
     }
 
+    #[test]
+    fn test_fetch_parses_timestamps_and_metadata() {
+        let mut node = issue_node(1, "Dated", 0, "Todo", "unstarted", &["rlph"]);
+        node["createdAt"] = "2026-01-02T03:04:05.000Z".into();
+        node["updatedAt"] = "2026-02-03T04:05:06.000Z".into();
+        node["estimate"] = 3.0.into();
+        node["project"] = serde_json::json!({ "name": "Q3 Roadmap" });
+        node["assignee"] = serde_json::json!({ "id": "u1", "displayName": "ada" });
+        let data = issues_response(vec![node]);
+        let client = MockLinearClient::new(vec![Ok(data)]);
+        let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        let tasks = source.fetch_eligible_tasks().unwrap();
+        assert_eq!(
+            tasks[0].created_at.as_deref(),
+            Some("2026-01-02T03:04:05.000Z")
+        );
+        assert_eq!(
+            tasks[0].updated_at.as_deref(),
+            Some("2026-02-03T04:05:06.000Z")
+        );
+        assert_eq!(tasks[0].estimate, Some(3.0));
+        assert_eq!(tasks[0].project.as_deref(), Some("Q3 Roadmap"));
+        assert_eq!(tasks[0].assignee.as_deref(), Some("ada"));
+    }
+
     #[test]
     fn test_fetch_handles_null_description() {
         let data = serde_json::json!({
@@ -934,13 +1851,133 @@ mod tests {
         assert_eq!(ids, HashSet::from([10, 20, 30]));
     }
 
+    fn page(nodes: Vec<serde_json::Value>, next: Option<&str>) -> serde_json::Value {
+        serde_json::json!({ "issues": {
+            "nodes": nodes,
+            "pageInfo": { "hasNextPage": next.is_some(), "endCursor": next },
+        }})
+    }
+
+    #[test]
+    fn test_fetch_eligible_follows_page_cursor() {
+        let client = MockLinearClient::new(vec![
+            Ok(page(
+                vec![issue_node(1, "Task 1", 0, "Todo", "unstarted", &["rlph"])],
+                Some("cursor-1"),
+            )),
+            Ok(page(
+                vec![issue_node(2, "Task 2", 0, "Todo", "unstarted", &["rlph"])],
+                None,
+            )),
+        ]);
+        let variables = client.variables.clone();
+        let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        let tasks = source.fetch_eligible_tasks().unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[1].id, "2");
+
+        let variables = variables.borrow();
+        assert_eq!(variables[0]["first"], PAGE_SIZE);
+        assert!(variables[0].get("after").is_none());
+        assert_eq!(variables[1]["after"], "cursor-1");
+    }
+
+    #[test]
+    fn test_fetch_closed_task_ids_reads_every_page() {
+        let client = MockLinearClient::new(vec![
+            Ok(page(
+                vec![serde_json::json!({ "number": 10 })],
+                Some("cursor-1"),
+            )),
+            Ok(page(vec![serde_json::json!({ "number": 20 })], None)),
+        ]);
+        let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        let ids = source.fetch_closed_task_ids().unwrap();
+        assert_eq!(ids, HashSet::from([10, 20]));
+    }
+
+    #[test]
+    fn test_fetch_closed_task_ids_since_filters_by_update_time() {
+        let client = MockLinearClient::new(vec![Ok(page(
+            vec![serde_json::json!({ "number": 30 })],
+            None,
+        ))]);
+        let variables = client.variables.clone();
+        let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        let ids = source.fetch_closed_task_ids_since(1_792_143_005).unwrap();
+        assert_eq!(ids, HashSet::from([30]));
+        assert_eq!(
+            variables.borrow()[0]["filter"]["updatedAt"]["gte"],
+            "2026-10-16T09:30:05Z"
+        );
+    }
+
+    #[test]
+    fn test_get_tasks_details_batches_ids() {
+        let data = page(
+            vec![
+                issue_node(3, "Three", 0, "Todo", "unstarted", &["rlph"]),
+                issue_node(5, "Five", 0, "Todo", "unstarted", &["rlph"]),
+            ],
+            None,
+        );
+        let client = MockLinearClient::new(vec![Ok(data)]);
+        let variables = client.variables.clone();
+        let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        let tasks = source.get_tasks_details(&["3", "4", "5"]).unwrap();
+        let ids: Vec<&str> = tasks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["3", "5"]);
+
+        let variables = variables.borrow();
+        assert_eq!(variables.len(), 1);
+        assert_eq!(variables[0]["numbers"], serde_json::json!([3.0, 4.0, 5.0]));
+
+        assert!(source.get_tasks_details(&["ENG-3"]).is_err());
+    }
+
+    #[test]
+    fn test_rate_limit_pause() {
+        let headers = HashMap::from([
+            ("X-RateLimit-Requests-Remaining", "1200"),
+            ("X-RateLimit-Requests-Reset", "1000500"),
+            ("X-Complexity", "300"),
+            ("X-RateLimit-Complexity-Remaining", "250"),
+            ("X-RateLimit-Complexity-Reset", "1002000"),
+        ]);
+        let limits = RateLimit::from_headers(|name| headers.get(name).copied());
+        assert_eq!(limits.complexity, Some(300));
+        // Complexity budget cannot cover another query this size.
+        assert_eq!(limits.pause(1_000_000), Some(Duration::from_millis(2000)));
+
+        let plenty = RateLimit {
+            complexity_remaining: Some(5000),
+            ..limits
+        };
+        assert_eq!(plenty.pause(1_000_000), None);
+        assert_eq!(
+            plenty.reset_wait(1_000_000),
+            Some(Duration::from_millis(2000))
+        );
+        assert_eq!(
+            plenty.reset_wait(0),
+            Some(MAX_RATE_LIMIT_WAIT),
+            "waits are capped"
+        );
+        assert_eq!(plenty.reset_wait(2_000_000), None);
+        assert_eq!(RateLimit::from_headers(|_| None).pause(0), None);
+    }
+
+    fn state_data(name: &str) -> serde_json::Value {
+        serde_json::json!({ "issues": { "nodes": [{ "state": { "name": name } }] } })
+    }
+
     #[test]
     fn test_mark_in_progress() {
-        // find_issue_id response (lightweight: only id), find_state_id, issueUpdate
+        // state check, find_issue_id (lightweight: only id), find_state_id, issueUpdate, read-back
         let issue_data = serde_json::json!({
             "issues": { "nodes": [{ "id": "uuid-42" }] }
         });
-        let state_data = serde_json::json!({
+        let workflow_data = serde_json::json!({
             "workflowStates": { "nodes": [
                 { "id": "state-1", "name": "In Progress" },
                 { "id": "state-2", "name": "Done" },
@@ -948,11 +1985,138 @@ mod tests {
         });
         let update_data = serde_json::json!({ "issueUpdate": { "success": true } });
 
-        let client = MockLinearClient::new(vec![Ok(issue_data), Ok(state_data), Ok(update_data)]);
+        let client = MockLinearClient::new(vec![
+            Ok(state_data("Todo")),
+            Ok(issue_data),
+            Ok(workflow_data),
+            Ok(update_data),
+            Ok(state_data("In Progress")),
+        ]);
         let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
         source.mark_in_progress("42").unwrap();
     }
 
+    fn assignee_source(responses: Vec<Result<serde_json::Value>>) -> LinearSource {
+        let mut source =
+            LinearSource::with_client("rlph", "ENG", Box::new(MockLinearClient::new(responses)));
+        source.claim_strategy = ClaimStrategy::Assignee;
+        source
+    }
+
+    fn claim_data(state: &str, assignee: Option<&str>) -> serde_json::Value {
+        let assignee = assignee.map(|id| serde_json::json!({ "id": id }));
+        serde_json::json!({ "issues": { "nodes": [{ "state": { "name": state }, "assignee": assignee }] } })
+    }
+
+    #[test]
+    fn test_mark_in_progress_claims_assignee() {
+        let issue_data = serde_json::json!({ "issues": { "nodes": [{ "id": "uuid-42" }] } });
+        let workflow_data = serde_json::json!({
+            "workflowStates": { "nodes": [{ "id": "state-1", "name": "In Progress" }] }
+        });
+        let update_data = serde_json::json!({ "issueUpdate": { "success": true } });
+        // The issue UUID is looked up once and reused for the state update.
+        let source = assignee_source(vec![
+            Ok(claim_data("Todo", None)),
+            Ok(serde_json::json!({ "viewer": { "id": "me" } })),
+            Ok(issue_data),
+            Ok(update_data.clone()),
+            Ok(claim_data("Todo", Some("me"))),
+            Ok(workflow_data),
+            Ok(update_data),
+            Ok(claim_data("In Progress", Some("me"))),
+        ]);
+        source.mark_in_progress("42").unwrap();
+    }
+
+    #[test]
+    fn test_mark_in_progress_conflict_when_assignee_overwritten() {
+        let issue_data = serde_json::json!({ "issues": { "nodes": [{ "id": "uuid-42" }] } });
+        let source = assignee_source(vec![
+            Ok(claim_data("Todo", None)),
+            Ok(serde_json::json!({ "viewer": { "id": "me" } })),
+            Ok(issue_data),
+            Ok(serde_json::json!({ "issueUpdate": { "success": true } })),
+            Ok(claim_data("Todo", Some("worker-2"))),
+        ]);
+        let err = source.mark_in_progress("42").unwrap_err();
+        assert!(
+            matches!(&err, Error::TaskClaimConflict { reason, .. } if reason.contains("worker-2")),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn test_fetch_skips_tasks_assigned_elsewhere() {
+        let mut mine = issue_node(1, "Mine", 0, "Todo", "unstarted", &["rlph"]);
+        mine["assignee"] = serde_json::json!({ "id": "me" });
+        let mut theirs = issue_node(2, "Theirs", 0, "Todo", "unstarted", &["rlph"]);
+        theirs["assignee"] = serde_json::json!({ "id": "someone" });
+        let free = issue_node(3, "Free", 0, "Todo", "unstarted", &["rlph"]);
+        let source = assignee_source(vec![
+            Ok(issues_response(vec![mine, theirs, free])),
+            Ok(serde_json::json!({ "viewer": { "id": "me" } })),
+        ]);
+        let ids: Vec<String> = source
+            .fetch_eligible_tasks()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec!["1", "3"]);
+    }
+
+    #[test]
+    fn test_mark_in_progress_conflict_when_already_started() {
+        let client = MockLinearClient::new(vec![Ok(state_data("In Progress"))]);
+        let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        let err = source.mark_in_progress("42").unwrap_err();
+        assert!(
+            matches!(&err, Error::TaskClaimConflict { task_id, .. } if task_id == "42"),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn test_mark_in_progress_verifies_state_applied() {
+        let issue_data = serde_json::json!({
+            "issues": { "nodes": [{ "id": "uuid-42" }] }
+        });
+        let workflow_data = serde_json::json!({
+            "workflowStates": { "nodes": [{ "id": "state-1", "name": "In Progress" }] }
+        });
+        let update_data = serde_json::json!({ "issueUpdate": { "success": true } });
+
+        let client = MockLinearClient::new(vec![
+            Ok(state_data("Todo")),
+            Ok(issue_data),
+            Ok(workflow_data),
+            Ok(update_data),
+            Ok(state_data("Canceled")),
+        ]);
+        let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        let err = source.mark_in_progress("42").unwrap_err();
+        assert!(err.to_string().contains("in state 'Canceled'"));
+    }
+
+    #[test]
+    fn test_release_task_moves_to_todo() {
+        let issue_data = serde_json::json!({
+            "issues": { "nodes": [{ "id": "uuid-42" }] }
+        });
+        let state_data = serde_json::json!({
+            "workflowStates": { "nodes": [
+                { "id": "state-0", "name": "Todo" },
+                { "id": "state-1", "name": "In Progress" },
+            ]}
+        });
+        let update_data = serde_json::json!({ "issueUpdate": { "success": true } });
+
+        let client = MockLinearClient::new(vec![Ok(issue_data), Ok(state_data), Ok(update_data)]);
+        let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        source.release_task("42").unwrap();
+    }
+
     #[test]
     fn test_fetch_error_propagated() {
         let client = MockLinearClient::new(vec![Err(Error::TaskSource(
@@ -1002,6 +2166,150 @@ mod tests {
         init_label_with_client("rlph", "ENG", &client).unwrap();
     }
 
+    #[test]
+    fn test_create_task_resolves_label_and_team() {
+        let label_data = serde_json::json!({
+            "issueLabels": { "nodes": [{ "id": "lbl-1", "name": "rlph-followup" }] }
+        });
+        let team_data = serde_json::json!({ "teams": { "nodes": [{ "id": "team-uuid" }] } });
+        let create_data = serde_json::json!({
+            "issueCreate": {
+                "success": true,
+                "issue": issue_node(12, "Follow-up", 0, "Todo", "unstarted", &["rlph-followup"])
+            }
+        });
+
+        let client = MockLinearClient::new(vec![Ok(label_data), Ok(team_data), Ok(create_data)]);
+        let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        let task = source
+            .create_task("Follow-up", "details", &["rlph-followup".to_string()])
+            .unwrap();
+        assert_eq!(task.id, "12");
+        assert_eq!(task.labels, vec!["rlph-followup".to_string()]);
+    }
+
+    #[test]
+    fn test_add_label_resolves_issue_and_label() {
+        let issue_data = serde_json::json!({
+            "issues": { "nodes": [{ "id": "uuid-42" }] }
+        });
+        let label_data = serde_json::json!({
+            "issueLabels": { "nodes": [{ "id": "lbl-9", "name": "rlph-failed" }] }
+        });
+        let add_data = serde_json::json!({ "issueAddLabel": { "success": true } });
+
+        let client = MockLinearClient::new(vec![Ok(issue_data), Ok(label_data), Ok(add_data)]);
+        let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        source.add_label("42", "rlph-failed").unwrap();
+    }
+
+    #[test]
+    fn test_add_comment_and_attach_link() {
+        let issue_data = serde_json::json!({
+            "issues": { "nodes": [{ "id": "uuid-42" }] }
+        });
+        let client = MockLinearClient::new(vec![
+            Ok(issue_data),
+            Ok(serde_json::json!({ "attachmentLinkURL": { "success": true } })),
+            Ok(serde_json::json!({ "attachmentLinkURL": { "success": false } })),
+            Ok(serde_json::json!({ "m0": { "success": true } })),
+        ]);
+        let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        source.add_comment("42", "PR opened").unwrap();
+        source
+            .attach_link("42", "https://github.com/o/r/pull/7", "PR #7")
+            .unwrap();
+        let err = source
+            .attach_link("42", "https://github.com/o/r/pull/7", "PR #7")
+            .unwrap_err();
+        assert!(err.to_string().contains("failed to attach"), "{err}");
+        source.flush().unwrap();
+        assert!(source.add_comment("ENG-42", "PR opened").is_err());
+    }
+
+    #[test]
+    fn test_flush_batches_queued_mutations() {
+        let client = MockLinearClient::new(vec![
+            Ok(page(
+                vec![
+                    serde_json::json!({ "id": "uuid-42", "number": 42 }),
+                    serde_json::json!({ "id": "uuid-43", "number": 43 }),
+                ],
+                None,
+            )),
+            Ok(serde_json::json!({
+                "workflowStates": { "nodes": [{ "id": "state-3", "name": "In Review" }] }
+            })),
+            Ok(serde_json::json!({
+                "m0": { "success": true },
+                "m1": { "success": true },
+                "m2": { "success": false },
+            })),
+        ]);
+        let variables = client.variables.clone();
+        let source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        source.mark_in_review("42").unwrap();
+        source.add_comment("42", "PR opened").unwrap();
+        source.add_comment("43", "Blocked by #42").unwrap();
+        assert!(
+            variables.borrow().is_empty(),
+            "nothing is sent before flush"
+        );
+
+        let err = source.flush().unwrap_err();
+        assert!(
+            err.to_string().contains("comment on issue #43"),
+            "only the failed mutation is reported: {err}"
+        );
+
+        let variables = variables.borrow();
+        assert_eq!(variables.len(), 3);
+        assert_eq!(variables[0]["numbers"], serde_json::json!([42.0, 43.0]));
+        assert_eq!(
+            variables[2],
+            serde_json::json!({
+                "issue0": "uuid-42", "state0": "state-3",
+                "issue1": "uuid-42", "body1": "PR opened",
+                "issue2": "uuid-43", "body2": "Blocked by #42",
+            })
+        );
+        drop(variables);
+
+        // The queue is empty again.
+        source.flush().unwrap();
+    }
+
+    #[test]
+    fn test_release_task_sends_queued_mutations_first() {
+        let client = MockLinearClient::new(vec![
+            Ok(page(
+                vec![serde_json::json!({ "id": "uuid-42", "number": 42 })],
+                None,
+            )),
+            Ok(serde_json::json!({
+                "workflowStates": { "nodes": [
+                    { "id": "state-0", "name": "Todo" },
+                    { "id": "state-3", "name": "In Review" },
+                ]}
+            })),
+            Ok(serde_json::json!({ "m0": { "success": true } })),
+            Ok(serde_json::json!({ "issueUpdate": { "success": true } })),
+        ]);
+        let variables = client.variables.clone();
+        let mut source = LinearSource::with_client("rlph", "ENG", Box::new(client));
+        source.claim_strategy = ClaimStrategy::Assignee;
+        source.mark_in_review("42").unwrap();
+        source.release_task("42").unwrap();
+
+        let variables = variables.borrow();
+        assert_eq!(variables.len(), 4);
+        assert_eq!(variables[2]["state0"], "state-3");
+        assert_eq!(
+            variables[3]["input"],
+            serde_json::json!({ "stateId": "state-0", "assigneeId": null })
+        );
+    }
+
     #[test]
     fn test_init_label_skips_when_exists() {
         let label_data = serde_json::json!({
@@ -1,10 +1,16 @@
+pub mod bitbucket;
+pub mod gitea;
 pub mod github;
+pub mod github_api;
+pub mod github_project;
 pub mod linear;
+pub mod trello;
 
 use serde::Serialize;
 
 use std::collections::HashSet;
 
+use crate::config::{PriorityPolicy, PrioritySignal};
 use crate::error::Result;
 
 /// Task priority (1 = highest, 9 = lowest).
@@ -30,6 +36,41 @@ impl Priority {
     }
 }
 
+/// Comment command that puts a task on hold until a later [`RESUME_COMMAND`].
+pub const HOLD_COMMAND: &str = "/rlph hold";
+
+/// Comment command that lifts a hold placed with [`HOLD_COMMAND`].
+pub const RESUME_COMMAND: &str = "/rlph resume";
+
+/// Returns true if a task should be skipped by the fetcher.
+///
+/// A task is on hold when it carries any of `hold_labels` (case-insensitive),
+/// or when the most recent hold/resume command among `comments` (oldest first)
+/// is a hold.
+pub fn is_on_hold<'a>(
+    labels: &[String],
+    comments: impl IntoIterator<Item = &'a str>,
+    hold_labels: &[String],
+) -> bool {
+    if labels
+        .iter()
+        .any(|l| hold_labels.iter().any(|h| h.eq_ignore_ascii_case(l)))
+    {
+        return true;
+    }
+
+    let mut held = false;
+    for line in comments.into_iter().flat_map(str::lines) {
+        let line = line.trim().to_lowercase();
+        if line.starts_with(HOLD_COMMAND) {
+            held = true;
+        } else if line.starts_with(RESUME_COMMAND) {
+            held = false;
+        }
+    }
+    held
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Task {
     pub id: String,
@@ -38,6 +79,125 @@ pub struct Task {
     pub labels: Vec<String>,
     pub url: String,
     pub priority: Option<Priority>,
+    /// When the task was created (ISO 8601, as reported by the source).
+    pub created_at: Option<String>,
+    /// When the task was last updated (ISO 8601, as reported by the source).
+    pub updated_at: Option<String>,
+    /// Milestone the task belongs to (a project milestone on Linear).
+    pub milestone: Option<String>,
+    /// 👍 reactions on GitHub, reactions on Linear; `None` when the source
+    /// does not report them.
+    pub reactions: Option<u32>,
+    /// Login or display name of the (first) assignee.
+    pub assignee: Option<String>,
+    /// Size estimate in the source's own units, e.g. Linear points.
+    pub estimate: Option<f64>,
+    /// Project the task is filed under; only Linear reports one.
+    pub project: Option<String>,
+}
+
+impl Task {
+    /// Whole days since the task was created, if its creation time is known.
+    pub fn age_days(&self, now_secs: u64) -> Option<u64> {
+        let created = parse_timestamp(self.created_at.as_deref()?)?;
+        Some(now_secs.saturating_sub(created) / 86_400)
+    }
+
+    /// Priority after applying `policy`: a task with an urgent label becomes p1,
+    /// and a task at least `stale_after_days` old moves up `stale_boost` levels
+    /// (from p9 when it has no priority), never above p1.
+    pub fn effective_priority(&self, policy: &PriorityPolicy, now_secs: u64) -> Option<Priority> {
+        let urgent = self.labels.iter().any(|l| {
+            policy
+                .urgent_labels
+                .iter()
+                .any(|u| u.eq_ignore_ascii_case(l))
+        });
+        if urgent {
+            return Some(Priority(1));
+        }
+        if let Some(days) = policy.stale_after_days
+            && self.age_days(now_secs).is_some_and(|age| age >= days)
+        {
+            let base = self.priority.map_or(9, |p| p.0);
+            return Some(Priority(base.saturating_sub(policy.stale_boost).max(1)));
+        }
+        self.priority
+    }
+
+    /// Sort key over `signals`, lowest ranks first: effective priority
+    /// ascending, reactions descending, compared in `signals` order. Tasks
+    /// without a priority rank last on that signal.
+    pub fn rank_key(
+        &self,
+        signals: &[PrioritySignal],
+        policy: &PriorityPolicy,
+        now_secs: u64,
+    ) -> Vec<u32> {
+        signals
+            .iter()
+            .map(|signal| match signal {
+                PrioritySignal::Labels => self
+                    .effective_priority(policy, now_secs)
+                    .map_or(u32::MAX, |p| p.0.into()),
+                PrioritySignal::Reactions => u32::MAX - self.reactions.unwrap_or(0),
+            })
+            .collect()
+    }
+}
+
+/// Format unix seconds as an ISO 8601 UTC timestamp, e.g. `2026-10-16T09:30:05Z`.
+pub fn format_iso_timestamp(secs: u64) -> String {
+    let compact = crate::runs::format_timestamp(secs);
+    format!(
+        "{}-{}-{}T{}:{}:{}Z",
+        &compact[0..4],
+        &compact[4..6],
+        &compact[6..8],
+        &compact[9..11],
+        &compact[11..13],
+        &compact[13..15]
+    )
+}
+
+/// Parse an ISO 8601 timestamp such as `2026-01-02T03:04:05Z` or
+/// `2026-01-02T03:04:05.123+02:00` into unix seconds.
+pub fn parse_timestamp(s: &str) -> Option<u64> {
+    let (date, time) = s.trim().split_once('T')?;
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: i64 = date_parts.next()?.parse().ok()?;
+    let day: i64 = date_parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (clock, offset_secs) = if let Some(clock) = time.strip_suffix('Z') {
+        (clock, 0)
+    } else {
+        let split = time.rfind(['+', '-'])?;
+        let (clock, offset) = time.split_at(split);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (oh, om) = offset[1..].split_once(':').unwrap_or((&offset[1..], "0"));
+        let offset = oh.parse::<i64>().ok()? * 3_600 + om.parse::<i64>().ok()? * 60;
+        (clock, sign * offset)
+    };
+    let clock = clock.split('.').next()?;
+    let mut clock_parts = clock.splitn(3, ':');
+    let hour: i64 = clock_parts.next()?.parse().ok()?;
+    let minute: i64 = clock_parts.next()?.parse().ok()?;
+    let second: i64 = clock_parts.next().unwrap_or("0").parse().ok()?;
+
+    // Days-from-civil (Howard Hinnant's algorithm).
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let secs = days * 86_400 + hour * 3_600 + minute * 60 + second - offset_secs;
+    u64::try_from(secs).ok()
 }
 
 pub trait TaskSource {
@@ -53,48 +213,340 @@ pub trait TaskSource {
     /// Get full details for a task.
     fn get_task_details(&self, task_id: &str) -> Result<Task>;
 
+    /// Get full details for several tasks. By default each task is fetched on
+    /// its own; sources that can batch the lookup override this and leave out
+    /// IDs they do not know.
+    fn get_tasks_details(&self, task_ids: &[&str]) -> Result<Vec<Task>> {
+        task_ids
+            .iter()
+            .map(|id| self.get_task_details(id))
+            .collect()
+    }
+
+    /// Whether `fetch_eligible_tasks` would return the task (open, not on
+    /// hold, not claimed). By default the eligible list is searched; sources
+    /// whose listing is capped check the task on its own.
+    fn is_task_eligible(&self, task_id: &str) -> Result<bool> {
+        Ok(self.fetch_eligible_tasks()?.iter().any(|t| t.id == task_id))
+    }
+
     /// Fetch IDs of closed/done tasks (used for dependency resolution).
     fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>>;
+
+    /// Fetch IDs of tasks closed at or after `since` (unix seconds), used to
+    /// update cached closed IDs. Sources that cannot filter by close time return
+    /// every closed task.
+    fn fetch_closed_task_ids_since(&self, _since: u64) -> Result<HashSet<u64>> {
+        self.fetch_closed_task_ids()
+    }
+
+    /// Fetch open tasks carrying `label`, regardless of workflow state.
+    fn fetch_open_tasks_with_label(&self, label: &str) -> Result<Vec<Task>>;
+
+    /// Create a new task with the given labels.
+    fn create_task(&self, title: &str, body: &str, labels: &[String]) -> Result<Task>;
+
+    /// Add a label to an existing task, creating the label if needed.
+    fn add_label(&self, task_id: &str, label: &str) -> Result<()>;
+
+    /// Return a task claimed by `mark_in_progress` to the eligible queue.
+    fn release_task(&self, task_id: &str) -> Result<()>;
+
+    /// Mark a task as done once its PR merged. A no-op for sources whose tasks
+    /// close with the PR on their own.
+    fn mark_done(&self, _task_id: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Post a comment on a task.
+    fn add_comment(&self, task_id: &str, body: &str) -> Result<()>;
+
+    /// Attach a titled link (e.g. a PR) to a task. A no-op for sources that
+    /// link PRs on their own.
+    fn attach_link(&self, task_id: &str, url: &str, title: &str) -> Result<()>;
+
+    /// Send mutations the source deferred to batch them. Sources that send
+    /// every mutation right away have nothing to do.
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 pub enum AnySource {
     GitHub(github::GitHubSource),
+    /// GitHub without `gh`; see `github_transport`.
+    GitHubApi(github_api::GitHubApiSource),
     Linear(linear::LinearSource),
+    Bitbucket(bitbucket::BitbucketSource),
+    Trello(trello::TrelloSource),
+    Gitea(gitea::GiteaSource),
+    /// Any other source, e.g. one provided by a crate embedding rlph.
+    Custom(Box<dyn TaskSource + Send + Sync>),
+}
+
+impl AnySource {
+    /// Wrap a third-party source so it can be used wherever `AnySource` is.
+    pub fn custom(source: impl TaskSource + Send + Sync + 'static) -> Self {
+        AnySource::Custom(Box::new(source))
+    }
+}
+
+impl<T: TaskSource + ?Sized> TaskSource for Box<T> {
+    fn fetch_eligible_tasks(&self) -> Result<Vec<Task>> {
+        (**self).fetch_eligible_tasks()
+    }
+
+    fn mark_in_progress(&self, task_id: &str) -> Result<()> {
+        (**self).mark_in_progress(task_id)
+    }
+
+    fn mark_in_review(&self, task_id: &str) -> Result<()> {
+        (**self).mark_in_review(task_id)
+    }
+
+    fn get_task_details(&self, task_id: &str) -> Result<Task> {
+        (**self).get_task_details(task_id)
+    }
+
+    fn get_tasks_details(&self, task_ids: &[&str]) -> Result<Vec<Task>> {
+        (**self).get_tasks_details(task_ids)
+    }
+
+    fn is_task_eligible(&self, task_id: &str) -> Result<bool> {
+        (**self).is_task_eligible(task_id)
+    }
+
+    fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>> {
+        (**self).fetch_closed_task_ids()
+    }
+
+    fn fetch_closed_task_ids_since(&self, since: u64) -> Result<HashSet<u64>> {
+        (**self).fetch_closed_task_ids_since(since)
+    }
+
+    fn fetch_open_tasks_with_label(&self, label: &str) -> Result<Vec<Task>> {
+        (**self).fetch_open_tasks_with_label(label)
+    }
+
+    fn create_task(&self, title: &str, body: &str, labels: &[String]) -> Result<Task> {
+        (**self).create_task(title, body, labels)
+    }
+
+    fn add_label(&self, task_id: &str, label: &str) -> Result<()> {
+        (**self).add_label(task_id, label)
+    }
+
+    fn release_task(&self, task_id: &str) -> Result<()> {
+        (**self).release_task(task_id)
+    }
+
+    fn mark_done(&self, task_id: &str) -> Result<()> {
+        (**self).mark_done(task_id)
+    }
+
+    fn add_comment(&self, task_id: &str, body: &str) -> Result<()> {
+        (**self).add_comment(task_id, body)
+    }
+
+    fn attach_link(&self, task_id: &str, url: &str, title: &str) -> Result<()> {
+        (**self).attach_link(task_id, url, title)
+    }
+
+    fn flush(&self) -> Result<()> {
+        (**self).flush()
+    }
 }
 
 impl TaskSource for AnySource {
     fn fetch_eligible_tasks(&self) -> Result<Vec<Task>> {
         match self {
             AnySource::GitHub(s) => s.fetch_eligible_tasks(),
+            AnySource::GitHubApi(s) => s.fetch_eligible_tasks(),
             AnySource::Linear(s) => s.fetch_eligible_tasks(),
+            AnySource::Bitbucket(s) => s.fetch_eligible_tasks(),
+            AnySource::Trello(s) => s.fetch_eligible_tasks(),
+            AnySource::Gitea(s) => s.fetch_eligible_tasks(),
+            AnySource::Custom(s) => s.fetch_eligible_tasks(),
         }
     }
 
     fn mark_in_progress(&self, task_id: &str) -> Result<()> {
         match self {
             AnySource::GitHub(s) => s.mark_in_progress(task_id),
+            AnySource::GitHubApi(s) => s.mark_in_progress(task_id),
             AnySource::Linear(s) => s.mark_in_progress(task_id),
+            AnySource::Bitbucket(s) => s.mark_in_progress(task_id),
+            AnySource::Trello(s) => s.mark_in_progress(task_id),
+            AnySource::Gitea(s) => s.mark_in_progress(task_id),
+            AnySource::Custom(s) => s.mark_in_progress(task_id),
         }
     }
 
     fn mark_in_review(&self, task_id: &str) -> Result<()> {
         match self {
             AnySource::GitHub(s) => s.mark_in_review(task_id),
+            AnySource::GitHubApi(s) => s.mark_in_review(task_id),
             AnySource::Linear(s) => s.mark_in_review(task_id),
+            AnySource::Bitbucket(s) => s.mark_in_review(task_id),
+            AnySource::Trello(s) => s.mark_in_review(task_id),
+            AnySource::Gitea(s) => s.mark_in_review(task_id),
+            AnySource::Custom(s) => s.mark_in_review(task_id),
         }
     }
 
     fn get_task_details(&self, task_id: &str) -> Result<Task> {
         match self {
             AnySource::GitHub(s) => s.get_task_details(task_id),
+            AnySource::GitHubApi(s) => s.get_task_details(task_id),
             AnySource::Linear(s) => s.get_task_details(task_id),
+            AnySource::Bitbucket(s) => s.get_task_details(task_id),
+            AnySource::Trello(s) => s.get_task_details(task_id),
+            AnySource::Gitea(s) => s.get_task_details(task_id),
+            AnySource::Custom(s) => s.get_task_details(task_id),
+        }
+    }
+
+    fn get_tasks_details(&self, task_ids: &[&str]) -> Result<Vec<Task>> {
+        match self {
+            AnySource::GitHub(s) => s.get_tasks_details(task_ids),
+            AnySource::GitHubApi(s) => s.get_tasks_details(task_ids),
+            AnySource::Linear(s) => s.get_tasks_details(task_ids),
+            AnySource::Bitbucket(s) => s.get_tasks_details(task_ids),
+            AnySource::Trello(s) => s.get_tasks_details(task_ids),
+            AnySource::Gitea(s) => s.get_tasks_details(task_ids),
+            AnySource::Custom(s) => s.get_tasks_details(task_ids),
+        }
+    }
+
+    fn is_task_eligible(&self, task_id: &str) -> Result<bool> {
+        match self {
+            AnySource::GitHub(s) => s.is_task_eligible(task_id),
+            AnySource::GitHubApi(s) => s.is_task_eligible(task_id),
+            AnySource::Linear(s) => s.is_task_eligible(task_id),
+            AnySource::Bitbucket(s) => s.is_task_eligible(task_id),
+            AnySource::Trello(s) => s.is_task_eligible(task_id),
+            AnySource::Gitea(s) => s.is_task_eligible(task_id),
+            AnySource::Custom(s) => s.is_task_eligible(task_id),
         }
     }
 
     fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>> {
         match self {
             AnySource::GitHub(s) => s.fetch_closed_task_ids(),
+            AnySource::GitHubApi(s) => s.fetch_closed_task_ids(),
             AnySource::Linear(s) => s.fetch_closed_task_ids(),
+            AnySource::Bitbucket(s) => s.fetch_closed_task_ids(),
+            AnySource::Trello(s) => s.fetch_closed_task_ids(),
+            AnySource::Gitea(s) => s.fetch_closed_task_ids(),
+            AnySource::Custom(s) => s.fetch_closed_task_ids(),
+        }
+    }
+
+    fn fetch_closed_task_ids_since(&self, since: u64) -> Result<HashSet<u64>> {
+        match self {
+            AnySource::GitHub(s) => s.fetch_closed_task_ids_since(since),
+            AnySource::GitHubApi(s) => s.fetch_closed_task_ids_since(since),
+            AnySource::Linear(s) => s.fetch_closed_task_ids_since(since),
+            AnySource::Bitbucket(s) => s.fetch_closed_task_ids_since(since),
+            AnySource::Trello(s) => s.fetch_closed_task_ids_since(since),
+            AnySource::Gitea(s) => s.fetch_closed_task_ids_since(since),
+            AnySource::Custom(s) => s.fetch_closed_task_ids_since(since),
+        }
+    }
+
+    fn fetch_open_tasks_with_label(&self, label: &str) -> Result<Vec<Task>> {
+        match self {
+            AnySource::GitHub(s) => s.fetch_open_tasks_with_label(label),
+            AnySource::GitHubApi(s) => s.fetch_open_tasks_with_label(label),
+            AnySource::Linear(s) => s.fetch_open_tasks_with_label(label),
+            AnySource::Bitbucket(s) => s.fetch_open_tasks_with_label(label),
+            AnySource::Trello(s) => s.fetch_open_tasks_with_label(label),
+            AnySource::Gitea(s) => s.fetch_open_tasks_with_label(label),
+            AnySource::Custom(s) => s.fetch_open_tasks_with_label(label),
+        }
+    }
+
+    fn create_task(&self, title: &str, body: &str, labels: &[String]) -> Result<Task> {
+        match self {
+            AnySource::GitHub(s) => s.create_task(title, body, labels),
+            AnySource::GitHubApi(s) => s.create_task(title, body, labels),
+            AnySource::Linear(s) => s.create_task(title, body, labels),
+            AnySource::Bitbucket(s) => s.create_task(title, body, labels),
+            AnySource::Trello(s) => s.create_task(title, body, labels),
+            AnySource::Gitea(s) => s.create_task(title, body, labels),
+            AnySource::Custom(s) => s.create_task(title, body, labels),
+        }
+    }
+
+    fn add_label(&self, task_id: &str, label: &str) -> Result<()> {
+        match self {
+            AnySource::GitHub(s) => s.add_label(task_id, label),
+            AnySource::GitHubApi(s) => s.add_label(task_id, label),
+            AnySource::Linear(s) => s.add_label(task_id, label),
+            AnySource::Bitbucket(s) => s.add_label(task_id, label),
+            AnySource::Trello(s) => s.add_label(task_id, label),
+            AnySource::Gitea(s) => s.add_label(task_id, label),
+            AnySource::Custom(s) => s.add_label(task_id, label),
+        }
+    }
+
+    fn release_task(&self, task_id: &str) -> Result<()> {
+        match self {
+            AnySource::GitHub(s) => s.release_task(task_id),
+            AnySource::GitHubApi(s) => s.release_task(task_id),
+            AnySource::Linear(s) => s.release_task(task_id),
+            AnySource::Bitbucket(s) => s.release_task(task_id),
+            AnySource::Trello(s) => s.release_task(task_id),
+            AnySource::Gitea(s) => s.release_task(task_id),
+            AnySource::Custom(s) => s.release_task(task_id),
+        }
+    }
+
+    fn mark_done(&self, task_id: &str) -> Result<()> {
+        match self {
+            AnySource::GitHub(s) => s.mark_done(task_id),
+            AnySource::GitHubApi(s) => s.mark_done(task_id),
+            AnySource::Linear(s) => s.mark_done(task_id),
+            AnySource::Bitbucket(s) => s.mark_done(task_id),
+            AnySource::Trello(s) => s.mark_done(task_id),
+            AnySource::Gitea(s) => s.mark_done(task_id),
+            AnySource::Custom(s) => s.mark_done(task_id),
+        }
+    }
+
+    fn add_comment(&self, task_id: &str, body: &str) -> Result<()> {
+        match self {
+            AnySource::GitHub(s) => s.add_comment(task_id, body),
+            AnySource::GitHubApi(s) => s.add_comment(task_id, body),
+            AnySource::Linear(s) => s.add_comment(task_id, body),
+            AnySource::Bitbucket(s) => s.add_comment(task_id, body),
+            AnySource::Trello(s) => s.add_comment(task_id, body),
+            AnySource::Gitea(s) => s.add_comment(task_id, body),
+            AnySource::Custom(s) => s.add_comment(task_id, body),
+        }
+    }
+
+    fn attach_link(&self, task_id: &str, url: &str, title: &str) -> Result<()> {
+        match self {
+            AnySource::GitHub(s) => s.attach_link(task_id, url, title),
+            AnySource::GitHubApi(s) => s.attach_link(task_id, url, title),
+            AnySource::Linear(s) => s.attach_link(task_id, url, title),
+            AnySource::Bitbucket(s) => s.attach_link(task_id, url, title),
+            AnySource::Trello(s) => s.attach_link(task_id, url, title),
+            AnySource::Gitea(s) => s.attach_link(task_id, url, title),
+            AnySource::Custom(s) => s.attach_link(task_id, url, title),
+        }
+    }
+
+    fn flush(&self) -> Result<()> {
+        match self {
+            AnySource::GitHub(s) => s.flush(),
+            AnySource::GitHubApi(s) => s.flush(),
+            AnySource::Linear(s) => s.flush(),
+            AnySource::Bitbucket(s) => s.flush(),
+            AnySource::Trello(s) => s.flush(),
+            AnySource::Gitea(s) => s.flush(),
+            AnySource::Custom(s) => s.flush(),
         }
     }
 }
@@ -124,6 +576,38 @@ mod tests {
         assert_eq!(Priority::from_label("PRIORITY-LOW"), Some(Priority(9)));
     }
 
+    fn hold_labels() -> Vec<String> {
+        vec!["rlph-hold".to_string(), "rlph-skip".to_string()]
+    }
+
+    #[test]
+    fn test_is_on_hold_by_label() {
+        let labels = vec!["rlph".to_string(), "RLPH-Hold".to_string()];
+        assert!(is_on_hold(&labels, [], &hold_labels()));
+        assert!(!is_on_hold(&["rlph".to_string()], [], &hold_labels()));
+    }
+
+    #[test]
+    fn test_is_on_hold_by_comment_latest_command_wins() {
+        let labels = vec!["rlph".to_string()];
+        assert!(is_on_hold(&labels, ["/rlph hold"], &hold_labels()));
+        assert!(!is_on_hold(
+            &labels,
+            ["/rlph hold", "thanks\n/rlph resume"],
+            &hold_labels()
+        ));
+        assert!(is_on_hold(
+            &labels,
+            ["/rlph resume", "  /RLPH HOLD until Monday"],
+            &hold_labels()
+        ));
+        assert!(!is_on_hold(
+            &labels,
+            ["please don't /rlph hold this"],
+            &hold_labels()
+        ));
+    }
+
     #[test]
     fn test_priority_invalid() {
         assert_eq!(Priority::from_label("p0"), None);
@@ -131,4 +615,156 @@ mod tests {
         assert_eq!(Priority::from_label("bug"), None);
         assert_eq!(Priority::from_label(""), None);
     }
+
+    #[test]
+    fn test_format_iso_timestamp() {
+        assert_eq!(format_iso_timestamp(1_792_143_005), "2026-10-16T09:30:05Z");
+        assert_eq!(
+            parse_timestamp(&format_iso_timestamp(951_782_400)),
+            Some(951_782_400)
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_timestamp("2026-10-16T09:30:05Z"), Some(1_792_143_005));
+        assert_eq!(
+            parse_timestamp("2026-10-16T09:30:05.123Z"),
+            Some(1_792_143_005)
+        );
+        assert_eq!(
+            parse_timestamp("2026-10-16T11:30:05+02:00"),
+            Some(1_792_143_005)
+        );
+        assert_eq!(parse_timestamp("2000-02-29T00:00:00Z"), Some(951_782_400));
+        assert_eq!(parse_timestamp("yesterday"), None);
+        assert_eq!(parse_timestamp("2026-13-01T00:00:00Z"), None);
+    }
+
+    fn dated_task(labels: &[&str], priority: Option<u8>, created_at: &str) -> Task {
+        Task {
+            id: "1".to_string(),
+            title: "Task".to_string(),
+            body: String::new(),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            url: String::new(),
+            priority: priority.map(Priority),
+            created_at: Some(created_at.to_string()),
+            updated_at: None,
+            milestone: None,
+            reactions: None,
+            assignee: None,
+            estimate: None,
+            project: None,
+        }
+    }
+
+    #[test]
+    fn test_effective_priority() {
+        // 2026-10-16T00:00:00Z
+        let now = 1_792_108_800;
+        let policy = PriorityPolicy {
+            stale_after_days: Some(14),
+            stale_boost: 2,
+            ..Default::default()
+        };
+
+        let fresh = dated_task(&[], Some(5), "2026-10-10T00:00:00Z");
+        assert_eq!(fresh.age_days(now), Some(6));
+        assert_eq!(fresh.effective_priority(&policy, now), Some(Priority(5)));
+
+        let stale = dated_task(&[], Some(5), "2026-09-01T00:00:00Z");
+        assert_eq!(stale.effective_priority(&policy, now), Some(Priority(3)));
+        let stale_p2 = dated_task(&[], Some(2), "2026-09-01T00:00:00Z");
+        assert_eq!(stale_p2.effective_priority(&policy, now), Some(Priority(1)));
+        let stale_unprioritized = dated_task(&[], None, "2026-09-01T00:00:00Z");
+        assert_eq!(
+            stale_unprioritized.effective_priority(&policy, now),
+            Some(Priority(7))
+        );
+
+        let urgent = dated_task(&["SLA-Urgent"], Some(9), "2026-10-15T00:00:00Z");
+        assert_eq!(urgent.effective_priority(&policy, now), Some(Priority(1)));
+
+        // Age boosts are off by default; unknown creation times are never stale.
+        let default = PriorityPolicy::default();
+        assert_eq!(stale.effective_priority(&default, now), Some(Priority(5)));
+        let undated = Task {
+            created_at: None,
+            ..stale.clone()
+        };
+        assert_eq!(undated.effective_priority(&policy, now), Some(Priority(5)));
+    }
+
+    #[test]
+    fn test_rank_key_orders_by_signals() {
+        let now = 1_792_108_800;
+        let policy = PriorityPolicy::default();
+        let p2 = dated_task(&[], Some(2), "2026-10-10T00:00:00Z");
+        let popular = Task {
+            reactions: Some(12),
+            ..dated_task(&[], Some(5), "2026-10-10T00:00:00Z")
+        };
+        let key = |task: &Task, signals: &[PrioritySignal]| task.rank_key(signals, &policy, now);
+
+        let labels_first = [PrioritySignal::Labels, PrioritySignal::Reactions];
+        assert!(key(&p2, &labels_first) < key(&popular, &labels_first));
+        let reactions_first = [PrioritySignal::Reactions, PrioritySignal::Labels];
+        assert!(key(&popular, &reactions_first) < key(&p2, &reactions_first));
+    }
+
+    /// A source defined outside the `AnySource` enum, as a downstream crate would.
+    struct StaticSource(Vec<Task>);
+
+    impl TaskSource for StaticSource {
+        fn fetch_eligible_tasks(&self) -> Result<Vec<Task>> {
+            Ok(self.0.clone())
+        }
+        fn mark_in_progress(&self, _task_id: &str) -> Result<()> {
+            Ok(())
+        }
+        fn mark_in_review(&self, _task_id: &str) -> Result<()> {
+            Ok(())
+        }
+        fn get_task_details(&self, task_id: &str) -> Result<Task> {
+            Ok(self.0.iter().find(|t| t.id == task_id).unwrap().clone())
+        }
+        fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>> {
+            Ok(HashSet::from([7]))
+        }
+        fn fetch_open_tasks_with_label(&self, _label: &str) -> Result<Vec<Task>> {
+            Ok(Vec::new())
+        }
+        fn create_task(&self, _title: &str, _body: &str, _labels: &[String]) -> Result<Task> {
+            Err(crate::error::Error::TaskSource("read-only".to_string()))
+        }
+        fn add_label(&self, _task_id: &str, _label: &str) -> Result<()> {
+            Ok(())
+        }
+        fn release_task(&self, _task_id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn add_comment(&self, _task_id: &str, _body: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn attach_link(&self, _task_id: &str, _url: &str, _title: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_custom_and_boxed_sources_dispatch() {
+        let task = dated_task(&[], None, "2026-01-01T00:00:00Z");
+        let source = AnySource::custom(StaticSource(vec![task.clone()]));
+        assert_eq!(source.fetch_eligible_tasks().unwrap()[0].id, task.id);
+        assert_eq!(source.get_task_details("1").unwrap().title, "Task");
+        assert!(source.fetch_closed_task_ids().unwrap().contains(&7));
+        assert!(source.create_task("t", "b", &[]).is_err());
+
+        let boxed: Box<dyn TaskSource> = Box::new(StaticSource(vec![task]));
+        assert_eq!(boxed.fetch_eligible_tasks().unwrap().len(), 1);
+    }
 }
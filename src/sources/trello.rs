@@ -0,0 +1,678 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use crate::config::{Config, TrelloConfig};
+use crate::error::{Error, Result};
+
+use super::{Priority, Task, TaskSource, format_iso_timestamp, is_on_hold, parse_timestamp};
+
+const TRELLO_API_URL: &str = "https://api.trello.com/1";
+const MAX_RETRIES: u32 = 3;
+const INITIAL_BACKOFF_MS: u64 = 500;
+/// Fields requested for every card.
+const CARD_FIELDS: &str = "idShort,name,desc,idList,labels,shortUrl,dateLastActivity,closed";
+
+// ---------------------------------------------------------------------------
+// Client abstraction (for testability)
+// ---------------------------------------------------------------------------
+
+pub trait TrelloClient {
+    /// Send `method` to `path` (relative to the API root) with `params` as the
+    /// query string, and return the response JSON.
+    fn request(&self, method: &str, path: &str, params: &[(&str, &str)]) -> Result<Value>;
+}
+
+struct DefaultTrelloClient {
+    api_key: String,
+    token: String,
+}
+
+impl TrelloClient for DefaultTrelloClient {
+    fn request(&self, method: &str, path: &str, params: &[(&str, &str)]) -> Result<Value> {
+        let url = format!("{TRELLO_API_URL}{path}");
+        let mut backoff_ms = INITIAL_BACKOFF_MS;
+        for attempt in 1..=MAX_RETRIES {
+            let mut request = ureq::request(method, &url)
+                .query("key", &self.api_key)
+                .query("token", &self.token);
+            for (name, value) in params {
+                request = request.query(name, value);
+            }
+            match request.call() {
+                Ok(response) => {
+                    return response.into_json().map_err(|e| {
+                        Error::TaskSource(format!("failed to parse Trello response: {e}"))
+                    });
+                }
+                Err(ureq::Error::Status(code, _))
+                    if (code == 429 || code >= 500) && attempt < MAX_RETRIES =>
+                {
+                    warn!(attempt, code, backoff_ms, "retrying Trello API request");
+                    thread::sleep(Duration::from_millis(backoff_ms));
+                    backoff_ms *= 2;
+                }
+                Err(ureq::Error::Status(code, response)) => {
+                    let body = response.into_string().unwrap_or_default();
+                    return Err(Error::TaskSource(format!(
+                        "Trello API {method} {path} failed: status {code}: {body}"
+                    )));
+                }
+                Err(e) if attempt < MAX_RETRIES => {
+                    warn!(attempt, error = %e, backoff_ms, "retrying Trello API after transient error");
+                    thread::sleep(Duration::from_millis(backoff_ms));
+                    backoff_ms *= 2;
+                }
+                Err(e) => {
+                    return Err(Error::TaskSource(format!(
+                        "Trello API {method} {path} failed: {e}"
+                    )));
+                }
+            }
+        }
+        Err(Error::TaskSource(format!(
+            "Trello API {method} {path} failed; retries exhausted"
+        )))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TrelloSource
+// ---------------------------------------------------------------------------
+
+/// Trello board as a task source.
+///
+/// Cards carrying the configured label are tasks, identified by their
+/// board-local short id. The list a card sits in is its state: cards in
+/// `todo_list` are eligible, claiming moves them to `in_progress_list`, an
+/// opened PR to `in_review_list`, and cards in `done_list` or archived count
+/// as closed. Checklists are appended to the task body as task lists.
+pub struct TrelloSource {
+    label: String,
+    hold_labels: Vec<String>,
+    board_id: String,
+    todo_list: String,
+    in_progress_list: String,
+    in_review_list: String,
+    done_list: String,
+    /// List name → list id for the board, fetched on first use.
+    list_ids: OnceLock<HashMap<String, String>>,
+    client: Box<dyn TrelloClient>,
+}
+
+impl TrelloSource {
+    pub fn new(config: &Config) -> Result<Self> {
+        let trello = config.trello.as_ref().ok_or_else(|| {
+            Error::ConfigValidation(
+                "[trello] config section required when source = \"trello\"".to_string(),
+            )
+        })?;
+        let env = |name: &str| {
+            std::env::var(name)
+                .map_err(|_| Error::TaskSource(format!("Trello credential not found in ${name}")))
+        };
+        let client = DefaultTrelloClient {
+            api_key: env(&trello.api_key_env)?,
+            token: env(&trello.token_env)?,
+        };
+        Ok(Self::with_client(
+            &config.label,
+            config.hold_labels.clone(),
+            trello,
+            Box::new(client),
+        ))
+    }
+
+    fn with_client(
+        label: &str,
+        hold_labels: Vec<String>,
+        trello: &TrelloConfig,
+        client: Box<dyn TrelloClient>,
+    ) -> Self {
+        Self {
+            label: label.to_string(),
+            hold_labels,
+            board_id: trello.board_id.clone(),
+            todo_list: trello.todo_list.clone(),
+            in_progress_list: trello.in_progress_list.clone(),
+            in_review_list: trello.in_review_list.clone(),
+            done_list: trello.done_list.clone(),
+            list_ids: OnceLock::new(),
+            client,
+        }
+    }
+
+    fn list_ids(&self) -> Result<&HashMap<String, String>> {
+        if let Some(ids) = self.list_ids.get() {
+            return Ok(ids);
+        }
+        let lists = self.client.request(
+            "GET",
+            &format!("/boards/{}/lists", self.board_id),
+            &[("fields", "name")],
+        )?;
+        let ids = lists
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|list| {
+                Some((
+                    list.get("name")?.as_str()?.to_string(),
+                    list.get("id")?.as_str()?.to_string(),
+                ))
+            })
+            .collect();
+        Ok(self.list_ids.get_or_init(|| ids))
+    }
+
+    fn list_id(&self, name: &str) -> Result<String> {
+        self.list_ids()?.get(name).cloned().ok_or_else(|| {
+            Error::TaskSource(format!(
+                "Trello list '{name}' not found on board {}",
+                self.board_id
+            ))
+        })
+    }
+
+    /// Open cards on the board, with checklists and comments.
+    fn board_cards(&self) -> Result<Vec<Value>> {
+        let cards = self.client.request(
+            "GET",
+            &format!("/boards/{}/cards", self.board_id),
+            &[
+                ("fields", CARD_FIELDS),
+                ("checklists", "all"),
+                ("members", "true"),
+                ("member_fields", "username"),
+                ("actions", "commentCard"),
+            ],
+        )?;
+        Ok(cards.as_array().cloned().unwrap_or_default())
+    }
+
+    /// Look up a card by its short id.
+    fn card(&self, task_id: &str) -> Result<Value> {
+        self.client.request(
+            "GET",
+            &format!("/boards/{}/cards/{task_id}", self.board_id),
+            &[
+                ("fields", CARD_FIELDS),
+                ("checklists", "all"),
+                ("members", "true"),
+                ("member_fields", "username"),
+            ],
+        )
+    }
+
+    fn card_id(&self, task_id: &str) -> Result<String> {
+        self.card(task_id)?
+            .get("id")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| Error::TaskSource(format!("Trello card {task_id} not found")))
+    }
+
+    fn move_card(&self, task_id: &str, list_name: &str) -> Result<()> {
+        let card_id = self.card_id(task_id)?;
+        let list_id = self.list_id(list_name)?;
+        self.client.request(
+            "PUT",
+            &format!("/cards/{card_id}"),
+            &[("idList", list_id.as_str())],
+        )?;
+        debug!(task_id, list = list_name, "moved Trello card");
+        Ok(())
+    }
+
+    /// Id of the board label named `name`, created if the board lacks it.
+    fn label_id(&self, name: &str) -> Result<String> {
+        let labels = self.client.request(
+            "GET",
+            &format!("/boards/{}/labels", self.board_id),
+            &[("fields", "name")],
+        )?;
+        let existing = labels.as_array().into_iter().flatten().find_map(|l| {
+            let matches = l
+                .get("name")
+                .and_then(Value::as_str)
+                .is_some_and(|n| n.eq_ignore_ascii_case(name));
+            if matches {
+                l.get("id").and_then(Value::as_str).map(str::to_string)
+            } else {
+                None
+            }
+        });
+        if let Some(id) = existing {
+            return Ok(id);
+        }
+        let created = self.client.request(
+            "POST",
+            &format!("/boards/{}/labels", self.board_id),
+            &[("name", name), ("color", "blue")],
+        )?;
+        created
+            .get("id")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| Error::TaskSource(format!("failed to create Trello label '{name}'")))
+    }
+
+    fn card_labels(card: &Value) -> Vec<String> {
+        card.get("labels")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|l| l.get("name").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn has_label(card: &Value, label: &str) -> bool {
+        Self::card_labels(card)
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(label))
+    }
+
+    fn is_held(&self, card: &Value) -> bool {
+        // Trello lists actions newest first; hold commands are evaluated
+        // oldest-first.
+        let mut comments: Vec<(&str, &str)> = card
+            .get("actions")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|a| Some((a.get("date")?.as_str()?, a.pointer("/data/text")?.as_str()?)))
+            .collect();
+        comments.sort();
+        is_on_hold(
+            &Self::card_labels(card),
+            comments.iter().map(|(_, text)| *text),
+            &self.hold_labels,
+        )
+    }
+
+    /// The card's checklists as markdown task lists.
+    fn checklists_markdown(card: &Value) -> String {
+        let mut out = String::new();
+        for checklist in card
+            .get("checklists")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let name = checklist
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or("Checklist");
+            out.push_str(&format!("\n\n## {name}\n"));
+            let mut items: Vec<&Value> = checklist
+                .get("checkItems")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .collect();
+            items.sort_by(|a, b| {
+                let pos = |v: &Value| v.get("pos").and_then(Value::as_f64).unwrap_or_default();
+                pos(a).total_cmp(&pos(b))
+            });
+            for item in items {
+                let done = item.get("state").and_then(Value::as_str) == Some("complete");
+                let text = item.get("name").and_then(Value::as_str).unwrap_or_default();
+                out.push_str(&format!("\n- [{}] {text}", if done { "x" } else { " " }));
+            }
+        }
+        out
+    }
+
+    fn parse_card(card: &Value) -> Option<Task> {
+        let str_at = |key: &str| card.get(key).and_then(Value::as_str);
+        let labels = Self::card_labels(card);
+        let priority = labels.iter().find_map(|l| Priority::from_label(l));
+        // A card id starts with its creation time as eight hex digits.
+        let created_at = str_at("id")
+            .and_then(|id| id.get(..8))
+            .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+            .map(format_iso_timestamp);
+        Some(Task {
+            id: card.get("idShort").and_then(Value::as_u64)?.to_string(),
+            title: str_at("name").unwrap_or_default().to_string(),
+            body: format!(
+                "{}{}",
+                str_at("desc").unwrap_or_default(),
+                Self::checklists_markdown(card)
+            ),
+            labels,
+            url: str_at("shortUrl").unwrap_or_default().to_string(),
+            priority,
+            created_at,
+            updated_at: str_at("dateLastActivity").map(str::to_string),
+            milestone: None,
+            reactions: None,
+            assignee: card
+                .pointer("/members/0/username")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            estimate: None,
+            project: None,
+        })
+    }
+
+    fn closed_card_ids(&self, since: Option<u64>) -> Result<HashSet<u64>> {
+        let done_list = self.list_id(&self.done_list)?;
+        let mut cards: Vec<Value> = self
+            .board_cards()?
+            .into_iter()
+            .filter(|c| c.get("idList").and_then(Value::as_str) == Some(done_list.as_str()))
+            .collect();
+        let archived = self.client.request(
+            "GET",
+            &format!("/boards/{}/cards/closed", self.board_id),
+            &[("fields", CARD_FIELDS)],
+        )?;
+        cards.extend(archived.as_array().cloned().unwrap_or_default());
+        Ok(cards
+            .iter()
+            .filter(|card| {
+                since.is_none_or(|since| {
+                    card.get("dateLastActivity")
+                        .and_then(Value::as_str)
+                        .and_then(parse_timestamp)
+                        .is_none_or(|updated| updated >= since)
+                })
+            })
+            .filter_map(|card| card.get("idShort").and_then(Value::as_u64))
+            .collect())
+    }
+}
+
+impl TaskSource for TrelloSource {
+    fn fetch_eligible_tasks(&self) -> Result<Vec<Task>> {
+        let todo = self.list_id(&self.todo_list)?;
+        let tasks: Vec<Task> = self
+            .board_cards()?
+            .iter()
+            .filter(|card| card.get("idList").and_then(Value::as_str) == Some(todo.as_str()))
+            .filter(|card| Self::has_label(card, &self.label))
+            .filter(|card| {
+                let held = self.is_held(card);
+                if held {
+                    debug!(card = ?card.get("idShort"), "skipping task on hold");
+                }
+                !held
+            })
+            .filter_map(Self::parse_card)
+            .collect();
+        debug!(count = tasks.len(), "fetched eligible Trello tasks");
+        Ok(tasks)
+    }
+
+    fn mark_in_progress(&self, task_id: &str) -> Result<()> {
+        let in_progress = self.list_id(&self.in_progress_list)?;
+        let card = self.card(task_id)?;
+        let current = card.get("idList").and_then(Value::as_str);
+        if current != Some(self.list_id(&self.todo_list)?.as_str()) {
+            return Err(Error::TaskClaimConflict {
+                task_id: task_id.to_string(),
+                reason: format!("card is no longer in '{}'", self.todo_list),
+            });
+        }
+        let card_id = card.get("id").and_then(Value::as_str).unwrap_or_default();
+        self.client.request(
+            "PUT",
+            &format!("/cards/{card_id}"),
+            &[("idList", in_progress.as_str())],
+        )?;
+        debug!(task_id, "claimed Trello card");
+        Ok(())
+    }
+
+    fn mark_in_review(&self, task_id: &str) -> Result<()> {
+        self.move_card(task_id, &self.in_review_list)
+    }
+
+    fn get_task_details(&self, task_id: &str) -> Result<Task> {
+        Self::parse_card(&self.card(task_id)?)
+            .ok_or_else(|| Error::TaskSource(format!("Trello card {task_id} not found")))
+    }
+
+    fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>> {
+        self.closed_card_ids(None)
+    }
+
+    fn fetch_closed_task_ids_since(&self, since: u64) -> Result<HashSet<u64>> {
+        self.closed_card_ids(Some(since))
+    }
+
+    fn fetch_open_tasks_with_label(&self, label: &str) -> Result<Vec<Task>> {
+        let done = self.list_id(&self.done_list)?;
+        Ok(self
+            .board_cards()?
+            .iter()
+            .filter(|card| card.get("idList").and_then(Value::as_str) != Some(done.as_str()))
+            .filter(|card| Self::has_label(card, label))
+            .filter_map(Self::parse_card)
+            .collect())
+    }
+
+    fn create_task(&self, title: &str, body: &str, labels: &[String]) -> Result<Task> {
+        let list_id = self.list_id(&self.todo_list)?;
+        let label_ids = labels
+            .iter()
+            .map(|l| self.label_id(l))
+            .collect::<Result<Vec<_>>>()?
+            .join(",");
+        let created = self.client.request(
+            "POST",
+            "/cards",
+            &[
+                ("idList", list_id.as_str()),
+                ("name", title),
+                ("desc", body),
+                ("idLabels", label_ids.as_str()),
+            ],
+        )?;
+        Self::parse_card(&created)
+            .ok_or_else(|| Error::TaskSource("Trello did not return the created card".to_string()))
+    }
+
+    fn add_label(&self, task_id: &str, label: &str) -> Result<()> {
+        let card = self.card(task_id)?;
+        if Self::has_label(&card, label) {
+            return Ok(());
+        }
+        let card_id = card.get("id").and_then(Value::as_str).unwrap_or_default();
+        let label_id = self.label_id(label)?;
+        self.client.request(
+            "POST",
+            &format!("/cards/{card_id}/idLabels"),
+            &[("value", label_id.as_str())],
+        )?;
+        debug!(task_id, label, "added label on Trello");
+        Ok(())
+    }
+
+    fn release_task(&self, task_id: &str) -> Result<()> {
+        self.move_card(task_id, &self.todo_list)
+    }
+
+    fn add_comment(&self, task_id: &str, body: &str) -> Result<()> {
+        let card_id = self.card_id(task_id)?;
+        self.client.request(
+            "POST",
+            &format!("/cards/{card_id}/actions/comments"),
+            &[("text", body)],
+        )?;
+        Ok(())
+    }
+
+    fn attach_link(&self, task_id: &str, url: &str, title: &str) -> Result<()> {
+        let card_id = self.card_id(task_id)?;
+        self.client.request(
+            "POST",
+            &format!("/cards/{card_id}/attachments"),
+            &[("url", url), ("name", title)],
+        )?;
+        debug!(task_id, url, "attached link on Trello");
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Returns queued responses in order and records each request.
+    struct MockTrelloClient {
+        responses: RefCell<Vec<Value>>,
+        requests: Rc<RefCell<Vec<(String, Vec<(String, String)>)>>>,
+    }
+
+    impl TrelloClient for MockTrelloClient {
+        fn request(&self, method: &str, path: &str, params: &[(&str, &str)]) -> Result<Value> {
+            self.requests.borrow_mut().push((
+                format!("{method} {path}"),
+                params
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ));
+            Ok(self.responses.borrow_mut().remove(0))
+        }
+    }
+
+    type Requests = Rc<RefCell<Vec<(String, Vec<(String, String)>)>>>;
+
+    fn source(responses: Vec<Value>) -> (TrelloSource, Requests) {
+        let requests = Requests::default();
+        let client = MockTrelloClient {
+            responses: RefCell::new(responses),
+            requests: Rc::clone(&requests),
+        };
+        let config = TrelloConfig {
+            board_id: "board".to_string(),
+            api_key_env: String::new(),
+            token_env: String::new(),
+            todo_list: "To Do".to_string(),
+            in_progress_list: "Doing".to_string(),
+            in_review_list: "In Review".to_string(),
+            done_list: "Done".to_string(),
+        };
+        let source = TrelloSource::with_client(
+            "rlph",
+            crate::config::default_hold_labels(),
+            &config,
+            Box::new(client),
+        );
+        (source, requests)
+    }
+
+    fn lists() -> Value {
+        json!([
+            { "id": "l-todo", "name": "To Do" },
+            { "id": "l-doing", "name": "Doing" },
+            { "id": "l-review", "name": "In Review" },
+            { "id": "l-done", "name": "Done" },
+        ])
+    }
+
+    fn card(short: u64, list: &str, labels: &[&str]) -> Value {
+        json!({
+            "id": "65a1b2c3d4e5f60718293a4b",
+            "idShort": short,
+            "name": format!("Card {short}"),
+            "desc": "Details",
+            "idList": list,
+            "labels": labels.iter().map(|l| json!({ "name": l })).collect::<Vec<_>>(),
+            "shortUrl": format!("https://trello.com/c/{short}"),
+            "dateLastActivity": "2026-02-01T00:00:00.000Z",
+        })
+    }
+
+    #[test]
+    fn test_fetch_eligible_filters_by_list_label_and_hold() {
+        let mut held = card(3, "l-todo", &["rlph"]);
+        held["actions"] = json!([
+            { "date": "2026-01-02T00:00:00Z", "data": { "text": "/rlph hold" } },
+            { "date": "2026-01-01T00:00:00Z", "data": { "text": "/rlph resume" } },
+        ]);
+        let mut assigned = card(1, "l-todo", &["rlph", "p2"]);
+        assigned["members"] = json!([{ "id": "m1", "username": "ada" }]);
+        let (source, _) = source(vec![
+            lists(),
+            json!([
+                assigned,
+                card(2, "l-doing", &["rlph"]),
+                held,
+                card(4, "l-todo", &["other"]),
+            ]),
+        ]);
+        let tasks = source.fetch_eligible_tasks().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, "1");
+        assert_eq!(tasks[0].priority, Some(Priority(2)));
+        assert_eq!(tasks[0].url, "https://trello.com/c/1");
+        assert_eq!(tasks[0].assignee.as_deref(), Some("ada"));
+        assert_eq!(
+            tasks[0].created_at.as_deref(),
+            Some(format_iso_timestamp(0x65a1b2c3).as_str())
+        );
+    }
+
+    #[test]
+    fn test_checklists_become_task_lists() {
+        let mut c = card(5, "l-todo", &["rlph"]);
+        c["checklists"] = json!([{
+            "name": "Steps",
+            "checkItems": [
+                { "name": "second", "state": "incomplete", "pos": 2.0 },
+                { "name": "first", "state": "complete", "pos": 1.0 },
+            ],
+        }]);
+        let task = TrelloSource::parse_card(&c).unwrap();
+        assert_eq!(
+            task.body,
+            "Details\n\n## Steps\n\n- [x] first\n- [ ] second"
+        );
+    }
+
+    #[test]
+    fn test_mark_in_progress_moves_card_from_todo() {
+        let (source, requests) = source(vec![lists(), card(1, "l-todo", &["rlph"]), json!({})]);
+        source.mark_in_progress("1").unwrap();
+        let requests = requests.borrow();
+        let (key, params) = requests.last().unwrap();
+        assert_eq!(key, "PUT /cards/65a1b2c3d4e5f60718293a4b");
+        assert_eq!(params, &[("idList".to_string(), "l-doing".to_string())]);
+
+        let (source, _) = source(vec![lists(), card(1, "l-doing", &["rlph"])]);
+        let err = source.mark_in_progress("1").unwrap_err();
+        assert!(
+            matches!(&err, Error::TaskClaimConflict { task_id, .. } if task_id == "1"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_fetch_closed_task_ids_includes_done_and_archived() {
+        let (source, _) = source(vec![
+            lists(),
+            json!([card(1, "l-done", &[]), card(2, "l-todo", &[])]),
+            json!([card(7, "l-doing", &[])]),
+        ]);
+        assert_eq!(
+            source.fetch_closed_task_ids().unwrap(),
+            HashSet::from([1, 7])
+        );
+    }
+}
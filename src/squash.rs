@@ -0,0 +1,220 @@
+use std::path::Path;
+
+use tracing::info;
+
+use crate::config::SquashMode;
+use crate::error::{Error, Result};
+use crate::worktree::git_in_dir;
+
+const CO_AUTHOR_KEY: &str = "Co-authored-by";
+
+/// Squash the commits on HEAD made after `review_start` (the fix rounds).
+///
+/// In [`SquashMode::Logical`] they are folded into the last commit made before
+/// review, keeping its message. In [`SquashMode::Single`] the whole branch
+/// since its merge base with `base_ref` becomes one commit titled `title`,
+/// listing the original commit subjects. Either way, `Co-authored-by:`
+/// trailers of the squashed commits are kept.
+///
+/// Returns whether history was rewritten. On failure HEAD is left where it was.
+pub fn squash_branch(
+    worktree: &Path,
+    mode: SquashMode,
+    base_ref: &str,
+    review_start: &str,
+    title: &str,
+) -> Result<bool> {
+    let git = |args: &[&str]| {
+        git_in_dir(worktree, args).map_err(|e| {
+            Error::Worktree(format!(
+                "git {} failed in {}: {}",
+                args.join(" "),
+                worktree.display(),
+                e.trim()
+            ))
+        })
+    };
+    let count = |range: &str| -> Result<usize> {
+        Ok(git(&["rev-list", "--count", range])?
+            .trim()
+            .parse()
+            .unwrap_or_default())
+    };
+
+    let head = git(&["rev-parse", "HEAD"])?.trim().to_string();
+    let base = git(&["merge-base", "HEAD", base_ref])?.trim().to_string();
+    let fixes = count(&format!("{review_start}..HEAD"))?;
+    let logical = count(&format!("{base}..{review_start}"))?;
+
+    let (reset_to, message, amend) = if mode == SquashMode::Logical && logical > 0 {
+        if fixes == 0 {
+            return Ok(false);
+        }
+        let message = git(&["log", "-1", "--format=%B", review_start])?;
+        let trailers = co_author_trailers(&git(&[
+            "log",
+            "--reverse",
+            "--format=%(trailers:key=Co-authored-by)",
+            &format!("{review_start}..HEAD"),
+        ])?);
+        (
+            review_start.to_string(),
+            with_trailers(&message, &trailers),
+            true,
+        )
+    } else {
+        if count(&format!("{base}..HEAD"))? <= 1 {
+            return Ok(false);
+        }
+        let subjects = git(&[
+            "log",
+            "--reverse",
+            "--format=%s",
+            &format!("{base}..{review_start}"),
+        ])?;
+        let mut message = title.trim().to_string();
+        let subjects: Vec<&str> = subjects.lines().filter(|s| !s.is_empty()).collect();
+        if subjects.len() > 1 {
+            message.push_str("\n\n");
+            for subject in subjects {
+                message.push_str(&format!("- {subject}\n"));
+            }
+        }
+        let trailers = co_author_trailers(&git(&[
+            "log",
+            "--reverse",
+            "--format=%(trailers:key=Co-authored-by)",
+            &format!("{base}..HEAD"),
+        ])?);
+        (base, with_trailers(&message, &trailers), false)
+    };
+
+    git(&["reset", "--soft", &reset_to])?;
+    let mut args = vec!["commit", "--quiet", "--no-verify", "--allow-empty"];
+    if amend {
+        args.push("--amend");
+    }
+    args.extend(["-m", &message]);
+    if let Err(e) = git(&args) {
+        let _ = git_in_dir(worktree, &["reset", "--soft", &head]);
+        return Err(e);
+    }
+    info!(?mode, fix_commits = fixes, "squashed fix rounds");
+    Ok(true)
+}
+
+/// Distinct `Co-authored-by:` lines from `git log --format=%(trailers:...)`
+/// output, in first-seen order.
+fn co_author_trailers(log: &str) -> Vec<String> {
+    let mut trailers: Vec<String> = Vec::new();
+    for line in log.lines().map(str::trim) {
+        if line.starts_with(CO_AUTHOR_KEY) && !trailers.iter().any(|t| t == line) {
+            trailers.push(line.to_string());
+        }
+    }
+    trailers
+}
+
+/// `message` with each of `trailers` it does not already contain appended as
+/// a trailer block.
+fn with_trailers(message: &str, trailers: &[String]) -> String {
+    let message = message.trim_end();
+    let missing: Vec<&str> = trailers
+        .iter()
+        .map(String::as_str)
+        .filter(|t| !message.lines().any(|line| line.trim() == *t))
+        .collect();
+    if missing.is_empty() {
+        return message.to_string();
+    }
+    let ends_with_trailers = message
+        .lines()
+        .last()
+        .is_some_and(|line| line.starts_with(CO_AUTHOR_KEY));
+    let separator = if ends_with_trailers { "\n" } else { "\n\n" };
+    format!("{message}{separator}{}", missing.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn repo_with_fix_rounds() -> (TempDir, String, String) {
+        let repo = TempDir::new().unwrap();
+        let git = |args: &[&str]| git_in_dir(repo.path(), args).unwrap();
+        git(&["init", "-q"]);
+        git(&["config", "user.name", "t"]);
+        git(&["config", "user.email", "t@t"]);
+        let commit = |msg: &str| git(&["commit", "-q", "--allow-empty", "-m", msg]);
+        commit("base");
+        let base = git(&["rev-parse", "HEAD"]).trim().to_string();
+        commit("Add parser");
+        commit("Wire parser into cli");
+        let review_start = git(&["rev-parse", "HEAD"]).trim().to_string();
+        commit("address review\n\nCo-authored-by: rlph security reviewer <r@x>\nRlph-Fix-Round: 1");
+        commit("address review\n\nCo-authored-by: alice <a@x>\nRlph-Fix-Round: feedback");
+        (repo, base, review_start)
+    }
+
+    fn messages(repo: &Path) -> Vec<String> {
+        git_in_dir(repo, &["log", "--format=%B%x00"])
+            .unwrap()
+            .split('\0')
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn test_logical_folds_fixes_into_last_commit() {
+        let (repo, base, review_start) = repo_with_fix_rounds();
+        let squashed =
+            squash_branch(repo.path(), SquashMode::Logical, &base, &review_start, "t").unwrap();
+        assert!(squashed);
+        assert_eq!(
+            messages(repo.path()),
+            [
+                "Wire parser into cli\n\nCo-authored-by: rlph security reviewer <r@x>\nCo-authored-by: alice <a@x>",
+                "Add parser",
+                "base",
+            ]
+        );
+        let head = git_in_dir(repo.path(), &["rev-parse", "HEAD"]).unwrap();
+        assert!(!squash_branch(repo.path(), SquashMode::Logical, &base, head.trim(), "t").unwrap());
+    }
+
+    #[test]
+    fn test_single_squashes_whole_branch() {
+        let (repo, base, review_start) = repo_with_fix_rounds();
+        let squashed = squash_branch(
+            repo.path(),
+            SquashMode::Single,
+            &base,
+            &review_start,
+            "Add a parser",
+        )
+        .unwrap();
+        assert!(squashed);
+        assert_eq!(
+            messages(repo.path()),
+            [
+                "Add a parser\n\n- Add parser\n- Wire parser into cli\n\nCo-authored-by: rlph security reviewer <r@x>\nCo-authored-by: alice <a@x>",
+                "base",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_with_trailers_skips_present_lines() {
+        let message = "Fix\n\nCo-authored-by: a <a@x>\n";
+        let trailers = [
+            "Co-authored-by: a <a@x>".to_string(),
+            "Co-authored-by: b <b@x>".to_string(),
+        ];
+        assert_eq!(
+            with_trailers(message, &trailers),
+            "Fix\n\nCo-authored-by: a <a@x>\nCo-authored-by: b <b@x>"
+        );
+    }
+}
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use tracing::warn;
 
 use crate::error::{Error, Result};
+use crate::submission::FeedbackKind;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CurrentTask {
@@ -21,6 +22,182 @@ pub struct CompletedTask {
     pub completed_at: u64,
 }
 
+/// A failed attempt at a task, kept so later attempts can learn from it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FailedAttempt {
+    pub id: String,
+    pub phase: String,
+    pub error: String,
+    pub failed_at: u64,
+}
+
+/// Retry bookkeeping for a task whose iterations keep failing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RetryEntry {
+    pub attempts: u32,
+    pub last_error: String,
+    /// Unix time before which the task is not picked up again.
+    pub next_retry_at: u64,
+    /// Set once the task ran out of attempts and was labeled as failed.
+    #[serde(default)]
+    pub gave_up: bool,
+}
+
+impl RetryEntry {
+    /// Whether the backoff has elapsed and the task may be attempted again.
+    pub fn is_due(&self) -> bool {
+        now_secs() >= self.next_retry_at
+    }
+}
+
+/// Failed attempts kept per task, all of which are surfaced to the agent.
+pub const MAX_PREVIOUS_ATTEMPTS: usize = 3;
+
+/// Upper bound on the delay between retries of a failing task.
+pub const MAX_RETRY_BACKOFF_SECS: u64 = 24 * 60 * 60;
+
+/// Exponential backoff: `base_secs` after the first failure, doubling per
+/// further attempt, capped at [`MAX_RETRY_BACKOFF_SECS`].
+pub fn retry_backoff_secs(base_secs: u64, attempts: u32) -> u64 {
+    let factor = 1u64
+        .checked_shl(attempts.saturating_sub(1))
+        .unwrap_or(u64::MAX);
+    base_secs.saturating_mul(factor).min(MAX_RETRY_BACKOFF_SECS)
+}
+
+/// Key of a PR's feedback cursor for `kind`. Conversation comments keep the
+/// bare PR number used before review feedback had cursors of its own.
+fn feedback_cursor_key(pr_number: u64, kind: FeedbackKind) -> String {
+    match kind {
+        FeedbackKind::Comment => pr_number.to_string(),
+        FeedbackKind::ReviewComment => format!("{pr_number}/review-comments"),
+        FeedbackKind::Review => format!("{pr_number}/reviews"),
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// A cached task size estimate. `fingerprint` identifies the task content it was
+/// computed from so edits to the task trigger a re-estimate.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaskEstimate {
+    pub complexity: String,
+    #[serde(default)]
+    pub risk_notes: String,
+    pub fingerprint: String,
+    pub estimated_at: u64,
+}
+
+/// A PR opened by rlph, kept until it is merged or closed and cleaned up.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrackedPr {
+    pub task_id: String,
+    pub branch: String,
+    #[serde(default)]
+    pub url: String,
+    /// When rlph opened (or, for PRs tracked before this was recorded, first
+    /// saw) the PR; `0` when not known yet.
+    #[serde(default)]
+    pub opened_at: u64,
+    /// `prompt_version` of the run that opened (or last re-tracked) the PR;
+    /// empty for PRs tracked before it was recorded.
+    #[serde(default)]
+    pub prompt_version: String,
+}
+
+/// The PR that delivered a completed task, kept after the PR is merged so a
+/// re-added label does not make rlph redo the task.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeliveredTask {
+    pub pr_number: u64,
+    #[serde(default)]
+    pub url: String,
+    /// Branch head when the task completed; empty when it could not be read.
+    #[serde(default)]
+    pub head_sha: String,
+    pub delivered_at: u64,
+    /// Whether the issue was already told why rlph skips it.
+    #[serde(default)]
+    pub skip_noted: bool,
+}
+
+/// A human-authored PR rlph manages after `rlph adopt-pr`, kept until it is
+/// merged or closed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AdoptedPr {
+    pub task_id: String,
+    pub branch: String,
+    #[serde(default)]
+    pub url: String,
+    /// Head commit the last review finished on. A different PR head means someone
+    /// pushed since, and the PR is reviewed again.
+    #[serde(default)]
+    pub reviewed_sha: String,
+}
+
+/// The commit the last aggregated review round of a PR covered, kept for
+/// `differential_review`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReviewedHead {
+    pub sha: String,
+    /// Findings of that round, as a JSON array.
+    #[serde(default)]
+    pub findings: String,
+}
+
+/// Closed task IDs kept between iterations for dependency resolution.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClosedTaskCache {
+    /// Source and label the IDs were fetched for; the cache is ignored when
+    /// either changes.
+    pub scope: String,
+    pub ids: Vec<u64>,
+    /// Unix time of the last full fetch.
+    pub full_sync_at: u64,
+    /// Unix time of the last fetch, full or incremental.
+    pub synced_at: u64,
+}
+
+/// Progress of a review pipeline, saved as it runs so `rlph resume` can pick an
+/// interrupted review up at the round it reached.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReviewCheckpoint {
+    pub task_id: String,
+    pub round: u32,
+    pub worktree_path: String,
+    pub branch: String,
+    pub pr_number: Option<u64>,
+    pub push_remote_branch: Option<String>,
+    /// Review-only runs post findings but never run the fix agent.
+    #[serde(default)]
+    pub review_only: bool,
+    /// Fail the review if agents modify the worktree (`rlph review` without `--allow-writes`).
+    #[serde(default)]
+    pub guard_writes: bool,
+    /// Prompt variables the pipeline was started with.
+    #[serde(default)]
+    pub vars: HashMap<String, String>,
+    /// Findings of the last aggregated round, as a JSON array.
+    #[serde(default)]
+    pub last_findings: String,
+    /// Phases that finished in the current round, keyed by phase name.
+    #[serde(default)]
+    pub phases: HashMap<String, PhaseCheckpoint>,
+}
+
+/// Raw output of a finished review phase.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhaseCheckpoint {
+    pub stdout: String,
+    #[serde(default)]
+    pub from_command: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct StateData {
     pub current_task: Option<CurrentTask>,
@@ -28,6 +205,38 @@ pub struct StateData {
     pub history: Vec<CompletedTask>,
     #[serde(default)]
     pub worktree_mappings: HashMap<String, String>,
+    /// Highest PR feedback ID already handled by the feedback loop, keyed by PR
+    /// number, plus a suffix for review comments and reviews.
+    #[serde(default)]
+    pub pr_feedback_cursors: HashMap<String, u64>,
+    /// Latest estimate per task ID.
+    #[serde(default)]
+    pub estimates: HashMap<String, TaskEstimate>,
+    #[serde(default)]
+    pub failed_attempts: Vec<FailedAttempt>,
+    /// Tasks waiting out a backoff after failing, keyed by task ID.
+    #[serde(default)]
+    pub retry_queue: HashMap<String, RetryEntry>,
+    /// rlph's PRs awaiting post-merge cleanup, keyed by PR number.
+    #[serde(default)]
+    pub tracked_prs: HashMap<String, TrackedPr>,
+    /// Human-authored PRs under rlph's review loop, keyed by PR number.
+    #[serde(default)]
+    pub adopted_prs: HashMap<String, AdoptedPr>,
+    /// PRs that delivered completed tasks, keyed by task ID.
+    #[serde(default)]
+    pub delivered_tasks: HashMap<String, DeliveredTask>,
+    /// Last reviewed commit and its findings, keyed by PR number.
+    #[serde(default)]
+    pub reviewed_heads: HashMap<String, ReviewedHead>,
+    /// The review in progress, if any.
+    #[serde(default)]
+    pub review_checkpoint: Option<ReviewCheckpoint>,
+    #[serde(default)]
+    pub closed_tasks: Option<ClosedTaskCache>,
+    /// Default branch of `origin`, detected when `base_branch` is not configured.
+    #[serde(default)]
+    pub detected_base_branch: Option<String>,
 }
 
 /// Manages local state persisted as TOML in `.rlph/state/`.
@@ -158,7 +367,8 @@ impl StateManager {
         })
     }
 
-    /// Mark the current task as completed and move it to history.
+    /// Mark the current task as completed and move it to history, forgetting
+    /// its failed attempts.
     pub fn complete_current_task(&self) -> Result<()> {
         self.modify(|state| {
             if let Some(task) = state.current_task.take() {
@@ -166,6 +376,7 @@ impl StateManager {
                     .duration_since(UNIX_EPOCH)
                     .unwrap_or_default()
                     .as_secs();
+                state.failed_attempts.retain(|a| a.id != task.id);
                 state.history.push(CompletedTask {
                     id: task.id,
                     completed_at: timestamp,
@@ -174,6 +385,98 @@ impl StateManager {
         })
     }
 
+    /// Record a failed attempt at the current task, using its current phase,
+    /// keeping only its latest [`MAX_PREVIOUS_ATTEMPTS`]. No-op when no task is
+    /// in progress.
+    pub fn record_failed_attempt(&self, error: &str) -> Result<()> {
+        let error = error.to_string();
+        self.modify(|state| {
+            if let Some(task) = &state.current_task {
+                let timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                state.failed_attempts.push(FailedAttempt {
+                    id: task.id.clone(),
+                    phase: task.phase.clone(),
+                    error,
+                    failed_at: timestamp,
+                });
+                let mut excess = state
+                    .failed_attempts
+                    .iter()
+                    .filter(|a| a.id == task.id)
+                    .count()
+                    .saturating_sub(MAX_PREVIOUS_ATTEMPTS);
+                state.failed_attempts.retain(|a| {
+                    let drop = excess > 0 && a.id == task.id;
+                    if drop {
+                        excess -= 1;
+                    }
+                    !drop
+                });
+            }
+        })
+    }
+
+    /// Failed attempts for a task, oldest first.
+    pub fn failed_attempts(&self, task_id: &str) -> Vec<FailedAttempt> {
+        self.load()
+            .failed_attempts
+            .into_iter()
+            .filter(|a| a.id == task_id)
+            .collect()
+    }
+
+    /// Count a failure of `task_id` and schedule its next retry with exponential
+    /// backoff from `base_backoff_secs`. Returns the updated entry.
+    pub fn schedule_retry(
+        &self,
+        task_id: &str,
+        error: &str,
+        base_backoff_secs: u64,
+    ) -> Result<RetryEntry> {
+        let mut updated = None;
+        self.modify(|state| {
+            let entry = state
+                .retry_queue
+                .entry(task_id.to_string())
+                .or_insert_with(|| RetryEntry {
+                    attempts: 0,
+                    last_error: String::new(),
+                    next_retry_at: 0,
+                    gave_up: false,
+                });
+            entry.attempts += 1;
+            entry.last_error = error.to_string();
+            entry.next_retry_at =
+                now_secs() + retry_backoff_secs(base_backoff_secs, entry.attempts);
+            updated = Some(entry.clone());
+        })?;
+        Ok(updated.expect("retry entry inserted"))
+    }
+
+    /// Record that a task ran out of attempts and was labeled as failed.
+    pub fn mark_retry_gave_up(&self, task_id: &str) -> Result<()> {
+        self.modify(|state| {
+            if let Some(entry) = state.retry_queue.get_mut(task_id) {
+                entry.gave_up = true;
+            }
+        })
+    }
+
+    /// Get the retry entry for a task, if it has failed before.
+    pub fn retry_entry(&self, task_id: &str) -> Option<RetryEntry> {
+        self.load().retry_queue.get(task_id).cloned()
+    }
+
+    /// Drop a task from the retry queue (after success or a manual re-queue).
+    pub fn clear_retry(&self, task_id: &str) -> Result<()> {
+        self.modify(|state| {
+            state.retry_queue.remove(task_id);
+        })
+    }
+
     /// Clear the current task without adding to history.
     pub fn clear_current_task(&self) -> Result<()> {
         self.modify(|state| {
@@ -194,6 +497,294 @@ impl StateManager {
         let state = self.load();
         state.worktree_mappings.get(task_id).cloned()
     }
+
+    /// Get the last handled feedback ID of `kind` for a PR (0 if none).
+    pub fn pr_feedback_cursor(&self, pr_number: u64, kind: FeedbackKind) -> u64 {
+        let state = self.load();
+        state
+            .pr_feedback_cursors
+            .get(&feedback_cursor_key(pr_number, kind))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Record the last handled feedback ID of `kind` for a PR.
+    pub fn set_pr_feedback_cursor(
+        &self,
+        pr_number: u64,
+        kind: FeedbackKind,
+        comment_id: u64,
+    ) -> Result<()> {
+        self.modify(|state| {
+            state
+                .pr_feedback_cursors
+                .insert(feedback_cursor_key(pr_number, kind), comment_id);
+        })
+    }
+
+    /// Remember a PR opened for `task_id` so it can be cleaned up once it is
+    /// merged or closed.
+    pub fn track_pr(
+        &self,
+        pr_number: u64,
+        task_id: &str,
+        branch: &str,
+        url: &str,
+        prompt_version: &str,
+    ) -> Result<()> {
+        let pr = TrackedPr {
+            task_id: task_id.to_string(),
+            branch: branch.to_string(),
+            url: url.to_string(),
+            opened_at: now_secs(),
+            prompt_version: prompt_version.to_string(),
+        };
+        self.modify(|state| {
+            state.tracked_prs.insert(pr_number.to_string(), pr);
+        })
+    }
+
+    /// Record the PR that delivered `task_id`, replacing any earlier delivery.
+    pub fn record_delivery(
+        &self,
+        task_id: &str,
+        pr_number: u64,
+        url: &str,
+        head_sha: &str,
+    ) -> Result<()> {
+        let delivered = DeliveredTask {
+            pr_number,
+            url: url.to_string(),
+            head_sha: head_sha.to_string(),
+            delivered_at: now_secs(),
+            skip_noted: false,
+        };
+        self.modify(|state| {
+            state.delivered_tasks.insert(task_id.to_string(), delivered);
+        })
+    }
+
+    /// The PR that delivered `task_id`, if it was completed before.
+    pub fn delivery(&self, task_id: &str) -> Option<DeliveredTask> {
+        self.load().delivered_tasks.remove(task_id)
+    }
+
+    /// Remember that the issue of `task_id` was told it is skipped.
+    pub fn mark_delivery_skip_noted(&self, task_id: &str) -> Result<()> {
+        self.modify(|state| {
+            if let Some(delivered) = state.delivered_tasks.get_mut(task_id) {
+                delivered.skip_noted = true;
+            }
+        })
+    }
+
+    /// The base branch detected on an earlier run, if any.
+    pub fn detected_base_branch(&self) -> Option<String> {
+        self.load().detected_base_branch
+    }
+
+    pub fn set_detected_base_branch(&self, branch: &str) -> Result<()> {
+        self.modify(|state| {
+            state.detected_base_branch = Some(branch.to_string());
+        })
+    }
+
+    /// Forget the delivery of `task_id`, e.g. after its PR was closed unmerged.
+    pub fn forget_delivery(&self, task_id: &str) -> Result<()> {
+        self.modify(|state| {
+            state.delivered_tasks.remove(task_id);
+        })
+    }
+
+    /// Record `opened_at` for a tracked PR that has none.
+    pub fn set_pr_opened_at(&self, pr_number: u64, opened_at: u64) -> Result<()> {
+        self.modify(|state| {
+            if let Some(pr) = state.tracked_prs.get_mut(&pr_number.to_string())
+                && pr.opened_at == 0
+            {
+                pr.opened_at = opened_at;
+            }
+        })
+    }
+
+    /// Tracked PRs, ordered by PR number.
+    pub fn tracked_prs(&self) -> Vec<(u64, TrackedPr)> {
+        let mut prs: Vec<_> = self
+            .load()
+            .tracked_prs
+            .into_iter()
+            .filter_map(|(number, pr)| Some((number.parse().ok()?, pr)))
+            .collect();
+        prs.sort_by_key(|(number, _)| *number);
+        prs
+    }
+
+    /// Forget a PR along with its feedback cursor and reviewed head.
+    pub fn untrack_pr(&self, pr_number: u64) -> Result<()> {
+        let key = pr_number.to_string();
+        self.modify(|state| {
+            state.tracked_prs.remove(&key);
+            for kind in FeedbackKind::ALL {
+                state
+                    .pr_feedback_cursors
+                    .remove(&feedback_cursor_key(pr_number, kind));
+            }
+            state.reviewed_heads.remove(&key);
+        })
+    }
+
+    /// The commit a PR was last reviewed at, with that review's findings.
+    pub fn reviewed_head(&self, pr_number: u64) -> Option<ReviewedHead> {
+        self.load().reviewed_heads.remove(&pr_number.to_string())
+    }
+
+    /// Record the commit a review round of a PR covered and its findings.
+    pub fn set_reviewed_head(&self, pr_number: u64, sha: &str, findings: &str) -> Result<()> {
+        let head = ReviewedHead {
+            sha: sha.to_string(),
+            findings: findings.to_string(),
+        };
+        self.modify(|state| {
+            state.reviewed_heads.insert(pr_number.to_string(), head);
+        })
+    }
+
+    /// Start managing an adopted PR, replacing any earlier record of it.
+    pub fn adopt_pr(&self, pr_number: u64, pr: AdoptedPr) -> Result<()> {
+        self.modify(|state| {
+            state.adopted_prs.insert(pr_number.to_string(), pr);
+        })
+    }
+
+    /// Adopted PRs, ordered by PR number.
+    pub fn adopted_prs(&self) -> Vec<(u64, AdoptedPr)> {
+        let mut prs: Vec<_> = self
+            .load()
+            .adopted_prs
+            .into_iter()
+            .filter_map(|(number, pr)| Some((number.parse().ok()?, pr)))
+            .collect();
+        prs.sort_by_key(|(number, _)| *number);
+        prs
+    }
+
+    /// Record the head commit an adopted PR was last reviewed at.
+    pub fn set_adopted_reviewed_sha(&self, pr_number: u64, sha: &str) -> Result<()> {
+        self.modify(|state| {
+            if let Some(pr) = state.adopted_prs.get_mut(&pr_number.to_string()) {
+                pr.reviewed_sha = sha.to_string();
+            }
+        })
+    }
+
+    /// Stop managing an adopted PR and forget its feedback cursor and reviewed
+    /// head.
+    pub fn release_adopted_pr(&self, pr_number: u64) -> Result<()> {
+        let key = pr_number.to_string();
+        self.modify(|state| {
+            state.adopted_prs.remove(&key);
+            for kind in FeedbackKind::ALL {
+                state
+                    .pr_feedback_cursors
+                    .remove(&feedback_cursor_key(pr_number, kind));
+            }
+            state.reviewed_heads.remove(&key);
+        })
+    }
+
+    /// The saved review checkpoint, if any.
+    pub fn review_checkpoint(&self) -> Option<ReviewCheckpoint> {
+        self.load().review_checkpoint
+    }
+
+    /// Replace the review checkpoint.
+    pub fn set_review_checkpoint(&self, checkpoint: ReviewCheckpoint) -> Result<()> {
+        self.modify(|state| {
+            state.review_checkpoint = Some(checkpoint);
+        })
+    }
+
+    /// Move the checkpoint to a new round, dropping the previous round's phase
+    /// outputs. No-op without a checkpoint.
+    pub fn begin_review_round(&self, round: u32, last_findings: &str) -> Result<()> {
+        let last_findings = last_findings.to_string();
+        self.modify(|state| {
+            if let Some(ref mut checkpoint) = state.review_checkpoint {
+                checkpoint.round = round;
+                checkpoint.last_findings = last_findings;
+                checkpoint.phases.clear();
+            }
+        })
+    }
+
+    /// Record a finished phase in the current round. No-op without a checkpoint.
+    pub fn record_review_phase(&self, name: &str, stdout: &str, from_command: bool) -> Result<()> {
+        let name = name.to_string();
+        let phase = PhaseCheckpoint {
+            stdout: stdout.to_string(),
+            from_command,
+        };
+        self.modify(|state| {
+            if let Some(ref mut checkpoint) = state.review_checkpoint {
+                checkpoint.phases.insert(name, phase);
+            }
+        })
+    }
+
+    /// Drop the review checkpoint.
+    pub fn clear_review_checkpoint(&self) -> Result<()> {
+        self.modify(|state| {
+            state.review_checkpoint = None;
+        })
+    }
+
+    /// The cached closed task IDs, if any.
+    pub fn closed_task_cache(&self) -> Option<ClosedTaskCache> {
+        self.load().closed_tasks
+    }
+
+    /// Replace the cached closed task IDs.
+    pub fn set_closed_task_cache(&self, cache: ClosedTaskCache) -> Result<()> {
+        self.modify(|state| {
+            state.closed_tasks = Some(cache);
+        })
+    }
+
+    /// Drop the cached closed task IDs so the next lookup fetches them all.
+    pub fn clear_closed_task_cache(&self) -> Result<()> {
+        self.modify(|state| {
+            state.closed_tasks = None;
+        })
+    }
+
+    /// Get the recorded estimate for a task.
+    pub fn get_estimate(&self, task_id: &str) -> Option<TaskEstimate> {
+        let state = self.load();
+        state.estimates.get(task_id).cloned()
+    }
+
+    /// Record an estimate for a task, replacing any previous one.
+    pub fn set_estimate(
+        &self,
+        task_id: &str,
+        complexity: &str,
+        risk_notes: &str,
+        fingerprint: &str,
+    ) -> Result<()> {
+        let estimate = TaskEstimate {
+            complexity: complexity.to_string(),
+            risk_notes: risk_notes.to_string(),
+            fingerprint: fingerprint.to_string(),
+            estimated_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+        let task_id = task_id.to_string();
+        self.modify(|state| {
+            state.estimates.insert(task_id, estimate);
+        })
+    }
 }
 
 #[cfg(test)]
@@ -231,6 +822,92 @@ mod tests {
                 ("gh-5".to_string(), "/tmp/wt".to_string()),
                 ("gh-3".to_string(), "/tmp/old".to_string()),
             ]),
+            pr_feedback_cursors: HashMap::from([("12".to_string(), 345)]),
+            estimates: HashMap::from([(
+                "gh-5".to_string(),
+                TaskEstimate {
+                    complexity: "M".to_string(),
+                    risk_notes: "touches config".to_string(),
+                    fingerprint: "abc123".to_string(),
+                    estimated_at: 1700000000,
+                },
+            )]),
+            failed_attempts: vec![FailedAttempt {
+                id: "gh-3".to_string(),
+                phase: "review".to_string(),
+                error: "review did not complete".to_string(),
+                failed_at: 1690000000,
+            }],
+            retry_queue: HashMap::from([(
+                "gh-4".to_string(),
+                RetryEntry {
+                    attempts: 2,
+                    last_error: "agent timed out".to_string(),
+                    next_retry_at: 1690000600,
+                    gave_up: false,
+                },
+            )]),
+            tracked_prs: HashMap::from([(
+                "12".to_string(),
+                TrackedPr {
+                    task_id: "gh-5".to_string(),
+                    branch: "rlph-5-fix".to_string(),
+                    url: "https://github.com/o/r/pull/12".to_string(),
+                    opened_at: 1690000000,
+                    prompt_version: "v1".to_string(),
+                },
+            )]),
+            adopted_prs: HashMap::from([(
+                "15".to_string(),
+                AdoptedPr {
+                    task_id: "gh-8".to_string(),
+                    branch: "feature/login".to_string(),
+                    url: "https://github.com/o/r/pull/15".to_string(),
+                    reviewed_sha: "abc123".to_string(),
+                },
+            )]),
+            delivered_tasks: HashMap::from([(
+                "gh-3".to_string(),
+                DeliveredTask {
+                    pr_number: 11,
+                    url: "https://github.com/o/r/pull/11".to_string(),
+                    head_sha: "def456".to_string(),
+                    delivered_at: 1700000000,
+                    skip_noted: true,
+                },
+            )]),
+            reviewed_heads: HashMap::from([(
+                "12".to_string(),
+                ReviewedHead {
+                    sha: "abc123".to_string(),
+                    findings: "[]".to_string(),
+                },
+            )]),
+            review_checkpoint: Some(ReviewCheckpoint {
+                task_id: "gh-5".to_string(),
+                round: 2,
+                worktree_path: "/tmp/wt".to_string(),
+                branch: "rlph-5-fix".to_string(),
+                pr_number: Some(12),
+                push_remote_branch: None,
+                review_only: false,
+                guard_writes: false,
+                vars: HashMap::from([("issue_number".to_string(), "5".to_string())]),
+                last_findings: "[]".to_string(),
+                phases: HashMap::from([(
+                    "security".to_string(),
+                    PhaseCheckpoint {
+                        stdout: r#"{"findings":[]}"#.to_string(),
+                        from_command: false,
+                    },
+                )]),
+            }),
+            closed_tasks: Some(ClosedTaskCache {
+                scope: "github:rlph".to_string(),
+                ids: vec![3, 4],
+                full_sync_at: 1700000000,
+                synced_at: 1700000600,
+            }),
         };
         mgr.save(&state).unwrap();
         let loaded = mgr.load();
@@ -325,6 +1002,217 @@ mod tests {
         assert!(mgr.get_worktree_path("gh-999").is_none());
     }
 
+    #[test]
+    fn test_pr_feedback_cursor() {
+        let (_dir, mgr) = test_manager();
+        assert_eq!(mgr.pr_feedback_cursor(12, FeedbackKind::Comment), 0);
+        mgr.set_pr_feedback_cursor(12, FeedbackKind::Comment, 345)
+            .unwrap();
+        mgr.set_pr_feedback_cursor(12, FeedbackKind::Review, 7)
+            .unwrap();
+        assert_eq!(mgr.pr_feedback_cursor(12, FeedbackKind::Comment), 345);
+        assert_eq!(mgr.pr_feedback_cursor(12, FeedbackKind::Review), 7);
+        assert_eq!(mgr.pr_feedback_cursor(12, FeedbackKind::ReviewComment), 0);
+        assert_eq!(mgr.pr_feedback_cursor(13, FeedbackKind::Comment), 0);
+    }
+
+    #[test]
+    fn test_tracked_pr_lifecycle() {
+        let (_dir, mgr) = test_manager();
+        assert!(mgr.tracked_prs().is_empty());
+        mgr.track_pr(20, "gh-7", "rlph-7-b", "https://x/pull/20", "v1")
+            .unwrap();
+        mgr.track_pr(9, "gh-3", "rlph-3-a", "https://x/pull/9", "v1")
+            .unwrap();
+        mgr.set_pr_feedback_cursor(9, FeedbackKind::Review, 100)
+            .unwrap();
+        mgr.set_reviewed_head(9, "abc123", r#"[{"id":"x"}]"#)
+            .unwrap();
+        let head = mgr.reviewed_head(9).unwrap();
+        assert_eq!(head.sha, "abc123");
+        assert_eq!(head.findings, r#"[{"id":"x"}]"#);
+
+        let prs = mgr.tracked_prs();
+        assert_eq!(prs.iter().map(|(n, _)| *n).collect::<Vec<_>>(), vec![9, 20]);
+        assert_eq!(prs[0].1.task_id, "gh-3");
+        assert_eq!(prs[0].1.branch, "rlph-3-a");
+        assert!(prs[0].1.opened_at > 0);
+        assert_eq!(prs[0].1.prompt_version, "v1");
+
+        mgr.set_pr_opened_at(9, 1).unwrap();
+        assert_eq!(mgr.tracked_prs()[0].1.opened_at, prs[0].1.opened_at);
+
+        mgr.untrack_pr(9).unwrap();
+        assert_eq!(mgr.tracked_prs().len(), 1);
+        assert_eq!(mgr.pr_feedback_cursor(9, FeedbackKind::Review), 0);
+        assert_eq!(mgr.reviewed_head(9), None);
+    }
+
+    #[test]
+    fn test_delivery_ledger() {
+        let (_dir, mgr) = test_manager();
+        assert_eq!(mgr.delivery("gh-7"), None);
+        mgr.record_delivery("gh-7", 20, "https://x/pull/20", "abc123")
+            .unwrap();
+
+        let delivered = mgr.delivery("gh-7").unwrap();
+        assert_eq!(delivered.pr_number, 20);
+        assert_eq!(delivered.head_sha, "abc123");
+        assert!(!delivered.skip_noted);
+
+        mgr.mark_delivery_skip_noted("gh-7").unwrap();
+        assert!(mgr.delivery("gh-7").unwrap().skip_noted);
+        // A new delivery starts unnoted.
+        mgr.record_delivery("gh-7", 21, "", "def456").unwrap();
+        assert!(!mgr.delivery("gh-7").unwrap().skip_noted);
+
+        mgr.forget_delivery("gh-7").unwrap();
+        assert_eq!(mgr.delivery("gh-7"), None);
+    }
+
+    #[test]
+    fn test_adopted_pr_lifecycle() {
+        let (_dir, mgr) = test_manager();
+        assert!(mgr.adopted_prs().is_empty());
+        let adopted = |task_id: &str, branch: &str| AdoptedPr {
+            task_id: task_id.to_string(),
+            branch: branch.to_string(),
+            url: String::new(),
+            reviewed_sha: String::new(),
+        };
+        mgr.adopt_pr(31, adopted("pr-31", "feature/b")).unwrap();
+        mgr.adopt_pr(4, adopted("gh-2", "feature/a")).unwrap();
+        mgr.set_adopted_reviewed_sha(4, "deadbeef").unwrap();
+        mgr.set_adopted_reviewed_sha(99, "ignored").unwrap();
+        mgr.set_pr_feedback_cursor(4, FeedbackKind::Review, 50)
+            .unwrap();
+        mgr.set_reviewed_head(4, "deadbeef", "[]").unwrap();
+
+        let prs = mgr.adopted_prs();
+        assert_eq!(prs.iter().map(|(n, _)| *n).collect::<Vec<_>>(), vec![4, 31]);
+        assert_eq!(prs[0].1.reviewed_sha, "deadbeef");
+        assert_eq!(prs[1].1.reviewed_sha, "");
+
+        mgr.release_adopted_pr(4).unwrap();
+        assert_eq!(mgr.adopted_prs().len(), 1);
+        assert_eq!(mgr.pr_feedback_cursor(4, FeedbackKind::Review), 0);
+        assert_eq!(mgr.reviewed_head(4), None);
+    }
+
+    #[test]
+    fn test_review_checkpoint_rounds() {
+        let (_dir, mgr) = test_manager();
+        mgr.record_review_phase("ignored", "{}", false).unwrap();
+        assert!(mgr.review_checkpoint().is_none());
+
+        mgr.set_review_checkpoint(ReviewCheckpoint {
+            task_id: "gh-4".to_string(),
+            round: 1,
+            worktree_path: "/tmp/wt4".to_string(),
+            branch: "rlph-4-fix".to_string(),
+            pr_number: Some(12),
+            push_remote_branch: None,
+            review_only: false,
+            guard_writes: false,
+            vars: HashMap::from([("issue_number".to_string(), "4".to_string())]),
+            last_findings: "[]".to_string(),
+            phases: HashMap::new(),
+        })
+        .unwrap();
+        mgr.record_review_phase("security", r#"{"findings":[]}"#, false)
+            .unwrap();
+        mgr.record_review_phase("clippy", r#"{"findings":[]}"#, true)
+            .unwrap();
+
+        let checkpoint = mgr.review_checkpoint().unwrap();
+        assert_eq!(checkpoint.phases.len(), 2);
+        assert!(checkpoint.phases["clippy"].from_command);
+        assert_eq!(checkpoint.vars["issue_number"], "4");
+
+        mgr.begin_review_round(2, r#"[{"id":"x"}]"#).unwrap();
+        let checkpoint = mgr.review_checkpoint().unwrap();
+        assert_eq!(checkpoint.round, 2);
+        assert_eq!(checkpoint.last_findings, r#"[{"id":"x"}]"#);
+        assert!(checkpoint.phases.is_empty());
+
+        mgr.clear_review_checkpoint().unwrap();
+        assert!(mgr.review_checkpoint().is_none());
+    }
+
+    #[test]
+    fn test_record_failed_attempt_uses_current_phase() {
+        let (_dir, mgr) = test_manager();
+        mgr.record_failed_attempt("ignored without a task").unwrap();
+        assert!(mgr.load().failed_attempts.is_empty());
+
+        mgr.set_current_task("gh-4", "implement", "/tmp/wt4")
+            .unwrap();
+        mgr.update_phase("review").unwrap();
+        mgr.record_failed_attempt("review exhausted").unwrap();
+        mgr.set_current_task("gh-5", "implement", "/tmp/wt5")
+            .unwrap();
+        mgr.record_failed_attempt("push failed").unwrap();
+
+        let attempts = mgr.failed_attempts("gh-4");
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(attempts[0].phase, "review");
+        assert_eq!(attempts[0].error, "review exhausted");
+        assert_eq!(mgr.failed_attempts("gh-5")[0].phase, "implement");
+
+        for i in 0..MAX_PREVIOUS_ATTEMPTS + 2 {
+            mgr.record_failed_attempt(&format!("retry {i}")).unwrap();
+        }
+        let attempts = mgr.failed_attempts("gh-5");
+        assert_eq!(attempts.len(), MAX_PREVIOUS_ATTEMPTS);
+        assert_eq!(attempts[0].error, "retry 2");
+        assert_eq!(mgr.failed_attempts("gh-4").len(), 1);
+
+        mgr.complete_current_task().unwrap();
+        assert!(mgr.failed_attempts("gh-5").is_empty());
+        assert_eq!(mgr.failed_attempts("gh-4").len(), 1);
+    }
+
+    #[test]
+    fn test_estimate_roundtrip() {
+        let (_dir, mgr) = test_manager();
+        assert!(mgr.get_estimate("gh-1").is_none());
+        mgr.set_estimate("gh-1", "S", "small", "f1").unwrap();
+        mgr.set_estimate("gh-1", "L", "grew", "f2").unwrap();
+        let estimate = mgr.get_estimate("gh-1").unwrap();
+        assert_eq!(estimate.complexity, "L");
+        assert_eq!(estimate.risk_notes, "grew");
+        assert_eq!(estimate.fingerprint, "f2");
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles_and_caps() {
+        assert_eq!(retry_backoff_secs(300, 1), 300);
+        assert_eq!(retry_backoff_secs(300, 2), 600);
+        assert_eq!(retry_backoff_secs(300, 4), 2400);
+        assert_eq!(retry_backoff_secs(300, 40), MAX_RETRY_BACKOFF_SECS);
+        assert_eq!(retry_backoff_secs(300, 200), MAX_RETRY_BACKOFF_SECS);
+    }
+
+    #[test]
+    fn test_retry_queue_lifecycle() {
+        let (_dir, mgr) = test_manager();
+        assert!(mgr.retry_entry("gh-1").is_none());
+
+        let first = mgr.schedule_retry("gh-1", "boom", 60).unwrap();
+        assert_eq!(first.attempts, 1);
+        let second = mgr.schedule_retry("gh-1", "boom again", 60).unwrap();
+        assert_eq!(second.attempts, 2);
+        assert_eq!(second.last_error, "boom again");
+        assert!(second.next_retry_at >= first.next_retry_at + 60);
+        assert!(!second.gave_up);
+
+        mgr.mark_retry_gave_up("gh-1").unwrap();
+        assert!(mgr.retry_entry("gh-1").unwrap().gave_up);
+
+        mgr.clear_retry("gh-1").unwrap();
+        assert!(mgr.retry_entry("gh-1").is_none());
+    }
+
     #[test]
     fn test_state_survives_reload() {
         let dir = TempDir::new().unwrap();
@@ -0,0 +1,152 @@
+use std::io::{IsTerminal, Write};
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// How a runner's formatted stream output is written to stderr.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StreamMode {
+    /// Each complete line is written as soon as it is formatted; lines from
+    /// parallel phases interleave but never mix mid-line.
+    #[default]
+    Interleaved,
+    /// The whole transcript is held until the phase ends and written as one block.
+    Serialized,
+}
+
+impl FromStr for StreamMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "interleaved" => Ok(StreamMode::Interleaved),
+            "serialized" => Ok(StreamMode::Serialized),
+            other => Err(Error::ConfigValidation(format!(
+                "unknown review_output: {other} (expected: interleaved, serialized)"
+            ))),
+        }
+    }
+}
+
+/// ANSI colors cycled through for phase prefixes.
+const PREFIX_COLORS: &[u8] = &[36, 35, 33, 32, 34, 31];
+
+/// Whether stderr output should be colored (a terminal, and `NO_COLOR` unset).
+pub fn color_enabled() -> bool {
+    std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// The prefix as shown inside `[...]`, colored when `color` is set. The color is
+/// derived from the prefix text so a phase keeps its color across runs.
+pub fn prefix_label(prefix: &str, color: bool) -> String {
+    if !color {
+        return prefix.to_string();
+    }
+    let index = prefix.bytes().map(usize::from).sum::<usize>() % PREFIX_COLORS.len();
+    format!("\x1b[{}m{prefix}\x1b[0m", PREFIX_COLORS[index])
+}
+
+/// Line-buffering writer for one phase's formatted output.
+///
+/// Partial lines are held back so each write to `out` carries whole lines only.
+/// In [`StreamMode::Serialized`] nothing is written until [`PhaseOutput::finish`].
+pub struct PhaseOutput<W: Write> {
+    mode: StreamMode,
+    buf: Vec<u8>,
+    out: W,
+}
+
+impl<W: Write> PhaseOutput<W> {
+    pub fn new(mode: StreamMode, out: W) -> Self {
+        Self {
+            mode,
+            buf: Vec::new(),
+            out,
+        }
+    }
+
+    /// Write everything still buffered, including a trailing partial line.
+    pub fn finish(mut self) -> std::io::Result<W> {
+        if !self.buf.is_empty() {
+            self.out.write_all(&self.buf)?;
+            self.buf.clear();
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+impl<W: Write> Write for PhaseOutput<W> {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(data);
+        if self.mode == StreamMode::Interleaved
+            && let Some(end) = self.buf.iter().rposition(|&b| b == b'\n')
+        {
+            self.out.write_all(&self.buf[..=end])?;
+            self.buf.drain(..=end);
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records each `write` call separately to check line atomicity.
+    #[derive(Default)]
+    struct Chunks(Vec<String>);
+
+    impl Write for Chunks {
+        fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+            self.0.push(String::from_utf8_lossy(data).into_owned());
+            Ok(data.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_interleaved_writes_whole_lines() {
+        let mut out = PhaseOutput::new(StreamMode::Interleaved, Chunks::default());
+        write!(out, "[a] hel").unwrap();
+        write!(out, "lo\n[a] wor").unwrap();
+        writeln!(out, "ld").unwrap();
+        write!(out, "[a] tail").unwrap();
+        let chunks = out.finish().unwrap().0;
+        assert_eq!(chunks, vec!["[a] hello\n", "[a] world\n", "[a] tail"]);
+    }
+
+    #[test]
+    fn test_serialized_holds_transcript_until_finish() {
+        let mut out = PhaseOutput::new(StreamMode::Serialized, Chunks::default());
+        writeln!(out, "[a] one").unwrap();
+        writeln!(out, "[a] two").unwrap();
+        let chunks = out.finish().unwrap().0;
+        assert_eq!(chunks, vec!["[a] one\n[a] two\n"]);
+    }
+
+    #[test]
+    fn test_prefix_label_color_is_stable() {
+        assert_eq!(prefix_label("review:security", false), "review:security");
+        let colored = prefix_label("review:security", true);
+        assert!(colored.starts_with("\x1b[") && colored.ends_with("review:security\x1b[0m"));
+        assert_eq!(colored, prefix_label("review:security", true));
+    }
+
+    #[test]
+    fn test_stream_mode_parse() {
+        assert_eq!(
+            "serialized".parse::<StreamMode>().unwrap(),
+            StreamMode::Serialized
+        );
+        let err = "chunked".parse::<StreamMode>().unwrap_err();
+        assert!(err.to_string().contains("unknown review_output: chunked"));
+    }
+}
@@ -1,9 +1,20 @@
 use std::process::Command;
 
 use serde::Deserialize;
-use tracing::info;
+use serde_json::Value;
+use tracing::{info, warn};
 
+use crate::bitbucket::BitbucketSubmission;
+use crate::check_run::{CHECK_RUN_NAME, ReviewCheck, annotation_batches};
+use crate::config::MergeMethod;
 use crate::error::{Error, Result};
+use crate::gh_quota::{gh_output, gh_output_with_input};
+use crate::gitea::GiteaSubmission;
+use crate::github_api::GitHubApiSubmission;
+use crate::sanitize::neutralize;
+use crate::suggestion::{
+    SuggestionComment, diff_line_ranges, posted_suggestion_ids, suggestion_in_diff,
+};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct PrComment {
@@ -15,6 +26,30 @@ pub struct PrComment {
     /// GitHub author association: OWNER, MEMBER, COLLABORATOR, CONTRIBUTOR, etc.
     #[serde(default)]
     pub author_association: Option<String>,
+    /// Where the comment was left; not part of GitHub's payload.
+    #[serde(skip)]
+    pub kind: FeedbackKind,
+}
+
+/// Where PR feedback was left. Each kind has its own ID sequence on GitHub,
+/// so feedback is tracked with a cursor per kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FeedbackKind {
+    /// A comment on the PR's conversation.
+    #[default]
+    Comment,
+    /// An inline comment on a line of the diff.
+    ReviewComment,
+    /// The body of a review requesting changes.
+    Review,
+}
+
+impl FeedbackKind {
+    pub const ALL: [FeedbackKind; 3] = [
+        FeedbackKind::Comment,
+        FeedbackKind::ReviewComment,
+        FeedbackKind::Review,
+    ];
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -26,6 +61,21 @@ struct PrCommentUser {
 const TRUSTED_ASSOCIATIONS: &[&str] = &["OWNER", "MEMBER", "COLLABORATOR"];
 
 impl PrComment {
+    /// A comment from a backend other than GitHub, which reports no author
+    /// association; such comments are never [trusted](Self::is_trusted).
+    pub(crate) fn new(id: u64, author: &str, body: &str, created_at: &str) -> Self {
+        Self {
+            id,
+            user_obj: Some(PrCommentUser {
+                login: author.to_string(),
+            }),
+            body: body.to_string(),
+            created_at: created_at.to_string(),
+            author_association: None,
+            kind: FeedbackKind::Comment,
+        }
+    }
+
     pub fn author(&self) -> &str {
         self.user_obj
             .as_ref()
@@ -54,9 +104,32 @@ pub struct PrContext {
     pub body: String,
     pub url: String,
     pub head_branch: String,
+    /// Commit at the tip of the head branch, when reported.
+    pub head_sha: Option<String>,
+    /// Branch the PR targets, when reported.
+    pub base_branch: Option<String>,
     pub linked_issue_number: Option<u64>,
 }
 
+/// Lifecycle state of a submitted PR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrState {
+    Open,
+    Merged,
+    Closed,
+}
+
+/// Combined result of the CI checks on a PR's head commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CiStatus {
+    /// Some checks have not finished yet.
+    Pending,
+    /// Every check passed, or the PR has none.
+    Passed,
+    /// At least one check failed; names the failing checks.
+    Failed(Vec<String>),
+}
+
 pub trait SubmissionBackend: Send + Sync {
     /// Submit a branch as a PR or diff. Returns the URL of the created PR/diff.
     fn submit(&self, branch: &str, base: &str, title: &str, body: &str) -> Result<SubmitResult>;
@@ -70,35 +143,278 @@ pub trait SubmissionBackend: Send + Sync {
 
     /// Fetch all comments on a PR/issue thread.
     fn fetch_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>>;
+
+    /// Inline review comments on a PR and the bodies of reviews requesting
+    /// changes. Backends without GitHub-style reviews report none.
+    fn fetch_pr_review_feedback(&self, _pr_number: u64) -> Result<Vec<PrComment>> {
+        Ok(Vec::new())
+    }
+
+    /// List open PRs in the repository.
+    fn list_open_prs(&self) -> Result<Vec<PrContext>>;
+
+    /// Post a new (non-upserted) comment on a PR.
+    fn post_pr_comment(&self, pr_number: u64, body: &str) -> Result<()>;
+
+    /// Replace the description of an existing PR.
+    fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()>;
+
+    /// Post suggested changes as inline review comments. Suggestions outside the PR
+    /// diff or already posted are skipped. Returns how many were posted.
+    fn post_suggestions(&self, pr_number: u64, suggestions: &[SuggestionComment]) -> Result<usize>;
+
+    /// Whether a PR is still open, merged, or closed without merging.
+    fn pr_state(&self, pr_number: u64) -> Result<PrState>;
+
+    /// Close a PR without merging, leaving `comment` on it explaining why.
+    fn close_pr(&self, pr_number: u64, comment: &str) -> Result<()>;
+
+    /// Publish a review as a completed check run on the PR's head commit, with its
+    /// findings as file/line annotations.
+    fn publish_review_check(&self, pr_number: u64, check: &ReviewCheck) -> Result<()>;
+
+    /// Post or update the heartbeat comment on an issue or PR thread.
+    fn upsert_heartbeat_comment(&self, number: u64, body: &str) -> Result<()>;
+
+    /// Delete the heartbeat comment from an issue or PR thread, if there is one.
+    fn delete_heartbeat_comment(&self, number: u64) -> Result<()>;
+
+    /// CI status of the PR's current head commit.
+    fn ci_status(&self, pr_number: u64) -> Result<CiStatus>;
+
+    /// Have the PR merged with `method` once its required checks pass.
+    fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()>;
+}
+
+/// The submission backend named by `config.submission`.
+pub enum AnySubmission {
+    GitHub(GitHubSubmission),
+    /// GitHub without `gh`; see `github_transport`.
+    GitHubApi(GitHubApiSubmission),
+    Bitbucket(BitbucketSubmission),
+    Gitea(GiteaSubmission),
+}
+
+impl AnySubmission {
+    /// Title, body, and branches of GitHub PR `pr_number`.
+    pub fn get_pr_context(&self, pr_number: u64) -> Result<PrContext> {
+        match self {
+            AnySubmission::GitHub(s) => s.get_pr_context(pr_number),
+            AnySubmission::GitHubApi(s) => s.get_pr_context(pr_number),
+            AnySubmission::Bitbucket(_) | AnySubmission::Gitea(_) => Err(Error::Submission(
+                "PR context is available only for GitHub submission".to_string(),
+            )),
+        }
+    }
+}
+
+impl SubmissionBackend for AnySubmission {
+    fn submit(&self, branch: &str, base: &str, title: &str, body: &str) -> Result<SubmitResult> {
+        match self {
+            AnySubmission::GitHub(s) => s.submit(branch, base, title, body),
+            AnySubmission::GitHubApi(s) => s.submit(branch, base, title, body),
+            AnySubmission::Bitbucket(s) => s.submit(branch, base, title, body),
+            AnySubmission::Gitea(s) => s.submit(branch, base, title, body),
+        }
+    }
+
+    fn find_existing_pr_for_issue(&self, issue_number: u64) -> Result<Option<u64>> {
+        match self {
+            AnySubmission::GitHub(s) => s.find_existing_pr_for_issue(issue_number),
+            AnySubmission::GitHubApi(s) => s.find_existing_pr_for_issue(issue_number),
+            AnySubmission::Bitbucket(s) => s.find_existing_pr_for_issue(issue_number),
+            AnySubmission::Gitea(s) => s.find_existing_pr_for_issue(issue_number),
+        }
+    }
+
+    fn upsert_review_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        match self {
+            AnySubmission::GitHub(s) => s.upsert_review_comment(pr_number, body),
+            AnySubmission::GitHubApi(s) => s.upsert_review_comment(pr_number, body),
+            AnySubmission::Bitbucket(s) => s.upsert_review_comment(pr_number, body),
+            AnySubmission::Gitea(s) => s.upsert_review_comment(pr_number, body),
+        }
+    }
+
+    fn fetch_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        match self {
+            AnySubmission::GitHub(s) => s.fetch_pr_comments(pr_number),
+            AnySubmission::GitHubApi(s) => s.fetch_pr_comments(pr_number),
+            AnySubmission::Bitbucket(s) => s.fetch_pr_comments(pr_number),
+            AnySubmission::Gitea(s) => s.fetch_pr_comments(pr_number),
+        }
+    }
+
+    fn fetch_pr_review_feedback(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        match self {
+            AnySubmission::GitHub(s) => s.fetch_pr_review_feedback(pr_number),
+            AnySubmission::GitHubApi(s) => s.fetch_pr_review_feedback(pr_number),
+            AnySubmission::Bitbucket(s) => s.fetch_pr_review_feedback(pr_number),
+            AnySubmission::Gitea(s) => s.fetch_pr_review_feedback(pr_number),
+        }
+    }
+
+    fn list_open_prs(&self) -> Result<Vec<PrContext>> {
+        match self {
+            AnySubmission::GitHub(s) => s.list_open_prs(),
+            AnySubmission::GitHubApi(s) => s.list_open_prs(),
+            AnySubmission::Bitbucket(s) => s.list_open_prs(),
+            AnySubmission::Gitea(s) => s.list_open_prs(),
+        }
+    }
+
+    fn post_pr_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        match self {
+            AnySubmission::GitHub(s) => s.post_pr_comment(pr_number, body),
+            AnySubmission::GitHubApi(s) => s.post_pr_comment(pr_number, body),
+            AnySubmission::Bitbucket(s) => s.post_pr_comment(pr_number, body),
+            AnySubmission::Gitea(s) => s.post_pr_comment(pr_number, body),
+        }
+    }
+
+    fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()> {
+        match self {
+            AnySubmission::GitHub(s) => s.update_pr_body(pr_number, body),
+            AnySubmission::GitHubApi(s) => s.update_pr_body(pr_number, body),
+            AnySubmission::Bitbucket(s) => s.update_pr_body(pr_number, body),
+            AnySubmission::Gitea(s) => s.update_pr_body(pr_number, body),
+        }
+    }
+
+    fn post_suggestions(&self, pr_number: u64, suggestions: &[SuggestionComment]) -> Result<usize> {
+        match self {
+            AnySubmission::GitHub(s) => s.post_suggestions(pr_number, suggestions),
+            AnySubmission::GitHubApi(s) => s.post_suggestions(pr_number, suggestions),
+            AnySubmission::Bitbucket(s) => s.post_suggestions(pr_number, suggestions),
+            AnySubmission::Gitea(s) => s.post_suggestions(pr_number, suggestions),
+        }
+    }
+
+    fn pr_state(&self, pr_number: u64) -> Result<PrState> {
+        match self {
+            AnySubmission::GitHub(s) => s.pr_state(pr_number),
+            AnySubmission::GitHubApi(s) => s.pr_state(pr_number),
+            AnySubmission::Bitbucket(s) => s.pr_state(pr_number),
+            AnySubmission::Gitea(s) => s.pr_state(pr_number),
+        }
+    }
+
+    fn close_pr(&self, pr_number: u64, comment: &str) -> Result<()> {
+        match self {
+            AnySubmission::GitHub(s) => s.close_pr(pr_number, comment),
+            AnySubmission::GitHubApi(s) => s.close_pr(pr_number, comment),
+            AnySubmission::Bitbucket(s) => s.close_pr(pr_number, comment),
+            AnySubmission::Gitea(s) => s.close_pr(pr_number, comment),
+        }
+    }
+
+    fn publish_review_check(&self, pr_number: u64, check: &ReviewCheck) -> Result<()> {
+        match self {
+            AnySubmission::GitHub(s) => s.publish_review_check(pr_number, check),
+            AnySubmission::GitHubApi(s) => s.publish_review_check(pr_number, check),
+            AnySubmission::Bitbucket(s) => s.publish_review_check(pr_number, check),
+            AnySubmission::Gitea(s) => s.publish_review_check(pr_number, check),
+        }
+    }
+
+    fn upsert_heartbeat_comment(&self, number: u64, body: &str) -> Result<()> {
+        match self {
+            AnySubmission::GitHub(s) => s.upsert_heartbeat_comment(number, body),
+            AnySubmission::GitHubApi(s) => s.upsert_heartbeat_comment(number, body),
+            AnySubmission::Bitbucket(s) => s.upsert_heartbeat_comment(number, body),
+            AnySubmission::Gitea(s) => s.upsert_heartbeat_comment(number, body),
+        }
+    }
+
+    fn delete_heartbeat_comment(&self, number: u64) -> Result<()> {
+        match self {
+            AnySubmission::GitHub(s) => s.delete_heartbeat_comment(number),
+            AnySubmission::GitHubApi(s) => s.delete_heartbeat_comment(number),
+            AnySubmission::Bitbucket(s) => s.delete_heartbeat_comment(number),
+            AnySubmission::Gitea(s) => s.delete_heartbeat_comment(number),
+        }
+    }
+
+    fn ci_status(&self, pr_number: u64) -> Result<CiStatus> {
+        match self {
+            AnySubmission::GitHub(s) => s.ci_status(pr_number),
+            AnySubmission::GitHubApi(s) => s.ci_status(pr_number),
+            AnySubmission::Bitbucket(s) => s.ci_status(pr_number),
+            AnySubmission::Gitea(s) => s.ci_status(pr_number),
+        }
+    }
+
+    fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        match self {
+            AnySubmission::GitHub(s) => s.enable_auto_merge(pr_number, method),
+            AnySubmission::GitHubApi(s) => s.enable_auto_merge(pr_number, method),
+            AnySubmission::Bitbucket(s) => s.enable_auto_merge(pr_number, method),
+            AnySubmission::Gitea(s) => s.enable_auto_merge(pr_number, method),
+        }
+    }
 }
 
+/// Default PR title template.
+pub const DEFAULT_PR_TITLE_TEMPLATE: &str = "{{ issue_title }}";
+
+/// Default PR body template. Estimate variables are empty when estimation is off.
+pub const DEFAULT_PR_BODY_TEMPLATE: &str = "\
+Resolves #{{ issue_number }}
+
+Automated implementation by rlph.\
+{% if estimate_complexity %}
+
+**Estimated complexity:** {{ estimate_complexity }}\
+{% if estimate_risk_notes %}
+**Risk notes:** {{ estimate_risk_notes }}{% endif %}{% endif %}";
+
 /// HTML marker injected into review comments so we can find and update them.
 pub const REVIEW_MARKER: &str = "<!-- rlph-review -->";
 
+/// HTML marker injected into replies to human PR feedback so they are not
+/// mistaken for new feedback on the next poll.
+pub const FEEDBACK_MARKER: &str = "<!-- rlph-feedback -->";
+
+/// HTML marker on the progress comment kept on an issue or PR while a long
+/// phase runs.
+pub const HEARTBEAT_MARKER: &str = "<!-- rlph-heartbeat -->";
+
 /// GitHub PR submission via `gh` CLI.
 #[derive(Default)]
-pub struct GitHubSubmission;
+pub struct GitHubSubmission {
+    head_owner: Option<String>,
+}
 
 impl GitHubSubmission {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Open PRs from branches in `owner`'s fork rather than the base repository.
+    pub fn with_head_owner(mut self, owner: Option<String>) -> Self {
+        self.head_owner = owner;
+        self
+    }
+
+    /// The `--head` argument for `gh pr create`: `owner:branch` for cross-fork PRs.
+    fn head_ref(&self, branch: &str) -> String {
+        match &self.head_owner {
+            Some(owner) => format!("{owner}:{branch}"),
+            None => branch.to_string(),
+        }
     }
 
     /// Check if a PR already exists for the given branch.
     fn find_existing_pr(&self, branch: &str) -> Result<Option<(String, Option<u64>)>> {
-        let output = Command::new("gh")
-            .args([
-                "pr",
-                "list",
-                "--head",
-                branch,
-                "--json",
-                "url,number",
-                "--limit",
-                "1",
-            ])
-            .output()
-            .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
+        let output = gh_output(Command::new("gh").args([
+            "pr",
+            "list",
+            "--head",
+            branch,
+            "--json",
+            "url,number,headRepositoryOwner",
+        ]))
+        .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -109,30 +425,21 @@ impl GitHubSubmission {
         let prs: Vec<serde_json::Value> = serde_json::from_str(&stdout)
             .map_err(|e| Error::Submission(format!("failed to parse gh output: {e}")))?;
 
-        if let Some(pr) = prs.first()
-            && let Some(url) = pr.get("url").and_then(|v| v.as_str())
-        {
-            let number = pr.get("number").and_then(|v| v.as_u64());
-            return Ok(Some((url.to_string(), number)));
-        }
-
-        Ok(None)
+        Ok(select_pr_for_head(&prs, self.head_owner.as_deref()))
     }
 
     fn find_existing_pr_for_issue_impl(&self, issue_number: u64) -> Result<Option<u64>> {
-        let output = Command::new("gh")
-            .args([
-                "pr",
-                "list",
-                "--state",
-                "open",
-                "--json",
-                "number,body",
-                "--limit",
-                "100",
-            ])
-            .output()
-            .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
+        let output = gh_output(Command::new("gh").args([
+            "pr",
+            "list",
+            "--state",
+            "open",
+            "--json",
+            "number,body",
+            "--limit",
+            "100",
+        ]))
+        .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -162,16 +469,19 @@ impl GitHubSubmission {
 
     /// Find an existing rlph review comment on a PR, returning its ID if found.
     fn find_review_comment(&self, pr_number: u64) -> Result<Option<u64>> {
-        let endpoint = format!("repos/{{owner}}/{{repo}}/issues/{pr_number}/comments");
-        let output = Command::new("gh")
-            .args([
-                "api",
-                &endpoint,
-                "--jq",
-                ".[] | select(.body | contains(\"<!-- rlph-review -->\")) | .id",
-            ])
-            .output()
-            .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
+        self.find_marked_comment(pr_number, REVIEW_MARKER)
+    }
+
+    /// Find the comment containing `marker` on an issue or PR thread.
+    fn find_marked_comment(&self, number: u64, marker: &str) -> Result<Option<u64>> {
+        let endpoint = format!("repos/{{owner}}/{{repo}}/issues/{number}/comments");
+        let output = gh_output(Command::new("gh").args([
+            "api",
+            &endpoint,
+            "--jq",
+            &format!(".[] | select(.body | contains(\"{marker}\")) | .id"),
+        ]))
+        .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -191,16 +501,14 @@ impl GitHubSubmission {
 
     pub fn get_pr_context(&self, pr_number: u64) -> Result<PrContext> {
         let number_str = pr_number.to_string();
-        let output = Command::new("gh")
-            .args([
-                "pr",
-                "view",
-                &number_str,
-                "--json",
-                "number,title,body,url,headRefName",
-            ])
-            .output()
-            .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
+        let output = gh_output(Command::new("gh").args([
+            "pr",
+            "view",
+            &number_str,
+            "--json",
+            "number,title,body,url,headRefName,headRefOid,baseRefName",
+        ]))
+        .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -239,7 +547,7 @@ pub fn format_pr_comments_for_prompt(comments: &[PrComment], pr_number: u64) ->
             c.author(),
             c.created_at,
             trust_label,
-            c.body
+            neutralize(&c.body)
         ));
     }
     out
@@ -254,12 +562,19 @@ impl SubmissionBackend for GitHubSubmission {
         }
 
         // Create new PR
-        let output = Command::new("gh")
-            .args([
-                "pr", "create", "--head", branch, "--base", base, "--title", title, "--body", body,
-            ])
-            .output()
-            .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
+        let output = gh_output(Command::new("gh").args([
+            "pr",
+            "create",
+            "--head",
+            &self.head_ref(branch),
+            "--base",
+            base,
+            "--title",
+            title,
+            "--body",
+            body,
+        ]))
+        .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -280,17 +595,15 @@ impl SubmissionBackend for GitHubSubmission {
         // Try to find an existing rlph review comment
         if let Some(comment_id) = self.find_review_comment(pr_number)? {
             let endpoint = format!("repos/{{owner}}/{{repo}}/issues/comments/{comment_id}");
-            let output = Command::new("gh")
-                .args([
-                    "api",
-                    &endpoint,
-                    "-X",
-                    "PATCH",
-                    "-f",
-                    &format!("body={body}"),
-                ])
-                .output()
-                .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
+            let output = gh_output(Command::new("gh").args([
+                "api",
+                &endpoint,
+                "-X",
+                "PATCH",
+                "-f",
+                &format!("body={body}"),
+            ]))
+            .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
 
             if !output.status.success() {
                 let stderr = String::from_utf8_lossy(&output.stderr);
@@ -306,10 +619,9 @@ impl SubmissionBackend for GitHubSubmission {
             );
         } else {
             let number_str = pr_number.to_string();
-            let output = Command::new("gh")
-                .args(["pr", "comment", &number_str, "--body", body])
-                .output()
-                .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
+            let output =
+                gh_output(Command::new("gh").args(["pr", "comment", &number_str, "--body", body]))
+                    .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
 
             if !output.status.success() {
                 let stderr = String::from_utf8_lossy(&output.stderr);
@@ -323,9 +635,7 @@ impl SubmissionBackend for GitHubSubmission {
 
     fn fetch_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>> {
         let endpoint = format!("repos/{{owner}}/{{repo}}/issues/{pr_number}/comments");
-        let output = Command::new("gh")
-            .args(["api", &endpoint])
-            .output()
+        let output = gh_output(Command::new("gh").args(["api", &endpoint]))
             .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
 
         if !output.status.success() {
@@ -340,6 +650,307 @@ impl SubmissionBackend for GitHubSubmission {
             .map_err(|e| Error::Submission(format!("failed to parse comments json: {e}")))?;
         Ok(comments)
     }
+
+    fn fetch_pr_review_feedback(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        let listing = |path: &str, what: &str| -> Result<Vec<Value>> {
+            let endpoint = format!("repos/{{owner}}/{{repo}}/pulls/{pr_number}/{path}");
+            gh_stdout(&["api", "--paginate", &endpoint, "--jq", ".[]"], what)?
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| {
+                    serde_json::from_str(line).map_err(|e| {
+                        Error::Submission(format!("failed to parse {what} output: {e}"))
+                    })
+                })
+                .collect()
+        };
+        let comments = listing("comments", "gh api fetch review comments")?;
+        let reviews = listing("reviews", "gh api fetch reviews")?;
+        Ok(review_feedback(&comments, &reviews))
+    }
+
+    fn list_open_prs(&self) -> Result<Vec<PrContext>> {
+        // `gh pr list` stops at `--limit`; the REST listing pages through all.
+        let stdout = gh_stdout(
+            &[
+                "api",
+                "--paginate",
+                "repos/{owner}/{repo}/pulls?state=open&per_page=100",
+                "--jq",
+                ".[] | {number, title, body: (.body // \"\"), url: .html_url, headRefName: .head.ref, headRefOid: .head.sha, baseRefName: .base.ref}",
+            ],
+            "gh api list open PRs",
+        )?;
+        parse_pr_list_json(&stdout)
+            .map_err(|e| Error::Submission(format!("failed to parse open PR listing: {e}")))
+    }
+
+    fn post_pr_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        let number_str = pr_number.to_string();
+        let output =
+            gh_output(Command::new("gh").args(["pr", "comment", &number_str, "--body", body]))
+                .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::Submission(format!("gh pr comment failed: {stderr}")));
+        }
+
+        info!(pr_number, "posted comment on PR");
+        Ok(())
+    }
+
+    fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()> {
+        let number_str = pr_number.to_string();
+        let output =
+            gh_output(Command::new("gh").args(["pr", "edit", &number_str, "--body", body]))
+                .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::Submission(format!("gh pr edit failed: {stderr}")));
+        }
+
+        info!(pr_number, "updated PR body");
+        Ok(())
+    }
+
+    fn pr_state(&self, pr_number: u64) -> Result<PrState> {
+        let number_str = pr_number.to_string();
+        let stdout = gh_stdout(
+            &["pr", "view", &number_str, "--json", "state"],
+            "gh pr view",
+        )?;
+        parse_pr_state_json(&stdout)
+            .map_err(|e| Error::Submission(format!("failed to parse PR #{pr_number} state: {e}")))
+    }
+
+    fn close_pr(&self, pr_number: u64, comment: &str) -> Result<()> {
+        let number_str = pr_number.to_string();
+        gh_stdout(
+            &["pr", "close", &number_str, "--comment", comment],
+            "gh pr close",
+        )?;
+        info!(pr_number, "closed PR");
+        Ok(())
+    }
+
+    fn ci_status(&self, pr_number: u64) -> Result<CiStatus> {
+        let number_str = pr_number.to_string();
+        let stdout = gh_stdout(
+            &["pr", "view", &number_str, "--json", "statusCheckRollup"],
+            "gh pr view",
+        )?;
+        parse_ci_status_json(&stdout)
+            .map_err(|e| Error::Submission(format!("failed to parse PR #{pr_number} checks: {e}")))
+    }
+
+    fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        let number_str = pr_number.to_string();
+        let method_flag = match method {
+            MergeMethod::Rebase => "--rebase",
+            MergeMethod::Squash => "--squash",
+            MergeMethod::Merge => "--merge",
+        };
+        gh_stdout(
+            &["pr", "merge", &number_str, "--auto", method_flag],
+            "gh pr merge",
+        )?;
+        info!(pr_number, "enabled auto-merge");
+        Ok(())
+    }
+
+    fn upsert_heartbeat_comment(&self, number: u64, body: &str) -> Result<()> {
+        let body_field = format!("body={body}");
+        match self.find_marked_comment(number, HEARTBEAT_MARKER)? {
+            Some(comment_id) => {
+                let endpoint = format!("repos/{{owner}}/{{repo}}/issues/comments/{comment_id}");
+                gh_stdout(
+                    &["api", &endpoint, "-X", "PATCH", "-f", &body_field],
+                    "gh api PATCH comment",
+                )?;
+            }
+            None => {
+                let endpoint = format!("repos/{{owner}}/{{repo}}/issues/{number}/comments");
+                gh_stdout(
+                    &["api", &endpoint, "-X", "POST", "-f", &body_field],
+                    "gh api POST comment",
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_heartbeat_comment(&self, number: u64) -> Result<()> {
+        if let Some(comment_id) = self.find_marked_comment(number, HEARTBEAT_MARKER)? {
+            let endpoint = format!("repos/{{owner}}/{{repo}}/issues/comments/{comment_id}");
+            gh_stdout(&["api", &endpoint, "-X", "DELETE"], "gh api DELETE comment")?;
+        }
+        Ok(())
+    }
+
+    fn publish_review_check(&self, pr_number: u64, check: &ReviewCheck) -> Result<()> {
+        let number_str = pr_number.to_string();
+        let head_sha = gh_stdout(
+            &[
+                "pr",
+                "view",
+                &number_str,
+                "--json",
+                "headRefOid",
+                "--jq",
+                ".headRefOid",
+            ],
+            "gh pr view",
+        )?
+        .trim()
+        .to_string();
+
+        // GitHub caps annotations per request: the first batch creates the check
+        // run and the rest are appended to it.
+        let mut check_run_id: Option<u64> = None;
+        for batch in annotation_batches(&check.annotations) {
+            let output = serde_json::json!({
+                "title": check.title,
+                "summary": check.summary,
+                "annotations": batch,
+            });
+            let (method, endpoint, payload) = match check_run_id {
+                None => (
+                    "POST",
+                    "repos/{owner}/{repo}/check-runs".to_string(),
+                    serde_json::json!({
+                        "name": CHECK_RUN_NAME,
+                        "head_sha": head_sha,
+                        "status": "completed",
+                        "conclusion": check.conclusion,
+                        "output": output,
+                    }),
+                ),
+                Some(id) => (
+                    "PATCH",
+                    format!("repos/{{owner}}/{{repo}}/check-runs/{id}"),
+                    serde_json::json!({ "output": output }),
+                ),
+            };
+            let result = gh_output_with_input(
+                Command::new("gh").args(["api", &endpoint, "-X", method, "--input", "-"]),
+                payload.to_string().as_bytes(),
+            )
+            .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
+            if !result.status.success() {
+                let stderr = String::from_utf8_lossy(&result.stderr);
+                return Err(Error::Submission(format!(
+                    "gh api {method} check run failed: {stderr}"
+                )));
+            }
+            if check_run_id.is_none() {
+                let created: serde_json::Value =
+                    serde_json::from_slice(&result.stdout).map_err(|e| {
+                        Error::Submission(format!("failed to parse check run response: {e}"))
+                    })?;
+                check_run_id = Some(created["id"].as_u64().ok_or_else(|| {
+                    Error::Submission("check run response has no id".to_string())
+                })?);
+            }
+        }
+
+        info!(
+            pr_number,
+            annotations = check.annotations.len(),
+            "published review check run"
+        );
+        Ok(())
+    }
+
+    fn post_suggestions(&self, pr_number: u64, suggestions: &[SuggestionComment]) -> Result<usize> {
+        if suggestions.is_empty() {
+            return Ok(0);
+        }
+        let number_str = pr_number.to_string();
+        let diff = gh_stdout(&["pr", "diff", &number_str], "gh pr diff")?;
+        let ranges = diff_line_ranges(&diff);
+        let head = gh_stdout(
+            &[
+                "pr",
+                "view",
+                &number_str,
+                "--json",
+                "headRefOid",
+                "--jq",
+                ".headRefOid",
+            ],
+            "gh pr view",
+        )?;
+        let head = head.trim();
+        let endpoint = format!("repos/{{owner}}/{{repo}}/pulls/{pr_number}/comments");
+        let existing = gh_stdout(
+            &["api", "--paginate", &endpoint, "--jq", ".[].body"],
+            "gh api fetch review comments",
+        )?;
+        let posted = posted_suggestion_ids(existing.split("\n"));
+
+        let mut count = 0;
+        for suggestion in suggestions {
+            if posted.contains(&suggestion.finding_id) {
+                continue;
+            }
+            if !suggestion_in_diff(suggestion, &ranges) {
+                warn!(
+                    finding = %suggestion.finding_id,
+                    path = %suggestion.path,
+                    "suggested patch is outside the PR diff — skipping"
+                );
+                continue;
+            }
+            let mut args = vec![
+                "api".to_string(),
+                endpoint.clone(),
+                "-X".to_string(),
+                "POST".to_string(),
+                "-f".to_string(),
+                format!("body={}", suggestion.body),
+                "-f".to_string(),
+                format!("commit_id={head}"),
+                "-f".to_string(),
+                format!("path={}", suggestion.path),
+                "-F".to_string(),
+                format!("line={}", suggestion.end_line),
+                "-f".to_string(),
+                "side=RIGHT".to_string(),
+            ];
+            if suggestion.start_line < suggestion.end_line {
+                args.extend([
+                    "-F".to_string(),
+                    format!("start_line={}", suggestion.start_line),
+                    "-f".to_string(),
+                    "start_side=RIGHT".to_string(),
+                ]);
+            }
+            let output = gh_output(Command::new("gh").args(&args))
+                .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                warn!(finding = %suggestion.finding_id, "failed to post suggestion: {stderr}");
+                continue;
+            }
+            count += 1;
+        }
+
+        info!(pr_number, count, "posted suggested changes on PR");
+        Ok(count)
+    }
+}
+
+/// Run `gh` with `args` and return its stdout, or a submission error naming `what`.
+fn gh_stdout(args: &[&str], what: &str) -> Result<String> {
+    let output = gh_output(Command::new("gh").args(args))
+        .map_err(|e| Error::Submission(format!("failed to run gh: {e}")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::Submission(format!("{what} failed: {stderr}")));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
 
 /// Parse PR number from a URL like `https://github.com/owner/repo/pull/123`.
@@ -347,7 +958,7 @@ fn parse_pr_number_from_url(url: &str) -> Option<u64> {
     url.rsplit('/').next().and_then(|s| s.parse().ok())
 }
 
-fn pr_body_references_issue(body: &str, issue_number: u64) -> bool {
+pub(crate) fn pr_body_references_issue(body: &str, issue_number: u64) -> bool {
     let needle = format!("#{issue_number}");
     body.split_whitespace().any(|token| {
         token == needle || token.trim_matches(|c: char| ",.;:()[]{}".contains(c)) == needle
@@ -363,11 +974,123 @@ struct GhPrView {
     url: String,
     #[serde(rename = "headRefName")]
     head_ref_name: String,
+    #[serde(rename = "headRefOid", default)]
+    head_ref_oid: String,
+    #[serde(rename = "baseRefName", default)]
+    base_ref_name: String,
 }
 
 fn parse_pr_context_json(json: &str) -> std::result::Result<PrContext, String> {
     let pr: GhPrView =
         serde_json::from_str(json).map_err(|e| format!("invalid json payload: {e}"))?;
+    pr_view_to_context(pr)
+}
+
+/// Parse open PRs listed one `gh pr view --json`-shaped object per line,
+/// skipping entries without a head branch.
+fn parse_pr_list_json(json: &str) -> std::result::Result<Vec<PrContext>, String> {
+    let mut prs = Vec::new();
+    for line in json.lines().filter(|line| !line.trim().is_empty()) {
+        let pr: GhPrView =
+            serde_json::from_str(line).map_err(|e| format!("invalid json payload: {e}"))?;
+        prs.extend(pr_view_to_context(pr).ok());
+    }
+    Ok(prs)
+}
+
+/// Feedback from GitHub's review comment (`pulls/<n>/comments`) and review
+/// (`pulls/<n>/reviews`) listings: every inline comment, prefixed with the
+/// line it is on, and the body of each review requesting changes.
+pub(crate) fn review_feedback(comments: &[Value], reviews: &[Value]) -> Vec<PrComment> {
+    let inline = comments.iter().filter_map(|c| {
+        let mut comment: PrComment = serde_json::from_value(c.clone()).ok()?;
+        let path = c.get("path").and_then(Value::as_str).unwrap_or_default();
+        let place = match c.get("line").and_then(Value::as_u64) {
+            Some(line) => format!("`{path}` line {line}"),
+            None => format!("`{path}`"),
+        };
+        comment.body = format!("On {place}:\n{}", comment.body);
+        comment.kind = FeedbackKind::ReviewComment;
+        Some(comment)
+    });
+    let requested = reviews
+        .iter()
+        .filter(|r| r.get("state").and_then(Value::as_str) == Some("CHANGES_REQUESTED"))
+        .filter(|r| {
+            r.get("body")
+                .and_then(Value::as_str)
+                .is_some_and(|b| !b.trim().is_empty())
+        })
+        .filter_map(|r| {
+            let mut review = r.clone();
+            review["created_at"] = r.get("submitted_at").cloned().unwrap_or_default();
+            let mut comment: PrComment = serde_json::from_value(review).ok()?;
+            comment.kind = FeedbackKind::Review;
+            Some(comment)
+        });
+    inline.chain(requested).collect()
+}
+
+fn parse_pr_state_json(json: &str) -> std::result::Result<PrState, String> {
+    #[derive(Deserialize)]
+    struct GhPrState {
+        state: String,
+    }
+    let pr: GhPrState =
+        serde_json::from_str(json).map_err(|e| format!("invalid json payload: {e}"))?;
+    match pr.state.as_str() {
+        "OPEN" => Ok(PrState::Open),
+        "MERGED" => Ok(PrState::Merged),
+        "CLOSED" => Ok(PrState::Closed),
+        other => Err(format!("unknown PR state: {other}")),
+    }
+}
+
+/// Parse `gh pr view --json statusCheckRollup`, which mixes check runs
+/// (`status`/`conclusion`) and commit statuses (`state`).
+fn parse_ci_status_json(json: &str) -> std::result::Result<CiStatus, String> {
+    #[derive(Deserialize)]
+    struct GhRollup {
+        #[serde(rename = "statusCheckRollup", default)]
+        checks: Vec<GhCheck>,
+    }
+    #[derive(Deserialize)]
+    struct GhCheck {
+        #[serde(default)]
+        name: String,
+        #[serde(default)]
+        context: String,
+        status: Option<String>,
+        conclusion: Option<String>,
+        state: Option<String>,
+    }
+    let rollup: GhRollup =
+        serde_json::from_str(json).map_err(|e| format!("invalid json payload: {e}"))?;
+    let mut pending = false;
+    let mut failed = Vec::new();
+    for check in rollup.checks {
+        let outcome = match (&check.state, &check.status) {
+            (Some(state), _) => state.as_str(),
+            (None, Some(status)) if status != "COMPLETED" => "PENDING",
+            _ => check.conclusion.as_deref().unwrap_or_default(),
+        };
+        match outcome {
+            "SUCCESS" | "NEUTRAL" | "SKIPPED" => {}
+            "PENDING" | "EXPECTED" | "" => pending = true,
+            _ if check.name.is_empty() => failed.push(check.context),
+            _ => failed.push(check.name),
+        }
+    }
+    Ok(if !failed.is_empty() {
+        CiStatus::Failed(failed)
+    } else if pending {
+        CiStatus::Pending
+    } else {
+        CiStatus::Passed
+    })
+}
+
+fn pr_view_to_context(pr: GhPrView) -> std::result::Result<PrContext, String> {
     if pr.head_ref_name.trim().is_empty() {
         return Err("missing headRefName".to_string());
     }
@@ -378,11 +1101,13 @@ fn parse_pr_context_json(json: &str) -> std::result::Result<PrContext, String> {
         body: pr.body.clone(),
         url: pr.url,
         head_branch: pr.head_ref_name,
+        head_sha: Some(pr.head_ref_oid).filter(|sha| !sha.is_empty()),
+        base_branch: Some(pr.base_ref_name).filter(|b| !b.is_empty()),
         linked_issue_number: extract_issue_number_reference(&pr.body),
     })
 }
 
-fn extract_issue_number_reference(body: &str) -> Option<u64> {
+pub(crate) fn extract_issue_number_reference(body: &str) -> Option<u64> {
     body.split_whitespace().find_map(|token| {
         let trimmed = token.trim_matches(|c: char| ",.;:()[]{}".contains(c));
         if let Some(num) = trimmed.strip_prefix('#') {
@@ -392,11 +1117,35 @@ fn extract_issue_number_reference(body: &str) -> Option<u64> {
     })
 }
 
+/// Pick the PR whose head is in `head_owner`'s repository (any head when
+/// `None`) from `gh pr list --json url,number,headRepositoryOwner` output.
+/// Same-named branches in other forks are ignored.
+fn select_pr_for_head(
+    prs: &[serde_json::Value],
+    head_owner: Option<&str>,
+) -> Option<(String, Option<u64>)> {
+    prs.iter()
+        .filter(|pr| {
+            head_owner.is_none_or(|owner| {
+                pr.pointer("/headRepositoryOwner/login")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|login| login.eq_ignore_ascii_case(owner))
+            })
+        })
+        .find_map(|pr| {
+            let url = pr.get("url").and_then(|v| v.as_str())?;
+            let number = pr.get("number").and_then(|v| v.as_u64());
+            Some((url.to_string(), number))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        PrComment, PrCommentUser, extract_issue_number_reference, format_pr_comments_for_prompt,
-        parse_pr_context_json, parse_pr_number_from_url, pr_body_references_issue,
+        FeedbackKind, GitHubSubmission, PrComment, PrCommentUser, PrState,
+        extract_issue_number_reference, format_pr_comments_for_prompt, parse_pr_context_json,
+        parse_pr_list_json, parse_pr_number_from_url, parse_pr_state_json,
+        pr_body_references_issue, review_feedback, select_pr_for_head,
     };
 
     #[test]
@@ -414,6 +1163,33 @@ mod tests {
         assert!(!pr_body_references_issue("Resolves #142", 42));
     }
 
+    #[test]
+    fn test_head_ref_for_cross_fork_prs() {
+        assert_eq!(GitHubSubmission::new().head_ref("rlph-1-x"), "rlph-1-x");
+        let fork = GitHubSubmission::new().with_head_owner(Some("rlph-bot".to_string()));
+        assert_eq!(fork.head_ref("rlph-1-x"), "rlph-bot:rlph-1-x");
+    }
+
+    #[test]
+    fn test_select_pr_for_head_filters_by_owner() {
+        let prs: Vec<serde_json::Value> = serde_json::from_str(
+            r#"[
+                {"url":"https://github.com/o/r/pull/1","number":1,"headRepositoryOwner":{"login":"someone"}},
+                {"url":"https://github.com/o/r/pull/2","number":2,"headRepositoryOwner":{"login":"RLPH-bot"}}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            select_pr_for_head(&prs, None),
+            Some(("https://github.com/o/r/pull/1".to_string(), Some(1)))
+        );
+        assert_eq!(
+            select_pr_for_head(&prs, Some("rlph-bot")),
+            Some(("https://github.com/o/r/pull/2".to_string(), Some(2)))
+        );
+        assert_eq!(select_pr_for_head(&prs, Some("other")), None);
+    }
+
     #[test]
     fn test_parse_pr_number_from_url() {
         assert_eq!(
@@ -434,7 +1210,9 @@ mod tests {
             "title": "Fix race condition",
             "body": "Resolves #42",
             "url": "https://github.com/o/r/pull/9",
-            "headRefName": "feature/fix-race"
+            "headRefName": "feature/fix-race",
+            "headRefOid": "4f2a9c1",
+            "baseRefName": "release/1.x"
         }"#;
 
         let ctx = parse_pr_context_json(json).unwrap();
@@ -443,6 +1221,8 @@ mod tests {
         assert_eq!(ctx.body, "Resolves #42");
         assert_eq!(ctx.url, "https://github.com/o/r/pull/9");
         assert_eq!(ctx.head_branch, "feature/fix-race");
+        assert_eq!(ctx.head_sha.as_deref(), Some("4f2a9c1"));
+        assert_eq!(ctx.base_branch.as_deref(), Some("release/1.x"));
         assert_eq!(ctx.linked_issue_number, Some(42));
     }
 
@@ -458,6 +1238,8 @@ mod tests {
 
         let ctx = parse_pr_context_json(json).unwrap();
         assert_eq!(ctx.number, 11);
+        assert_eq!(ctx.head_sha, None);
+        assert_eq!(ctx.base_branch, None);
         assert_eq!(ctx.linked_issue_number, None);
     }
 
@@ -475,6 +1257,95 @@ mod tests {
         assert!(err.contains("headRefName"));
     }
 
+    #[test]
+    fn test_parse_pr_list_json_skips_missing_head_ref() {
+        let json = concat!(
+            r#"{"number": 3, "title": "A", "body": "Resolves #1", "url": "u3", "headRefName": "rlph-1-a"}"#,
+            "\n",
+            r#"{"number": 4, "title": "B", "body": "", "url": "u4", "headRefName": ""}"#,
+            "\n",
+        );
+
+        let prs = parse_pr_list_json(json).unwrap();
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].number, 3);
+        assert_eq!(prs[0].head_branch, "rlph-1-a");
+        assert_eq!(prs[0].linked_issue_number, Some(1));
+    }
+
+    #[test]
+    fn test_review_feedback_keeps_inline_comments_and_change_requests() {
+        let user = serde_json::json!({ "login": "alice" });
+        let comments = [serde_json::json!({
+            "id": 5, "user": user, "body": "Off by one", "created_at": "t1",
+            "author_association": "MEMBER", "path": "src/lib.rs", "line": 12,
+        })];
+        let reviews = [
+            serde_json::json!({
+                "id": 8, "user": user, "body": "Please add tests", "submitted_at": "t2",
+                "author_association": "OWNER", "state": "CHANGES_REQUESTED",
+            }),
+            serde_json::json!({
+                "id": 9, "user": user, "body": "Nice", "submitted_at": "t3",
+                "author_association": "OWNER", "state": "APPROVED",
+            }),
+            serde_json::json!({
+                "id": 10, "user": user, "body": "", "submitted_at": "t4",
+                "author_association": "OWNER", "state": "CHANGES_REQUESTED",
+            }),
+        ];
+
+        let feedback = review_feedback(&comments, &reviews);
+        assert_eq!(feedback.len(), 2);
+        assert_eq!(feedback[0].kind, FeedbackKind::ReviewComment);
+        assert_eq!(feedback[0].body, "On `src/lib.rs` line 12:\nOff by one");
+        assert!(feedback[0].is_trusted());
+        assert_eq!(feedback[1].kind, FeedbackKind::Review);
+        assert_eq!((feedback[1].id, feedback[1].created_at.as_str()), (8, "t2"));
+        assert_eq!(feedback[1].author(), "alice");
+    }
+
+    #[test]
+    fn test_parse_pr_state_json() {
+        assert_eq!(
+            parse_pr_state_json(r#"{"state":"OPEN"}"#).unwrap(),
+            PrState::Open
+        );
+        assert_eq!(
+            parse_pr_state_json(r#"{"state":"MERGED"}"#).unwrap(),
+            PrState::Merged
+        );
+        assert_eq!(
+            parse_pr_state_json(r#"{"state":"CLOSED"}"#).unwrap(),
+            PrState::Closed
+        );
+        assert!(parse_pr_state_json(r#"{"state":"DRAFT"}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_ci_status_json() {
+        let parse = |checks: &str| {
+            parse_ci_status_json(&format!(r#"{{"statusCheckRollup":[{checks}]}}"#)).unwrap()
+        };
+        assert_eq!(parse(""), CiStatus::Passed);
+        assert_eq!(
+            parse(
+                r#"{"name":"test","status":"COMPLETED","conclusion":"SUCCESS"},{"context":"ci/lint","state":"SUCCESS"}"#
+            ),
+            CiStatus::Passed
+        );
+        assert_eq!(
+            parse(r#"{"name":"test","status":"IN_PROGRESS","conclusion":""}"#),
+            CiStatus::Pending
+        );
+        assert_eq!(
+            parse(
+                r#"{"name":"test","status":"IN_PROGRESS"},{"context":"ci/lint","state":"FAILURE"}"#
+            ),
+            CiStatus::Failed(vec!["ci/lint".to_string()])
+        );
+    }
+
     #[test]
     fn test_format_pr_comments_empty() {
         let result = format_pr_comments_for_prompt(&[], 42);
@@ -492,6 +1363,7 @@ mod tests {
                 body: "Looks good!".to_string(),
                 created_at: "2025-01-01T00:00:00Z".to_string(),
                 author_association: Some("OWNER".to_string()),
+                kind: FeedbackKind::Comment,
             },
             PrComment {
                 id: 2,
@@ -499,6 +1371,7 @@ mod tests {
                 body: "Needs fix".to_string(),
                 created_at: "2025-01-02T00:00:00Z".to_string(),
                 author_association: Some("NONE".to_string()),
+                kind: FeedbackKind::Comment,
             },
         ];
         let result = format_pr_comments_for_prompt(&comments, 10);
@@ -520,6 +1393,7 @@ mod tests {
             body: String::new(),
             created_at: String::new(),
             author_association: Some("OWNER".to_string()),
+            kind: FeedbackKind::Comment,
         };
         assert!(trusted.is_trusted());
 
@@ -529,6 +1403,7 @@ mod tests {
             body: String::new(),
             created_at: String::new(),
             author_association: Some("MEMBER".to_string()),
+            kind: FeedbackKind::Comment,
         };
         assert!(member.is_trusted());
 
@@ -538,6 +1413,7 @@ mod tests {
             body: String::new(),
             created_at: String::new(),
             author_association: Some("NONE".to_string()),
+            kind: FeedbackKind::Comment,
         };
         assert!(!external.is_trusted());
 
@@ -547,6 +1423,7 @@ mod tests {
             body: String::new(),
             created_at: String::new(),
             author_association: None,
+            kind: FeedbackKind::Comment,
         };
         assert!(!missing.is_trusted());
     }
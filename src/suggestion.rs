@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::review_schema::ReviewFinding;
+
+/// Finding extension field holding a unified diff snippet for a trivial fix.
+pub const SUGGESTED_PATCH_FIELD: &str = "suggested_patch";
+
+/// HTML marker embedded in suggestion comments so re-runs do not post duplicates.
+pub const SUGGESTION_MARKER: &str = "<!-- rlph-suggestion:";
+
+/// A GitHub suggested change anchored to a line range on the PR head.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SuggestionComment {
+    pub finding_id: String,
+    pub path: String,
+    /// First replaced line (1-based, new side of the PR diff).
+    pub start_line: u32,
+    /// Last replaced line (inclusive).
+    pub end_line: u32,
+    pub body: String,
+}
+
+/// Build a suggestion comment from a finding's `suggested_patch`, if it has a usable one.
+///
+/// The patch must be a single hunk. Its old side (context and `-` lines) is the range
+/// being replaced; its new side (context and `+` lines) becomes the suggestion. Without
+/// an `@@` header the hunk is anchored at the finding's line. Pure insertions have no
+/// line to anchor to and are skipped.
+pub fn suggestion_from_finding(finding: &ReviewFinding) -> Option<SuggestionComment> {
+    let patch = finding.extra_text(SUGGESTED_PATCH_FIELD)?;
+    let mut path = finding.file.clone();
+    let mut start = None;
+    let mut old_count = 0u32;
+    let mut new_lines = Vec::new();
+
+    for line in patch.lines() {
+        if let Some(target) = line.strip_prefix("+++ ") {
+            let target = target.trim();
+            path = target.strip_prefix("b/").unwrap_or(target).to_string();
+        } else if line.starts_with("--- ") || line.starts_with('\\') {
+            continue;
+        } else if line.starts_with("@@") {
+            if start.is_some() || old_count > 0 || !new_lines.is_empty() {
+                return None;
+            }
+            start = Some(parse_hunk_header(line)?.0);
+        } else if let Some(added) = line.strip_prefix('+') {
+            new_lines.push(added);
+        } else if line.starts_with('-') {
+            old_count += 1;
+        } else {
+            // Context line; a leading space is conventional but often dropped.
+            new_lines.push(line.strip_prefix(' ').unwrap_or(line));
+            old_count += 1;
+        }
+    }
+
+    if old_count == 0 {
+        return None;
+    }
+    let start_line = start.unwrap_or(finding.line).max(1);
+    let body = format!(
+        "{SUGGESTION_MARKER}{} -->\n**{}** {}\n\n{}",
+        finding.id,
+        finding.severity.label(),
+        finding.description.trim(),
+        suggestion_block(&new_lines)
+    );
+    Some(SuggestionComment {
+        finding_id: finding.id.clone(),
+        path,
+        start_line,
+        end_line: start_line + old_count - 1,
+        body,
+    })
+}
+
+/// Wrap lines in a ```` ```suggestion ```` fence longer than any backtick run inside.
+fn suggestion_block(lines: &[&str]) -> String {
+    let longest = lines
+        .iter()
+        .flat_map(|l| l.split(|c| c != '`'))
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{fence}suggestion\n{}\n{fence}", lines.join("\n"))
+}
+
+/// Parse `@@ -a,b +c,d @@` into `(a, c, d)`; counts default to 1 when omitted.
+fn parse_hunk_header(line: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = line.split_whitespace().skip(1);
+    let old = parts.next()?.strip_prefix('-')?;
+    let new = parts.next()?.strip_prefix('+')?;
+    let old_start = old.split(',').next()?.parse().ok()?;
+    let (new_start, new_count) = match new.split_once(',') {
+        Some((s, c)) => (s.parse().ok()?, c.parse().ok()?),
+        None => (new.parse().ok()?, 1),
+    };
+    Some((old_start, new_start, new_count))
+}
+
+/// New-side line ranges (inclusive) covered by each file's hunks in a unified diff,
+/// i.e. the lines GitHub accepts review comments on.
+pub fn diff_line_ranges(diff: &str) -> HashMap<String, Vec<(u32, u32)>> {
+    let mut ranges: HashMap<String, Vec<(u32, u32)>> = HashMap::new();
+    let mut current: Option<String> = None;
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            current = None;
+        } else if let Some(target) = line.strip_prefix("+++ ") {
+            current = target.trim().strip_prefix("b/").map(str::to_string);
+        } else if line.starts_with("@@")
+            && let Some(path) = &current
+            && let Some((_, start, count)) = parse_hunk_header(line)
+            && count > 0
+        {
+            ranges
+                .entry(path.clone())
+                .or_default()
+                .push((start, start + count - 1));
+        }
+    }
+    ranges
+}
+
+/// Whether a suggestion's whole line range falls inside a single hunk of the PR diff.
+pub fn suggestion_in_diff(
+    suggestion: &SuggestionComment,
+    ranges: &HashMap<String, Vec<(u32, u32)>>,
+) -> bool {
+    ranges.get(&suggestion.path).is_some_and(|hunks| {
+        hunks
+            .iter()
+            .any(|&(lo, hi)| lo <= suggestion.start_line && suggestion.end_line <= hi)
+    })
+}
+
+/// Finding ids that already have a suggestion comment, read from existing comment bodies.
+pub fn posted_suggestion_ids<'a>(bodies: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    bodies
+        .into_iter()
+        .filter_map(|body| {
+            let rest = &body[body.find(SUGGESTION_MARKER)? + SUGGESTION_MARKER.len()..];
+            Some(rest[..rest.find("-->")?].trim().to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::make_finding;
+
+    fn with_patch(patch: &str) -> ReviewFinding {
+        let mut finding = make_finding("typo");
+        finding.extra.insert(
+            SUGGESTED_PATCH_FIELD.to_string(),
+            serde_json::Value::String(patch.to_string()),
+        );
+        finding
+    }
+
+    #[test]
+    fn test_suggestion_from_hunk() {
+        let finding = with_patch(
+            "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -10,3 +10,3 @@\n fn main() {\n-    let x = 1\n+    let x = 1;\n }\n",
+        );
+        let s = suggestion_from_finding(&finding).unwrap();
+        assert_eq!(s.path, "src/lib.rs");
+        assert_eq!((s.start_line, s.end_line), (10, 12));
+        assert!(
+            s.body
+                .starts_with("<!-- rlph-suggestion:typo -->\n**WARNING**")
+        );
+        assert!(
+            s.body
+                .ends_with("```suggestion\nfn main() {\n    let x = 1;\n}\n```"),
+            "got: {}",
+            s.body
+        );
+    }
+
+    #[test]
+    fn test_suggestion_without_header_uses_finding_line() {
+        let s = suggestion_from_finding(&with_patch("-old\n+new")).unwrap();
+        assert_eq!(s.path, "src/main.rs");
+        assert_eq!((s.start_line, s.end_line), (42, 42));
+    }
+
+    #[test]
+    fn test_suggestion_rejects_unusable_patches() {
+        assert!(suggestion_from_finding(&make_finding("none")).is_none());
+        assert!(suggestion_from_finding(&with_patch("+only added")).is_none());
+        assert!(
+            suggestion_from_finding(&with_patch("@@ -1 +1 @@\n-a\n+b\n@@ -9 +9 @@\n-c\n+d"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_suggestion_fence_outgrows_backticks() {
+        let s = suggestion_from_finding(&with_patch("-a\n+let s = \"```\";")).unwrap();
+        assert!(s.body.contains("````suggestion\n"));
+        assert!(s.body.ends_with("\n````"));
+    }
+
+    #[test]
+    fn test_diff_line_ranges_and_membership() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n@@ -1,2 +1,3 @@\n a\n+b\n c\n@@ -20 +21,4 @@\n x\ndiff --git a/gone.rs b/gone.rs\n--- a/gone.rs\n+++ /dev/null\n@@ -1 +0,0 @@\n-x\n";
+        let ranges = diff_line_ranges(diff);
+        assert_eq!(ranges["src/a.rs"], vec![(1, 3), (21, 24)]);
+        assert!(!ranges.contains_key("gone.rs"));
+
+        let mut s = suggestion_from_finding(&with_patch("@@ -2,2 +2,2 @@\n-b\n+B\n c")).unwrap();
+        s.path = "src/a.rs".to_string();
+        assert!(suggestion_in_diff(&s, &ranges));
+        s.start_line = 3;
+        s.end_line = 4;
+        assert!(!suggestion_in_diff(&s, &ranges));
+        s.path = "other.rs".to_string();
+        assert!(!suggestion_in_diff(&s, &ranges));
+    }
+
+    #[test]
+    fn test_posted_suggestion_ids() {
+        let ids = posted_suggestion_ids([
+            "<!-- rlph-suggestion:typo -->\nbody",
+            "unrelated",
+            "<!-- rlph-suggestion: other -->",
+        ]);
+        assert_eq!(ids, vec!["typo", "other"]);
+    }
+}
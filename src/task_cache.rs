@@ -0,0 +1,237 @@
+//! Per-iteration cache of task details over a [`TaskSource`].
+//!
+//! Task lists already carry everything `get_task_details` returns, so the
+//! cache is seeded from them and later lookups in the same iteration are
+//! served without another API call. Any mutation of a task drops its entry,
+//! and the orchestrator clears the whole cache at the start of each iteration.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use tracing::debug;
+
+use crate::error::Result;
+use crate::sources::{Task, TaskSource};
+
+/// A task source that remembers the tasks it has seen until they are mutated
+/// or the cache is cleared.
+pub struct CachedSource<S> {
+    inner: S,
+    tasks: Mutex<HashMap<String, Task>>,
+}
+
+impl<S> CachedSource<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            tasks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Forget every cached task.
+    pub fn clear(&self) {
+        self.tasks.lock().unwrap().clear();
+    }
+
+    /// Forget one task, so the next lookup reaches the source.
+    pub fn invalidate(&self, task_id: &str) {
+        self.tasks.lock().unwrap().remove(task_id);
+    }
+
+    fn remember(&self, tasks: &[Task]) {
+        let mut cache = self.tasks.lock().unwrap();
+        for task in tasks {
+            cache.insert(task.id.clone(), task.clone());
+        }
+    }
+
+    fn cached(&self, task_id: &str) -> Option<Task> {
+        self.tasks.lock().unwrap().get(task_id).cloned()
+    }
+
+    /// Run a mutation of `task_id`, dropping its entry whether or not it
+    /// succeeded: a failed call may still have changed the task.
+    fn mutate<T>(&self, task_id: &str, send: impl FnOnce(&S) -> Result<T>) -> Result<T> {
+        let result = send(&self.inner);
+        self.invalidate(task_id);
+        result
+    }
+}
+
+impl<S: TaskSource> TaskSource for CachedSource<S> {
+    fn fetch_eligible_tasks(&self) -> Result<Vec<Task>> {
+        let tasks = self.inner.fetch_eligible_tasks()?;
+        self.remember(&tasks);
+        Ok(tasks)
+    }
+
+    fn mark_in_progress(&self, task_id: &str) -> Result<()> {
+        self.mutate(task_id, |inner| inner.mark_in_progress(task_id))
+    }
+
+    fn mark_in_review(&self, task_id: &str) -> Result<()> {
+        self.mutate(task_id, |inner| inner.mark_in_review(task_id))
+    }
+
+    fn get_task_details(&self, task_id: &str) -> Result<Task> {
+        if let Some(task) = self.cached(task_id) {
+            debug!(task_id, "task details served from cache");
+            return Ok(task);
+        }
+        let task = self.inner.get_task_details(task_id)?;
+        self.remember(std::slice::from_ref(&task));
+        Ok(task)
+    }
+
+    /// Only the tasks missing from the cache are fetched, in one batch.
+    fn get_tasks_details(&self, task_ids: &[&str]) -> Result<Vec<Task>> {
+        let mut tasks = Vec::new();
+        let mut missing = Vec::new();
+        for &id in task_ids {
+            match self.cached(id) {
+                Some(task) => tasks.push(task),
+                None => missing.push(id),
+            }
+        }
+        if !missing.is_empty() {
+            let fetched = self.inner.get_tasks_details(&missing)?;
+            self.remember(&fetched);
+            tasks.extend(fetched);
+        }
+        Ok(tasks)
+    }
+
+    fn is_task_eligible(&self, task_id: &str) -> Result<bool> {
+        self.inner.is_task_eligible(task_id)
+    }
+
+    fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>> {
+        self.inner.fetch_closed_task_ids()
+    }
+
+    fn fetch_closed_task_ids_since(&self, since: u64) -> Result<HashSet<u64>> {
+        self.inner.fetch_closed_task_ids_since(since)
+    }
+
+    fn fetch_open_tasks_with_label(&self, label: &str) -> Result<Vec<Task>> {
+        let tasks = self.inner.fetch_open_tasks_with_label(label)?;
+        self.remember(&tasks);
+        Ok(tasks)
+    }
+
+    fn create_task(&self, title: &str, body: &str, labels: &[String]) -> Result<Task> {
+        self.inner.create_task(title, body, labels)
+    }
+
+    fn add_label(&self, task_id: &str, label: &str) -> Result<()> {
+        self.mutate(task_id, |inner| inner.add_label(task_id, label))
+    }
+
+    fn release_task(&self, task_id: &str) -> Result<()> {
+        self.mutate(task_id, |inner| inner.release_task(task_id))
+    }
+
+    fn mark_done(&self, task_id: &str) -> Result<()> {
+        self.mutate(task_id, |inner| inner.mark_done(task_id))
+    }
+
+    fn add_comment(&self, task_id: &str, body: &str) -> Result<()> {
+        self.mutate(task_id, |inner| inner.add_comment(task_id, body))
+    }
+
+    fn attach_link(&self, task_id: &str, url: &str, title: &str) -> Result<()> {
+        self.mutate(task_id, |inner| inner.attach_link(task_id, url, title))
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Source with a single task that counts detail lookups.
+    #[derive(Default)]
+    struct CountingSource {
+        lookups: AtomicUsize,
+    }
+
+    fn task() -> Task {
+        Task {
+            id: "7".to_string(),
+            title: "Cache me".to_string(),
+            body: String::new(),
+            labels: vec!["rlph".to_string()],
+            url: String::new(),
+            priority: None,
+            created_at: None,
+            updated_at: None,
+            milestone: None,
+            reactions: None,
+            assignee: None,
+            estimate: None,
+            project: None,
+        }
+    }
+
+    impl TaskSource for CountingSource {
+        fn fetch_eligible_tasks(&self) -> Result<Vec<Task>> {
+            Ok(vec![task()])
+        }
+        fn mark_in_progress(&self, _: &str) -> Result<()> {
+            Ok(())
+        }
+        fn mark_in_review(&self, _: &str) -> Result<()> {
+            Ok(())
+        }
+        fn get_task_details(&self, _: &str) -> Result<Task> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            Ok(task())
+        }
+        fn fetch_closed_task_ids(&self) -> Result<HashSet<u64>> {
+            Ok(HashSet::new())
+        }
+        fn fetch_open_tasks_with_label(&self, _: &str) -> Result<Vec<Task>> {
+            Ok(Vec::new())
+        }
+        fn create_task(&self, _: &str, _: &str, _: &[String]) -> Result<Task> {
+            Ok(task())
+        }
+        fn add_label(&self, _: &str, _: &str) -> Result<()> {
+            Ok(())
+        }
+        fn release_task(&self, _: &str) -> Result<()> {
+            Ok(())
+        }
+        fn add_comment(&self, _: &str, _: &str) -> Result<()> {
+            Ok(())
+        }
+        fn attach_link(&self, _: &str, _: &str, _: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_details_cached_until_mutated_or_cleared() {
+        let source = CachedSource::new(CountingSource::default());
+        let lookups = || source.inner.lookups.load(Ordering::SeqCst);
+
+        source.fetch_eligible_tasks().unwrap();
+        assert_eq!(source.get_task_details("7").unwrap().title, "Cache me");
+        assert_eq!(source.get_tasks_details(&["7"]).unwrap().len(), 1);
+        assert_eq!(lookups(), 0);
+
+        source.mark_in_progress("7").unwrap();
+        source.get_task_details("7").unwrap();
+        source.get_task_details("7").unwrap();
+        assert_eq!(lookups(), 1);
+
+        source.clear();
+        source.get_task_details("7").unwrap();
+        assert_eq!(lookups(), 2);
+    }
+}
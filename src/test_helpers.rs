@@ -1,3 +1,7 @@
+use std::sync::Mutex;
+
+use serde_json::Value;
+
 use crate::review_schema::{ReviewFinding, Severity};
 
 /// Create a `ReviewFinding` with sensible defaults for tests.
@@ -10,5 +14,55 @@ pub fn make_finding(id: &str) -> ReviewFinding {
         description: format!("{id} description"),
         category: Some("correctness".to_string()),
         depends_on: vec![],
+        extra: Default::default(),
+    }
+}
+
+/// Mock for the forge HTTP clients (GitHub, Gitea, Bitbucket). Replays canned
+/// responses keyed by `"<METHOD> <path>"` and records every request with its
+/// query and body. A key also answers the same path with a query string
+/// appended, and a key with a query string answers any path it prefixes.
+/// Unmatched requests get `Null`.
+#[derive(Default)]
+pub struct MockHttpClient {
+    pub responses: Mutex<Vec<(String, Value)>>,
+    pub requests: Mutex<Vec<(String, Vec<(String, String)>, Option<Value>)>>,
+}
+
+impl MockHttpClient {
+    pub fn respond(self, request: &str, response: Value) -> Self {
+        self.responses
+            .lock()
+            .unwrap()
+            .push((request.to_string(), response));
+        self
+    }
+
+    /// Record a request and return the first canned response matching it.
+    pub fn reply(
+        &self,
+        method: &str,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Value>,
+    ) -> std::result::Result<Value, String> {
+        let key = format!("{method} {path}");
+        self.requests.lock().unwrap().push((
+            key.clone(),
+            query
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            body.cloned(),
+        ));
+        let matches = |k: &str| {
+            key.strip_prefix(k)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('?') || k.contains('?'))
+        };
+        let mut responses = self.responses.lock().unwrap();
+        match responses.iter().position(|(k, _)| matches(k)) {
+            Some(i) => Ok(responses.remove(i).1),
+            None => Ok(Value::Null),
+        }
     }
 }
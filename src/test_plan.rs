@@ -0,0 +1,132 @@
+//! Test plans reported by the implement agent (`test_plan = true`).
+//!
+//! The agent ends its output with a `TEST_PLAN:` line holding a JSON object.
+//! The plan is rendered as a checklist in the PR body and handed to the review
+//! phases as `test_plan`, so the correctness review can check each claimed
+//! test against the diff.
+
+use serde::Deserialize;
+
+const PREFIX: &str = "TEST_PLAN:";
+
+/// Appended to the implement prompt as `test_plan_instructions`.
+pub const TEST_PLAN_INSTRUCTIONS: &str = "\
+Before the `IMPLEMENTATION_COMPLETE:` line, output your test plan as exactly one line \
+beginning with `TEST_PLAN:` followed by a JSON object on the same line, e.g.:
+
+TEST_PLAN: {\"items\": [{\"description\": \"rejects an empty config path\", \"test\": \"config::tests::test_empty_path\"}, {\"description\": \"manually ran `rlph --once` against a scratch repo\", \"test\": null}]}
+
+List each behavior the change needs verified. `test` names the automated test you \
+added or updated for it (test function, file, or command), or is `null` for a check \
+that is not automated. Only claim tests that are in your commits.";
+
+/// One behavior the implement agent says is verified.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TestPlanItem {
+    pub description: String,
+    /// Automated test covering the item; `None` for a manual check.
+    #[serde(default)]
+    pub test: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TestPlan {
+    pub items: Vec<TestPlanItem>,
+}
+
+impl TestPlan {
+    /// `## Test plan` checklist for the PR body.
+    pub fn checklist(&self) -> String {
+        let mut out = String::from("## Test plan\n\n");
+        for item in &self.items {
+            match &item.test {
+                Some(test) => out.push_str(&format!("- [ ] {} (`{test}`)\n", item.description)),
+                None => out.push_str(&format!("- [ ] {} (manual)\n", item.description)),
+            }
+        }
+        out
+    }
+
+    /// Numbered items for the `test_plan` review variable.
+    pub fn for_review(&self) -> String {
+        self.items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| match &item.test {
+                Some(test) => format!("{}. {} — test: `{test}`", i + 1, item.description),
+                None => format!("{}. {} — manual check", i + 1, item.description),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Parse the last `TEST_PLAN:` line of the implement agent's output. Fails with
+/// a reason when the line is missing, is not valid JSON, or lists no items.
+pub fn parse_test_plan(stdout: &str) -> std::result::Result<TestPlan, String> {
+    let json = stdout
+        .lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix(PREFIX))
+        .ok_or_else(|| format!("no `{PREFIX}` line in the implement output"))?;
+    let plan: TestPlan =
+        serde_json::from_str(json.trim()).map_err(|e| format!("invalid test plan JSON: {e}"))?;
+    if plan.items.is_empty() {
+        return Err("the test plan lists no items".to_string());
+    }
+    if plan
+        .items
+        .iter()
+        .any(|item| item.description.trim().is_empty())
+    {
+        return Err("a test plan item has an empty description".to_string());
+    }
+    Ok(plan)
+}
+
+/// PR body section for a plan that could not be parsed.
+pub fn missing_plan_note(reason: &str) -> String {
+    format!("## Test plan\n\n_The implement agent did not report a valid test plan: {reason}._\n")
+}
+
+/// `test_plan` review variable for a plan that could not be parsed.
+pub fn missing_plan_for_review(reason: &str) -> String {
+    format!(
+        "The implement agent did not report a valid test plan ({reason}). Check the diff's \
+         tests on your own."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_render_test_plan() {
+        let stdout = "working...\n\
+            TEST_PLAN: {\"items\": [{\"description\": \"parses ids\", \"test\": \"ids::tests::test_parse\"}, {\"description\": \"ran the CLI\"}]}\n\
+            IMPLEMENTATION_COMPLETE: added id parsing\n";
+        let plan = parse_test_plan(stdout).unwrap();
+        assert_eq!(plan.items.len(), 2);
+        assert_eq!(
+            plan.checklist(),
+            "## Test plan\n\n- [ ] parses ids (`ids::tests::test_parse`)\n- [ ] ran the CLI (manual)\n"
+        );
+        assert_eq!(
+            plan.for_review(),
+            "1. parses ids — test: `ids::tests::test_parse`\n2. ran the CLI — manual check"
+        );
+
+        assert!(parse_test_plan("IMPLEMENTATION_COMPLETE: done").is_err());
+        assert!(
+            parse_test_plan("TEST_PLAN: {\"items\": []}")
+                .unwrap_err()
+                .contains("no items")
+        );
+        assert!(
+            parse_test_plan("TEST_PLAN: [1, 2]")
+                .unwrap_err()
+                .starts_with("invalid test plan JSON")
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! Snapshot tests for prompt templates.
+//!
+//! Projects that override prompts in `.rlph/prompts` can render every built-in
+//! prompt, overrides included, with fixed sample variables and compare the
+//! output against golden files. After upgrading rlph, a failing snapshot shows
+//! how the prompts your agents receive have changed.
+//!
+//! ```no_run
+//! // Inside a `#[test]` function of your project:
+//! rlph::testing::assert_prompt_snapshots(".rlph/prompts", "tests/prompt-snapshots");
+//! ```
+//!
+//! Run with `RLPH_UPDATE_SNAPSHOTS=1` to write missing or changed snapshots.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::prompt_vars::PromptPhase;
+use crate::prompts::{BUILTIN_PROMPTS, PROMPT_VERSION, PromptEngine, findings_schema};
+
+/// Environment variable that makes snapshot assertions write the rendered
+/// output instead of comparing it.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "RLPH_UPDATE_SNAPSHOTS";
+
+/// Sample values for every variable `phase` provides. Each is `<name>`, so every
+/// `{% if %}` branch renders and each substitution is easy to spot, except
+/// `findings_schema`, which keeps the built-in partial.
+pub fn sample_vars(phase: PromptPhase) -> HashMap<String, String> {
+    phase
+        .vars()
+        .into_iter()
+        .map(|v| {
+            let value = match v.name {
+                "findings_schema" => findings_schema(PROMPT_VERSION, &[]),
+                name => format!("<{name}>"),
+            };
+            (v.name.to_string(), value)
+        })
+        .collect()
+}
+
+/// Render the built-in prompt `prompt` (e.g. `review-fix`) with [`sample_vars`]
+/// for its phase, using any override `engine` has for it.
+pub fn render_sample(engine: &PromptEngine, prompt: &str) -> Result<String> {
+    let phase = PromptPhase::of_prompt(prompt)
+        .ok_or_else(|| Error::Prompt(format!("'{prompt}' is not a built-in prompt")))?;
+    engine.render_step(phase, prompt, &sample_vars(phase))
+}
+
+/// Compare `actual` to the golden file at `path`, panicking with the first
+/// differing line on a mismatch. With `RLPH_UPDATE_SNAPSHOTS` set, the file is
+/// written instead.
+pub fn assert_snapshot(path: impl AsRef<Path>, actual: &str) {
+    if let Err(message) = check_snapshot(path.as_ref(), actual) {
+        panic!("{message}");
+    }
+}
+
+/// Render every built-in prompt with the overrides in `prompt_dir` and compare
+/// each to `<snapshot_dir>/<prompt>.md`. All mismatches are reported together.
+pub fn assert_prompt_snapshots(prompt_dir: impl AsRef<Path>, snapshot_dir: impl AsRef<Path>) {
+    let engine = PromptEngine::new(Some(prompt_dir.as_ref().display().to_string()));
+    let snapshot_dir = snapshot_dir.as_ref();
+    let failures: Vec<String> = BUILTIN_PROMPTS
+        .iter()
+        .filter_map(|prompt| {
+            let rendered = match render_sample(&engine, prompt) {
+                Ok(rendered) => rendered,
+                Err(e) => return Some(format!("{prompt}: {e}")),
+            };
+            check_snapshot(&snapshot_dir.join(format!("{prompt}.md")), &rendered).err()
+        })
+        .collect();
+    if !failures.is_empty() {
+        panic!(
+            "{} prompt snapshot(s) failed:\n\n{}",
+            failures.len(),
+            failures.join("\n\n")
+        );
+    }
+}
+
+fn check_snapshot(path: &Path, actual: &str) -> std::result::Result<(), String> {
+    if std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("failed to create {}: {e}", parent.display()))?;
+        }
+        return std::fs::write(path, actual)
+            .map_err(|e| format!("failed to write {}: {e}", path.display()));
+    }
+    let expected = std::fs::read_to_string(path).map_err(|e| {
+        format!(
+            "failed to read snapshot {}: {e} (run with {UPDATE_SNAPSHOTS_ENV}=1 to create it)",
+            path.display()
+        )
+    })?;
+    if expected == actual {
+        return Ok(());
+    }
+    // `lines()` ignores line endings and the trailing newline, so report
+    // differences only there on their own.
+    if expected.lines().eq(actual.lines()) {
+        let what = if expected.replace("\r\n", "\n") == actual.replace("\r\n", "\n") {
+            "line endings (CRLF vs LF) differ"
+        } else {
+            "trailing newline differs"
+        };
+        return Err(format!(
+            "snapshot {} {what}\n(run with {UPDATE_SNAPSHOTS_ENV}=1 to accept the new output)",
+            path.display()
+        ));
+    }
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(e), Some(a)) if e == a => line += 1,
+            (e, a) => {
+                return Err(format!(
+                    "snapshot {} differs at line {line}\n  expected: {}\n    actual: {}\n(run with {UPDATE_SNAPSHOTS_ENV}=1 to accept the new output)",
+                    path.display(),
+                    e.unwrap_or("<end of file>"),
+                    a.unwrap_or("<end of file>"),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_every_builtin_prompt_renders_with_sample_vars() {
+        let engine = PromptEngine::new(None);
+        for prompt in BUILTIN_PROMPTS {
+            let rendered = render_sample(&engine, prompt).unwrap_or_else(|e| panic!("{e}"));
+            assert!(!rendered.trim().is_empty(), "{prompt}");
+        }
+        let fix = render_sample(&engine, "fix").unwrap();
+        assert!(fix.contains("<finding_description>"));
+        assert!(render_sample(&engine, "team-review").is_err());
+    }
+
+    #[test]
+    fn test_check_snapshot_reports_first_difference() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("fix.md");
+        assert!(
+            check_snapshot(&path, "a\n")
+                .unwrap_err()
+                .contains("failed to read")
+        );
+
+        std::fs::write(&path, "same\nold\n").unwrap();
+        assert!(check_snapshot(&path, "same\nold\n").is_ok());
+        let err = check_snapshot(&path, "same\nnew\n").unwrap_err();
+        assert!(err.contains("differs at line 2"), "{err}");
+        assert!(err.contains("expected: old"), "{err}");
+        assert!(err.contains("actual: new"), "{err}");
+
+        let err = check_snapshot(&path, "same\n").unwrap_err();
+        assert!(err.contains("actual: <end of file>"), "{err}");
+
+        let err = check_snapshot(&path, "same\nold").unwrap_err();
+        assert!(err.contains("trailing newline differs"), "{err}");
+        let err = check_snapshot(&path, "same\r\nold\r\n").unwrap_err();
+        assert!(err.contains("line endings (CRLF vs LF) differ"), "{err}");
+    }
+}
@@ -0,0 +1,175 @@
+//! Prompts and git helpers for an implement phase split into time slices
+//! (`[implement_slices]`).
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::sources::Task;
+use crate::worktree::git_in_dir;
+
+/// How long the agent gets to commit and summarize after a slice runs out.
+pub const CHECKPOINT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+const DONE_PREFIX: &str = "PROGRESS_DONE:";
+const REMAINING_PREFIX: &str = "PROGRESS_REMAINING:";
+
+/// Prompt sent when a slice runs out: commit everything, then summarize.
+pub fn checkpoint_prompt(implement_prompt: &str) -> String {
+    format!(
+        "{implement_prompt}\n\n## Time Slice Over\n\n\
+         You were working on the task above in this worktree, and your time slice is \
+         over. Do not continue the task. Check `git status` and `git diff`, then:\n\n\
+         1. Commit all work in progress now, even if it is incomplete or does not build \
+         yet. Start the commit message with `WIP:`.\n\
+         2. End your reply with exactly these two lines:\n\
+         {DONE_PREFIX} <one sentence on what is finished>\n\
+         {REMAINING_PREFIX} <one sentence on what is left to do>"
+    )
+}
+
+/// Note appended to the implement prompt of slice `slice` (1-based).
+pub fn slice_notice(slice: u32, max_slices: u32, minutes: u64) -> String {
+    let last = if slice == max_slices {
+        " This is the last slice: finish the task in it, or leave it in a state \
+         that can be merged as a partial change and picked up later."
+    } else {
+        ""
+    };
+    format!(
+        "\n\n## Time Box\n\nThis is time slice {slice} of {max_slices}, about {minutes} minutes. \
+         Commit as you go: when the slice runs out you will be stopped and asked to \
+         commit whatever is in progress.{last}"
+    )
+}
+
+/// Implement prompt for a slice after the first, resuming from `progress`.
+pub fn continuation_prompt(
+    implement_prompt: &str,
+    slice: u32,
+    max_slices: u32,
+    minutes: u64,
+    progress: &str,
+) -> String {
+    format!(
+        "{implement_prompt}\n\n## Progress So Far\n\nEarlier time slices already worked on \
+         this task and committed their work on this branch. Review `git log` and continue \
+         from where they stopped; do not start over.\n\n{progress}{}",
+        slice_notice(slice, max_slices, minutes)
+    )
+}
+
+/// The `PROGRESS_DONE:` / `PROGRESS_REMAINING:` lines of a checkpoint reply,
+/// as Markdown. `None` when the agent gave neither.
+pub fn parse_progress(stdout: &str) -> Option<String> {
+    let last = |prefix: &str| {
+        stdout
+            .lines()
+            .rev()
+            .find_map(|line| line.trim().strip_prefix(prefix))
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    };
+    let done = last(DONE_PREFIX);
+    let remaining = last(REMAINING_PREFIX);
+    if done.is_none() && remaining.is_none() {
+        return None;
+    }
+    let mut progress = String::new();
+    if let Some(done) = done {
+        progress.push_str(&format!("**Done:** {done}\n"));
+    }
+    if let Some(remaining) = remaining {
+        progress.push_str(&format!("**Remaining:** {remaining}\n"));
+    }
+    Some(progress)
+}
+
+/// Commit anything the agent left uncommitted at the end of `slice`. Returns
+/// whether a commit was made.
+pub fn commit_wip(worktree: &Path, slice: u32) -> Result<bool> {
+    let git = |args: &[&str]| {
+        git_in_dir(worktree, args).map_err(|e| {
+            Error::Worktree(format!(
+                "git {} failed in {}: {}",
+                args.join(" "),
+                worktree.display(),
+                e.trim()
+            ))
+        })
+    };
+    if git(&["status", "--porcelain"])?.trim().is_empty() {
+        return Ok(false);
+    }
+    git(&["add", "-A"])?;
+    let message = format!("WIP: implement slice {slice}");
+    git(&["commit", "--quiet", "--no-verify", "-m", &message])?;
+    Ok(true)
+}
+
+/// Section added to the body of a partial PR.
+pub fn partial_pr_note(progress: &str) -> String {
+    format!(
+        "## Partial Implementation\n\nThe implement phase ran out of time slices before \
+         finishing. A follow-up issue covers the rest.\n\n{progress}"
+    )
+}
+
+/// Title and body of the issue that picks up a task left unfinished after the
+/// last slice. It depends on the original issue, so it waits until the partial
+/// PR lands.
+pub fn followup_task(task: &Task, pr_url: &str, progress: &str) -> (String, String) {
+    let title = format!("Finish: {}", task.title);
+    let pr = if pr_url.is_empty() {
+        String::new()
+    } else {
+        format!(" in {pr_url}")
+    };
+    let body = format!(
+        "The implement phase for #{id} ran out of time slices and was submitted as a \
+         partial change{pr}.\n\n{progress}\nDepends on #{id}\n\n---\n\n{body}",
+        id = task.id,
+        body = task.body.trim(),
+    );
+    (title, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_progress() {
+        let stdout =
+            "committed\nPROGRESS_DONE: parser and tests\nPROGRESS_REMAINING: wire into cli\n";
+        assert_eq!(
+            parse_progress(stdout).unwrap(),
+            "**Done:** parser and tests\n**Remaining:** wire into cli\n"
+        );
+        assert_eq!(
+            parse_progress("PROGRESS_REMAINING: docs").unwrap(),
+            "**Remaining:** docs\n"
+        );
+        assert!(parse_progress("all committed").is_none());
+    }
+
+    #[test]
+    fn test_commit_wip_only_when_dirty() {
+        let repo = TempDir::new().unwrap();
+        let git = |args: &[&str]| git_in_dir(repo.path(), args).unwrap();
+        git(&["init", "-q"]);
+        git(&["config", "user.name", "t"]);
+        git(&["config", "user.email", "t@t"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "base"]);
+
+        assert!(!commit_wip(repo.path(), 1).unwrap());
+        std::fs::write(repo.path().join("half.rs"), "fn half() {}\n").unwrap();
+        assert!(commit_wip(repo.path(), 1).unwrap());
+        assert_eq!(
+            git(&["log", "-1", "--format=%s"]).trim(),
+            "WIP: implement slice 1"
+        );
+        assert!(git(&["status", "--porcelain"]).trim().is_empty());
+    }
+}
@@ -0,0 +1,296 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::config::ToolchainConfig;
+use crate::coverage::DEFAULT_COVERAGE_COMMAND;
+
+/// A project type recognised by its marker file in the worktree root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Toolchain {
+    Rust,
+    Go,
+    Node,
+    Python,
+}
+
+impl Toolchain {
+    /// Detection order; the first detected toolchain supplies the default commands.
+    pub const ALL: [Toolchain; 4] = [
+        Toolchain::Rust,
+        Toolchain::Go,
+        Toolchain::Node,
+        Toolchain::Python,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Toolchain::Rust => "Rust",
+            Toolchain::Go => "Go",
+            Toolchain::Node => "Node.js",
+            Toolchain::Python => "Python",
+        }
+    }
+
+    pub fn marker(self) -> &'static str {
+        match self {
+            Toolchain::Rust => "Cargo.toml",
+            Toolchain::Go => "go.mod",
+            Toolchain::Node => "package.json",
+            Toolchain::Python => "pyproject.toml",
+        }
+    }
+
+    pub fn build_command(self) -> Option<&'static str> {
+        match self {
+            Toolchain::Rust => Some("cargo build"),
+            Toolchain::Go => Some("go build ./..."),
+            Toolchain::Node => Some("npm run build"),
+            Toolchain::Python => None,
+        }
+    }
+
+    pub fn test_command(self) -> &'static str {
+        match self {
+            Toolchain::Rust => "cargo test",
+            Toolchain::Go => "go test ./...",
+            Toolchain::Node => "npm test",
+            Toolchain::Python => "pytest",
+        }
+    }
+
+    pub fn lint_command(self) -> &'static str {
+        match self {
+            Toolchain::Rust => "cargo clippy --all-targets -- -D warnings",
+            Toolchain::Go => "go vet ./...",
+            Toolchain::Node => "npm run lint",
+            Toolchain::Python => "ruff check .",
+        }
+    }
+
+    /// A command printing coverage the coverage gate can parse (llvm-cov JSON or
+    /// LCOV on stdout). Go's cover profiles are neither, so Go has none.
+    pub fn coverage_command(self) -> Option<&'static str> {
+        match self {
+            Toolchain::Rust => Some(DEFAULT_COVERAGE_COMMAND),
+            Toolchain::Go => None,
+            Toolchain::Node => Some("npx c8 --reporter=text-lcov npm test"),
+            Toolchain::Python => Some("coverage run -m pytest && coverage lcov -o /dev/stdout"),
+        }
+    }
+}
+
+/// `command` limited to `crates` with `-p` flags after the cargo subcommand, e.g.
+/// `cargo test -p core`. Returned unchanged when `crates` is empty.
+pub fn scope_to_crates(command: &str, crates: &[String]) -> String {
+    let mut words = command.splitn(3, ' ');
+    let (Some(cargo), Some(subcommand)) = (words.next(), words.next()) else {
+        return command.to_string();
+    };
+    if cargo != "cargo" || crates.is_empty() {
+        return command.to_string();
+    }
+    let mut scoped = format!("{cargo} {subcommand}");
+    for name in crates {
+        scoped.push_str(" -p ");
+        scoped.push_str(name);
+    }
+    if let Some(rest) = words.next() {
+        scoped.push(' ');
+        scoped.push_str(rest);
+    }
+    scoped
+}
+
+/// Toolchains whose marker file exists in `dir`, in [`Toolchain::ALL`] order.
+pub fn detect_toolchains(dir: &Path) -> Vec<Toolchain> {
+    Toolchain::ALL
+        .into_iter()
+        .filter(|t| dir.join(t.marker()).is_file())
+        .collect()
+}
+
+/// Render the `project_toolchain` prompt variable for the worktree at `dir`:
+/// detected project types and the build/test/lint commands to run, with
+/// `[toolchain]` commands taking precedence. Detected cargo commands are limited
+/// to `affected_crates` when any are given. Empty when nothing is known.
+pub fn describe_toolchain(
+    dir: &Path,
+    config: &ToolchainConfig,
+    affected_crates: &[String],
+) -> String {
+    let detected = detect_toolchains(dir);
+    let primary = detected.first().copied();
+    let detected_command =
+        |command: Option<&str>| command.map(|c| scope_to_crates(c, affected_crates));
+    let commands = [
+        (
+            "Build",
+            config
+                .build
+                .clone()
+                .or(detected_command(primary.and_then(Toolchain::build_command))),
+        ),
+        (
+            "Test",
+            config
+                .test
+                .clone()
+                .or(detected_command(primary.map(Toolchain::test_command))),
+        ),
+        (
+            "Lint",
+            config
+                .lint
+                .clone()
+                .or(detected_command(primary.map(Toolchain::lint_command))),
+        ),
+    ];
+
+    let mut out = String::new();
+    if !detected.is_empty() {
+        let types: Vec<String> = detected
+            .iter()
+            .map(|t| format!("{} (`{}`)", t.name(), t.marker()))
+            .collect();
+        write!(out, "Project type: {}", types.join(", ")).unwrap();
+    }
+    for (label, command) in commands {
+        if let Some(command) = command {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            write!(out, "- {label}: `{command}`").unwrap();
+        }
+    }
+    out
+}
+
+/// Command for a coverage review phase without its own: `[toolchain] coverage`, then
+/// the first detected toolchain with a coverage command, then
+/// [`DEFAULT_COVERAGE_COMMAND`]. Detected and default cargo commands are limited
+/// to `affected_crates` when any are given.
+pub fn coverage_command(
+    dir: &Path,
+    config: &ToolchainConfig,
+    affected_crates: &[String],
+) -> String {
+    if let Some(command) = &config.coverage {
+        return command.clone();
+    }
+    let command = detect_toolchains(dir)
+        .into_iter()
+        .find_map(Toolchain::coverage_command)
+        .unwrap_or(DEFAULT_COVERAGE_COMMAND);
+    scope_to_crates(command, affected_crates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_toolchains_in_order() {
+        let dir = TempDir::new().unwrap();
+        assert!(detect_toolchains(dir.path()).is_empty());
+
+        std::fs::write(dir.path().join("pyproject.toml"), "").unwrap();
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        assert_eq!(
+            detect_toolchains(dir.path()),
+            vec![Toolchain::Node, Toolchain::Python]
+        );
+
+        std::fs::create_dir(dir.path().join("Cargo.toml")).unwrap();
+        assert_eq!(
+            detect_toolchains(dir.path()),
+            vec![Toolchain::Node, Toolchain::Python],
+            "a directory is not a marker file"
+        );
+    }
+
+    #[test]
+    fn test_describe_toolchain_detected_and_configured() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(
+            describe_toolchain(dir.path(), &ToolchainConfig::default(), &[]),
+            ""
+        );
+
+        let config = ToolchainConfig {
+            test: Some("make test".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            describe_toolchain(dir.path(), &config, &[]),
+            "- Test: `make test`"
+        );
+
+        std::fs::write(dir.path().join("pyproject.toml"), "").unwrap();
+        assert_eq!(
+            describe_toolchain(dir.path(), &config, &[]),
+            "Project type: Python (`pyproject.toml`)\n- Test: `make test`\n- Lint: `ruff check .`"
+        );
+
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        assert_eq!(
+            describe_toolchain(dir.path(), &ToolchainConfig::default(), &[]),
+            "Project type: Rust (`Cargo.toml`), Python (`pyproject.toml`)\n\
+             - Build: `cargo build`\n\
+             - Test: `cargo test`\n\
+             - Lint: `cargo clippy --all-targets -- -D warnings`"
+        );
+    }
+
+    #[test]
+    fn test_scope_to_crates() {
+        let crates = vec!["core".to_string(), "cli".to_string()];
+        assert_eq!(
+            scope_to_crates("cargo test", &crates),
+            "cargo test -p core -p cli"
+        );
+        assert_eq!(
+            scope_to_crates("cargo clippy --all-targets -- -D warnings", &crates[..1]),
+            "cargo clippy -p core --all-targets -- -D warnings"
+        );
+        assert_eq!(scope_to_crates("cargo test", &[]), "cargo test");
+        assert_eq!(scope_to_crates("npm test", &crates), "npm test");
+
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        let described = describe_toolchain(dir.path(), &ToolchainConfig::default(), &crates);
+        assert!(described.contains("- Test: `cargo test -p core -p cli`"));
+        assert_eq!(
+            coverage_command(dir.path(), &ToolchainConfig::default(), &crates[1..]),
+            "cargo llvm-cov -p cli --json"
+        );
+    }
+
+    #[test]
+    fn test_coverage_command_precedence() {
+        let dir = TempDir::new().unwrap();
+        let default = ToolchainConfig::default();
+        assert_eq!(
+            coverage_command(dir.path(), &default, &[]),
+            DEFAULT_COVERAGE_COMMAND
+        );
+
+        std::fs::write(dir.path().join("go.mod"), "module x").unwrap();
+        assert_eq!(
+            coverage_command(dir.path(), &default, &[]),
+            DEFAULT_COVERAGE_COMMAND
+        );
+
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        assert_eq!(
+            coverage_command(dir.path(), &default, &[]),
+            "npx c8 --reporter=text-lcov npm test"
+        );
+
+        let config = ToolchainConfig {
+            coverage: Some("make coverage".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(coverage_command(dir.path(), &config, &[]), "make coverage");
+    }
+}
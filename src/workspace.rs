@@ -0,0 +1,308 @@
+use std::path::Path;
+
+use tracing::{debug, warn};
+
+use crate::worktree::git_in_dir;
+
+/// Root files whose changes can affect every crate in a Cargo workspace.
+const WORKSPACE_WIDE_FILES: &[&str] = &["Cargo.toml", "Cargo.lock", "rust-toolchain.toml"];
+
+/// A package of the Cargo workspace rooted at a checkout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceMember {
+    pub name: String,
+    /// Package directory relative to the workspace root; empty for a root package.
+    pub dir: String,
+}
+
+/// Packages of the Cargo workspace at `root`, sorted by name. Empty when `root`
+/// has no `Cargo.toml` with a `[workspace]` table. `members` entries may use `*`
+/// and `?` wildcards; `exclude` entries are matched literally.
+pub fn workspace_members(root: &Path) -> Vec<WorkspaceMember> {
+    let Some(manifest) = read_manifest(&root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Some(workspace) = manifest.get("workspace") else {
+        return Vec::new();
+    };
+    let patterns = string_list(workspace.get("members"));
+    let excluded = string_list(workspace.get("exclude"));
+
+    let mut members = Vec::new();
+    if let Some(name) = package_name(&manifest) {
+        members.push(WorkspaceMember {
+            name,
+            dir: String::new(),
+        });
+    }
+    for pattern in &patterns {
+        for dir in expand_member_pattern(root, pattern) {
+            if dir.is_empty() || excluded.iter().any(|e| normalize(e) == dir) {
+                continue;
+            }
+            let Some(name) = read_manifest(&root.join(&dir).join("Cargo.toml"))
+                .as_ref()
+                .and_then(package_name)
+            else {
+                debug!(%dir, "workspace member has no package manifest");
+                continue;
+            };
+            members.push(WorkspaceMember { name, dir });
+        }
+    }
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+    members.dedup_by(|a, b| a.name == b.name);
+    members
+}
+
+/// Names of the members owning `files` (repo-relative paths), sorted. Each file
+/// belongs to the member with the deepest directory containing it; files outside
+/// every member are ignored. A change to a workspace-wide file such as the root
+/// `Cargo.lock` affects every member.
+pub fn affected_crates(members: &[WorkspaceMember], files: &[String]) -> Vec<String> {
+    if files
+        .iter()
+        .any(|f| WORKSPACE_WIDE_FILES.contains(&f.as_str()) || f.starts_with(".cargo/"))
+    {
+        return members.iter().map(|m| m.name.clone()).collect();
+    }
+    let mut crates: Vec<String> = files
+        .iter()
+        .filter_map(|file| {
+            members
+                .iter()
+                .filter(|m| m.dir.is_empty() || file.starts_with(&format!("{}/", m.dir)))
+                .max_by_key(|m| m.dir.len())
+        })
+        .map(|m| m.name.clone())
+        .collect();
+    crates.sort();
+    crates.dedup();
+    crates
+}
+
+/// Members of the workspace at `worktree` changed by its branch relative to
+/// `base` (e.g. `origin/main`). Best-effort: empty outside a Cargo workspace or
+/// when the diff fails.
+pub fn branch_affected_crates(worktree: &Path, base: &str) -> Vec<String> {
+    let members = workspace_members(worktree);
+    if members.is_empty() {
+        return Vec::new();
+    }
+    let range = format!("{base}...HEAD");
+    match git_in_dir(worktree, &["diff", "--name-only", &range]) {
+        Ok(output) => {
+            let files: Vec<String> = output
+                .lines()
+                .map(str::trim)
+                .filter(|f| !f.is_empty())
+                .map(str::to_string)
+                .collect();
+            affected_crates(&members, &files)
+        }
+        Err(e) => {
+            warn!(
+                base,
+                error = e.trim(),
+                "failed to diff branch for affected crates"
+            );
+            Vec::new()
+        }
+    }
+}
+
+fn read_manifest(path: &Path) -> Option<toml::Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match toml::from_str(&content) {
+        Ok(manifest) => Some(manifest),
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "failed to parse Cargo manifest");
+            None
+        }
+    }
+}
+
+fn package_name(manifest: &toml::Value) -> Option<String> {
+    manifest
+        .get("package")?
+        .get("name")?
+        .as_str()
+        .map(str::to_string)
+}
+
+fn string_list(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(toml::Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A member path with `./` prefixes and trailing slashes removed; `.` is the root.
+fn normalize(path: &str) -> String {
+    path.trim_end_matches('/')
+        .split('/')
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Directories under `root` matching a `members` pattern, relative to `root`.
+fn expand_member_pattern(root: &Path, pattern: &str) -> Vec<String> {
+    let mut dirs = vec![String::new()];
+    for component in normalize(pattern).split('/').filter(|c| !c.is_empty()) {
+        let mut next = Vec::new();
+        for dir in &dirs {
+            let join = |name: &str| {
+                if dir.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{dir}/{name}")
+                }
+            };
+            if !component.contains(['*', '?']) {
+                next.push(join(component));
+                continue;
+            }
+            let Ok(entries) = std::fs::read_dir(root.join(dir)) else {
+                continue;
+            };
+            let mut matched: Vec<String> = entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| wildcard_match(component, name))
+                .map(|name| join(&name))
+                .collect();
+            matched.sort();
+            next.extend(matched);
+        }
+        dirs = next;
+    }
+    dirs
+}
+
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_package(root: &Path, dir: &str, name: &str) {
+        let dir = root.join(dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n"),
+        )
+        .unwrap();
+    }
+
+    fn member(name: &str, dir: &str) -> WorkspaceMember {
+        WorkspaceMember {
+            name: name.to_string(),
+            dir: dir.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_workspace_members_expands_globs_and_excludes() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        assert!(workspace_members(root).is_empty());
+
+        write_package(root, "crates/core", "app-core");
+        write_package(root, "crates/cli", "app-cli");
+        write_package(root, "crates/legacy", "app-legacy");
+        write_package(root, "tools/xtask", "xtask");
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\", \"./tools/xtask/\"]\nexclude = [\"crates/legacy\"]\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            workspace_members(root),
+            vec![
+                member("app-cli", "crates/cli"),
+                member("app-core", "crates/core"),
+                member("xtask", "tools/xtask"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_package_is_not_a_workspace() {
+        let dir = TempDir::new().unwrap();
+        write_package(dir.path(), "", "solo");
+        assert!(workspace_members(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_affected_crates() {
+        let members = [
+            member("app", ""),
+            member("app-cli", "crates/cli"),
+            member("app-core", "crates/core"),
+        ];
+        let files = |paths: &[&str]| paths.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            affected_crates(&members, &files(&["crates/core/src/lib.rs"])),
+            vec!["app-core"]
+        );
+        assert_eq!(
+            affected_crates(
+                &members,
+                &files(&["crates/cli/src/main.rs", "src/lib.rs", "crates/core2/x.rs"])
+            ),
+            vec!["app", "app-cli"]
+        );
+        assert_eq!(
+            affected_crates(&members, &files(&["Cargo.lock"])),
+            vec!["app", "app-cli", "app-core"]
+        );
+        assert!(affected_crates(&members[1..], &files(&["README.md"])).is_empty());
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*", "core"));
+        assert!(wildcard_match("app-*", "app-core"));
+        assert!(wildcard_match("c?re", "core"));
+        assert!(wildcard_match("*-*-x", "a-b-c-x"));
+        assert!(!wildcard_match("app-*", "lib-core"));
+        assert!(!wildcard_match("c?re", "coore"));
+    }
+}
@@ -1,9 +1,62 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use regex::Regex;
 use tracing::{debug, info, warn};
 
 use crate::error::{Error, Result};
+use crate::prompts::render_template;
+use crate::rlphignore::RlphIgnore;
+
+/// Default branch naming template: `rlph-<issue>-<slug>`.
+pub const DEFAULT_BRANCH_TEMPLATE: &str = "rlph-{{ issue }}-{{ slug }}";
+
+/// Render a branch name from a template with `issue`, `slug`, and `user`
+/// variables, then validate it.
+pub fn render_branch_name(
+    template: &str,
+    issue_number: u64,
+    slug: &str,
+    user: &str,
+) -> Result<String> {
+    let vars = HashMap::from([
+        ("issue".to_string(), issue_number.to_string()),
+        ("slug".to_string(), slug.to_string()),
+        ("user".to_string(), user.to_string()),
+    ]);
+    let name = render_template(template, &vars)?.trim().to_string();
+    validate_branch_name(&name)?;
+    Ok(name)
+}
+
+/// Build a regex matching any branch name `template` can produce.
+pub fn branch_template_regex(template: &str) -> Result<Regex> {
+    const ISSUE: &str = "\u{1}issue\u{1}";
+    const SLUG: &str = "\u{1}slug\u{1}";
+    const USER: &str = "\u{1}user\u{1}";
+    let vars = HashMap::from([
+        ("issue".to_string(), ISSUE.to_string()),
+        ("slug".to_string(), SLUG.to_string()),
+        ("user".to_string(), USER.to_string()),
+    ]);
+    let rendered = render_template(template, &vars)?;
+    let pattern = regex::escape(rendered.trim())
+        .replace(ISSUE, r"\d+")
+        .replace(SLUG, "[a-z0-9-]*")
+        .replace(USER, "[A-Za-z0-9_.-]+");
+    Regex::new(&format!("^{pattern}$"))
+        .map_err(|e| Error::Worktree(format!("invalid branch template: {e}")))
+}
+
+/// The `user` value for branch templates: `$USER`, slugified, or `rlph`.
+pub fn branch_user() -> String {
+    std::env::var("USER")
+        .ok()
+        .map(|u| WorktreeManager::slugify(&u))
+        .filter(|u| !u.is_empty())
+        .unwrap_or_else(|| "rlph".to_string())
+}
 
 /// Validate that a branch name is safe: matches `^[a-zA-Z0-9/_.-]+$` and does not start with `refs/`.
 pub fn validate_branch_name(name: &str) -> Result<()> {
@@ -46,6 +99,134 @@ pub(crate) fn git_in_dir(cwd: &Path, args: &[&str]) -> std::result::Result<Strin
     }
 }
 
+/// HEAD and working-tree status of a worktree, used to detect modifications.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorktreeSnapshot {
+    head: String,
+    status: String,
+}
+
+impl WorktreeSnapshot {
+    pub fn capture(path: &Path) -> Result<Self> {
+        let git = |args: &[&str]| {
+            git_in_dir(path, args).map_err(|e| {
+                Error::Worktree(format!(
+                    "failed to inspect {}: {}",
+                    path.display(),
+                    e.trim()
+                ))
+            })
+        };
+        Ok(Self {
+            head: git(&["rev-parse", "HEAD"])?.trim().to_string(),
+            status: git(&["status", "--porcelain", "--untracked-files=all"])?,
+        })
+    }
+
+    /// Describe what changed in the worktree since this snapshot, or `None` if nothing did.
+    /// Ignored files (build output, shared caches) and paths matched by `ignore`
+    /// do not count.
+    pub fn changes_since(&self, path: &Path, ignore: &RlphIgnore) -> Result<Option<String>> {
+        let now = Self::capture(path)?;
+        if now == *self {
+            return Ok(None);
+        }
+        let mut changes = Vec::new();
+        if now.head != self.head {
+            changes.push(format!("HEAD moved from {} to {}", self.head, now.head));
+        }
+        let relevant = |line: &&str| !ignore.is_ignored(status_path(line));
+        let before: Vec<&str> = self.status.lines().filter(relevant).collect();
+        let after: Vec<&str> = now.status.lines().filter(relevant).collect();
+        changes.extend(
+            after
+                .iter()
+                .filter(|line| !before.contains(line))
+                .map(|line| line.trim().to_string()),
+        );
+        if changes.is_empty() && before.iter().any(|line| !after.contains(line)) {
+            changes.push("working tree status changed".to_string());
+        }
+        if changes.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(changes.join(", ")))
+    }
+
+    /// Repo-relative files changed since this snapshot, committed or not, sorted.
+    /// Files already dirty when the snapshot was taken count only if their status
+    /// changed.
+    pub fn changed_files_since(&self, path: &Path) -> Result<Vec<String>> {
+        let now = Self::capture(path)?;
+        let mut files: Vec<String> = Vec::new();
+        if now.head != self.head {
+            let range = format!("{}..{}", self.head, now.head);
+            let diff = git_in_dir(path, &["diff", "--name-only", "--no-renames", &range]).map_err(
+                |e| {
+                    Error::Worktree(format!(
+                        "failed to diff {} in {}: {}",
+                        range,
+                        path.display(),
+                        e.trim()
+                    ))
+                },
+            )?;
+            files.extend(diff.lines().map(str::to_string));
+        }
+        let before: Vec<&str> = self.status.lines().collect();
+        files.extend(
+            now.status
+                .lines()
+                .filter(|line| !before.contains(line))
+                .map(|line| status_path(line).to_string()),
+        );
+        files.retain(|f| !f.is_empty());
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+}
+
+/// The path of a `git status --porcelain` line (the new path for renames).
+fn status_path(line: &str) -> &str {
+    let path = line.get(3..).unwrap_or_default();
+    path.rsplit_once(" -> ").map_or(path, |(_, new)| new)
+}
+
+/// Commit identity and signing applied to every worktree rlph creates, as
+/// worktree-local git config so the main checkout keeps the user's own.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitIdentity {
+    pub user_name: Option<String>,
+    pub user_email: Option<String>,
+    /// `user.signingkey`; setting it turns on `commit.gpgsign` and `tag.gpgsign`.
+    pub signing_key: Option<String>,
+    /// `gpg.format`: `openpgp`, `ssh`, or `x509`. Git's default (`openpgp`) when unset.
+    pub signing_format: Option<String>,
+}
+
+impl GitIdentity {
+    /// The `git config` entries to set, in order. Empty when nothing is configured.
+    pub fn config_entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = Vec::new();
+        if let Some(name) = &self.user_name {
+            entries.push(("user.name", name.clone()));
+        }
+        if let Some(email) = &self.user_email {
+            entries.push(("user.email", email.clone()));
+        }
+        if let Some(key) = &self.signing_key {
+            if let Some(format) = &self.signing_format {
+                entries.push(("gpg.format", format.clone()));
+            }
+            entries.push(("user.signingkey", key.clone()));
+            entries.push(("commit.gpgsign", "true".to_string()));
+            entries.push(("tag.gpgsign", "true".to_string()));
+        }
+        entries
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct WorktreeInfo {
     pub path: PathBuf,
@@ -57,6 +238,11 @@ pub struct WorktreeManager {
     repo_root: PathBuf,
     base_dir: PathBuf,
     base_branch: String,
+    branch_template: String,
+    cache_root: PathBuf,
+    shared_caches: Vec<String>,
+    push_remote: String,
+    git_identity: GitIdentity,
 }
 
 impl WorktreeManager {
@@ -65,9 +251,49 @@ impl WorktreeManager {
             repo_root,
             base_dir,
             base_branch,
+            branch_template: DEFAULT_BRANCH_TEMPLATE.to_string(),
+            cache_root: PathBuf::new(),
+            shared_caches: Vec::new(),
+            push_remote: "origin".to_string(),
+            git_identity: GitIdentity::default(),
         }
     }
 
+    /// Symlink each of `dirs` (paths relative to the worktree root) to a shared
+    /// directory under `cache_root` in every worktree this manager creates.
+    pub fn with_shared_caches(mut self, cache_root: PathBuf, dirs: Vec<String>) -> Self {
+        self.cache_root = cache_root;
+        self.shared_caches = dirs;
+        self
+    }
+
+    /// Fetch PR branches from `remote` (e.g. a fork) instead of `origin`. The
+    /// base branch is always fetched from `origin`.
+    pub fn with_push_remote(mut self, remote: impl Into<String>) -> Self {
+        self.push_remote = remote.into();
+        self
+    }
+
+    /// Set the commit identity and signing key in every worktree this manager
+    /// creates or reuses.
+    pub fn with_git_identity(mut self, identity: GitIdentity) -> Self {
+        self.git_identity = identity;
+        self
+    }
+
+    /// Use a custom template for branches created by [`create`](Self::create).
+    pub fn with_branch_template(mut self, template: impl Into<String>) -> Self {
+        self.branch_template = template.into();
+        self
+    }
+
+    /// Whether `branch` was created by rlph: any `rlph-` branch, or one
+    /// matching the configured branch template.
+    pub fn is_managed_branch(&self, branch: &str) -> bool {
+        branch.starts_with("rlph-")
+            || branch_template_regex(&self.branch_template).is_ok_and(|re| re.is_match(branch))
+    }
+
     /// Generate the worktree directory name: `rlph-{issue_number}-{slug}`.
     pub fn worktree_name(issue_number: u64, slug: &str) -> String {
         format!("rlph-{issue_number}-{slug}")
@@ -114,6 +340,17 @@ impl WorktreeManager {
 
     /// Create a worktree for an issue. Reuses existing worktrees.
     pub fn create(&self, issue_number: u64, slug: &str) -> Result<WorktreeInfo> {
+        self.create_on(issue_number, slug, &self.base_branch)
+    }
+
+    /// Like [`create`](Self::create), but a new worktree branches from
+    /// `origin/<base_branch>` instead of the manager's base branch.
+    pub fn create_on(
+        &self,
+        issue_number: u64,
+        slug: &str,
+        base_branch: &str,
+    ) -> Result<WorktreeInfo> {
         // Check for existing worktree
         if let Some(existing) = self.find_existing(issue_number)? {
             info!(
@@ -121,12 +358,14 @@ impl WorktreeManager {
                 path = %existing.path.display(),
                 "reusing existing worktree"
             );
+            self.link_shared_caches(&existing.path);
+            self.apply_git_identity(&existing.path)?;
             return Ok(existing);
         }
 
         let name = Self::worktree_name(issue_number, slug);
         let path = self.base_dir.join(&name);
-        let branch = name.clone();
+        let branch = render_branch_name(&self.branch_template, issue_number, slug, &branch_user())?;
 
         // Ensure base directory exists
         std::fs::create_dir_all(&self.base_dir).map_err(|e| {
@@ -137,10 +376,10 @@ impl WorktreeManager {
         })?;
 
         // Fetch latest base branch from origin (mandatory, with retries)
-        self.fetch_with_retry(&self.base_branch, 3)?;
+        self.fetch_with_retry("origin", base_branch, 3)?;
 
         // Start point is always origin/<base> since fetch above succeeded
-        let start_point = format!("origin/{}", self.base_branch);
+        let start_point = format!("origin/{base_branch}");
 
         // Try creating with a new branch from main
         let create_result = match self.git_worktree_add(&path, &branch, true, Some(&start_point)) {
@@ -175,9 +414,10 @@ impl WorktreeManager {
             path = %canonical_path.display(),
             branch = %branch,
             commit = %commit_sha,
-            "created worktree from origin/{}",
-            self.base_branch
+            "created worktree from origin/{base_branch}"
         );
+        self.link_shared_caches(&canonical_path);
+        self.apply_git_identity(&canonical_path)?;
         Ok(WorktreeInfo {
             path: canonical_path,
             branch,
@@ -208,11 +448,11 @@ impl WorktreeManager {
                 "reusing existing PR review worktree, updating to latest"
             );
 
-            // Fetch latest from origin so we don't review stale code
-            self.fetch_with_retry(branch, 3)?;
+            // Fetch latest from the push remote so we don't review stale code
+            self.fetch_with_retry(&self.push_remote, branch, 3)?;
 
             // Reset the worktree to the latest remote HEAD
-            let remote_ref = format!("origin/{branch}");
+            let remote_ref = format!("{}/{branch}", self.push_remote);
             let reset_output = Command::new("git")
                 .args(["reset", "--hard", &remote_ref])
                 .current_dir(&existing.path)
@@ -227,6 +467,8 @@ impl WorktreeManager {
                 )));
             }
 
+            self.link_shared_caches(&existing.path);
+            self.apply_git_identity(&existing.path)?;
             return Ok(existing);
         }
 
@@ -239,10 +481,10 @@ impl WorktreeManager {
             ))
         })?;
 
-        // Fetch latest branch from origin (mandatory, with retries)
-        self.fetch_with_retry(branch, 3)?;
+        // Fetch latest branch from the push remote (mandatory, with retries)
+        self.fetch_with_retry(&self.push_remote, branch, 3)?;
 
-        let remote_ref = format!("origin/{branch}");
+        let remote_ref = format!("{}/{branch}", self.push_remote);
         let local_ref = format!("refs/heads/{local_branch}");
         let local_branch_exists = self
             .git(&["show-ref", "--verify", "--quiet", &local_ref])
@@ -281,6 +523,8 @@ impl WorktreeManager {
             commit = %commit_sha,
             "created PR review worktree"
         );
+        self.link_shared_caches(&canonical_path);
+        self.apply_git_identity(&canonical_path)?;
         Ok(WorktreeInfo {
             path: canonical_path,
             branch: local_branch,
@@ -294,7 +538,7 @@ impl WorktreeManager {
         validate_branch_name(branch_name)?;
 
         // Fetch latest remote branch
-        self.fetch_with_retry(remote_branch, 3)?;
+        self.fetch_with_retry(&self.push_remote, remote_branch, 3)?;
 
         std::fs::create_dir_all(&self.base_dir).map_err(|e| {
             Error::Worktree(format!(
@@ -321,10 +565,12 @@ impl WorktreeManager {
         }
 
         // Create worktree with new branch from remote ref
-        let remote_ref = format!("origin/{remote_branch}");
+        let remote_ref = format!("{}/{remote_branch}", self.push_remote);
         self.git_worktree_add(&path, branch_name, true, Some(&remote_ref))?;
 
         let canonical = path.canonicalize().unwrap_or(path);
+        self.link_shared_caches(&canonical);
+        self.apply_git_identity(&canonical)?;
         Ok(WorktreeInfo {
             path: canonical,
             branch: branch_name.to_string(),
@@ -358,7 +604,7 @@ impl WorktreeManager {
 
         // Clean up the branch
         if let Some(branch) = branch {
-            if !branch.starts_with("rlph-") {
+            if !self.is_managed_branch(&branch) {
                 info!(
                     branch = %branch,
                     "skipping deletion for non-rlph branch after worktree removal"
@@ -374,6 +620,145 @@ impl WorktreeManager {
         Ok(())
     }
 
+    /// Delete a finished rlph branch locally and, with `include_remote`, from
+    /// the push remote. Branches that are already gone are not an error, and
+    /// branches rlph did not create are left alone.
+    pub fn delete_branch(&self, branch: &str, include_remote: bool) -> Result<()> {
+        if !self.is_managed_branch(branch) {
+            info!(branch, "not deleting non-rlph branch");
+            return Ok(());
+        }
+
+        let local_ref = format!("refs/heads/{branch}");
+        if self
+            .git(&["show-ref", "--verify", "--quiet", &local_ref])
+            .is_ok()
+        {
+            self.git(&["branch", "-D", branch])
+                .map_err(|e| Error::Worktree(format!("failed to delete branch {branch}: {e}")))?;
+            info!(branch, "deleted local branch");
+        }
+
+        if include_remote {
+            match self.git(&["push", &self.push_remote, "--delete", branch]) {
+                Ok(_) => info!(branch, remote = self.push_remote, "deleted remote branch"),
+                Err(e) if e.contains("remote ref does not exist") => {
+                    debug!(branch, "remote branch already deleted");
+                }
+                Err(e) => {
+                    return Err(Error::Worktree(format!(
+                        "failed to delete {}/{branch}: {}",
+                        self.push_remote,
+                        e.trim()
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the configured identity as worktree-local config. This needs
+    /// `extensions.worktreeConfig`, without which `git config` in a linked
+    /// worktree would change the shared repository config.
+    fn apply_git_identity(&self, worktree: &Path) -> Result<()> {
+        let entries = self.git_identity.config_entries();
+        if entries.is_empty() {
+            return Ok(());
+        }
+        self.git(&["config", "extensions.worktreeConfig", "true"])
+            .map_err(|e| Error::Worktree(format!("failed to enable worktree config: {e}")))?;
+        for (key, value) in entries {
+            git_in_dir(worktree, &["config", "--worktree", key, &value]).map_err(|e| {
+                Error::Worktree(format!(
+                    "failed to set {key} in {}: {e}",
+                    worktree.display()
+                ))
+            })?;
+        }
+        debug!(path = %worktree.display(), "applied git identity");
+        Ok(())
+    }
+
+    /// Symlink configured shared cache dirs into `worktree` and exclude the links
+    /// from git so agents never commit them. Best-effort: a cache that can't be
+    /// linked only costs a slower build, so failures are logged, not returned.
+    fn link_shared_caches(&self, worktree: &Path) {
+        if self.shared_caches.is_empty() {
+            return;
+        }
+        let mut linked = Vec::new();
+        for dir in &self.shared_caches {
+            let target = self.cache_root.join(dir);
+            if let Err(e) = std::fs::create_dir_all(&target) {
+                warn!(dir, error = %e, "failed to create shared cache dir");
+                continue;
+            }
+            let target = target.canonicalize().unwrap_or(target);
+            let link = worktree.join(dir);
+            if link.is_symlink() {
+                linked.push(dir.as_str());
+                continue;
+            }
+            if link.exists() {
+                warn!(
+                    path = %link.display(),
+                    "path already exists in worktree, not sharing cache"
+                );
+                continue;
+            }
+            if let Some(parent) = link.parent()
+                && let Err(e) = std::fs::create_dir_all(parent)
+            {
+                warn!(dir, error = %e, "failed to create cache link parent");
+                continue;
+            }
+            match std::os::unix::fs::symlink(&target, &link) {
+                Ok(()) => {
+                    debug!(link = %link.display(), target = %target.display(), "linked shared cache");
+                    linked.push(dir.as_str());
+                }
+                Err(e) => warn!(dir, error = %e, "failed to link shared cache"),
+            }
+        }
+        if let Err(e) = self.exclude_paths(&linked) {
+            warn!(error = %e, "failed to exclude shared caches from git");
+        }
+    }
+
+    /// Append root-anchored entries to the repository's `info/exclude`. No
+    /// trailing slash: git sees a symlink as a file, so `dir/` would not match.
+    fn exclude_paths(&self, paths: &[&str]) -> Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let common_dir = self
+            .git(&["rev-parse", "--git-common-dir"])
+            .map_err(|e| Error::Worktree(format!("failed to locate git dir: {e}")))?;
+        let info_dir = self.repo_root.join(common_dir.trim()).join("info");
+        std::fs::create_dir_all(&info_dir).map_err(|e| {
+            Error::Worktree(format!("failed to create {}: {e}", info_dir.display()))
+        })?;
+        let exclude = info_dir.join("exclude");
+        let existing = std::fs::read_to_string(&exclude).unwrap_or_default();
+        let mut content = existing.clone();
+        for path in paths {
+            let entry = format!("/{}", path.trim_matches('/'));
+            if !existing.lines().any(|l| l.trim() == entry) {
+                if !content.is_empty() && !content.ends_with('\n') {
+                    content.push('\n');
+                }
+                content.push_str(&entry);
+                content.push('\n');
+            }
+        }
+        if content != existing {
+            std::fs::write(&exclude, content).map_err(|e| {
+                Error::Worktree(format!("failed to write {}: {e}", exclude.display()))
+            })?;
+        }
+        Ok(())
+    }
+
     /// Parse `git worktree list --porcelain` output, returning the first entry
     /// whose directory name satisfies `predicate`.
     fn find_worktree(&self, predicate: impl Fn(&str) -> bool) -> Result<Option<WorktreeInfo>> {
@@ -477,18 +862,19 @@ impl WorktreeManager {
         None
     }
 
-    /// Fetch a ref from origin with retries. Returns an error if all attempts fail.
-    fn fetch_with_retry(&self, refspec: &str, max_attempts: u32) -> Result<()> {
+    /// Fetch a ref from `remote` with retries. Returns an error if all attempts fail.
+    fn fetch_with_retry(&self, remote: &str, refspec: &str, max_attempts: u32) -> Result<()> {
         let mut last_err = String::new();
         for attempt in 1..=max_attempts {
-            match self.git(&["fetch", "origin", refspec]) {
+            match self.git(&["fetch", remote, refspec]) {
                 Ok(_) => return Ok(()),
                 Err(e) => {
                     warn!(
                         attempt,
                         max_attempts,
                         error = %e.trim(),
-                        "git fetch origin {} failed",
+                        "git fetch {} {} failed",
+                        remote,
                         refspec
                     );
                     last_err = e;
@@ -499,7 +885,7 @@ impl WorktreeManager {
             }
         }
         Err(Error::Worktree(format!(
-            "failed to fetch origin/{} after {max_attempts} attempts: {}",
+            "failed to fetch {remote}/{} after {max_attempts} attempts: {}",
             refspec,
             last_err.trim()
         )))
@@ -515,6 +901,29 @@ impl WorktreeManager {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_git_identity_config_entries() {
+        assert!(GitIdentity::default().config_entries().is_empty());
+
+        let identity = GitIdentity {
+            user_email: Some("bot@example.com".to_string()),
+            signing_key: Some("~/.ssh/rlph.pub".to_string()),
+            signing_format: Some("ssh".to_string()),
+            ..Default::default()
+        };
+        let keys: Vec<&str> = identity.config_entries().iter().map(|(k, _)| *k).collect();
+        assert_eq!(
+            keys,
+            [
+                "user.email",
+                "gpg.format",
+                "user.signingkey",
+                "commit.gpgsign",
+                "tag.gpgsign"
+            ]
+        );
+    }
+
     #[test]
     fn test_worktree_name() {
         assert_eq!(
@@ -527,6 +936,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_render_branch_name_default_and_custom() {
+        assert_eq!(
+            render_branch_name(DEFAULT_BRANCH_TEMPLATE, 5, "fix-bug", "jdoe").unwrap(),
+            "rlph-5-fix-bug"
+        );
+        assert_eq!(
+            render_branch_name(
+                "{{ user }}/rlph/{{ issue }}-{{ slug }}",
+                5,
+                "fix-bug",
+                "jdoe"
+            )
+            .unwrap(),
+            "jdoe/rlph/5-fix-bug"
+        );
+        assert!(render_branch_name("refs/{{ issue }}", 5, "x", "u").is_err());
+        assert!(render_branch_name("{{ nope }}", 5, "x", "u").is_err());
+    }
+
+    #[test]
+    fn test_branch_template_regex() {
+        let re = branch_template_regex("{{ user }}/rlph/{{ issue }}-{{ slug }}").unwrap();
+        assert!(re.is_match("jdoe/rlph/12-add-auth"));
+        assert!(!re.is_match("jdoe/feature/12-add-auth"));
+        assert!(!re.is_match("jdoe/rlph/x-add-auth"));
+
+        let re = branch_template_regex("feat.{{ issue }}").unwrap();
+        assert!(re.is_match("feat.3"));
+        assert!(!re.is_match("featx3"));
+    }
+
     #[test]
     fn test_slugify_basic() {
         assert_eq!(WorktreeManager::slugify("Fix the bug"), "fix-the-bug");
@@ -61,6 +61,19 @@ fn prd_help() {
         .stdout(predicate::str::contains("DESCRIPTION"));
 }
 
+#[test]
+fn prompts_vars_lists_phase_variables() {
+    if !integration_enabled() {
+        return;
+    }
+    cmd()
+        .args(["prompts", "vars", "fix"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("finding_description"))
+        .stdout(predicate::str::contains("issue_title").not());
+}
+
 // --- Mode flag validation ---
 
 #[test]
@@ -167,7 +180,7 @@ fn unknown_source_rejected() {
         .args(["--once", "--source", "jira"])
         .assert()
         .failure()
-        .code(1)
+        .code(6)
         .stderr(predicate::str::contains("unknown source: jira"));
 }
 
@@ -182,7 +195,7 @@ fn unknown_runner_rejected() {
         .args(["--once", "--runner", "foo"])
         .assert()
         .failure()
-        .code(1)
+        .code(6)
         .stderr(predicate::str::contains("unknown runner: foo"));
 }
 
@@ -197,7 +210,7 @@ fn unknown_submission_rejected() {
         .args(["--once", "--submission", "gitlab"])
         .assert()
         .failure()
-        .code(1)
+        .code(6)
         .stderr(predicate::str::contains("unknown submission: gitlab"));
 }
 
@@ -212,7 +225,7 @@ fn zero_poll_seconds_rejected() {
         .args(["--once", "--poll-seconds", "0"])
         .assert()
         .failure()
-        .code(1)
+        .code(6)
         .stderr(predicate::str::contains("poll_seconds must be > 0"));
 }
 
@@ -229,7 +242,7 @@ fn config_file_not_found() {
         .args(["--once", "--config", "/nonexistent.toml"])
         .assert()
         .failure()
-        .code(1)
+        .code(6)
         .stderr(predicate::str::contains("config file not found"));
 }
 
@@ -247,7 +260,7 @@ fn invalid_toml_config() {
         .arg("--once")
         .assert()
         .failure()
-        .code(1)
+        .code(6)
         .stderr(predicate::str::contains("config parse error"));
 }
 
@@ -271,7 +284,7 @@ fn review_rejects_non_github_source() {
         .args(["review", "123"])
         .assert()
         .failure()
-        .code(1)
+        .code(6)
         .stderr(predicate::str::contains(
             "supports only source = \"github\"",
         ));
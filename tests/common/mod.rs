@@ -3,8 +3,12 @@
 use std::path::Path;
 use std::process::Command;
 
-use rlph::config::{Config, default_review_phases, default_review_step};
-use rlph::runner::RunnerKind;
+use rlph::config::{
+    ChooseStrategy, ClaimStrategy, Config, GitHubTransport, OnError, PrioritySignal, ReviewTarget,
+    default_hold_labels, default_review_phases, default_review_step,
+};
+use rlph::runner::{PermissionMode, RunnerKind};
+use rlph::stream::StreamMode;
 
 pub fn run_git(dir: &Path, args: &[&str]) {
     let output = Command::new("git")
@@ -49,24 +53,100 @@ pub fn default_test_config() -> Config {
         submission: "github".to_string(),
         label: "rlph".to_string(),
         poll_seconds: 30,
+        poll_max_seconds: 600,
         worktree_dir: String::new(),
         base_branch: "main".to_string(),
+        detect_base_branch: false,
+        base_branches: Default::default(),
         max_iterations: None,
         dry_run: false,
         once: true,
         continuous: false,
+        quiet: false,
+        interactive: false,
         agent_binary: "claude".to_string(),
         agent_model: None,
         agent_timeout: None,
         implement_timeout: None,
         agent_effort: None,
         agent_variant: None,
+        agent_temperature: None,
+        agent_extra_args: Vec::new(),
+        agent_identity: Default::default(),
         max_review_rounds: 3,
+        review_round_policy: Default::default(),
         agent_timeout_retries: 2,
         review_phases: default_review_phases(),
+        review_phase_filter: None,
+        skip_review: false,
         review_aggregate: default_review_step("review-aggregate"),
         review_fix: default_review_step("review-fix"),
         fix: default_review_step("fix"),
+        pr_feedback: false,
+        hold_labels: default_hold_labels(),
+        estimate: None,
+        max_complexity: None,
+        agent_language: None,
+        translate: None,
+        followup_issues: false,
+        followup_label: "rlph-followup".to_string(),
+        branch_template: rlph::worktree::DEFAULT_BRANCH_TEMPLATE.to_string(),
+        pr_title_template: rlph::submission::DEFAULT_PR_TITLE_TEMPLATE.to_string(),
+        pr_body_template: rlph::submission::DEFAULT_PR_BODY_TEMPLATE.to_string(),
+        shared_cache_dirs: vec![],
+        conventions_files: vec![],
+        protected_paths: vec![],
+        cache_dir: ".rlph-cache".to_string(),
+        finding_required_fields: vec![],
+        prompt_version: rlph::prompts::PROMPT_VERSION.to_string(),
+        finding_template: None,
+        suggestion_comments: false,
+        review_lessons: false,
+        issue_pr_comments: false,
+        test_plan: false,
+        differential_review: false,
+        untrusted_sources: false,
+        allow_dangerous_permissions: false,
+        permission_mode: PermissionMode::Full,
+        max_task_attempts: 3,
+        retry_backoff_seconds: 300,
+        closed_tasks_ttl_seconds: 86_400,
+        refresh_deps: false,
+        redo: false,
+        abandon_after_days: None,
+        heartbeat_minutes: None,
+        max_worktree_disk_mb: None,
+        digest_issue: None,
+        failed_label: "rlph-failed".to_string(),
+        on_error: OnError::Stop,
+        claim_strategy: ClaimStrategy::Label,
+        review_concurrency: None,
+        review_output: StreamMode::Interleaved,
+        review_target: ReviewTarget::PrComment,
+        findings_export: None,
+        runner_probe: false,
+        push_remote: "origin".to_string(),
+        pr_head_owner: None,
+        github_rps: rlph::gh_quota::DEFAULT_GITHUB_RPS,
+        github_transport: GitHubTransport::Auto,
+        git_identity: Default::default(),
+        choose_strategy: ChooseStrategy::Agent,
+        choose_max_tasks: 30,
+        choose_body_chars: 1000,
+        priority_policy: Default::default(),
+        priority_signal: vec![PrioritySignal::Labels],
+        hooks: Default::default(),
+        cleanup: Default::default(),
+        preprocess: Default::default(),
+        permalink_context: true,
+        fix_trailers: Default::default(),
+        squash: Default::default(),
+        implement_slices: None,
+        toolchain: Default::default(),
         linear: None,
+        bitbucket: None,
+        trello: None,
+        gitea: None,
+        github_project: None,
     }
 }
@@ -3,13 +3,17 @@ mod common;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
-use rlph::config::{Config, ReviewStepConfig};
+use rlph::check_run::ReviewCheck;
+use rlph::config::{Config, MergeMethod, ReviewStepConfig};
 use rlph::error::{Error, Result};
 use rlph::fix::run_fix;
 use rlph::orchestrator::CorrectionRunner;
 use rlph::review_schema::{ReviewFinding, render_findings_for_github};
-use rlph::runner::{RunResult, RunnerKind};
-use rlph::submission::{PrComment, REVIEW_MARKER, SubmissionBackend, SubmitResult};
+use rlph::runner::{AgentIdentity, PermissionMode, RunResult, RunnerKind};
+use rlph::submission::{
+    CiStatus, PrComment, PrState, REVIEW_MARKER, SubmissionBackend, SubmitResult,
+};
+use rlph::suggestion::SuggestionComment;
 use rlph::test_helpers::make_finding;
 
 use common::{default_test_config, run_git, setup_git_repo};
@@ -67,7 +71,11 @@ fn make_fix_step_config(agent_binary: String) -> ReviewStepConfig {
         agent_model: None,
         agent_effort: None,
         agent_variant: None,
+        agent_temperature: None,
+        agent_extra_args: Vec::new(),
+        agent_identity: Default::default(),
         agent_timeout: Some(30),
+        permission_mode: PermissionMode::Full,
     }
 }
 
@@ -121,6 +129,54 @@ impl SubmissionBackend for MockFixSubmission {
         let body = self.comment_body.lock().unwrap().clone();
         Ok(vec![make_pr_comment(&body)])
     }
+
+    fn list_open_prs(&self) -> Result<Vec<rlph::submission::PrContext>> {
+        Ok(vec![])
+    }
+
+    fn post_pr_comment(&self, _pr_number: u64, _body: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn update_pr_body(&self, _pr_number: u64, _body: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn post_suggestions(
+        &self,
+        _pr_number: u64,
+        _suggestions: &[SuggestionComment],
+    ) -> Result<usize> {
+        Ok(0)
+    }
+
+    fn pr_state(&self, _pr_number: u64) -> Result<PrState> {
+        Ok(PrState::Open)
+    }
+
+    fn close_pr(&self, _pr_number: u64, _comment: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn publish_review_check(&self, _pr_number: u64, _check: &ReviewCheck) -> Result<()> {
+        Ok(())
+    }
+
+    fn upsert_heartbeat_comment(&self, _number: u64, _body: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn delete_heartbeat_comment(&self, _number: u64) -> Result<()> {
+        Ok(())
+    }
+
+    fn ci_status(&self, _pr_number: u64) -> Result<CiStatus> {
+        Ok(CiStatus::Passed)
+    }
+
+    fn enable_auto_merge(&self, _pr_number: u64, _method: MergeMethod) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// No-op correction runner for tests.
@@ -134,6 +190,8 @@ impl CorrectionRunner for MockCorrectionRunner {
         _model: Option<&str>,
         _effort: Option<&str>,
         _variant: Option<&str>,
+        _permission_mode: PermissionMode,
+        _identity: &AgentIdentity,
         _session_id: &str,
         _correction_prompt: &str,
         _working_dir: &Path,
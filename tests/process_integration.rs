@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 use std::time::Duration;
 
+use rlph::disk_usage::with_disk_quota;
 use rlph::process::{ProcessConfig, spawn_and_stream};
 use serial_test::serial;
 
@@ -410,3 +411,64 @@ async fn test_quiet_suppresses_process_noise() {
     // so we verify the process completes correctly with quiet enabled.
     assert!(output.stderr_lines.is_empty());
 }
+
+#[tokio::test]
+#[serial]
+#[cfg(unix)]
+async fn test_disk_quota_breach_kills_descendants() {
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("descendant.pid");
+
+    // The shell ignores TERM and waits on a background child while filling
+    // its directory past the quota.
+    let config = ProcessConfig {
+        command: "bash".to_string(),
+        args: vec![
+            "-c".to_string(),
+            "sleep 30 & echo $! > descendant.pid; head -c 3000000 /dev/zero > big; trap '' TERM; wait".to_string(),
+        ],
+        working_dir: dir.path().to_path_buf(),
+        timeout: None,
+        log_prefix: "test:quota-descendants".to_string(),
+        env: vec![],
+        stdin_data: None,
+        stream_output: true,
+        quiet: false,
+        stdout_tx: None,
+    };
+
+    let result = with_disk_quota(
+        spawn_and_stream(config),
+        dir.path(),
+        1,
+        Duration::from_millis(20),
+    )
+    .await;
+    assert!(matches!(result, Err(used) if used > 1));
+
+    let descendant_pid: i32 = std::fs::read_to_string(&pid_file)
+        .expect("child should write descendant pid file")
+        .trim()
+        .parse()
+        .unwrap();
+    // SAFETY: kill(pid, 0) only checks for process existence.
+    let alive = || unsafe { libc::kill(descendant_pid, 0) == 0 };
+    for _ in 0..50 {
+        if !alive() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let still_alive = alive();
+    if still_alive {
+        // SAFETY: best-effort cleanup for leaked process from the test.
+        unsafe {
+            libc::kill(descendant_pid, libc::SIGKILL);
+        }
+    }
+
+    assert!(
+        !still_alive,
+        "descendant process {descendant_pid} survived the quota breach"
+    );
+}
@@ -3,7 +3,8 @@ mod common;
 use std::process::Command;
 
 use common::run_git;
-use rlph::worktree::WorktreeManager;
+use rlph::rlphignore::RlphIgnore;
+use rlph::worktree::{GitIdentity, WorktreeManager, WorktreeSnapshot};
 use tempfile::TempDir;
 
 /// Create a temporary git repo with an initial commit.
@@ -162,6 +163,217 @@ fn test_remove_worktree() {
     assert!(found.is_none());
 }
 
+#[test]
+fn test_create_and_remove_with_branch_template() {
+    let repo = init_temp_repo();
+    let wt_base = TempDir::new().unwrap();
+
+    let mgr = WorktreeManager::new(
+        repo.path().to_path_buf(),
+        wt_base.path().to_path_buf(),
+        "main".to_string(),
+    )
+    .with_branch_template("feature/rlph/{{ issue }}-{{ slug }}");
+
+    let info = mgr.create(21, "templated").unwrap();
+    assert_eq!(info.branch, "feature/rlph/21-templated");
+    assert!(info.path.ends_with("rlph-21-templated"));
+    assert!(mgr.is_managed_branch("feature/rlph/21-templated"));
+    assert!(!mgr.is_managed_branch("feature/other"));
+
+    // Reuse finds the worktree by directory and reports the templated branch
+    let found = mgr.find_existing(21).unwrap().unwrap();
+    assert_eq!(found.branch, "feature/rlph/21-templated");
+
+    mgr.remove(&info.path).unwrap();
+    let output = Command::new("git")
+        .args(["branch", "--list", "feature/rlph/21-templated"])
+        .current_dir(repo.path())
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).trim().is_empty());
+}
+
+#[test]
+fn test_shared_caches_linked_and_excluded() {
+    let repo = init_temp_repo();
+    let wt_base = TempDir::new().unwrap();
+    let cache = TempDir::new().unwrap();
+
+    let mgr = WorktreeManager::new(
+        repo.path().to_path_buf(),
+        wt_base.path().to_path_buf(),
+        "main".to_string(),
+    )
+    .with_shared_caches(
+        cache.path().to_path_buf(),
+        vec!["target".to_string(), "node_modules/.cache".to_string()],
+    );
+
+    let info = mgr.create(30, "cache").unwrap();
+    let link = info.path.join("target");
+    assert!(link.is_symlink());
+    std::fs::write(link.join("artifact"), "built").unwrap();
+    assert!(cache.path().join("target").join("artifact").exists());
+    assert!(info.path.join("node_modules/.cache").is_symlink());
+
+    // Links must not show up as changes an agent could commit
+    let status = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(&info.path)
+        .output()
+        .unwrap();
+    let status = String::from_utf8_lossy(&status.stdout);
+    assert!(!status.contains("target"), "status: {status}");
+    assert!(!status.contains(".cache"), "status: {status}");
+
+    // Removing the worktree keeps the shared cache contents
+    mgr.remove(&info.path).unwrap();
+    assert!(cache.path().join("target").join("artifact").exists());
+
+    // A second worktree reuses the same cache
+    let second = mgr.create(31, "cache-again").unwrap();
+    assert!(second.path.join("target").join("artifact").exists());
+}
+
+#[test]
+fn test_git_identity_is_worktree_local() {
+    let repo = init_temp_repo();
+    let wt_base = TempDir::new().unwrap();
+
+    let mgr = WorktreeManager::new(
+        repo.path().to_path_buf(),
+        wt_base.path().to_path_buf(),
+        "main".to_string(),
+    )
+    .with_git_identity(GitIdentity {
+        user_name: Some("rlph-bot".to_string()),
+        user_email: Some("bot@example.com".to_string()),
+        ..Default::default()
+    });
+
+    let git_output = |dir: &std::path::Path, args: &[&str]| {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+
+    let info = mgr.create(40, "identity").unwrap();
+    std::fs::write(info.path.join("bot.txt"), "bot").unwrap();
+    run_git(&info.path, &["add", "."]);
+    run_git(&info.path, &["commit", "-m", "bot commit"]);
+    assert_eq!(
+        git_output(&info.path, &["log", "-1", "--format=%an <%ae>"]),
+        "rlph-bot <bot@example.com>"
+    );
+
+    // The main checkout keeps the user's identity
+    assert_eq!(git_output(repo.path(), &["config", "user.name"]), "Test");
+
+    // Reused worktrees get the identity too
+    let reused = mgr.create(40, "identity").unwrap();
+    assert_eq!(
+        git_output(&reused.path, &["config", "user.name"]),
+        "rlph-bot"
+    );
+}
+
+#[test]
+fn test_snapshot_detects_edits_and_commits() {
+    let repo = init_temp_repo();
+    let wt_base = TempDir::new().unwrap();
+    let mgr = WorktreeManager::new(
+        repo.path().to_path_buf(),
+        wt_base.path().to_path_buf(),
+        "main".to_string(),
+    )
+    .with_shared_caches(repo.path().join(".cache-root"), vec!["target".to_string()]);
+    let info = mgr.create(50, "snapshot").unwrap();
+
+    let no_ignore = RlphIgnore::default();
+    let snapshot = WorktreeSnapshot::capture(&info.path).unwrap();
+    // Writes into git-excluded shared caches are not modifications
+    std::fs::write(info.path.join("target").join("out"), "built").unwrap();
+    assert_eq!(
+        snapshot.changes_since(&info.path, &no_ignore).unwrap(),
+        None
+    );
+
+    std::fs::write(info.path.join("README.md"), "# changed").unwrap();
+    std::fs::write(info.path.join("new.txt"), "new").unwrap();
+    let changes = snapshot
+        .changes_since(&info.path, &no_ignore)
+        .unwrap()
+        .unwrap();
+    assert!(changes.contains("M README.md"), "changes: {changes}");
+    assert!(changes.contains("?? new.txt"), "changes: {changes}");
+
+    run_git(&info.path, &["add", "."]);
+    run_git(&info.path, &["commit", "-m", "sneaky"]);
+    let changes = snapshot
+        .changes_since(&info.path, &no_ignore)
+        .unwrap()
+        .unwrap();
+    assert!(changes.contains("HEAD moved from"), "changes: {changes}");
+}
+
+#[test]
+fn test_snapshot_lists_changed_files() {
+    let repo = init_temp_repo();
+    let wt_base = TempDir::new().unwrap();
+    let mgr = WorktreeManager::new(
+        repo.path().to_path_buf(),
+        wt_base.path().to_path_buf(),
+        "main".to_string(),
+    );
+    let info = mgr.create(52, "changed-files").unwrap();
+
+    // A file dirty before the snapshot does not count until its status changes.
+    std::fs::write(info.path.join("scratch.txt"), "before").unwrap();
+    let snapshot = WorktreeSnapshot::capture(&info.path).unwrap();
+    assert!(snapshot.changed_files_since(&info.path).unwrap().is_empty());
+
+    std::fs::create_dir_all(info.path.join("src")).unwrap();
+    std::fs::write(info.path.join("src/lib.rs"), "pub fn f() {}").unwrap();
+    run_git(&info.path, &["add", "src/lib.rs"]);
+    run_git(&info.path, &["commit", "-m", "add lib"]);
+    std::fs::write(info.path.join("README.md"), "# changed").unwrap();
+
+    assert_eq!(
+        snapshot.changed_files_since(&info.path).unwrap(),
+        vec!["README.md", "src/lib.rs"]
+    );
+}
+
+#[test]
+fn test_snapshot_skips_rlphignored_paths() {
+    let repo = init_temp_repo();
+    let wt_base = TempDir::new().unwrap();
+    let mgr = WorktreeManager::new(
+        repo.path().to_path_buf(),
+        wt_base.path().to_path_buf(),
+        "main".to_string(),
+    );
+    let info = mgr.create(51, "rlphignore").unwrap();
+    let ignore = RlphIgnore::parse("Cargo.lock\nvendor/\n");
+
+    let snapshot = WorktreeSnapshot::capture(&info.path).unwrap();
+    std::fs::write(info.path.join("Cargo.lock"), "regenerated").unwrap();
+    std::fs::create_dir_all(info.path.join("vendor")).unwrap();
+    std::fs::write(info.path.join("vendor").join("dep.rs"), "vendored").unwrap();
+    assert_eq!(snapshot.changes_since(&info.path, &ignore).unwrap(), None);
+
+    std::fs::write(info.path.join("README.md"), "# changed").unwrap();
+    let changes = snapshot
+        .changes_since(&info.path, &ignore)
+        .unwrap()
+        .unwrap();
+    assert_eq!(changes, "M README.md");
+}
+
 #[test]
 fn test_remove_worktree_cleans_branch() {
     let repo = init_temp_repo();
@@ -231,6 +443,47 @@ fn test_create_for_branch() {
     assert!(info.path.join("branch.txt").exists());
 }
 
+#[test]
+fn test_create_for_branch_fetches_from_push_remote() {
+    let repo = init_temp_repo();
+    let wt_base = TempDir::new().unwrap();
+    let fork = TempDir::new().unwrap();
+    run_git(fork.path(), &["init", "--bare"]);
+    run_git(
+        repo.path(),
+        &["remote", "add", "fork", fork.path().to_str().unwrap()],
+    );
+
+    // The branch exists only on the fork.
+    run_git(repo.path(), &["checkout", "-b", "rlph-5-fork-only"]);
+    std::fs::write(repo.path().join("fork.txt"), "fork").unwrap();
+    run_git(repo.path(), &["add", "."]);
+    run_git(repo.path(), &["commit", "-m", "fork commit"]);
+    run_git(repo.path(), &["push", "fork", "rlph-5-fork-only"]);
+    run_git(repo.path(), &["checkout", "main"]);
+    run_git(repo.path(), &["branch", "-D", "rlph-5-fork-only"]);
+
+    let origin_only = WorktreeManager::new(
+        repo.path().to_path_buf(),
+        wt_base.path().to_path_buf(),
+        "main".to_string(),
+    );
+    assert!(
+        origin_only
+            .create_for_branch(5, "rlph-5-fork-only")
+            .is_err()
+    );
+
+    let mgr = WorktreeManager::new(
+        repo.path().to_path_buf(),
+        wt_base.path().to_path_buf(),
+        "main".to_string(),
+    )
+    .with_push_remote("fork");
+    let info = mgr.create_for_branch(5, "rlph-5-fork-only").unwrap();
+    assert!(info.path.join("fork.txt").exists());
+}
+
 #[test]
 fn test_create_for_branch_reuses_existing() {
     let repo = init_temp_repo();
@@ -249,3 +502,47 @@ fn test_create_for_branch_reuses_existing() {
     assert_eq!(first.path, second.path);
     assert_eq!(first.branch, second.branch);
 }
+
+#[test]
+fn test_delete_branch_removes_local_and_remote() {
+    let repo = init_temp_repo();
+    let remote = TempDir::new().unwrap();
+    run_git(remote.path(), &["init", "--bare"]);
+    run_git(
+        repo.path(),
+        &["remote", "add", "fork", remote.path().to_str().unwrap()],
+    );
+    run_git(repo.path(), &["branch", "rlph-30-done"]);
+    run_git(repo.path(), &["push", "fork", "rlph-30-done"]);
+    run_git(repo.path(), &["branch", "feature-keep"]);
+    run_git(repo.path(), &["push", "fork", "feature-keep"]);
+
+    let wt_base = TempDir::new().unwrap();
+    let mgr = WorktreeManager::new(
+        repo.path().to_path_buf(),
+        wt_base.path().to_path_buf(),
+        "main".to_string(),
+    )
+    .with_push_remote("fork");
+
+    mgr.delete_branch("rlph-30-done", true).unwrap();
+    // Already gone everywhere: still fine.
+    mgr.delete_branch("rlph-30-done", true).unwrap();
+    // Not an rlph branch: left alone.
+    mgr.delete_branch("feature-keep", true).unwrap();
+
+    let branches = |dir: &std::path::Path| {
+        let output = Command::new("git")
+            .args(["branch", "--list"])
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    let local = branches(repo.path());
+    assert!(!local.contains("rlph-30-done"), "{local}");
+    assert!(local.contains("feature-keep"), "{local}");
+    let remote_heads = branches(remote.path());
+    assert!(!remote_heads.contains("rlph-30-done"), "{remote_heads}");
+    assert!(remote_heads.contains("feature-keep"), "{remote_heads}");
+}